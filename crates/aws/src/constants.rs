@@ -47,6 +47,34 @@ pub const AWS_S3_ADDRESSING_STYLE: &str = "AWS_S3_ADDRESSING_STYLE";
 /// `dynamodb` is currently the only supported locking provider.
 /// If not set, safe atomic rename is not available.
 pub const AWS_S3_LOCKING_PROVIDER: &str = "AWS_S3_LOCKING_PROVIDER";
+/// Enable S3 Express One Zone (directory bucket) support.
+///
+/// Directory buckets are detected automatically from their `--x-s3` name suffix, this option
+/// only needs to be set when that detection is not applicable (e.g. custom endpoints).
+/// S3 Express supports conditional writes natively, so commits are always performed with
+/// put-if-absent and do not require a locking provider.
+pub const AWS_S3_EXPRESS: &str = "AWS_S3_EXPRESS";
+/// Enable Cloudflare R2 support.
+///
+/// R2 is detected automatically from an `*.r2.cloudflarestorage.com` endpoint, this option only
+/// needs to be set when R2 is accessed through a custom domain. R2 is strongly consistent and
+/// supports conditional writes, so commits never require a locking provider. The region defaults
+/// to `auto` and copy-if-not-exists uses the R2 specific `cf-copy-destination-if-none-match`
+/// header.
+pub const AWS_S3_R2: &str = "AWS_S3_R2";
+/// Server side encryption of all objects written to the table (data files, log files and
+/// multipart uploads), one of `AES256`, `aws:kms`, `aws:kms:dsse` or `sse-c`.
+///
+/// Inferred as `aws:kms` when only [AWS_SSE_KMS_KEY_ID] is set and as `sse-c` when only
+/// [AWS_SSE_CUSTOMER_KEY_BASE64] is set.
+pub const AWS_SERVER_SIDE_ENCRYPTION: &str = "AWS_SERVER_SIDE_ENCRYPTION";
+/// Id or ARN of the KMS key used for SSE-KMS.
+pub const AWS_SSE_KMS_KEY_ID: &str = "AWS_SSE_KMS_KEY_ID";
+/// Whether S3 bucket keys are used for SSE-KMS, overriding the bucket default.
+pub const AWS_SSE_BUCKET_KEY_ENABLED: &str = "AWS_SSE_BUCKET_KEY_ENABLED";
+/// Base64 encoded 256-bit customer key used for SSE-C. The key is required to read the objects
+/// back, so it must be passed to every reader of the table.
+pub const AWS_SSE_CUSTOMER_KEY_BASE64: &str = "AWS_SSE_CUSTOMER_KEY_BASE64";
 /// The role to assume for S3 writes.
 pub const AWS_IAM_ROLE_ARN: &str = "AWS_IAM_ROLE_ARN";
 /// The role to assume. Please use [AWS_IAM_ROLE_ARN] instead
@@ -124,6 +152,9 @@ pub const S3_OPTS: &[&str] = &[
     AWS_SECRET_ACCESS_KEY,
     AWS_SESSION_TOKEN,
     AWS_S3_LOCKING_PROVIDER,
+    DYNAMO_LOCK_TABLE_KEY_NAME,
+    AWS_S3_EXPRESS,
+    AWS_S3_R2,
     AWS_IAM_ROLE_ARN,
     AWS_IAM_ROLE_SESSION_NAME,
     AWS_S3_ASSUME_ROLE_ARN,
@@ -138,6 +169,33 @@ pub const S3_OPTS: &[&str] = &[
     AWS_EC2_METADATA_TIMEOUT,
 ];
 
+/// Name suffix shared by all S3 Express One Zone directory buckets.
+pub const S3_EXPRESS_BUCKET_SUFFIX: &str = "--x-s3";
+
+/// Host suffix of Cloudflare R2 S3 API endpoints.
+pub const R2_ENDPOINT_SUFFIX: &str = ".r2.cloudflarestorage.com";
+/// Header used by R2 to perform a copy only if the destination does not exist.
+pub const R2_COPY_IF_NOT_EXISTS_HEADER: &str = "cf-copy-destination-if-none-match";
+
+/// Name of the DynamoDB table holding the commit locks of the
+/// [DynamoDbLockProvider](crate::lock::DynamoDbLockProvider).
+pub const DYNAMO_LOCK_TABLE_KEY_NAME: &str = "DELTA_DYNAMO_LOCK_TABLE_NAME";
+/// Default name of the DynamoDB lock table.
+pub const DEFAULT_DYNAMO_LOCK_TABLE_NAME: &str = "delta_rs_lock";
+
+pub const ATTR_LOCK_KEY: &str = "lockKey";
+pub const ATTR_LOCK_OWNER: &str = "lockOwner";
+pub const ATTR_LEASE_EXPIRY: &str = "leaseExpiry";
+
+/// The lock is free, already held by the owner or its lease elapsed.
+pub static CONDITION_LOCK_ACQUIRE: LazyLock<String> = LazyLock::new(|| {
+    format!(
+        "attribute_not_exists({ATTR_LOCK_KEY}) or {ATTR_LOCK_OWNER} = :owner or {ATTR_LEASE_EXPIRY} < :now"
+    )
+});
+pub static CONDITION_LOCK_RELEASE: LazyLock<String> =
+    LazyLock::new(|| format!("{ATTR_LOCK_OWNER} = :owner"));
+
 pub const DEFAULT_LOCK_TABLE_NAME: &str = "delta_log";
 pub const LOCK_TABLE_KEY_NAME: &str = "DELTA_DYNAMO_TABLE_NAME";
 pub const BILLING_MODE_KEY_NAME: &str = "DELTA_DYNAMO_BILLING_MODE";
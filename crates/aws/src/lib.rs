@@ -1,22 +1,25 @@
 //! AWS S3 and similar tooling for delta-rs
 //!
-//! This module also contains the [S3DynamoDbLogStore](crate::logstore::S3DynamoDbLogStore)
-//! implementation for concurrent writer support with AWS S3 specifically.
+//! This module also contains the [DynamoDbLockProvider](crate::lock::DynamoDbLockProvider)
+//! for concurrent writer support with AWS S3 buckets which do not support conditional writes.
 
 pub mod constants;
 mod credentials;
+pub mod lock;
 pub mod logstore;
 pub mod storage;
 
 pub use aws_credential_types::provider::SharedCredentialsProvider;
 use deltalake_core::DeltaResult;
+use deltalake_core::logstore::object_store::aws::AmazonS3ConfigKey;
 use deltalake_core::logstore::{
-    LogStore, LogStoreFactory, ObjectStoreRef, StorageConfig, default_logstore, logstore_factories,
-    object_store_factories,
+    LockingLogStore, LogStore, LogStoreConfig, LogStoreFactory, MultipartConfig, ObjectStoreRef,
+    StorageConfig, default_logstore, logstore_factories, object_store_factories,
 };
+use lock::DynamoDbLockProvider;
 use std::sync::Arc;
 use storage::S3StorageOptionsConversion;
-use storage::{S3ObjectStoreFactory, S3StorageOptions};
+use storage::{S3ObjectStoreFactory, S3StorageOptions, is_s3_express_bucket, str_option};
 use tracing::log::*;
 use url::Url;
 
@@ -34,19 +37,43 @@ impl LogStoreFactory for S3LogStoreFactory {
         options: &StorageConfig,
     ) -> DeltaResult<Arc<dyn LogStore>> {
         let s3_options = self.with_env_s3(&options.raw.clone());
+        let r2_endpoint = s3_options
+            .get(AmazonS3ConfigKey::Endpoint.as_ref())
+            .is_some_and(|endpoint| storage::is_r2_endpoint(endpoint));
+        let table_name = str_option(&s3_options, constants::DYNAMO_LOCK_TABLE_KEY_NAME);
         let s3_options = S3StorageOptions::from_map(&s3_options)?;
+        // S3 Express and R2 support conditional writes, see `conditional_put_storage_handler`
+        let conditional_put = s3_options.s3_express
+            || s3_options.r2
+            || r2_endpoint
+            || location.host_str().is_some_and(is_s3_express_bucket);
 
-        if s3_options.locking_provider.as_deref() == Some("dynamodb") {
-            error!(
-                "delta-rs **no longer** supports S3DynamoDBLogStores, see <https://github.com/delta-io/delta-rs/discussions/4482> for more"
-            );
+        // R2 rejects multipart uploads whose parts differ in size, except for the last one
+        let mut options = options.clone();
+        if s3_options.r2 || r2_endpoint {
+            let multipart = options.multipart_config();
+            options.multipart = Some(MultipartConfig {
+                multipart_equal_parts: multipart.multipart_equal_parts.or(Some(true)),
+                ..multipart
+            });
+        }
+
+        if s3_options.locking_provider.as_deref() == Some("dynamodb") && !conditional_put {
+            debug!("Serializing commits through the DynamoDB lock table");
+            let provider = DynamoDbLockProvider::try_new(&s3_options, table_name)?;
+            return Ok(Arc::new(LockingLogStore::new(
+                prefixed_store,
+                root_store,
+                LogStoreConfig::new(location, options),
+                Arc::new(provider),
+            )));
         }
 
         Ok(default_logstore(
             prefixed_store,
             root_store,
             location,
-            options,
+            &options,
         ))
     }
 }
@@ -87,4 +114,49 @@ mod tests {
             .unwrap();
         assert_eq!(logstore.name(), "DefaultLogStore");
     }
+
+    #[test]
+    #[serial]
+    fn test_logstore_factory_dynamodb_lock() {
+        let factory = S3LogStoreFactory::default();
+        let store = Arc::new(InMemory::new());
+        let url = Url::parse("s3://test-bucket").unwrap();
+        let options = StorageConfig::parse_options([
+            (constants::AWS_S3_LOCKING_PROVIDER, "dynamodb"),
+            (constants::AWS_REGION, "us-east-1"),
+            (constants::AWS_ACCESS_KEY_ID, "test"),
+            (constants::AWS_SECRET_ACCESS_KEY, "test"),
+            (constants::AWS_EC2_METADATA_DISABLED, "true"),
+            ("lock_lease_duration", "30s"),
+        ])
+        .unwrap();
+        let logstore = factory
+            .with_options(store.clone(), store.clone(), &url, &options)
+            .unwrap();
+        assert_eq!(logstore.name(), "LockingLogStore(DynamoDbLockProvider)");
+
+        // directory buckets write commits conditionally, the lock is not needed
+        let url = Url::parse("s3://test--use1-az4--x-s3").unwrap();
+        let logstore = factory
+            .with_options(store.clone(), store, &url, &options)
+            .unwrap();
+        assert_eq!(logstore.name(), "DefaultLogStore");
+    }
+
+    #[test]
+    #[serial]
+    fn test_logstore_factory_r2_equal_parts() {
+        let factory = S3LogStoreFactory::default();
+        let store = Arc::new(InMemory::new());
+        let url = Url::parse("s3://test-bucket").unwrap();
+        let options = StorageConfig::parse_options([(
+            constants::AWS_ENDPOINT_URL,
+            "https://account.r2.cloudflarestorage.com",
+        )])
+        .unwrap();
+        let logstore = factory
+            .with_options(store.clone(), store, &url, &options)
+            .unwrap();
+        assert!(logstore.config().options().multipart_config().equal_parts());
+    }
 }
@@ -0,0 +1,171 @@
+//! Commit lock held in a DynamoDB table.
+//!
+//! S3 buckets which do not support conditional writes serialize commits through a
+//! [`LockingLogStore`](deltalake_core::logstore::LockingLogStore) with a
+//! [`DynamoDbLockProvider`], selected with `AWS_S3_LOCKING_PROVIDER=dynamodb`.
+
+use std::time::Duration;
+
+use async_trait::async_trait;
+use aws_config::{Region, SdkConfig};
+use aws_credential_types::Credentials;
+use aws_sdk_dynamodb::Client;
+use aws_sdk_dynamodb::operation::delete_item::DeleteItemError;
+use aws_sdk_dynamodb::operation::put_item::PutItemError;
+use aws_sdk_dynamodb::types::AttributeValue;
+use deltalake_core::logstore::LockProvider;
+use deltalake_core::{DeltaResult, DeltaTableError};
+
+use crate::constants::{
+    ATTR_LEASE_EXPIRY, ATTR_LOCK_KEY, ATTR_LOCK_OWNER, CONDITION_LOCK_ACQUIRE,
+    CONDITION_LOCK_RELEASE, DEFAULT_DYNAMO_LOCK_TABLE_NAME,
+};
+use crate::storage::{S3StorageOptions, execute_sdk_future};
+
+/// [`LockProvider`] which holds commit locks as items of a DynamoDB table.
+///
+/// The table must exist before the first commit and use the string attribute `lockKey` as its
+/// partition key. Leases are stored as epoch milliseconds and compared against the clock of the
+/// writer trying to take the lock over, so clock skew between writers shortens or extends the
+/// lease by the same amount.
+#[derive(Debug, Clone)]
+pub struct DynamoDbLockProvider {
+    client: Client,
+    table_name: String,
+}
+
+impl DynamoDbLockProvider {
+    /// Create a provider using the DynamoDB endpoint, region and credentials of `s3_options`.
+    ///
+    /// The lock table defaults to [`DEFAULT_DYNAMO_LOCK_TABLE_NAME`].
+    pub fn try_new(s3_options: &S3StorageOptions, table_name: Option<String>) -> DeltaResult<Self> {
+        let sdk_config = match &s3_options.sdk_config {
+            Some(sdk_config) => sdk_config.clone(),
+            None => execute_sdk_future(aws_config::from_env().load())?,
+        };
+        Ok(Self::new(
+            dynamodb_client(s3_options, &sdk_config),
+            table_name.unwrap_or_else(|| DEFAULT_DYNAMO_LOCK_TABLE_NAME.to_string()),
+        ))
+    }
+
+    /// Create a provider using an existing DynamoDB `client`.
+    pub fn new(client: Client, table_name: impl Into<String>) -> Self {
+        Self {
+            client,
+            table_name: table_name.into(),
+        }
+    }
+
+    /// Name of the DynamoDB table holding the locks.
+    pub fn table_name(&self) -> &str {
+        &self.table_name
+    }
+}
+
+fn dynamodb_client(s3_options: &S3StorageOptions, sdk_config: &SdkConfig) -> Client {
+    let mut builder = aws_sdk_dynamodb::config::Builder::from(sdk_config);
+    if let Some(endpoint) = &s3_options.dynamodb_endpoint {
+        builder = builder.endpoint_url(endpoint);
+    }
+    if let Some(region) = &s3_options.dynamodb_region {
+        builder = builder.region(Region::new(region.clone()));
+    }
+    if let (Some(access_key_id), Some(secret_access_key)) = (
+        &s3_options.dynamodb_access_key_id,
+        &s3_options.dynamodb_secret_access_key,
+    ) {
+        builder = builder.credentials_provider(Credentials::new(
+            access_key_id,
+            secret_access_key,
+            s3_options.dynamodb_session_token.clone(),
+            None,
+            "DynamoDbLockProvider",
+        ));
+    }
+    Client::from_conf(builder.build())
+}
+
+#[async_trait]
+impl LockProvider for DynamoDbLockProvider {
+    fn name(&self) -> String {
+        "DynamoDbLockProvider".into()
+    }
+
+    async fn try_acquire(&self, key: &str, owner: &str, lease: Duration) -> DeltaResult<bool> {
+        let now = chrono::Utc::now().timestamp_millis();
+        let lease_expiry = now.saturating_add(lease.as_millis().try_into().unwrap_or(i64::MAX));
+        let result = self
+            .client
+            .put_item()
+            .table_name(&self.table_name)
+            .item(ATTR_LOCK_KEY, AttributeValue::S(key.to_string()))
+            .item(ATTR_LOCK_OWNER, AttributeValue::S(owner.to_string()))
+            .item(
+                ATTR_LEASE_EXPIRY,
+                AttributeValue::N(lease_expiry.to_string()),
+            )
+            .condition_expression(CONDITION_LOCK_ACQUIRE.as_str())
+            .expression_attribute_values(":owner", AttributeValue::S(owner.to_string()))
+            .expression_attribute_values(":now", AttributeValue::N(now.to_string()))
+            .send()
+            .await;
+        match result.map_err(|err| err.into_service_error()) {
+            Ok(_) => Ok(true),
+            Err(PutItemError::ConditionalCheckFailedException(_)) => Ok(false),
+            Err(err) => Err(DeltaTableError::generic(format!(
+                "Failed to acquire DynamoDB lock {key} in table {}: {err}",
+                self.table_name
+            ))),
+        }
+    }
+
+    async fn release(&self, key: &str, owner: &str) -> DeltaResult<()> {
+        let result = self
+            .client
+            .delete_item()
+            .table_name(&self.table_name)
+            .key(ATTR_LOCK_KEY, AttributeValue::S(key.to_string()))
+            .condition_expression(CONDITION_LOCK_RELEASE.as_str())
+            .expression_attribute_values(":owner", AttributeValue::S(owner.to_string()))
+            .send()
+            .await;
+        match result.map_err(|err| err.into_service_error()) {
+            // the lease elapsed and another writer took the lock over, which is theirs to release
+            Ok(_) | Err(DeleteItemError::ConditionalCheckFailedException(_)) => Ok(()),
+            Err(err) => Err(DeltaTableError::generic(format!(
+                "Failed to release DynamoDB lock {key} in table {}: {err}",
+                self.table_name
+            ))),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_dynamodb_overrides() {
+        let s3_options = S3StorageOptions::builder()
+            .sdk_config(
+                SdkConfig::builder()
+                    .endpoint_url("http://localhost:4566")
+                    .region(Region::from_static("us-east-1"))
+                    .build(),
+            )
+            .dynamodb_endpoint("http://localhost:8000")
+            .dynamodb_region("eu-west-1")
+            .build();
+        let provider = DynamoDbLockProvider::try_new(&s3_options, None).unwrap();
+        assert_eq!(provider.table_name(), DEFAULT_DYNAMO_LOCK_TABLE_NAME);
+
+        let config = provider.client.config();
+        assert_eq!(config.region(), Some(&Region::from_static("eu-west-1")));
+        assert_eq!(
+            provider.name(),
+            "DynamoDbLockProvider",
+            "the provider name is part of the log store name"
+        );
+    }
+}
@@ -3,26 +3,28 @@ use std::collections::HashMap;
 use std::fmt::Debug;
 use std::ops::Range;
 use std::str::FromStr;
-use std::sync::Arc;
+use std::sync::{Arc, Mutex};
 use std::time::Duration;
 
 use aws_config::{Region, SdkConfig};
 use bytes::Bytes;
-use deltalake_core::logstore::object_store::aws::{AmazonS3Builder, AmazonS3ConfigKey};
+use deltalake_core::logstore::object_store::aws::{
+    AmazonS3Builder, AmazonS3ConfigKey, S3CopyIfNotExists,
+};
 use deltalake_core::logstore::object_store::{
     CopyOptions, GetOptions, GetResult, ListResult, MultipartUpload, ObjectMeta, ObjectStore,
     ObjectStoreScheme, PutMultipartOptions, PutOptions, PutPayload, PutResult, RenameOptions,
     Result as ObjectStoreResult, path::Path,
 };
 use deltalake_core::logstore::{
-    ObjectStoreFactory, ObjectStoreRef, StorageConfig, client_options_from_certificate,
-    config::str_is_truthy,
+    ObjectStoreFactory, ObjectStoreRef, StorageConfig, config::str_is_truthy,
 };
 use deltalake_core::{DeltaResult, DeltaTableError};
 use futures::Future;
 use futures::stream::BoxStream;
 use object_store::aws::AmazonS3;
 use object_store::client::SpawnedReqwestConnector;
+use object_store::signer::Signer;
 use tracing::log::*;
 use typed_builder::TypedBuilder;
 use url::Url;
@@ -33,17 +35,28 @@ use crate::constants::{
 };
 use crate::credentials::AWSForObjectStore;
 
+/// Key of a cached signer, the table URL along with the sorted storage options
+type SignerKey = (String, Vec<(String, String)>);
+
 #[derive(Clone, Default, Debug)]
-pub struct S3ObjectStoreFactory {}
+pub struct S3ObjectStoreFactory {
+    /// Signers by the table and options they were built for, since every signer sets up its own
+    /// HTTP client and credential provider.
+    signers: Arc<Mutex<HashMap<SignerKey, Arc<dyn Signer>>>>,
+}
 
 impl S3StorageOptionsConversion for S3ObjectStoreFactory {}
 
-impl ObjectStoreFactory for S3ObjectStoreFactory {
-    fn parse_url_opts(
+impl S3ObjectStoreFactory {
+    /// Configure the builder for the bucket at `url`.
+    ///
+    /// The `s3_express` and `r2` flags of the returned options are resolved, i.e. also set when
+    /// they were detected from the bucket name or endpoint.
+    fn s3_builder(
         &self,
         url: &Url,
         config: &StorageConfig,
-    ) -> DeltaResult<(ObjectStoreRef, Path)> {
+    ) -> DeltaResult<(AmazonS3Builder, S3StorageOptions)> {
         let options = self.with_env_s3(&config.raw);
 
         // All S3-likes should start their builder the same way
@@ -56,10 +69,8 @@ impl ObjectStoreFactory for S3ObjectStoreFactory {
                 builder.with_http_connector(SpawnedReqwestConnector::new(runtime.get_handle()));
         }
 
-        if let Some(ref cert_config) = config.certificate
-            && let Some(ref path) = cert_config.certificate_path
-        {
-            builder = builder.with_client_options(client_options_from_certificate(path)?);
+        if let Some(client_options) = config.client_options()? {
+            builder = builder.with_client_options(client_options);
         }
 
         for (key, value) in options.iter() {
@@ -68,11 +79,38 @@ impl ObjectStoreFactory for S3ObjectStoreFactory {
             }
         }
 
-        let s3_options = S3StorageOptions::from_map(&options)?;
+        builder = configure_encryption(builder)?;
+
+        let mut s3_options = S3StorageOptions::from_map(&options)?;
+        s3_options.s3_express =
+            s3_options.s3_express || url.host_str().is_some_and(is_s3_express_bucket);
+        if s3_options.s3_express {
+            debug!("Configuring S3 Express One Zone directory bucket for {url}");
+            builder = builder.with_s3_express(true);
+        }
+        s3_options.r2 = s3_options.r2
+            || builder
+                .get_config_value(&AmazonS3ConfigKey::Endpoint)
+                .is_some_and(|endpoint| is_r2_endpoint(&endpoint));
+        if s3_options.r2 {
+            debug!("Configuring Cloudflare R2 for {url}");
+            builder = configure_r2(builder, &config.raw);
+        }
         if let Some(ref sdk_config) = s3_options.sdk_config {
             builder =
                 builder.with_credentials(Arc::new(AWSForObjectStore::new(sdk_config.clone())));
         }
+        Ok((builder, s3_options))
+    }
+}
+
+impl ObjectStoreFactory for S3ObjectStoreFactory {
+    fn parse_url_opts(
+        &self,
+        url: &Url,
+        config: &StorageConfig,
+    ) -> DeltaResult<(ObjectStoreRef, Path)> {
+        let (builder, s3_options) = self.s3_builder(url, config)?;
 
         let (_, path) =
             ObjectStoreScheme::parse(url).map_err(|e| DeltaTableError::GenericError {
@@ -80,11 +118,35 @@ impl ObjectStoreFactory for S3ObjectStoreFactory {
             })?;
         let prefix = Path::parse(path)?;
 
-        let store = aws_storage_handler(builder.build()?, &s3_options)?;
+        let store = if s3_options.s3_express {
+            conditional_put_storage_handler(builder.build()?, &s3_options, "S3 Express One Zone")
+        } else if s3_options.r2 {
+            conditional_put_storage_handler(builder.build()?, &s3_options, "Cloudflare R2")
+        } else {
+            aws_storage_handler(builder.build()?, &s3_options)?
+        };
         debug!("Initialized the object store: {store:?}");
 
         Ok((store, prefix))
     }
+
+    fn signer(&self, url: &Url, config: &StorageConfig) -> DeltaResult<Option<Arc<dyn Signer>>> {
+        let mut options: Vec<_> = config
+            .raw
+            .iter()
+            .map(|(key, value)| (key.clone(), value.clone()))
+            .collect();
+        options.sort();
+        let key = (url.to_string(), options);
+        if let Some(signer) = self.signers.lock().unwrap().get(&key) {
+            return Ok(Some(signer.clone()));
+        }
+
+        let (builder, _) = self.s3_builder(url, config)?;
+        let signer: Arc<dyn Signer> = Arc::new(builder.build()?);
+        self.signers.lock().unwrap().insert(key, signer.clone());
+        Ok(Some(signer))
+    }
 }
 
 fn aws_storage_handler(
@@ -106,6 +168,105 @@ fn aws_storage_handler(
     }
 }
 
+/// Returns true if `bucket` names an S3 Express One Zone directory bucket.
+///
+/// Directory bucket names always follow the `<name>--<zone-id>--x-s3` format.
+pub fn is_s3_express_bucket(bucket: &str) -> bool {
+    bucket
+        .strip_suffix(constants::S3_EXPRESS_BUCKET_SUFFIX)
+        .is_some_and(|rest| rest.contains("--"))
+}
+
+/// Returns true if `endpoint` points at the S3 API of Cloudflare R2.
+pub fn is_r2_endpoint(endpoint: &str) -> bool {
+    Url::parse(endpoint)
+        .ok()
+        .and_then(|url| url.host_str().map(str::to_ascii_lowercase))
+        .is_some_and(|host| host.ends_with(constants::R2_ENDPOINT_SUFFIX))
+}
+
+/// Apply the settings required by Cloudflare R2 unless explicitly configured otherwise.
+///
+/// R2 only accepts the `auto` region and does not support the multipart based
+/// copy-if-not-exists, instead it offers a dedicated precondition header.
+fn configure_r2(mut builder: AmazonS3Builder, raw: &HashMap<String, String>) -> AmazonS3Builder {
+    if str_option(raw, constants::AWS_REGION).is_none()
+        && !raw.keys().any(|key| {
+            AmazonS3ConfigKey::from_str(&key.to_ascii_lowercase())
+                .is_ok_and(|key| key == AmazonS3ConfigKey::Region)
+        })
+    {
+        builder = builder.with_region("auto");
+    }
+    if !has_copy_if_not_exists(raw) && std::env::var("AWS_COPY_IF_NOT_EXISTS").is_err() {
+        builder = builder.with_copy_if_not_exists(S3CopyIfNotExists::Header(
+            constants::R2_COPY_IF_NOT_EXISTS_HEADER.to_string(),
+            "*".to_string(),
+        ));
+    }
+    builder
+}
+
+/// Infer and validate the server side encryption settings.
+///
+/// object_store only sends a KMS key id or customer key along with an explicitly configured
+/// encryption type, without it objects would silently be written with the bucket default
+/// encryption.
+fn configure_encryption(builder: AmazonS3Builder) -> DeltaResult<AmazonS3Builder> {
+    let value = |key: &str| {
+        AmazonS3ConfigKey::from_str(&key.to_ascii_lowercase())
+            .ok()
+            .and_then(|key| builder.get_config_value(&key))
+    };
+    let encryption = value(constants::AWS_SERVER_SIDE_ENCRYPTION);
+    let kms_key_id = value(constants::AWS_SSE_KMS_KEY_ID);
+    let bucket_key = value(constants::AWS_SSE_BUCKET_KEY_ENABLED);
+    let customer_key = value(constants::AWS_SSE_CUSTOMER_KEY_BASE64);
+
+    let invalid = |msg: &str| -> DeltaResult<AmazonS3Builder> {
+        Err(DeltaTableError::Generic(format!(
+            "Invalid S3 server side encryption options: {msg}"
+        )))
+    };
+    match (encryption.as_deref(), kms_key_id, customer_key) {
+        (_, Some(_), Some(_)) => invalid("a KMS key id and an SSE-C key are mutually exclusive"),
+        (None, Some(kms_key_id), None) => {
+            debug!("Configuring SSE-KMS with key {kms_key_id}");
+            Ok(builder.with_sse_kms_encryption(kms_key_id))
+        }
+        (None, None, Some(customer_key)) => {
+            debug!("Configuring SSE-C");
+            Ok(builder.with_ssec_encryption(customer_key))
+        }
+        (Some("sse-c"), _, None) => invalid("SSE-C requires a customer key"),
+        (Some("aws:kms" | "aws:kms:dsse"), _, Some(_)) => {
+            invalid("an SSE-C key cannot be used with SSE-KMS")
+        }
+        (Some("AES256"), Some(_), _) | (Some("AES256"), _, Some(_)) => {
+            invalid("keys cannot be used with SSE-S3 (AES256)")
+        }
+        (None | Some("AES256" | "sse-c"), _, _) if bucket_key.is_some() => {
+            invalid("bucket keys can only be used with SSE-KMS")
+        }
+        _ => Ok(builder),
+    }
+}
+
+/// Stores which are strongly consistent and support conditional writes (S3 Express One Zone,
+/// Cloudflare R2) never need the rename based [S3StorageBackend].
+fn conditional_put_storage_handler(
+    store: AmazonS3,
+    s3_options: &S3StorageOptions,
+    service: &str,
+) -> ObjectStoreRef {
+    if s3_options.locking_provider.is_some() || s3_options.allow_unsafe_rename {
+        warn!(
+            "{service} supports conditional writes, ignoring the configured locking provider / unsafe rename"
+        );
+    }
+    Arc::new(store)
+}
+
 // Determine whether this crate is being configured for use with native AWS S3 or an S3-alike
 //
 // This function will return true in the default case since it's most likely that the absence of
@@ -167,6 +328,12 @@ pub struct S3StorageOptions {
     /// Allow unsafe rename operations
     #[builder(default = false)]
     pub allow_unsafe_rename: bool,
+    /// Whether the bucket is an S3 Express One Zone directory bucket
+    #[builder(default = false)]
+    pub s3_express: bool,
+    /// Whether the bucket is hosted on Cloudflare R2
+    #[builder(default = false)]
+    pub r2: bool,
     /// Extra storage options not handled by other fields
     #[builder(default)]
     pub extra_opts: HashMap<String, String>,
@@ -190,6 +357,8 @@ impl PartialEq for S3StorageOptions {
             && self.s3_get_internal_server_error_retries
                 == other.s3_get_internal_server_error_retries
             && self.allow_unsafe_rename == other.allow_unsafe_rename
+            && self.s3_express == other.s3_express
+            && self.r2 == other.r2
             && self.extra_opts == other.extra_opts
     }
 }
@@ -238,6 +407,14 @@ impl S3StorageOptions {
             .map(|val| str_is_truthy(&val))
             .unwrap_or(false);
 
+        let s3_express = str_option(options, constants::AWS_S3_EXPRESS)
+            .map(|val| str_is_truthy(&val))
+            .unwrap_or(false);
+
+        let r2 = str_option(options, constants::AWS_S3_R2)
+            .map(|val| str_is_truthy(&val))
+            .unwrap_or(false);
+
         let sdk_config = match is_aws(options) {
             false => None,
             true => {
@@ -263,6 +440,8 @@ impl S3StorageOptions {
             sts_pool_idle_timeout: Duration::from_secs(sts_pool_idle_timeout),
             s3_get_internal_server_error_retries,
             allow_unsafe_rename,
+            s3_express,
+            r2,
             extra_opts,
             sdk_config,
         })
@@ -297,7 +476,7 @@ impl S3StorageOptions {
     }
 }
 
-fn execute_sdk_future<F, T>(future: F) -> DeltaResult<T>
+pub(crate) fn execute_sdk_future<F, T>(future: F) -> DeltaResult<T>
 where
     T: Send,
     F: Future<Output = T> + Send,
@@ -481,20 +660,24 @@ pub(crate) trait S3StorageOptionsConversion {
         // copy_if_not_exists behavior needs to be explicitly specifedj for AWS  S3 however.
         //
         // Users of other stores should define their copy_if_not_exists configuration as needed
-        if !options.keys().any(|key| {
-            let key = key.to_ascii_lowercase();
-            [
-                AmazonS3ConfigKey::CopyIfNotExists.as_ref(),
-                "copy_if_not_exists",
-            ]
-            .contains(&key.as_str())
-        }) {
+        if !has_copy_if_not_exists(&options) {
             options.insert("copy_if_not_exists".into(), "multipart".into());
         }
         options
     }
 }
 
+fn has_copy_if_not_exists(options: &HashMap<String, String>) -> bool {
+    options.keys().any(|key| {
+        let key = key.to_ascii_lowercase();
+        [
+            AmazonS3ConfigKey::CopyIfNotExists.as_ref(),
+            "copy_if_not_exists",
+        ]
+        .contains(&key.as_str())
+    })
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -913,7 +1096,7 @@ mod tests {
                 std::env::set_var(constants::AWS_SECRET_ACCESS_KEY, "env_key");
                 std::env::set_var(constants::AWS_REGION, "env_key");
             }
-            let combined_options = S3ObjectStoreFactory {}.with_env_s3(&raw_options);
+            let combined_options = S3ObjectStoreFactory::default().with_env_s3(&raw_options);
 
             // Four and then the conditional_put built-in
             assert_eq!(combined_options.len(), 5);
@@ -947,7 +1130,7 @@ mod tests {
                 std::env::set_var("aws_region", "env_key");
             }
 
-            let combined_options = S3ObjectStoreFactory {}.with_env_s3(&raw_options);
+            let combined_options = S3ObjectStoreFactory::default().with_env_s3(&raw_options);
 
             for (key, v) in combined_options {
                 if key != "copy_if_not_exists" {
@@ -957,6 +1140,164 @@ mod tests {
         });
     }
 
+    #[test]
+    fn test_is_s3_express_bucket() {
+        assert!(is_s3_express_bucket("my-bucket--usw2-az1--x-s3"));
+        assert!(!is_s3_express_bucket("my-bucket"));
+        assert!(!is_s3_express_bucket("x-s3"));
+        assert!(!is_s3_express_bucket("bucket--x-s3-data"));
+    }
+
+    #[test]
+    #[serial]
+    fn storage_options_s3_express() {
+        ScopedEnv::run(|| {
+            clear_env_of_aws_keys();
+            let options = S3StorageOptions::from_map(&HashMap::from([
+                (
+                    constants::AWS_ENDPOINT_URL.to_string(),
+                    "http://localhost".to_string(),
+                ),
+                (constants::AWS_S3_EXPRESS.to_string(), "true".to_string()),
+            ]))
+            .unwrap();
+            assert!(options.s3_express);
+        });
+    }
+
+    #[test]
+    #[serial]
+    fn s3_express_directory_bucket_uses_conditional_put() {
+        ScopedEnv::run(|| {
+            clear_env_of_aws_keys();
+            let url = Url::parse("s3://my-bucket--usw2-az1--x-s3/table").unwrap();
+            let config = StorageConfig::parse_options([
+                ("AWS_REGION", "us-west-2"),
+                ("AWS_ACCESS_KEY_ID", "key"),
+                ("AWS_SECRET_ACCESS_KEY", "secret"),
+                ("AWS_ENDPOINT_URL", "http://localhost"),
+                (constants::AWS_S3_ALLOW_UNSAFE_RENAME, "true"),
+            ])
+            .unwrap();
+            let (store, _) = S3ObjectStoreFactory::default()
+                .parse_url_opts(&url, &config)
+                .unwrap();
+            assert!(!format!("{store:?}").contains("S3StorageBackend"));
+        });
+    }
+
+    #[test]
+    fn test_configure_encryption() {
+        let value = |builder: &AmazonS3Builder, key: &str| {
+            builder.get_config_value(&AmazonS3ConfigKey::from_str(key).unwrap())
+        };
+
+        let builder = AmazonS3Builder::new().with_config(
+            AmazonS3ConfigKey::from_str("aws_sse_kms_key_id").unwrap(),
+            "arn:aws:kms:us-east-1:123456789012:key/cmk",
+        );
+        let builder = configure_encryption(builder).unwrap();
+        assert_eq!(
+            value(&builder, "aws_server_side_encryption"),
+            Some("aws:kms".to_string())
+        );
+        assert_eq!(
+            value(&builder, "aws_sse_kms_key_id"),
+            Some("arn:aws:kms:us-east-1:123456789012:key/cmk".to_string())
+        );
+
+        let builder = AmazonS3Builder::new().with_config(
+            AmazonS3ConfigKey::from_str("aws_sse_customer_key_base64").unwrap(),
+            "MDEyMzQ1Njc4OWFiY2RlZjAxMjM0NTY3ODlhYmNkZWY=",
+        );
+        let builder = configure_encryption(builder).unwrap();
+        assert_eq!(
+            value(&builder, "aws_server_side_encryption"),
+            Some("sse-c".to_string())
+        );
+
+        let builder = AmazonS3Builder::new()
+            .with_sse_kms_encryption("cmk")
+            .with_ssec_encryption("MDEyMzQ1Njc4OWFiY2RlZjAxMjM0NTY3ODlhYmNkZWY=");
+        assert!(configure_encryption(builder).is_err());
+
+        let builder = AmazonS3Builder::new().with_bucket_key(true);
+        assert!(configure_encryption(builder).is_err());
+
+        let builder = AmazonS3Builder::new()
+            .with_sse_kms_encryption("cmk")
+            .with_bucket_key(true);
+        assert!(configure_encryption(builder).is_ok());
+    }
+
+    #[test]
+    fn test_is_r2_endpoint() {
+        assert!(is_r2_endpoint(
+            "https://0123456789abcdef.r2.cloudflarestorage.com"
+        ));
+        assert!(is_r2_endpoint(
+            "https://0123456789abcdef.eu.r2.cloudflarestorage.com/"
+        ));
+        assert!(!is_r2_endpoint("https://s3.us-east-1.amazonaws.com"));
+        assert!(!is_r2_endpoint("not a url"));
+    }
+
+    #[test]
+    #[serial]
+    fn r2_defaults_region_and_copy_header() {
+        ScopedEnv::run(|| {
+            clear_env_of_aws_keys();
+            let builder = configure_r2(AmazonS3Builder::new(), &HashMap::new());
+            assert_eq!(
+                builder.get_config_value(&AmazonS3ConfigKey::Region),
+                Some("auto".to_string())
+            );
+            assert!(
+                builder
+                    .get_config_value(&AmazonS3ConfigKey::CopyIfNotExists)
+                    .is_some_and(|v| v.contains(constants::R2_COPY_IF_NOT_EXISTS_HEADER))
+            );
+
+            let raw = HashMap::from([
+                ("aws_region".to_string(), "wnam".to_string()),
+                ("copy_if_not_exists".to_string(), "multipart".to_string()),
+            ]);
+            let builder = configure_r2(AmazonS3Builder::new().with_region("wnam"), &raw);
+            assert_eq!(
+                builder.get_config_value(&AmazonS3ConfigKey::Region),
+                Some("wnam".to_string())
+            );
+            assert!(
+                builder
+                    .get_config_value(&AmazonS3ConfigKey::CopyIfNotExists)
+                    .is_none()
+            );
+        });
+    }
+
+    #[test]
+    #[serial]
+    fn r2_endpoint_uses_conditional_put() {
+        ScopedEnv::run(|| {
+            clear_env_of_aws_keys();
+            let url = Url::parse("s3://bucket/table").unwrap();
+            let config = StorageConfig::parse_options([
+                ("AWS_ACCESS_KEY_ID", "key"),
+                ("AWS_SECRET_ACCESS_KEY", "secret"),
+                (
+                    "AWS_ENDPOINT_URL",
+                    "https://0123456789abcdef.r2.cloudflarestorage.com",
+                ),
+                (constants::AWS_S3_ALLOW_UNSAFE_RENAME, "true"),
+            ])
+            .unwrap();
+            let (store, _) = S3ObjectStoreFactory::default()
+                .parse_url_opts(&url, &config)
+                .unwrap();
+            assert!(!format!("{store:?}").contains("S3StorageBackend"));
+        });
+    }
+
     #[test]
     #[serial]
     fn test_is_aws() {
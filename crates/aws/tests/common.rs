@@ -43,8 +43,8 @@ impl StorageIntegration for S3Integration {
     /// prepare_env
     fn prepare_env(&self) {
         set_env_if_not_set(
-            constants::LOCK_TABLE_KEY_NAME,
-            format!("delta_log_it_{}", random::<u16>()),
+            constants::DYNAMO_LOCK_TABLE_KEY_NAME,
+            format!("delta_rs_lock_it_{}", random::<u16>()),
         );
         match std::env::var(constants::AWS_ENDPOINT_URL).ok() {
             Some(endpoint_url) if endpoint_url.to_lowercase() == "none" => unsafe {
@@ -131,18 +131,12 @@ impl S3Integration {
     }
 
     pub fn create_lock_table() -> std::io::Result<ExitStatus> {
-        let table_name =
-            std::env::var(constants::LOCK_TABLE_KEY_NAME).unwrap_or_else(|_| "delta_log".into());
+        let table_name = std::env::var(constants::DYNAMO_LOCK_TABLE_KEY_NAME)
+            .unwrap_or_else(|_| constants::DEFAULT_DYNAMO_LOCK_TABLE_NAME.into());
         Self::create_dynamodb_table(
             &table_name,
-            &[
-                "AttributeName=tablePath,AttributeType=S",
-                "AttributeName=fileName,AttributeType=S",
-            ],
-            &[
-                "AttributeName=tablePath,KeyType=HASH",
-                "AttributeName=fileName,KeyType=RANGE",
-            ],
+            &["AttributeName=lockKey,AttributeType=S"],
+            &["AttributeName=lockKey,KeyType=HASH"],
         )
     }
 
@@ -156,8 +150,8 @@ impl S3Integration {
     }
 
     pub fn delete_lock_table() -> std::io::Result<ExitStatus> {
-        let table_name =
-            std::env::var(constants::LOCK_TABLE_KEY_NAME).unwrap_or_else(|_| "delta_log".into());
+        let table_name = std::env::var(constants::DYNAMO_LOCK_TABLE_KEY_NAME)
+            .unwrap_or_else(|_| constants::DEFAULT_DYNAMO_LOCK_TABLE_NAME.into());
         Self::delete_dynamodb_table(&table_name)
     }
 }
@@ -0,0 +1,198 @@
+//! Proactive refresh of Azure AD credentials.
+//!
+//! The token caches in `object_store` only refresh a bearer token once it is about to expire
+//! within a fixed window. Long running writers may still hold on to such a token while a commit
+//! is in flight. [`RefreshingCredentialProvider`] tracks the expiry of the issued bearer tokens and
+//! obtains a fresh one as soon as the remaining lifetime drops below a configurable clock skew.
+//! The skew is capped at half the lifetime of a token, so short lived tokens are still used for
+//! a while instead of being replaced on every request.
+//!
+//! A fresh token is acquired by re-creating the underlying credential chain, which also means
+//! that rotated federated token files (e.g. AKS workload identity) are re-read.
+use std::sync::Arc;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use base64::Engine as _;
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use object_store::CredentialProvider;
+use object_store::azure::{AzureCredential, AzureCredentialProvider};
+use tokio::sync::Mutex;
+
+/// Storage option to configure the clock skew used when refreshing Azure AD tokens.
+///
+/// The value is parsed as a human readable duration, e.g. `10m` or `90s`.
+pub const AZURE_CREDENTIAL_REFRESH_SKEW: &str = "AZURE_CREDENTIAL_REFRESH_SKEW";
+
+/// Creates a new credential provider from the current configuration.
+pub(crate) type CredentialFactory =
+    Arc<dyn Fn() -> object_store::Result<AzureCredentialProvider> + Send + Sync>;
+
+struct CachedCredential {
+    provider: AzureCredentialProvider,
+    credential: Arc<AzureCredential>,
+    refresh_at: Option<SystemTime>,
+}
+
+/// Whether a token which should be refreshed at `refresh_at` is due.
+fn is_stale(refresh_at: Option<SystemTime>) -> bool {
+    refresh_at.is_some_and(|refresh_at| SystemTime::now() >= refresh_at)
+}
+
+/// Time at which `credential` should be replaced, `clock_skew` before it expires but no earlier
+/// than halfway through its lifetime.
+fn refresh_at(credential: &AzureCredential, clock_skew: Duration) -> Option<SystemTime> {
+    let (issued_at, expires_at) = token_lifetime(credential)?;
+    let lifetime = expires_at
+        .duration_since(issued_at.unwrap_or_else(SystemTime::now))
+        .unwrap_or_default();
+    Some(expires_at - clock_skew.min(lifetime / 2))
+}
+
+/// An [`AzureCredentialProvider`] which refreshes bearer tokens ahead of their expiry.
+pub struct RefreshingCredentialProvider {
+    factory: CredentialFactory,
+    clock_skew: Duration,
+    cache: Mutex<Option<CachedCredential>>,
+}
+
+impl std::fmt::Debug for RefreshingCredentialProvider {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("RefreshingCredentialProvider")
+            .field("clock_skew", &self.clock_skew)
+            .finish_non_exhaustive()
+    }
+}
+
+impl RefreshingCredentialProvider {
+    /// Create a new provider which obtains credential chains from `factory`.
+    pub(crate) fn new(factory: CredentialFactory, clock_skew: Duration) -> Self {
+        Self {
+            factory,
+            clock_skew,
+            cache: Mutex::new(None),
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl CredentialProvider for RefreshingCredentialProvider {
+    type Credential = AzureCredential;
+
+    async fn get_credential(&self) -> object_store::Result<Arc<AzureCredential>> {
+        let mut cache = self.cache.lock().await;
+        if let Some(cached) = cache.as_ref()
+            && !is_stale(cached.refresh_at)
+        {
+            return Ok(cached.credential.clone());
+        }
+
+        // The existing chain may already have cached a newer token, only rebuild it if not.
+        let mut provider = match cache.take() {
+            Some(cached) => cached.provider,
+            None => (self.factory)()?,
+        };
+        let mut credential = provider.get_credential().await?;
+        if is_stale(refresh_at(&credential, self.clock_skew)) {
+            tracing::debug!("Azure credential is about to expire, acquiring a new token");
+            provider = (self.factory)()?;
+            credential = provider.get_credential().await?;
+        }
+
+        *cache = Some(CachedCredential {
+            refresh_at: refresh_at(&credential, self.clock_skew),
+            provider,
+            credential: credential.clone(),
+        });
+        Ok(credential)
+    }
+}
+
+/// Extract the issue time (`iat` claim), if present, and the expiry (`exp` claim) of a JWT
+/// bearer token.
+///
+/// Returns `None` for credentials which do not expire or whose expiry cannot be determined.
+fn token_lifetime(credential: &AzureCredential) -> Option<(Option<SystemTime>, SystemTime)> {
+    let AzureCredential::BearerToken(token) = credential else {
+        return None;
+    };
+    let payload = URL_SAFE_NO_PAD
+        .decode(token.split('.').nth(1)?.trim_end_matches('='))
+        .ok()?;
+    let claims = serde_json::from_slice::<serde_json::Value>(&payload).ok()?;
+    let claim = |name: &str| {
+        claims
+            .get(name)
+            .and_then(|value| value.as_u64())
+            .map(|secs| UNIX_EPOCH + Duration::from_secs(secs))
+    };
+    Some((claim("iat"), claim("exp")?))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    use object_store::StaticCredentialProvider;
+
+    use super::*;
+
+    fn jwt(issued_ago: u64, expires_in: u64) -> String {
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap()
+            .as_secs();
+        let (iat, exp) = (now - issued_ago, now + expires_in);
+        let payload = URL_SAFE_NO_PAD.encode(format!(r#"{{"iat":{iat},"exp":{exp}}}"#));
+        format!("eyJhbGciOiJub25lIn0.{payload}.")
+    }
+
+    fn counting_factory(issued_ago: u64, expires_in: u64) -> (CredentialFactory, Arc<AtomicUsize>) {
+        let calls = Arc::new(AtomicUsize::new(0));
+        let counter = calls.clone();
+        let factory: CredentialFactory = Arc::new(move || {
+            counter.fetch_add(1, Ordering::SeqCst);
+            Ok(
+                Arc::new(StaticCredentialProvider::new(AzureCredential::BearerToken(
+                    jwt(issued_ago, expires_in),
+                ))) as AzureCredentialProvider,
+            )
+        });
+        (factory, calls)
+    }
+
+    #[test]
+    fn test_token_lifetime() {
+        let credential = AzureCredential::BearerToken(jwt(0, 60));
+        assert!(matches!(token_lifetime(&credential), Some((Some(_), _))));
+        assert!(token_lifetime(&AzureCredential::BearerToken("opaque".into())).is_none());
+        assert!(token_lifetime(&AzureCredential::SASToken(vec![])).is_none());
+    }
+
+    #[tokio::test]
+    async fn test_fresh_token_is_cached() {
+        let (factory, calls) = counting_factory(0, 3600);
+        let provider = RefreshingCredentialProvider::new(factory, Duration::from_secs(300));
+        provider.get_credential().await.unwrap();
+        provider.get_credential().await.unwrap();
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test]
+    async fn test_token_within_skew_is_refreshed() {
+        let (factory, calls) = counting_factory(3540, 60);
+        let provider = RefreshingCredentialProvider::new(factory, Duration::from_secs(300));
+        provider.get_credential().await.unwrap();
+        assert_eq!(calls.load(Ordering::SeqCst), 2);
+        provider.get_credential().await.unwrap();
+        assert_eq!(calls.load(Ordering::SeqCst), 3);
+    }
+
+    #[tokio::test]
+    async fn test_skew_is_capped_for_short_lived_tokens() {
+        let (factory, calls) = counting_factory(0, 60);
+        let provider = RefreshingCredentialProvider::new(factory, Duration::from_secs(300));
+        provider.get_credential().await.unwrap();
+        provider.get_credential().await.unwrap();
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+    }
+}
@@ -2,19 +2,24 @@ use std::collections::HashMap;
 use std::str::FromStr;
 use std::sync::Arc;
 
+use deltalake_core::logstore::config::parse_duration;
 use deltalake_core::logstore::{
-    LogStore, LogStoreFactory, ObjectStoreFactory, ObjectStoreRef, StorageConfig,
-    client_options_from_certificate, default_logstore, logstore_factories, object_store_factories,
+    LogStore, LogStoreFactory, ObjectStoreFactory, ObjectStoreRef, StorageConfig, default_logstore,
+    logstore_factories, object_store_factories,
 };
 use deltalake_core::{DeltaResult, DeltaTableError, Path};
 use object_store::ObjectStoreScheme;
 use object_store::azure::{AzureConfigKey, MicrosoftAzureBuilder};
 use object_store::client::SpawnedReqwestConnector;
+use object_store::signer::Signer;
 use url::Url;
 
 mod config;
+pub mod credential;
 pub mod error;
 
+pub use credential::{AZURE_CREDENTIAL_REFRESH_SKEW, RefreshingCredentialProvider};
+
 trait AzureOptions {
     fn as_azure_options(&self) -> HashMap<AzureConfigKey, String>;
 }
@@ -35,33 +40,44 @@ impl AzureOptions for HashMap<String, String> {
 #[derive(Clone, Default, Debug)]
 pub struct AzureFactory {}
 
+/// Configure the builder for the container at `url`.
+fn azure_builder(url: &Url, config: &StorageConfig) -> DeltaResult<MicrosoftAzureBuilder> {
+    let mut builder = MicrosoftAzureBuilder::new()
+        .with_url(url.to_string())
+        .with_retry(config.retry.clone());
+    if let Some(runtime) = &config.runtime {
+        builder = builder.with_http_connector(SpawnedReqwestConnector::new(runtime.get_handle()));
+    }
+
+    if let Some(client_options) = config.client_options()? {
+        builder = builder.with_client_options(client_options);
+    }
+
+    let refresh_skew = refresh_skew(&config.raw)?;
+    let config = config::AzureConfigHelper::try_new(config.raw.as_azure_options())?.build()?;
+
+    for (key, value) in config.iter() {
+        builder = builder.with_config(*key, value.clone());
+    }
+
+    if let Some(clock_skew) = refresh_skew {
+        let template = builder.clone();
+        let factory: credential::CredentialFactory =
+            Arc::new(move || Ok(template.clone().build()?.credentials().clone()));
+        builder = builder.with_credentials(Arc::new(RefreshingCredentialProvider::new(
+            factory, clock_skew,
+        )));
+    }
+    Ok(builder)
+}
+
 impl ObjectStoreFactory for AzureFactory {
     fn parse_url_opts(
         &self,
         url: &Url,
         config: &StorageConfig,
     ) -> DeltaResult<(ObjectStoreRef, Path)> {
-        let mut builder = MicrosoftAzureBuilder::new()
-            .with_url(url.to_string())
-            .with_retry(config.retry.clone());
-        if let Some(runtime) = &config.runtime {
-            builder =
-                builder.with_http_connector(SpawnedReqwestConnector::new(runtime.get_handle()));
-        }
-
-        if let Some(ref cert_config) = config.certificate
-            && let Some(ref path) = cert_config.certificate_path
-        {
-            builder = builder.with_client_options(client_options_from_certificate(path)?);
-        }
-
-        let config = config::AzureConfigHelper::try_new(config.raw.as_azure_options())?.build()?;
-
-        for (key, value) in config.iter() {
-            builder = builder.with_config(*key, value.clone());
-        }
-
-        let store = builder.build()?;
+        let store = azure_builder(url, config)?.build()?;
 
         let (_, path) =
             ObjectStoreScheme::parse(url).map_err(|e| DeltaTableError::GenericError {
@@ -71,6 +87,21 @@ impl ObjectStoreFactory for AzureFactory {
 
         Ok((Arc::new(store), prefix))
     }
+
+    fn signer(&self, url: &Url, config: &StorageConfig) -> DeltaResult<Option<Arc<dyn Signer>>> {
+        Ok(Some(Arc::new(azure_builder(url, config)?.build()?)))
+    }
+}
+
+/// Read the configured token refresh clock skew from the options or the environment.
+fn refresh_skew(options: &HashMap<String, String>) -> DeltaResult<Option<std::time::Duration>> {
+    options
+        .iter()
+        .find(|(key, _)| key.eq_ignore_ascii_case(AZURE_CREDENTIAL_REFRESH_SKEW))
+        .map(|(_, value)| value.clone())
+        .or_else(|| std::env::var(AZURE_CREDENTIAL_REFRESH_SKEW).ok())
+        .map(|value| parse_duration(&value))
+        .transpose()
 }
 
 impl LogStoreFactory for AzureFactory {
@@ -116,4 +147,22 @@ mod tests {
         let converted = options.as_azure_options();
         assert_eq!(converted.get(&AzureConfigKey::AccessKey), Some(&value));
     }
+
+    #[test]
+    fn test_refresh_skew() {
+        let options = HashMap::from([(
+            AZURE_CREDENTIAL_REFRESH_SKEW.to_ascii_lowercase(),
+            "10m".to_string(),
+        )]);
+        assert_eq!(
+            refresh_skew(&options).unwrap(),
+            Some(std::time::Duration::from_secs(600))
+        );
+
+        let options = HashMap::from([(
+            AZURE_CREDENTIAL_REFRESH_SKEW.to_string(),
+            "soon".to_string(),
+        )]);
+        assert!(refresh_skew(&options).is_err());
+    }
 }
@@ -0,0 +1,292 @@
+//! Keeping the Glue Data Catalog in sync with the Delta log.
+//!
+//! The [`GlueCommitHook`] registers a table in Glue once it is created and updates the schema,
+//! partition keys and location of the entry whenever a commit changes the metadata of the table.
+//! The entry is marked as Delta table, so engines like Athena read the Delta log at the location
+//! instead of listing the files.
+use std::collections::HashMap;
+use std::fmt;
+use std::sync::Arc;
+
+use aws_sdk_glue::types::{Column, StorageDescriptor, TableInput};
+use deltalake_core::data_catalog::DataCatalog;
+use deltalake_core::kernel::transaction::CommitHook;
+use deltalake_core::kernel::{
+    Action, DataType, Metadata, MetadataValue, PrimitiveType, StructField, Version,
+};
+use deltalake_core::logstore::LogStoreRef;
+use deltalake_core::protocol::DeltaOperation;
+use deltalake_core::{DeltaResult, DeltaTableError};
+
+use crate::{GlueDataCatalog, GlueError};
+
+/// A [`CommitHook`] registering a table in the Glue Data Catalog and keeping its entry up to
+/// date.
+pub struct GlueCommitHook {
+    catalog: Arc<GlueDataCatalog>,
+    catalog_id: Option<String>,
+    database_name: String,
+    table_name: String,
+}
+
+impl GlueCommitHook {
+    /// Create a hook for the table `table_name` in the database `database_name`
+    pub fn new(
+        catalog: Arc<GlueDataCatalog>,
+        database_name: impl Into<String>,
+        table_name: impl Into<String>,
+    ) -> Self {
+        Self {
+            catalog,
+            catalog_id: None,
+            database_name: database_name.into(),
+            table_name: table_name.into(),
+        }
+    }
+
+    /// Use the catalog of another AWS account instead of the one of the caller
+    pub fn with_catalog_id(mut self, catalog_id: impl Into<String>) -> Self {
+        self.catalog_id = Some(catalog_id.into());
+        self
+    }
+
+    /// Create the table entry, or update it if it already exists, e.g. when the table was
+    /// replaced.
+    async fn create_table(&self, metadata: &Metadata, location: &str) -> DeltaResult<()> {
+        let result = self
+            .catalog
+            .client()
+            .create_table()
+            .set_catalog_id(self.catalog_id.clone())
+            .database_name(&self.database_name)
+            .table_input(table_input(&self.table_name, metadata, location)?)
+            .send()
+            .await;
+        match result.map_err(aws_sdk_glue::Error::from) {
+            Ok(_) => Ok(()),
+            Err(aws_sdk_glue::Error::AlreadyExistsException(_)) => {
+                self.update_table(metadata, location).await
+            }
+            Err(source) => Err(GlueError::AWSError { source }.into()),
+        }
+    }
+
+    async fn update_table(&self, metadata: &Metadata, location: &str) -> DeltaResult<()> {
+        self.catalog
+            .client()
+            .update_table()
+            .set_catalog_id(self.catalog_id.clone())
+            .database_name(&self.database_name)
+            .table_input(table_input(&self.table_name, metadata, location)?)
+            .send()
+            .await
+            .map_err(|e| GlueError::AWSError { source: e.into() })?;
+        Ok(())
+    }
+}
+
+impl fmt::Debug for GlueCommitHook {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "GlueCommitHook({}.{})",
+            self.database_name, self.table_name
+        )
+    }
+}
+
+#[async_trait::async_trait]
+impl CommitHook for GlueCommitHook {
+    async fn post_commit(
+        &self,
+        _log_store: &LogStoreRef,
+        version: Version,
+        operation: &DeltaOperation,
+        actions: &[Action],
+    ) -> DeltaResult<()> {
+        if let DeltaOperation::Create {
+            metadata, location, ..
+        } = operation
+        {
+            return self.create_table(metadata, location.as_str()).await;
+        }
+        let metadata = actions.iter().find_map(|action| match action {
+            Action::Metadata(metadata) => Some(metadata),
+            _ => None,
+        });
+        if let Some(metadata) = metadata {
+            tracing::debug!("Updating {self:?} to the metadata of version {version}");
+            // the location does not change after the table was created
+            let location = self
+                .catalog
+                .get_table_storage_location(
+                    self.catalog_id.clone(),
+                    &self.database_name,
+                    &self.table_name,
+                )
+                .await
+                .map_err(|err| DeltaTableError::GenericError {
+                    source: Box::new(err),
+                })?;
+            self.update_table(metadata, &location).await?;
+        }
+        Ok(())
+    }
+}
+
+/// Describe the table as external Delta table, partition columns become partition keys
+pub(crate) fn table_input(
+    name: &str,
+    metadata: &Metadata,
+    location: &str,
+) -> DeltaResult<TableInput> {
+    let schema = metadata.parse_schema()?;
+    let partition_columns = metadata.partition_columns();
+
+    let mut columns = Vec::new();
+    for field in schema
+        .fields()
+        .filter(|field| !partition_columns.contains(field.name()))
+    {
+        columns.push(column(field)?);
+    }
+    let mut partition_keys = Vec::new();
+    for name in partition_columns {
+        let field = schema.field(name).ok_or_else(|| {
+            DeltaTableError::Generic(format!("Partition column {name} is not in the schema"))
+        })?;
+        partition_keys.push(column(field)?);
+    }
+
+    let parameters = HashMap::from_iter([
+        ("table_type".to_string(), "DELTA".to_string()),
+        ("EXTERNAL".to_string(), "TRUE".to_string()),
+        (
+            "spark.sql.sources.provider".to_string(),
+            "delta".to_string(),
+        ),
+    ]);
+    let storage_descriptor = StorageDescriptor::builder()
+        .location(location)
+        .set_columns(Some(columns))
+        .build();
+
+    Ok(TableInput::builder()
+        .name(name)
+        .set_description(metadata.description().map(ToString::to_string))
+        .table_type("EXTERNAL_TABLE")
+        .set_parameters(Some(parameters))
+        .storage_descriptor(storage_descriptor)
+        .set_partition_keys(Some(partition_keys))
+        .build()
+        .map_err(GlueError::from)?)
+}
+
+fn column(field: &StructField) -> Result<Column, GlueError> {
+    let comment = match field.metadata().get("comment") {
+        Some(MetadataValue::String(comment)) => Some(comment.clone()),
+        _ => None,
+    };
+    Ok(Column::builder()
+        .name(field.name())
+        .r#type(hive_type(field.data_type()))
+        .set_comment(comment)
+        .build()?)
+}
+
+/// The Hive name of a type, e.g. `bigint` or `array<string>`
+fn hive_type(data_type: &DataType) -> String {
+    match data_type {
+        DataType::Primitive(primitive) => match primitive {
+            PrimitiveType::Byte => "tinyint".to_string(),
+            PrimitiveType::Short => "smallint".to_string(),
+            PrimitiveType::Integer => "int".to_string(),
+            PrimitiveType::Long => "bigint".to_string(),
+            // Hive has no timestamps without time zone
+            PrimitiveType::Timestamp
+            | PrimitiveType::TimestampNtz
+            | PrimitiveType::TimestampNanos
+            | PrimitiveType::TimestampNanosNtz => "timestamp".to_string(),
+            // e.g. `decimal(10,2)`, `string`
+            other => other.to_string(),
+        },
+        DataType::Array(array) => format!("array<{}>", hive_type(array.element_type())),
+        DataType::Map(map) => format!(
+            "map<{},{}>",
+            hive_type(map.key_type()),
+            hive_type(map.value_type())
+        ),
+        DataType::Struct(fields) | DataType::Variant(fields) => {
+            let fields: Vec<_> = fields
+                .fields()
+                .map(|field| format!("{}:{}", field.name(), hive_type(field.data_type())))
+                .collect();
+            format!("struct<{}>", fields.join(","))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use deltalake_core::kernel::{ArrayType, MapType, StructType, new_metadata};
+
+    use super::*;
+
+    #[test]
+    fn test_hive_type() {
+        assert_eq!(hive_type(&DataType::LONG), "bigint");
+        assert_eq!(hive_type(&DataType::TIMESTAMP_NTZ), "timestamp");
+        assert_eq!(
+            hive_type(&DataType::decimal(10, 2).unwrap()),
+            "decimal(10,2)"
+        );
+        assert_eq!(
+            hive_type(&MapType::new(DataType::STRING, DataType::INTEGER, true).into()),
+            "map<string,int>"
+        );
+        let nested = StructType::try_new(vec![
+            StructField::new("a", DataType::SHORT, true),
+            StructField::new("b", ArrayType::new(DataType::STRING, true), true),
+        ])
+        .unwrap();
+        assert_eq!(
+            hive_type(&nested.into()),
+            "struct<a:smallint,b:array<string>>"
+        );
+    }
+
+    #[test]
+    fn test_table_input() {
+        let schema = StructType::try_new(vec![
+            StructField::new("id", DataType::LONG, false),
+            StructField::new("value", DataType::STRING, true),
+            StructField::new("date", DataType::DATE, true),
+        ])
+        .unwrap();
+        let metadata = new_metadata(&schema, ["date"], std::iter::empty::<(&str, &str)>()).unwrap();
+
+        let input = table_input("table", &metadata, "s3://bucket/table").unwrap();
+        assert_eq!(input.name(), "table");
+        assert_eq!(input.table_type(), Some("EXTERNAL_TABLE"));
+        assert_eq!(
+            input.parameters().unwrap().get("table_type"),
+            Some(&"DELTA".to_string())
+        );
+
+        let storage_descriptor = input.storage_descriptor().unwrap();
+        assert_eq!(storage_descriptor.location(), Some("s3://bucket/table"));
+        let columns: Vec<_> = storage_descriptor
+            .columns()
+            .iter()
+            .map(|column| (column.name(), column.r#type().unwrap()))
+            .collect();
+        assert_eq!(columns, vec![("id", "bigint"), ("value", "string")]);
+
+        let partition_keys: Vec<_> = input
+            .partition_keys()
+            .iter()
+            .map(|column| (column.name(), column.r#type().unwrap()))
+            .collect();
+        assert_eq!(partition_keys, vec![("date", "date")]);
+    }
+}
@@ -1,7 +1,26 @@
 //! Glue Data Catalog.
 //!
+//! Tables registered in Glue can be opened as `glue://database.table`, which resolves the
+//! location of the table through the catalog. The [`GlueCommitHook`] registers created tables
+//! in Glue and keeps their schema in sync, e.g. for querying them with Athena.
+use std::collections::HashMap;
+use std::future::Future;
+use std::sync::Arc;
+
 use aws_config::{BehaviorVersion, SdkConfig};
 use deltalake_core::data_catalog::{DataCatalog, DataCatalogError};
+use deltalake_core::kernel::Metadata;
+use deltalake_core::logstore::object_store::prefix::PrefixStore;
+use deltalake_core::logstore::{
+    LogStore, LogStoreFactory, ObjectStoreFactory, ObjectStoreRef, StorageConfig, default_logstore,
+    logstore_factories, object_store_factories,
+};
+use deltalake_core::{DeltaResult, DeltaTableError, Path, ensure_table_uri};
+use url::Url;
+
+pub mod hooks;
+
+pub use hooks::GlueCommitHook;
 
 #[derive(thiserror::Error, Debug)]
 pub enum GlueError {
@@ -18,6 +37,28 @@ pub enum GlueError {
         #[from]
         source: aws_sdk_glue::Error,
     },
+
+    /// A request to the AWS SDK is missing required fields
+    #[error("Invalid AWS SDK request: {source}")]
+    InvalidRequest {
+        #[from]
+        source: aws_sdk_glue::error::BuildError,
+    },
+
+    /// Invalid Table URI
+    #[error("Invalid Glue table URI: {table_uri}, expected glue://database.table")]
+    InvalidTableURI {
+        /// Table URI
+        table_uri: String,
+    },
+}
+
+impl From<GlueError> for DeltaTableError {
+    fn from(val: GlueError) -> Self {
+        DeltaTableError::GenericError {
+            source: Box::new(val),
+        }
+    }
 }
 
 impl From<GlueError> for DataCatalogError {
@@ -47,6 +88,10 @@ impl GlueDataCatalog {
         let client = aws_sdk_glue::Client::new(config);
         Self { client }
     }
+
+    pub(crate) fn client(&self) -> &aws_sdk_glue::Client {
+        &self.client
+    }
 }
 
 impl std::fmt::Debug for GlueDataCatalog {
@@ -113,4 +158,241 @@ impl DataCatalog for GlueDataCatalog {
             Err(err) => Err(err.into()),
         }
     }
+
+    async fn list_database_names(
+        &self,
+        catalog_id: Option<String>,
+    ) -> Result<Vec<String>, DataCatalogError> {
+        let databases = self
+            .client
+            .get_databases()
+            .set_catalog_id(catalog_id)
+            .into_paginator()
+            .items()
+            .send()
+            .try_collect()
+            .await
+            .map_err(|e| GlueError::AWSError { source: e.into() })?;
+        Ok(databases
+            .into_iter()
+            .map(|database| database.name)
+            .collect())
+    }
+
+    async fn list_table_names(
+        &self,
+        catalog_id: Option<String>,
+        database_name: &str,
+    ) -> Result<Vec<String>, DataCatalogError> {
+        let tables = self
+            .client
+            .get_tables()
+            .set_catalog_id(catalog_id)
+            .database_name(database_name)
+            .into_paginator()
+            .items()
+            .send()
+            .try_collect()
+            .await
+            .map_err(|e| GlueError::AWSError { source: e.into() })?;
+        Ok(tables.into_iter().map(|table| table.name).collect())
+    }
+
+    async fn register_table(
+        &self,
+        catalog_id: Option<String>,
+        database_name: &str,
+        table_name: &str,
+        location: &str,
+        metadata: &Metadata,
+    ) -> Result<(), DataCatalogError> {
+        let table_input = hooks::table_input(table_name, metadata, location).map_err(|err| {
+            DataCatalogError::Generic {
+                catalog: "glue",
+                source: Box::new(err),
+            }
+        })?;
+        self.client
+            .create_table()
+            .set_catalog_id(catalog_id)
+            .database_name(database_name)
+            .table_input(table_input)
+            .send()
+            .await
+            .map_err(|e| GlueError::AWSError { source: e.into() })?;
+        Ok(())
+    }
+
+    async fn drop_table(
+        &self,
+        catalog_id: Option<String>,
+        database_name: &str,
+        table_name: &str,
+    ) -> Result<(), DataCatalogError> {
+        self.client
+            .delete_table()
+            .set_catalog_id(catalog_id)
+            .database_name(database_name)
+            .name(table_name)
+            .send()
+            .await
+            .map_err(|e| GlueError::AWSError { source: e.into() })?;
+        Ok(())
+    }
+
+    async fn get_table_properties(
+        &self,
+        catalog_id: Option<String>,
+        database_name: &str,
+        table_name: &str,
+    ) -> Result<HashMap<String, String>, DataCatalogError> {
+        let response = self
+            .client
+            .get_table()
+            .set_catalog_id(catalog_id)
+            .database_name(database_name)
+            .name(table_name)
+            .send()
+            .await
+            .map_err(|e| GlueError::AWSError { source: e.into() })?;
+        let table = response.table.ok_or(GlueError::MissingMetadata {
+            metadata: "Table".to_string(),
+        })?;
+        Ok(table.parameters.unwrap_or_default())
+    }
+}
+
+/// Split a `glue://database.table` URI into its database and table name.
+fn parse_table_uri(table_uri: &Url) -> Result<(&str, &str), GlueError> {
+    let invalid = || GlueError::InvalidTableURI {
+        table_uri: table_uri.to_string(),
+    };
+    if table_uri.scheme() != "glue" {
+        return Err(invalid());
+    }
+    let name = table_uri.host_str().ok_or_else(invalid)?;
+    match name.split('.').collect::<Vec<_>>()[..] {
+        [database_name, table_name] if !database_name.is_empty() && !table_name.is_empty() => {
+            Ok((database_name, table_name))
+        }
+        _ => Err(invalid()),
+    }
+}
+
+/// Run `future` to completion from the synchronous object store factory.
+fn execute_glue_future<F, T>(future: F) -> DeltaResult<T>
+where
+    T: Send,
+    F: Future<Output = T> + Send,
+{
+    match tokio::runtime::Handle::try_current() {
+        Ok(handle) => match handle.runtime_flavor() {
+            tokio::runtime::RuntimeFlavor::MultiThread => {
+                Ok(tokio::task::block_in_place(move || handle.block_on(future)))
+            }
+            _ => std::thread::scope(|scope| {
+                scope
+                    .spawn(|| handle.block_on(future))
+                    .join()
+                    .map_err(|_| DeltaTableError::Generic("Glue catalog request panicked".into()))
+            }),
+        },
+        Err(_) => {
+            let runtime = tokio::runtime::Builder::new_current_thread()
+                .enable_all()
+                .build()
+                .map_err(|err| DeltaTableError::GenericError {
+                    source: Box::new(err),
+                })?;
+            Ok(runtime.block_on(future))
+        }
+    }
+}
+
+/// Resolves `glue://database.table` URIs to the location registered in the Glue Data Catalog.
+///
+/// The catalog client is configured from the environment. The store of the resolved location
+/// is built by the factory registered for its scheme, e.g. `s3`, with the storage options of
+/// the table.
+#[derive(Clone, Default, Debug)]
+pub struct GlueCatalogFactory {}
+
+impl ObjectStoreFactory for GlueCatalogFactory {
+    fn parse_url_opts(
+        &self,
+        table_uri: &Url,
+        config: &StorageConfig,
+    ) -> DeltaResult<(ObjectStoreRef, Path)> {
+        let (database_name, table_name) = parse_table_uri(table_uri)?;
+        let location = execute_glue_future(async {
+            let catalog = GlueDataCatalog::from_env().await?;
+            let location = catalog
+                .get_table_storage_location(None, database_name, table_name)
+                .await?;
+            Ok::<_, DataCatalogError>(location)
+        })?
+        .map_err(|err| DeltaTableError::GenericError {
+            source: Box::new(err),
+        })?;
+        let table_url = ensure_table_uri(&location)?;
+
+        let scheme = Url::parse(&format!("{}://", table_url.scheme()))
+            .map_err(|_| DeltaTableError::InvalidTableLocation(location.clone()))?;
+        let factory = object_store_factories()
+            .get(&scheme)
+            .map(|entry| entry.value().clone())
+            .ok_or_else(|| DeltaTableError::InvalidTableLocation(location.clone()))?;
+        let (store, prefix) = factory.parse_url_opts(&table_url, config)?;
+
+        Ok((Arc::new(PrefixStore::new(store, prefix)), Path::default()))
+    }
+}
+
+impl LogStoreFactory for GlueCatalogFactory {
+    fn with_options(
+        &self,
+        prefixed_store: ObjectStoreRef,
+        root_store: ObjectStoreRef,
+        location: &Url,
+        options: &StorageConfig,
+    ) -> DeltaResult<Arc<dyn LogStore>> {
+        Ok(default_logstore(
+            prefixed_store,
+            root_store,
+            location,
+            options,
+        ))
+    }
+}
+
+/// Register an [ObjectStoreFactory] and [LogStoreFactory] for `glue://` [Url]s
+pub fn register_handlers(_additional_prefixes: Option<Url>) {
+    let factory = Arc::new(GlueCatalogFactory::default());
+    let url = Url::parse("glue://").unwrap();
+    object_store_factories().insert(url.clone(), factory.clone());
+    logstore_factories().insert(url, factory);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_table_uri() {
+        let uri = Url::parse("glue://database.table").unwrap();
+        assert_eq!(parse_table_uri(&uri).unwrap(), ("database", "table"));
+
+        for uri in [
+            "glue://database",
+            "glue://catalog.database.table",
+            "glue://.table",
+            "s3://database.table",
+        ] {
+            let uri = Url::parse(uri).unwrap();
+            assert!(matches!(
+                parse_table_uri(&uri),
+                Err(GlueError::InvalidTableURI { .. })
+            ));
+        }
+    }
 }
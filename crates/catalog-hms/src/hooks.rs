@@ -0,0 +1,287 @@
+//! Keeping the Hive Metastore in sync with the Delta log.
+//!
+//! The [`HiveMetastoreCommitHook`] registers a table in the metastore once it is created and
+//! alters the columns, partition keys and location of the entry whenever a commit changes the
+//! metadata of the table. Like Spark, the entry is marked as Delta data source table, which lets
+//! engines like Spark and Trino read the Delta log at the location.
+use std::fmt;
+use std::sync::Arc;
+
+use deltalake_core::kernel::transaction::CommitHook;
+use deltalake_core::kernel::{
+    Action, DataType, Metadata, MetadataValue, PrimitiveType, StructField, Version,
+};
+use deltalake_core::logstore::LogStoreRef;
+use deltalake_core::protocol::DeltaOperation;
+use deltalake_core::{DeltaResult, DeltaTableError};
+use hive_metastore::{
+    FieldSchema, SerDeInfo, StorageDescriptor, Table, ThriftHiveMetastoreCreateTableException,
+};
+use pilota::{AHashMap, FastStr};
+use volo_thrift::MaybeException;
+
+use crate::{HiveMetastoreCatalog, HmsError, table_location};
+
+const INPUT_FORMAT: &str = "org.apache.hadoop.hive.ql.io.parquet.MapredParquetInputFormat";
+const OUTPUT_FORMAT: &str = "org.apache.hadoop.hive.ql.io.parquet.MapredParquetOutputFormat";
+const SERIALIZATION_LIB: &str = "org.apache.hadoop.hive.ql.io.parquet.serde.ParquetHiveSerDe";
+
+/// A [`CommitHook`] registering a table in the Hive Metastore and keeping its entry up to date.
+pub struct HiveMetastoreCommitHook {
+    catalog: Arc<HiveMetastoreCatalog>,
+    database_name: String,
+    table_name: String,
+}
+
+impl HiveMetastoreCommitHook {
+    /// Create a hook for the table `table_name` in the database `database_name`
+    pub fn new(
+        catalog: Arc<HiveMetastoreCatalog>,
+        database_name: impl Into<String>,
+        table_name: impl Into<String>,
+    ) -> Self {
+        Self {
+            catalog,
+            database_name: database_name.into(),
+            table_name: table_name.into(),
+        }
+    }
+
+    /// Create the table entry, or alter it if it already exists, e.g. when the table was
+    /// replaced.
+    async fn create_table(&self, metadata: &Metadata, location: &str) -> DeltaResult<()> {
+        let table = hive_table(&self.database_name, &self.table_name, metadata, location)?;
+        let response = self
+            .catalog
+            .client()
+            .create_table(table)
+            .await
+            .map_err(HmsError::thrift)?;
+        match response {
+            MaybeException::Ok(_) => Ok(()),
+            MaybeException::Exception(ThriftHiveMetastoreCreateTableException::O1(_)) => {
+                self.alter_table(metadata, location).await
+            }
+            MaybeException::Exception(exception) => Err(HmsError::exception(exception).into()),
+        }
+    }
+
+    async fn alter_table(&self, metadata: &Metadata, location: &str) -> DeltaResult<()> {
+        let table = hive_table(&self.database_name, &self.table_name, metadata, location)?;
+        let response = self
+            .catalog
+            .client()
+            .alter_table(
+                FastStr::new(&self.database_name),
+                FastStr::new(&self.table_name),
+                table,
+            )
+            .await
+            .map_err(HmsError::thrift)?;
+        match response {
+            MaybeException::Ok(_) => Ok(()),
+            MaybeException::Exception(exception) => Err(HmsError::exception(exception).into()),
+        }
+    }
+}
+
+impl fmt::Debug for HiveMetastoreCommitHook {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "HiveMetastoreCommitHook({}.{})",
+            self.database_name, self.table_name
+        )
+    }
+}
+
+#[async_trait::async_trait]
+impl CommitHook for HiveMetastoreCommitHook {
+    async fn post_commit(
+        &self,
+        _log_store: &LogStoreRef,
+        version: Version,
+        operation: &DeltaOperation,
+        actions: &[Action],
+    ) -> DeltaResult<()> {
+        if let DeltaOperation::Create {
+            metadata, location, ..
+        } = operation
+        {
+            return self.create_table(metadata, location.as_str()).await;
+        }
+        let metadata = actions.iter().find_map(|action| match action {
+            Action::Metadata(metadata) => Some(metadata),
+            _ => None,
+        });
+        if let Some(metadata) = metadata {
+            tracing::debug!("Altering {self:?} to the metadata of version {version}");
+            // the location does not change after the table was created
+            let table = self
+                .catalog
+                .get_table(&self.database_name, &self.table_name)
+                .await?;
+            let location = table_location(&table)?;
+            self.alter_table(metadata, &location).await?;
+        }
+        Ok(())
+    }
+}
+
+/// Describe the table as external Delta table, partition columns become partition keys
+pub(crate) fn hive_table(
+    database_name: &str,
+    table_name: &str,
+    metadata: &Metadata,
+    location: &str,
+) -> DeltaResult<Table> {
+    let schema = metadata.parse_schema()?;
+    let partition_columns = metadata.partition_columns();
+
+    let columns = schema
+        .fields()
+        .filter(|field| !partition_columns.contains(field.name()))
+        .map(field_schema)
+        .collect();
+    let mut partition_keys = Vec::new();
+    for name in partition_columns {
+        let field = schema.field(name).ok_or_else(|| {
+            DeltaTableError::Generic(format!("Partition column {name} is not in the schema"))
+        })?;
+        partition_keys.push(field_schema(field));
+    }
+
+    let mut parameters = AHashMap::from_iter([
+        ("EXTERNAL".into(), "TRUE".into()),
+        ("spark.sql.sources.provider".into(), "delta".into()),
+    ]);
+    if let Some(description) = metadata.description() {
+        parameters.insert("comment".into(), FastStr::new(description));
+    }
+    let storage_descriptor = StorageDescriptor {
+        location: Some(FastStr::new(location)),
+        cols: Some(columns),
+        input_format: Some(INPUT_FORMAT.into()),
+        output_format: Some(OUTPUT_FORMAT.into()),
+        serde_info: Some(SerDeInfo {
+            serialization_lib: Some(SERIALIZATION_LIB.into()),
+            parameters: Some(AHashMap::from_iter([(
+                "path".into(),
+                FastStr::new(location),
+            )])),
+            ..Default::default()
+        }),
+        ..Default::default()
+    };
+
+    Ok(Table {
+        table_name: Some(FastStr::new(table_name)),
+        db_name: Some(FastStr::new(database_name)),
+        table_type: Some("EXTERNAL_TABLE".into()),
+        sd: Some(storage_descriptor),
+        partition_keys: Some(partition_keys),
+        parameters: Some(parameters),
+        ..Default::default()
+    })
+}
+
+fn field_schema(field: &StructField) -> FieldSchema {
+    let comment = match field.metadata().get("comment") {
+        Some(MetadataValue::String(comment)) => Some(FastStr::new(comment)),
+        _ => None,
+    };
+    FieldSchema {
+        name: Some(FastStr::new(field.name())),
+        r#type: Some(hive_type(field.data_type()).into()),
+        comment,
+    }
+}
+
+/// The Hive name of a type, e.g. `bigint` or `array<string>`
+fn hive_type(data_type: &DataType) -> String {
+    match data_type {
+        DataType::Primitive(primitive) => match primitive {
+            PrimitiveType::Byte => "tinyint".to_string(),
+            PrimitiveType::Short => "smallint".to_string(),
+            PrimitiveType::Integer => "int".to_string(),
+            PrimitiveType::Long => "bigint".to_string(),
+            // Hive has no timestamps without time zone
+            PrimitiveType::Timestamp
+            | PrimitiveType::TimestampNtz
+            | PrimitiveType::TimestampNanos
+            | PrimitiveType::TimestampNanosNtz => "timestamp".to_string(),
+            // e.g. `decimal(10,2)`, `string`
+            other => other.to_string(),
+        },
+        DataType::Array(array) => format!("array<{}>", hive_type(array.element_type())),
+        DataType::Map(map) => format!(
+            "map<{},{}>",
+            hive_type(map.key_type()),
+            hive_type(map.value_type())
+        ),
+        DataType::Struct(fields) | DataType::Variant(fields) => {
+            let fields: Vec<_> = fields
+                .fields()
+                .map(|field| format!("{}:{}", field.name(), hive_type(field.data_type())))
+                .collect();
+            format!("struct<{}>", fields.join(","))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use deltalake_core::kernel::{StructType, new_metadata};
+
+    use super::*;
+
+    #[test]
+    fn test_hive_table() {
+        let schema = StructType::try_new(vec![
+            StructField::new("id", DataType::LONG, false),
+            StructField::new("ts", DataType::TIMESTAMP_NTZ, true),
+            StructField::new("date", DataType::DATE, true),
+        ])
+        .unwrap();
+        let metadata = new_metadata(&schema, ["date"], std::iter::empty::<(&str, &str)>()).unwrap();
+
+        let table = hive_table("database", "table", &metadata, "s3://bucket/table").unwrap();
+        assert_eq!(table.db_name.as_deref(), Some("database"));
+        assert_eq!(table.table_name.as_deref(), Some("table"));
+        assert_eq!(table.table_type.as_deref(), Some("EXTERNAL_TABLE"));
+        assert_eq!(
+            table
+                .parameters
+                .as_ref()
+                .unwrap()
+                .get("spark.sql.sources.provider")
+                .map(|provider| provider.as_str()),
+            Some("delta")
+        );
+        assert_eq!(table_location(&table).unwrap(), "s3://bucket/table");
+
+        let describe = |fields: &Option<Vec<FieldSchema>>| -> Vec<(String, String)> {
+            fields
+                .iter()
+                .flatten()
+                .map(|field| {
+                    (
+                        field.name.as_deref().unwrap().to_string(),
+                        field.r#type.as_deref().unwrap().to_string(),
+                    )
+                })
+                .collect()
+        };
+        assert_eq!(
+            describe(&table.sd.as_ref().unwrap().cols),
+            vec![
+                ("id".to_string(), "bigint".to_string()),
+                ("ts".to_string(), "timestamp".to_string())
+            ]
+        );
+        assert_eq!(
+            describe(&table.partition_keys),
+            vec![("date".to_string(), "date".to_string())]
+        );
+    }
+}
@@ -0,0 +1,495 @@
+//! Hive Metastore catalog.
+//!
+//! Talks to a Hive Metastore over thrift to resolve the location of tables, which can be opened
+//! as `hms://host:port/database/table`. The [`HiveMetastoreCommitHook`] registers created tables
+//! in the metastore and keeps their schema in sync.
+use std::collections::HashMap;
+use std::future::Future;
+use std::net::{SocketAddr, ToSocketAddrs};
+use std::sync::Arc;
+
+use deltalake_core::data_catalog::{DataCatalog, DataCatalogError};
+use deltalake_core::kernel::Metadata;
+use deltalake_core::logstore::object_store::prefix::PrefixStore;
+use deltalake_core::logstore::{
+    LogStore, LogStoreFactory, ObjectStoreFactory, ObjectStoreRef, StorageConfig, default_logstore,
+    logstore_factories, object_store_factories,
+};
+use deltalake_core::{DeltaResult, DeltaTableError, Path, ensure_table_uri};
+use hive_metastore::{
+    Table, ThriftHiveMetastoreClient, ThriftHiveMetastoreClientBuilder,
+    ThriftHiveMetastoreGetTableException,
+};
+use pilota::FastStr;
+use url::Url;
+use volo_thrift::MaybeException;
+use volo_thrift::codec::default::DefaultMakeCodec;
+
+pub mod hooks;
+
+pub use hooks::HiveMetastoreCommitHook;
+
+/// The port the metastore listens on unless configured otherwise
+pub const DEFAULT_PORT: u16 = 9083;
+
+#[derive(thiserror::Error, Debug)]
+pub enum HmsError {
+    /// Missing metadata in the catalog
+    #[error("Missing Metadata {metadata} in the Hive Metastore")]
+    MissingMetadata {
+        /// The missing metadata property
+        metadata: String,
+    },
+
+    /// The table does not exist in the metastore
+    #[error("Table {database_name}.{table_name} does not exist in the Hive Metastore")]
+    TableNotFound {
+        /// Name of the database
+        database_name: String,
+        /// Name of the table
+        table_name: String,
+    },
+
+    /// The metastore rejected a request
+    #[error("Hive Metastore request failed: {message}")]
+    Exception {
+        /// The exception thrown by the metastore
+        message: String,
+    },
+
+    /// The metastore could not be reached
+    #[error("Failed to connect to the Hive Metastore at {address}: {source}")]
+    InvalidAddress {
+        /// The address of the metastore
+        address: String,
+        /// The underlying error
+        source: std::io::Error,
+    },
+
+    /// The thrift transport failed
+    #[error("Thrift error: {source}")]
+    Thrift {
+        /// The underlying error
+        source: Box<dyn std::error::Error + Send + Sync + 'static>,
+    },
+
+    /// Invalid Table URI
+    #[error(
+        "Invalid Hive Metastore table URI: {table_uri}, expected hms://host:port/database/table"
+    )]
+    InvalidTableURI {
+        /// Table URI
+        table_uri: String,
+    },
+}
+
+impl HmsError {
+    pub(crate) fn thrift(source: impl std::error::Error + Send + Sync + 'static) -> Self {
+        Self::Thrift {
+            source: Box::new(source),
+        }
+    }
+
+    pub(crate) fn exception(exception: impl std::fmt::Debug) -> Self {
+        Self::Exception {
+            message: format!("{exception:?}"),
+        }
+    }
+}
+
+impl From<HmsError> for DataCatalogError {
+    fn from(val: HmsError) -> Self {
+        DataCatalogError::Generic {
+            catalog: "hms",
+            source: Box::new(val),
+        }
+    }
+}
+
+impl From<HmsError> for DeltaTableError {
+    fn from(val: HmsError) -> Self {
+        DeltaTableError::GenericError {
+            source: Box::new(val),
+        }
+    }
+}
+
+/// A Hive Metastore implementation of the `Catalog` trait
+pub struct HiveMetastoreCatalog {
+    address: SocketAddr,
+    client: ThriftHiveMetastoreClient,
+}
+
+impl HiveMetastoreCatalog {
+    /// Creates a new [HiveMetastoreCatalog] for the metastore at `address`, e.g.
+    /// `metastore:9083`. The port defaults to [DEFAULT_PORT].
+    pub fn try_new(address: &str) -> Result<Self, HmsError> {
+        let invalid = |source| HmsError::InvalidAddress {
+            address: address.to_string(),
+            source,
+        };
+        let resolved = if address.contains(':') {
+            address.to_socket_addrs()
+        } else {
+            (address, DEFAULT_PORT).to_socket_addrs()
+        };
+        let address = resolved.map_err(invalid)?.next().ok_or_else(|| {
+            invalid(std::io::Error::new(
+                std::io::ErrorKind::NotFound,
+                "address did not resolve",
+            ))
+        })?;
+        Ok(Self::with_address(address))
+    }
+
+    /// Create a new [HiveMetastoreCatalog] for the metastore at the given socket address
+    pub fn with_address(address: SocketAddr) -> Self {
+        // the metastore speaks the binary protocol over a buffered, unframed transport
+        let client = ThriftHiveMetastoreClientBuilder::new("hms")
+            .address(address)
+            .make_codec(DefaultMakeCodec::buffered())
+            .build();
+        Self { address, client }
+    }
+
+    pub(crate) fn client(&self) -> &ThriftHiveMetastoreClient {
+        &self.client
+    }
+
+    /// Get the table definition from the metastore
+    pub async fn get_table(
+        &self,
+        database_name: &str,
+        table_name: &str,
+    ) -> Result<Table, HmsError> {
+        let response = self
+            .client
+            .get_table(FastStr::new(database_name), FastStr::new(table_name))
+            .await
+            .map_err(HmsError::thrift)?;
+        match response {
+            MaybeException::Ok(table) => Ok(table),
+            MaybeException::Exception(ThriftHiveMetastoreGetTableException::O2(_)) => {
+                Err(HmsError::TableNotFound {
+                    database_name: database_name.to_string(),
+                    table_name: table_name.to_string(),
+                })
+            }
+            MaybeException::Exception(exception) => Err(HmsError::exception(exception)),
+        }
+    }
+}
+
+impl std::fmt::Debug for HiveMetastoreCatalog {
+    fn fmt(&self, fmt: &mut std::fmt::Formatter<'_>) -> Result<(), std::fmt::Error> {
+        write!(fmt, "HiveMetastoreCatalog({})", self.address)
+    }
+}
+
+// Placeholder suffix created by Spark for data source tables, whose actual location is stored
+// in the `path` parameter of the serde
+const PLACEHOLDER_SUFFIX: &str = "-__PLACEHOLDER__";
+
+/// The storage location of `table`
+fn table_location(table: &Table) -> Result<String, HmsError> {
+    let storage_descriptor = table.sd.as_ref().ok_or(HmsError::MissingMetadata {
+        metadata: "Storage Descriptor".to_string(),
+    })?;
+    let path = storage_descriptor
+        .serde_info
+        .as_ref()
+        .and_then(|serde_info| serde_info.parameters.as_ref())
+        .and_then(|parameters| parameters.get("path"));
+    let location = match (&storage_descriptor.location, path) {
+        (Some(location), _) if !location.ends_with(PLACEHOLDER_SUFFIX) => location,
+        (_, Some(path)) => path,
+        _ => {
+            return Err(HmsError::MissingMetadata {
+                metadata: "Location".to_string(),
+            });
+        }
+    };
+    Ok(location.replace("s3a://", "s3://"))
+}
+
+#[async_trait::async_trait]
+impl DataCatalog for HiveMetastoreCatalog {
+    type Error = DataCatalogError;
+
+    /// Get the table storage location from the Hive Metastore
+    async fn get_table_storage_location(
+        &self,
+        _catalog_id: Option<String>,
+        database_name: &str,
+        table_name: &str,
+    ) -> Result<String, DataCatalogError> {
+        let table = self.get_table(database_name, table_name).await?;
+        Ok(table_location(&table)?)
+    }
+
+    async fn list_database_names(
+        &self,
+        _catalog_id: Option<String>,
+    ) -> Result<Vec<String>, DataCatalogError> {
+        let response = self
+            .client
+            .get_all_databases()
+            .await
+            .map_err(HmsError::thrift)?;
+        match response {
+            MaybeException::Ok(databases) => Ok(databases.iter().map(|d| d.to_string()).collect()),
+            MaybeException::Exception(exception) => Err(HmsError::exception(exception).into()),
+        }
+    }
+
+    async fn list_table_names(
+        &self,
+        _catalog_id: Option<String>,
+        database_name: &str,
+    ) -> Result<Vec<String>, DataCatalogError> {
+        let response = self
+            .client
+            .get_all_tables(FastStr::new(database_name))
+            .await
+            .map_err(HmsError::thrift)?;
+        match response {
+            MaybeException::Ok(tables) => Ok(tables.iter().map(|t| t.to_string()).collect()),
+            MaybeException::Exception(exception) => Err(HmsError::exception(exception).into()),
+        }
+    }
+
+    async fn register_table(
+        &self,
+        _catalog_id: Option<String>,
+        database_name: &str,
+        table_name: &str,
+        location: &str,
+        metadata: &Metadata,
+    ) -> Result<(), DataCatalogError> {
+        let table =
+            hooks::hive_table(database_name, table_name, metadata, location).map_err(|err| {
+                DataCatalogError::Generic {
+                    catalog: "hms",
+                    source: Box::new(err),
+                }
+            })?;
+        let response = self
+            .client
+            .create_table(table)
+            .await
+            .map_err(HmsError::thrift)?;
+        match response {
+            MaybeException::Ok(_) => Ok(()),
+            MaybeException::Exception(exception) => Err(HmsError::exception(exception).into()),
+        }
+    }
+
+    async fn drop_table(
+        &self,
+        _catalog_id: Option<String>,
+        database_name: &str,
+        table_name: &str,
+    ) -> Result<(), DataCatalogError> {
+        // only the entry is dropped, the data of external tables is never deleted
+        let response = self
+            .client
+            .drop_table(FastStr::new(database_name), FastStr::new(table_name), false)
+            .await
+            .map_err(HmsError::thrift)?;
+        match response {
+            MaybeException::Ok(_) => Ok(()),
+            MaybeException::Exception(exception) => Err(HmsError::exception(exception).into()),
+        }
+    }
+
+    async fn get_table_properties(
+        &self,
+        _catalog_id: Option<String>,
+        database_name: &str,
+        table_name: &str,
+    ) -> Result<HashMap<String, String>, DataCatalogError> {
+        let table = self.get_table(database_name, table_name).await?;
+        Ok(table
+            .parameters
+            .iter()
+            .flatten()
+            .map(|(key, value)| (key.to_string(), value.to_string()))
+            .collect())
+    }
+}
+
+/// Split a `hms://host:port/database/table` URI into the metastore address, the database and
+/// the table name.
+fn parse_table_uri(table_uri: &Url) -> Result<(String, &str, &str), HmsError> {
+    let invalid = || HmsError::InvalidTableURI {
+        table_uri: table_uri.to_string(),
+    };
+    if table_uri.scheme() != "hms" {
+        return Err(invalid());
+    }
+    let host = table_uri.host_str().ok_or_else(invalid)?;
+    let address = format!("{host}:{}", table_uri.port().unwrap_or(DEFAULT_PORT));
+    let segments: Vec<_> = table_uri
+        .path_segments()
+        .ok_or_else(invalid)?
+        .filter(|segment| !segment.is_empty())
+        .collect();
+    match segments[..] {
+        [database_name, table_name] => Ok((address, database_name, table_name)),
+        _ => Err(invalid()),
+    }
+}
+
+/// Run `future` to completion from the synchronous object store factory.
+fn execute_hms_future<F, T>(future: F) -> DeltaResult<T>
+where
+    T: Send,
+    F: Future<Output = T> + Send,
+{
+    match tokio::runtime::Handle::try_current() {
+        Ok(handle) => match handle.runtime_flavor() {
+            tokio::runtime::RuntimeFlavor::MultiThread => {
+                Ok(tokio::task::block_in_place(move || handle.block_on(future)))
+            }
+            _ => std::thread::scope(|scope| {
+                scope
+                    .spawn(|| handle.block_on(future))
+                    .join()
+                    .map_err(|_| DeltaTableError::Generic("Hive Metastore request panicked".into()))
+            }),
+        },
+        Err(_) => {
+            let runtime = tokio::runtime::Builder::new_current_thread()
+                .enable_all()
+                .build()
+                .map_err(|err| DeltaTableError::GenericError {
+                    source: Box::new(err),
+                })?;
+            Ok(runtime.block_on(future))
+        }
+    }
+}
+
+/// Resolves `hms://host:port/database/table` URIs to the location registered in the Hive
+/// Metastore.
+///
+/// The store of the resolved location is built by the factory registered for its scheme, e.g.
+/// `s3` or `hdfs`, with the storage options of the table.
+#[derive(Clone, Default, Debug)]
+pub struct HiveMetastoreCatalogFactory {}
+
+impl ObjectStoreFactory for HiveMetastoreCatalogFactory {
+    fn parse_url_opts(
+        &self,
+        table_uri: &Url,
+        config: &StorageConfig,
+    ) -> DeltaResult<(ObjectStoreRef, Path)> {
+        let (address, database_name, table_name) = parse_table_uri(table_uri)?;
+        let location = execute_hms_future(async {
+            let catalog = HiveMetastoreCatalog::try_new(&address)?;
+            let table = catalog.get_table(database_name, table_name).await?;
+            table_location(&table)
+        })??;
+        let table_url = ensure_table_uri(&location)?;
+
+        let scheme = Url::parse(&format!("{}://", table_url.scheme()))
+            .map_err(|_| DeltaTableError::InvalidTableLocation(location.clone()))?;
+        let factory = object_store_factories()
+            .get(&scheme)
+            .map(|entry| entry.value().clone())
+            .ok_or_else(|| DeltaTableError::InvalidTableLocation(location.clone()))?;
+        let (store, prefix) = factory.parse_url_opts(&table_url, config)?;
+
+        Ok((Arc::new(PrefixStore::new(store, prefix)), Path::default()))
+    }
+}
+
+impl LogStoreFactory for HiveMetastoreCatalogFactory {
+    fn with_options(
+        &self,
+        prefixed_store: ObjectStoreRef,
+        root_store: ObjectStoreRef,
+        location: &Url,
+        options: &StorageConfig,
+    ) -> DeltaResult<Arc<dyn LogStore>> {
+        Ok(default_logstore(
+            prefixed_store,
+            root_store,
+            location,
+            options,
+        ))
+    }
+}
+
+/// Register an [ObjectStoreFactory] and [LogStoreFactory] for `hms://` [Url]s
+pub fn register_handlers(_additional_prefixes: Option<Url>) {
+    let factory = Arc::new(HiveMetastoreCatalogFactory::default());
+    let url = Url::parse("hms://").unwrap();
+    object_store_factories().insert(url.clone(), factory.clone());
+    logstore_factories().insert(url, factory);
+}
+
+#[cfg(test)]
+mod tests {
+    use hive_metastore::{SerDeInfo, StorageDescriptor};
+    use pilota::AHashMap;
+
+    use super::*;
+
+    #[test]
+    fn test_parse_table_uri() {
+        let uri = Url::parse("hms://metastore:9084/database/table").unwrap();
+        assert_eq!(
+            parse_table_uri(&uri).unwrap(),
+            ("metastore:9084".to_string(), "database", "table")
+        );
+        let uri = Url::parse("hms://metastore/database/table").unwrap();
+        assert_eq!(parse_table_uri(&uri).unwrap().0, "metastore:9083");
+
+        for uri in [
+            "hms://metastore/database",
+            "hms://metastore/catalog/database/table",
+            "s3://metastore/database/table",
+        ] {
+            let uri = Url::parse(uri).unwrap();
+            assert!(matches!(
+                parse_table_uri(&uri),
+                Err(HmsError::InvalidTableURI { .. })
+            ));
+        }
+    }
+
+    #[test]
+    fn test_table_location() {
+        let table = |location: &'static str, path: Option<&'static str>| Table {
+            sd: Some(StorageDescriptor {
+                location: Some(location.into()),
+                serde_info: Some(SerDeInfo {
+                    parameters: path
+                        .map(|path| AHashMap::from_iter([("path".into(), path.into())])),
+                    ..Default::default()
+                }),
+                ..Default::default()
+            }),
+            ..Default::default()
+        };
+
+        assert_eq!(
+            table_location(&table("s3a://bucket/table", None)).unwrap(),
+            "s3://bucket/table"
+        );
+        // tables created by Spark only store a placeholder as location
+        assert_eq!(
+            table_location(&table(
+                "hdfs://namenode/warehouse/table-__PLACEHOLDER__",
+                Some("hdfs://namenode/data/table")
+            ))
+            .unwrap(),
+            "hdfs://namenode/data/table"
+        );
+        assert!(matches!(
+            table_location(&Table::default()),
+            Err(HmsError::MissingMetadata { .. })
+        ));
+    }
+}
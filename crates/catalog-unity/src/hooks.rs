@@ -0,0 +1,307 @@
+//! Keeping Unity Catalog in sync with the Delta log.
+//!
+//! The [`UnityCatalogCommitHook`] registers a table as external table in Unity Catalog once it
+//! is created and pushes the columns and comment of the table whenever a commit changes its
+//! metadata. Attach it to a single [`CreateBuilder`](deltalake_core::operations::create::CreateBuilder)
+//! to only register the table, or to the log store via
+//! [`DeltaTableBuilder::with_commit_hook`](deltalake_core::DeltaTableBuilder::with_commit_hook)
+//! to also keep the schema in sync.
+use std::fmt;
+use std::sync::Arc;
+
+use deltalake_core::DeltaResult;
+use deltalake_core::kernel::transaction::CommitHook;
+use deltalake_core::kernel::{
+    Action, DataType, Metadata, MetadataValue, PrimitiveType, StructType, Version,
+};
+use deltalake_core::logstore::LogStoreRef;
+use deltalake_core::protocol::DeltaOperation;
+use reqwest::Url;
+
+use crate::models::{
+    ColumnInfo, ColumnTypeName, CreateTableRequest, DataSourceFormat, GetTableResponse, TableType,
+    UpdateTableRequest,
+};
+use crate::{UnityCatalog, UnityCatalogError};
+
+/// A [`CommitHook`] registering a table in Unity Catalog and keeping its schema up to date.
+pub struct UnityCatalogCommitHook {
+    catalog: Arc<UnityCatalog>,
+    catalog_name: String,
+    schema_name: String,
+    table_name: String,
+}
+
+impl UnityCatalogCommitHook {
+    /// Create a hook for the table `catalog_name.schema_name.table_name`
+    pub fn new(
+        catalog: Arc<UnityCatalog>,
+        catalog_name: impl Into<String>,
+        schema_name: impl Into<String>,
+        table_name: impl Into<String>,
+    ) -> Self {
+        Self {
+            catalog,
+            catalog_name: catalog_name.into(),
+            schema_name: schema_name.into(),
+            table_name: table_name.into(),
+        }
+    }
+
+    /// Register the table as external table, or update it if it is already registered, e.g.
+    /// when the table was replaced.
+    async fn register_table(&self, metadata: &Metadata, location: &Url) -> DeltaResult<()> {
+        let request = CreateTableRequest {
+            name: self.table_name.clone(),
+            catalog_name: self.catalog_name.clone(),
+            schema_name: self.schema_name.clone(),
+            table_type: TableType::External,
+            data_source_format: DataSourceFormat::Delta,
+            columns: column_infos(metadata)?,
+            storage_location: location.to_string(),
+            comment: metadata.description().map(ToString::to_string),
+            properties: metadata.configuration().clone(),
+        };
+        match self.catalog.create_table(&request).await? {
+            GetTableResponse::Success(_) => Ok(()),
+            GetTableResponse::Error(err) if err.error_code.ends_with("ALREADY_EXISTS") => {
+                self.update_table(metadata).await
+            }
+            GetTableResponse::Error(err) => Err(UnityCatalogError::from(err).into()),
+        }
+    }
+
+    async fn update_table(&self, metadata: &Metadata) -> DeltaResult<()> {
+        let request = UpdateTableRequest {
+            columns: column_infos(metadata)?,
+            comment: metadata.description().map(ToString::to_string),
+        };
+        match self
+            .catalog
+            .update_table(
+                self.catalog_name.as_str(),
+                self.schema_name.as_str(),
+                self.table_name.as_str(),
+                &request,
+            )
+            .await?
+        {
+            GetTableResponse::Success(_) => Ok(()),
+            GetTableResponse::Error(err) => Err(UnityCatalogError::from(err).into()),
+        }
+    }
+}
+
+impl fmt::Debug for UnityCatalogCommitHook {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "UnityCatalogCommitHook({}.{}.{})",
+            self.catalog_name, self.schema_name, self.table_name
+        )
+    }
+}
+
+#[async_trait::async_trait]
+impl CommitHook for UnityCatalogCommitHook {
+    async fn post_commit(
+        &self,
+        _log_store: &LogStoreRef,
+        version: Version,
+        operation: &DeltaOperation,
+        actions: &[Action],
+    ) -> DeltaResult<()> {
+        if let DeltaOperation::Create {
+            metadata, location, ..
+        } = operation
+        {
+            tracing::debug!("Registering {self:?} at {location}");
+            return self.register_table(metadata, location).await;
+        }
+        let metadata = actions.iter().find_map(|action| match action {
+            Action::Metadata(metadata) => Some(metadata),
+            _ => None,
+        });
+        if let Some(metadata) = metadata {
+            tracing::debug!("Updating {self:?} to the metadata of version {version}");
+            self.update_table(metadata).await?;
+        }
+        Ok(())
+    }
+}
+
+/// Describe the columns of the table as expected by Unity Catalog
+pub(crate) fn column_infos(metadata: &Metadata) -> DeltaResult<Vec<ColumnInfo>> {
+    let schema = metadata.parse_schema()?;
+    let partition_columns = metadata.partition_columns();
+    schema
+        .fields()
+        .enumerate()
+        .map(|(position, field)| -> DeltaResult<ColumnInfo> {
+            let (type_precision, type_scale) = match field.data_type() {
+                DataType::Primitive(PrimitiveType::Decimal(decimal)) => (
+                    Some(decimal.precision() as i32),
+                    Some(decimal.scale() as i32),
+                ),
+                _ => (None, None),
+            };
+            Ok(ColumnInfo {
+                name: field.name().clone(),
+                type_text: Some(type_text(field.data_type())),
+                type_json: Some(serde_json::to_string(field)?),
+                type_name: type_name(field.data_type()),
+                type_precision,
+                type_scale,
+                type_interval_type: None,
+                position: position as u32,
+                comment: match field.metadata().get("comment") {
+                    Some(MetadataValue::String(comment)) => Some(comment.clone()),
+                    _ => None,
+                },
+                nullable: field.is_nullable(),
+                partition_index: partition_columns
+                    .iter()
+                    .position(|column| column == field.name())
+                    .map(|index| index as i32),
+            })
+        })
+        .collect()
+}
+
+/// The SQL name of a type, e.g. `bigint` or `array<string>`
+fn type_text(data_type: &DataType) -> String {
+    match data_type {
+        DataType::Primitive(primitive) => match primitive {
+            PrimitiveType::Byte => "tinyint".to_string(),
+            PrimitiveType::Short => "smallint".to_string(),
+            PrimitiveType::Integer => "int".to_string(),
+            PrimitiveType::Long => "bigint".to_string(),
+            // e.g. `decimal(10,2)`, `timestamp_ntz`, `string`
+            other => other.to_string(),
+        },
+        DataType::Array(array) => format!("array<{}>", type_text(array.element_type())),
+        DataType::Map(map) => format!(
+            "map<{},{}>",
+            type_text(map.key_type()),
+            type_text(map.value_type())
+        ),
+        DataType::Struct(fields) => format!("struct<{}>", struct_fields_text(fields)),
+        DataType::Variant(_) => "variant".to_string(),
+    }
+}
+
+fn struct_fields_text(fields: &StructType) -> String {
+    fields
+        .fields()
+        .map(|field| format!("{}:{}", field.name(), type_text(field.data_type())))
+        .collect::<Vec<_>>()
+        .join(",")
+}
+
+fn type_name(data_type: &DataType) -> Option<ColumnTypeName> {
+    Some(match data_type {
+        DataType::Primitive(primitive) => match primitive {
+            PrimitiveType::Boolean => ColumnTypeName::Boolean,
+            PrimitiveType::Byte => ColumnTypeName::Byte,
+            PrimitiveType::Short => ColumnTypeName::Short,
+            PrimitiveType::Integer => ColumnTypeName::Int,
+            PrimitiveType::Long => ColumnTypeName::Long,
+            PrimitiveType::Float => ColumnTypeName::Float,
+            PrimitiveType::Double => ColumnTypeName::Double,
+            PrimitiveType::String => ColumnTypeName::String,
+            PrimitiveType::Binary => ColumnTypeName::Binary,
+            PrimitiveType::Date => ColumnTypeName::Date,
+            PrimitiveType::Timestamp | PrimitiveType::TimestampNanos => ColumnTypeName::Timestamp,
+            PrimitiveType::TimestampNtz | PrimitiveType::TimestampNanosNtz => {
+                ColumnTypeName::TimestampNtz
+            }
+            PrimitiveType::Decimal(_) => ColumnTypeName::Decimal,
+        },
+        DataType::Array(_) => ColumnTypeName::Array,
+        DataType::Map(_) => ColumnTypeName::Map,
+        DataType::Struct(_) => ColumnTypeName::Struct,
+        DataType::Variant(_) => return None,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use deltalake_core::DeltaTableBuilder;
+    use deltalake_core::kernel::{ArrayType, StructField};
+    use httpmock::prelude::*;
+
+    use super::*;
+    use crate::UnityCatalogBuilder;
+    use crate::client::ClientOptions;
+    use crate::models::tests::GET_TABLE_RESPONSE;
+
+    #[test]
+    fn test_type_text() {
+        let nested = StructType::try_new(vec![
+            StructField::new("a", DataType::INTEGER, true),
+            StructField::new("b", ArrayType::new(DataType::STRING, true), true),
+        ])
+        .unwrap();
+        assert_eq!(type_text(&DataType::LONG), "bigint");
+        assert_eq!(
+            type_text(&DataType::decimal(10, 2).unwrap()),
+            "decimal(10,2)"
+        );
+        assert_eq!(type_text(&nested.into()), "struct<a:int,b:array<string>>");
+    }
+
+    #[tokio::test]
+    async fn test_register_and_update_table() {
+        let server = MockServer::start_async().await;
+        let create = server
+            .mock_async(|when, then| {
+                when.path("/api/2.1/unity-catalog/tables")
+                    .method("POST")
+                    .body_includes(r#""catalog_name":"catalog","schema_name":"schema""#)
+                    .body_includes(r#""table_type":"EXTERNAL","data_source_format":"DELTA""#)
+                    .body_includes(r#""storage_location":"memory:///""#);
+                then.body(GET_TABLE_RESPONSE);
+            })
+            .await;
+        let update = server
+            .mock_async(|when, then| {
+                when.path("/api/2.1/unity-catalog/tables/catalog.schema.table")
+                    .method("PATCH")
+                    .body_includes(r#""name":"added""#);
+                then.body(GET_TABLE_RESPONSE);
+            })
+            .await;
+
+        let catalog = UnityCatalogBuilder::builder()
+            .workspace_url(server.url(""))
+            .bearer_token("bearer_token")
+            .client_options(ClientOptions::builder().allow_http(true).build())
+            .build()
+            .build()
+            .unwrap();
+        let hook = Arc::new(UnityCatalogCommitHook::new(
+            Arc::new(catalog),
+            "catalog",
+            "schema",
+            "table",
+        ));
+
+        let table = DeltaTableBuilder::from_url(Url::parse("memory:///").unwrap())
+            .unwrap()
+            .with_commit_hook(hook)
+            .build()
+            .unwrap()
+            .create()
+            .with_column("id", DataType::LONG, false, None)
+            .await
+            .unwrap();
+        create.assert_async().await;
+
+        table
+            .add_columns()
+            .with_fields([StructField::new("added", DataType::STRING, true)])
+            .await
+            .unwrap();
+        update.assert_async().await;
+    }
+}
@@ -9,8 +9,9 @@ compile_error!(
 
 use dashmap::DashMap;
 use deltalake_core::logstore::{
-    LogStore, LogStoreFactory, StorageConfig, default_logstore, logstore_factories,
-    object_store::RetryConfig,
+    CredentialRefreshingStore, LogStore, LogStoreFactory, StorageConfig, default_logstore,
+    logstore_factories,
+    object_store::{ObjectStoreScheme, RetryConfig, prefix::PrefixStore},
 };
 use reqwest::Url;
 use reqwest::header::{AUTHORIZATION, HeaderValue, InvalidHeaderValue};
@@ -26,16 +27,21 @@ use crate::credential::{
     AzureCliCredential, ClientSecretOAuthProvider, CredentialProvider, TokenCredential,
     WorkspaceOAuthProvider,
 };
+use crate::hooks::column_infos;
 use crate::models::{
-    ErrorResponse, GetSchemaResponse, GetTableResponse, ListCatalogsResponse, ListSchemasResponse,
-    ListTableSummariesResponse, Table, TableTempCredentialsResponse, TableType,
-    TemporaryTableCredentialsRequest, TokenErrorResponse,
+    CreateTableRequest, DataSourceFormat, ErrorResponse, GetSchemaResponse, GetTableResponse,
+    ListCatalogsResponse, ListSchemasResponse, ListTableSummariesResponse, Table,
+    TableTempCredentialsResponse, TableType, TemporaryTableCredentials,
+    TemporaryTableCredentialsRequest, TokenErrorResponse, UpdateTableRequest,
+    register_storage_handlers,
 };
+use crate::storage::UnityCatalogCredentialProvider;
 
 use deltalake_core::data_catalog::DataCatalogResult;
+use deltalake_core::kernel::Metadata;
 use deltalake_core::{
-    DataCatalog, DataCatalogError, DeltaResult, DeltaTableBuilder, DeltaTableError,
-    ObjectStoreError, Path, ensure_table_uri,
+    DataCatalog, DataCatalogError, DeltaResult, DeltaTableError, ObjectStoreError, Path,
+    ensure_table_uri,
 };
 
 use crate::client::retry::*;
@@ -48,8 +54,10 @@ pub mod credential;
 const STORE_NAME: &str = "UnityCatalogObjectStore";
 #[cfg(feature = "datafusion")]
 pub mod datafusion;
+pub mod hooks;
 pub mod models;
 pub mod prelude;
+pub mod storage;
 
 /// Possible errors from the unity-catalog/tables API call
 #[derive(thiserror::Error, Debug)]
@@ -533,66 +541,32 @@ impl UnityCatalogBuilder {
         table_uri: &str,
         storage_options: Option<&HashMap<String, String>>,
     ) -> Result<(String, HashMap<String, String>), UnityCatalogError> {
-        let uri_parts: Vec<&str> = table_uri[5..].split('.').collect();
-        if uri_parts.len() != 3 {
-            return Err(UnityCatalogError::InvalidTableURI {
-                table_uri: table_uri.to_string(),
-            });
-        }
-
-        let catalog_id = uri_parts[0];
-        let database_name = uri_parts[1];
-        let table_name = uri_parts[2];
-
-        let unity_catalog = if let Some(options) = storage_options {
-            let mut builder = UnityCatalogBuilder::from_env();
-            builder =
-                builder.try_with_options(options.iter().map(|(k, v)| (k.as_str(), v.as_str())))?;
-            builder.build()?
-        } else {
-            UnityCatalogBuilder::from_env().build()?
-        };
+        let (catalog_id, database_name, table_name) = parse_table_uri(table_uri)?;
+        let unity_catalog = Self::build_from_options(storage_options)?;
 
         let storage_location = unity_catalog
             .get_table_storage_location(Some(catalog_id.to_string()), database_name, table_name)
             .await?;
-        // Attempt to get read/write permissions to begin with.
-        let temp_creds_res = unity_catalog
-            .get_temp_table_credentials_with_permission(
-                catalog_id,
-                database_name,
-                table_name,
-                "READ_WRITE",
-            )
-            .await?;
-        let credentials = match temp_creds_res {
-            TableTempCredentialsResponse::Success(temp_creds) => temp_creds
-                .get_credentials()
-                .ok_or_else(|| UnityCatalogError::MissingCredential)?,
-            TableTempCredentialsResponse::Error(rw_error) => {
-                // If that fails attempt to get just read permissions.
-                match unity_catalog
-                    .get_temp_table_credentials(catalog_id, database_name, table_name)
-                    .await?
-                {
-                    TableTempCredentialsResponse::Success(temp_creds) => temp_creds
-                        .get_credentials()
-                        .ok_or_else(|| UnityCatalogError::MissingCredential)?,
-                    TableTempCredentialsResponse::Error(read_error) => {
-                        return Err(UnityCatalogError::TemporaryCredentialsFetchFailure {
-                            error_code: read_error.error_code,
-                            message: format!(
-                                "READ_WRITE failed: {}. READ failed: {}",
-                                rw_error.message, read_error.message
-                            ),
-                        });
-                    }
-                }
-            }
-        };
+        let credentials = unity_catalog
+            .get_table_credentials(catalog_id, database_name, table_name)
+            .await?
+            .get_credentials()
+            .ok_or(UnityCatalogError::MissingCredential)?;
         Ok((storage_location, credentials))
     }
 
+    /// Build a [`UnityCatalog`] from the environment, overridden by `storage_options`.
+    fn build_from_options(
+        storage_options: Option<&HashMap<String, String>>,
+    ) -> Result<UnityCatalog, UnityCatalogError> {
+        let mut builder = UnityCatalogBuilder::from_env();
+        if let Some(options) = storage_options {
+            builder =
+                builder.try_with_options(options.iter().map(|(k, v)| (k.as_str(), v.as_str())))?;
+        }
+        Ok(builder.build()?)
+    }
+
     fn get_credential_provider(&mut self) -> Option<CredentialProvider> {
         if let Some(token_credential) = self.token_credential.take() {
             return Some(CredentialProvider::TokenCredential(
@@ -873,6 +847,104 @@ impl UnityCatalog {
         Ok(table)
     }
 
+    /// Creates a new table in the specified catalog and schema.
+    ///
+    /// The caller must be a metastore admin, or have the USE_CATALOG privilege on the parent
+    /// catalog and the USE_SCHEMA and CREATE_TABLE privileges on the parent schema. External
+    /// tables additionally require the CREATE_EXTERNAL_TABLE privilege on the external location.
+    #[tracing::instrument(skip_all)]
+    pub async fn create_table(
+        &self,
+        request: &CreateTableRequest,
+    ) -> Result<GetTableResponse, UnityCatalogError> {
+        let full_path = format!(
+            "{}.{}.{}",
+            request.catalog_name, request.schema_name, request.name
+        );
+        tracing::event!(tracing::Level::DEBUG, "Creating table: {}", full_path);
+        let token = self.get_credential().await?;
+        let resp = self
+            .client
+            .post(format!("{}/tables", self.catalog_url()))
+            .header(AUTHORIZATION, token)
+            .json(request)
+            .send()
+            .await?;
+        self.table_cache.remove(&full_path);
+        Ok(resp.json().await?)
+    }
+
+    /// Updates the columns and comment of a table.
+    ///
+    /// The caller must be the owner of the table or have the MODIFY privilege on it, as well as
+    /// the USE_CATALOG privilege on the parent catalog and the USE_SCHEMA privilege on the parent
+    /// schema.
+    #[tracing::instrument(skip_all)]
+    pub async fn update_table<S>(
+        &self,
+        catalog_id: S,
+        database_name: S,
+        table_name: S,
+        request: &UpdateTableRequest,
+    ) -> Result<GetTableResponse, UnityCatalogError>
+    where
+        S: Into<String> + Debug,
+    {
+        let full_path = format!(
+            "{}.{}.{}",
+            catalog_id.into(),
+            database_name.into(),
+            table_name.into()
+        );
+        tracing::event!(tracing::Level::DEBUG, "Updating table: {}", full_path);
+        let token = self.get_credential().await?;
+        let resp = self
+            .client
+            .patch(format!("{}/tables/{}", self.catalog_url(), full_path))
+            .header(AUTHORIZATION, token)
+            .json(request)
+            .send()
+            .await?;
+        self.table_cache.remove(&full_path);
+        Ok(resp.json().await?)
+    }
+
+    /// Deletes a table from the specified catalog and schema.
+    ///
+    /// The data of external tables is kept. The caller must be the owner of the parent catalog,
+    /// or the owner of the table and have the USE_CATALOG privilege on the parent catalog and
+    /// the USE_SCHEMA privilege on the parent schema.
+    #[tracing::instrument(skip_all)]
+    pub async fn delete_table<S>(
+        &self,
+        catalog_id: S,
+        database_name: S,
+        table_name: S,
+    ) -> Result<(), UnityCatalogError>
+    where
+        S: Into<String> + Debug,
+    {
+        let full_path = format!(
+            "{}.{}.{}",
+            catalog_id.into(),
+            database_name.into(),
+            table_name.into()
+        );
+        tracing::event!(tracing::Level::DEBUG, "Deleting table: {}", full_path);
+        let token = self.get_credential().await?;
+        let resp = self
+            .client
+            .delete(format!("{}/tables/{}", self.catalog_url(), full_path))
+            .header(AUTHORIZATION, token)
+            .send()
+            .await?;
+        self.table_cache.remove(&full_path);
+        if resp.status().is_success() {
+            return Ok(());
+        }
+        Err(resp.json::<ErrorResponse>().await?.into())
+    }
+
     pub async fn get_temp_table_credentials<S>(
         &self,
         catalog_id: S,
@@ -891,6 +963,45 @@ impl UnityCatalog {
         .await
     }
 
+    /// Gets temporary credentials for the storage location of a table.
+    ///
+    /// Credentials allowing reads and writes are requested first, if the caller lacks the
+    /// privileges to modify the table, read-only credentials are requested instead.
+    pub async fn get_table_credentials(
+        &self,
+        catalog_id: &str,
+        database_name: &str,
+        table_name: &str,
+    ) -> Result<TemporaryTableCredentials, UnityCatalogError> {
+        let rw_error = match self
+            .get_temp_table_credentials_with_permission(
+                catalog_id,
+                database_name,
+                table_name,
+                "READ_WRITE",
+            )
+            .await?
+        {
+            TableTempCredentialsResponse::Success(temp_creds) => return Ok(temp_creds),
+            TableTempCredentialsResponse::Error(rw_error) => rw_error,
+        };
+        match self
+            .get_temp_table_credentials(catalog_id, database_name, table_name)
+            .await?
+        {
+            TableTempCredentialsResponse::Success(temp_creds) => Ok(temp_creds),
+            TableTempCredentialsResponse::Error(read_error) => {
+                Err(UnityCatalogError::TemporaryCredentialsFetchFailure {
+                    error_code: read_error.error_code,
+                    message: format!(
+                        "READ_WRITE failed: {}. READ failed: {}",
+                        rw_error.message, read_error.message
+                    ),
+                })
+            }
+        }
+    }
+
     #[tracing::instrument(skip_all)]
     pub async fn get_temp_table_credentials_with_permission<S>(
         &self,
@@ -939,27 +1050,40 @@ impl ObjectStoreFactory for UnityCatalogFactory {
         table_uri: &Url,
         config: &StorageConfig,
     ) -> DeltaResult<(ObjectStoreRef, Path)> {
-        let (table_path, temp_creds) = UnityCatalogBuilder::execute_uc_future(
-            UnityCatalogBuilder::get_uc_location_and_token(table_uri.as_str(), Some(&config.raw)),
-        )??;
-
-        let mut storage_options = config.raw.clone();
-        storage_options.extend(temp_creds);
-
-        // TODO(roeap): we should not have to go through the table here.
-        // ideally we just create the right storage ...
+        let (catalog_id, database_name, table_name) = parse_table_uri(table_uri.as_str())?;
+        let unity_catalog = Arc::new(UnityCatalogBuilder::build_from_options(Some(&config.raw))?);
+        let table_path =
+            UnityCatalogBuilder::execute_uc_future(unity_catalog.get_table_storage_location(
+                Some(catalog_id.to_string()),
+                database_name,
+                table_name,
+            ))??;
         let table_url = ensure_table_uri(&table_path)?;
-        let mut builder = DeltaTableBuilder::from_url(table_url)?;
 
-        if let Some(runtime) = &config.runtime {
-            builder = builder.with_io_runtime(runtime.clone());
-        }
-
-        if !storage_options.is_empty() {
-            builder = builder.with_storage_options(storage_options.clone());
-        }
+        // The store of the storage location is built by its own factory, using the temporary
+        // credentials vended for the table, which are refreshed shortly before they expire.
+        register_storage_handlers();
+        let scheme = Url::parse(&format!("{}://", table_url.scheme()))
+            .map_err(|_| DeltaTableError::InvalidTableLocation(table_path.clone()))?;
+        let factory = object_store_factories()
+            .get(&scheme)
+            .map(|entry| entry.value().clone())
+            .ok_or_else(|| DeltaTableError::InvalidTableLocation(table_path.clone()))?;
+        let provider = Arc::new(UnityCatalogCredentialProvider::new(
+            unity_catalog,
+            catalog_id,
+            database_name,
+            table_name,
+        ));
+        let store =
+            CredentialRefreshingStore::new(factory, table_url.clone(), config.clone(), provider);
+
+        let (_, table_prefix) =
+            ObjectStoreScheme::parse(&table_url).map_err(|e| DeltaTableError::GenericError {
+                source: Box::new(e),
+            })?;
+        let store: ObjectStoreRef = Arc::new(PrefixStore::new(store, table_prefix));
         let prefix = Path::parse(table_uri.path())?;
-        let store = builder.build_storage()?.object_store(None);
 
         Ok((store, prefix))
     }
@@ -982,6 +1106,18 @@ impl LogStoreFactory for UnityCatalogFactory {
     }
 }
 
+/// Split a `uc://catalog.schema.table` URI into its catalog, schema and table name.
+fn parse_table_uri(table_uri: &str) -> Result<(&str, &str, &str), UnityCatalogError> {
+    let invalid = || UnityCatalogError::InvalidTableURI {
+        table_uri: table_uri.to_string(),
+    };
+    let name = table_uri.strip_prefix("uc://").ok_or_else(invalid)?;
+    match name.split('.').collect::<Vec<_>>()[..] {
+        [catalog_id, database_name, table_name] => Ok((catalog_id, database_name, table_name)),
+        _ => Err(invalid()),
+    }
+}
+
 /// Register an [ObjectStoreFactory] for common UnityCatalogFactory [Url] schemes
 pub fn register_handlers(_additional_prefixes: Option<Url>) {
     let factory = Arc::new(UnityCatalogFactory::default());
@@ -1024,6 +1160,119 @@ impl DataCatalog for UnityCatalog {
             }),
         }
     }
+
+    async fn list_catalog_names(&self) -> Result<Vec<String>, UnityCatalogError> {
+        match self.list_catalogs().await? {
+            ListCatalogsResponse::Success { catalogs, .. } => {
+                Ok(catalogs.into_iter().map(|catalog| catalog.name).collect())
+            }
+            ListCatalogsResponse::Error(err) => Err(err.into()),
+        }
+    }
+
+    async fn list_database_names(
+        &self,
+        catalog_id: Option<String>,
+    ) -> Result<Vec<String>, UnityCatalogError> {
+        match self
+            .list_schemas(catalog_id.unwrap_or("main".into()))
+            .await?
+        {
+            ListSchemasResponse::Success { schemas } => {
+                Ok(schemas.into_iter().map(|schema| schema.name).collect())
+            }
+            ListSchemasResponse::Error(err) => Err(err.into()),
+        }
+    }
+
+    async fn list_table_names(
+        &self,
+        catalog_id: Option<String>,
+        database_name: &str,
+    ) -> Result<Vec<String>, UnityCatalogError> {
+        match self
+            .list_table_summaries(
+                catalog_id.unwrap_or("main".into()),
+                database_name.to_string(),
+            )
+            .await?
+        {
+            // the summaries are filtered by a pattern, so only keep exact matches of the schema
+            ListTableSummariesResponse::Success { tables, .. } => Ok(tables
+                .into_iter()
+                .filter_map(|table| {
+                    let mut parts = table.full_name.splitn(3, '.').skip(1);
+                    match (parts.next(), parts.next()) {
+                        (Some(schema), Some(name)) if schema == database_name => {
+                            Some(name.to_string())
+                        }
+                        _ => None,
+                    }
+                })
+                .collect()),
+            ListTableSummariesResponse::Error(err) => Err(err.into()),
+        }
+    }
+
+    async fn register_table(
+        &self,
+        catalog_id: Option<String>,
+        database_name: &str,
+        table_name: &str,
+        location: &str,
+        metadata: &Metadata,
+    ) -> Result<(), UnityCatalogError> {
+        let request = CreateTableRequest {
+            name: table_name.to_string(),
+            catalog_name: catalog_id.unwrap_or("main".into()),
+            schema_name: database_name.to_string(),
+            table_type: TableType::External,
+            data_source_format: DataSourceFormat::Delta,
+            columns: column_infos(metadata).map_err(|err| UnityCatalogError::Generic {
+                source: Box::new(err),
+            })?,
+            storage_location: location.to_string(),
+            comment: metadata.description().map(ToString::to_string),
+            properties: metadata.configuration().clone(),
+        };
+        match self.create_table(&request).await? {
+            GetTableResponse::Success(_) => Ok(()),
+            GetTableResponse::Error(err) => Err(err.into()),
+        }
+    }
+
+    async fn drop_table(
+        &self,
+        catalog_id: Option<String>,
+        database_name: &str,
+        table_name: &str,
+    ) -> Result<(), UnityCatalogError> {
+        self.delete_table(
+            catalog_id.unwrap_or("main".into()),
+            database_name.to_string(),
+            table_name.to_string(),
+        )
+        .await
+    }
+
+    async fn get_table_properties(
+        &self,
+        catalog_id: Option<String>,
+        database_name: &str,
+        table_name: &str,
+    ) -> Result<HashMap<String, String>, UnityCatalogError> {
+        match self
+            .get_table(
+                catalog_id.unwrap_or("main".into()),
+                database_name.to_string(),
+                table_name.to_string(),
+            )
+            .await?
+        {
+            GetTableResponse::Success(table) => Ok(table.properties),
+            GetTableResponse::Error(err) => Err(err.into()),
+        }
+    }
 }
 
 impl std::fmt::Debug for UnityCatalog {
@@ -1038,7 +1287,9 @@ mod tests {
     use crate::client::ClientOptions;
     use crate::client::token::TemporaryToken;
     use crate::credential::TokenCredential;
-    use crate::models::tests::{GET_SCHEMA_RESPONSE, GET_TABLE_RESPONSE, LIST_SCHEMAS_RESPONSE};
+    use crate::models::tests::{
+        GET_SCHEMA_RESPONSE, GET_TABLE_RESPONSE, LIST_SCHEMAS_RESPONSE, LIST_TABLES,
+    };
     use crate::models::*;
     use deltalake_core::DataCatalog;
     use httpmock::prelude::*;
@@ -1113,6 +1364,77 @@ mod tests {
         assert!(storage_location.eq_ignore_ascii_case("string"));
     }
 
+    #[tokio::test]
+    async fn test_browse_catalog() {
+        let server = MockServer::start_async().await;
+        let client = UnityCatalogBuilder::builder()
+            .workspace_url(server.url(""))
+            .bearer_token("bearer_token")
+            .client_options(ClientOptions::builder().allow_http(true).build())
+            .build()
+            .build()
+            .unwrap();
+
+        server
+            .mock_async(|when, then| {
+                when.path("/api/2.1/unity-catalog/schemas")
+                    .method("GET")
+                    .query_param("catalog_name", "catalog");
+                then.body(LIST_SCHEMAS_RESPONSE);
+            })
+            .await;
+        server
+            .mock_async(|when, then| {
+                when.path("/api/2.1/unity-catalog/table-summaries")
+                    .method("GET")
+                    .query_param("catalog_name", "catalog")
+                    .query_param("schema_name_pattern", "schema");
+                then.body(LIST_TABLES);
+            })
+            .await;
+        server
+            .mock_async(|when, then| {
+                when.path("/api/2.1/unity-catalog/tables/catalog.schema.table_name")
+                    .method("GET");
+                then.body(GET_TABLE_RESPONSE);
+            })
+            .await;
+        let delete = server
+            .mock_async(|when, then| {
+                when.path("/api/2.1/unity-catalog/tables/catalog.schema.table_name")
+                    .method("DELETE");
+                then.body("{}");
+            })
+            .await;
+
+        let catalog_id = Some("catalog".to_string());
+        assert_eq!(
+            client
+                .list_database_names(catalog_id.clone())
+                .await
+                .unwrap(),
+            vec!["string"]
+        );
+        assert_eq!(
+            client
+                .list_table_names(catalog_id.clone(), "schema")
+                .await
+                .unwrap(),
+            vec!["table_name"]
+        );
+        let properties = client
+            .get_table_properties(catalog_id.clone(), "schema", "table_name")
+            .await
+            .unwrap();
+        assert_eq!(properties["property1"], "string");
+
+        client
+            .drop_table(catalog_id, "schema", "table_name")
+            .await
+            .unwrap();
+        delete.assert_async().await;
+    }
+
     #[test]
     fn test_unitycatalogbuilder_with_storage_options() {
         let mut storage_options = HashMap::new();
@@ -266,7 +266,7 @@ pub struct Schema {
     pub metastore_id: String,
 }
 
-#[derive(Deserialize, Default, Debug)]
+#[derive(Serialize, Deserialize, Default, Debug)]
 #[serde(rename_all = "SCREAMING_SNAKE_CASE")]
 #[allow(missing_docs)]
 /// Possible data source formats for unity tables
@@ -299,7 +299,7 @@ pub enum DataSourceFormat {
     VectorIndexFormat,
 }
 
-#[derive(Deserialize, Default, Debug, PartialEq, Clone)]
+#[derive(Serialize, Deserialize, Default, Debug, PartialEq, Clone)]
 #[serde(rename_all = "SCREAMING_SNAKE_CASE")]
 #[allow(missing_docs)]
 /// Possible data source formats for unity tables
@@ -448,6 +448,16 @@ static INIT_AZURE: Once = Once::new();
 #[cfg(feature = "gcp")]
 static INIT_GCP: Once = Once::new();
 
+/// Register the object store factories of the enabled cloud providers.
+pub(crate) fn register_storage_handlers() {
+    #[cfg(any(feature = "aws", feature = "r2"))]
+    INIT_AWS.call_once(|| deltalake_aws::register_handlers(None));
+    #[cfg(feature = "azure")]
+    INIT_AZURE.call_once(|| deltalake_azure::register_handlers(None));
+    #[cfg(feature = "gcp")]
+    INIT_GCP.call_once(|| deltalake_gcp::register_handlers(None));
+}
+
 impl TemporaryTableCredentials {
     #[cfg(feature = "aws")]
     pub fn get_aws_credentials(&self) -> Option<HashMap<String, String>> {
@@ -601,6 +611,38 @@ impl TemporaryTableCredentialsRequest {
     }
 }
 
+/// Request to create a table within a schema
+#[derive(Serialize, Debug, Clone)]
+pub struct CreateTableRequest {
+    pub name: String,
+    /// Name of parent catalog.
+    pub catalog_name: String,
+    /// Name of parent schema relative to its parent catalog.
+    pub schema_name: String,
+    pub table_type: TableType,
+    pub data_source_format: DataSourceFormat,
+    /// The array of __ColumnInfo__ definitions of the table's columns.
+    pub columns: Vec<ColumnInfo>,
+    /// Storage root URL for the table.
+    pub storage_location: String,
+    /// User-provided free-form text description.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub comment: Option<String>,
+    /// A map of key-value properties attached to the securable.
+    #[serde(skip_serializing_if = "HashMap::is_empty")]
+    pub properties: HashMap<String, String>,
+}
+
+/// Request to update the definition of a table
+#[derive(Serialize, Debug, Clone)]
+pub struct UpdateTableRequest {
+    /// The array of __ColumnInfo__ definitions of the table's columns.
+    pub columns: Vec<ColumnInfo>,
+    /// User-provided free-form text description.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub comment: Option<String>,
+}
+
 #[cfg(test)]
 pub(crate) mod tests {
     use super::*;
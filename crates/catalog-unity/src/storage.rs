@@ -0,0 +1,133 @@
+//! Temporary storage credentials vended by Unity Catalog.
+//!
+//! Unity Catalog hands out short-lived credentials for the storage location of a table. The
+//! [`UnityCatalogCredentialProvider`] requests them for a single table and is used by the
+//! `uc://` object store factory to refresh the credentials before they expire.
+use std::fmt;
+use std::sync::Arc;
+
+use deltalake_core::DeltaResult;
+use deltalake_core::logstore::{StorageCredentialProvider, StorageCredentials};
+use reqwest::Url;
+
+use crate::{UnityCatalog, UnityCatalogError};
+
+/// A [`StorageCredentialProvider`] issuing the temporary credentials Unity Catalog vends for a
+/// table.
+pub struct UnityCatalogCredentialProvider {
+    catalog: Arc<UnityCatalog>,
+    catalog_name: String,
+    schema_name: String,
+    table_name: String,
+}
+
+impl UnityCatalogCredentialProvider {
+    /// Create a provider for the table `catalog_name.schema_name.table_name`
+    pub fn new(
+        catalog: Arc<UnityCatalog>,
+        catalog_name: impl Into<String>,
+        schema_name: impl Into<String>,
+        table_name: impl Into<String>,
+    ) -> Self {
+        Self {
+            catalog,
+            catalog_name: catalog_name.into(),
+            schema_name: schema_name.into(),
+            table_name: table_name.into(),
+        }
+    }
+}
+
+impl fmt::Debug for UnityCatalogCredentialProvider {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "UnityCatalogCredentialProvider({}.{}.{})",
+            self.catalog_name, self.schema_name, self.table_name
+        )
+    }
+}
+
+#[async_trait::async_trait]
+impl StorageCredentialProvider for UnityCatalogCredentialProvider {
+    async fn get_credentials(&self, location: &Url) -> DeltaResult<StorageCredentials> {
+        tracing::debug!(
+            "Requesting temporary credentials for {}.{}.{} at {location}",
+            self.catalog_name,
+            self.schema_name,
+            self.table_name
+        );
+        let credentials = self
+            .catalog
+            .get_table_credentials(
+                self.catalog_name.as_str(),
+                self.schema_name.as_str(),
+                self.table_name.as_str(),
+            )
+            .await?;
+        let expires_at = credentials.expiration_time;
+        let options = credentials
+            .get_credentials()
+            .ok_or(UnityCatalogError::MissingCredential)?;
+        Ok(StorageCredentials {
+            options,
+            expires_at: Some(expires_at),
+        })
+    }
+}
+
+#[cfg(all(test, feature = "aws"))]
+mod tests {
+    use deltalake_aws::constants::{AWS_ACCESS_KEY_ID, AWS_SESSION_TOKEN};
+    use httpmock::prelude::*;
+
+    use super::*;
+    use crate::UnityCatalogBuilder;
+    use crate::client::ClientOptions;
+    use crate::models::tests::GET_TABLE_RESPONSE;
+
+    #[tokio::test]
+    async fn test_vended_credentials() {
+        let server = MockServer::start_async().await;
+        server
+            .mock_async(|when, then| {
+                when.path("/api/2.1/unity-catalog/tables/catalog.schema.table")
+                    .method("GET");
+                then.body(GET_TABLE_RESPONSE);
+            })
+            .await;
+        server
+            .mock_async(|when, then| {
+                when.path("/api/2.1/unity-catalog/temporary-table-credentials")
+                    .method("POST");
+                then.body(
+                    r#"{
+                        "aws_temp_credentials": {
+                            "access_key_id": "key",
+                            "secret_access_key": "secret",
+                            "session_token": "token"
+                        },
+                        "expiration_time": 1767225600000,
+                        "url": "s3://bucket/table"
+                    }"#,
+                );
+            })
+            .await;
+
+        let catalog = UnityCatalogBuilder::builder()
+            .workspace_url(server.url(""))
+            .bearer_token("bearer_token")
+            .client_options(ClientOptions::builder().allow_http(true).build())
+            .build()
+            .build()
+            .unwrap();
+        let provider =
+            UnityCatalogCredentialProvider::new(Arc::new(catalog), "catalog", "schema", "table");
+
+        let location = Url::parse("s3://bucket/table").unwrap();
+        let issued = provider.get_credentials(&location).await.unwrap();
+        assert_eq!(issued.options[AWS_ACCESS_KEY_ID], "key");
+        assert_eq!(issued.options[AWS_SESSION_TOKEN], "token");
+        assert_eq!(issued.expires_at.unwrap().timestamp_millis(), 1767225600000);
+    }
+}
@@ -1,7 +1,10 @@
 //! Catalog abstraction for Delta Table
 
+use std::collections::HashMap;
 use std::fmt::Debug;
 
+use crate::kernel::Metadata;
+
 #[cfg(feature = "datafusion")]
 pub mod storage;
 
@@ -43,13 +46,24 @@ pub enum DataCatalogError {
         /// The underlying transport or service error that caused the request to fail.
         source: Box<dyn std::error::Error + Send + Sync + 'static>,
     },
+
+    /// The catalog does not support the operation
+    #[error("The data catalog does not support {operation}")]
+    Unsupported {
+        /// The unsupported operation
+        operation: &'static str,
+    },
 }
 
 /// Abstractions for data catalog for the Delta table. To add support for new cloud, simply implement this trait.
+///
+/// Only resolving the storage location of a table is required, the operations for browsing and
+/// managing the catalog return [`DataCatalogError::Unsupported`] unless the catalog implements
+/// them.
 #[async_trait::async_trait]
 pub trait DataCatalog: Send + Sync + Debug {
     /// Error type returned by catalog operations.
-    type Error;
+    type Error: From<DataCatalogError>;
 
     /// Get the table storage location from the Data Catalog
     async fn get_table_storage_location(
@@ -58,4 +72,120 @@ pub trait DataCatalog: Send + Sync + Debug {
         database_name: &str,
         table_name: &str,
     ) -> Result<String, Self::Error>;
+
+    /// List the names of the catalogs, for catalogs with more than one level of namespaces
+    async fn list_catalog_names(&self) -> Result<Vec<String>, Self::Error> {
+        Err(DataCatalogError::Unsupported {
+            operation: "listing catalogs",
+        }
+        .into())
+    }
+
+    /// List the names of the databases, also called schemas, within a catalog
+    async fn list_database_names(
+        &self,
+        _catalog_id: Option<String>,
+    ) -> Result<Vec<String>, Self::Error> {
+        Err(DataCatalogError::Unsupported {
+            operation: "listing databases",
+        }
+        .into())
+    }
+
+    /// List the names of the tables within a database
+    async fn list_table_names(
+        &self,
+        _catalog_id: Option<String>,
+        _database_name: &str,
+    ) -> Result<Vec<String>, Self::Error> {
+        Err(DataCatalogError::Unsupported {
+            operation: "listing tables",
+        }
+        .into())
+    }
+
+    /// Create an entry for the Delta table at `location`, described by its `metadata`
+    async fn register_table(
+        &self,
+        _catalog_id: Option<String>,
+        _database_name: &str,
+        _table_name: &str,
+        _location: &str,
+        _metadata: &Metadata,
+    ) -> Result<(), Self::Error> {
+        Err(DataCatalogError::Unsupported {
+            operation: "registering tables",
+        }
+        .into())
+    }
+
+    /// Drop the entry of a table, the data of the table is left untouched
+    async fn drop_table(
+        &self,
+        _catalog_id: Option<String>,
+        _database_name: &str,
+        _table_name: &str,
+    ) -> Result<(), Self::Error> {
+        Err(DataCatalogError::Unsupported {
+            operation: "dropping tables",
+        }
+        .into())
+    }
+
+    /// Get the properties the catalog stores for a table
+    async fn get_table_properties(
+        &self,
+        _catalog_id: Option<String>,
+        _database_name: &str,
+        _table_name: &str,
+    ) -> Result<HashMap<String, String>, Self::Error> {
+        Err(DataCatalogError::Unsupported {
+            operation: "table properties",
+        }
+        .into())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Debug)]
+    struct LocationOnlyCatalog;
+
+    #[async_trait::async_trait]
+    impl DataCatalog for LocationOnlyCatalog {
+        type Error = DataCatalogError;
+
+        async fn get_table_storage_location(
+            &self,
+            _catalog_id: Option<String>,
+            database_name: &str,
+            table_name: &str,
+        ) -> Result<String, Self::Error> {
+            Ok(format!("memory:///{database_name}/{table_name}"))
+        }
+    }
+
+    #[tokio::test]
+    async fn test_unsupported_operations() {
+        let catalog = LocationOnlyCatalog;
+        assert_eq!(
+            catalog
+                .get_table_storage_location(None, "database", "table")
+                .await
+                .unwrap(),
+            "memory:///database/table"
+        );
+        assert!(matches!(
+            catalog.list_database_names(None).await,
+            Err(DataCatalogError::Unsupported {
+                operation: "listing databases"
+            })
+        ));
+        assert!(matches!(
+            catalog.drop_table(None, "database", "table").await,
+            Err(DataCatalogError::Unsupported { .. })
+        ));
+    }
 }
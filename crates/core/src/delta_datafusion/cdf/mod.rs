@@ -5,12 +5,16 @@ use std::sync::LazyLock;
 use arrow_schema::{DataType, Field, TimeUnit};
 
 pub(crate) use self::scan_utils::*;
+pub use self::table_function::{TABLE_CHANGES_FUNCTION_NAME, TableChangesFunction};
 use crate::DeltaResult;
 use crate::kernel::{Add, AddCDCFile, Remove, Version};
 
 /// Scan-related types and helpers for reading Change Data Feed (CDF) batches.
 pub mod scan;
 mod scan_utils;
+/// Streaming source reading the changes of a Delta table as new commits arrive.
+pub mod stream;
+mod table_function;
 
 /// Change type column name
 pub const CHANGE_TYPE_COL: &str = "_change_type";
@@ -102,17 +106,11 @@ impl FileAction for AddCDCFile {
 
 impl FileAction for Remove {
     fn partition_values(&self) -> DeltaResult<&HashMap<String, Option<String>>> {
-        // If extended_file_metadata is true, it should be required to have this filled in
-        if self.extended_file_metadata.unwrap_or_default() {
-            Ok(self.partition_values.as_ref().unwrap())
-        } else {
-            match self.partition_values {
-                Some(ref part_map) => Ok(part_map),
-                _ => Err(crate::DeltaTableError::MetadataError(
-                    "Remove action is missing required field: 'partition_values'".to_string(),
-                )),
-            }
-        }
+        self.partition_values.as_ref().ok_or_else(|| {
+            crate::DeltaTableError::MetadataError(
+                "Remove action is missing required field: 'partition_values'".to_string(),
+            )
+        })
     }
 
     fn path(&self) -> String {
@@ -120,16 +118,10 @@ impl FileAction for Remove {
     }
 
     fn size(&self) -> DeltaResult<usize> {
-        // If extended_file_metadata is true, it should be required to have this filled in
-        if self.extended_file_metadata.unwrap_or_default() {
-            Ok(self.size.unwrap() as usize)
-        } else {
-            match self.size {
-                Some(size) => Ok(size as usize),
-                _ => Err(crate::DeltaTableError::MetadataError(
-                    "Remove action is missing required field: 'size'".to_string(),
-                )),
-            }
-        }
+        self.size.map(|size| size as usize).ok_or_else(|| {
+            crate::DeltaTableError::MetadataError(
+                "Remove action is missing required field: 'size'".to_string(),
+            )
+        })
     }
 }
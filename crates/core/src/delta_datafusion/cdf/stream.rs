@@ -0,0 +1,408 @@
+//! Streaming source continuously reading the changes of a Delta table as commits arrive.
+//!
+//! # Example
+//! ```rust ignore
+//! let table = open_table(Url::from_directory_path("/abs/path/to/table").unwrap())?;
+//! let source = table
+//!     .stream_cdf(3)?
+//!     .with_max_versions_per_batch(10)
+//!     .with_poll_interval(Duration::from_secs(5));
+//! let offset = source.offset();
+//!
+//! let ctx = SessionContext::new();
+//! let mut stream = execute_stream(Arc::new(source), ctx.task_ctx())?;
+//! while let Some(batch) = stream.next().await {
+//!     // process the batch, then persist `offset.version()` to resume from it later
+//! }
+//! ```
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use arrow_schema::{Schema, SchemaRef};
+use datafusion::common::{Result as DataFusionResult, plan_err};
+use datafusion::execution::{SendableRecordBatchStream, SessionStateBuilder, TaskContext};
+use datafusion::physical_expr::EquivalenceProperties;
+use datafusion::physical_plan::execution_plan::{Boundedness, EmissionType};
+use datafusion::physical_plan::stream::RecordBatchStreamAdapter;
+use datafusion::physical_plan::{
+    DisplayAs, DisplayFormatType, ExecutionPlan, Partitioning, PlanProperties, execute_stream,
+};
+use futures::StreamExt as _;
+use tracing::log;
+
+use super::ADD_PARTITION_SCHEMA;
+use crate::delta_datafusion::DataFusionMixins as _;
+use crate::kernel::{EagerSnapshot, Version};
+use crate::logstore::LogStoreRef;
+use crate::operations::load_cdf::CdfLoadBuilder;
+use crate::table::config::TablePropertiesExt as _;
+use crate::{DeltaResult, DeltaTableError};
+
+const DEFAULT_POLL_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Offset of a [`DeltaCdfStreamExec`], shared by all clones of the handle.
+///
+/// The offset is the last table version whose changes were completely emitted by the
+/// stream. It only advances once all batches of a micro-batch were read, so a stream
+/// resumed at the version after the offset neither skips nor repeats any commit.
+#[derive(Debug, Clone, Default)]
+pub struct CdfStreamOffset {
+    version: Arc<Mutex<Option<Version>>>,
+}
+
+impl CdfStreamOffset {
+    /// The last version whose changes were completely emitted, if any
+    pub fn version(&self) -> Option<Version> {
+        *self.version.lock().unwrap()
+    }
+
+    fn advance(&self, version: Version) {
+        *self.version.lock().unwrap() = Some(version);
+    }
+}
+
+/// An unbounded [`ExecutionPlan`] reading the changes of a Delta table as new commits arrive.
+///
+/// The table log is polled for new commits, which are read in micro-batches of consecutive
+/// versions. For tables with the change data feed enabled all changes are emitted, otherwise
+/// only the data files appended by each commit are read as inserts. Every row carries the
+/// `_change_type`, `_commit_version` and `_commit_timestamp` columns of the change data feed.
+#[derive(Debug, Clone)]
+pub struct DeltaCdfStreamExec {
+    log_store: LogStoreRef,
+    snapshot: EagerSnapshot,
+    starting_version: Version,
+    max_versions_per_batch: Option<u64>,
+    poll_interval: Duration,
+    appends_only: bool,
+    offset: CdfStreamOffset,
+    properties: Arc<PlanProperties>,
+}
+
+impl DeltaCdfStreamExec {
+    /// Stream the changes of the table starting at `starting_version` (inclusive).
+    ///
+    /// The schema of the stream is derived from `snapshot`.
+    pub fn try_new(
+        log_store: LogStoreRef,
+        snapshot: EagerSnapshot,
+        starting_version: Version,
+    ) -> DeltaResult<Self> {
+        let mut fields = snapshot.input_schema().fields().to_vec();
+        for f in ADD_PARTITION_SCHEMA.clone() {
+            fields.push(f.into());
+        }
+        let schema: SchemaRef = Arc::new(Schema::new(fields));
+        let properties = Arc::new(PlanProperties::new(
+            EquivalenceProperties::new(schema),
+            Partitioning::UnknownPartitioning(1),
+            EmissionType::Incremental,
+            Boundedness::Unbounded {
+                requires_infinite_memory: false,
+            },
+        ));
+        let appends_only = !snapshot.table_properties().enable_change_data_feed();
+        Ok(Self {
+            log_store,
+            snapshot,
+            starting_version,
+            max_versions_per_batch: None,
+            poll_interval: DEFAULT_POLL_INTERVAL,
+            appends_only,
+            offset: CdfStreamOffset::default(),
+            properties,
+        })
+    }
+
+    /// Maximum number of table versions read in a single micro-batch
+    pub fn with_max_versions_per_batch(mut self, max_versions: u64) -> Self {
+        self.max_versions_per_batch = Some(max_versions.max(1));
+        self
+    }
+
+    /// How long to wait before checking the log for new commits again, defaults to one second
+    pub fn with_poll_interval(mut self, poll_interval: Duration) -> Self {
+        self.poll_interval = poll_interval;
+        self
+    }
+
+    /// Handle to the offset of the stream
+    pub fn offset(&self) -> CdfStreamOffset {
+        self.offset.clone()
+    }
+
+    /// The latest version of the table, if there are commits at or after `next_version`
+    async fn latest_version(&self, next_version: Version) -> DeltaResult<Option<Version>> {
+        match self.log_store.get_latest_version(next_version).await {
+            Ok(version) => Ok(Some(version)),
+            Err(DeltaTableError::InvalidVersion(_)) => Ok(None),
+            Err(err) => Err(err),
+        }
+    }
+
+    /// Read the changes of the versions `start..=end`
+    async fn micro_batch(
+        &self,
+        start: Version,
+        end: Version,
+        context: Arc<TaskContext>,
+    ) -> DeltaResult<SendableRecordBatchStream> {
+        let session = SessionStateBuilder::new()
+            .with_config(context.session_config().clone())
+            .with_runtime_env(context.runtime_env())
+            .build();
+        let mut builder = CdfLoadBuilder::new(self.log_store.clone(), Some(self.snapshot.clone()))
+            .with_starting_version(start)
+            .with_ending_version(end);
+        if self.appends_only {
+            builder = builder.with_appends_only();
+        }
+        let plan = builder.build(&session, None).await?;
+        Ok(execute_stream(plan, context)?)
+    }
+}
+
+impl DisplayAs for DeltaCdfStreamExec {
+    fn fmt_as(&self, _t: DisplayFormatType, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(
+            f,
+            "DeltaCdfStreamExec: starting_version={}, appends_only={}",
+            self.starting_version, self.appends_only
+        )
+    }
+}
+
+impl ExecutionPlan for DeltaCdfStreamExec {
+    fn name(&self) -> &'static str {
+        "DeltaCdfStreamExec"
+    }
+
+    fn properties(&self) -> &Arc<PlanProperties> {
+        &self.properties
+    }
+
+    fn children(&self) -> Vec<&Arc<dyn ExecutionPlan>> {
+        vec![]
+    }
+
+    fn with_new_children(
+        self: Arc<Self>,
+        children: Vec<Arc<dyn ExecutionPlan>>,
+    ) -> DataFusionResult<Arc<dyn ExecutionPlan>> {
+        if !children.is_empty() {
+            return plan_err!(
+                "DeltaCdfStreamExec: wrong number of children {}",
+                children.len()
+            );
+        }
+        Ok(self)
+    }
+
+    fn execute(
+        &self,
+        partition: usize,
+        context: Arc<TaskContext>,
+    ) -> DataFusionResult<SendableRecordBatchStream> {
+        if partition != 0 {
+            return plan_err!("DeltaCdfStreamExec: invalid partition {partition}");
+        }
+        let state = CdfStreamState {
+            exec: self.clone(),
+            context,
+            next_version: self
+                .offset
+                .version()
+                .map_or(self.starting_version, |v| v + 1),
+            current: None,
+        };
+        let stream = futures::stream::unfold(Some(state), |state| async move {
+            let mut state = state?;
+            match state.next_batch().await {
+                Ok(batch) => Some((Ok(batch), Some(state))),
+                // Stop the stream after the first error.
+                Err(err) => Some((Err(err), None)),
+            }
+        });
+        Ok(Box::pin(RecordBatchStreamAdapter::new(
+            self.schema(),
+            stream,
+        )))
+    }
+}
+
+struct CdfStreamState {
+    exec: DeltaCdfStreamExec,
+    context: Arc<TaskContext>,
+    next_version: Version,
+    /// Batches of the current micro-batch and the last version it covers
+    current: Option<(SendableRecordBatchStream, Version)>,
+}
+
+impl CdfStreamState {
+    async fn next_batch(&mut self) -> DataFusionResult<arrow_array::RecordBatch> {
+        loop {
+            if let Some((stream, end)) = &mut self.current {
+                match stream.next().await {
+                    Some(batch) => return batch,
+                    None => {
+                        let end = *end;
+                        self.exec.offset.advance(end);
+                        self.next_version = end + 1;
+                        self.current = None;
+                    }
+                }
+                continue;
+            }
+
+            let Some(latest) = self.exec.latest_version(self.next_version).await? else {
+                tokio::time::sleep(self.exec.poll_interval).await;
+                continue;
+            };
+            let end = match self.exec.max_versions_per_batch {
+                Some(max_versions) => latest.min(self.next_version + max_versions - 1),
+                None => latest,
+            };
+            log::debug!(
+                "cdf stream: reading versions {} to {end}",
+                self.next_version
+            );
+            let stream = self
+                .exec
+                .micro_batch(self.next_version, end, Arc::clone(&self.context))
+                .await?;
+            self.current = Some((stream, end));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use arrow_array::{Int32Array, RecordBatch, StringArray};
+    use datafusion::prelude::SessionContext;
+    use delta_kernel::engine::arrow_conversion::TryIntoArrow as _;
+
+    use super::*;
+    use crate::delta_datafusion::cdf::{CHANGE_TYPE_COL, COMMIT_VERSION_COL};
+    use crate::protocol::SaveMode;
+    use crate::test_utils::TestSchemas;
+    use crate::test_utils::TestResult;
+    use crate::{DeltaTable, TableProperty};
+
+    fn simple_batch(ids: &[&str]) -> TestResult<RecordBatch> {
+        let schema: Arc<Schema> = Arc::new(TestSchemas::simple().try_into_arrow()?);
+        Ok(RecordBatch::try_new(
+            schema,
+            vec![
+                Arc::new(StringArray::from(ids.to_vec())),
+                Arc::new(Int32Array::from(vec![1; ids.len()])),
+                Arc::new(StringArray::from(vec!["yes"; ids.len()])),
+            ],
+        )?)
+    }
+
+    /// Read batches from the stream until `num_rows` rows were returned.
+    async fn next_rows(
+        stream: &mut SendableRecordBatchStream,
+        num_rows: usize,
+    ) -> TestResult<Vec<RecordBatch>> {
+        let mut batches = vec![];
+        let mut rows = 0;
+        while rows < num_rows {
+            let batch = stream.next().await.expect("stream ended")?;
+            rows += batch.num_rows();
+            batches.push(batch);
+        }
+        assert_eq!(rows, num_rows);
+        Ok(batches)
+    }
+
+    fn column_values(batches: &[RecordBatch], column: &str) -> Vec<String> {
+        let mut values = batches
+            .iter()
+            .flat_map(|batch| {
+                let array = batch.column_by_name(column).unwrap();
+                arrow::util::display::ArrayFormatter::try_new(array, &Default::default())
+                    .map(|formatter| {
+                        (0..array.len())
+                            .map(|idx| formatter.value(idx).to_string())
+                            .collect::<Vec<_>>()
+                    })
+                    .unwrap()
+            })
+            .collect::<Vec<_>>();
+        values.sort();
+        values
+    }
+
+    #[tokio::test]
+    async fn test_stream_reads_new_commits_in_micro_batches() -> TestResult {
+        let table = DeltaTable::new_in_memory()
+            .create()
+            .with_columns(TestSchemas::simple().fields().cloned())
+            .with_configuration_property(TableProperty::EnableChangeDataFeed, Some("true"))
+            .await?;
+        let table = table.write(vec![simple_batch(&["1", "2"])?]).await?;
+        assert_eq!(table.version(), Some(1));
+
+        let source = table
+            .stream_cdf(1)?
+            .with_max_versions_per_batch(1)
+            .with_poll_interval(Duration::from_millis(10));
+        let offset = source.offset();
+        let ctx = SessionContext::new();
+        let mut stream = source.execute(0, ctx.task_ctx())?;
+
+        let batches = next_rows(&mut stream, 2).await?;
+        assert_eq!(column_values(&batches, "id"), ["1", "2"]);
+        assert_eq!(column_values(&batches, COMMIT_VERSION_COL), ["1", "1"]);
+        assert_eq!(offset.version(), None);
+
+        let table = table
+            .write(vec![simple_batch(&["3"])?])
+            .with_save_mode(SaveMode::Overwrite)
+            .await?;
+        assert_eq!(table.version(), Some(2));
+
+        let batches = next_rows(&mut stream, 3).await?;
+        assert_eq!(offset.version(), Some(1));
+        assert_eq!(column_values(&batches, COMMIT_VERSION_COL), ["2", "2", "2"]);
+        assert_eq!(
+            column_values(&batches, CHANGE_TYPE_COL),
+            ["delete", "delete", "insert"]
+        );
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_stream_reads_appends_without_change_data_feed() -> TestResult {
+        let table = DeltaTable::new_in_memory()
+            .create()
+            .with_columns(TestSchemas::simple().fields().cloned())
+            .await?;
+
+        let source = table
+            .stream_cdf(0)?
+            .with_poll_interval(Duration::from_millis(10));
+        let ctx = SessionContext::new();
+        let mut stream = source.execute(0, ctx.task_ctx())?;
+
+        let table = table.write(vec![simple_batch(&["1", "2"])?]).await?;
+        let batches = next_rows(&mut stream, 2).await?;
+        assert_eq!(
+            column_values(&batches, CHANGE_TYPE_COL),
+            ["insert", "insert"]
+        );
+
+        // Removed files are ignored when the table does not record change data.
+        table
+            .write(vec![simple_batch(&["3"])?])
+            .with_save_mode(SaveMode::Overwrite)
+            .await?;
+        let batches = next_rows(&mut stream, 1).await?;
+        assert_eq!(column_values(&batches, "id"), ["3"]);
+        assert_eq!(column_values(&batches, CHANGE_TYPE_COL), ["insert"]);
+
+        Ok(())
+    }
+}
@@ -0,0 +1,268 @@
+//! The `table_changes` table function, querying the change data feed of a table from SQL.
+//!
+//! ```sql
+//! SELECT * FROM table_changes('s3://bucket/table', 3, 5);
+//! SELECT * FROM table_changes('s3://bucket/table', '2024-01-01T00:00:00Z');
+//! ```
+//!
+//! The first argument is the location of the table, the second the first version (or timestamp)
+//! to read changes from, and the optional third the last version (or timestamp), inclusive.
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use arrow_schema::{DataType, SchemaRef};
+use chrono::{DateTime, Utc};
+use datafusion::catalog::{Session, TableFunctionImpl, TableProvider};
+use datafusion::common::{Result, ScalarValue, plan_datafusion_err, plan_err};
+use datafusion::logical_expr::{Expr, TableProviderFilterPushDown, TableType};
+use datafusion::physical_plan::ExecutionPlan;
+use url::Url;
+
+use super::scan::DeltaCdfTableProvider;
+use crate::delta_datafusion::table_functions::block_on;
+use crate::kernel::Version;
+use crate::operations::load_cdf::CdfLoadBuilder;
+use crate::table::builder::parse_table_uri;
+use crate::{DeltaResult, DeltaTableBuilder};
+
+/// Name the [`TableChangesFunction`] is registered under in sessions created by delta-rs.
+pub const TABLE_CHANGES_FUNCTION_NAME: &str = "table_changes";
+
+/// Bound of the range of commits to read changes from.
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum ChangesBound {
+    Version(Version),
+    Timestamp(DateTime<Utc>),
+}
+
+impl ChangesBound {
+    fn try_from_expr(expr: &Expr, name: &str) -> Result<Self> {
+        let Expr::Literal(value, _) = expr else {
+            return plan_err!("table_changes: {name} must be a literal, got {expr}");
+        };
+        if let Some(timestamp) = value.try_as_str().flatten() {
+            return DateTime::parse_from_rfc3339(timestamp)
+                .map(|timestamp| Self::Timestamp(timestamp.with_timezone(&Utc)))
+                .map_err(|e| {
+                    plan_datafusion_err!(
+                        "table_changes: {name} '{timestamp}' is not an RFC 3339 timestamp: {e}"
+                    )
+                });
+        }
+        match value.cast_to(&DataType::Int64)? {
+            ScalarValue::Int64(Some(version)) if version >= 0 => {
+                Ok(Self::Version(version as Version))
+            }
+            _ => plan_err!("table_changes: {name} must be a version or a timestamp, got {value}"),
+        }
+    }
+}
+
+/// A DataFusion table function returning the change data feed of a Delta table.
+///
+/// Tables are loaded with the storage options the function was created with, so the function
+/// should be registered separately for tables requiring different credentials.
+#[derive(Debug, Default, Clone)]
+pub struct TableChangesFunction {
+    storage_options: HashMap<String, String>,
+}
+
+impl TableChangesFunction {
+    /// Create a function loading tables with the given `storage_options`.
+    pub fn new(storage_options: HashMap<String, String>) -> Self {
+        Self { storage_options }
+    }
+}
+
+impl TableFunctionImpl for TableChangesFunction {
+    fn call(&self, args: &[Expr]) -> Result<Arc<dyn TableProvider>> {
+        let (uri, start, end) = match args {
+            [uri, start] => (uri, start, None),
+            [uri, start, end] => (uri, start, Some(end)),
+            _ => {
+                return plan_err!(
+                    "table_changes expects 2 or 3 arguments (table_uri, start, [end]), got {}",
+                    args.len()
+                );
+            }
+        };
+        let uri = match uri {
+            Expr::Literal(value, _) => value.try_as_str().flatten(),
+            _ => None,
+        }
+        .ok_or_else(|| plan_datafusion_err!("table_changes: table_uri must be a string literal"))?;
+        let start = ChangesBound::try_from_expr(start, "start")?;
+        let end = end
+            .map(|end| ChangesBound::try_from_expr(end, "end"))
+            .transpose()?;
+
+        let table_url = parse_table_uri(uri)?;
+        let storage_options = self.storage_options.clone();
+        // Only the version and schema are resolved while planning. The table is loaded again
+        // when it is scanned, so its object store is bound to the runtime of the session rather
+        // than to the temporary runtime used here.
+        let (version, schema) = block_on({
+            let table_url = table_url.clone();
+            async move {
+                let table = DeltaTableBuilder::from_url(table_url)?
+                    .with_storage_options(storage_options)
+                    .without_files()
+                    .load()
+                    .await?;
+                let version = table.snapshot()?.version();
+                let provider =
+                    DeltaCdfTableProvider::try_new(changes_builder(table.scan_cdf(), start, end))?;
+                Ok::<_, crate::DeltaTableError>((version, provider.schema()))
+            }
+        })?;
+
+        Ok(Arc::new(TableChangesProvider {
+            table_url,
+            storage_options: self.storage_options.clone(),
+            version,
+            start,
+            end,
+            schema,
+        }))
+    }
+}
+
+fn changes_builder(
+    builder: CdfLoadBuilder,
+    start: ChangesBound,
+    end: Option<ChangesBound>,
+) -> CdfLoadBuilder {
+    let builder = match start {
+        ChangesBound::Version(version) => builder.with_starting_version(version),
+        ChangesBound::Timestamp(timestamp) => builder.with_starting_timestamp(timestamp),
+    };
+    match end {
+        Some(ChangesBound::Version(version)) => builder.with_ending_version(version),
+        Some(ChangesBound::Timestamp(timestamp)) => builder.with_ending_timestamp(timestamp),
+        None => builder,
+    }
+}
+
+/// Table provider returned by [`TableChangesFunction`], loading the table once it is scanned.
+///
+/// The table is pinned to the version it had while the query was planned.
+#[derive(Debug)]
+struct TableChangesProvider {
+    table_url: Url,
+    storage_options: HashMap<String, String>,
+    version: Version,
+    start: ChangesBound,
+    end: Option<ChangesBound>,
+    schema: SchemaRef,
+}
+
+impl TableChangesProvider {
+    async fn cdf_provider(&self) -> DeltaResult<DeltaCdfTableProvider> {
+        let table = DeltaTableBuilder::from_url(self.table_url.clone())?
+            .with_storage_options(self.storage_options.clone())
+            .with_version(self.version)
+            .load()
+            .await?;
+        DeltaCdfTableProvider::try_new(changes_builder(table.scan_cdf(), self.start, self.end))
+    }
+}
+
+#[async_trait::async_trait]
+impl TableProvider for TableChangesProvider {
+    fn schema(&self) -> SchemaRef {
+        self.schema.clone()
+    }
+
+    fn table_type(&self) -> TableType {
+        TableType::Base
+    }
+
+    async fn scan(
+        &self,
+        session: &dyn Session,
+        projection: Option<&Vec<usize>>,
+        filters: &[Expr],
+        limit: Option<usize>,
+    ) -> Result<Arc<dyn ExecutionPlan>> {
+        self.cdf_provider()
+            .await?
+            .scan(session, projection, filters, limit)
+            .await
+    }
+
+    fn supports_filters_pushdown(
+        &self,
+        filter: &[&Expr],
+    ) -> Result<Vec<TableProviderFilterPushDown>> {
+        // filters are applied by the scan of the change data feed
+        Ok(vec![TableProviderFilterPushDown::Exact; filter.len()])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+
+    use datafusion::assert_batches_sorted_eq;
+    use datafusion::prelude::{SessionContext, lit};
+
+    use super::*;
+    use crate::delta_datafusion::create_session;
+
+    fn table_uri() -> String {
+        std::fs::canonicalize(Path::new("../test/tests/data/cdf-table"))
+            .unwrap()
+            .to_string_lossy()
+            .to_string()
+    }
+
+    #[test]
+    fn test_changes_bound() {
+        assert_eq!(
+            ChangesBound::try_from_expr(&lit(3_i64), "start").unwrap(),
+            ChangesBound::Version(3)
+        );
+        assert!(matches!(
+            ChangesBound::try_from_expr(&lit("2024-01-01T00:00:00Z"), "start").unwrap(),
+            ChangesBound::Timestamp(_)
+        ));
+        assert!(ChangesBound::try_from_expr(&lit(-1_i64), "start").is_err());
+        assert!(ChangesBound::try_from_expr(&lit("yesterday"), "start").is_err());
+    }
+
+    #[tokio::test]
+    async fn test_table_changes_function() -> crate::test_utils::TestResult {
+        let ctx: SessionContext = create_session().into();
+        let sql = format!(
+            "SELECT id, _change_type, _commit_version FROM table_changes('{}', 0, 0)",
+            table_uri()
+        );
+        let batches = ctx.sql(&sql).await?.collect().await?;
+        assert_batches_sorted_eq! {
+        [
+            "+----+--------------+-----------------+",
+            "| id | _change_type | _commit_version |",
+            "+----+--------------+-----------------+",
+            "| 1  | insert       | 0               |",
+            "| 10 | insert       | 0               |",
+            "| 2  | insert       | 0               |",
+            "| 3  | insert       | 0               |",
+            "| 4  | insert       | 0               |",
+            "| 5  | insert       | 0               |",
+            "| 6  | insert       | 0               |",
+            "| 7  | insert       | 0               |",
+            "| 8  | insert       | 0               |",
+            "| 9  | insert       | 0               |",
+            "+----+--------------+-----------------+",
+        ], &batches }
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_table_changes_invalid_arguments() {
+        let ctx: SessionContext = create_session().into();
+        assert!(ctx.sql("SELECT * FROM table_changes(1)").await.is_err());
+        let sql = format!("SELECT * FROM table_changes('{}', 'never')", table_uri());
+        assert!(ctx.sql(&sql).await.is_err());
+    }
+}
@@ -87,7 +87,19 @@ impl ColumnMappingState {
         &self,
         partition_columns: &[String],
     ) -> DeltaResult<Vec<String>> {
-        partition_columns
+        self.physical_column_names(partition_columns, "partition column")
+    }
+
+    /// Translate logical sort column names to physical.
+    pub(crate) fn physical_sort_columns(
+        &self,
+        sort_columns: &[String],
+    ) -> DeltaResult<Vec<String>> {
+        self.physical_column_names(sort_columns, "sort column")
+    }
+
+    fn physical_column_names(&self, columns: &[String], kind: &str) -> DeltaResult<Vec<String>> {
+        columns
             .iter()
             .map(|name| {
                 self.logical_schema
@@ -95,7 +107,7 @@ impl ColumnMappingState {
                     .map(|field| field.physical_name(self.mode).to_string())
                     .ok_or_else(|| {
                         DeltaTableError::Generic(format!(
-                            "partition column '{name}' not found in the table schema"
+                            "{kind} '{name}' not found in the table schema"
                         ))
                     })
             })
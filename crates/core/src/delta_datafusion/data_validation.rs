@@ -6,13 +6,13 @@ use std::task::{Context, Poll};
 
 use arrow::array::AsArray;
 use arrow::compute::{filter_record_batch, not};
-use arrow_array::RecordBatch;
+use arrow_array::{BooleanArray, RecordBatch, new_null_array};
 use arrow_cast::pretty::pretty_format_batches;
 use arrow_schema::{DataType, Field, Schema, SchemaRef};
 use datafusion::catalog::Session;
 use datafusion::common::tree_node::{TreeNode, TreeNodeRecursion, TreeNodeVisitor};
 use datafusion::common::{
-    DFSchema, DFSchemaRef, Statistics, ToDFSchema, exec_err, plan_datafusion_err, plan_err,
+    DFSchema, DFSchemaRef, Statistics, ToDFSchema, plan_datafusion_err, plan_err,
 };
 use datafusion::config::ConfigOptions;
 use datafusion::error::{DataFusionError, Result};
@@ -43,6 +43,7 @@ use futures::Stream;
 use itertools::Itertools as _;
 use pin_project_lite::pin_project;
 
+use crate::delta_datafusion::create_session;
 use crate::delta_datafusion::engine::{to_datafusion_expr, to_delta_expression};
 use crate::delta_datafusion::expr::{
     parse_generated_column_expression, parse_predicate_expression,
@@ -50,7 +51,7 @@ use crate::delta_datafusion::expr::{
 use crate::delta_datafusion::table_provider::simplify_expr;
 use crate::table::config::TablePropertiesExt as _;
 use crate::table::{Constraint, GeneratedColumn};
-use crate::{DeltaTableError, StructTypeExt as _};
+use crate::{DeltaResult, DeltaTableError, StructTypeExt as _};
 
 /// Logical plan node for data validation
 ///
@@ -578,35 +579,8 @@ where
         let this = self.project();
         match this.stream.poll_next(cx) {
             Poll::Ready(Some(Ok(batch))) => {
-                match this.check_expression.evaluate(&batch)? {
-                    ColumnarValue::Array(array) => {
-                        let validity_mask = array.as_boolean();
-                        let invalid_count = validity_mask
-                            .iter()
-                            .filter(|v| matches!(v, Some(false) | None))
-                            .count();
-                        if invalid_count > 0 {
-                            let invalid_data = filter_record_batch(&batch, &not(validity_mask)?)?;
-                            let invalid_slice =
-                                invalid_data.slice(0, invalid_data.num_rows().min(5));
-                            let preview = pretty_format_batches(&[invalid_slice])?;
-                            return Poll::Ready(Some(Err(DataFusionError::External(Box::new(
-                                DeltaTableError::InvalidData {
-                                    message: format!(
-                                        "Invalid data found: {invalid_count} rows failed \
-                                        validation check.\nPreview of invalid data:\n\n{preview}"
-                                    ),
-                                },
-                            )))));
-                        }
-                    }
-                    ColumnarValue::Scalar(value) => {
-                        if !matches!(value, ScalarValue::Boolean(Some(true))) {
-                            return Poll::Ready(Some(exec_err!(
-                                "Invalid data found: validation check failed with value {value:?}."
-                            )));
-                        }
-                    }
+                if let Err(err) = validate_batch(this.check_expression.as_ref(), &batch) {
+                    return Poll::Ready(Some(Err(DataFusionError::External(Box::new(err)))));
                 }
                 let (_, arrays, _) = batch.into_parts();
                 Poll::Ready(Some(Ok(RecordBatch::try_new(
@@ -630,6 +604,195 @@ where
     }
 }
 
+/// Check that every row of `batch` satisfies `check_expression`.
+///
+/// The expression is evaluated once for the whole batch, the offending rows are only
+/// materialized to build a preview for the error message.
+pub(crate) fn validate_batch(
+    check_expression: &dyn PhysicalExpr,
+    batch: &RecordBatch,
+) -> DeltaResult<()> {
+    match check_expression.evaluate(batch)? {
+        ColumnarValue::Array(array) => {
+            let validity_mask = array.as_boolean();
+            let invalid_count = validity_mask
+                .iter()
+                .filter(|v| matches!(v, Some(false) | None))
+                .count();
+            if invalid_count > 0 {
+                let invalid_data = filter_record_batch(batch, &not(validity_mask)?)?;
+                let invalid_slice = invalid_data.slice(0, invalid_data.num_rows().min(5));
+                let preview = pretty_format_batches(&[invalid_slice])?;
+                return Err(DeltaTableError::InvalidData {
+                    message: format!(
+                        "Invalid data found: {invalid_count} rows failed \
+                        validation check.\nPreview of invalid data:\n\n{preview}"
+                    ),
+                });
+            }
+        }
+        ColumnarValue::Scalar(value) => {
+            if !matches!(value, ScalarValue::Boolean(Some(true))) {
+                return Err(DeltaTableError::InvalidData {
+                    message: format!(
+                        "Invalid data found: validation check failed with value {value:?}."
+                    ),
+                });
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Mask of the rows of `batch` for which `check_expression` does not hold, `None` if it holds
+/// for all rows. Rows evaluating to null violate the check as well.
+fn invalid_rows(
+    check_expression: &dyn PhysicalExpr,
+    batch: &RecordBatch,
+) -> DeltaResult<Option<BooleanArray>> {
+    let valid = check_expression
+        .evaluate(batch)?
+        .into_array(batch.num_rows())?;
+    let invalid: BooleanArray = valid
+        .as_boolean()
+        .iter()
+        .map(|valid| Some(valid != Some(true)))
+        .collect();
+    Ok((invalid.true_count() > 0).then_some(invalid))
+}
+
+/// Validates record batches written outside of a DataFusion plan.
+///
+/// Applies the same checks as [`DataValidationExec`] for the non-nullable columns, invariants,
+/// CHECK constraints and generated columns of a table, so that writers like the
+/// [`RecordBatchWriter`](crate::writer::RecordBatchWriter) enforce them as well. Columns of the
+/// table missing in a batch are checked as if they were null, just like they are written.
+///
+/// The check expression is planned for the schema of the first batch and re-planned whenever
+/// the schema of the batches changes.
+#[derive(Debug, Clone)]
+pub(crate) struct BatchValidator {
+    table_configuration: TableConfiguration,
+    planned: Option<PlannedCheck>,
+}
+
+/// Check expression planned for batches of a given schema
+#[derive(Debug, Clone)]
+struct PlannedCheck {
+    /// Schema of the batches the check was planned for
+    input_schema: SchemaRef,
+    /// Columns of the table missing in the input, appended as null columns before the check
+    missing_fields: Vec<Field>,
+    /// The check expression, `None` if there is nothing to check
+    check_expression: Option<Arc<dyn PhysicalExpr>>,
+}
+
+impl BatchValidator {
+    pub(crate) fn new(table_configuration: TableConfiguration) -> Self {
+        Self {
+            table_configuration,
+            planned: None,
+        }
+    }
+
+    /// Check all rows of `batch`, failing with [`DeltaTableError::InvalidData`] if any row
+    /// violates the constraints of the table.
+    pub(crate) fn validate(&mut self, batch: &RecordBatch) -> DeltaResult<()> {
+        self.check(batch, validate_batch).map(|_| ())
+    }
+
+    /// Mask of the rows of `batch` violating the constraints of the table, `None` if all rows
+    /// are valid.
+    pub(crate) fn invalid_rows(
+        &mut self,
+        batch: &RecordBatch,
+    ) -> DeltaResult<Option<BooleanArray>> {
+        self.check(batch, invalid_rows).map(Option::flatten)
+    }
+
+    /// Apply `check` with the check expression planned for the schema of `batch`, `None` if
+    /// there is nothing to check.
+    fn check<T>(
+        &mut self,
+        batch: &RecordBatch,
+        check: impl FnOnce(&dyn PhysicalExpr, &RecordBatch) -> DeltaResult<T>,
+    ) -> DeltaResult<Option<T>> {
+        let planned = match self.planned.take() {
+            Some(planned) if planned.input_schema == batch.schema() => planned,
+            _ => self.plan(batch.schema())?,
+        };
+        let result = match &planned.check_expression {
+            Some(check_expression) if planned.missing_fields.is_empty() => {
+                check(check_expression.as_ref(), batch).map(Some)
+            }
+            Some(check_expression) => with_null_columns(batch, &planned.missing_fields)
+                .and_then(|batch| check(check_expression.as_ref(), &batch))
+                .map(Some),
+            None => Ok(None),
+        };
+        self.planned = Some(planned);
+        result
+    }
+
+    fn plan(&self, input_schema: SchemaRef) -> DeltaResult<PlannedCheck> {
+        let table_schema: Schema = self
+            .table_configuration
+            .logical_schema()
+            .as_ref()
+            .try_into_arrow()?;
+        let missing_fields: Vec<_> = table_schema
+            .fields()
+            .iter()
+            .filter(|field| input_schema.field_with_name(field.name()).is_err())
+            .map(|field| field.as_ref().clone().with_nullable(true))
+            .collect();
+        let schema = Schema::new(
+            input_schema
+                .fields()
+                .iter()
+                .map(|field| field.as_ref().clone())
+                .chain(missing_fields.iter().cloned())
+                .collect::<Vec<_>>(),
+        );
+
+        let session = create_session().state();
+        let df_schema = schema.to_dfschema()?;
+        let predicates = validation_predicates(&session, &df_schema, &self.table_configuration)?;
+        let check_expression = conjunction(simplify_predicates(predicates)?)
+            .map(|expr| simplify_expr(&session, df_schema.into(), expr))
+            .transpose()?;
+        Ok(PlannedCheck {
+            input_schema,
+            missing_fields,
+            check_expression,
+        })
+    }
+}
+
+/// Append null columns for `fields` to `batch`
+fn with_null_columns(batch: &RecordBatch, fields: &[Field]) -> DeltaResult<RecordBatch> {
+    let schema = Schema::new(
+        batch
+            .schema()
+            .fields()
+            .iter()
+            .map(|field| field.as_ref().clone())
+            .chain(fields.iter().cloned())
+            .collect::<Vec<_>>(),
+    );
+    let columns = batch
+        .columns()
+        .iter()
+        .cloned()
+        .chain(
+            fields
+                .iter()
+                .map(|field| new_null_array(field.data_type(), batch.num_rows())),
+        )
+        .collect();
+    Ok(RecordBatch::try_new(Arc::new(schema), columns)?)
+}
+
 /// Collect all non-nullable field paths from an Arrow schema.
 ///
 /// This function traverses the schema recursively and returns the paths of all
@@ -0,0 +1,187 @@
+//! Writing DataFusion [`DataFrame`]s to Delta tables.
+//!
+//! ```rust,no_run
+//! use datafusion::prelude::SessionContext;
+//! use deltalake_core::delta_datafusion::{DeltaDataFrameExt as _, WriteOptions};
+//! use deltalake_core::protocol::SaveMode;
+//!
+//! async {
+//!     let ctx = SessionContext::new();
+//!     let df = ctx.sql("SELECT 1 AS id").await.unwrap();
+//!     let options = WriteOptions::default().with_save_mode(SaveMode::Overwrite);
+//!     let table = df.write_delta("/tmp/table", options).await.unwrap();
+//! };
+//! ```
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use datafusion::dataframe::DataFrame;
+use parquet::file::properties::WriterProperties;
+
+use crate::kernel::transaction::CommitProperties;
+use crate::operations::write::{SchemaMode, WriteBuilder};
+use crate::protocol::SaveMode;
+use crate::{DeltaResult, DeltaTable, ensure_table_uri};
+
+/// Options for writing a [`DataFrame`] with [`DeltaDataFrameExt::write_delta`].
+#[derive(Debug, Clone)]
+pub struct WriteOptions {
+    save_mode: SaveMode,
+    schema_mode: Option<SchemaMode>,
+    partition_columns: Option<Vec<String>>,
+    storage_options: HashMap<String, String>,
+    writer_properties: Option<WriterProperties>,
+    commit_properties: CommitProperties,
+}
+
+impl Default for WriteOptions {
+    fn default() -> Self {
+        Self {
+            save_mode: SaveMode::Append,
+            schema_mode: None,
+            partition_columns: None,
+            storage_options: HashMap::new(),
+            writer_properties: None,
+            commit_properties: CommitProperties::default(),
+        }
+    }
+}
+
+impl WriteOptions {
+    /// Specify the behavior when a table exists at location
+    pub fn with_save_mode(mut self, save_mode: SaveMode) -> Self {
+        self.save_mode = save_mode;
+        self
+    }
+
+    /// Add Schema Write Mode
+    pub fn with_schema_mode(mut self, schema_mode: SchemaMode) -> Self {
+        self.schema_mode = Some(schema_mode);
+        self
+    }
+
+    /// (Optional) Specify table partitioning. If specified, the partitioning is validated,
+    /// if the table already exists. In case a new table is created, the partitioning is applied.
+    pub fn with_partition_columns(
+        mut self,
+        partition_columns: impl IntoIterator<Item = impl Into<String>>,
+    ) -> Self {
+        self.partition_columns = Some(partition_columns.into_iter().map(|s| s.into()).collect());
+        self
+    }
+
+    /// Options used to access the storage the table is located in
+    pub fn with_storage_options(mut self, storage_options: HashMap<String, String>) -> Self {
+        self.storage_options = storage_options;
+        self
+    }
+
+    /// Specify the writer properties to use when writing a parquet file
+    pub fn with_writer_properties(mut self, writer_properties: WriterProperties) -> Self {
+        self.writer_properties = Some(writer_properties);
+        self
+    }
+
+    /// Additional metadata to be added to commit info
+    pub fn with_commit_properties(mut self, commit_properties: CommitProperties) -> Self {
+        self.commit_properties = commit_properties;
+        self
+    }
+}
+
+/// Extension methods writing DataFusion [`DataFrame`]s to Delta tables.
+#[async_trait::async_trait]
+pub trait DeltaDataFrameExt {
+    /// Write the data frame to the Delta table at `table_uri`, creating the table if it does
+    /// not exist yet.
+    ///
+    /// The plan of the data frame is executed by the session it was created in and streamed
+    /// into the writer, without collecting its results in memory first.
+    async fn write_delta(self, table_uri: &str, options: WriteOptions) -> DeltaResult<DeltaTable>;
+}
+
+#[async_trait::async_trait]
+impl DeltaDataFrameExt for DataFrame {
+    async fn write_delta(self, table_uri: &str, options: WriteOptions) -> DeltaResult<DeltaTable> {
+        let table_url = ensure_table_uri(table_uri)?;
+        let table =
+            DeltaTable::try_from_url_with_storage_options(table_url, options.storage_options)
+                .await?;
+        let (session_state, plan) = self.into_parts();
+
+        let mut builder =
+            WriteBuilder::new(table.log_store(), table.state.map(|state| state.snapshot))
+                .with_input_plan(plan)
+                .with_session_state(Arc::new(session_state))
+                .with_save_mode(options.save_mode)
+                .with_commit_properties(options.commit_properties);
+        if let Some(schema_mode) = options.schema_mode {
+            builder = builder.with_schema_mode(schema_mode);
+        }
+        if let Some(partition_columns) = options.partition_columns {
+            builder = builder.with_partition_columns(partition_columns);
+        }
+        if let Some(writer_properties) = options.writer_properties {
+            builder = builder.with_writer_properties(writer_properties);
+        }
+        builder.await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use datafusion::assert_batches_sorted_eq;
+    use datafusion::prelude::SessionContext;
+
+    use super::*;
+
+    #[tokio::test]
+    async fn test_write_delta() -> crate::test_utils::TestResult {
+        let tmp_dir = tempfile::tempdir()?;
+        let table_uri = tmp_dir.path().to_str().unwrap();
+        let ctx = SessionContext::new();
+
+        let df = ctx
+            .sql("SELECT * FROM (VALUES (1, 'a'), (2, 'b'), (3, 'a')) AS t(id, part)")
+            .await?;
+        let options = WriteOptions::default().with_partition_columns(["part"]);
+        let table = df.write_delta(table_uri, options).await?;
+        assert_eq!(table.version(), Some(0));
+        assert_eq!(
+            table.snapshot()?.metadata().partition_columns(),
+            &vec!["part".to_string()]
+        );
+
+        let df = ctx.sql("SELECT 4 AS id, 'c' AS part").await?;
+        let table = df.write_delta(table_uri, WriteOptions::default()).await?;
+        assert_eq!(table.version(), Some(1));
+
+        ctx.register_table("delta", table.table_provider().await?)?;
+        let batches = ctx
+            .sql("SELECT id, part FROM delta")
+            .await?
+            .collect()
+            .await?;
+        assert_batches_sorted_eq!(
+            [
+                "+----+------+",
+                "| id | part |",
+                "+----+------+",
+                "| 1  | a    |",
+                "| 2  | b    |",
+                "| 3  | a    |",
+                "| 4  | c    |",
+                "+----+------+",
+            ],
+            &batches
+        );
+
+        let df = ctx.sql("SELECT 5 AS id, 'd' AS part").await?;
+        let options = WriteOptions::default().with_save_mode(SaveMode::Overwrite);
+        let table = df.write_delta(table_uri, options).await?;
+        assert_eq!(table.version(), Some(2));
+        assert_eq!(table.snapshot()?.log_data().num_files(), 1);
+
+        Ok(())
+    }
+}
@@ -1,6 +1,6 @@
 use datafusion::common::tree_node::{Transformed, TreeNode as _};
 use datafusion::common::{Result, ScalarValue, plan_datafusion_err, plan_err};
-use datafusion::logical_expr::expr::InList;
+use datafusion::logical_expr::expr::{Cast, InList};
 use datafusion::logical_expr::utils::{conjunction, disjunction};
 use datafusion::logical_expr::{BinaryExpr, Expr, Operator};
 use delta_kernel::expressions::{
@@ -80,6 +80,14 @@ pub(crate) fn to_delta_expression(expr: &Expr) -> Result<Expression> {
         Expr::Literal(scalar, _meta) => {
             Ok(Expression::Literal(datafusion_scalar_to_scalar(scalar)?))
         }
+        // Type coercion may leave casts of literals in pushed down filters. Folding them keeps
+        // such predicates usable for file skipping.
+        Expr::Cast(Cast { expr: inner, field }) => match inner.as_ref() {
+            Expr::Literal(scalar, _meta) => Ok(Expression::Literal(datafusion_scalar_to_scalar(
+                &scalar.cast_to(field.data_type())?,
+            )?)),
+            _ => plan_err!("Cannot convert cast to kernel expression: {:?}", expr),
+        },
         Expr::BinaryExpr(BinaryExpr {
             op: op @ (Operator::And | Operator::Or),
             ..
@@ -237,6 +245,24 @@ pub(crate) fn datafusion_scalar_to_scalar(scalar: &ScalarValue) -> Result<Scalar
             Some(value) => Ok(Scalar::Long(*value)),
             None => Ok(Scalar::Null(DataType::LONG)),
         },
+        ScalarValue::UInt8(maybe_value) => match maybe_value {
+            Some(value) => Ok(Scalar::Short(i16::from(*value))),
+            None => Ok(Scalar::Null(DataType::SHORT)),
+        },
+        ScalarValue::UInt16(maybe_value) => match maybe_value {
+            Some(value) => Ok(Scalar::Integer(i32::from(*value))),
+            None => Ok(Scalar::Null(DataType::INTEGER)),
+        },
+        ScalarValue::UInt32(maybe_value) => match maybe_value {
+            Some(value) => Ok(Scalar::Long(i64::from(*value))),
+            None => Ok(Scalar::Null(DataType::LONG)),
+        },
+        ScalarValue::UInt64(maybe_value) => match maybe_value {
+            Some(value) => Ok(Scalar::Long(i64::try_from(*value).map_err(|_| {
+                plan_datafusion_err!("UInt64 literal {value} does not fit into a kernel long")
+            })?)),
+            None => Ok(Scalar::Null(DataType::LONG)),
+        },
         ScalarValue::Float32(maybe_value) => match maybe_value {
             Some(value) => Ok(Scalar::Float(*value)),
             None => Ok(Scalar::Null(DataType::FLOAT)),
@@ -263,6 +289,12 @@ pub(crate) fn datafusion_scalar_to_scalar(scalar: &ScalarValue) -> Result<Scalar
             Some(value) => Ok(Scalar::TimestampNtz(*value)),
             None => Ok(Scalar::Null(DataType::TIMESTAMP_NTZ)),
         },
+        ScalarValue::TimestampMillisecond(maybe_value, tz) => {
+            timestamp_to_scalar(*maybe_value, tz.is_some(), 1_000)
+        }
+        ScalarValue::TimestampSecond(maybe_value, tz) => {
+            timestamp_to_scalar(*maybe_value, tz.is_some(), 1_000_000)
+        }
         ScalarValue::Date32(maybe_value) => match maybe_value {
             Some(value) => Ok(Scalar::Date(*value)),
             None => Ok(Scalar::Null(DataType::DATE)),
@@ -295,6 +327,25 @@ pub(crate) fn datafusion_scalar_to_scalar(scalar: &ScalarValue) -> Result<Scalar
     }
 }
 
+/// Converts a timestamp with `micros_per_unit` microseconds per unit to a kernel timestamp.
+fn timestamp_to_scalar(value: Option<i64>, utc: bool, micros_per_unit: i64) -> Result<Scalar> {
+    let Some(value) = value else {
+        return Ok(Scalar::Null(if utc {
+            DataType::TIMESTAMP
+        } else {
+            DataType::TIMESTAMP_NTZ
+        }));
+    };
+    let micros = value
+        .checked_mul(micros_per_unit)
+        .ok_or_else(|| plan_datafusion_err!("Timestamp literal {value} overflows microseconds"))?;
+    Ok(if utc {
+        Scalar::Timestamp(micros)
+    } else {
+        Scalar::TimestampNtz(micros)
+    })
+}
+
 fn to_binary_predicate_op(op: Operator) -> Result<BinaryPredicateOp> {
     match op {
         Operator::Eq => Ok(BinaryPredicateOp::Equal),
@@ -793,6 +844,65 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_unsigned_and_timestamp_literals() {
+        let test_cases = vec![
+            (lit(7u8), Scalar::Short(7)),
+            (lit(7u16), Scalar::Integer(7)),
+            (lit(7u32), Scalar::Long(7)),
+            (lit(7u64), Scalar::Long(7)),
+            (
+                lit(ScalarValue::TimestampMillisecond(
+                    Some(1_500),
+                    Some("UTC".into()),
+                )),
+                Scalar::Timestamp(1_500_000),
+            ),
+            (
+                lit(ScalarValue::TimestampSecond(Some(2), None)),
+                Scalar::TimestampNtz(2_000_000),
+            ),
+        ];
+
+        for (expr, expected) in test_cases {
+            match to_delta_expression(&expr).unwrap() {
+                Expression::Literal(value) => assert_eq!(value, expected),
+                other => panic!("Expected literal, got {:?}", other),
+            }
+        }
+
+        assert!(to_delta_expression(&lit(u64::MAX)).is_err());
+        assert!(
+            to_delta_expression(&lit(ScalarValue::TimestampSecond(Some(i64::MAX), None))).is_err()
+        );
+    }
+
+    #[test]
+    fn test_cast_of_literal_is_folded() {
+        let expr = col("id").eq(Expr::Cast(Cast::new(
+            Box::new(lit(5i32)),
+            arrow_schema::DataType::Int64,
+        )));
+        match to_delta_expression(&expr).unwrap() {
+            Expression::Predicate(predicate) => match predicate.as_ref() {
+                Predicate::Binary(binary) => {
+                    assert_eq!(binary.op, BinaryPredicateOp::Equal);
+                    assert_eq!(*binary.right, Expression::Literal(Scalar::Long(5)));
+                }
+                _ => panic!("Expected Binary predicate, got {:?}", predicate),
+            },
+            other => panic!("Expected predicate, got {:?}", other),
+        }
+
+        // casts of columns cannot be expressed in kernel
+        let expr = Expr::Cast(Cast::new(
+            Box::new(col("id")),
+            arrow_schema::DataType::Int64,
+        ))
+        .eq(lit(5i64));
+        assert!(to_delta_expression(&expr).is_err());
+    }
+
     #[test]
     fn test_between_expressions() {
         // Test BETWEEN (not negated) - should be equivalent to: NOT (x < low OR x > high)
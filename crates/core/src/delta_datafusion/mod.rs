@@ -23,6 +23,7 @@
 //! };
 //! ```
 
+use std::collections::HashMap;
 use std::fmt::Debug;
 use std::sync::Arc;
 
@@ -37,6 +38,7 @@ use datafusion::catalog::{Session, TableProviderFactory};
 use datafusion::common::scalar::ScalarValue;
 use datafusion::common::{
     Column, DFSchema, DataFusionError, Result as DataFusionResult, TableReference, ToDFSchema,
+    plan_datafusion_err, plan_err,
 };
 use datafusion::datasource::TableProvider;
 use datafusion::datasource::physical_plan::wrap_partition_type_in_dict;
@@ -52,12 +54,12 @@ use datafusion_proto::physical_plan::PhysicalExtensionCodec;
 use delta_kernel::engine::arrow_conversion::TryIntoArrow as _;
 use either::Either;
 
+use crate::DeltaTableBuilder;
 use crate::delta_datafusion::expr::parse_predicate_expression;
 use crate::delta_datafusion::table_provider::{DeltaScan, DeltaScanWire};
 use crate::ensure_table_uri;
 use crate::errors::{DeltaResult, DeltaTableError};
 use crate::kernel::{Add, EagerSnapshot, LogDataHandler, Snapshot};
-use crate::{open_table, open_table_with_storage_options};
 
 pub(crate) use self::session::DeltaSessionExt;
 pub use self::session::{
@@ -69,14 +71,23 @@ pub use self::table_provider::next::{
 };
 pub(crate) use self::utils::*;
 pub use cdf::scan::DeltaCdfTableProvider;
+pub use cdf::stream::{CdfStreamOffset, DeltaCdfStreamExec};
+pub use cdf::{TABLE_CHANGES_FUNCTION_NAME, TableChangesFunction};
 pub(crate) use column_mapping::ColumnMappingState;
 pub(crate) use data_validation::{
-    DataValidationExec, constraints_to_exprs, generated_columns_to_exprs, validation_predicates,
+    BatchValidator, DataValidationExec, constraints_to_exprs, generated_columns_to_exprs,
+    validation_predicates,
 };
+pub use dataframe::{DeltaDataFrameExt, WriteOptions};
 pub(crate) use find_files::*;
+pub use table_functions::{
+    DELTA_DETAIL_FUNCTION_NAME, DELTA_HISTORY_FUNCTION_NAME, DeltaDetailFunction,
+    DeltaHistoryFunction,
+};
 pub(crate) use table_provider::next::normalize_path_as_file_id;
 pub use table_provider::{
-    DeltaScanConfig, DeltaScanConfigBuilder, TableProviderBuilder, next::DeltaScanExec,
+    DeltaDataSink, DeltaScanConfig, DeltaScanConfigBuilder, ScanFileOrder, TableProviderBuilder,
+    next::DeltaScanExec,
 };
 pub(crate) use table_provider::{
     next::FILE_ID_COLUMN_DEFAULT, resolve_file_column_name, update_datafusion_session,
@@ -89,6 +100,7 @@ pub mod bench_support;
 pub mod cdf;
 mod column_mapping;
 mod data_validation;
+mod dataframe;
 /// DataFusion-backed kernel engine and its storage/format handlers.
 pub mod engine;
 pub mod expr;
@@ -100,6 +112,7 @@ pub mod planner;
 mod session;
 pub use session::SessionFallbackPolicy;
 pub(crate) use session::{SessionResolveContext, resolve_session_state};
+mod table_functions;
 mod table_provider;
 pub(crate) mod utils;
 
@@ -529,7 +542,22 @@ impl LogicalExtensionCodec for DeltaLogicalCodec {
     }
 }
 
+/// Option of `CREATE EXTERNAL TABLE` selecting the version of the table to read.
+const VERSION_OPTION: &str = "version";
+/// Option of `CREATE EXTERNAL TABLE` selecting the version of the table as of an
+/// RFC 3339 timestamp.
+const TIMESTAMP_OPTION: &str = "timestamp";
+
 /// Responsible for creating deltatables
+///
+/// Historical versions of a table are registered with the `version` or `timestamp` options,
+/// all other options are passed on as storage options.
+///
+/// ```sql
+/// CREATE EXTERNAL TABLE demo STORED AS DELTATABLE OPTIONS ('version' '42') LOCATION '...';
+/// CREATE EXTERNAL TABLE demo STORED AS DELTATABLE
+///     OPTIONS ('timestamp' '2024-01-01T00:00:00Z') LOCATION '...';
+/// ```
 #[derive(Debug)]
 pub struct DeltaTableFactory {}
 
@@ -540,13 +568,29 @@ impl TableProviderFactory for DeltaTableFactory {
         ctx: &dyn Session,
         cmd: &CreateExternalTable,
     ) -> datafusion::error::Result<Arc<dyn TableProvider>> {
-        let table = if cmd.options.is_empty() {
-            let table_url = ensure_table_uri(&cmd.to_owned().location)?;
-            open_table(table_url).await?
-        } else {
-            let table_url = ensure_table_uri(&cmd.to_owned().location)?;
-            open_table_with_storage_options(table_url, cmd.to_owned().options).await?
+        let mut storage_options = cmd.options.clone();
+        let version = take_option(&mut storage_options, VERSION_OPTION);
+        let timestamp = take_option(&mut storage_options, TIMESTAMP_OPTION);
+
+        let table_url = ensure_table_uri(&cmd.location)?;
+        let mut builder = DeltaTableBuilder::from_url(table_url)?;
+        if !storage_options.is_empty() {
+            builder = builder.with_storage_options(storage_options);
+        }
+        builder = match (version, timestamp) {
+            (None, None) => builder,
+            (Some(version), None) => builder.with_version(version.parse().map_err(|_| {
+                plan_datafusion_err!("Delta table version must be an integer, got '{version}'")
+            })?),
+            (None, Some(timestamp)) => builder.with_datestring(timestamp)?,
+            (Some(_), Some(_)) => {
+                return plan_err!(
+                    "Only one of the '{VERSION_OPTION}' and '{TIMESTAMP_OPTION}' options can be specified"
+                );
+            }
         };
+        let table = builder.load().await?;
+
         let table_uri = table.log_store().root_url().clone();
         let (session_state, _) = resolve_session_state(
             Some(ctx),
@@ -566,6 +610,15 @@ impl TableProviderFactory for DeltaTableFactory {
     }
 }
 
+/// Remove the option `name` from `options`, matching its key case-insensitively.
+fn take_option(options: &mut HashMap<String, String>, name: &str) -> Option<String> {
+    let key = options
+        .keys()
+        .find(|key| key.eq_ignore_ascii_case(name))?
+        .clone();
+    options.remove(&key)
+}
+
 /// A wrapper for Deltafusion's Column to preserve case-sensitivity during string conversion
 pub struct DeltaColumn {
     inner: Column,
@@ -1695,4 +1748,82 @@ mod tests {
             "range predicate '<' on binary partition must not prune any files"
         );
     }
+
+    /// Null checks prune files using partition values and file level null counts.
+    #[tokio::test]
+    async fn test_files_matching_predicate_null_checks() {
+        use crate::delta_datafusion::files_matching_predicate;
+        use arrow::array::{Int32Array, StringArray};
+        use datafusion::logical_expr::col;
+
+        let arrow_schema = Arc::new(ArrowSchema::new(vec![
+            Field::new("id", ArrowDataType::Int32, true),
+            Field::new("part", ArrowDataType::Utf8, true),
+        ]));
+        // One file per partition, the null partition is written to `__HIVE_DEFAULT_PARTITION__`.
+        let batch = RecordBatch::try_new(
+            arrow_schema,
+            vec![
+                Arc::new(Int32Array::from(vec![
+                    Some(1),
+                    Some(2),
+                    Some(3),
+                    None,
+                    Some(4),
+                    None,
+                ])) as Arc<dyn Array>,
+                Arc::new(StringArray::from(vec![
+                    Some("a"),
+                    Some("a"),
+                    None,
+                    Some("b"),
+                    Some("b"),
+                    Some("c"),
+                ])) as Arc<dyn Array>,
+            ],
+        )
+        .unwrap();
+
+        let table = DeltaTable::new_in_memory()
+            .create()
+            .with_column(
+                "id",
+                delta_kernel::schema::DataType::Primitive(
+                    delta_kernel::schema::PrimitiveType::Integer,
+                ),
+                true,
+                None,
+            )
+            .with_column(
+                "part",
+                delta_kernel::schema::DataType::Primitive(
+                    delta_kernel::schema::PrimitiveType::String,
+                ),
+                true,
+                None,
+            )
+            .with_partition_columns(["part"])
+            .await
+            .unwrap();
+        let table = table
+            .write(vec![batch])
+            .with_save_mode(crate::protocol::SaveMode::Append)
+            .await
+            .unwrap();
+
+        let snapshot = table.snapshot().unwrap().snapshot().clone();
+        let log_data = snapshot.log_data();
+        assert_eq!(log_data.num_files(), 4);
+
+        let kept = |predicate: Expr| {
+            files_matching_predicate(log_data.clone(), &[predicate])
+                .unwrap()
+                .count()
+        };
+        assert_eq!(kept(col("part").is_null()), 1);
+        assert_eq!(kept(col("part").is_not_null()), 3);
+        // the files in partitions "b" and "c" contain nulls, "c" only nulls
+        assert_eq!(kept(col("id").is_null()), 2);
+        assert_eq!(kept(col("id").is_not_null()), 3);
+    }
 }
@@ -178,6 +178,15 @@ pub(crate) fn find_metric_node(
     None
 }
 
+/// Total number of bytes the operators of an executed plan spilled to disk
+pub(crate) fn spilled_bytes(plan: &Arc<dyn ExecutionPlan>) -> u64 {
+    let spilled = plan
+        .metrics()
+        .and_then(|metrics| metrics.spilled_bytes())
+        .unwrap_or_default() as u64;
+    spilled + plan.children().into_iter().map(spilled_bytes).sum::<u64>()
+}
+
 pub(crate) fn get_metric(metrics: &MetricsSet, name: &str) -> usize {
     metrics.sum_by_name(name).map(|m| m.as_usize()).unwrap_or(0)
 }
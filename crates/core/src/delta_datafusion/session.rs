@@ -14,8 +14,13 @@ use datafusion::{
 use url::Url;
 use uuid::Uuid;
 
+use crate::delta_datafusion::cdf::{TABLE_CHANGES_FUNCTION_NAME, TableChangesFunction};
 use crate::delta_datafusion::engine::AsObjectStoreUrl;
 use crate::delta_datafusion::planner::DeltaPlanner;
+use crate::delta_datafusion::table_functions::{
+    DELTA_DETAIL_FUNCTION_NAME, DELTA_HISTORY_FUNCTION_NAME, DeltaDetailFunction,
+    DeltaHistoryFunction,
+};
 use crate::errors::{DeltaResult, DeltaTableError};
 use crate::logstore::LogStore;
 
@@ -366,6 +371,18 @@ impl DeltaSessionContext {
             .build();
 
         let inner = SessionContext::new_with_state(state);
+        inner.register_udtf(
+            TABLE_CHANGES_FUNCTION_NAME,
+            Arc::new(TableChangesFunction::default()),
+        );
+        inner.register_udtf(
+            DELTA_HISTORY_FUNCTION_NAME,
+            Arc::new(DeltaHistoryFunction::default()),
+        );
+        inner.register_udtf(
+            DELTA_DETAIL_FUNCTION_NAME,
+            Arc::new(DeltaDetailFunction::default()),
+        );
         Self { inner }
     }
 
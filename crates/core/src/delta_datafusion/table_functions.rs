@@ -0,0 +1,437 @@
+//! Table functions exposing the history and details of Delta tables to SQL.
+//!
+//! ```sql
+//! SELECT * FROM delta_history('s3://bucket/table') WHERE operation = 'OPTIMIZE';
+//! SELECT * FROM delta_history('s3://bucket/table', 10);
+//! SELECT num_files, size_in_bytes FROM delta_detail('s3://bucket/table');
+//! ```
+use std::collections::HashMap;
+use std::future::Future;
+use std::sync::Arc;
+
+use arrow::array::{
+    ArrayRef, BooleanBuilder, Int64Builder, ListBuilder, MapBuilder, RecordBatch, StringArray,
+    StringBuilder, TimestampMillisecondBuilder,
+};
+use arrow_schema::DataType;
+use datafusion::catalog::{TableFunctionImpl, TableProvider};
+use datafusion::common::{DataFusionError, Result, ScalarValue, plan_datafusion_err, plan_err};
+use datafusion::datasource::MemTable;
+use datafusion::logical_expr::Expr;
+use futures::TryStreamExt as _;
+use serde_json::Value;
+
+use crate::kernel::{CommitInfo, Version};
+use crate::table::builder::parse_table_uri;
+use crate::{DeltaResult, DeltaTable, DeltaTableBuilder};
+
+/// Name the [`DeltaHistoryFunction`] is registered under in sessions created by delta-rs.
+pub const DELTA_HISTORY_FUNCTION_NAME: &str = "delta_history";
+/// Name the [`DeltaDetailFunction`] is registered under in sessions created by delta-rs.
+pub const DELTA_DETAIL_FUNCTION_NAME: &str = "delta_detail";
+
+type StringMapBuilder = MapBuilder<StringBuilder, StringBuilder>;
+
+/// A DataFusion table function returning the commit history of a Delta table, latest first.
+///
+/// Takes the location of the table and optionally the maximum number of commits to return.
+/// Tables are loaded with the storage options the function was created with.
+#[derive(Debug, Default, Clone)]
+pub struct DeltaHistoryFunction {
+    storage_options: HashMap<String, String>,
+}
+
+impl DeltaHistoryFunction {
+    /// Create a function loading tables with the given `storage_options`.
+    pub fn new(storage_options: HashMap<String, String>) -> Self {
+        Self { storage_options }
+    }
+}
+
+impl TableFunctionImpl for DeltaHistoryFunction {
+    fn call(&self, args: &[Expr]) -> Result<Arc<dyn TableProvider>> {
+        let (uri, limit) = match args {
+            [uri] => (uri, None),
+            [uri, limit] => (uri, Some(limit)),
+            _ => {
+                return plan_err!(
+                    "{DELTA_HISTORY_FUNCTION_NAME} expects 1 or 2 arguments (table_uri, [limit]), got {}",
+                    args.len()
+                );
+            }
+        };
+        let uri = table_uri_arg(DELTA_HISTORY_FUNCTION_NAME, uri)?;
+        let limit = limit.map(limit_arg).transpose()?;
+
+        let storage_options = self.storage_options.clone();
+        let commits = block_on(async move {
+            let table = load_table(&uri, storage_options).await?;
+            let snapshot = table.snapshot()?.snapshot().snapshot();
+            let latest = snapshot.version();
+            // Commit infos are returned for consecutive commits, starting at the latest one.
+            let infos = snapshot
+                .commit_infos(&table.log_store(), limit)
+                .await?
+                .try_collect::<Vec<_>>()
+                .await?;
+            Ok::<_, crate::DeltaTableError>(
+                infos
+                    .into_iter()
+                    .enumerate()
+                    .filter_map(|(idx, info)| Some((latest - idx as Version, info?)))
+                    .collect::<Vec<_>>(),
+            )
+        })?;
+        memory_table(history_batch(commits)?)
+    }
+}
+
+/// A DataFusion table function returning a single row describing a Delta table, such as its
+/// protocol, properties and the number and size of its files.
+///
+/// Tables are loaded with the storage options the function was created with.
+#[derive(Debug, Default, Clone)]
+pub struct DeltaDetailFunction {
+    storage_options: HashMap<String, String>,
+}
+
+impl DeltaDetailFunction {
+    /// Create a function loading tables with the given `storage_options`.
+    pub fn new(storage_options: HashMap<String, String>) -> Self {
+        Self { storage_options }
+    }
+}
+
+impl TableFunctionImpl for DeltaDetailFunction {
+    fn call(&self, args: &[Expr]) -> Result<Arc<dyn TableProvider>> {
+        let [uri] = args else {
+            return plan_err!(
+                "{DELTA_DETAIL_FUNCTION_NAME} expects 1 argument (table_uri), got {}",
+                args.len()
+            );
+        };
+        let uri = table_uri_arg(DELTA_DETAIL_FUNCTION_NAME, uri)?;
+        let storage_options = self.storage_options.clone();
+        let table = block_on(async move { load_table(&uri, storage_options).await })?;
+        memory_table(detail_batch(&table)?)
+    }
+}
+
+fn table_uri_arg(function: &str, expr: &Expr) -> Result<String> {
+    match expr {
+        Expr::Literal(value, _) => value.try_as_str().flatten().map(ToString::to_string),
+        _ => None,
+    }
+    .ok_or_else(|| plan_datafusion_err!("{function}: table_uri must be a string literal"))
+}
+
+fn limit_arg(expr: &Expr) -> Result<usize> {
+    let Expr::Literal(value, _) = expr else {
+        return plan_err!("{DELTA_HISTORY_FUNCTION_NAME}: limit must be a literal, got {expr}");
+    };
+    match value.cast_to(&DataType::Int64)? {
+        ScalarValue::Int64(Some(limit)) if limit > 0 => Ok(limit as usize),
+        _ => plan_err!("{DELTA_HISTORY_FUNCTION_NAME}: limit must be positive, got {value}"),
+    }
+}
+
+async fn load_table(
+    uri: &str,
+    storage_options: HashMap<String, String>,
+) -> DeltaResult<DeltaTable> {
+    DeltaTableBuilder::from_url(parse_table_uri(uri)?)?
+        .with_storage_options(storage_options)
+        .load()
+        .await
+}
+
+fn memory_table(batch: RecordBatch) -> Result<Arc<dyn TableProvider>> {
+    Ok(Arc::new(MemTable::try_new(
+        batch.schema(),
+        vec![vec![batch]],
+    )?))
+}
+
+fn history_batch(commits: Vec<(Version, CommitInfo)>) -> Result<RecordBatch> {
+    let mut version = Int64Builder::new();
+    let mut timestamp = TimestampMillisecondBuilder::new().with_timezone("UTC");
+    let mut user_id = StringBuilder::new();
+    let mut user_name = StringBuilder::new();
+    let mut operation = StringBuilder::new();
+    let mut operation_parameters =
+        StringMapBuilder::new(None, StringBuilder::new(), StringBuilder::new());
+    let mut operation_metrics =
+        StringMapBuilder::new(None, StringBuilder::new(), StringBuilder::new());
+    let mut read_version = Int64Builder::new();
+    let mut isolation_level = StringBuilder::new();
+    let mut is_blind_append = BooleanBuilder::new();
+    let mut engine_info = StringBuilder::new();
+    let mut user_metadata = StringBuilder::new();
+
+    for (commit_version, info) in commits {
+        version.append_value(commit_version as i64);
+        timestamp.append_option(info.in_commit_timestamp.or(info.timestamp));
+        user_id.append_option(info.user_id);
+        user_name.append_option(info.user_name);
+        operation.append_option(info.operation);
+        append_json_map(
+            &mut operation_parameters,
+            info.operation_parameters.as_ref(),
+        )?;
+        let metrics = match info.info.get("operationMetrics") {
+            Some(Value::Object(metrics)) => Some(metrics),
+            _ => None,
+        };
+        append_json_map(&mut operation_metrics, metrics)?;
+        read_version.append_option(info.read_version.map(|version| version as i64));
+        isolation_level.append_option(info.isolation_level);
+        is_blind_append.append_option(info.is_blind_append);
+        engine_info.append_option(info.engine_info);
+        user_metadata.append_option(info.user_metadata);
+    }
+
+    Ok(RecordBatch::try_from_iter_with_nullable([
+        ("version", Arc::new(version.finish()) as ArrayRef, false),
+        ("timestamp", Arc::new(timestamp.finish()), true),
+        ("user_id", Arc::new(user_id.finish()), true),
+        ("user_name", Arc::new(user_name.finish()), true),
+        ("operation", Arc::new(operation.finish()), true),
+        (
+            "operation_parameters",
+            Arc::new(operation_parameters.finish()),
+            true,
+        ),
+        (
+            "operation_metrics",
+            Arc::new(operation_metrics.finish()),
+            true,
+        ),
+        ("read_version", Arc::new(read_version.finish()), true),
+        ("isolation_level", Arc::new(isolation_level.finish()), true),
+        ("is_blind_append", Arc::new(is_blind_append.finish()), true),
+        ("engine_info", Arc::new(engine_info.finish()), true),
+        ("user_metadata", Arc::new(user_metadata.finish()), true),
+    ])?)
+}
+
+/// Append `entries` to `builder`, rendering values other than strings as JSON.
+fn append_json_map<'a>(
+    builder: &mut StringMapBuilder,
+    entries: Option<impl IntoIterator<Item = (&'a String, &'a Value)>>,
+) -> Result<()> {
+    let Some(entries) = entries else {
+        builder.append(false)?;
+        return Ok(());
+    };
+    for (key, value) in entries {
+        builder.keys().append_value(key);
+        match value {
+            Value::String(value) => builder.values().append_value(value),
+            Value::Null => builder.values().append_null(),
+            value => builder.values().append_value(value.to_string()),
+        }
+    }
+    builder.append(true)?;
+    Ok(())
+}
+
+fn detail_batch(table: &DeltaTable) -> Result<RecordBatch> {
+    let snapshot = table.snapshot()?.snapshot();
+    let metadata = snapshot.metadata();
+    let protocol = snapshot.protocol();
+    let log_data = snapshot.log_data();
+
+    let mut id = StringBuilder::new();
+    let mut name = StringBuilder::new();
+    let mut description = StringBuilder::new();
+    let mut location = StringBuilder::new();
+    let mut created_at = TimestampMillisecondBuilder::new().with_timezone("UTC");
+    let mut last_modified = TimestampMillisecondBuilder::new().with_timezone("UTC");
+    let mut version = Int64Builder::new();
+    let mut partition_columns = ListBuilder::new(StringBuilder::new());
+    let mut num_files = Int64Builder::new();
+    let mut size_in_bytes = Int64Builder::new();
+    let mut properties = StringMapBuilder::new(None, StringBuilder::new(), StringBuilder::new());
+    let mut min_reader_version = Int64Builder::new();
+    let mut min_writer_version = Int64Builder::new();
+    let mut table_features = ListBuilder::new(StringBuilder::new());
+
+    id.append_value(metadata.id());
+    name.append_option(metadata.name());
+    description.append_option(metadata.description());
+    location.append_value(table.table_url());
+    created_at.append_option(metadata.created_time());
+    last_modified.append_option(snapshot.version_timestamp(snapshot.version()));
+    version.append_value(snapshot.version() as i64);
+    partition_columns.append_value(metadata.partition_columns().iter().map(Some));
+    num_files.append_value(log_data.num_files() as i64);
+    size_in_bytes.append_value(log_data.iter().map(|file| file.size()).sum());
+    for (key, value) in metadata.configuration() {
+        properties.keys().append_value(key);
+        properties.values().append_value(value);
+    }
+    properties.append(true)?;
+    min_reader_version.append_value(protocol.min_reader_version() as i64);
+    min_writer_version.append_value(protocol.min_writer_version() as i64);
+    let mut features = protocol
+        .reader_features()
+        .into_iter()
+        .chain(protocol.writer_features())
+        .flatten()
+        .map(|feature| feature.to_string())
+        .collect::<Vec<_>>();
+    features.sort();
+    features.dedup();
+    table_features.append_value(features.into_iter().map(Some));
+
+    Ok(RecordBatch::try_from_iter_with_nullable([
+        (
+            "format",
+            Arc::new(StringArray::from(vec!["delta"])) as ArrayRef,
+            false,
+        ),
+        ("id", Arc::new(id.finish()), false),
+        ("name", Arc::new(name.finish()), true),
+        ("description", Arc::new(description.finish()), true),
+        ("location", Arc::new(location.finish()), false),
+        ("created_at", Arc::new(created_at.finish()), true),
+        ("last_modified", Arc::new(last_modified.finish()), true),
+        ("version", Arc::new(version.finish()), false),
+        (
+            "partition_columns",
+            Arc::new(partition_columns.finish()),
+            false,
+        ),
+        ("num_files", Arc::new(num_files.finish()), false),
+        ("size_in_bytes", Arc::new(size_in_bytes.finish()), false),
+        ("properties", Arc::new(properties.finish()), false),
+        (
+            "min_reader_version",
+            Arc::new(min_reader_version.finish()),
+            false,
+        ),
+        (
+            "min_writer_version",
+            Arc::new(min_writer_version.finish()),
+            false,
+        ),
+        ("table_features", Arc::new(table_features.finish()), false),
+    ])?)
+}
+
+/// Run `future` to completion from the synchronous planning of a table function.
+///
+/// The future runs on a separate thread with its own runtime, since blocking on the runtime the
+/// query is planned on could deadlock when it only has a single thread.
+pub(crate) fn block_on<F, T, E>(future: F) -> Result<T>
+where
+    F: Future<Output = std::result::Result<T, E>> + Send,
+    T: Send,
+    E: Into<DataFusionError> + Send,
+{
+    std::thread::scope(|scope| {
+        scope
+            .spawn(|| {
+                let runtime = tokio::runtime::Builder::new_current_thread()
+                    .enable_all()
+                    .build()
+                    .map_err(|err| {
+                        DataFusionError::Context(
+                            "failed to create runtime for table function".to_string(),
+                            Box::new(DataFusionError::IoError(err)),
+                        )
+                    })?;
+                runtime.block_on(future).map_err(Into::into)
+            })
+            .join()
+            .unwrap_or_else(|panic| std::panic::resume_unwind(panic))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+
+    use datafusion::assert_batches_eq;
+    use datafusion::prelude::SessionContext;
+
+    use crate::delta_datafusion::create_session;
+
+    fn table_uri() -> String {
+        std::fs::canonicalize(Path::new("../test/tests/data/simple_table"))
+            .unwrap()
+            .to_string_lossy()
+            .to_string()
+    }
+
+    #[tokio::test]
+    async fn test_delta_history_function() -> crate::test_utils::TestResult {
+        let ctx: SessionContext = create_session().into();
+        let sql = format!(
+            "SELECT version, operation FROM delta_history('{}')",
+            table_uri()
+        );
+        let batches = ctx.sql(&sql).await?.collect().await?;
+        assert_batches_eq!(
+            [
+                "+---------+-----------+",
+                "| version | operation |",
+                "+---------+-----------+",
+                "| 4       | DELETE    |",
+                "| 3       | UPDATE    |",
+                "| 2       | WRITE     |",
+                "| 1       | MERGE     |",
+                "| 0       | WRITE     |",
+                "+---------+-----------+",
+            ],
+            &batches
+        );
+
+        let sql = format!(
+            "SELECT version FROM delta_history('{}', 2) WHERE operation = 'DELETE'",
+            table_uri()
+        );
+        let batches = ctx.sql(&sql).await?.collect().await?;
+        assert_batches_eq!(
+            [
+                "+---------+",
+                "| version |",
+                "+---------+",
+                "| 4       |",
+                "+---------+"
+            ],
+            &batches
+        );
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_delta_detail_function() -> crate::test_utils::TestResult {
+        let ctx: SessionContext = create_session().into();
+        let sql = format!(
+            "SELECT format, id, version, min_reader_version, min_writer_version FROM delta_detail('{}')",
+            table_uri()
+        );
+        let batches = ctx.sql(&sql).await?.collect().await?;
+        assert_batches_eq!(
+            [
+                "+--------+--------------------------------------+---------+--------------------+--------------------+",
+                "| format | id                                   | version | min_reader_version | min_writer_version |",
+                "+--------+--------------------------------------+---------+--------------------+--------------------+",
+                "| delta  | 5fba94ed-9794-4965-ba6e-6ee3c0d22af9 | 4       | 1                  | 2                  |",
+                "+--------+--------------------------------------+---------+--------------------+--------------------+",
+            ],
+            &batches
+        );
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_invalid_arguments() {
+        let ctx: SessionContext = create_session().into();
+        assert!(ctx.sql("SELECT * FROM delta_history(1)").await.is_err());
+        assert!(ctx.sql("SELECT * FROM delta_detail()").await.is_err());
+        let sql = format!("SELECT * FROM delta_history('{}', 0)", table_uri());
+        assert!(ctx.sql(&sql).await.is_err());
+    }
+}
@@ -25,12 +25,16 @@ use crate::delta_datafusion::table_provider::next::SnapshotWrapper;
 use crate::delta_datafusion::{DataFusionMixins as _, FindFilesExprProperties};
 use crate::kernel::{Add, EagerSnapshot, Snapshot, Version};
 use crate::logstore::{LogStore, LogStoreExt as _};
+#[cfg(feature = "parquet-encryption")]
+use crate::parquet_encryption::KeyManagementClient;
 use crate::table::normalize_table_url;
 use crate::{DeltaResult, DeltaTable, DeltaTableError};
 
 mod data_sink;
 pub(crate) mod next;
 
+pub use data_sink::DeltaDataSink;
+
 const PATH_COLUMN: &str = "__delta_rs_path";
 
 pub(crate) fn resolve_file_column_name(
@@ -155,10 +159,33 @@ impl DeltaScanConfigBuilder {
             enable_parquet_pushdown: self.enable_parquet_pushdown,
             schema: self.schema.clone(),
             schema_force_view_types: true,
+            target_partitions: None,
+            max_files_per_partition: None,
+            file_order: ScanFileOrder::Log,
+            row_filter: None,
+            denied_columns: Vec::new(),
+            #[cfg(feature = "parquet-encryption")]
+            key_management: None,
         })
     }
 }
 
+/// Order in which data files are assigned to the partitions of a Delta scan.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ScanFileOrder {
+    /// Keep the order in which files are returned from the log.
+    #[default]
+    Log,
+    /// Largest files first, distributed so every partition reads a similar number of bytes.
+    Size,
+    /// Order files by their partition values and then by the minimum values of their columns.
+    ///
+    /// This preserves the clustering of the data files, and the scan reports its output
+    /// as ordered by the partition columns. Minimum values are only known for columns
+    /// referenced in the scan filters.
+    Clustered,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 /// Include additional metadata columns during a [`crate::delta_datafusion::DeltaScanNext`]
 pub struct DeltaScanConfig {
@@ -167,12 +194,58 @@ pub struct DeltaScanConfig {
     /// Wrap partition values in a dictionary encoding, defaults to true
     pub wrap_partition_values: bool,
     /// Allow pushdown of the scan filter, defaults to true
+    ///
+    /// When disabled, the filter is still used to prune Parquet row groups via
+    /// statistics and bloom filters, but not evaluated while decoding rows.
     pub enable_parquet_pushdown: bool,
     /// If true, parquet reader will read columns of `Utf8`/`Utf8Large`
     /// with Utf8View, and `Binary`/`BinaryLarge` with `BinaryView`
     pub schema_force_view_types: bool,
     /// Schema to read as
     pub schema: Option<SchemaRef>,
+    /// Number of partitions data files are distributed across.
+    ///
+    /// When unset and no other file layout option is configured, DataFusion may split
+    /// the scan as it sees fit. Otherwise defaults to the session's target partitions.
+    #[serde(default)]
+    pub target_partitions: Option<usize>,
+    /// Maximum number of data files read by a single partition.
+    #[serde(default)]
+    pub max_files_per_partition: Option<usize>,
+    /// Order in which data files are read, defaults to [`ScanFileOrder::Log`]
+    #[serde(default)]
+    pub file_order: ScanFileOrder,
+    /// Filter every row returned by the provider must satisfy.
+    ///
+    /// The filter is applied to all scans, regardless of the filters of the query, and may
+    /// reference columns listed in [`denied_columns`](Self::denied_columns). Configs with a
+    /// row filter can not be serialized, so the filter is never dropped silently.
+    #[serde(
+        default,
+        skip_deserializing,
+        skip_serializing_if = "Option::is_none",
+        serialize_with = "serialize_row_filter"
+    )]
+    pub row_filter: Option<Expr>,
+    /// Columns hidden from the schema of the provider.
+    #[serde(default)]
+    pub denied_columns: Vec<String>,
+    /// Client resolving the keys of encrypted data files.
+    ///
+    /// The client is not serialized, deserialized configs read with the client configured for
+    /// the table, if any.
+    #[cfg(feature = "parquet-encryption")]
+    #[serde(skip)]
+    pub key_management: Option<Arc<dyn KeyManagementClient>>,
+}
+
+fn serialize_row_filter<S: serde::Serializer>(
+    _row_filter: &Option<Expr>,
+    _serializer: S,
+) -> Result<S::Ok, S::Error> {
+    Err(serde::ser::Error::custom(
+        "DeltaScanConfig with a row filter can not be serialized",
+    ))
 }
 
 impl Default for DeltaScanConfig {
@@ -190,6 +263,13 @@ impl DeltaScanConfig {
             enable_parquet_pushdown: true,
             schema_force_view_types: true,
             schema: None,
+            target_partitions: None,
+            max_files_per_partition: None,
+            file_order: ScanFileOrder::Log,
+            row_filter: None,
+            denied_columns: Vec::new(),
+            #[cfg(feature = "parquet-encryption")]
+            key_management: None,
         }
     }
 
@@ -203,6 +283,13 @@ impl DeltaScanConfig {
             enable_parquet_pushdown: config_options.execution.parquet.pushdown_filters,
             schema_force_view_types: config_options.execution.parquet.schema_force_view_types,
             schema: None,
+            target_partitions: None,
+            max_files_per_partition: None,
+            file_order: ScanFileOrder::Log,
+            row_filter: None,
+            denied_columns: Vec::new(),
+            #[cfg(feature = "parquet-encryption")]
+            key_management: None,
         }
     }
 
@@ -233,6 +320,60 @@ impl DeltaScanConfig {
         self.schema = Some(schema);
         self
     }
+
+    /// Number of partitions the data files are distributed across
+    pub fn with_target_partitions(mut self, target_partitions: usize) -> Self {
+        self.target_partitions = Some(target_partitions);
+        self
+    }
+
+    /// Maximum number of data files read by a single partition
+    pub fn with_max_files_per_partition(mut self, max_files: usize) -> Self {
+        self.max_files_per_partition = Some(max_files);
+        self
+    }
+
+    /// Order in which data files are assigned to partitions
+    pub fn with_file_order(mut self, file_order: ScanFileOrder) -> Self {
+        self.file_order = file_order;
+        self
+    }
+
+    /// Only return rows matching `row_filter` from all scans of the provider
+    ///
+    /// Multiple calls are combined, so rows must match all of the filters.
+    pub fn with_row_filter(mut self, row_filter: Expr) -> Self {
+        self.row_filter = Some(match self.row_filter.take() {
+            Some(existing) => existing.and(row_filter),
+            None => row_filter,
+        });
+        self
+    }
+
+    /// Hide the given columns from the schema of the provider
+    ///
+    /// Hidden columns can not be projected or filtered on by queries,
+    /// but may still be referenced by the [row filter](Self::with_row_filter).
+    pub fn with_denied_columns(
+        mut self,
+        columns: impl IntoIterator<Item = impl Into<String>>,
+    ) -> Self {
+        self.denied_columns
+            .extend(columns.into_iter().map(|column| column.into()));
+        self
+    }
+
+    /// Decrypt encrypted data files with the keys of `client`
+    #[cfg(feature = "parquet-encryption")]
+    pub fn with_key_management(mut self, client: Arc<dyn KeyManagementClient>) -> Self {
+        self.key_management = Some(client);
+        self
+    }
+
+    /// Whether a row filter or denied columns restrict what the provider exposes
+    pub(crate) fn has_access_policy(&self) -> bool {
+        self.row_filter.is_some() || !self.denied_columns.is_empty()
+    }
 }
 
 /// Builder for a datafusion [TableProvider] for a Delta table
@@ -13,12 +13,22 @@ use datafusion::{
 use datafusion_datasource::sink::DataSink;
 use futures::{StreamExt as _, TryStreamExt as _};
 use itertools::Itertools as _;
+use parking_lot::RwLock;
+#[cfg(feature = "parquet-encryption")]
+use parquet::basic::Compression;
 use uuid::Uuid;
 
+#[cfg(feature = "parquet-encryption")]
+use crate::parquet_encryption::encrypt_writer_properties;
+#[cfg(feature = "parquet-encryption")]
+use crate::parquet_utils::default_writer_properties;
 use crate::{
     cast_record_batch,
     delta_datafusion::{ColumnMappingState, DataFusionMixins as _},
-    kernel::{Action, EagerSnapshot, transaction::CommitBuilder},
+    kernel::{
+        Action, EagerSnapshot,
+        transaction::{CommitBuilder, CommitProperties},
+    },
     logstore::LogStoreRef,
     operations::write::{WriterStatsConfig, execution::write_streams, writer::WriterConfig},
     protocol::{DeltaOperation, SaveMode},
@@ -32,12 +42,14 @@ use crate::{
 pub struct DeltaDataSink {
     /// The log store
     log_store: LogStoreRef,
-    /// The snapshot
-    snapshot: EagerSnapshot,
+    /// The snapshot, advanced to the committed version after every write
+    snapshot: RwLock<EagerSnapshot>,
     /// The save mode
     save_mode: SaveMode,
     /// The schema
     schema: SchemaRef,
+    /// Additional information to add to the commits
+    commit_properties: CommitProperties,
     /// Metrics for monitoring throughput
     metrics: ExecutionPlanMetricsSet,
 }
@@ -48,18 +60,33 @@ pub struct DeltaDataSink {
 /// stream [`RecordBatch`]es into a Delta table. It encapsulates everything
 /// needed to perform an insert/append/overwrite operation, including
 /// transaction log access, snapshot state, and session configuration.
+///
+/// The sink can be executed repeatedly, e.g. for micro-batch inserts. Every execution commits
+/// its data on top of the version committed by the previous one.
 impl DeltaDataSink {
     /// Create a new [`DeltaDataSink`]
     pub fn new(log_store: LogStoreRef, snapshot: EagerSnapshot, save_mode: SaveMode) -> Self {
         Self {
             log_store,
             schema: snapshot.read_schema(),
-            snapshot,
+            snapshot: RwLock::new(snapshot),
             save_mode,
+            commit_properties: CommitProperties::default(),
             metrics: ExecutionPlanMetricsSet::new(),
         }
     }
 
+    /// Additional metadata to be added to the commits of the sink
+    pub fn with_commit_properties(mut self, commit_properties: CommitProperties) -> Self {
+        self.commit_properties = commit_properties;
+        self
+    }
+
+    /// The snapshot the next write is committed on top of
+    pub fn snapshot(&self) -> EagerSnapshot {
+        self.snapshot.read().clone()
+    }
+
     /// Create a streaming transformed version of the input that converts dictionary columns
     /// This is used to convert dictionary columns to their native types
     fn create_converted_stream(
@@ -105,12 +132,13 @@ impl DataSink for DeltaDataSink {
         data: SendableRecordBatchStream,
         _context: &Arc<TaskContext>,
     ) -> datafusion::common::Result<u64> {
-        let target_schema = self.snapshot.input_schema();
-        let table_props = self.snapshot.table_configuration().table_properties();
+        let snapshot = self.snapshot();
+        let target_schema = snapshot.input_schema();
+        let table_props = snapshot.table_configuration().table_properties();
 
         let operation_id = Uuid::new_v4();
         let stream = self.create_converted_stream(data, target_schema.clone());
-        let logical_partition_columns = self.snapshot.metadata().partition_columns();
+        let logical_partition_columns = snapshot.metadata().partition_columns();
         let object_store = self.log_store.object_store(Some(operation_id));
         let total_rows_metric = MetricBuilder::new(&self.metrics).counter("total_rows", 0);
         let stream = {
@@ -125,19 +153,18 @@ impl DataSink for DeltaDataSink {
                 }),
             )) as SendableRecordBatchStream
         };
-        let column_mapping =
-            ColumnMappingState::from_table_config(self.snapshot.table_configuration());
-        let stats_config = WriterStatsConfig::from_config(self.snapshot.table_configuration());
+        let column_mapping = ColumnMappingState::from_table_config(snapshot.table_configuration());
+        let stats_config = WriterStatsConfig::from_config(snapshot.table_configuration());
         let (stream, table_schema, physical_partition_columns, random_prefix_length) =
             match &column_mapping {
                 None => (
                     stream,
-                    self.snapshot.read_schema(),
+                    snapshot.read_schema(),
                     logical_partition_columns.to_vec(),
                     None,
                 ),
                 Some(state) => {
-                    let physical_schema = state.physical_schema(&self.snapshot.read_schema());
+                    let physical_schema = state.physical_schema(&snapshot.read_schema());
                     let physical_partition_columns = state
                         .physical_partition_columns(logical_partition_columns)
                         .map_err(|e| DataFusionError::External(Box::new(e)))?;
@@ -158,16 +185,31 @@ impl DataSink for DeltaDataSink {
                     )
                 }
             };
+        #[cfg(not(feature = "parquet-encryption"))]
+        let writer_properties = None;
+        #[cfg(feature = "parquet-encryption")]
+        let writer_properties = if snapshot.load_config().parquet_encryption.is_some() {
+            let writer_properties = encrypt_writer_properties(
+                snapshot.load_config(),
+                default_writer_properties(Compression::SNAPPY),
+            )
+            .map_err(|e| DataFusionError::External(Box::new(e)))?;
+            Some(writer_properties)
+        } else {
+            None
+        };
         let config = WriterConfig::new(
             table_schema,
             physical_partition_columns,
-            None,
+            writer_properties,
             Some(table_props.target_file_size()),
             None,
             stats_config.num_indexed_cols,
             stats_config.stats_columns,
         )
-        .with_random_prefix_length(random_prefix_length);
+        .with_random_prefix_length(random_prefix_length)
+        .with_multipart_config(self.log_store.config().options().multipart_config())
+        .with_distinct_count_columns(stats_config.distinct_count_columns);
 
         let (adds, write_metrics) = write_streams(vec![stream], object_store, config)
             .await
@@ -178,7 +220,7 @@ impl DataSink for DeltaDataSink {
 
         if self.save_mode == SaveMode::Overwrite {
             actions.extend(
-                self.snapshot
+                snapshot
                     .file_views(&self.log_store, None)
                     .map_ok(|f| Action::Remove(f.remove_action(true)))
                     .try_collect::<Vec<_>>()
@@ -197,13 +239,18 @@ impl DataSink for DeltaDataSink {
             predicate: None,
         };
 
-        CommitBuilder::default()
+        let commit = CommitBuilder::from(self.commit_properties.clone())
             .with_actions(actions)
             .with_operation_id(operation_id)
-            .build(Some(&self.snapshot), self.log_store.clone(), operation)
+            .build(Some(&snapshot), self.log_store.clone(), operation)
             .await
             .map_err(|e| DataFusionError::External(Box::new(e)))?;
 
+        let mut current = self.snapshot.write();
+        if current.version() < commit.version() {
+            *current = commit.snapshot.snapshot;
+        }
+
         Ok(total_rows)
     }
 }
@@ -214,3 +261,63 @@ impl DisplayAs for DeltaDataSink {
         write!(f, "DeltaDataSink")
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use arrow::array::{Int64Array, RecordBatch};
+    use arrow_schema::{DataType, Field, Schema};
+    use datafusion::assert_batches_sorted_eq;
+    use datafusion::datasource::{MemTable, TableProvider as _, sink::DataSinkExec};
+    use datafusion::physical_plan::{ExecutionPlan, collect};
+    use datafusion::prelude::SessionContext;
+
+    use super::*;
+    use crate::DeltaTable;
+    use crate::kernel::{DataType as DeltaDataType, PrimitiveType, StructField};
+
+    async fn input_plan(ctx: &SessionContext, values: Vec<i64>) -> Arc<dyn ExecutionPlan> {
+        let schema = Arc::new(Schema::new(vec![Field::new("id", DataType::Int64, true)]));
+        let batch =
+            RecordBatch::try_new(schema.clone(), vec![Arc::new(Int64Array::from(values))]).unwrap();
+        let table = MemTable::try_new(schema, vec![vec![batch]]).unwrap();
+        table.scan(&ctx.state(), None, &[], None).await.unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_sink_commits_repeated_writes() -> crate::test_utils::TestResult {
+        let table = DeltaTable::new_in_memory()
+            .create()
+            .with_columns(vec![StructField::new(
+                "id",
+                DeltaDataType::Primitive(PrimitiveType::Long),
+                true,
+            )])
+            .await?;
+        let ctx = SessionContext::new();
+        let sink = Arc::new(DeltaDataSink::new(
+            table.log_store(),
+            table.snapshot()?.snapshot().clone(),
+            SaveMode::Append,
+        ));
+
+        for values in [vec![1, 2], vec![3]] {
+            let exec = DataSinkExec::new(input_plan(&ctx, values).await, sink.clone(), None);
+            collect(Arc::new(exec), ctx.task_ctx()).await?;
+        }
+        assert_eq!(sink.snapshot().version(), 2);
+        assert_eq!(sink.snapshot().log_data().num_files(), 2);
+
+        let mut table = table;
+        table.update_state().await?;
+        ctx.register_table("delta", table.table_provider().await?)?;
+        let batches = ctx.sql("SELECT id FROM delta").await?.collect().await?;
+        assert_batches_sorted_eq!(
+            [
+                "+----+", "| id |", "+----+", "| 1  |", "| 2  |", "| 3  |", "+----+"
+            ],
+            &batches
+        );
+
+        Ok(())
+    }
+}
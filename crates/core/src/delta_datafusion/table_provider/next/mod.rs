@@ -29,14 +29,15 @@ use std::collections::HashSet;
 use std::{borrow::Cow, fmt, sync::Arc};
 
 use arrow::datatypes::{DataType, Field, Schema, SchemaRef};
-use datafusion::common::{DataFusionError, Result};
+use datafusion::common::{DFSchema, DataFusionError, Result, Statistics};
 use datafusion::datasource::{TableType, sink::DataSinkExec};
 use datafusion::logical_expr::{TableProviderFilterPushDown, dml::InsertOp};
 use datafusion::prelude::Expr;
 use datafusion::{
     catalog::{Session, TableProvider},
     logical_expr::LogicalPlan,
-    physical_plan::ExecutionPlan,
+    physical_expr::{PhysicalExpr, expressions::Column},
+    physical_plan::{ExecutionPlan, filter::FilterExec, projection::ProjectionExec},
 };
 use delta_kernel::{Engine, table_configuration::TableConfiguration, table_features::TableFeature};
 use object_store::path::Path;
@@ -52,6 +53,7 @@ use crate::DeltaTableError;
 use crate::delta_datafusion::DeltaScanConfig;
 use crate::delta_datafusion::engine::DataFusionEngine;
 use crate::delta_datafusion::table_provider::TableProviderBuilder;
+use crate::delta_datafusion::{DataValidationExec, validation_predicates};
 use crate::kernel::transaction::{PROTOCOL, TransactionError};
 use crate::kernel::{Add, EagerSnapshot, SendableScanMetadataStream, Snapshot};
 use crate::logstore::LogStoreRef;
@@ -531,6 +533,15 @@ impl DeltaScan {
         } else {
             scan_schema.clone()
         };
+        if let Some(column) = config
+            .denied_columns
+            .iter()
+            .find(|column| full_schema.index_of(column).is_err())
+        {
+            return Err(DataFusionError::Plan(format!(
+                "Denied column '{column}' does not exist in the table schema"
+            )));
+        }
         Ok(Self {
             snapshot,
             config,
@@ -705,34 +716,32 @@ impl DeltaScan {
     pub fn builder() -> TableProviderBuilder {
         TableProviderBuilder::new()
     }
-}
-
-#[async_trait::async_trait]
-impl TableProvider for DeltaScan {
-    fn schema(&self) -> SchemaRef {
-        self.full_schema.clone()
-    }
 
-    fn table_type(&self) -> TableType {
-        TableType::Base
-    }
-
-    fn get_table_definition(&self) -> Option<&str> {
-        None
-    }
-
-    fn get_logical_plan(&self) -> Option<Cow<'_, LogicalPlan>> {
-        None
+    /// Schema exposed to queries, without the columns denied by the scan config.
+    fn provider_schema(&self) -> SchemaRef {
+        if self.config.denied_columns.is_empty() {
+            return self.full_schema.clone();
+        }
+        let fields = self
+            .full_schema
+            .fields()
+            .iter()
+            .filter(|field| !self.config.denied_columns.contains(field.name()))
+            .cloned()
+            .collect::<Vec<_>>();
+        Arc::new(Schema::new_with_metadata(
+            fields,
+            self.full_schema.metadata().clone(),
+        ))
     }
 
-    async fn scan(
+    async fn scan_files(
         &self,
         session: &dyn Session,
         projection: Option<&Vec<usize>>,
         filters: &[Expr],
         limit: Option<usize>,
     ) -> Result<Arc<dyn ExecutionPlan>> {
-        self.ensure_read_ready(session)?;
         let engine = DataFusionEngine::new_from_session(session);
         let contract = ProjectedScanContract::try_new(
             self.scan_schema.clone(),
@@ -767,12 +776,142 @@ impl TableProvider for DeltaScan {
         .await
     }
 
+    /// Scan the table enforcing the row filter and denied columns of the scan config.
+    ///
+    /// The projection refers to the [provider schema](Self::provider_schema). Columns
+    /// referenced by the row filter are read in addition to the projected columns, and the
+    /// filter is always evaluated on top of the scan, even if it was pushed into the reads.
+    async fn scan_with_access_policy(
+        &self,
+        session: &dyn Session,
+        projection: Option<&Vec<usize>>,
+        filters: &[Expr],
+        limit: Option<usize>,
+    ) -> Result<Arc<dyn ExecutionPlan>> {
+        let provider_schema = self.provider_schema();
+        let output_columns = match projection {
+            Some(projection) => projection
+                .iter()
+                .map(|idx| provider_schema.field(*idx).name().clone())
+                .collect::<Vec<_>>(),
+            None => provider_schema
+                .fields()
+                .iter()
+                .map(|field| field.name().clone())
+                .collect::<Vec<_>>(),
+        };
+
+        let mut scan_projection = output_columns
+            .iter()
+            .map(|column| self.full_schema.index_of(column))
+            .collect::<Result<Vec<_>, _>>()?;
+        let mut scan_filters = filters.to_vec();
+        if let Some(row_filter) = &self.config.row_filter {
+            for column in row_filter.column_refs() {
+                let idx = self.full_schema.index_of(&column.name)?;
+                if !scan_projection.contains(&idx) {
+                    scan_projection.push(idx);
+                }
+            }
+            scan_filters.push(row_filter.clone());
+        }
+
+        // The scan may only apply the limit after the row filter was evaluated.
+        let scan_limit = self.config.row_filter.is_none().then_some(limit).flatten();
+        let mut plan = self
+            .scan_files(session, Some(&scan_projection), &scan_filters, scan_limit)
+            .await?;
+
+        if let Some(row_filter) = &self.config.row_filter {
+            let df_schema = DFSchema::try_from(plan.schema())?;
+            let predicate = session.create_physical_expr(row_filter.clone(), &df_schema)?;
+            plan = Arc::new(FilterExec::try_new(predicate, plan)?);
+        }
+
+        let plan_schema = plan.schema();
+        let exprs = output_columns
+            .into_iter()
+            .map(|name| {
+                let column = Column::new_with_schema(&name, &plan_schema)?;
+                Ok((Arc::new(column) as Arc<dyn PhysicalExpr>, name))
+            })
+            .collect::<Result<Vec<_>>>()?;
+        let is_identity = exprs.len() == plan_schema.fields().len()
+            && exprs
+                .iter()
+                .enumerate()
+                .all(|(idx, (_, name))| plan_schema.field(idx).name() == name);
+        if is_identity {
+            return Ok(plan);
+        }
+        Ok(Arc::new(ProjectionExec::try_new(exprs, plan)?))
+    }
+}
+
+#[async_trait::async_trait]
+impl TableProvider for DeltaScan {
+    fn schema(&self) -> SchemaRef {
+        self.provider_schema()
+    }
+
+    fn table_type(&self) -> TableType {
+        TableType::Base
+    }
+
+    fn get_table_definition(&self) -> Option<&str> {
+        None
+    }
+
+    fn statistics(&self) -> Option<Statistics> {
+        // Statistics of lazily loaded snapshots would require reading the log during planning.
+        let SnapshotWrapper::EagerSnapshot(snapshot) = &self.snapshot else {
+            return None;
+        };
+        let statistics = snapshot.log_data().statistics(&self.provider_schema());
+        // File selections, skipping predicates and row filters restrict the scan to a subset
+        // of the rows.
+        if self.file_selection.is_some()
+            || self.file_skipping_predicate.is_some()
+            || self.config.row_filter.is_some()
+        {
+            Some(statistics.to_inexact())
+        } else {
+            Some(statistics)
+        }
+    }
+
+    fn get_logical_plan(&self) -> Option<Cow<'_, LogicalPlan>> {
+        None
+    }
+
+    async fn scan(
+        &self,
+        session: &dyn Session,
+        projection: Option<&Vec<usize>>,
+        filters: &[Expr],
+        limit: Option<usize>,
+    ) -> Result<Arc<dyn ExecutionPlan>> {
+        self.ensure_read_ready(session)?;
+        if self.config.has_access_policy() {
+            return self
+                .scan_with_access_policy(session, projection, filters, limit)
+                .await;
+        }
+        self.scan_files(session, projection, filters, limit).await
+    }
+
     async fn insert_into(
         &self,
         state: &dyn Session,
         input: Arc<dyn ExecutionPlan>,
         insert_op: InsertOp,
     ) -> Result<Arc<dyn ExecutionPlan>> {
+        if self.config.has_access_policy() {
+            return Err(DataFusionError::Plan(
+                "DeltaScan with a row filter or denied columns does not support inserts"
+                    .to_string(),
+            ));
+        }
         let log_store = self.log_store.clone().ok_or_else(|| {
             DataFusionError::Plan(
                 "DeltaScan insert_into requires a runtime log_store handle".to_string(),
@@ -798,6 +937,15 @@ impl TableProvider for DeltaScan {
             }
         };
 
+        // Enforce nullability, invariants, check constraints and generated columns like any
+        // other write to the table.
+        let validations = validation_predicates(
+            state,
+            &DFSchema::try_from(input.schema())?,
+            snapshot.table_configuration(),
+        )?;
+        let input = DataValidationExec::try_new_with_predicates(state, input, validations)?;
+
         let data_sink = DeltaDataSink::new(log_store, snapshot, save_mode);
 
         Ok(Arc::new(DataSinkExec::new(
@@ -861,29 +1009,36 @@ mod tests {
         datatypes::{
             DataType as ArrowDataType, Field as ArrowField, Schema as ArrowSchema, TimeUnit,
         },
+        error::ArrowError,
         record_batch::RecordBatch,
     };
     use arrow_array::{
         DictionaryArray, UInt16Array,
         builder::{BinaryDictionaryBuilder, StringDictionaryBuilder},
+        cast::AsArray as _,
         types::UInt16Type,
     };
     use datafusion::{
         catalog::Session,
+        common::stats::Precision,
         datasource::MemTable,
         datasource::{
-            physical_plan::{FileScanConfig, ParquetSource},
+            physical_plan::{FileScanConfig, ParquetSource, parquet::ParquetAccessPlan},
             source::DataSource,
         },
         error::DataFusionError,
-        logical_expr::dml::InsertOp,
+        logical_expr::{dml::InsertOp, expr::Cast},
         physical_optimizer::pruning::PruningPredicate,
-        physical_plan::{ExecutionPlanVisitor, collect_partitioned, visit_execution_plan},
+        physical_plan::{
+            ExecutionPlanVisitor, collect_partitioned, metrics::MetricValue, visit_execution_plan,
+        },
         prelude::{col, lit},
+        scalar::ScalarValue,
     };
     use datafusion_datasource::file::FileSource as _;
     use datafusion_datasource::source::DataSourceExec;
     use futures::{StreamExt as _, TryStreamExt as _};
+    use parquet::file::properties::WriterProperties;
     use parquet::file::reader::FileReader as _;
     use parquet::file::serialized_reader::SerializedFileReader;
     use std::{
@@ -895,7 +1050,7 @@ mod tests {
     use super::*;
     use crate::{
         assert_batches_sorted_eq,
-        delta_datafusion::{DeltaScanConfig, session::create_session},
+        delta_datafusion::{DeltaScanConfig, ScanFileOrder, session::create_session},
         kernel::{
             Action, DataType, EagerSnapshot, PrimitiveType, ProtocolInner, Snapshot, StructField,
             StructType,
@@ -967,7 +1122,13 @@ mod tests {
     #[derive(Default)]
     struct DeltaScanVisitor {
         num_scanned: Option<usize>,
+        files_pruned_by_partition: Option<usize>,
+        files_pruned_by_stats: Option<usize>,
+        rows_skipped_by_dv: Option<usize>,
+        bytes_read: Option<usize>,
+        planning_time: Option<usize>,
         total_bytes_scanned: Option<usize>,
+        row_groups_pruned_bloom_filter: Option<usize>,
     }
 
     impl DeltaScanVisitor {
@@ -982,6 +1143,17 @@ mod tests {
             self.num_scanned = metrics
                 .sum_by_name("count_files_scanned")
                 .map(|v| v.as_usize());
+            self.files_pruned_by_partition = metrics
+                .sum_by_name("count_files_pruned_by_partition")
+                .map(|v| v.as_usize());
+            self.files_pruned_by_stats = metrics
+                .sum_by_name("count_files_pruned_by_stats")
+                .map(|v| v.as_usize());
+            self.rows_skipped_by_dv = metrics
+                .sum_by_name("count_rows_skipped_by_dv")
+                .map(|v| v.as_usize());
+            self.bytes_read = metrics.sum_by_name("bytes_read").map(|v| v.as_usize());
+            self.planning_time = metrics.sum_by_name("planning_time").map(|v| v.as_usize());
 
             Ok(true)
         }
@@ -997,11 +1169,24 @@ mod tests {
                 return Ok(true);
             };
 
-            let pq_metrics = scan_config
-                .metrics()
-                .clone_inner()
-                .sum_by_name("bytes_scanned");
-            self.total_bytes_scanned = pq_metrics.map(|v| v.as_usize());
+            let pq_metrics = scan_config.metrics().clone_inner();
+            self.total_bytes_scanned = pq_metrics
+                .sum_by_name("bytes_scanned")
+                .map(|v| v.as_usize());
+            self.row_groups_pruned_bloom_filter = Some(
+                pq_metrics
+                    .iter()
+                    .filter_map(|metric| match metric.value() {
+                        MetricValue::PruningMetrics {
+                            name,
+                            pruning_metrics,
+                        } if name == "row_groups_pruned_bloom_filter" => {
+                            Some(pruning_metrics.pruned())
+                        }
+                        _ => None,
+                    })
+                    .sum(),
+            );
 
             Ok(true)
         }
@@ -1087,6 +1272,24 @@ mod tests {
         table.write(vec![batch]).await
     }
 
+    async fn create_in_memory_id_table_with_files(
+        files: Vec<Vec<i64>>,
+    ) -> crate::DeltaResult<crate::DeltaTable> {
+        let mut table = create_in_memory_id_table().await?;
+        for values in files {
+            let batch = RecordBatch::try_new(
+                Arc::new(ArrowSchema::new(vec![ArrowField::new(
+                    "id",
+                    ArrowDataType::Int64,
+                    true,
+                )])),
+                vec![Arc::new(Int64Array::from(values))],
+            )?;
+            table = table.write(vec![batch]).await?;
+        }
+        Ok(table)
+    }
+
     async fn create_in_memory_id_table_with_unsupported_reader_protocol()
     -> crate::DeltaResult<crate::DeltaTable> {
         let schema = StructType::try_new(vec![StructField::new(
@@ -1376,6 +1579,38 @@ mod tests {
         Ok(())
     }
 
+    #[tokio::test]
+    async fn test_insert_into_enforces_check_constraints() -> TestResult {
+        let table = create_in_memory_id_table()
+            .await?
+            .add_constraint()
+            .with_constraint("id_positive", "id > 0")
+            .await?;
+        let log_store = table.log_store();
+        let provider = DeltaScan::builder()
+            .with_log_store(log_store.clone())
+            .build()
+            .await?;
+
+        let session = Arc::new(create_session().into_inner());
+        let state = session.state_ref().read().clone();
+        let input = build_insert_input(&state, provider.schema(), vec![1, -1]).await?;
+
+        let write_plan = provider
+            .insert_into(&state, input, InsertOp::Append)
+            .await?;
+        let err = collect_partitioned(write_plan, session.task_ctx())
+            .await
+            .unwrap_err();
+        assert!(
+            err.to_string().contains("Invalid data found"),
+            "unexpected error: {err}"
+        );
+        assert!(log_store.read_commit_entry(2).await?.is_none());
+
+        Ok(())
+    }
+
     #[tokio::test]
     async fn test_insert_into_rejects_replace() -> TestResult {
         let table = create_in_memory_id_table().await?;
@@ -1658,6 +1893,58 @@ mod tests {
         Ok(())
     }
 
+    #[tokio::test]
+    async fn test_scan_prunes_row_groups_with_bloom_filters() -> TestResult {
+        let schema = Arc::new(ArrowSchema::new(vec![ArrowField::new(
+            "id",
+            ArrowDataType::Int64,
+            true,
+        )]));
+        let writer_properties = WriterProperties::builder()
+            .set_bloom_filter_enabled(true)
+            .build();
+
+        // Both files have overlapping min/max statistics, so only the bloom
+        // filters can tell that the second file does not contain the value.
+        let mut table = crate::DeltaTable::new_in_memory();
+        for values in [vec![1, 3, 5, 7, 9], vec![2, 4, 6, 8, 10]] {
+            let batch =
+                RecordBatch::try_new(schema.clone(), vec![Arc::new(Int64Array::from(values))])?;
+            table = table
+                .write(vec![batch])
+                .with_save_mode(crate::protocol::SaveMode::Append)
+                .with_writer_properties(writer_properties.clone())
+                .await?;
+        }
+
+        // Without row level pushdown, the predicate is still used for pruning.
+        let provider = DeltaScan::new(
+            table.snapshot()?.snapshot().snapshot().clone(),
+            DeltaScanConfig::default().with_parquet_pushdown(false),
+        )?
+        .with_log_store(table.log_store());
+
+        let session = Arc::new(create_session().into_inner());
+        let state = session.state_ref().read().clone();
+        let plan = provider
+            .scan(&state, None, &[col("id").eq(lit(5i64))], None)
+            .await?;
+        let batches: Vec<_> = collect_partitioned(plan.clone(), session.task_ctx())
+            .await?
+            .into_iter()
+            .flatten()
+            .collect();
+        let num_rows: usize = batches.iter().map(|batch| batch.num_rows()).sum();
+        assert_eq!(num_rows, 5);
+
+        let mut visitor = DeltaScanVisitor::default();
+        visit_execution_plan(plan.as_ref(), &mut visitor)?;
+        assert_eq!(visitor.num_scanned, Some(2));
+        assert_eq!(visitor.row_groups_pruned_bloom_filter, Some(1));
+
+        Ok(())
+    }
+
     #[tokio::test]
     async fn test_boolean_predicate_does_not_require_minmax_stats() -> TestResult {
         let schema = Arc::new(ArrowSchema::new(vec![
@@ -2046,6 +2333,48 @@ mod tests {
         Ok(())
     }
 
+    #[tokio::test]
+    async fn test_scan_applies_deletion_vectors_as_parquet_row_selection() -> TestResult {
+        let log_store = TestTables::WithDvSmall.table_builder()?.build_storage()?;
+        let snapshot = Snapshot::try_new(&log_store, Default::default(), None).await?;
+        let provider = DeltaScan::new(snapshot, DeltaScanConfig::default())?;
+
+        let session = Arc::new(create_session().into_inner());
+        let state = session.state_ref().read().clone();
+        let plan = provider.scan(&state, None, &[], None).await?;
+
+        let exec = plan
+            .downcast_ref::<scan::DeltaScanExec>()
+            .expect("expected DeltaScanExec");
+        let data_source = exec.children()[0]
+            .downcast_ref::<DataSourceExec>()
+            .expect("expected DataSourceExec child");
+        let (file_scan_config, _) = data_source
+            .downcast_to_file_source::<ParquetSource>()
+            .expect("expected parquet file source");
+        let access_plans = file_scan_config
+            .file_groups
+            .iter()
+            .flat_map(|group| group.iter())
+            .filter_map(|file| file.extension::<ParquetAccessPlan>())
+            .collect::<Vec<_>>();
+        assert_eq!(access_plans.len(), 1);
+
+        let batches: Vec<_> = collect_partitioned(plan.clone(), session.task_ctx())
+            .await?
+            .into_iter()
+            .flatten()
+            .collect();
+        let num_rows: usize = batches.iter().map(|batch| batch.num_rows()).sum();
+        assert_eq!(num_rows, 8);
+
+        let mut visitor = DeltaScanVisitor::default();
+        visit_execution_plan(plan.as_ref(), &mut visitor)?;
+        assert_eq!(visitor.rows_skipped_by_dv, Some(2));
+
+        Ok(())
+    }
+
     #[tokio::test]
     async fn test_scan_with_file_selection_mutated_add_uses_snapshot_deletion_vector_metadata()
     -> TestResult {
@@ -2349,6 +2678,343 @@ mod tests {
         Ok(())
     }
 
+    #[tokio::test]
+    async fn test_point_lookup_on_data_column_skips_files_by_stats() -> TestResult {
+        let table =
+            create_in_memory_id_table_with_files(vec![vec![1, 2, 3], vec![4, 5, 6], vec![7, 8, 9]])
+                .await?;
+        let log_store = table.log_store();
+        let snapshot = Arc::new(Snapshot::try_new(&log_store, Default::default(), None).await?);
+
+        let session = Arc::new(create_session().into_inner());
+        let state = session.state_ref().read().clone();
+        let provider = DeltaScan::builder()
+            .with_snapshot(snapshot)
+            .with_log_store(log_store)
+            .build()
+            .await?;
+
+        let plan = provider.scan(&state, None, &[], None).await?;
+        let mut visitor = DeltaScanVisitor::default();
+        visit_execution_plan(plan.as_ref(), &mut visitor).unwrap();
+        assert_eq!(visitor.num_scanned, Some(3));
+
+        // literals as left behind by type coercion must not disable data skipping
+        let filters = [
+            col("id").eq(lit(5i64)),
+            col("id").eq(Expr::Cast(Cast::new(
+                Box::new(lit(5i32)),
+                ArrowDataType::Int64,
+            ))),
+            col("id").eq(lit(5u32)),
+        ];
+        for filter in filters {
+            let plan = provider
+                .scan(&state, None, std::slice::from_ref(&filter), None)
+                .await?;
+            let mut visitor = DeltaScanVisitor::default();
+            visit_execution_plan(plan.as_ref(), &mut visitor).unwrap();
+            assert_eq!(visitor.num_scanned, Some(1), "filter: {filter}");
+            assert_eq!(visitor.files_pruned_by_stats, Some(2), "filter: {filter}");
+            assert_eq!(
+                visitor.files_pruned_by_partition,
+                Some(0),
+                "filter: {filter}"
+            );
+        }
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_null_checks_skip_files_by_partition_values_and_null_counts() -> TestResult {
+        let schema = Arc::new(ArrowSchema::new(vec![
+            ArrowField::new("id", ArrowDataType::Int64, true),
+            ArrowField::new("part", ArrowDataType::Utf8, true),
+        ]));
+        let batch = RecordBatch::try_new(
+            schema,
+            vec![
+                Arc::new(Int64Array::from(vec![
+                    Some(1),
+                    Some(2),
+                    Some(3),
+                    None,
+                    Some(4),
+                    None,
+                ])),
+                Arc::new(StringArray::from(vec![
+                    Some("a"),
+                    Some("a"),
+                    None,
+                    Some("b"),
+                    Some("b"),
+                    Some("c"),
+                ])),
+            ],
+        )?;
+        let table = crate::DeltaTable::new_in_memory()
+            .create()
+            .with_columns([
+                StructField::new("id", DataType::Primitive(PrimitiveType::Long), true),
+                StructField::new("part", DataType::Primitive(PrimitiveType::String), true),
+            ])
+            .with_partition_columns(["part"])
+            .await?
+            .write(vec![batch])
+            .await?;
+        let log_store = table.log_store();
+        let snapshot = Arc::new(Snapshot::try_new(&log_store, Default::default(), None).await?);
+
+        let session = Arc::new(create_session().into_inner());
+        let state = session.state_ref().read().clone();
+        let provider = DeltaScan::builder()
+            .with_snapshot(snapshot)
+            .with_log_store(log_store)
+            .build()
+            .await?;
+
+        for (filter, expected_files) in [
+            (col("part").is_null(), 1),
+            (col("part").is_not_null(), 3),
+            (col("id").is_null(), 2),
+            (col("id").is_not_null(), 3),
+        ] {
+            let plan = provider
+                .scan(&state, None, std::slice::from_ref(&filter), None)
+                .await?;
+            let mut visitor = DeltaScanVisitor::default();
+            visit_execution_plan(plan.as_ref(), &mut visitor).unwrap();
+            assert_eq!(
+                visitor.num_scanned,
+                Some(expected_files),
+                "filter: {filter}"
+            );
+        }
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_row_filter_and_denied_columns_are_enforced() -> TestResult {
+        let schema = Arc::new(ArrowSchema::new(vec![
+            ArrowField::new("id", ArrowDataType::Int64, true),
+            ArrowField::new("tenant", ArrowDataType::Utf8, true),
+            ArrowField::new("secret", ArrowDataType::Utf8, true),
+        ]));
+        let batch = RecordBatch::try_new(
+            schema,
+            vec![
+                Arc::new(Int64Array::from(vec![1, 2, 3, 4])),
+                Arc::new(StringArray::from(vec!["a", "b", "a", "b"])),
+                Arc::new(StringArray::from(vec!["s1", "s2", "s3", "s4"])),
+            ],
+        )?;
+        let table = crate::DeltaTable::new_in_memory()
+            .create()
+            .with_columns([
+                StructField::new("id", DataType::Primitive(PrimitiveType::Long), true),
+                StructField::new("tenant", DataType::Primitive(PrimitiveType::String), true),
+                StructField::new("secret", DataType::Primitive(PrimitiveType::String), true),
+            ])
+            .await?
+            .write(vec![batch])
+            .await?;
+        let log_store = table.log_store();
+        let snapshot = Arc::new(Snapshot::try_new(&log_store, Default::default(), None).await?);
+
+        let config = DeltaScanConfig::new()
+            .with_row_filter(col("tenant").eq(lit("a")))
+            .with_denied_columns(["tenant", "secret"]);
+        let provider = DeltaScan::new(snapshot.clone(), config)?.with_log_store(log_store);
+        assert_eq!(provider.schema().fields().len(), 1);
+        assert_eq!(provider.schema().field(0).name(), "id");
+        assert!(serde_json::to_vec(&provider).is_err());
+
+        let session = create_session().into_inner();
+        session.register_table("delta_table", Arc::new(provider))?;
+
+        let batches = session
+            .sql("SELECT * FROM delta_table")
+            .await?
+            .collect()
+            .await?;
+        let expected = vec!["+----+", "| id |", "+----+", "| 1  |", "| 3  |", "+----+"];
+        assert_batches_sorted_eq!(&expected, &batches);
+
+        let batches = session
+            .sql("SELECT id FROM delta_table WHERE id > 1")
+            .await?
+            .collect()
+            .await?;
+        let expected = vec!["+----+", "| id |", "+----+", "| 3  |", "+----+"];
+        assert_batches_sorted_eq!(&expected, &batches);
+
+        assert!(session.sql("SELECT secret FROM delta_table").await.is_err());
+
+        let config = DeltaScanConfig::new().with_denied_columns(["missing"]);
+        assert!(DeltaScan::new(snapshot, config).is_err());
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_scan_metrics_report_pruned_files_and_bytes_read() -> TestResult {
+        let log_store = TestTables::Delta0_8_0Partitioned
+            .table_builder()?
+            .build_storage()?;
+        let snapshot = Arc::new(Snapshot::try_new(&log_store, Default::default(), None).await?);
+
+        let session = Arc::new(create_session().into_inner());
+        let state = session.state_ref().read().clone();
+        let provider = DeltaScan::builder()
+            .with_snapshot(snapshot)
+            .with_log_store(log_store)
+            .build()
+            .await?;
+
+        let filter = col("year").eq(lit("2021"));
+        let plan = provider
+            .scan(&state, None, std::slice::from_ref(&filter), None)
+            .await?;
+        let _batches: Vec<_> = collect_partitioned(plan.clone(), session.task_ctx()).await?;
+
+        let mut visitor = DeltaScanVisitor::default();
+        visit_execution_plan(plan.as_ref(), &mut visitor).unwrap();
+        assert_eq!(visitor.num_scanned, Some(3));
+        assert_eq!(visitor.files_pruned_by_partition, Some(3));
+        assert_eq!(visitor.files_pruned_by_stats, Some(0));
+        assert!(visitor.planning_time.is_some());
+        assert!(visitor.bytes_read.is_some_and(|bytes| bytes > 0));
+        assert_eq!(visitor.bytes_read, visitor.total_bytes_scanned);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_clustered_scan_orders_files_by_partition_values() -> TestResult {
+        let log_store = TestTables::Delta0_8_0Partitioned
+            .table_builder()?
+            .build_storage()?;
+        let snapshot = Snapshot::try_new(&log_store, Default::default(), None).await?;
+        let config = DeltaScanConfig::default()
+            .with_file_order(ScanFileOrder::Clustered)
+            .with_target_partitions(2);
+        let provider = DeltaScan::new(snapshot, config)?.with_log_store(log_store);
+
+        let session = Arc::new(create_session().into_inner());
+        let state = session.state_ref().read().clone();
+        let plan = provider.scan(&state, None, &[], None).await?;
+        assert_eq!(plan.properties().output_partitioning().partition_count(), 2);
+        let ordering = plan
+            .properties()
+            .output_ordering()
+            .expect("clustered scans are ordered");
+        assert_eq!(ordering.len(), 3);
+
+        let partitions = collect_partitioned(plan, session.task_ctx()).await?;
+        let years = partitions
+            .iter()
+            .map(|batches| {
+                let mut years = Vec::new();
+                for batch in batches {
+                    let column = arrow::compute::cast(
+                        batch.column_by_name("year").unwrap(),
+                        &ArrowDataType::Utf8,
+                    )?;
+                    years.extend(column.as_string::<i32>().iter().flatten().map(String::from));
+                }
+                years.dedup();
+                Ok(years)
+            })
+            .collect::<Result<Vec<_>, ArrowError>>()?;
+        assert_eq!(years, vec![vec!["2020"], vec!["2021"]]);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_limit_stops_adding_files_once_row_counts_cover_it() -> TestResult {
+        let table =
+            create_in_memory_id_table_with_files(vec![vec![1, 2, 3], vec![4, 5, 6], vec![7, 8, 9]])
+                .await?;
+        let log_store = table.log_store();
+        let snapshot = Arc::new(Snapshot::try_new(&log_store, Default::default(), None).await?);
+
+        let session = Arc::new(create_session().into_inner());
+        let state = session.state_ref().read().clone();
+        let provider = DeltaScan::builder()
+            .with_snapshot(snapshot)
+            .with_log_store(log_store)
+            .build()
+            .await?;
+
+        for (limit, expected_files) in [(2, 1), (3, 1), (4, 2), (100, 3)] {
+            let plan = provider.scan(&state, None, &[], Some(limit)).await?;
+            let mut visitor = DeltaScanVisitor::default();
+            visit_execution_plan(plan.as_ref(), &mut visitor).unwrap();
+            assert_eq!(visitor.num_scanned, Some(expected_files), "limit: {limit}");
+
+            let batches: Vec<_> = collect_partitioned(plan, session.task_ctx())
+                .await?
+                .into_iter()
+                .flatten()
+                .collect();
+            let returned_rows = batches.iter().map(|batch| batch.num_rows()).sum::<usize>();
+            assert!(returned_rows >= limit.min(9), "limit: {limit}");
+        }
+
+        // filters on data columns may remove rows, so all matching files must be scanned
+        let filter = col("id").gt(lit(0i64));
+        let plan = provider
+            .scan(&state, None, std::slice::from_ref(&filter), Some(2))
+            .await?;
+        let mut visitor = DeltaScanVisitor::default();
+        visit_execution_plan(plan.as_ref(), &mut visitor).unwrap();
+        assert_eq!(visitor.num_scanned, Some(3));
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_statistics_aggregate_file_stats() -> TestResult {
+        let table =
+            create_in_memory_id_table_with_files(vec![vec![1, 2, 3], vec![4, 5, 6]]).await?;
+        let provider = DeltaScan::builder()
+            .with_eager_snapshot(table.snapshot()?.snapshot().clone())
+            .build()
+            .await?;
+
+        let statistics = provider.statistics().unwrap();
+        assert_eq!(statistics.num_rows, Precision::Exact(6));
+        assert!(matches!(statistics.total_byte_size, Precision::Inexact(_)));
+        let id_statistics = &statistics.column_statistics[0];
+        assert_eq!(id_statistics.null_count, Precision::Exact(0));
+        assert_eq!(
+            id_statistics.min_value,
+            Precision::Inexact(ScalarValue::Int64(Some(1)))
+        );
+        assert_eq!(
+            id_statistics.max_value,
+            Precision::Inexact(ScalarValue::Int64(Some(6)))
+        );
+
+        let selected = provider.with_file_paths(Vec::<String>::new());
+        assert_eq!(
+            selected.statistics().unwrap().num_rows,
+            Precision::Inexact(6)
+        );
+
+        // lazy snapshots do not hold the file statistics
+        let provider = DeltaScan::builder()
+            .with_snapshot(table.snapshot()?.snapshot().snapshot().clone())
+            .build()
+            .await?;
+        assert!(provider.statistics().is_none());
+
+        Ok(())
+    }
+
     #[tokio::test]
     async fn test_file_selection_resolves_against_provider_snapshot_not_latest() -> TestResult {
         let table = create_in_memory_id_table_with_rows(vec![1, 2]).await?;
@@ -16,16 +16,21 @@ use arrow_array::{Array, ArrayRef, BooleanArray, UInt64Array};
 use dashmap::DashMap;
 use datafusion::common::config::ConfigOptions;
 use datafusion::common::error::{DataFusionError, Result};
+use datafusion::common::tree_node::TreeNode as _;
 use datafusion::common::{
     ColumnStatistics, HashMap, internal_datafusion_err, internal_err, plan_err,
 };
 use datafusion::execution::{RecordBatchStream, SendableRecordBatchStream, TaskContext};
+use datafusion::functions::core::getfield::GetFieldFunc;
 use datafusion::physical_expr::expressions::Column;
-use datafusion::physical_expr::utils::collect_columns;
-use datafusion::physical_expr::{Distribution, EquivalenceProperties};
+use datafusion::physical_expr::utils::{collect_columns, reassign_expr_columns};
+use datafusion::physical_expr::{Distribution, EquivalenceProperties, ScalarFunctionExpr};
 use datafusion::physical_plan::execution_plan::{CardinalityEffect, PlanProperties};
 use datafusion::physical_plan::filter_pushdown::{FilterDescription, FilterPushdownPhase};
-use datafusion::physical_plan::metrics::{BaselineMetrics, ExecutionPlanMetricsSet, MetricsSet};
+use datafusion::physical_plan::metrics::{
+    BaselineMetrics, Count, ExecutionPlanMetricsSet, Metric, MetricValue, MetricsSet,
+};
+use datafusion::physical_plan::projection::ProjectionExec;
 use datafusion::physical_plan::{
     DisplayAs, DisplayFormatType, ExecutionPlan, PhysicalExpr, Statistics,
 };
@@ -90,6 +95,19 @@ pub(crate) fn consume_dv_mask(
     }
 }
 
+/// Total `bytes_scanned` reported by `plan` and all plans below it.
+fn bytes_scanned(plan: &dyn ExecutionPlan) -> usize {
+    let own = plan
+        .metrics()
+        .and_then(|metrics| metrics.sum_by_name("bytes_scanned"))
+        .map_or(0, |value| value.as_usize());
+    own + plan
+        .children()
+        .into_iter()
+        .map(|child| bytes_scanned(child.as_ref()))
+        .sum::<usize>()
+}
+
 /// Physical execution plan for scanning Delta tables.
 ///
 /// Wraps a Parquet reader execution plan and applies Delta Lake protocol transformations
@@ -97,7 +115,7 @@ pub(crate) fn consume_dv_mask(
 ///
 /// - **Column mapping**: Translates physical column names to logical names
 /// - **Partition values**: Materializes partition column values from file paths
-/// - **Deletion vectors**: Filters out deleted rows using per-file selection vectors
+/// - **Deletion vectors**: Filters out deleted rows the Parquet reader did not already skip
 /// - **Schema evolution**: Handles missing columns and type coercion
 ///
 /// # Data Flow
@@ -164,8 +182,13 @@ impl DeltaScanExec {
             .contract
             .retain_file_id
             .then(|| scan_plan.contract.file_id_field.name().to_owned());
+        let schema = Arc::clone(&scan_plan.contract.output_schema);
+        let eq_properties = match scan_plan.output_ordering() {
+            Some(ordering) => EquivalenceProperties::new_with_orderings(schema, [ordering]),
+            None => EquivalenceProperties::new(schema),
+        };
         let properties = Arc::new(PlanProperties::new(
-            EquivalenceProperties::new(Arc::clone(&scan_plan.contract.output_schema)),
+            eq_properties,
             input.properties().partitioning.clone(),
             input.properties().emission_type,
             input.properties().boundedness,
@@ -190,12 +213,12 @@ impl DeltaScanExec {
     /// - predicates on metadata columns (like file id) are not really useful (random etc.)
     fn map_statistics(&self, mut stats: Statistics) -> Result<Statistics> {
         // Column statistics include stats for the added file id column, so we expect the
-        // number of physical schema fields + 1 to match the number of column statistics.
+        // number of input schema fields to match the number of column statistics.
         // We validate this to en sure we can safely remap the statistics below.
-        if self.scan_plan.scan.physical_schema().fields().len() > stats.column_statistics.len() {
+        if self.input.schema().fields().len() > stats.column_statistics.len() {
             return internal_err!(
                 "mismatched number of column statistics: expected {}, got {}",
-                self.scan_plan.scan.physical_schema().fields().len(),
+                self.input.schema().fields().len(),
                 stats.column_statistics.len()
             );
         }
@@ -223,13 +246,9 @@ impl DeltaScanExec {
                 }
             }
         } else {
+            let input_schema = self.input.schema();
             for field in self.schema().fields() {
-                if let Some((index, _)) = self
-                    .scan_plan
-                    .scan
-                    .physical_schema()
-                    .field_with_index(field.name())
-                {
+                if let Ok(index) = input_schema.index_of(field.name()) {
                     new_stats.push(stats.column_statistics[index].clone());
                 } else if let Some(part_stat) = self.partition_stats.get(field.name()) {
                     new_stats.push(part_stat.clone());
@@ -329,6 +348,11 @@ impl ExecutionPlan for DeltaScanExec {
             return Ok(None);
         }
 
+        if self.scan_plan.file_layout.is_explicit() {
+            // Files were ordered and grouped as configured in `DeltaScanConfig`.
+            return Ok(None);
+        }
+
         if let Some(input) = self.input.repartitioned(target_partitions, config)? {
             Ok(Some(Arc::new(Self {
                 input,
@@ -375,7 +399,18 @@ impl ExecutionPlan for DeltaScanExec {
     }
 
     fn metrics(&self) -> Option<MetricsSet> {
-        Some(self.metrics.clone_inner())
+        let mut metrics = self.metrics.clone_inner();
+        // Surface the bytes read by the Parquet scans next to the Delta specific metrics.
+        let bytes_read = Count::new();
+        bytes_read.add(bytes_scanned(self.input.as_ref()));
+        metrics.push(Arc::new(Metric::new(
+            MetricValue::Count {
+                name: "bytes_read".into(),
+                count: bytes_read,
+            },
+            None,
+        )));
+        Some(metrics)
     }
 
     fn supports_limit_pushdown(&self) -> bool {
@@ -403,6 +438,79 @@ impl ExecutionPlan for DeltaScanExec {
             .map(Arc::new)
     }
 
+    /// Push struct field accesses of a parent projection into the Parquet read.
+    ///
+    /// The Parquet reader only decodes the leaves of a struct column accessed via `get_field`,
+    /// so `payload.user.id` can be read without decoding all of `payload`. The projection can
+    /// only be evaluated on the raw file data if this exec passes the referenced columns through
+    /// unchanged, i.e. no per-file transforms apply and no Delta materialized columns are used.
+    fn try_swapping_with_projection(
+        &self,
+        projection: &ProjectionExec,
+    ) -> Result<Option<Arc<dyn ExecutionPlan>>> {
+        let contract = &self.scan_plan.contract;
+        if !self.transforms.is_empty()
+            || contract.retain_file_id
+            || contract.retained_row_index_field().is_some()
+            || !projection
+                .expr()
+                .iter()
+                .any(|expr| accesses_struct_field(&expr.expr))
+        {
+            return Ok(None);
+        }
+
+        let input_schema = self.input.schema();
+        let output_schema = self.schema();
+        let passed_through = |column: &Column| match (
+            output_schema.field_with_name(column.name()),
+            input_schema.field_with_name(column.name()),
+        ) {
+            (Ok(output), Ok(input)) => output.data_type() == input.data_type(),
+            _ => false,
+        };
+        if !projection.expr().iter().all(|expr| {
+            expr.alias != self.input_file_id_column
+                && collect_columns(&expr.expr).iter().all(&passed_through)
+        }) {
+            return Ok(None);
+        }
+
+        // Batches must keep carrying the file id to correlate rows with deletion vectors.
+        let mut exprs = projection
+            .expr()
+            .iter()
+            .map(|expr| {
+                Ok((
+                    reassign_expr_columns(Arc::clone(&expr.expr), &input_schema)?,
+                    expr.alias.clone(),
+                ))
+            })
+            .collect::<Result<Vec<_>>>()?;
+        exprs.push((
+            Arc::new(Column::new_with_schema(
+                &self.input_file_id_column,
+                &input_schema,
+            )?),
+            self.input_file_id_column.clone(),
+        ));
+        let input = Arc::new(ProjectionExec::try_new(exprs, Arc::clone(&self.input))?);
+
+        let mut scan_plan = self.scan_plan.as_ref().clone();
+        scan_plan.contract.result_schema = projection.schema();
+        scan_plan.contract.output_schema = projection.schema();
+        scan_plan.contract.result_projection = None;
+        Ok(Some(Arc::new(Self::new(
+            Arc::new(scan_plan),
+            input,
+            self.transforms.clone(),
+            self.selection_vectors.clone(),
+            self.public_file_ids.clone(),
+            self.partition_stats.clone(),
+            self.metrics.clone(),
+        ))))
+    }
+
     fn gather_filters_for_pushdown(
         &self,
         _phase: FilterPushdownPhase,
@@ -674,6 +782,14 @@ impl RecordBatchStream for DeltaScanStream {
     }
 }
 
+/// Whether `expr` accesses a field of a struct column.
+fn accesses_struct_field(expr: &Arc<dyn PhysicalExpr>) -> bool {
+    expr.exists(|node| {
+        Ok(ScalarFunctionExpr::try_downcast_func::<GetFieldFunc>(node.as_ref()).is_some())
+    })
+    .unwrap_or_default()
+}
+
 #[inline]
 fn file_id_column_idx(batch: &RecordBatch, file_id_column: &str) -> Result<usize> {
     batch
@@ -794,7 +910,7 @@ mod tests {
         },
         physical_expr::{Distribution, Partitioning},
         physical_plan::{
-            PhysicalExpr, collect, collect_partitioned,
+            PhysicalExpr, collect, collect_partitioned, displayable,
             filter_pushdown::{FilterPushdownPhase, PushedDown},
             repartition::RepartitionExec,
         },
@@ -836,6 +952,38 @@ mod tests {
         Ok(())
     }
 
+    #[tokio::test]
+    async fn test_scan_pushes_struct_field_access_into_parquet() -> TestResult {
+        let table = open_fs_path("../../dat/v0.0.3/reader_tests/generated/nested_types/delta");
+        let provider = table.table_provider().await?;
+        let session = Arc::new(create_session().into_inner());
+        session.register_table("delta_table", provider).unwrap();
+
+        let df = session
+            .sql(r#"SELECT pk, "struct"['float64'] AS value FROM delta_table"#)
+            .await?;
+        let plan = df.clone().create_physical_plan().await?;
+        let plan_str = displayable(plan.as_ref()).indent(false).to_string();
+        assert!(!plan_str.contains("ProjectionExec"), "{plan_str}");
+        assert!(plan_str.contains("get_field"), "{plan_str}");
+
+        let batches = df.collect().await?;
+        let expected = vec![
+            "+----+-------+",
+            "| pk | value |",
+            "+----+-------+",
+            "| 0  | 0.0   |",
+            "| 1  | 1.0   |",
+            "| 2  | 2.0   |",
+            "| 3  | 3.0   |",
+            "| 4  | 4.0   |",
+            "+----+-------+",
+        ];
+        assert_batches_sorted_eq!(&expected, &batches);
+
+        Ok(())
+    }
+
     #[tokio::test]
     async fn test_scan_with_file_id() -> TestResult {
         let table = open_fs_path("../../dat/v0.0.3/reader_tests/generated/multi_partitioned/delta");
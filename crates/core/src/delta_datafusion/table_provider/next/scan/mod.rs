@@ -15,14 +15,16 @@
 //! predicates, while execution plans handle the actual data reading and transformation.
 
 use std::{
-    collections::{HashSet, VecDeque},
+    cmp::{Ordering, Reverse},
+    collections::{BinaryHeap, HashSet, VecDeque},
     pin::Pin,
     sync::Arc,
+    time::Instant,
 };
 
 use arrow::datatypes::UInt16Type;
 use arrow_array::{
-    ArrayRef, DictionaryArray, RecordBatch, StringArray, StringViewArray, UInt16Array,
+    ArrayRef, BooleanArray, DictionaryArray, RecordBatch, StringArray, StringViewArray, UInt16Array,
 };
 use arrow_cast::{CastOptions, cast_with_options};
 use arrow_schema::{DataType, FieldRef, Schema, SchemaBuilder, SchemaRef};
@@ -35,13 +37,19 @@ use datafusion::{
         plan_err, stats::Precision,
     },
     config::TableParquetOptions,
-    datasource::physical_plan::{ParquetSource, parquet::CachedParquetFileReaderFactory},
+    datasource::physical_plan::{
+        ParquetSource,
+        parquet::{
+            CachedParquetFileReaderFactory, ParquetAccessPlan, RowGroupAccess,
+            metadata::DFParquetMetadata,
+        },
+    },
     error::DataFusionError,
     execution::object_store::ObjectStoreUrl,
     physical_plan::{
         ExecutionPlan,
         empty::EmptyExec,
-        metrics::{ExecutionPlanMetricsSet, MetricBuilder},
+        metrics::{ExecutionPlanMetricsSet, MetricBuilder, Time},
         union::UnionExec,
     },
     prelude::Expr,
@@ -60,24 +68,28 @@ use delta_kernel::{
 use futures::{Stream, TryStreamExt as _, future::ready};
 use itertools::Itertools as _;
 use object_store::{ObjectMeta, path::Path};
+use parquet::arrow::arrow_reader::RowSelection;
 use tracing::debug;
 use url::Url;
 
 pub use self::exec::DeltaScanExec;
 use self::exec_meta::DeltaScanMetaExec;
 use self::expr_adapter::{DeltaPhysicalExprAdapterFactory, relax_schema_nested_nullability};
+use self::plan::{FileLayout, KeyManagementRef};
 pub(crate) use self::plan::{KernelScanPlan, ProjectedScanContract, supports_filters_pushdown};
-use self::replay::{ScanFileContext, ScanFileStream};
+use self::replay::{PartitionFilter, ScanFileContext, ScanFileStream};
 use super::{FileSelection, ResolvedFileSelection};
+#[cfg(feature = "parquet-encryption")]
+use crate::parquet_encryption::KeyManagementEncryptionFactory;
 use crate::{
     DeltaTableError,
     delta_datafusion::{
-        DeltaScanConfig,
+        DeltaScanConfig, ScanFileOrder,
         engine::{AsObjectStoreUrl as _, to_datafusion_scalar},
         file_id::wrap_file_id_value,
         table_provider::next::DeletionVectorSelection,
     },
-    kernel::LogicalFileView,
+    kernel::{LogicalFileView, StructDataExt as _},
 };
 
 mod exec;
@@ -95,6 +107,7 @@ struct ReplayedScanFiles {
     dvs: DashMap<String, Vec<bool>>,
     public_file_ids: PublicFileIdMap,
     metrics: ExecutionPlanMetricsSet,
+    planning_time: Time,
 }
 
 pub(super) async fn execution_plan(
@@ -114,7 +127,18 @@ pub(super) async fn execution_plan(
         )));
     }
 
-    let replayed = replay_files(engine, &scan_plan, config.clone(), stream, file_selection).await?;
+    // Without a sort order any rows satisfy a limit, so files beyond those known to hold enough
+    // rows can be dropped, as long as the filters cannot remove rows from the scanned files.
+    let file_limit = limit.filter(|_| scan_plan.exact_filters);
+    let replayed = replay_files(
+        engine,
+        &scan_plan,
+        config.clone(),
+        stream,
+        file_selection,
+        file_limit,
+    )
+    .await?;
 
     let file_id_field = scan_plan.contract.file_id_field.clone();
     if scan_plan.is_metadata_only() && !scan_plan.contract.retain_row_index {
@@ -306,19 +330,61 @@ async fn replay_files(
     scan_config: DeltaScanConfig,
     stream: ScanMetadataStream,
     file_selection: Option<&ResolvedFileSelection>,
+    limit: Option<usize>,
 ) -> Result<ReplayedScanFiles> {
+    let start = Instant::now();
+    let partition_filter = scan_plan.partition_filter.as_ref().and_then(|filter| {
+        let table_config = scan_plan.table_configuration();
+        scan_config
+            .table_schema(table_config)
+            .and_then(|table_schema| {
+                PartitionFilter::try_new(
+                    filter,
+                    &table_schema,
+                    table_config.metadata().partition_columns(),
+                )
+            })
+            .inspect_err(|err| debug!("Not tracking partition pruning: {err}"))
+            .ok()
+    });
     let mut stream = ScanFileStream::new(
         engine,
         &scan_plan.scan,
         scan_config,
         file_selection.map(|selection| &selection.active_file_ids),
         stream,
-    );
+    )
+    .with_partition_filter(partition_filter);
     let mut files = Vec::new();
     while let Some(file) = stream.try_next().await? {
         files.extend(file);
     }
 
+    let dv_stream = stream.dv_stream.build();
+    let mut dvs_by_url: HashMap<_, _> = dv_stream
+        .try_filter_map(|(url, dv, _)| ready(Ok(dv.map(|dv| (url.to_string(), dv)))))
+        .try_collect()
+        .await?;
+
+    let mut num_scanned = stream.metrics.num_scanned;
+    if let Some(limit) = limit {
+        let num_files = files_covering_limit(&files, &dvs_by_url, limit);
+        if num_files < files.len() {
+            files.truncate(num_files);
+            let retained: HashSet<_> = files.iter().map(|f| f.file_url.as_str()).collect();
+            dvs_by_url.retain(|url, _| retained.contains(url.as_str()));
+            num_scanned = files.len();
+        }
+    }
+    sort_scan_files(
+        &mut files,
+        scan_plan.file_layout.order,
+        scan_plan
+            .table_configuration()
+            .metadata()
+            .partition_columns(),
+    );
+
     let mut public_file_ids = PublicFileIdMap::default();
     if scan_plan.contract.retain_file_id {
         for (file_index, file) in files.iter().enumerate() {
@@ -339,17 +405,20 @@ async fn replay_files(
         })
         .collect();
 
-    let dv_stream = stream.dv_stream.build();
-    let dvs_by_url: HashMap<_, _> = dv_stream
-        .try_filter_map(|(url, dv, _)| ready(Ok(dv.map(|dv| (url.to_string(), dv)))))
-        .try_collect()
-        .await?;
     let dvs = remap_deletion_vectors_to_internal_file_ids(&files, dvs_by_url)?;
 
     let metrics = ExecutionPlanMetricsSet::new();
     MetricBuilder::new(&metrics)
         .global_counter("count_files_scanned")
-        .add(stream.metrics.num_scanned);
+        .add(num_scanned);
+    MetricBuilder::new(&metrics)
+        .global_counter("count_files_pruned_by_partition")
+        .add(stream.metrics.num_pruned_by_partition);
+    MetricBuilder::new(&metrics)
+        .global_counter("count_files_pruned_by_stats")
+        .add(stream.metrics.num_pruned_by_stats);
+    let planning_time = MetricBuilder::new(&metrics).subset_time("planning_time", 0);
+    planning_time.add_elapsed(start);
 
     Ok(ReplayedScanFiles {
         files,
@@ -357,6 +426,7 @@ async fn replay_files(
         dvs,
         public_file_ids,
         metrics,
+        planning_time,
     })
 }
 
@@ -409,9 +479,21 @@ async fn get_data_scan_plan(
         dvs,
         public_file_ids,
         metrics,
+        planning_time,
     } = replayed;
+    let start = Instant::now();
     let mut partition_stats = HashMap::new();
 
+    // Deletion vectors are handed to the Parquet reader as row selections, so deleted rows
+    // are never decoded. Any masks left in `dvs` are applied by `DeltaScanExec` instead.
+    let rows_skipped_by_dv: usize = dvs
+        .iter()
+        .map(|dv| dv.value().iter().filter(|keep| !**keep).count())
+        .sum();
+    MetricBuilder::new(&metrics)
+        .global_counter("count_rows_skipped_by_dv")
+        .add(rows_skipped_by_dv);
+
     // Convert files into DataFusion `PartitionedFile`s grouped by object store.
     // Create one `DataSourceExec` plan for each store.
     // Add a compact scan file id as a partition value for file correlation.
@@ -436,9 +518,12 @@ async fn get_data_scan_plan(
         // on `partition_values`, so partition values must be set first.
         partitioned_file.partition_values = vec![file_value.clone()];
         partitioned_file = partitioned_file.with_statistics(Arc::new(f.stats));
+        let selection_vector = dvs
+            .remove(&compact_internal_file_id(file_index))
+            .map(|(_, dv)| dv);
         Ok::<_, DataFusionError>((
             f.file_url.as_object_store_url(),
-            (partitioned_file, None::<Vec<bool>>),
+            (partitioned_file, selection_vector),
         ))
     };
 
@@ -454,6 +539,8 @@ async fn get_data_scan_plan(
 
     // TODO(roeap); not sure exactly how row tracking is implemented in kernel right now
     // so leaving predicate as None for now until we are sure this is safe to do.
+    // Deletion vectors are safe to combine with pruning, since they are handed to the reader
+    // as access plans addressing rows by their position in the file.
     let table_config = scan_plan.table_configuration();
     let predicate = if table_config.is_feature_enabled(&TableFeature::RowTracking) {
         None
@@ -469,8 +556,12 @@ async fn get_data_scan_plan(
         limit,
         &file_id_field,
         predicate,
+        scan_plan.parquet_pushdown,
+        &scan_plan.file_layout,
+        scan_plan.key_management.as_ref(),
     )
     .await?;
+    planning_time.add_elapsed(start);
 
     let transforms = Arc::new(transforms);
     let dvs = Arc::new(dvs);
@@ -526,10 +617,127 @@ fn update_partition_stats(
 
 type FilesByStore = (ObjectStoreUrl, Vec<(PartitionedFile, Option<Vec<bool>>)>);
 
+/// Translate a deletion vector keep mask into a [`ParquetAccessPlan`].
+///
+/// Row groups without deleted rows are scanned in full and row groups with only deleted rows
+/// are skipped. All other row groups are read with a [`RowSelection`], so deleted rows are
+/// never decoded. Like in `exec::consume_dv_mask`, missing trailing mask entries are treated
+/// as `true` (keep row).
+fn deletion_vector_access_plan(
+    keep_mask: &[bool],
+    row_group_rows: impl IntoIterator<Item = i64>,
+    file_name: &str,
+) -> Result<ParquetAccessPlan> {
+    let mut offset = 0_usize;
+    let mut row_groups = Vec::new();
+    for num_rows in row_group_rows {
+        let num_rows = usize::try_from(num_rows).map_err(|_| {
+            internal_datafusion_err!("invalid row group row count {num_rows} in {file_name}")
+        })?;
+        let end = offset.saturating_add(num_rows);
+        let mask = &keep_mask[offset.min(keep_mask.len())..end.min(keep_mask.len())];
+        let access = if mask.iter().all(|keep| *keep) {
+            RowGroupAccess::Scan
+        } else if mask.len() == num_rows && !mask.iter().any(|keep| *keep) {
+            RowGroupAccess::Skip
+        } else {
+            let mut mask = mask.to_vec();
+            mask.resize(num_rows, true);
+            RowGroupAccess::Selection(RowSelection::from_filters(&[BooleanArray::from(mask)]))
+        };
+        row_groups.push(access);
+        offset = end;
+    }
+
+    if keep_mask.len() > offset {
+        return plan_err!(
+            "Deletion vector mask length {} exceeds row count {} for file: {}",
+            keep_mask.len(),
+            offset,
+            file_name
+        );
+    }
+
+    Ok(ParquetAccessPlan::new(row_groups))
+}
+
 fn compact_internal_file_id(file_index: usize) -> String {
     file_index.to_string()
 }
 
+/// Number of leading `files` known to hold at least `limit` rows.
+///
+/// Files with a deletion vector or without an exact row count are retained, but do not count
+/// towards the limit, since the number of rows they return is unknown.
+fn files_covering_limit(
+    files: &[ScanFileContext],
+    dvs_by_url: &HashMap<String, Vec<bool>>,
+    limit: usize,
+) -> usize {
+    let mut num_rows = 0_usize;
+    for (idx, file) in files.iter().enumerate() {
+        if num_rows >= limit {
+            return idx;
+        }
+        if let Precision::Exact(file_rows) = file.stats.num_rows
+            && !dvs_by_url.contains_key(file.file_url.as_str())
+        {
+            num_rows = num_rows.saturating_add(file_rows);
+        }
+    }
+    files.len()
+}
+
+/// Sort `files` into the order in which they are read by the scan.
+///
+/// Clustered files are ordered by their partition values in the order of `partition_columns`,
+/// followed by the minimum values of their columns, with unknown values first. Ties keep the
+/// order of the log.
+fn sort_scan_files(
+    files: &mut Vec<ScanFileContext>,
+    order: ScanFileOrder,
+    partition_columns: &[String],
+) {
+    match order {
+        ScanFileOrder::Log => {}
+        ScanFileOrder::Size => files.sort_by(|left, right| right.size.cmp(&left.size)),
+        ScanFileOrder::Clustered => {
+            let clustering_key = |file: &ScanFileContext| {
+                let partition_values = partition_columns.iter().map(|name| {
+                    file.partitions
+                        .as_ref()
+                        .and_then(|values| values.index_of(name).map(|idx| &values.values()[idx]))
+                        .filter(|value| !value.is_null())
+                        .and_then(|value| to_datafusion_scalar(value).ok())
+                });
+                let min_values = file
+                    .stats
+                    .column_statistics
+                    .iter()
+                    .map(|stats| stats.min_value.get_value().cloned());
+                partition_values.chain(min_values).collect_vec()
+            };
+            let mut keyed = std::mem::take(files)
+                .into_iter()
+                .map(|file| (clustering_key(&file), file))
+                .collect_vec();
+            keyed.sort_by(|(left, _), (right, _)| {
+                left.iter()
+                    .zip(right)
+                    .map(|(left, right)| match (left, right) {
+                        (Some(left), Some(right)) => {
+                            left.partial_cmp(right).unwrap_or(Ordering::Equal)
+                        }
+                        (left, right) => left.is_some().cmp(&right.is_some()),
+                    })
+                    .find(|ordering| ordering.is_ne())
+                    .unwrap_or(Ordering::Equal)
+            });
+            files.extend(keyed.into_iter().map(|(_, file)| file));
+        }
+    }
+}
+
 fn remap_deletion_vectors_to_internal_file_ids(
     files: &[ScanFileContext],
     mut dvs_by_url: HashMap<String, Vec<bool>>,
@@ -623,24 +831,82 @@ fn partitioned_files_to_file_groups_with_limit(
         .collect_vec();
 
     #[cfg(debug_assertions)]
-    {
-        let mut owner_by_path = HashMap::new();
-        for (partition, group) in file_groups.iter().enumerate() {
-            for file in group.iter() {
-                let path = file.object_meta.location.to_string();
-                if let Some(previous_partition) = owner_by_path.insert(path.clone(), partition) {
-                    debug_assert_eq!(
-                        previous_partition, partition,
-                        "file {path} was assigned to multiple scan partitions; row indexes require whole file ownership"
-                    );
-                }
-            }
+    debug_assert_whole_file_ownership(&file_groups);
+    file_groups
+}
+
+/// Distribute `files` across file groups according to an explicitly configured [`FileLayout`].
+///
+/// Files are expected to be sorted according to the layout's order already. Files ordered
+/// by size are assigned to the group reading the fewest bytes so far, which balances the
+/// groups. All other orders assign contiguous runs of files, so every group preserves the
+/// file order.
+fn partitioned_files_to_file_groups_with_layout(
+    files: Vec<PartitionedFile>,
+    file_layout: &FileLayout,
+    default_target_partitions: usize,
+) -> Vec<FileGroup> {
+    let max_files_per_group = file_layout
+        .max_files_per_partition
+        .unwrap_or(MAX_PARTITION_DICT_CARDINALITY)
+        .clamp(1, MAX_PARTITION_DICT_CARDINALITY);
+    let num_groups = file_layout
+        .target_partitions
+        .unwrap_or(default_target_partitions)
+        .max(files.len().div_ceil(max_files_per_group))
+        .max(1);
+
+    if file_layout.order != ScanFileOrder::Size {
+        let group_size = files.len().div_ceil(num_groups).max(1);
+        return partitioned_files_to_file_groups_with_limit(files, group_size);
+    }
+
+    let mut groups: Vec<Vec<PartitionedFile>> = vec![Vec::new(); num_groups];
+    let mut group_bytes = BinaryHeap::from_iter((0..num_groups).map(|idx| Reverse((0_u64, idx))));
+    for file in files {
+        // `num_groups` is large enough for every group to stay within `max_files_per_group`,
+        // so running out of groups with capacity left is not expected.
+        let (bytes, idx) = group_bytes
+            .pop()
+            .map(|Reverse(entry)| entry)
+            .unwrap_or_else(|| {
+                groups.push(Vec::new());
+                (0, groups.len() - 1)
+            });
+        let file_size = file.object_meta.size;
+        groups[idx].push(file);
+        if groups[idx].len() < max_files_per_group {
+            group_bytes.push(Reverse((bytes + file_size, idx)));
         }
     }
 
+    let file_groups = groups
+        .into_iter()
+        .filter(|group| !group.is_empty())
+        .map(FileGroup::new)
+        .collect_vec();
+    #[cfg(debug_assertions)]
+    debug_assert_whole_file_ownership(&file_groups);
     file_groups
 }
 
+#[cfg(debug_assertions)]
+fn debug_assert_whole_file_ownership(file_groups: &[FileGroup]) {
+    let mut owner_by_path = HashMap::new();
+    for (partition, group) in file_groups.iter().enumerate() {
+        for file in group.iter() {
+            let path = file.object_meta.location.to_string();
+            if let Some(previous_partition) = owner_by_path.insert(path.clone(), partition) {
+                debug_assert_eq!(
+                    previous_partition, partition,
+                    "file {path} was assigned to multiple scan partitions; row indexes require whole file ownership"
+                );
+            }
+        }
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
 async fn get_read_plan(
     state: &dyn Session,
     files_by_store: impl IntoIterator<Item = FilesByStore>,
@@ -654,7 +920,15 @@ async fn get_read_plan(
     parquet_predicate_schema: &SchemaRef,
     limit: Option<usize>,
     file_id_field: &FieldRef,
+    // Predicate used to prune row groups via statistics, page indexes and bloom filters.
     predicate: Option<&Expr>,
+    // Whether the predicate is also evaluated as a row filter while decoding.
+    pushdown_filters: bool,
+    // Ordering and distribution of the files across scan partitions.
+    file_layout: &FileLayout,
+    // Client resolving the keys of encrypted data files.
+    #[cfg_attr(not(feature = "parquet-encryption"), allow(unused_variables))]
+    key_management: Option<&KeyManagementRef>,
 ) -> Result<Arc<dyn ExecutionPlan>> {
     let mut plans = Vec::new();
 
@@ -677,9 +951,11 @@ async fn get_read_plan(
     let adapter_factory = Arc::new(DeltaPhysicalExprAdapterFactory);
 
     for (store_url, files) in files_by_store.into_iter() {
+        let store = state.runtime_env().object_store(&store_url)?;
+        let metadata_cache = state.runtime_env().cache_manager.get_file_metadata_cache();
         let reader_factory = Arc::new(CachedParquetFileReaderFactory::new(
-            state.runtime_env().object_store(&store_url)?,
-            state.runtime_env().cache_manager.get_file_metadata_cache(),
+            Arc::clone(&store),
+            Arc::clone(&metadata_cache),
         ));
 
         // NOTE: In the "next" provider, DataFusion's Parquet scan partition fields are file-id
@@ -691,12 +967,16 @@ async fn get_read_plan(
         let mut file_source = ParquetSource::new(table_schema)
             .with_table_parquet_options(pq_options.clone())
             .with_parquet_file_reader_factory(reader_factory);
+        #[cfg(feature = "parquet-encryption")]
+        if let Some(client) = key_management {
+            file_source = file_source.with_encryption_factory(Arc::new(
+                KeyManagementEncryptionFactory::new(client.clone()),
+            ));
+        }
 
-        // TODO(roeap); we might be able to also push selection vectors into the read plan
-        // by creating parquet access plans. However we need to make sure this does not
-        // interfere with other delta features like row ids.
-        let has_selection_vectors = files.iter().any(|(_, sv)| sv.is_some());
-        if !has_selection_vectors && let Some(pred) = predicate {
+        // Selection vectors are pushed into the read plan as parquet access plans. Since these
+        // address rows by their position in the file, they compose with row group pruning.
+        if let Some(pred) = predicate {
             match state.create_physical_expr(pred.clone(), &parquet_predicate_df_schema) {
                 Ok(physical) => match adapter_factory
                     .create(parquet_predicate_schema.clone(), full_read_schema.clone())
@@ -705,7 +985,7 @@ async fn get_read_plan(
                         Ok(rewritten) => {
                             file_source = file_source
                                 .with_predicate(rewritten)
-                                .with_pushdown_filters(true);
+                                .with_pushdown_filters(pushdown_filters);
                         }
                         Err(err) => {
                             debug!(
@@ -736,7 +1016,34 @@ async fn get_read_plan(
             }
         }
 
-        let file_groups = partitioned_files_to_file_groups(files.into_iter().map(|file| file.0));
+        let mut partitioned_files = Vec::with_capacity(files.len());
+        for (file, selection_vector) in files {
+            let Some(selection_vector) = selection_vector else {
+                partitioned_files.push(file);
+                continue;
+            };
+            // The footer is cached, so the parquet scan does not need to fetch it again.
+            let metadata = DFParquetMetadata::new(store.as_ref(), &file.object_meta)
+                .with_file_metadata_cache(Some(Arc::clone(&metadata_cache)))
+                .fetch_metadata()
+                .await?;
+            let access_plan = deletion_vector_access_plan(
+                &selection_vector,
+                metadata.row_groups().iter().map(|rg| rg.num_rows()),
+                file.object_meta.location.as_ref(),
+            )?;
+            partitioned_files.push(file.with_extension(access_plan));
+        }
+
+        let file_groups = if file_layout.is_explicit() {
+            partitioned_files_to_file_groups_with_layout(
+                partitioned_files,
+                file_layout,
+                state.config().target_partitions(),
+            )
+        } else {
+            partitioned_files_to_file_groups(partitioned_files)
+        };
         let (file_groups, statistics) =
             compute_all_files_statistics(file_groups, full_table_schema, true, false)?;
 
@@ -901,6 +1208,103 @@ mod tests {
         assert_eq!(groups[1].len(), 1);
     }
 
+    #[test]
+    fn test_partitioned_files_to_file_groups_with_layout() {
+        let files = [10, 7, 5, 4, 2, 1]
+            .into_iter()
+            .enumerate()
+            .map(|(i, size)| PartitionedFile::new(format!("f{i}.parquet"), size))
+            .collect_vec();
+        let group_files = |groups: Vec<FileGroup>| {
+            groups
+                .iter()
+                .map(|group| {
+                    group
+                        .iter()
+                        .map(|file| file.object_meta.location.to_string())
+                        .collect_vec()
+                })
+                .collect_vec()
+        };
+
+        // files sorted by size are balanced by bytes
+        let layout = FileLayout {
+            order: ScanFileOrder::Size,
+            target_partitions: Some(2),
+            max_files_per_partition: None,
+        };
+        let groups = partitioned_files_to_file_groups_with_layout(files.clone(), &layout, 8);
+        assert_eq!(
+            group_files(groups),
+            vec![
+                vec!["f0.parquet", "f3.parquet", "f5.parquet"],
+                vec!["f1.parquet", "f2.parquet", "f4.parquet"],
+            ]
+        );
+
+        // the file limit takes precedence over the target partitions
+        let layout = FileLayout {
+            max_files_per_partition: Some(2),
+            ..layout
+        };
+        let groups = partitioned_files_to_file_groups_with_layout(files.clone(), &layout, 8);
+        assert_eq!(groups.len(), 3);
+        assert!(groups.iter().all(|group| group.len() == 2));
+
+        // other orders keep contiguous runs of files, defaulting to the session's partitions
+        let layout = FileLayout {
+            order: ScanFileOrder::Clustered,
+            target_partitions: None,
+            max_files_per_partition: None,
+        };
+        let groups = partitioned_files_to_file_groups_with_layout(files, &layout, 4);
+        assert_eq!(
+            group_files(groups),
+            vec![
+                vec!["f0.parquet", "f1.parquet"],
+                vec!["f2.parquet", "f3.parquet"],
+                vec!["f4.parquet", "f5.parquet"],
+            ]
+        );
+    }
+
+    #[test]
+    fn test_deletion_vector_access_plan() -> TestResult {
+        let keep_mask = [
+            vec![true; 3],
+            vec![false; 3],
+            vec![true, false, true],
+            vec![false],
+        ]
+        .concat();
+        let plan = deletion_vector_access_plan(&keep_mask, [3, 3, 3, 3], "f.parquet")?;
+        let expected_selection =
+            RowSelection::from_filters(&[BooleanArray::from(vec![true, false, true])]);
+        let expected_partial =
+            RowSelection::from_filters(&[BooleanArray::from(vec![false, true, true])]);
+        assert_eq!(
+            plan.into_inner(),
+            vec![
+                RowGroupAccess::Scan,
+                RowGroupAccess::Skip,
+                RowGroupAccess::Selection(expected_selection),
+                RowGroupAccess::Selection(expected_partial),
+            ]
+        );
+
+        // masks are sparse, trailing rows without entries are kept
+        let plan = deletion_vector_access_plan(&[true, true], [3, 3], "f.parquet")?;
+        assert_eq!(
+            plan.into_inner(),
+            vec![RowGroupAccess::Scan, RowGroupAccess::Scan]
+        );
+
+        let err = deletion_vector_access_plan(&[true; 4], [3], "f.parquet").unwrap_err();
+        assert!(err.to_string().contains("exceeds row count 3"));
+
+        Ok(())
+    }
+
     #[tokio::test]
     async fn test_resolve_empty_file_selection_does_not_poll_metadata_stream() -> TestResult {
         let log_store = TestTables::Simple.table_builder()?.build_storage()?;
@@ -1384,6 +1788,9 @@ mod tests {
             None,
             &file_id_field,
             None,
+            false,
+            &FileLayout::default(),
+            None,
         )
         .await?;
         let batches = collect(plan, session.task_ctx()).await?;
@@ -1407,6 +1814,9 @@ mod tests {
             Some(1),
             &file_id_field,
             None,
+            false,
+            &FileLayout::default(),
+            None,
         )
         .await?;
         let batches = collect(plan, session.task_ctx()).await?;
@@ -1435,6 +1845,9 @@ mod tests {
             Some(1),
             &file_id_field,
             None,
+            false,
+            &FileLayout::default(),
+            None,
         )
         .await?;
         let batches = collect(plan, session.task_ctx()).await?;
@@ -1512,6 +1925,9 @@ mod tests {
             None,
             &file_id_field,
             None,
+            false,
+            &FileLayout::default(),
+            None,
         )
         .await?;
         let batches = collect(plan, session.task_ctx()).await?;
@@ -1550,6 +1966,9 @@ mod tests {
             None,
             &file_id_field,
             None,
+            false,
+            &FileLayout::default(),
+            None,
         )
         .await?;
         let batches = collect(plan, session.task_ctx()).await?;
@@ -1743,6 +2162,9 @@ mod tests {
             None,
             &file_id_field,
             None,
+            false,
+            &FileLayout::default(),
+            None,
         )
         .await?;
         let batches = collect(plan, session.task_ctx()).await?;
@@ -1810,6 +2232,9 @@ mod tests {
             None,
             &file_id_field,
             Some(&predicate),
+            true,
+            &FileLayout::default(),
+            None,
         )
         .await?;
         let batches = collect(plan, session.task_ctx()).await?;
@@ -1876,6 +2301,9 @@ mod tests {
             None,
             &file_id_field,
             Some(&predicate),
+            true,
+            &FileLayout::default(),
+            None,
         )
         .await?;
         let batches = collect(plan, session.task_ctx()).await?;
@@ -1955,6 +2383,9 @@ mod tests {
             None,
             &file_id_field,
             Some(&predicate),
+            true,
+            &FileLayout::default(),
+            None,
         )
         .await?;
         let batches = collect(plan, session.task_ctx()).await?;
@@ -2031,6 +2462,9 @@ mod tests {
             None,
             &file_id_field,
             Some(&predicate),
+            true,
+            &FileLayout::default(),
+            None,
         )
         .await?;
         let batches = collect(plan, session.task_ctx()).await?;
@@ -2108,6 +2542,9 @@ mod tests {
             None,
             &file_id_field,
             Some(&predicate),
+            true,
+            &FileLayout::default(),
+            None,
         )
         .await?;
         let batches = collect(plan, session.task_ctx()).await?;
@@ -2197,6 +2634,9 @@ mod tests {
             None,
             &file_id_field,
             Some(&predicate),
+            true,
+            &FileLayout::default(),
+            None,
         )
         .await?;
         let batches = collect(plan, session.task_ctx()).await?;
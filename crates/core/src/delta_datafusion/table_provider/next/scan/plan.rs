@@ -22,6 +22,8 @@ use datafusion::common::tree_node::{Transformed, TreeNode};
 use datafusion::common::{HashMap, HashSet, plan_err};
 use datafusion::logical_expr::TableProviderFilterPushDown;
 use datafusion::logical_expr::utils::conjunction;
+use datafusion::physical_expr::PhysicalSortExpr;
+use datafusion::physical_expr::expressions::Column;
 use datafusion::prelude::Expr;
 use datafusion::scalar::ScalarValue;
 use datafusion_datasource::file_scan_config::wrap_partition_type_in_dict;
@@ -33,12 +35,21 @@ use delta_kernel::{Expression, Predicate, PredicateRef};
 use itertools::Itertools;
 use tracing::debug;
 
-use crate::delta_datafusion::DeltaScanConfig;
 use crate::delta_datafusion::engine::{
     to_datafusion_expr, to_delta_expression, to_delta_predicate,
 };
 use crate::delta_datafusion::table_provider::next::FILE_ID_COLUMN_DEFAULT;
+use crate::delta_datafusion::{DeltaScanConfig, ScanFileOrder};
 use crate::kernel::{Scan, Snapshot};
+#[cfg(feature = "parquet-encryption")]
+use crate::parquet_encryption::KeyManagementClient;
+
+/// Client resolving the keys of encrypted data files.
+#[cfg(feature = "parquet-encryption")]
+pub(crate) type KeyManagementRef = Arc<dyn KeyManagementClient>;
+/// Encrypted data files can only be read with the `parquet-encryption` feature.
+#[cfg(not(feature = "parquet-encryption"))]
+pub(crate) type KeyManagementRef = std::convert::Infallible;
 
 /// Query scoped contract between the provider, logical planner, and scan execs.
 ///
@@ -231,7 +242,45 @@ pub(crate) struct KernelScanPlan {
     /// column.
     pub(crate) parquet_predicate_schema: SchemaRef,
     /// If set, indicates a predicate to apply at the Parquet scan level
+    ///
+    /// The predicate is always used to prune row groups via statistics and bloom filters.
     pub(crate) parquet_predicate: Option<Expr>,
+    /// Whether the Parquet predicate may also be evaluated as a row filter while decoding.
+    pub(crate) parquet_pushdown: bool,
+    /// Conjunction of the file skipping filters referencing only partition columns.
+    ///
+    /// Used to attribute files skipped by kernel to partition pruning in the scan metrics.
+    pub(crate) partition_filter: Option<Expr>,
+    /// Whether all filters are handled exactly by file skipping, i.e. every row in the
+    /// scanned files is part of the result.
+    pub(crate) exact_filters: bool,
+    /// How data files are ordered and distributed across the scan partitions.
+    pub(crate) file_layout: FileLayout,
+    /// Client resolving the keys of encrypted data files.
+    pub(crate) key_management: Option<KeyManagementRef>,
+}
+
+/// Ordering and distribution of data files across the partitions of a scan.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub(crate) struct FileLayout {
+    pub(crate) order: ScanFileOrder,
+    pub(crate) target_partitions: Option<usize>,
+    pub(crate) max_files_per_partition: Option<usize>,
+}
+
+impl FileLayout {
+    fn new(config: &DeltaScanConfig) -> Self {
+        Self {
+            order: config.file_order,
+            target_partitions: config.target_partitions,
+            max_files_per_partition: config.max_files_per_partition,
+        }
+    }
+
+    /// Whether the file groups are configured explicitly and must not be redistributed.
+    pub(crate) fn is_explicit(&self) -> bool {
+        *self != Self::default()
+    }
 }
 
 impl KernelScanPlan {
@@ -274,6 +323,19 @@ impl KernelScanPlan {
         // At this point we should only have supported predicates, but we decide where
         // when can handle them (kernel scan and/or parquet scan)
         let (kernel_predicate, parquet_predicate) = process_filters(filters, table_config, config)?;
+        let exact_filters =
+            supports_filters_pushdown(&filters.iter().collect_vec(), table_config, config)
+                .into_iter()
+                .all(|pushdown| pushdown == TableProviderFilterPushDown::Exact);
+
+        let partition_filter = conjunction(
+            skipping_predicate
+                .as_deref()
+                .unwrap_or(filters)
+                .iter()
+                .filter(|f| is_partition_filter(f, table_config))
+                .cloned(),
+        );
 
         // if some dedicated file skipping predicate is supplied,
         // we do not push the scan filters into the kernel scan.
@@ -320,6 +382,11 @@ impl KernelScanPlan {
             parquet_read_schema,
             parquet_predicate_schema,
             parquet_predicate,
+            parquet_pushdown: parquet_pushdown_enabled(table_config, config),
+            partition_filter,
+            exact_filters,
+            file_layout: FileLayout::new(config),
+            key_management: key_management(snapshot, config),
         })
     }
 
@@ -334,6 +401,26 @@ impl KernelScanPlan {
     pub(crate) fn table_configuration(&self) -> &TableConfiguration {
         self.scan.snapshot().table_configuration()
     }
+
+    /// Ordering of the scan output within each partition, if known.
+    ///
+    /// Clustered scans read files ordered by their partition values, so the output is
+    /// ordered by the leading partition columns included in the output schema.
+    pub(crate) fn output_ordering(&self) -> Option<Vec<PhysicalSortExpr>> {
+        if self.file_layout.order != ScanFileOrder::Clustered {
+            return None;
+        }
+        let schema = &self.contract.output_schema;
+        let ordering = self
+            .table_configuration()
+            .metadata()
+            .partition_columns()
+            .iter()
+            .map_while(|name| Column::new_with_schema(name, schema).ok())
+            .map(|column| PhysicalSortExpr::new_default(Arc::new(column)))
+            .collect_vec();
+        (!ordering.is_empty()).then_some(ordering)
+    }
 }
 
 pub(crate) fn build_parquet_predicate_schema(
@@ -491,27 +578,50 @@ pub(crate) fn supports_filters_pushdown(
         .as_deref()
         .unwrap_or(FILE_ID_COLUMN_DEFAULT);
 
-    // Parquet predicate pushdown is enabled only when we can safely apply it at read time.
-    // Deletion vectors require preserving row order for selection masks, and row tracking
-    // disables predicate pushdown in the read plan.
-    let parquet_pushdown_enabled = scan_config.enable_parquet_pushdown
-        && !config.is_feature_enabled(&TableFeature::RowTracking)
-        && !config.is_feature_enabled(&TableFeature::DeletionVectors);
     filter
         .iter()
-        .map(|f| {
-            process_predicate(
-                f,
-                config,
-                scan_config,
-                file_id_field,
-                parquet_pushdown_enabled,
-            )
-            .pushdown
-        })
+        .map(|f| process_predicate(f, config, scan_config, file_id_field).pushdown)
         .collect()
 }
 
+/// Whether `expr` only references partition columns and can be evaluated by kernel.
+fn is_partition_filter(expr: &Expr, config: &TableConfiguration) -> bool {
+    let cols = config.metadata().partition_columns();
+    let column_refs = expr.column_refs();
+    !column_refs.is_empty()
+        && column_refs.iter().all(|c| cols.contains(&c.name))
+        && to_delta_predicate(expr).is_ok()
+}
+
+/// Whether the Parquet predicate may be evaluated as a row filter during decoding.
+///
+/// Parquet predicate pushdown is enabled only when we can safely apply it at read time.
+/// Deletion vectors require preserving row order for selection masks, and row tracking
+/// disables predicate pushdown in the read plan.
+fn parquet_pushdown_enabled(config: &TableConfiguration, scan_config: &DeltaScanConfig) -> bool {
+    scan_config.enable_parquet_pushdown
+        && !config.is_feature_enabled(&TableFeature::RowTracking)
+        && !config.is_feature_enabled(&TableFeature::DeletionVectors)
+}
+
+/// The key management client of the scan config, falling back to the client of the parquet
+/// encryption configured for the table.
+#[cfg(feature = "parquet-encryption")]
+fn key_management(snapshot: &Snapshot, scan_config: &DeltaScanConfig) -> Option<KeyManagementRef> {
+    scan_config.key_management.clone().or_else(|| {
+        let encryption = snapshot.load_config().parquet_encryption.as_ref()?;
+        Some(encryption.client().clone())
+    })
+}
+
+#[cfg(not(feature = "parquet-encryption"))]
+fn key_management(
+    _snapshot: &Snapshot,
+    _scan_config: &DeltaScanConfig,
+) -> Option<KeyManagementRef> {
+    None
+}
+
 /// Process a list of filter expressions and determine which
 /// predicates can be pushed down to the parquet scan and which
 /// can be handled at the kernel scan level.
@@ -531,20 +641,9 @@ fn process_filters(
         .as_deref()
         .unwrap_or(FILE_ID_COLUMN_DEFAULT);
 
-    let parquet_pushdown_enabled = scan_config.enable_parquet_pushdown
-        && !config.is_feature_enabled(&TableFeature::RowTracking)
-        && !config.is_feature_enabled(&TableFeature::DeletionVectors);
     let (parquet, kernel): (Vec<_>, Vec<_>) = filters
         .iter()
-        .map(|f| {
-            process_predicate(
-                f,
-                config,
-                scan_config,
-                file_id_field,
-                parquet_pushdown_enabled,
-            )
-        })
+        .map(|f| process_predicate(f, config, scan_config, file_id_field))
         .map(|p| (p.parquet_predicate, p.kernel_predicate))
         .unzip();
     let parquet = if config.is_feature_enabled(&TableFeature::ColumnMapping) {
@@ -573,7 +672,6 @@ fn process_predicate<'a>(
     config: &TableConfiguration,
     scan_config: &DeltaScanConfig,
     file_id_column: &str,
-    parquet_pushdown_enabled: bool,
 ) -> ProcessedPredicate<'a> {
     let cols = config.metadata().partition_columns();
     let only_partition_refs = expr.column_refs().iter().all(|c| cols.contains(&c.name));
@@ -608,13 +706,10 @@ fn process_predicate<'a>(
             // push down any predicate to parquet
             (TableProviderFilterPushDown::Inexact, None)
         } else {
-            // For non-partition predicates we can *attempt* Parquet pushdown, but it is not a
-            // correctness boundary (it may be partially applied or skipped). Keep this Inexact so
-            // DataFusion retains a post-scan Filter.
-            (
-                TableProviderFilterPushDown::Inexact,
-                parquet_pushdown_enabled.then_some(expr),
-            )
+            // For non-partition predicates we can *attempt* Parquet pruning and pushdown, but it
+            // is not a correctness boundary (it may be partially applied or skipped). Keep this
+            // Inexact so DataFusion retains a post-scan Filter.
+            (TableProviderFilterPushDown::Inexact, Some(expr))
         };
         return ProcessedPredicate {
             pushdown,
@@ -663,7 +758,7 @@ fn process_predicate<'a>(
     ProcessedPredicate {
         pushdown: TableProviderFilterPushDown::Inexact,
         kernel_predicate: None,
-        parquet_predicate: parquet_pushdown_enabled.then_some(expr),
+        parquet_predicate: Some(expr),
     }
 }
 
@@ -12,12 +12,18 @@ use std::{
 };
 
 use arrow::{array::BooleanArray, compute::filter_record_batch};
-use arrow_array::RecordBatch;
+use arrow_array::{Array as _, RecordBatch, cast::AsArray as _};
+use arrow_cast::cast;
+use arrow_schema::{Schema, SchemaRef};
 use datafusion::{
     common::{
-        ColumnStatistics, HashMap, Statistics, error::DataFusionErrorBuilder, stats::Precision,
+        ColumnStatistics, HashMap, Statistics, ToDFSchema as _, error::DataFusionErrorBuilder,
+        stats::Precision,
     },
     error::DataFusionError,
+    logical_expr::execution_props::ExecutionProps,
+    physical_expr::{PhysicalExpr, create_physical_expr},
+    prelude::Expr,
     scalar::ScalarValue,
 };
 use delta_kernel::{
@@ -47,11 +53,92 @@ use crate::{
 #[derive(Debug)]
 pub(crate) struct ReplayStats {
     pub(crate) num_scanned: usize,
+    /// Files skipped by kernel whose partition values do not satisfy the partition filters.
+    pub(crate) num_pruned_by_partition: usize,
+    /// All other files skipped by kernel, mostly based on their statistics.
+    pub(crate) num_pruned_by_stats: usize,
 }
 
 impl ReplayStats {
     fn new() -> Self {
-        Self { num_scanned: 0 }
+        Self {
+            num_scanned: 0,
+            num_pruned_by_partition: 0,
+            num_pruned_by_stats: 0,
+        }
+    }
+}
+
+/// Partition filters bound against the partition columns of a table.
+///
+/// Kernel applies partition and statistics based file skipping in one pass. Re-evaluating
+/// the partition filters on the skipped files tells us which files were pruned by partition.
+#[derive(Debug, Clone)]
+pub(crate) struct PartitionFilter {
+    predicate: Arc<dyn PhysicalExpr>,
+    schema: SchemaRef,
+}
+
+impl PartitionFilter {
+    pub(crate) fn try_new(
+        filter: &Expr,
+        table_schema: &Schema,
+        partition_columns: &[String],
+    ) -> Result<Self, DataFusionError> {
+        let fields: Vec<_> = partition_columns
+            .iter()
+            .map(|name| table_schema.field_with_name(name).cloned())
+            .try_collect()?;
+        let schema = Arc::new(Schema::new(fields));
+        let predicate = create_physical_expr(
+            filter,
+            &schema.clone().to_dfschema()?,
+            &ExecutionProps::new(),
+        )?;
+        Ok(Self { predicate, schema })
+    }
+
+    /// Number of files whose partition values do not satisfy the filter.
+    fn count_pruned(
+        &self,
+        partition_values: &[Option<StructData>],
+    ) -> Result<usize, DataFusionError> {
+        if partition_values.is_empty() {
+            return Ok(0);
+        }
+        let columns: Vec<_> = self
+            .schema
+            .fields()
+            .iter()
+            .map(|field| {
+                let values: Vec<_> = partition_values
+                    .iter()
+                    .map(|values| {
+                        match values
+                            .as_ref()
+                            .and_then(|v| v.index_of(field.name()).map(|idx| &v.values()[idx]))
+                        {
+                            Some(value) => to_datafusion_scalar(value),
+                            None => ScalarValue::try_from(field.data_type()),
+                        }
+                    })
+                    .try_collect()?;
+                Ok::<_, DataFusionError>(cast(
+                    &ScalarValue::iter_to_array(values)?,
+                    field.data_type(),
+                )?)
+            })
+            .try_collect()?;
+        let batch = RecordBatch::try_new(self.schema.clone(), columns)?;
+        let result = self
+            .predicate
+            .evaluate(&batch)?
+            .into_array(batch.num_rows())?;
+        Ok(result
+            .as_boolean()
+            .iter()
+            .filter(|keep| *keep == Some(false))
+            .count())
     }
 }
 
@@ -78,6 +165,8 @@ pin_project! {
 
         file_selection: Option<&'a HashSet<String>>,
 
+        partition_filter: Option<PartitionFilter>,
+
         pub(crate) dv_stream: ReceiverStreamBuilder<(Url, Option<Vec<bool>>, Option<u64>)>,
 
         #[pin]
@@ -102,8 +191,18 @@ impl<'a, S> ScanFileStream<'a, S> {
             stream,
             scan_config,
             file_selection,
+            partition_filter: None,
         }
     }
+
+    /// Attribute files skipped by kernel to partition pruning using `partition_filter`.
+    pub(crate) fn with_partition_filter(
+        mut self,
+        partition_filter: Option<PartitionFilter>,
+    ) -> Self {
+        self.partition_filter = partition_filter;
+        self
+    }
 }
 
 impl<'a, S> Stream for ScanFileStream<'a, S>
@@ -126,6 +225,15 @@ where
         };
         match this.stream.poll_next(cx) {
             Poll::Ready(Some(Ok(scan_data))) => {
+                let scan_data = match record_pruned_files(
+                    scan_data,
+                    this.kernel_scan,
+                    this.partition_filter.as_ref(),
+                    this.metrics,
+                ) {
+                    Ok(scan_data) => scan_data,
+                    Err(err) => return Poll::Ready(Some(Err(err))),
+                };
                 let scan_data = if let Some(selection) = this.file_selection {
                     match apply_file_selection(scan_data, this.table_root, selection) {
                         Ok(scan_data) => scan_data,
@@ -451,6 +559,57 @@ pub(super) fn parse_path(url: &Url, path: &str) -> DeltaResult<Url, DataFusionEr
     })
 }
 
+/// Record the files skipped by kernel file skipping in `metrics`.
+///
+/// Skipped files are add actions in the scan metadata which are not selected. Rows of other
+/// actions carry no path.
+fn record_pruned_files(
+    mut scan_data: ScanMetadata,
+    kernel_scan: &KernelScan,
+    partition_filter: Option<&PartitionFilter>,
+    metrics: &mut ReplayStats,
+) -> DeltaResult<ScanMetadata> {
+    let (data, mut selection_vector) = scan_data.scan_files.into_parts();
+    let batch: RecordBatch = ArrowEngineData::try_from_engine_data(data)?.into();
+
+    // Kernel allows a shorter selection vector; missing entries are implicitly true.
+    selection_vector.resize(batch.num_rows(), true);
+
+    let pruned = match batch.column_by_name("path") {
+        Some(paths) => BooleanArray::from(
+            selection_vector
+                .iter()
+                .enumerate()
+                .map(|(idx, select)| !select && paths.is_valid(idx))
+                .collect_vec(),
+        ),
+        None => BooleanArray::from(vec![false; batch.num_rows()]),
+    };
+    let num_pruned = pruned.true_count();
+    if num_pruned > 0 {
+        let num_pruned_by_partition = if let Some(filter) = partition_filter {
+            let pruned_files = filter_record_batch(&batch, &pruned)?;
+            let stats_projection = StatsProjection::for_scan(kernel_scan)?;
+            let snapshot = kernel_scan.snapshot();
+            let stats_schema = stats_projection.stats_schema(snapshot)?;
+            let parsed =
+                parse_stats_column_with_schema(snapshot.as_ref(), &pruned_files, stats_schema)?;
+            let partition_values = (0..parsed.num_rows())
+                .map(|idx| LogicalFileView::new(parsed.clone(), idx).partition_values())
+                .collect_vec();
+            filter.count_pruned(&partition_values)?
+        } else {
+            0
+        };
+        metrics.num_pruned_by_partition += num_pruned_by_partition;
+        metrics.num_pruned_by_stats += num_pruned - num_pruned_by_partition;
+    }
+
+    scan_data.scan_files =
+        FilteredEngineData::try_new(Box::new(ArrowEngineData::new(batch)), selection_vector)?;
+    Ok(scan_data)
+}
+
 fn apply_file_selection(
     mut scan_data: ScanMetadata,
     table_root: &Url,
@@ -26,6 +26,128 @@ impl std::fmt::Display for ColumnMappingOperation {
     }
 }
 
+/// Category of a [`DeltaTableError`], to handle errors without matching on their messages.
+///
+/// Every kind has a stable [code](ErrorKind::code), and [retryable](ErrorKind::is_retryable)
+/// kinds mark errors after which running the operation again against the latest version of the
+/// table may succeed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum ErrorKind {
+    /// A transient failure of the storage or log store, e.g. a timeout or throttling.
+    Storage,
+    /// The table or a file of it does not exist.
+    NotFound,
+    /// The storage denied access to the table.
+    PermissionDenied,
+    /// A concurrent transaction committed a conflicting change first.
+    CommitConflict,
+    /// The commit did not succeed within the attempts or deadline of its retry policy.
+    CommitRetriesExhausted,
+    /// The data or schema does not match the table.
+    Schema,
+    /// The protocol or properties of the table do not permit the operation.
+    Protocol,
+    /// The log or metadata of the table is invalid.
+    InvalidTable,
+    /// An argument of the operation is invalid, e.g. a version or partition filter.
+    InvalidArgument,
+    /// The operation is not supported by this build of delta-rs.
+    Unsupported,
+    /// The operation was cancelled.
+    Cancelled,
+    /// Any other error.
+    Internal,
+}
+
+impl ErrorKind {
+    /// Stable code of the kind, e.g. `DELTA_COMMIT_CONFLICT`
+    pub fn code(&self) -> &'static str {
+        match self {
+            Self::Storage => "DELTA_STORAGE",
+            Self::NotFound => "DELTA_NOT_FOUND",
+            Self::PermissionDenied => "DELTA_PERMISSION_DENIED",
+            Self::CommitConflict => "DELTA_COMMIT_CONFLICT",
+            Self::CommitRetriesExhausted => "DELTA_COMMIT_RETRIES_EXHAUSTED",
+            Self::Schema => "DELTA_SCHEMA",
+            Self::Protocol => "DELTA_PROTOCOL",
+            Self::InvalidTable => "DELTA_INVALID_TABLE",
+            Self::InvalidArgument => "DELTA_INVALID_ARGUMENT",
+            Self::Unsupported => "DELTA_UNSUPPORTED",
+            Self::Cancelled => "DELTA_CANCELLED",
+            Self::Internal => "DELTA_INTERNAL",
+        }
+    }
+
+    /// Whether running the operation again may succeed
+    pub fn is_retryable(&self) -> bool {
+        matches!(
+            self,
+            Self::Storage | Self::CommitConflict | Self::CommitRetriesExhausted
+        )
+    }
+
+    pub(crate) fn from_object_store(err: &ObjectStoreError) -> Self {
+        match err {
+            ObjectStoreError::NotFound { .. } => Self::NotFound,
+            // the version was written by a concurrent writer
+            ObjectStoreError::AlreadyExists { .. } | ObjectStoreError::Precondition { .. } => {
+                Self::CommitConflict
+            }
+            ObjectStoreError::PermissionDenied { .. }
+            | ObjectStoreError::Unauthenticated { .. } => Self::PermissionDenied,
+            ObjectStoreError::NotSupported { .. } | ObjectStoreError::NotImplemented { .. } => {
+                Self::Unsupported
+            }
+            ObjectStoreError::InvalidPath { .. }
+            | ObjectStoreError::UnknownConfigurationKey { .. } => Self::InvalidArgument,
+            _ => Self::Storage,
+        }
+    }
+
+    fn from_io(err: &std::io::Error) -> Self {
+        use std::io::ErrorKind as IoErrorKind;
+        match err.kind() {
+            IoErrorKind::NotFound => Self::NotFound,
+            IoErrorKind::PermissionDenied => Self::PermissionDenied,
+            IoErrorKind::Unsupported => Self::Unsupported,
+            IoErrorKind::TimedOut
+            | IoErrorKind::Interrupted
+            | IoErrorKind::WouldBlock
+            | IoErrorKind::ConnectionRefused
+            | IoErrorKind::ConnectionReset
+            | IoErrorKind::ConnectionAborted
+            | IoErrorKind::NotConnected
+            | IoErrorKind::BrokenPipe => Self::Storage,
+            _ => Self::Internal,
+        }
+    }
+
+    fn from_kernel(err: &crate::kernel::Error) -> Self {
+        use crate::kernel::Error;
+        match err {
+            Error::ObjectStore(source) => Self::from_object_store(source),
+            Error::FileNotFound(_) => Self::NotFound,
+            Error::MissingColumn(_) | Error::UnexpectedColumnType(_) | Error::Schema(_) => {
+                Self::Schema
+            }
+            Error::MissingVersion
+            | Error::MissingMetadata
+            | Error::MalformedJson(_)
+            | Error::MetadataError(_)
+            | Error::InvalidInvariantJson { .. }
+            | Error::InvalidGenerationExpressionJson { .. } => Self::InvalidTable,
+            _ => Self::Internal,
+        }
+    }
+}
+
+impl std::fmt::Display for ErrorKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.code())
+    }
+}
+
 /// Delta Table specific error
 #[allow(missing_docs)]
 #[derive(thiserror::Error, Debug)]
@@ -211,6 +333,13 @@ pub enum DeltaTableError {
     #[error("Table has not yet been initialized with files, therefore {0} is not supported")]
     NotInitializedWithFiles(String),
 
+    /// Error returned when the file actions of a snapshot which is loaded with files exceed
+    /// [`DeltaTableConfig::max_materialized_files_bytes`](crate::DeltaTableConfig::max_materialized_files_bytes).
+    #[error(
+        "File actions of version {version} exceed the memory budget of {max_bytes} bytes, load the table without files instead"
+    )]
+    MemoryBudgetExceeded { version: Version, max_bytes: usize },
+
     #[error("Change Data not enabled for version: {version}, Start: {start}, End: {end}")]
     ChangeDataNotRecorded {
         version: Version,
@@ -224,8 +353,23 @@ pub enum DeltaTableError {
     #[error("Invalid version. Start version {start} is greater than end version {end}")]
     ChangeDataInvalidVersionRange { start: Version, end: Version },
 
-    #[error("End timestamp {ending_timestamp} is greater than latest commit timestamp")]
-    ChangeDataTimestampGreaterThanCommit { ending_timestamp: DateTime<Utc> },
+    #[error(
+        "Timestamp {timestamp} is greater than latest commit timestamp, valid range is {earliest_timestamp} to {latest_timestamp}"
+    )]
+    ChangeDataTimestampGreaterThanCommit {
+        timestamp: DateTime<Utc>,
+        earliest_timestamp: DateTime<Utc>,
+        latest_timestamp: DateTime<Utc>,
+    },
+
+    #[error(
+        "Timestamp {timestamp} is before earliest commit timestamp, valid range is {earliest_timestamp} to {latest_timestamp}"
+    )]
+    ChangeDataTimestampBeforeFirstCommit {
+        timestamp: DateTime<Utc>,
+        earliest_timestamp: DateTime<Utc>,
+        latest_timestamp: DateTime<Utc>,
+    },
 
     #[error("No starting version or timestamp provided for CDC")]
     NoStartingVersionOrTimestamp,
@@ -239,6 +383,11 @@ pub enum DeltaTableError {
         /// Human-readable description of the operation (e.g. "ADD COLUMN").
         operation: String,
     },
+
+    /// Error returned when an operation was cancelled via its cancellation token before it
+    /// committed.
+    #[error("Operation was cancelled before it committed")]
+    Cancelled,
 }
 
 impl From<object_store::path::Error> for DeltaTableError {
@@ -280,4 +429,115 @@ impl DeltaTableError {
             operation: operation.to_string(),
         }
     }
+
+    /// The [kind](ErrorKind) of the error
+    pub fn kind(&self) -> ErrorKind {
+        match self {
+            Self::KernelError(delta_kernel::Error::ObjectStore(source))
+            | Self::ObjectStore { source } => ErrorKind::from_object_store(source),
+            Self::KernelError(delta_kernel::Error::FileNotFound(_)) => ErrorKind::NotFound,
+            Self::Kernel { source } => ErrorKind::from_kernel(source),
+            Self::Io { source } => ErrorKind::from_io(source),
+            Self::Transaction { source } => source.kind(),
+            Self::VersionAlreadyExists(_) => ErrorKind::CommitConflict,
+            Self::Cancelled => ErrorKind::Cancelled,
+            Self::NotATable(_) => ErrorKind::NotFound,
+            Self::InvalidData { .. } | Self::SchemaMismatch { .. } => ErrorKind::Schema,
+            Self::Arrow {
+                source: arrow::error::ArrowError::SchemaError(_),
+            } => ErrorKind::Schema,
+            Self::InvalidJsonLog { .. }
+            | Self::InvalidStatsJson { .. }
+            | Self::NoSchema
+            | Self::MetadataError(_) => ErrorKind::InvalidTable,
+            Self::MissingFeature { .. }
+            | Self::NotInitializedWithFiles(_)
+            | Self::UnsupportedColumnMapping { .. } => ErrorKind::Unsupported,
+            Self::InvalidVersion(_)
+            | Self::VersionDowngrade { .. }
+            | Self::InvalidDateTimeString { .. }
+            | Self::PartitionError { .. }
+            | Self::InvalidPartitionFilter { .. }
+            | Self::CommitValidation { .. }
+            | Self::VersionMismatch(_, _)
+            | Self::InvalidTableLocation(_)
+            | Self::NotInitialized
+            | Self::MemoryBudgetExceeded { .. }
+            | Self::ChangeDataNotRecorded { .. }
+            | Self::ChangeDataNotEnabled { .. }
+            | Self::ChangeDataInvalidVersionRange { .. }
+            | Self::ChangeDataTimestampGreaterThanCommit { .. }
+            | Self::ChangeDataTimestampBeforeFirstCommit { .. }
+            | Self::NoStartingVersionOrTimestamp => ErrorKind::InvalidArgument,
+            Self::KernelError(_)
+            | Self::Parquet { .. }
+            | Self::Arrow { .. }
+            | Self::SerializeLogJson { .. }
+            | Self::Generic(_)
+            | Self::GenericError { .. } => ErrorKind::Internal,
+        }
+    }
+
+    /// Stable code of the [kind](ErrorKind) of the error, e.g. `DELTA_COMMIT_CONFLICT`
+    pub fn code(&self) -> &'static str {
+        self.kind().code()
+    }
+
+    /// Whether running the operation again, against the latest version of the table, may succeed.
+    ///
+    /// This is the case for transient storage failures and for commits which lost the race
+    /// against a concurrent writer.
+    pub fn is_retryable(&self) -> bool {
+        self.kind().is_retryable()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::*;
+    use crate::kernel::transaction::CommitConflictError;
+
+    #[test]
+    fn test_error_kind() {
+        let conflict: DeltaTableError =
+            TransactionError::from(CommitConflictError::ConcurrentAppend).into();
+        assert_eq!(conflict.kind(), ErrorKind::CommitConflict);
+        assert_eq!(conflict.code(), "DELTA_COMMIT_CONFLICT");
+        assert!(conflict.is_retryable());
+
+        let exhausted: DeltaTableError =
+            TransactionError::CommitDeadlineExceeded(Duration::from_secs(1)).into();
+        assert_eq!(exhausted.kind(), ErrorKind::CommitRetriesExhausted);
+        assert!(DeltaTableError::VersionAlreadyExists(1).is_retryable());
+
+        let timeout = DeltaTableError::ObjectStore {
+            source: ObjectStoreError::Generic {
+                store: "memory",
+                source: "timed out".into(),
+            },
+        };
+        assert_eq!(timeout.kind(), ErrorKind::Storage);
+        assert!(timeout.is_retryable());
+
+        let not_found = DeltaTableError::ObjectStore {
+            source: ObjectStoreError::NotFound {
+                path: "_delta_log/00000000000000000000.json".into(),
+                source: "not found".into(),
+            },
+        };
+        assert_eq!(not_found.kind(), ErrorKind::NotFound);
+        assert!(!not_found.is_retryable());
+
+        let protocol: DeltaTableError = TransactionError::DeltaTableAppendOnly.into();
+        assert_eq!(protocol.kind(), ErrorKind::Protocol);
+        assert!(!protocol.is_retryable());
+        let schema = DeltaTableError::SchemaMismatch {
+            msg: "missing column".into(),
+        };
+        assert_eq!(schema.kind(), ErrorKind::Schema);
+        assert!(!schema.is_retryable());
+        assert_eq!(DeltaTableError::Cancelled.code(), "DELTA_CANCELLED");
+    }
 }
@@ -0,0 +1,285 @@
+//! Approximate distinct counts of column values.
+//!
+//! Writers collect a [`DistinctCountSketch`] per column and data file for the columns listed in
+//! the `delta-rs.dataSkippingDistinctCountColumns` table property and store it in the
+//! `distinctCountSketches` of the file statistics. Sketches of several files are merged to
+//! estimate the number of distinct values of a column in the table.
+//!
+//! The sketches are HyperLogLog sketches with 2^10 registers, so estimates are off by about 3%.
+use arrow::array::AsArray;
+use arrow_array::{Array, ArrayRef};
+use arrow_cast::display::{ArrayFormatter, FormatOptions};
+use arrow_schema::DataType;
+use base64::Engine as _;
+use base64::engine::general_purpose::STANDARD;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+use crate::{DeltaResult, DeltaTableError};
+
+/// Number of bits of a hash selecting the register
+const PRECISION: u8 = 10;
+const NUM_REGISTERS: usize = 1 << PRECISION;
+
+/// A HyperLogLog sketch estimating the number of distinct non-null values of a column.
+#[derive(Clone, PartialEq, Eq)]
+pub struct DistinctCountSketch {
+    registers: Box<[u8]>,
+}
+
+impl Default for DistinctCountSketch {
+    fn default() -> Self {
+        Self {
+            registers: vec![0; NUM_REGISTERS].into_boxed_slice(),
+        }
+    }
+}
+
+impl std::fmt::Debug for DistinctCountSketch {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "DistinctCountSketch(~{})", self.estimate())
+    }
+}
+
+impl DistinctCountSketch {
+    /// Create an empty sketch
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add the value with the binary representation `value` to the sketch
+    pub fn insert(&mut self, value: &[u8]) {
+        let hash = hash(value);
+        let index = (hash >> (64 - PRECISION)) as usize;
+        // position of the first set bit among the remaining bits, an empty remainder counts as
+        // if the bit right after it was set
+        let rank = ((hash << PRECISION) | (1 << (PRECISION - 1))).leading_zeros() as u8 + 1;
+        if rank > self.registers[index] {
+            self.registers[index] = rank;
+        }
+    }
+
+    /// Add all non-null values of `array` to the sketch.
+    ///
+    /// Values are added by their binary representation, so values of different types with the
+    /// same representation are counted once.
+    pub fn update(&mut self, array: &dyn Array) -> DeltaResult<()> {
+        let valid = |i: usize| array.is_valid(i);
+        match array.data_type() {
+            DataType::Utf8 => array.as_string::<i32>().iter().flatten().for_each(|value| {
+                self.insert(value.as_bytes());
+            }),
+            DataType::LargeUtf8 => array.as_string::<i64>().iter().flatten().for_each(|value| {
+                self.insert(value.as_bytes());
+            }),
+            DataType::Utf8View => array.as_string_view().iter().flatten().for_each(|value| {
+                self.insert(value.as_bytes());
+            }),
+            DataType::Binary => array
+                .as_binary::<i32>()
+                .iter()
+                .flatten()
+                .for_each(|value| self.insert(value)),
+            DataType::LargeBinary => array
+                .as_binary::<i64>()
+                .iter()
+                .flatten()
+                .for_each(|value| self.insert(value)),
+            DataType::BinaryView => array
+                .as_binary_view()
+                .iter()
+                .flatten()
+                .for_each(|value| self.insert(value)),
+            DataType::FixedSizeBinary(_) => array
+                .as_fixed_size_binary()
+                .iter()
+                .flatten()
+                .for_each(|value| self.insert(value)),
+            DataType::Boolean => array
+                .as_boolean()
+                .iter()
+                .flatten()
+                .for_each(|value| self.insert(&[value as u8])),
+            DataType::Dictionary(_, value_type) => {
+                let values: ArrayRef = arrow_cast::cast(array, value_type)?;
+                self.update(values.as_ref())?;
+            }
+            data_type if data_type.primitive_width().is_some() => {
+                let width = data_type.primitive_width().unwrap_or_default();
+                let data = array.to_data();
+                let values = &data.buffers()[0].as_slice()[data.offset() * width..];
+                (0..array.len())
+                    .filter(|i| valid(*i))
+                    .for_each(|i| self.insert(&values[i * width..(i + 1) * width]));
+            }
+            // nested values are counted by their display representation
+            _ => {
+                let formatter = ArrayFormatter::try_new(array, &FormatOptions::default())?;
+                (0..array.len())
+                    .filter(|i| valid(*i))
+                    .for_each(|i| self.insert(formatter.value(i).to_string().as_bytes()));
+            }
+        }
+        Ok(())
+    }
+
+    /// Add the values counted by `other` to the sketch
+    pub fn merge(&mut self, other: &DistinctCountSketch) {
+        self.registers
+            .iter_mut()
+            .zip(other.registers.iter())
+            .for_each(|(register, other)| *register = (*register).max(*other));
+    }
+
+    /// The estimated number of distinct values added to the sketch
+    pub fn estimate(&self) -> u64 {
+        let m = NUM_REGISTERS as f64;
+        let alpha = 0.7213 / (1.0 + 1.079 / m);
+        let sum: f64 = self
+            .registers
+            .iter()
+            .map(|register| 2f64.powi(-(*register as i32)))
+            .sum();
+        let estimate = alpha * m * m / sum;
+
+        let zeros = self
+            .registers
+            .iter()
+            .filter(|register| **register == 0)
+            .count();
+        if estimate <= 2.5 * m && zeros > 0 {
+            // linear counting is more accurate for small cardinalities
+            (m * (m / zeros as f64).ln()).round() as u64
+        } else {
+            estimate.round() as u64
+        }
+    }
+
+    /// Serialize the sketch as base64 encoded precision followed by the registers
+    pub fn to_base64(&self) -> String {
+        let mut bytes = Vec::with_capacity(NUM_REGISTERS + 1);
+        bytes.push(PRECISION);
+        bytes.extend_from_slice(&self.registers);
+        STANDARD.encode(bytes)
+    }
+
+    /// Deserialize a sketch serialized by [`to_base64`](Self::to_base64)
+    pub fn try_from_base64(value: &str) -> DeltaResult<Self> {
+        let invalid = || DeltaTableError::Generic("Invalid distinct count sketch".to_string());
+        let bytes = STANDARD.decode(value).map_err(|_| invalid())?;
+        match bytes.split_first() {
+            Some((&PRECISION, registers)) if registers.len() == NUM_REGISTERS => Ok(Self {
+                registers: registers.into(),
+            }),
+            _ => Err(invalid()),
+        }
+    }
+}
+
+impl Serialize for DistinctCountSketch {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&self.to_base64())
+    }
+}
+
+impl<'de> Deserialize<'de> for DistinctCountSketch {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let value = String::deserialize(deserializer)?;
+        Self::try_from_base64(&value).map_err(serde::de::Error::custom)
+    }
+}
+
+/// FNV-1a followed by the finalizer of MurmurHash3 to spread the bits.
+///
+/// The hash has to be stable across versions and platforms, since sketches are persisted.
+fn hash(value: &[u8]) -> u64 {
+    let mut hash: u64 = 0xcbf29ce484222325;
+    for byte in value {
+        hash ^= *byte as u64;
+        hash = hash.wrapping_mul(0x100000001b3);
+    }
+    hash ^= hash >> 33;
+    hash = hash.wrapping_mul(0xff51afd7ed558ccd);
+    hash ^= hash >> 33;
+    hash = hash.wrapping_mul(0xc4ceb9fe1a85ec53);
+    hash ^ (hash >> 33)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use arrow_array::{DictionaryArray, Int64Array, StringArray, types::Int32Type};
+
+    use super::*;
+
+    fn assert_close(estimate: u64, expected: u64) {
+        let error = (estimate as f64 - expected as f64).abs() / expected as f64;
+        assert!(
+            error < 0.1,
+            "estimate {estimate} is too far from {expected}"
+        );
+    }
+
+    #[test]
+    fn test_estimate() {
+        assert_eq!(DistinctCountSketch::new().estimate(), 0);
+
+        for expected in [10, 1_000, 100_000] {
+            let mut sketch = DistinctCountSketch::new();
+            let values = Int64Array::from_iter_values(0..expected);
+            sketch.update(&values).unwrap();
+            // duplicates do not change the estimate
+            sketch.update(&values).unwrap();
+            assert_close(sketch.estimate(), expected as u64);
+        }
+    }
+
+    #[test]
+    fn test_update_skips_nulls() {
+        let mut sketch = DistinctCountSketch::new();
+        let values = StringArray::from(vec![Some("a"), None, Some("b"), Some("a"), None]);
+        sketch.update(&values).unwrap();
+        assert_eq!(sketch.estimate(), 2);
+
+        let values: DictionaryArray<Int32Type> = vec!["a", "c", "c"].into_iter().collect();
+        sketch.update(&values).unwrap();
+        assert_eq!(sketch.estimate(), 3);
+    }
+
+    #[test]
+    fn test_merge() {
+        let mut left = DistinctCountSketch::new();
+        left.update(&Int64Array::from_iter_values(0..5_000))
+            .unwrap();
+        let mut right = DistinctCountSketch::new();
+        right
+            .update(&Int64Array::from_iter_values(2_500..10_000))
+            .unwrap();
+        left.merge(&right);
+        assert_close(left.estimate(), 10_000);
+
+        // slices of an array are counted like the values they contain
+        let values: ArrayRef = Arc::new(Int64Array::from_iter_values(0..100));
+        let mut sliced = DistinctCountSketch::new();
+        sliced.update(&values.slice(50, 50)).unwrap();
+        let mut expected = DistinctCountSketch::new();
+        expected
+            .update(&Int64Array::from_iter_values(50..100))
+            .unwrap();
+        assert_eq!(sliced, expected);
+    }
+
+    #[test]
+    fn test_serialization() {
+        let mut sketch = DistinctCountSketch::new();
+        sketch
+            .update(&StringArray::from(vec!["a", "b", "c"]))
+            .unwrap();
+        let json = serde_json::to_string(&sketch).unwrap();
+        let parsed: DistinctCountSketch = serde_json::from_str(&json).unwrap();
+        assert_eq!(parsed, sketch);
+
+        assert!(DistinctCountSketch::try_from_base64("not a sketch").is_err());
+        assert!(DistinctCountSketch::try_from_base64(&STANDARD.encode([PRECISION, 0])).is_err());
+    }
+}
@@ -9,6 +9,7 @@ use tracing::Span;
 use tracing::dispatcher;
 
 pub mod arrow;
+pub mod distinct_count;
 pub mod error;
 /// Core Delta log action models (Add, Remove, Metadata, Protocol, ...) and related types.
 pub mod models;
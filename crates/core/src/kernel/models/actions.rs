@@ -443,6 +443,7 @@ impl ProtocolInner {
     /// features
     pub fn apply_column_metadata_to_protocol(mut self, schema: &StructType) -> DeltaResult<Self> {
         let generated_cols = schema.get_generated_columns()?;
+        let column_defaults = schema.get_column_defaults()?;
         let invariants = schema.get_invariants()?;
         let contains_timestamp_ntz = self.contains_timestampntz(schema.fields());
         #[cfg(feature = "nanosecond-timestamps")]
@@ -468,6 +469,10 @@ impl ProtocolInner {
             self = self.enable_generated_columns()
         }
 
+        if !column_defaults.is_empty() {
+            self = self.enable_column_defaults()
+        }
+
         if !invariants.is_empty() {
             self = self.enable_invariants()
         }
@@ -654,6 +659,11 @@ impl ProtocolInner {
         self
     }
 
+    /// Enable column default values, a writer-only feature that requires writer version 7
+    fn enable_column_defaults(self) -> Self {
+        self.append_writer_features([allow_column_defaults_feature()])
+    }
+
     /// Enabled generated columns
     fn enable_invariants(mut self) -> Self {
         if self.min_writer_version >= 7 {
@@ -706,6 +716,8 @@ pub enum TableFeatures {
     VariantShreddingPreview,
     /// Support for materializing partition column values into data files.
     MaterializePartitionColumns,
+    /// Columns with default values
+    AllowColumnDefaults,
 }
 
 impl FromStr for TableFeatures {
@@ -732,6 +744,7 @@ impl FromStr for TableFeatures {
             "variantType-preview" => Ok(TableFeatures::VariantTypePreview),
             "variantShredding-preview" => Ok(TableFeatures::VariantShreddingPreview),
             "materializePartitionColumns" => Ok(TableFeatures::MaterializePartitionColumns),
+            "allowColumnDefaults" => Ok(TableFeatures::AllowColumnDefaults),
             _ => Err(()),
         }
     }
@@ -759,6 +772,7 @@ impl AsRef<str> for TableFeatures {
             TableFeatures::VariantTypePreview => "variantType-preview",
             TableFeatures::VariantShreddingPreview => "variantShredding-preview",
             TableFeatures::MaterializePartitionColumns => "materializePartitionColumns",
+            TableFeatures::AllowColumnDefaults => "allowColumnDefaults",
         }
     }
 }
@@ -780,6 +794,9 @@ impl TryFrom<&TableFeatures> for TableFeature {
 impl TableFeatures {
     /// Convert table feature to respective reader or/and write feature
     pub fn to_reader_writer_features(&self) -> (Option<TableFeature>, Option<TableFeature>) {
+        if self == &TableFeatures::AllowColumnDefaults {
+            return (None, Some(allow_column_defaults_feature()));
+        }
         let feature = TableFeature::try_from(self).ok();
         match feature {
             Some(feature) => {
@@ -832,6 +849,12 @@ impl TableFeatures {
     }
 }
 
+/// The kernel representation of the writer-only `allowColumnDefaults` feature.
+pub(crate) fn allow_column_defaults_feature() -> TableFeature {
+    let Ok(feature) = TableFeature::try_from(&TableFeatures::AllowColumnDefaults);
+    feature
+}
+
 ///Storage type of deletion vector
 #[derive(Serialize, Deserialize, Copy, Clone, Debug, PartialEq, Eq, Default)]
 pub enum StorageType {
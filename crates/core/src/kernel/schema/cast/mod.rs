@@ -237,6 +237,197 @@ pub fn cast_record_batch(
     )?)
 }
 
+/// Returns `true` if both types map to the same Delta type, e.g. `Utf8View` and `Utf8`,
+/// `LargeList<LargeBinary>` and `List<Binary>`, or `Dictionary<Int32, Utf8>` and `Utf8`.
+fn is_same_delta_type(a: &DataType, b: &DataType) -> bool {
+    match (a, b) {
+        (DataType::Dictionary(_, a), DataType::Dictionary(_, b)) => is_same_delta_type(a, b),
+        (DataType::Dictionary(_, value), other) | (other, DataType::Dictionary(_, value)) => {
+            is_same_delta_type(value, other)
+        }
+        (
+            DataType::Utf8 | DataType::LargeUtf8 | DataType::Utf8View,
+            DataType::Utf8 | DataType::LargeUtf8 | DataType::Utf8View,
+        )
+        | (
+            DataType::Binary | DataType::LargeBinary | DataType::BinaryView,
+            DataType::Binary | DataType::LargeBinary | DataType::BinaryView,
+        ) => true,
+        (
+            DataType::List(a) | DataType::LargeList(a),
+            DataType::List(b) | DataType::LargeList(b),
+        ) => a.is_nullable() == b.is_nullable() && is_same_delta_type(a.data_type(), b.data_type()),
+        (DataType::Map(a, _), DataType::Map(b, _)) => {
+            is_same_delta_type(a.data_type(), b.data_type())
+        }
+        (DataType::Struct(a), DataType::Struct(b)) => is_same_delta_fields(a, b),
+        _ => a.equals_datatype(b),
+    }
+}
+
+fn is_same_delta_fields(fields: &Fields, target: &Fields) -> bool {
+    fields.len() == target.len()
+        && target.iter().all(|target_field| {
+            fields.find(target_field.name()).is_some_and(|(_, field)| {
+                field.is_nullable() == target_field.is_nullable()
+                    && is_same_delta_type(field.data_type(), target_field.data_type())
+            })
+        })
+}
+
+/// Returns `true` if `schema` describes the same Delta schema as `target` and only differs in
+/// the Arrow encoding of its fields, such as large, view or dictionary encoded types.
+///
+/// Batches with such a schema can be cast to `target` with [`cast_record_batch`].
+pub(crate) fn is_same_delta_schema(schema: &Schema, target: &Schema) -> bool {
+    is_same_delta_fields(schema.fields(), target.fields())
+}
+
+/// Returns `target` with the dictionary encoding of the matching top-level fields in `schema`,
+/// so dictionary arrays can be handed to the parquet writer without being materialized.
+///
+/// Fields listed in `exclude` keep the type from `target`.
+pub(crate) fn with_dictionary_encoding(
+    target: &ArrowSchemaRef,
+    schema: &Schema,
+    exclude: &[String],
+) -> ArrowSchemaRef {
+    let mut changed = false;
+    let fields: Vec<FieldRef> = target
+        .fields()
+        .iter()
+        .map(
+            |target_field| match schema.field_with_name(target_field.name()) {
+                Ok(field) if !exclude.contains(target_field.name()) => match field.data_type() {
+                    DataType::Dictionary(key_type, _)
+                        if !matches!(target_field.data_type(), DataType::Dictionary(..)) =>
+                    {
+                        changed = true;
+                        Arc::new(target_field.as_ref().clone().with_data_type(
+                            DataType::Dictionary(
+                                key_type.clone(),
+                                Box::new(target_field.data_type().clone()),
+                            ),
+                        ))
+                    }
+                    _ => Arc::clone(target_field),
+                },
+                _ => Arc::clone(target_field),
+            },
+        )
+        .collect();
+
+    if changed {
+        Arc::new(Schema::new_with_metadata(fields, target.metadata().clone()))
+    } else {
+        Arc::clone(target)
+    }
+}
+
+/// Controls how the types of incoming data are reconciled with the table schema on write.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum CoercionPolicy {
+    /// Only accept data with the table's types.
+    ///
+    /// Other Arrow encodings of the same type, e.g. `Utf8View` for `Utf8`, and the
+    /// normalizations required by the protocol, e.g. for nanosecond timestamps, are applied.
+    Strict,
+    /// Cast data to the table's types, e.g. `Int32` to `Int64` or `UInt64` to `Int64`.
+    ///
+    /// The write fails if a value cannot be represented in the table's type.
+    #[default]
+    SafeCast,
+    /// Cast data to the table's types, writing null for values which cannot be represented.
+    ///
+    /// A warning is logged for all columns cast to a type which may not hold all their values.
+    LossyCast,
+}
+
+impl CoercionPolicy {
+    /// Whether values which fail to cast are replaced with null
+    pub(crate) fn null_on_cast_failure(&self) -> bool {
+        matches!(self, Self::LossyCast)
+    }
+
+    /// Check if a field of type `from` may be written to a column of type `to`.
+    ///
+    /// Whether the types can be cast at all is checked separately.
+    pub(crate) fn check_field(
+        &self,
+        name: &str,
+        from: &DataType,
+        to: &DataType,
+    ) -> Result<(), ArrowError> {
+        if *self == Self::Strict && !is_same_delta_type(from, to) {
+            return Err(ArrowError::SchemaError(format!(
+                "Field {name} has type {from}, but the table expects {to} and type coercion is strict"
+            )));
+        }
+        Ok(())
+    }
+
+    /// Log a warning for the given `(name, from, to)` casts which may lose information.
+    pub(crate) fn warn_lossy_casts<'a>(
+        &self,
+        casts: impl IntoIterator<Item = (&'a str, &'a DataType, &'a DataType)>,
+    ) {
+        if *self != Self::LossyCast {
+            return;
+        }
+        let lossy: Vec<_> = casts
+            .into_iter()
+            .filter(|(_, from, to)| !is_lossless_cast(from, to))
+            .map(|(name, from, to)| format!("{name} ({from} -> {to})"))
+            .collect();
+        if !lossy.is_empty() {
+            tracing::warn!(
+                fields = ?lossy,
+                "Lossy cast: values which cannot be represented in the table's type are written as null"
+            );
+        }
+    }
+}
+
+/// Returns `true` if every value of type `from` can be represented as type `to`.
+pub(crate) fn is_lossless_cast(from: &DataType, to: &DataType) -> bool {
+    use DataType::*;
+
+    fn unit_rank(unit: &TimeUnit) -> u8 {
+        match unit {
+            TimeUnit::Second => 0,
+            TimeUnit::Millisecond => 1,
+            TimeUnit::Microsecond => 2,
+            TimeUnit::Nanosecond => 3,
+        }
+    }
+
+    if is_same_delta_type(from, to) {
+        return true;
+    }
+    match (from, to) {
+        (Int8, Int16 | Int32 | Int64)
+        | (Int16, Int32 | Int64)
+        | (Int32, Int64)
+        | (UInt8, Int16 | Int32 | Int64)
+        | (UInt16, Int32 | Int64)
+        | (UInt32, Int64)
+        | (Int8 | Int16 | UInt8 | UInt16, Float32 | Float64)
+        | (Int32 | UInt32, Float64)
+        | (Float16, Float32 | Float64)
+        | (Float32, Float64)
+        | (Date32, Timestamp(_, _)) => true,
+        (Timestamp(from_unit, from_tz), Timestamp(to_unit, to_tz)) => {
+            from_tz.is_some() == to_tz.is_some() && unit_rank(from_unit) <= unit_rank(to_unit)
+        }
+        (Decimal128(from_precision, from_scale), Decimal128(to_precision, to_scale)) => {
+            from_scale <= to_scale
+                && (*from_precision as i16 - *from_scale as i16)
+                    <= (*to_precision as i16 - *to_scale as i16)
+        }
+        _ => false,
+    }
+}
+
 /// Normalizes an Arrow schema for Delta compatibility.
 ///
 /// Delta protocol supports a subset of Arrow types. This function converts
@@ -394,7 +585,7 @@ mod tests {
         StringArray, StructArray, new_empty_array, new_null_array,
     };
     use arrow::buffer::{Buffer, NullBuffer};
-    use arrow_schema::{DataType, Field, FieldRef, Fields, Schema, SchemaRef};
+    use arrow_schema::{DataType, Field, FieldRef, Fields, Schema, SchemaRef, TimeUnit};
     use delta_kernel::engine::arrow_conversion::TryIntoKernel as _;
     use delta_kernel::schema::{ColumnMetadataKey, MetadataValue};
     use itertools::Itertools;
@@ -402,13 +593,136 @@ mod tests {
     use super::normalize_for_delta;
 
     use super::merge_schema::{merge_arrow_schema, merge_delta_struct};
-    use super::{cast_record_batch, is_cast_required};
+    use super::{
+        CoercionPolicy, cast_record_batch, is_cast_required, is_lossless_cast,
+        is_same_delta_schema, with_dictionary_encoding,
+    };
     use crate::kernel::{
         ArrayType as DeltaArrayType, DataType as DeltaDataType, StructField as DeltaStructField,
         StructType as DeltaStructType,
     };
     use crate::symmetric_differences;
 
+    #[test]
+    fn test_is_lossless_cast() {
+        assert!(is_lossless_cast(&DataType::Int32, &DataType::Int64));
+        assert!(is_lossless_cast(&DataType::UInt32, &DataType::Int64));
+        assert!(is_lossless_cast(&DataType::Utf8View, &DataType::Utf8));
+        assert!(is_lossless_cast(
+            &DataType::Timestamp(TimeUnit::Millisecond, None),
+            &DataType::Timestamp(TimeUnit::Microsecond, None)
+        ));
+        assert!(is_lossless_cast(
+            &DataType::Decimal128(10, 2),
+            &DataType::Decimal128(12, 3)
+        ));
+
+        assert!(!is_lossless_cast(&DataType::Int64, &DataType::Int32));
+        assert!(!is_lossless_cast(&DataType::UInt64, &DataType::Int64));
+        assert!(!is_lossless_cast(&DataType::Int64, &DataType::Float64));
+        assert!(!is_lossless_cast(&DataType::Utf8, &DataType::Int32));
+        assert!(!is_lossless_cast(
+            &DataType::Timestamp(TimeUnit::Nanosecond, None),
+            &DataType::Timestamp(TimeUnit::Microsecond, None)
+        ));
+        assert!(!is_lossless_cast(
+            &DataType::Decimal128(10, 2),
+            &DataType::Decimal128(10, 3)
+        ));
+    }
+
+    #[test]
+    fn test_coercion_policy_check_field() {
+        let strict = CoercionPolicy::Strict;
+        assert!(
+            strict
+                .check_field("a", &DataType::LargeUtf8, &DataType::Utf8)
+                .is_ok()
+        );
+        assert!(
+            strict
+                .check_field("a", &DataType::Int32, &DataType::Int64)
+                .is_err()
+        );
+        for policy in [CoercionPolicy::SafeCast, CoercionPolicy::LossyCast] {
+            assert!(
+                policy
+                    .check_field("a", &DataType::UInt64, &DataType::Int64)
+                    .is_ok()
+            );
+        }
+    }
+
+    #[test]
+    fn test_is_same_delta_schema() {
+        let target = Schema::new(vec![
+            Field::new("s", DataType::Utf8, true),
+            Field::new("b", DataType::Binary, true),
+            Field::new_list("l", Field::new("element", DataType::Utf8, true), true),
+        ]);
+        let views = Schema::new(vec![
+            Field::new("b", DataType::BinaryView, true),
+            Field::new("s", DataType::Utf8View, true),
+            Field::new_large_list("l", Field::new("item", DataType::LargeUtf8, true), true),
+        ]);
+        assert!(is_same_delta_schema(&views, &target));
+
+        let not_nullable = Schema::new(vec![
+            Field::new("s", DataType::LargeUtf8, false),
+            Field::new("b", DataType::Binary, true),
+            Field::new_list("l", Field::new("element", DataType::Utf8, true), true),
+        ]);
+        assert!(!is_same_delta_schema(&not_nullable, &target));
+
+        let other_type = Schema::new(vec![
+            Field::new("s", DataType::Int32, true),
+            Field::new("b", DataType::Binary, true),
+            Field::new_list("l", Field::new("element", DataType::Utf8, true), true),
+        ]);
+        assert!(!is_same_delta_schema(&other_type, &target));
+
+        let dictionary = Schema::new(vec![
+            Field::new(
+                "s",
+                DataType::Dictionary(Box::new(DataType::Int32), Box::new(DataType::Utf8View)),
+                true,
+            ),
+            Field::new("b", DataType::Binary, true),
+            Field::new_list("l", Field::new("element", DataType::Utf8, true), true),
+        ]);
+        assert!(is_same_delta_schema(&dictionary, &target));
+    }
+
+    #[test]
+    fn test_with_dictionary_encoding() {
+        let target = Arc::new(Schema::new(vec![
+            Field::new("s", DataType::Utf8, true),
+            Field::new("p", DataType::Utf8, true),
+        ]));
+        let dictionary_type =
+            DataType::Dictionary(Box::new(DataType::Int8), Box::new(DataType::LargeUtf8));
+        let schema = Schema::new(vec![
+            Field::new("s", dictionary_type.clone(), true),
+            Field::new("p", dictionary_type, true),
+        ]);
+
+        let encoded = with_dictionary_encoding(&target, &schema, &["p".to_string()]);
+        assert_eq!(
+            encoded.field(0).data_type(),
+            &DataType::Dictionary(Box::new(DataType::Int8), Box::new(DataType::Utf8))
+        );
+        assert_eq!(encoded.field(1).data_type(), &DataType::Utf8);
+
+        let plain = Schema::new(vec![
+            Field::new("s", DataType::Utf8, true),
+            Field::new("p", DataType::Utf8, true),
+        ]);
+        assert!(Arc::ptr_eq(
+            &with_dictionary_encoding(&target, &plain, &[]),
+            &target
+        ));
+    }
+
     #[test]
     fn test_merge_arrow_schema_with_dict() {
         let left_schema = Arc::new(Schema::new(vec![Field::new(
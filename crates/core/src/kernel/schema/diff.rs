@@ -0,0 +1,354 @@
+//! Differences between two schemas.
+//!
+//! [`StructTypeExt::diff`](super::StructTypeExt::diff) compares the schema of a table with
+//! another schema, usually the one of data to be written, and reports the fields which were
+//! added, removed, retyped or changed their nullability. Whether the data can be written with a
+//! given [`SchemaWritePolicy`] is answered by [`SchemaDiff::is_write_compatible`], which allows
+//! validating a data contract before writing anything, e.g. in CI.
+
+use std::fmt;
+
+use crate::kernel::{DataType, PrimitiveType, StructField, StructType};
+
+/// How a write treats data whose schema differs from the table schema
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SchemaWritePolicy {
+    /// The table schema is kept. Every column of the table must be present in the data, with a
+    /// type which can be cast to the column type without loss.
+    Strict,
+    /// New columns are added to the table schema, like writes with `SchemaMode::Merge`. Missing
+    /// columns are filled with nulls and must thus be nullable.
+    Merge,
+    /// The table schema is replaced, like overwrites with `SchemaMode::Overwrite`.
+    Overwrite,
+}
+
+/// A change of a single field between two schemas
+#[derive(Debug, Clone, PartialEq)]
+pub enum FieldChange {
+    /// The field only exists in the other schema
+    Added {
+        /// Dot separated path of the field
+        path: String,
+        /// The added field
+        field: StructField,
+    },
+    /// The field only exists in the original schema
+    Removed {
+        /// Dot separated path of the field
+        path: String,
+        /// The removed field
+        field: StructField,
+    },
+    /// The type of the field changed.
+    ///
+    /// Structs, arrays and maps are compared by their children, so this is only reported for the
+    /// innermost type which differs.
+    TypeChanged {
+        /// Dot separated path of the field, `element`, `key` and `value` denote the children of
+        /// arrays and maps
+        path: String,
+        /// Type in the original schema
+        from: DataType,
+        /// Type in the other schema
+        to: DataType,
+    },
+    /// Whether the field, the elements of an array or the values of a map may be null changed
+    NullabilityChanged {
+        /// Dot separated path of the field, `element`, `key` and `value` denote the children of
+        /// arrays and maps
+        path: String,
+        /// Nullability in the original schema
+        from: bool,
+        /// Nullability in the other schema
+        to: bool,
+    },
+}
+
+impl FieldChange {
+    /// Dot separated path of the changed field
+    pub fn path(&self) -> &str {
+        match self {
+            Self::Added { path, .. }
+            | Self::Removed { path, .. }
+            | Self::TypeChanged { path, .. }
+            | Self::NullabilityChanged { path, .. } => path,
+        }
+    }
+
+    /// Whether data with the other schema can be written to a table with the original schema
+    /// despite this change
+    pub fn is_write_compatible(&self, policy: SchemaWritePolicy) -> bool {
+        match (policy, self) {
+            (SchemaWritePolicy::Overwrite, _) => true,
+            (SchemaWritePolicy::Strict, Self::Added { .. }) => false,
+            (SchemaWritePolicy::Merge, Self::Added { .. }) => true,
+            (SchemaWritePolicy::Strict, Self::Removed { .. }) => false,
+            (SchemaWritePolicy::Merge, Self::Removed { field, .. }) => field.is_nullable(),
+            (_, Self::TypeChanged { from, to, .. }) => is_lossless_cast(to, from),
+            (SchemaWritePolicy::Strict, Self::NullabilityChanged { to, .. }) => !to,
+            (SchemaWritePolicy::Merge, Self::NullabilityChanged { .. }) => true,
+        }
+    }
+}
+
+impl fmt::Display for FieldChange {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Added { path, field } => {
+                write!(f, "added field {path} of type {}", field.data_type())
+            }
+            Self::Removed { path, .. } => write!(f, "removed field {path}"),
+            Self::TypeChanged { path, from, to } => {
+                write!(f, "changed type of field {path} from {from} to {to}")
+            }
+            Self::NullabilityChanged { path, to: true, .. } => {
+                write!(f, "made field {path} nullable")
+            }
+            Self::NullabilityChanged {
+                path, to: false, ..
+            } => {
+                write!(f, "made field {path} non-nullable")
+            }
+        }
+    }
+}
+
+/// Changes between two schemas, see [`StructTypeExt::diff`](super::StructTypeExt::diff)
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct SchemaDiff {
+    changes: Vec<FieldChange>,
+}
+
+impl SchemaDiff {
+    pub(crate) fn new(from: &StructType, to: &StructType) -> Self {
+        let mut diff = Self::default();
+        diff.diff_structs("", from, to);
+        diff
+    }
+
+    /// All changes, in the order of the fields of the original schema followed by added fields
+    pub fn changes(&self) -> &[FieldChange] {
+        &self.changes
+    }
+
+    /// Whether the schemas have the same fields, types and nullability
+    pub fn is_empty(&self) -> bool {
+        self.changes.is_empty()
+    }
+
+    /// The changes which prevent writing data with the other schema under `policy`
+    pub fn incompatible_changes(
+        &self,
+        policy: SchemaWritePolicy,
+    ) -> impl Iterator<Item = &FieldChange> {
+        self.changes
+            .iter()
+            .filter(move |change| !change.is_write_compatible(policy))
+    }
+
+    /// Whether data with the other schema can be written to a table with the original schema
+    /// under `policy`
+    pub fn is_write_compatible(&self, policy: SchemaWritePolicy) -> bool {
+        self.incompatible_changes(policy).next().is_none()
+    }
+
+    fn diff_structs(&mut self, prefix: &str, from: &StructType, to: &StructType) {
+        for field in from.fields() {
+            let path = join_path(prefix, field.name());
+            match to.field(field.name()) {
+                Some(other) => {
+                    self.diff_nullability(&path, field.is_nullable(), other.is_nullable());
+                    self.diff_types(&path, field.data_type(), other.data_type());
+                }
+                None => self.changes.push(FieldChange::Removed {
+                    path,
+                    field: field.clone(),
+                }),
+            }
+        }
+        for field in to.fields() {
+            if from.field(field.name()).is_none() {
+                self.changes.push(FieldChange::Added {
+                    path: join_path(prefix, field.name()),
+                    field: field.clone(),
+                });
+            }
+        }
+    }
+
+    fn diff_types(&mut self, path: &str, from: &DataType, to: &DataType) {
+        match (from, to) {
+            (DataType::Struct(from), DataType::Struct(to)) => self.diff_structs(path, from, to),
+            (DataType::Array(from), DataType::Array(to)) => {
+                let path = join_path(path, "element");
+                self.diff_nullability(&path, from.contains_null(), to.contains_null());
+                self.diff_types(&path, from.element_type(), to.element_type());
+            }
+            (DataType::Map(from), DataType::Map(to)) => {
+                self.diff_types(&join_path(path, "key"), from.key_type(), to.key_type());
+                let path = join_path(path, "value");
+                self.diff_nullability(&path, from.value_contains_null(), to.value_contains_null());
+                self.diff_types(&path, from.value_type(), to.value_type());
+            }
+            (from, to) if from != to => self.changes.push(FieldChange::TypeChanged {
+                path: path.to_string(),
+                from: from.clone(),
+                to: to.clone(),
+            }),
+            _ => {}
+        }
+    }
+
+    fn diff_nullability(&mut self, path: &str, from: bool, to: bool) {
+        if from != to {
+            self.changes.push(FieldChange::NullabilityChanged {
+                path: path.to_string(),
+                from,
+                to,
+            });
+        }
+    }
+}
+
+impl fmt::Display for SchemaDiff {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (i, change) in self.changes.iter().enumerate() {
+            if i > 0 {
+                f.write_str(", ")?;
+            }
+            write!(f, "{change}")?;
+        }
+        Ok(())
+    }
+}
+
+fn join_path(prefix: &str, name: &str) -> String {
+    if prefix.is_empty() {
+        name.to_string()
+    } else {
+        format!("{prefix}.{name}")
+    }
+}
+
+/// Whether every value of type `from` can be represented by type `to`
+fn is_lossless_cast(from: &DataType, to: &DataType) -> bool {
+    use PrimitiveType::*;
+
+    let (DataType::Primitive(from), DataType::Primitive(to)) = (from, to) else {
+        return false;
+    };
+    match (from, to) {
+        (Byte, Short | Integer | Long | Double)
+        | (Short, Integer | Long | Double)
+        | (Integer, Long | Double)
+        | (Float, Double)
+        | (Date, TimestampNtz) => true,
+        (Decimal(from), Decimal(to)) => {
+            to.scale() >= from.scale()
+                && to.precision() - to.scale() >= from.precision() - from.scale()
+        }
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::kernel::{ArrayType, StructTypeExt as _};
+
+    fn field(name: &str, data_type: impl Into<DataType>, nullable: bool) -> StructField {
+        StructField::new(name, data_type, nullable)
+    }
+
+    #[test]
+    fn test_schema_diff() {
+        let table = StructType::try_new(vec![
+            field("id", DataType::LONG, false),
+            field("value", DataType::INTEGER, true),
+            field("dropped", DataType::STRING, true),
+            field(
+                "nested",
+                StructType::try_new(vec![field("a", DataType::INTEGER, true)]).unwrap(),
+                true,
+            ),
+            field("tags", ArrayType::new(DataType::STRING, false), true),
+        ])
+        .unwrap();
+        let data = StructType::try_new(vec![
+            field("id", DataType::LONG, true),
+            field("value", DataType::LONG, true),
+            field(
+                "nested",
+                StructType::try_new(vec![
+                    field("a", DataType::INTEGER, true),
+                    field("b", DataType::STRING, true),
+                ])
+                .unwrap(),
+                true,
+            ),
+            field("tags", ArrayType::new(DataType::STRING, true), true),
+            field("added", DataType::DATE, true),
+        ])
+        .unwrap();
+
+        assert!(table.diff(&table).is_empty());
+
+        let diff = table.diff(&data);
+        let paths: Vec<_> = diff.changes().iter().map(FieldChange::path).collect();
+        assert_eq!(
+            paths,
+            vec![
+                "id",
+                "value",
+                "dropped",
+                "nested.b",
+                "tags.element",
+                "added"
+            ]
+        );
+        assert_eq!(
+            diff.changes()[1],
+            FieldChange::TypeChanged {
+                path: "value".to_string(),
+                from: DataType::INTEGER,
+                to: DataType::LONG,
+            }
+        );
+        assert_eq!(
+            diff.to_string(),
+            "made field id nullable, changed type of field value from integer to long, \
+            removed field dropped, added field nested.b of type string, \
+            made field tags.element nullable, added field added of type date"
+        );
+
+        // writing long values into an integer column may overflow
+        assert!(!diff.is_write_compatible(SchemaWritePolicy::Merge));
+        assert!(diff.is_write_compatible(SchemaWritePolicy::Overwrite));
+
+        // the reverse direction only widens types and drops nullable fields
+        let diff = data.diff(&table);
+        let incompatible: Vec<_> = diff
+            .incompatible_changes(SchemaWritePolicy::Merge)
+            .map(FieldChange::path)
+            .collect();
+        assert!(incompatible.is_empty());
+        let incompatible: Vec<_> = diff
+            .incompatible_changes(SchemaWritePolicy::Strict)
+            .map(FieldChange::path)
+            .collect();
+        assert_eq!(incompatible, vec!["nested.b", "added", "dropped"]);
+    }
+
+    #[test]
+    fn test_is_lossless_cast() {
+        let decimal = |p, s| DataType::decimal(p, s).unwrap();
+        assert!(is_lossless_cast(&DataType::INTEGER, &DataType::LONG));
+        assert!(is_lossless_cast(&DataType::SHORT, &DataType::DOUBLE));
+        assert!(!is_lossless_cast(&DataType::LONG, &DataType::DOUBLE));
+        assert!(!is_lossless_cast(&DataType::LONG, &DataType::INTEGER));
+        assert!(is_lossless_cast(&decimal(5, 1), &decimal(7, 2)));
+        assert!(!is_lossless_cast(&decimal(5, 1), &decimal(5, 2)));
+        assert!(is_lossless_cast(&DataType::DATE, &DataType::TIMESTAMP_NTZ));
+    }
+}
@@ -1,11 +1,13 @@
 use std::any::Any;
 
 pub mod cast;
+pub mod diff;
 pub mod partitions;
 #[allow(clippy::module_inception)]
 mod schema;
 
 pub use cast::*;
+pub use diff::*;
 pub use schema::*;
 
 /// A trait for all kernel types that are used as part of data checking
@@ -10,8 +10,9 @@ pub use delta_kernel::schema::{
 use serde_json::Value;
 
 use crate::kernel::error::Error;
+use crate::kernel::schema::SchemaDiff;
 use crate::schema::DataCheck;
-use crate::table::GeneratedColumn;
+use crate::table::{COLUMN_DEFAULT_KEY, ColumnDefault, GeneratedColumn};
 
 /// Type alias for a top level schema
 pub type Schema = StructType;
@@ -58,6 +59,12 @@ pub trait StructTypeExt {
 
     /// Get all generated column expressions
     fn get_generated_columns(&self) -> Result<Vec<GeneratedColumn>, Error>;
+
+    /// Get all column default value expressions
+    fn get_column_defaults(&self) -> Result<Vec<ColumnDefault>, Error>;
+
+    /// Describe the changes from this schema to `other`, see [`SchemaDiff`]
+    fn diff(&self, other: &StructType) -> SchemaDiff;
 }
 
 impl StructTypeExt for StructType {
@@ -84,6 +91,21 @@ impl StructTypeExt for StructType {
         Ok(generated_cols)
     }
 
+    /// Get all columns with a default value in the schema
+    fn get_column_defaults(&self) -> Result<Vec<ColumnDefault>, Error> {
+        Ok(self
+            .fields()
+            .filter_map(|field| match field.metadata.get(COLUMN_DEFAULT_KEY) {
+                Some(MetadataValue::String(default_expr)) => Some(ColumnDefault::new(
+                    &field.name,
+                    default_expr,
+                    field.data_type(),
+                )),
+                _ => None,
+            })
+            .collect())
+    }
+
     /// Get all invariants in the schemas
     fn get_invariants(&self) -> Result<Vec<Invariant>, Error> {
         let mut remaining_fields: Vec<(String, StructField)> = self
@@ -154,6 +176,10 @@ impl StructTypeExt for StructType {
         }
         Ok(invariants)
     }
+
+    fn diff(&self, other: &StructType) -> SchemaDiff {
+        SchemaDiff::new(self, other)
+    }
 }
 
 #[cfg(test)]
@@ -162,6 +188,24 @@ mod tests {
     use serde_json;
     use serde_json::json;
 
+    #[test]
+    fn test_get_column_defaults() {
+        let schema: StructType = serde_json::from_value(json!(
+            {
+                "type":"struct",
+                "fields":[
+                    {"name":"id","type":"integer","nullable":true,"metadata":{}},
+                    {"name":"status","type":"string","nullable":true,"metadata":{"CURRENT_DEFAULT":"'active'"}}]
+            }
+        ))
+        .unwrap();
+        let defaults = schema.get_column_defaults().unwrap();
+        assert_eq!(defaults.len(), 1);
+        assert_eq!(defaults[0].name, "status");
+        assert_eq!(defaults[0].get_default_expression(), "'active'");
+        assert_eq!(defaults[0].data_type, DataType::STRING);
+    }
+
     #[test]
     fn test_get_generated_columns() {
         let schema: StructType = serde_json::from_value(json!(
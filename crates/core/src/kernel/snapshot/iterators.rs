@@ -5,7 +5,7 @@ use std::sync::LazyLock;
 use arrow::datatypes::Int32Type;
 use arrow_array::cast::AsArray;
 use arrow_array::types::Int64Type;
-use arrow_array::{Array, MapArray, RecordBatch, StringArray, StructArray};
+use arrow_array::{Array, ArrayRef, MapArray, RecordBatch, StringArray, StructArray};
 use arrow_schema::DataType as ArrowDataType;
 use chrono::{DateTime, Utc};
 use delta_kernel::engine::arrow_expression::evaluate_expression::to_json;
@@ -17,12 +17,14 @@ use indexmap::IndexMap;
 use object_store::ObjectMeta;
 use object_store::path::Path;
 use percent_encoding::percent_decode_str;
+use serde::Deserialize;
 
 #[cfg(feature = "datafusion")]
 pub(crate) use self::scan_row::parse_stats_column_with_schema;
 pub use self::tombstones::TombstoneView;
 #[cfg(any(test, feature = "datafusion"))]
 use crate::kernel::StructType;
+use crate::kernel::distinct_count::DistinctCountSketch;
 use crate::kernel::scalars::ScalarExt;
 use crate::kernel::{Add, DeletionVectorDescriptor, Remove};
 use crate::{DeltaResult, DeltaTableError};
@@ -283,6 +285,38 @@ impl LogicalFileView {
             .map(|s| round_ms_datetimes(s, &ceil_datetime))
     }
 
+    /// Returns the distinct count sketches of the columns in this file, keyed by physical
+    /// column name.
+    ///
+    /// Writers only collect sketches for the columns listed in the
+    /// `delta-rs.dataSkippingDistinctCountColumns` table property.
+    pub fn distinct_count_sketches(&self) -> HashMap<String, DistinctCountSketch> {
+        #[derive(Deserialize)]
+        #[serde(rename_all = "camelCase")]
+        struct SketchStats {
+            #[serde(default)]
+            distinct_count_sketches: HashMap<String, DistinctCountSketch>,
+        }
+
+        self.files
+            .column_by_name(FIELD_NAME_STATS)
+            .and_then(|col| get_string_value(col, self.index))
+            .and_then(|stats| serde_json::from_str::<SketchStats>(stats).ok())
+            .map(|stats| stats.distinct_count_sketches)
+            .unwrap_or_default()
+    }
+
+    /// Returns the column at the nested `path` of the file data along with the index of this
+    /// file, e.g. `["stats_parsed", "minValues", "id"]`.
+    pub(crate) fn column_at(&self, path: &[&str]) -> Option<(ArrayRef, usize)> {
+        let (first, rest) = path.split_first()?;
+        let mut column = self.files.column_by_name(first)?.clone();
+        for name in rest {
+            column = column.as_struct_opt()?.column_by_name(name)?.clone();
+        }
+        Some((column, self.index))
+    }
+
     /// Return the underlying [DeletionVectorDescriptor] if it exists.
     ///
     /// **NOTE**: THis API may be removed in the future without deprecation warnings as the
@@ -1,3 +1,4 @@
+use std::collections::HashMap;
 use std::sync::Arc;
 
 use arrow_array::RecordBatch;
@@ -7,6 +8,7 @@ use delta_kernel::table_configuration::TableConfiguration;
 use delta_kernel::table_properties::TableProperties;
 use indexmap::IndexMap;
 
+use super::super::distinct_count::DistinctCountSketch;
 use super::super::scalars::ScalarExt;
 use super::iterators::LogicalFileView;
 
@@ -94,6 +96,33 @@ impl<'a> LogDataHandler<'a> {
             (0..batch.num_rows()).map(move |idx| LogicalFileView::new(batch.clone(), idx))
         })
     }
+
+    /// Approximate number of distinct values per column, keyed by logical column name.
+    ///
+    /// Only columns with a distinct count sketch in every file are included, see
+    /// [`LogicalFileView::distinct_count_sketches`]. Rows removed by deletion vectors are still
+    /// counted.
+    pub fn distinct_counts(&self) -> HashMap<String, u64> {
+        let mut sketches: HashMap<String, (usize, DistinctCountSketch)> = HashMap::new();
+        for file in self.iter() {
+            for (column, sketch) in file.distinct_count_sketches() {
+                let (num_files, merged) = sketches.entry(column).or_default();
+                *num_files += 1;
+                merged.merge(&sketch);
+            }
+        }
+
+        let num_files = self.num_files();
+        let column_mapping_mode = self.config.column_mapping_mode();
+        self.config
+            .logical_schema()
+            .fields()
+            .filter_map(|field| {
+                let (count, sketch) = sketches.get(field.physical_name(column_mapping_mode))?;
+                (*count == num_files).then(|| (field.name().clone(), sketch.estimate()))
+            })
+            .collect()
+    }
 }
 
 impl IntoIterator for LogDataHandler<'_> {
@@ -115,11 +144,14 @@ mod datafusion {
 
     use ::datafusion::common::Column;
     use ::datafusion::common::scalar::ScalarValue;
+    use ::datafusion::common::stats::{ColumnStatistics, Precision, Statistics};
+    use ::datafusion::functions_aggregate::min_max::{MaxAccumulator, MinAccumulator};
+    use ::datafusion::logical_expr::Accumulator;
     use ::datafusion::physical_optimizer::pruning::PruningStatistics;
     use arrow::compute::concat;
     use arrow_array::{Array, StringArray};
     use arrow_array::{ArrayRef, BooleanArray, UInt64Array};
-    use arrow_schema::DataType as ArrowDataType;
+    use arrow_schema::{DataType as ArrowDataType, Schema as ArrowSchema};
     use delta_kernel::expressions::Expression;
     use delta_kernel::schema::{DataType, PrimitiveType};
     use delta_kernel::{EvaluationHandler, ExpressionEvaluator};
@@ -136,7 +168,10 @@ mod datafusion {
             let schema = self.config.logical_schema();
             let field = schema.field(&column.name)?;
             // See issue #1214. Binary type does not support natural order which is required for Datafusion to prune
-            if field.data_type() == &DataType::Primitive(PrimitiveType::Binary) {
+            // on min / max values. Null counts do not depend on the order.
+            if stats_field != "nullCount"
+                && field.data_type() == &DataType::Primitive(PrimitiveType::Binary)
+            {
                 return None;
             }
             let expression = if self
@@ -278,6 +313,74 @@ mod datafusion {
         }
     }
 
+    impl LogDataHandler<'_> {
+        /// Table statistics for the columns in `schema`, aggregated from the file statistics.
+        ///
+        /// Counts are only exact if all files report them and no rows are deleted by deletion
+        /// vectors. Bounds are always inexact, since string statistics are truncated and the
+        /// rows defining them may have been deleted. Distinct counts are estimated from the
+        /// distinct count sketches of the files.
+        pub(crate) fn statistics(&self, schema: &ArrowSchema) -> Statistics {
+            let distinct_counts = self.distinct_counts();
+            let has_deletion_vectors = self
+                .iter()
+                .any(|file| file.deletion_vector_descriptor().is_some());
+            let total = |counts: Option<ArrayRef>| {
+                let counts = counts?;
+                let counts = counts.as_any().downcast_ref::<UInt64Array>()?;
+                if counts.null_count() > 0 {
+                    return None;
+                }
+                let total = counts.values().iter().sum::<u64>() as usize;
+                Some(if has_deletion_vectors {
+                    Precision::Inexact(total)
+                } else {
+                    Precision::Exact(total)
+                })
+            };
+
+            let column_statistics = schema
+                .fields()
+                .iter()
+                .map(|field| {
+                    let column = Column::from_name(field.name());
+                    let bound = |values: Option<ArrayRef>, min: bool| {
+                        let values = values?;
+                        if values.null_count() > 0 {
+                            return None;
+                        }
+                        let mut acc: Box<dyn Accumulator> = if min {
+                            Box::new(MinAccumulator::try_new(values.data_type()).ok()?)
+                        } else {
+                            Box::new(MaxAccumulator::try_new(values.data_type()).ok()?)
+                        };
+                        acc.update_batch(&[values]).ok()?;
+                        let value = acc.evaluate().ok()?.cast_to(field.data_type()).ok()?;
+                        (!value.is_null()).then_some(Precision::Inexact(value))
+                    };
+                    ColumnStatistics {
+                        null_count: total(self.null_counts(&column)).unwrap_or_default(),
+                        min_value: bound(self.min_values(&column), true).unwrap_or_default(),
+                        max_value: bound(self.max_values(&column), false).unwrap_or_default(),
+                        distinct_count: distinct_counts
+                            .get(field.name())
+                            .map(|count| Precision::Inexact(*count as usize))
+                            .unwrap_or_default(),
+                        ..Default::default()
+                    }
+                })
+                .collect();
+
+            Statistics {
+                num_rows: total(self.row_counts()).unwrap_or_default(),
+                total_byte_size: Precision::Inexact(
+                    self.iter().map(|file| file.size().max(0) as usize).sum(),
+                ),
+                column_statistics,
+            }
+        }
+    }
+
     impl PruningStatistics for LogDataHandler<'_> {
         /// return the minimum values for the named column, if known.
         /// Note: the returned array must contain `num_containers()` rows
@@ -311,19 +414,25 @@ mod datafusion {
                 let counts = self.pick_stats(column, "nullCount")?;
                 return arrow_cast::cast(counts.as_ref(), &ArrowDataType::UInt64).ok();
             }
-            let partition_values = self.pick_stats(column, "__dummy__")?;
+            // Null partition values (`__HIVE_DEFAULT_PARTITION__` in the data path) make every
+            // row of a file null. Binary values are only checked for nulls, so unlike in
+            // `pick_stats` they are not excluded here.
+            let partition_values = self
+                .pick_stats(column, "__dummy__")
+                .or_else(|| self.pick_binary_partition_values(column))?;
             let row_counts = self.row_counts()?;
             let row_counts = row_counts.as_any().downcast_ref::<UInt64Array>()?;
-            let mut null_counts = Vec::with_capacity(partition_values.len());
-            for i in 0..partition_values.len() {
-                let null_count = if partition_values.is_null(i) {
-                    row_counts.value(i)
-                } else {
-                    0
-                };
-                null_counts.push(null_count);
-            }
-            Some(Arc::new(UInt64Array::from(null_counts)))
+            let null_counts = (0..partition_values.len())
+                .map(|i| {
+                    if partition_values.is_valid(i) {
+                        Some(0)
+                    } else {
+                        // Without a row count the number of nulls is unknown.
+                        row_counts.is_valid(i).then(|| row_counts.value(i))
+                    }
+                })
+                .collect::<UInt64Array>();
+            Some(Arc::new(null_counts))
         }
 
         /// return the number of rows in each container as an `Option<UInt64Array>`.
@@ -15,12 +15,15 @@
 //!
 //!
 
+use std::collections::HashMap;
 use std::sync::{Arc, LazyLock};
 
-use arrow::array::RecordBatch;
+use arrow::array::cast::AsArray as _;
+use arrow::array::{Array as _, RecordBatch};
 use arrow::compute::{filter_record_batch, is_not_null};
+use arrow::datatypes::Int64Type;
 use arrow::datatypes::SchemaRef;
-use delta_kernel::actions::{Remove, Sidecar};
+use delta_kernel::actions::{Remove, SetTransaction, Sidecar};
 use delta_kernel::engine::arrow_conversion::TryIntoArrow as _;
 use delta_kernel::engine::arrow_data::ArrowEngineData;
 use delta_kernel::path::{LogPathFileType, ParsedLogPath};
@@ -41,11 +44,12 @@ use object_store::{ObjectStore, ObjectStoreExt as _};
 use serde_json::Deserializer;
 use url::Url;
 
-use super::{Action, CommitInfo, Metadata, Protocol};
+use super::{Action, CommitInfo, Metadata, Protocol, Transaction};
 use crate::checkpoints::parse_last_checkpoint_hint;
 use crate::kernel::arrow::engine_ext::{ExpressionEvaluatorExt, rb_from_scan_meta};
 use crate::kernel::{ARROW_HANDLER, StructType, spawn_blocking_with_span};
 use crate::logstore::{LogStore, LogStoreExt};
+use crate::protocol::checksum::read_checksum;
 use crate::{DeltaResult, DeltaTableConfig, DeltaTableError, PartitionFilter, to_kernel_predicate};
 
 pub use self::log_data::*;
@@ -58,6 +62,7 @@ pub use stream::*;
 
 mod iterators;
 mod log_data;
+mod partition_stats;
 mod scan;
 mod serde;
 mod stats_projection;
@@ -209,6 +214,15 @@ impl Snapshot {
     ///
     /// When `version` is `None` the latest available version is loaded. This is the engine-aware
     /// constructor used when callers want to control the kernel [`Engine`] backing log replay.
+    #[tracing::instrument(
+        skip(engine, config),
+        fields(
+            operation = "snapshot_load",
+            table_uri = %table_root,
+            requested_version = ?version,
+            version = tracing::field::Empty,
+        )
+    )]
     pub async fn try_new_with_engine(
         engine: Arc<dyn Engine>,
         table_root: Url,
@@ -235,6 +249,7 @@ impl Snapshot {
                 }
             }
         };
+        tracing::Span::current().record("version", snapshot.version());
 
         Ok(Self {
             inner: snapshot,
@@ -920,7 +935,7 @@ impl Snapshot {
             return Ok(self);
         }
 
-        let batches = match materialized_seed.and_then(|seed| seed.full_table_seed()) {
+        let files = match materialized_seed.and_then(|seed| seed.full_table_seed()) {
             Some(materialized_seed) => {
                 let (existing_version, existing_data, existing_predicate) =
                     materialized_seed.into_parts();
@@ -931,15 +946,15 @@ impl Snapshot {
                     Box::new(existing_data),
                     existing_predicate,
                 )
-                .try_collect()
-                .await?
-            }
-            None => {
-                self.files_with_engine_preserving_raw(engine, None)
-                    .try_collect()
-                    .await?
             }
+            None => self.files_with_engine_preserving_raw(engine, None),
         };
+        let batches = collect_within_budget(
+            files,
+            self.version(),
+            self.config.max_materialized_files_bytes,
+        )
+        .await?;
         let materialized_files = Arc::new(MaterializedFiles::full(self.as_ref(), batches));
         Ok(Arc::new(
             self.with_materialized_files(Some(materialized_files)),
@@ -1106,6 +1121,66 @@ impl Snapshot {
         Ok(None)
     }
 
+    /// Fetch the latest transaction of every application id which committed to this snapshot.
+    ///
+    /// Like [`Self::application_transaction_version`], transactions older than the
+    /// SetTransactionRetentionDuration property are considered expired and skipped.
+    async fn application_transactions(
+        &self,
+        log_store: &dyn LogStore,
+    ) -> DeltaResult<HashMap<String, Transaction>> {
+        static TXN_SCHEMA: LazyLock<Arc<StructType>> = LazyLock::new(|| {
+            Arc::new(
+                StructType::try_new(vec![StructField::nullable(
+                    "txn",
+                    SetTransaction::to_data_type(),
+                )])
+                .expect("Failed to create a StructType somehow"),
+            )
+        });
+
+        let engine = log_store.engine(None);
+        let inner = self.inner.clone();
+        let mut transactions = spawn_blocking_with_span(move || {
+            // Commits are replayed newest first, so the first transaction seen for an
+            // application id is its latest one.
+            let mut transactions = HashMap::new();
+            for res in inner
+                .log_segment()
+                .read_actions(engine.as_ref(), TXN_SCHEMA.clone())?
+            {
+                let batch: RecordBatch =
+                    ArrowEngineData::try_from_engine_data(res?.actions)?.into();
+                let txn = batch.column(0).as_struct();
+                let app_ids = txn.column(0).as_string::<i32>();
+                let versions = txn.column(1).as_primitive::<Int64Type>();
+                let last_updated = txn.column(2).as_primitive::<Int64Type>();
+                for idx in (0..txn.len()).filter(|idx| txn.is_valid(*idx)) {
+                    let app_id = app_ids.value(idx);
+                    if !transactions.contains_key(app_id) {
+                        let transaction = Transaction {
+                            app_id: app_id.to_string(),
+                            version: versions.value(idx),
+                            last_updated: last_updated
+                                .is_valid(idx)
+                                .then(|| last_updated.value(idx)),
+                        };
+                        transactions.insert(app_id.to_string(), transaction);
+                    }
+                }
+            }
+            Ok::<_, DeltaTableError>(transactions)
+        })
+        .await
+        .map_err(|e| DeltaTableError::GenericError { source: e.into() })??;
+
+        if let Some(retention) = self.table_properties().set_transaction_retention_duration {
+            let cutoff = chrono::Utc::now().timestamp_millis() - retention.as_millis() as i64;
+            transactions.retain(|_, txn| txn.last_updated.is_none_or(|ts| ts > cutoff));
+        }
+        Ok(transactions)
+    }
+
     /// Fetch the [domainMetadata] for a specific domain in this snapshot.
     ///
     /// This returns the latest configuration for the domain, or None if the domain does not exist.
@@ -1267,6 +1342,27 @@ async fn read_last_checkpoint_version(
     .map_err(|e| DeltaTableError::Generic(e.to_string()))?
 }
 
+/// Collect the file action batches, failing once they exceed `max_bytes`.
+///
+/// The stream is consumed batch by batch, so at most one batch beyond the budget is held
+/// in memory before loading is aborted.
+async fn collect_within_budget(
+    mut files: SendableRBStream,
+    version: Version,
+    max_bytes: Option<usize>,
+) -> DeltaResult<Vec<RecordBatch>> {
+    let mut batches = Vec::new();
+    let mut total_bytes = 0usize;
+    while let Some(batch) = files.try_next().await? {
+        total_bytes += batch.get_array_memory_size();
+        if let Some(max_bytes) = max_bytes.filter(|max_bytes| total_bytes > *max_bytes) {
+            return Err(DeltaTableError::MemoryBudgetExceeded { version, max_bytes });
+        }
+        batches.push(batch);
+    }
+    Ok(batches)
+}
+
 pub(crate) async fn resolve_snapshot(
     log_store: &dyn LogStore,
     maybe_snapshot: Option<EagerSnapshot>,
@@ -1306,6 +1402,14 @@ impl EagerSnapshot {
         Self::try_new_with_snapshot(log_store, snapshot.into()).await
     }
 
+    #[tracing::instrument(
+        skip_all,
+        fields(
+            operation = "snapshot_materialize",
+            version = snapshot.version(),
+            table_uri = %log_store.root_url(),
+        )
+    )]
     pub(crate) async fn try_new_with_snapshot(
         log_store: &dyn LogStore,
         snapshot: Arc<Snapshot>,
@@ -1316,7 +1420,14 @@ impl EagerSnapshot {
             }
             SnapshotMaterializationMode::Lazy => snapshot,
         };
-        Ok(Self { snapshot })
+        let snapshot = Self { snapshot };
+        if snapshot.load_config().verify_checksum && snapshot.load_config().require_files {
+            // a missing checksum is no error, tables only write them when configured to
+            if let Some(checksum) = read_checksum(log_store, snapshot.version()).await? {
+                checksum.verify(&snapshot)?;
+            }
+        }
+        Ok(snapshot)
     }
 
     pub(crate) async fn with_files(self, log_store: &dyn LogStore) -> DeltaResult<Self> {
@@ -1497,6 +1608,30 @@ impl EagerSnapshot {
             .await
     }
 
+    /// Return the latest committed transaction for the given application id, if any.
+    ///
+    /// In addition to the version returned by [`Self::transaction_version`], this includes the
+    /// time the transaction was last updated.
+    pub async fn application_transaction(
+        &self,
+        log_store: &dyn LogStore,
+        app_id: impl ToString,
+    ) -> DeltaResult<Option<Transaction>> {
+        let app_id = app_id.to_string();
+        Ok(self
+            .application_transactions(log_store)
+            .await?
+            .remove(&app_id))
+    }
+
+    /// Return the latest committed transaction of every application id, keyed by application id.
+    pub async fn application_transactions(
+        &self,
+        log_store: &dyn LogStore,
+    ) -> DeltaResult<HashMap<String, Transaction>> {
+        self.snapshot.application_transactions(log_store).await
+    }
+
     /// Return the configuration string stored for the given metadata `domain`, if present.
     pub async fn domain_metadata(
         &self,
@@ -2045,6 +2180,52 @@ mod tests {
         Ok(())
     }
 
+    #[tokio::test]
+    async fn snapshot_exceeding_memory_budget_fails_to_load_eagerly() -> TestResult {
+        let log_store = TestTables::Checkpoints.table_builder()?.build_storage()?;
+        let expected = active_add_paths(
+            &Snapshot::try_new(&log_store, Default::default(), None).await?,
+            &log_store,
+        )
+        .await?;
+
+        let config = DeltaTableConfig {
+            max_materialized_files_bytes: Some(1),
+            ..Default::default()
+        };
+        let err = EagerSnapshot::try_new(&log_store, config.clone(), None)
+            .await
+            .unwrap_err();
+        assert!(matches!(
+            err,
+            DeltaTableError::MemoryBudgetExceeded { max_bytes: 1, .. }
+        ));
+
+        // lazy snapshots stream the file actions and are not bound by the budget
+        let config = DeltaTableConfig {
+            require_files: false,
+            ..config
+        };
+        let snapshot = EagerSnapshot::try_new(&log_store, config, None).await?;
+        assert!(!snapshot.snapshot().has_materialized_files_for_test());
+        assert_eq!(
+            active_add_paths(snapshot.snapshot(), &log_store).await?,
+            expected
+        );
+
+        let err = snapshot.with_files(&log_store).await.unwrap_err();
+        assert!(matches!(err, DeltaTableError::MemoryBudgetExceeded { .. }));
+
+        let config = DeltaTableConfig {
+            max_materialized_files_bytes: Some(usize::MAX),
+            ..Default::default()
+        };
+        let snapshot = EagerSnapshot::try_new(&log_store, config, None).await?;
+        assert!(snapshot.snapshot().has_materialized_files_for_test());
+
+        Ok(())
+    }
+
     #[tokio::test]
     async fn lazy_snapshot_roundtrip_preserves_loading_policy_after_materialization() -> TestResult
     {
@@ -0,0 +1,311 @@
+//! Statistics of the partitions of a table, aggregated from the add actions.
+use std::sync::Arc;
+
+use arrow::compute::{SortColumn, cast, interleave, lexsort_to_indices, take_record_batch};
+use arrow_array::{Array, ArrayRef, Int64Array, RecordBatch, StringArray, new_null_array};
+use arrow_schema::{DataType as ArrowDataType, Field, Schema as ArrowSchema};
+use delta_kernel::expressions::Scalar;
+use futures::TryStreamExt;
+use indexmap::IndexMap;
+
+use super::{EagerSnapshot, LogicalFileView};
+use crate::kernel::scalars::ScalarExt;
+use crate::logstore::LogStore;
+use crate::{DeltaResult, DeltaTableError, PartitionFilter, to_kernel_predicate};
+
+/// The file of a partition holding the smallest or largest value of a column.
+type Bound = Option<(LogicalFileView, Scalar)>;
+
+/// Running aggregate of the files in a partition
+struct PartitionAggregate {
+    num_files: i64,
+    size_bytes: i64,
+    num_records: Option<i64>,
+    min: Vec<Bound>,
+    max: Vec<Bound>,
+}
+
+impl PartitionAggregate {
+    fn new(num_columns: usize) -> Self {
+        Self {
+            num_files: 0,
+            size_bytes: 0,
+            num_records: Some(0),
+            min: vec![None; num_columns],
+            max: vec![None; num_columns],
+        }
+    }
+
+    fn add(&mut self, file: &LogicalFileView, columns: &[&str]) {
+        self.num_files += 1;
+        self.size_bytes += file.size();
+        // a single file without row count makes the total unknown
+        self.num_records = self
+            .num_records
+            .zip(file.num_records())
+            .map(|(total, count)| total + count as i64);
+
+        for (idx, column) in columns.iter().enumerate() {
+            update_bound(
+                &mut self.min[idx],
+                file,
+                "minValues",
+                column,
+                |value, bound| value < bound,
+            );
+            update_bound(
+                &mut self.max[idx],
+                file,
+                "maxValues",
+                column,
+                |value, bound| value > bound,
+            );
+        }
+    }
+}
+
+fn update_bound(
+    bound: &mut Bound,
+    file: &LogicalFileView,
+    stats_field: &str,
+    column: &str,
+    replaces: impl Fn(&Scalar, &Scalar) -> bool,
+) {
+    let Some((array, index)) = file.column_at(&["stats_parsed", stats_field, column]) else {
+        return;
+    };
+    let Some(value) = Scalar::from_array(array.as_ref(), index) else {
+        return;
+    };
+    if matches!(value, Scalar::Null(_)) {
+        return;
+    }
+    match bound {
+        Some((_, current)) if !replaces(&value, current) => {}
+        _ => *bound = Some((file.clone(), value)),
+    }
+}
+
+/// Collect the values at `rows` into a single array, `None` rows become null.
+fn gather(
+    data_type: &ArrowDataType,
+    rows: impl IntoIterator<Item = Option<(ArrayRef, usize)>>,
+) -> DeltaResult<ArrayRef> {
+    let mut arrays = vec![new_null_array(data_type, 1)];
+    let mut indices = Vec::new();
+    for row in rows {
+        match row {
+            Some((array, index)) => {
+                let array = if array.data_type() == data_type {
+                    array
+                } else {
+                    cast(array.as_ref(), data_type)?
+                };
+                arrays.push(array);
+                indices.push((arrays.len() - 1, index));
+            }
+            None => indices.push((0, 0)),
+        }
+    }
+    let arrays: Vec<&dyn Array> = arrays.iter().map(|array| array.as_ref()).collect();
+    Ok(interleave(&arrays, &indices)?)
+}
+
+impl EagerSnapshot {
+    /// Statistics of the partitions matching `filters`, computed from the add actions without
+    /// reading any data files.
+    ///
+    /// Every row of the returned batch describes one partition:
+    ///
+    /// * `partition.{partition column name}` (matches column type): value of the partition.
+    /// * `num_files` (Int64): number of files in the partition.
+    /// * `size_bytes` (Int64): total size of the files in bytes.
+    /// * `num_records` (Int64): number of rows, null if a file has no row count.
+    /// * `min.{col_name}` / `max.{col_name}` (matches column type): smallest and largest value
+    ///   of the top-level `columns` reported by the file statistics, null if no file reports
+    ///   one.
+    ///
+    /// Partitions are sorted by their values. An unpartitioned table is reported as a single
+    /// partition, unless it has no files.
+    pub async fn partition_stats(
+        &self,
+        log_store: &dyn LogStore,
+        filters: &[PartitionFilter],
+        columns: &[&str],
+    ) -> DeltaResult<RecordBatch> {
+        let table_schema = self.arrow_schema();
+        let column_fields = columns
+            .iter()
+            .map(|column| {
+                table_schema
+                    .field_with_name(column)
+                    .map_err(|_| DeltaTableError::SchemaMismatch {
+                        msg: format!("Column '{column}' is not a top-level column of the table"),
+                    })
+            })
+            .collect::<DeltaResult<Vec<_>>>()?;
+
+        let predicate = if filters.is_empty() {
+            None
+        } else {
+            Some(Arc::new(to_kernel_predicate(
+                filters,
+                self.schema().as_ref(),
+            )?))
+        };
+        let files: Vec<LogicalFileView> =
+            self.file_views(log_store, predicate).try_collect().await?;
+
+        // partitions are keyed by their serialized values in the order of the partition columns,
+        // the parsed partition values only hold the columns referenced by the predicate
+        let partition_columns = self.metadata().partition_columns();
+        let mut partitions: IndexMap<Vec<Option<String>>, PartitionAggregate> = IndexMap::new();
+        for file in files {
+            let mut values = file.partition_values_map();
+            let key = partition_columns
+                .iter()
+                .map(|column| values.remove(column).flatten())
+                .collect();
+            partitions
+                .entry(key)
+                .or_insert_with(|| PartitionAggregate::new(columns.len()))
+                .add(&file, columns);
+        }
+
+        let mut fields = Vec::new();
+        let mut arrays = Vec::new();
+        for (idx, partition_column) in partition_columns.iter().enumerate() {
+            let field = table_schema.field_with_name(partition_column)?;
+            fields.push(Field::new(
+                format!("partition.{partition_column}"),
+                field.data_type().clone(),
+                true,
+            ));
+            let values: StringArray = partitions.keys().map(|key| key[idx].as_deref()).collect();
+            arrays.push(cast(&values, field.data_type())?);
+        }
+        let partitions: Vec<_> = partitions.into_values().collect();
+
+        fields.push(Field::new("num_files", ArrowDataType::Int64, false));
+        arrays.push(Arc::new(Int64Array::from_iter_values(
+            partitions.iter().map(|partition| partition.num_files),
+        )));
+        fields.push(Field::new("size_bytes", ArrowDataType::Int64, false));
+        arrays.push(Arc::new(Int64Array::from_iter_values(
+            partitions.iter().map(|partition| partition.size_bytes),
+        )));
+        fields.push(Field::new("num_records", ArrowDataType::Int64, true));
+        arrays.push(Arc::new(Int64Array::from_iter(
+            partitions.iter().map(|partition| partition.num_records),
+        )));
+
+        for (prefix, stats_field) in [("min", "minValues"), ("max", "maxValues")] {
+            for (idx, (column, field)) in columns.iter().zip(&column_fields).enumerate() {
+                fields.push(Field::new(
+                    format!("{prefix}.{column}"),
+                    field.data_type().clone(),
+                    true,
+                ));
+                arrays.push(gather(
+                    field.data_type(),
+                    partitions.iter().map(|partition| {
+                        let bound = match prefix {
+                            "min" => &partition.min[idx],
+                            _ => &partition.max[idx],
+                        };
+                        bound.as_ref().and_then(|(file, _)| {
+                            file.column_at(&["stats_parsed", stats_field, column])
+                        })
+                    }),
+                )?);
+            }
+        }
+
+        let batch = RecordBatch::try_new(Arc::new(ArrowSchema::new(fields)), arrays)?;
+        if partition_columns.is_empty() || batch.num_rows() < 2 {
+            return Ok(batch);
+        }
+        let sort_columns: Vec<_> = batch.columns()[..partition_columns.len()]
+            .iter()
+            .map(|values| SortColumn {
+                values: values.clone(),
+                options: None,
+            })
+            .collect();
+        let indices = lexsort_to_indices(&sort_columns, None)?;
+        Ok(take_record_batch(&batch, &indices)?)
+    }
+}
+
+#[cfg(all(test, feature = "datafusion"))]
+mod tests {
+    use arrow::array::AsArray as _;
+    use arrow::datatypes::{Int32Type, Int64Type};
+
+    use crate::DeltaTable;
+    use crate::writer::test_utils::{get_delta_schema, get_record_batch};
+
+    #[tokio::test]
+    async fn test_partition_stats() {
+        let table = DeltaTable::new_in_memory()
+            .create()
+            .with_columns(get_delta_schema().fields().cloned())
+            .with_partition_columns(["modified"])
+            .await
+            .unwrap();
+        let table = table
+            .write(vec![get_record_batch(None, false)])
+            .await
+            .unwrap();
+        let snapshot = table.snapshot().unwrap().snapshot();
+        let log_store = table.log_store();
+
+        let stats = snapshot
+            .partition_stats(log_store.as_ref(), &[], &["value"])
+            .await
+            .unwrap();
+        let column_names: Vec<_> = stats
+            .schema()
+            .fields()
+            .iter()
+            .map(|field| field.name().clone())
+            .collect();
+        assert_eq!(
+            column_names,
+            vec![
+                "partition.modified",
+                "num_files",
+                "size_bytes",
+                "num_records",
+                "min.value",
+                "max.value"
+            ]
+        );
+        assert_eq!(stats.num_rows(), 2);
+
+        let partitions = stats.column(0).as_string::<i32>();
+        assert_eq!(partitions.value(0), "2021-02-01");
+        assert_eq!(partitions.value(1), "2021-02-02");
+        let num_records = stats.column(3).as_primitive::<Int64Type>();
+        assert_eq!(num_records.values().as_ref(), &[8, 3]);
+        let min = stats.column(4).as_primitive::<Int32Type>();
+        assert_eq!(min.values().as_ref(), &[4, 1]);
+        let max = stats.column(5).as_primitive::<Int32Type>();
+        assert_eq!(max.values().as_ref(), &[11, 3]);
+
+        let filters = vec![("modified", "=", "2021-02-02").try_into().unwrap()];
+        let stats = snapshot
+            .partition_stats(log_store.as_ref(), &filters, &[])
+            .await
+            .unwrap();
+        assert_eq!(stats.num_rows(), 1);
+        assert_eq!(stats.column(1).as_primitive::<Int64Type>().value(0), 1);
+
+        assert!(
+            snapshot
+                .partition_stats(log_store.as_ref(), &[], &["missing"])
+                .await
+                .is_err()
+        );
+    }
+}
@@ -145,4 +145,55 @@ mod tests {
             .unwrap();
         assert_eq!(txn_version, Some(3));
     }
+
+    #[tokio::test]
+    async fn test_application_transactions() {
+        let table = DeltaTable::new_in_memory()
+            .write(vec![get_record_batch(None, false)])
+            .with_commit_properties(
+                CommitProperties::default()
+                    .with_application_transaction(Transaction::new("app-a", 1))
+                    .with_application_transaction(Transaction::new_with_last_update(
+                        "app-b",
+                        7,
+                        Some(1000),
+                    )),
+            )
+            .await
+            .unwrap();
+        checkpoints::create_checkpoint(&table, None).await.unwrap();
+
+        let table = table
+            .write(vec![get_record_batch(None, false)])
+            .with_commit_properties(
+                CommitProperties::default()
+                    .with_application_transaction(Transaction::new("app-a", 2)),
+            )
+            .await
+            .unwrap();
+
+        let log_store = table.log_store();
+        let snapshot = table.snapshot().unwrap();
+        let transactions = snapshot
+            .application_transactions(log_store.as_ref())
+            .await
+            .unwrap();
+        assert_eq!(transactions.len(), 2);
+        assert_eq!(transactions["app-a"], Transaction::new("app-a", 2));
+        assert_eq!(
+            transactions["app-b"],
+            Transaction::new_with_last_update("app-b", 7, Some(1000))
+        );
+
+        let txn = snapshot
+            .application_transaction(log_store.as_ref(), "app-b")
+            .await
+            .unwrap();
+        assert_eq!(txn.map(|txn| txn.version), Some(7));
+        let txn = snapshot
+            .application_transaction(log_store.as_ref(), "unknown")
+            .await
+            .unwrap();
+        assert!(txn.is_none());
+    }
 }
@@ -1,5 +1,5 @@
 //! Helper module to check if a transaction can be committed in case of conflicting commits.
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet};
 
 use delta_kernel::table_properties::IsolationLevel;
 
@@ -7,7 +7,7 @@ use super::CommitInfo;
 use crate::DeltaTableError;
 #[cfg(feature = "datafusion")]
 use crate::delta_datafusion::DataFusionMixins;
-use crate::errors::DeltaResult;
+use crate::errors::{DeltaResult, ErrorKind};
 use crate::kernel::{
     Action, Add, ConflictReadSet, Metadata, Protocol, Remove, Transaction, Version,
 };
@@ -93,6 +93,195 @@ pub enum CommitConflictError {
     NoMetadata,
 }
 
+impl CommitConflictError {
+    /// The [kind](ErrorKind) of the conflict
+    pub fn kind(&self) -> ErrorKind {
+        match self {
+            Self::ConcurrentAppend
+            | Self::ConcurrentDeleteRead
+            | Self::ConcurrentDeleteDelete
+            | Self::MetadataChanged
+            | Self::ConcurrentTransaction
+            | Self::ProtocolChanged(_) => ErrorKind::CommitConflict,
+            Self::UnsupportedWriterVersion(_) | Self::UnsupportedReaderVersion(_) => {
+                ErrorKind::Protocol
+            }
+            Self::CorruptedState { .. } => ErrorKind::InvalidTable,
+            Self::Predicate { .. } | Self::NoMetadata => ErrorKind::Internal,
+        }
+    }
+}
+
+/// Details on the concurrent commit a transaction conflicted with
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ConflictDiagnostics {
+    /// Version of the table the transaction was based on
+    pub read_version: Version,
+    /// Version of the concurrent commit which caused the conflict
+    pub winning_version: Version,
+    /// Operation of the concurrent commit, if recorded
+    pub winning_operation: Option<String>,
+    /// Commit info of the concurrent commit, if recorded
+    pub winning_commit_info: Option<CommitInfo>,
+    /// Paths of the files both transactions depend on
+    pub conflicting_files: Vec<String>,
+    /// Distinct partition values of the conflicting files
+    pub conflicting_partitions: Vec<HashMap<String, Option<String>>>,
+    /// Application ids updated by both transactions
+    pub conflicting_app_ids: Vec<String>,
+}
+
+impl std::fmt::Display for ConflictDiagnostics {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "Conflicting commit: version {} ({}), read version {}",
+            self.winning_version,
+            self.winning_operation
+                .as_deref()
+                .unwrap_or("unknown operation"),
+            self.read_version
+        )?;
+        if !self.conflicting_files.is_empty() {
+            write!(f, ", files: [{}]", self.conflicting_files.join(", "))?;
+        }
+        if !self.conflicting_app_ids.is_empty() {
+            write!(f, ", app ids: [{}]", self.conflicting_app_ids.join(", "))?;
+        }
+        Ok(())
+    }
+}
+
+/// A commit conflict along with diagnostics on the concurrent commit which caused it
+#[derive(Debug)]
+pub struct CommitConflictReport {
+    /// The conflict resolution rule violated by the transaction
+    pub error: CommitConflictError,
+    /// Details on the conflicting commit, if available
+    pub diagnostics: Option<Box<ConflictDiagnostics>>,
+}
+
+impl CommitConflictReport {
+    /// Attach diagnostics to a conflict
+    pub fn new(error: CommitConflictError, diagnostics: ConflictDiagnostics) -> Self {
+        Self {
+            error,
+            diagnostics: Some(Box::new(diagnostics)),
+        }
+    }
+}
+
+impl std::fmt::Display for CommitConflictReport {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.error)?;
+        if let Some(diagnostics) = &self.diagnostics {
+            write!(f, "\n{diagnostics}")?;
+        }
+        Ok(())
+    }
+}
+
+impl std::error::Error for CommitConflictReport {}
+
+impl From<CommitConflictError> for CommitConflictReport {
+    fn from(error: CommitConflictError) -> Self {
+        Self {
+            error,
+            diagnostics: None,
+        }
+    }
+}
+
+/// Partition values of a file in a canonical, hashable form
+type PartitionKey = Vec<(String, Option<String>)>;
+
+fn partition_key(partition_values: &HashMap<String, Option<String>>) -> PartitionKey {
+    let mut key: PartitionKey = partition_values
+        .iter()
+        .map(|(k, v)| (k.clone(), v.clone()))
+        .collect();
+    key.sort();
+    key
+}
+
+/// The files and partitions a transaction depends on.
+///
+/// By default the conflict checker derives what a transaction read from its operation predicate,
+/// which for operations without a predicate means the whole table. Operations which know exactly
+/// which files they read, e.g. a compaction rewriting a single partition, can declare them so
+/// that concurrent commits touching other files or partitions do not cause a conflict.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ReadSet {
+    files: HashSet<String>,
+    partitions: Option<HashSet<PartitionKey>>,
+}
+
+impl ReadSet {
+    /// Create an empty read set
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Declare the files read by the transaction along with their partitions
+    pub fn from_files<'a>(files: impl IntoIterator<Item = &'a Add>) -> Self {
+        files.into_iter().fold(Self::new(), |read_set, add| {
+            read_set
+                .with_file(&add.path)
+                .with_partition(&add.partition_values)
+        })
+    }
+
+    /// Declare the files removed by `actions` as read, as operations which rewrite files
+    /// necessarily read them first.
+    pub fn from_removed_files<'a>(actions: impl IntoIterator<Item = &'a Action>) -> Self {
+        actions
+            .into_iter()
+            .fold(Self::new(), |read_set, action| match action {
+                Action::Remove(remove) => {
+                    let read_set = read_set.with_file(&remove.path);
+                    match &remove.partition_values {
+                        Some(partition_values) => read_set.with_partition(partition_values),
+                        None => read_set,
+                    }
+                }
+                _ => read_set,
+            })
+    }
+
+    /// Add a file path to the read set
+    pub fn with_file(mut self, path: impl Into<String>) -> Self {
+        self.files.insert(path.into());
+        self
+    }
+
+    /// Add a partition to the read set.
+    ///
+    /// Once any partition is declared, files added concurrently to other partitions are not
+    /// considered to have been read by the transaction.
+    pub fn with_partition(mut self, partition_values: &HashMap<String, Option<String>>) -> Self {
+        self.partitions
+            .get_or_insert_with(HashSet::new)
+            .insert(partition_key(partition_values));
+        self
+    }
+
+    /// Paths of the files read by the transaction
+    pub fn files(&self) -> &HashSet<String> {
+        &self.files
+    }
+
+    /// Whether the transaction read the partition, `true` if no partitions were declared
+    pub(crate) fn reads_partition(
+        &self,
+        partition_values: Option<&HashMap<String, Option<String>>>,
+    ) -> bool {
+        match (&self.partitions, partition_values) {
+            (Some(partitions), Some(values)) => partitions.contains(&partition_key(values)),
+            _ => true,
+        }
+    }
+}
+
 /// A struct representing different attributes of current transaction needed for conflict detection.
 #[allow(unused)]
 pub(crate) struct TransactionInfo<'a> {
@@ -114,6 +303,8 @@ pub(crate) struct TransactionInfo<'a> {
     read_snapshot: ConflictReadSet<'a>,
     /// Whether the transaction tainted the whole table
     read_whole_table: bool,
+    /// Files and partitions explicitly declared as read by the transaction
+    read_set: Option<&'a ReadSet>,
 }
 
 impl<'a> TransactionInfo<'a> {
@@ -167,6 +358,7 @@ impl<'a> TransactionInfo<'a> {
             actions,
             read_snapshot,
             read_whole_table,
+            read_set: None,
         }
     }
 
@@ -190,6 +382,33 @@ impl<'a> TransactionInfo<'a> {
             actions,
             read_snapshot,
             read_whole_table,
+            read_set: None,
+        })
+    }
+
+    /// Use the declared read set instead of deriving the files read from the predicates
+    pub fn with_read_set(mut self, read_set: Option<&'a ReadSet>) -> Self {
+        self.read_set = read_set;
+        self
+    }
+
+    /// Isolation level requested for this transaction in its commit info, if any.
+    ///
+    /// Only `Serializable` and `WriteSerializable` can be requested, snapshot isolation is
+    /// reserved for operations which are known not to conflict.
+    pub fn isolation_level(&self) -> Option<IsolationLevel> {
+        self.actions.iter().find_map(|action| match action {
+            Action::CommitInfo(CommitInfo {
+                isolation_level: Some(level),
+                ..
+            }) => match level {
+                crate::kernel::IsolationLevel::Serializable => Some(IsolationLevel::Serializable),
+                crate::kernel::IsolationLevel::WriteSerializable => {
+                    Some(IsolationLevel::WriteSerializable)
+                }
+                crate::kernel::IsolationLevel::SnapshotIsolation => None,
+            },
+            _ => None,
         })
     }
 
@@ -372,29 +591,27 @@ impl<'a> ConflictChecker<'a> {
         winning_commit_summary: WinningCommitSummary,
         operation: Option<&DeltaOperation>,
     ) -> ConflictChecker<'a> {
+        // The level requested by the transaction takes precedence over the table default
+        let configured = transaction_info.isolation_level().unwrap_or_else(|| {
+            transaction_info
+                .read_snapshot
+                .log_data()
+                .table_properties()
+                .isolation_level()
+        });
         let isolation_level = operation
             .and_then(|op| {
                 if can_downgrade_to_snapshot_isolation(
                     &winning_commit_summary.actions,
                     op,
-                    &transaction_info
-                        .read_snapshot
-                        .log_data()
-                        .table_properties()
-                        .isolation_level(),
+                    &configured,
                 ) {
                     Some(IsolationLevel::SnapshotIsolation)
                 } else {
                     None
                 }
             })
-            .unwrap_or_else(|| {
-                transaction_info
-                    .read_snapshot
-                    .log_data()
-                    .table_properties()
-                    .isolation_level()
-            });
+            .unwrap_or(configured);
 
         Self {
             txn_info: transaction_info,
@@ -471,9 +688,18 @@ impl<'a> ConflictChecker<'a> {
     fn check_for_added_files_that_should_have_been_read_by_current_txn(
         &self,
     ) -> Result<(), CommitConflictError> {
+        if self.added_files_read_by_current_txn()?.is_empty() {
+            Ok(())
+        } else {
+            Err(CommitConflictError::ConcurrentAppend)
+        }
+    }
+
+    /// Files added by the winning commit which the current transaction should have read
+    fn added_files_read_by_current_txn(&self) -> Result<Vec<Add>, CommitConflictError> {
         // Skip check, if the operation can be downgraded to snapshot isolation
         if matches!(self.isolation_level, IsolationLevel::SnapshotIsolation) {
-            return Ok(());
+            return Ok(vec![]);
         }
 
         // Fail if new files have been added that the txn should have read.
@@ -530,11 +756,14 @@ impl<'a> ConflictChecker<'a> {
             }
         }
 
-        if !added_files_matching_predicates.is_empty() {
-            Err(CommitConflictError::ConcurrentAppend)
-        } else {
-            Ok(())
-        }
+        // Files added to partitions the transaction did not read cannot affect its result
+        Ok(match self.txn_info.read_set {
+            Some(read_set) => added_files_matching_predicates
+                .into_iter()
+                .filter(|add| read_set.reads_partition(Some(&add.partition_values)))
+                .collect(),
+            None => added_files_matching_predicates,
+        })
     }
 
     /// Check if [Remove] actions added by already committed transactions
@@ -542,12 +771,24 @@ impl<'a> ConflictChecker<'a> {
     fn check_for_deleted_files_against_current_txn_read_files(
         &self,
     ) -> Result<(), CommitConflictError> {
+        if self.deleted_files_read_by_current_txn()?.is_empty() {
+            Ok(())
+        } else {
+            Err(CommitConflictError::ConcurrentDeleteRead)
+        }
+    }
+
+    /// Files removed by the winning commit which the current transaction read
+    fn deleted_files_read_by_current_txn(&self) -> Result<Vec<Remove>, CommitConflictError> {
         // Fail if files have been deleted that the txn read.
-        let read_file_path: HashSet<String> = self
-            .txn_info
-            .read_files()?
-            .map(|f| f.path.clone())
-            .collect();
+        let read_file_path: HashSet<String> = match self.txn_info.read_set {
+            Some(read_set) => read_set.files().clone(),
+            None => self
+                .txn_info
+                .read_files()?
+                .map(|f| f.path.clone())
+                .collect(),
+        };
 
         // Only consider removals with data_change = true as conflicts.
         // Removals with data_change = false (e.g., from OPTIMIZE/compaction)
@@ -560,17 +801,19 @@ impl<'a> ConflictChecker<'a> {
             .filter(|r| r.data_change)
             .collect();
 
-        let deleted_read_overlap = removed_files_with_data_change
-            .iter()
-            .find(|f| read_file_path.contains(&f.path));
-
-        if deleted_read_overlap.is_some()
-            || (!removed_files_with_data_change.is_empty() && self.txn_info.read_whole_table())
-        {
-            Err(CommitConflictError::ConcurrentDeleteRead)
-        } else {
-            Ok(())
-        }
+        // A transaction which read the whole table conflicts with any removal from the
+        // partitions it read
+        let read_whole_table = self.txn_info.read_whole_table();
+        Ok(removed_files_with_data_change
+            .into_iter()
+            .filter(|f| {
+                read_file_path.contains(&f.path)
+                    || (read_whole_table
+                        && self.txn_info.read_set.is_none_or(|read_set| {
+                            read_set.reads_partition(f.partition_values.as_ref())
+                        }))
+            })
+            .collect())
     }
 
     /// Check if [Remove] actions added by already committed transactions conflicts
@@ -578,6 +821,15 @@ impl<'a> ConflictChecker<'a> {
     fn check_for_deleted_files_against_current_txn_deleted_files(
         &self,
     ) -> Result<(), CommitConflictError> {
+        if self.files_deleted_by_both_txns().is_empty() {
+            Ok(())
+        } else {
+            Err(CommitConflictError::ConcurrentDeleteDelete)
+        }
+    }
+
+    /// Files removed by both the winning commit and the current transaction
+    fn files_deleted_by_both_txns(&self) -> Vec<Remove> {
         // Fail if a file is deleted twice.
         let txn_deleted_files: HashSet<String> = self
             .txn_info
@@ -589,22 +841,11 @@ impl<'a> ConflictChecker<'a> {
                 _ => None,
             })
             .collect();
-        let winning_deleted_files: HashSet<String> = self
-            .winning_commit_summary
+        self.winning_commit_summary
             .removed_files()
-            .iter()
-            .cloned()
-            .map(|r| r.path)
-            .collect();
-        let intersection: HashSet<&String> = txn_deleted_files
-            .intersection(&winning_deleted_files)
-            .collect();
-
-        if !intersection.is_empty() {
-            Err(CommitConflictError::ConcurrentDeleteDelete)
-        } else {
-            Ok(())
-        }
+            .into_iter()
+            .filter(|r| txn_deleted_files.contains(&r.path))
+            .collect()
     }
 
     /// Checks if the winning transaction corresponds to some AppId on which
@@ -612,19 +853,80 @@ impl<'a> ConflictChecker<'a> {
     fn check_for_updated_application_transaction_ids_that_current_txn_depends_on(
         &self,
     ) -> Result<(), CommitConflictError> {
+        if self.app_ids_updated_by_both_txns().is_empty() {
+            Ok(())
+        } else {
+            Err(CommitConflictError::ConcurrentTransaction)
+        }
+    }
+
+    /// Application ids updated by the winning commit which the current transaction depends on
+    fn app_ids_updated_by_both_txns(&self) -> Vec<String> {
         // Fail if the appIds seen by the current transaction has been updated by the winning
         // transaction i.e. the winning transaction have [Txn] corresponding to
         // some appId on which current transaction depends on. Example - This can happen when
         // multiple instances of the same streaming query are running at the same time.
         let winning_txns = self.winning_commit_summary.app_level_transactions();
-        let txn_overlap: HashSet<&String> = winning_txns
+        let mut txn_overlap: Vec<String> = winning_txns
             .intersection(&self.txn_info.read_app_ids)
+            .cloned()
             .collect();
-        if !txn_overlap.is_empty() {
-            Err(CommitConflictError::ConcurrentTransaction)
-        } else {
-            Ok(())
+        txn_overlap.sort();
+        txn_overlap
+    }
+
+    /// Collect details on the winning commit and the data involved in a conflict returned
+    /// by [`Self::check_conflicts`].
+    pub fn diagnostics(
+        &self,
+        error: &CommitConflictError,
+        read_version: Version,
+        winning_version: Version,
+    ) -> ConflictDiagnostics {
+        let commit_info = self.winning_commit_summary.commit_info.clone();
+        let mut diagnostics = ConflictDiagnostics {
+            read_version,
+            winning_version,
+            winning_operation: commit_info.as_ref().and_then(|info| info.operation.clone()),
+            winning_commit_info: commit_info,
+            ..Default::default()
+        };
+
+        let files: Vec<(String, HashMap<String, Option<String>>)> = match error {
+            CommitConflictError::ConcurrentAppend => self
+                .added_files_read_by_current_txn()
+                .unwrap_or_default()
+                .into_iter()
+                .map(|add| (add.path, add.partition_values))
+                .collect(),
+            CommitConflictError::ConcurrentDeleteRead => self
+                .deleted_files_read_by_current_txn()
+                .unwrap_or_default()
+                .into_iter()
+                .map(|remove| (remove.path, remove.partition_values.unwrap_or_default()))
+                .collect(),
+            CommitConflictError::ConcurrentDeleteDelete => self
+                .files_deleted_by_both_txns()
+                .into_iter()
+                .map(|remove| (remove.path, remove.partition_values.unwrap_or_default()))
+                .collect(),
+            CommitConflictError::ConcurrentTransaction => {
+                diagnostics.conflicting_app_ids = self.app_ids_updated_by_both_txns();
+                vec![]
+            }
+            _ => vec![],
+        };
+        for (path, partition_values) in files {
+            if !partition_values.is_empty()
+                && !diagnostics
+                    .conflicting_partitions
+                    .contains(&partition_values)
+            {
+                diagnostics.conflicting_partitions.push(partition_values);
+            }
+            diagnostics.conflicting_files.push(path);
         }
+        diagnostics
     }
 }
 
@@ -750,6 +1052,29 @@ mod tests {
         checker.check_conflicts()
     }
 
+    #[cfg(feature = "datafusion")]
+    async fn execute_test_with_read_set(
+        setup: Vec<Action>,
+        concurrent: Vec<Action>,
+        actions: Vec<Action>,
+        read_whole_table: bool,
+        read_set: &ReadSet,
+    ) -> Result<(), CommitConflictError> {
+        use crate::table::state::DeltaTableState;
+
+        let state = DeltaTableState::from_actions(setup).await.unwrap();
+        let conflict_read_set =
+            ConflictReadSet::from_log_data_for_test(state.snapshot().log_data());
+        let transaction_info =
+            TransactionInfo::new(conflict_read_set, None, &actions, read_whole_table)
+                .with_read_set(Some(read_set));
+        let summary = WinningCommitSummary {
+            actions: concurrent,
+            commit_info: None,
+        };
+        ConflictChecker::new(transaction_info, summary, None).check_conflicts()
+    }
+
     // tests adopted from https://github.com/delta-io/delta/blob/24c025128612a4ae02d0ad958621f928cda9a3ec/core/src/test/scala/org/apache/spark/sql/delta/OptimisticTransactionSuite.scala#L40-L94
     #[tokio::test]
     #[cfg(feature = "datafusion")]
@@ -1276,4 +1601,146 @@ mod tests {
             "Disjoint replaceWhere-style transactions with empty reads should succeed"
         );
     }
+
+    #[tokio::test]
+    #[cfg(feature = "datafusion")]
+    async fn test_read_set_ignores_concurrent_delete_of_unread_file() {
+        // a compaction rewriting `file_b` must not fail because `file_a` was deleted concurrently
+        let file_a = simple_add(true, "1", "10");
+        let file_b = simple_add(true, "100", "1000");
+        let mut setup_actions = init_table_actions();
+        setup_actions.push(file_a.clone().into());
+        setup_actions.push(file_b.clone().into());
+
+        let concurrent = vec![ActionFactory::remove(&file_a, true).into()];
+        let actions = vec![
+            ActionFactory::remove(&file_b, false).into(),
+            simple_add(false, "100", "1000").into(),
+        ];
+
+        let result = execute_test(
+            Some(setup_actions.clone()),
+            None,
+            concurrent.clone(),
+            actions.clone(),
+            false,
+        )
+        .await;
+        assert!(matches!(
+            result,
+            Err(CommitConflictError::ConcurrentDeleteRead)
+        ));
+
+        let read_set = ReadSet::from_removed_files(&actions);
+        assert!(read_set.files().contains(&file_b.path));
+        let result =
+            execute_test_with_read_set(setup_actions, concurrent, actions, false, &read_set).await;
+        assert!(result.is_ok(), "{result:?}");
+    }
+
+    #[tokio::test]
+    #[cfg(feature = "datafusion")]
+    async fn test_read_set_partitions_limit_concurrent_appends() {
+        let partition =
+            |value: &str| HashMap::from([("part".to_string(), Some(value.to_string()))]);
+        let mut file_part_a = simple_add(true, "1", "10");
+        file_part_a.partition_values = partition("a");
+
+        let read_set_b = ReadSet::new().with_partition(&partition("b"));
+        let result = execute_test_with_read_set(
+            init_table_actions(),
+            vec![file_part_a.clone().into()],
+            vec![],
+            true,
+            &read_set_b,
+        )
+        .await;
+        assert!(result.is_ok(), "{result:?}");
+
+        let read_set_a = ReadSet::new().with_partition(&partition("a"));
+        let result = execute_test_with_read_set(
+            init_table_actions(),
+            vec![file_part_a.into()],
+            vec![],
+            true,
+            &read_set_a,
+        )
+        .await;
+        assert!(matches!(result, Err(CommitConflictError::ConcurrentAppend)));
+    }
+
+    #[tokio::test]
+    #[cfg(feature = "datafusion")]
+    async fn test_isolation_level_of_transaction_decides_blind_append_conflicts() {
+        use crate::table::state::DeltaTableState;
+
+        let state = DeltaTableState::from_actions(init_table_actions())
+            .await
+            .unwrap();
+        let check = |level: crate::kernel::IsolationLevel| {
+            let actions: Vec<Action> = vec![
+                Action::CommitInfo(CommitInfo {
+                    isolation_level: Some(level),
+                    ..Default::default()
+                }),
+                simple_add(true, "1", "10").into(),
+            ];
+            let conflict_read_set =
+                ConflictReadSet::from_log_data_for_test(state.snapshot().log_data());
+            let transaction_info = TransactionInfo::new(conflict_read_set, None, &actions, true);
+            assert!(transaction_info.isolation_level().is_some());
+            let summary = WinningCommitSummary {
+                actions: vec![simple_add(true, "2", "20").into()],
+                commit_info: Some(CommitInfo {
+                    is_blind_append: Some(true),
+                    ..Default::default()
+                }),
+            };
+            ConflictChecker::new(transaction_info, summary, None).check_conflicts()
+        };
+
+        let result = check(crate::kernel::IsolationLevel::WriteSerializable);
+        assert!(result.is_ok(), "{result:?}");
+        let result = check(crate::kernel::IsolationLevel::Serializable);
+        assert!(matches!(result, Err(CommitConflictError::ConcurrentAppend)));
+    }
+
+    #[tokio::test]
+    async fn test_conflict_diagnostics() {
+        use crate::table::state::DeltaTableState;
+
+        let state = DeltaTableState::from_actions(init_table_actions())
+            .await
+            .unwrap();
+        let removed: Action = ActionFactory::remove(&simple_add(true, "1", "10"), true).into();
+        let Action::Remove(remove) = &removed else {
+            unreachable!()
+        };
+        let actions = vec![removed.clone()];
+        let conflict_read_set =
+            ConflictReadSet::from_log_data_for_test(state.snapshot().log_data());
+        let transaction_info = TransactionInfo::new(conflict_read_set, None, &actions, false);
+        let summary = WinningCommitSummary {
+            actions: vec![removed.clone()],
+            commit_info: Some(CommitInfo {
+                operation: Some("DELETE".to_string()),
+                ..Default::default()
+            }),
+        };
+        let checker = ConflictChecker::new(transaction_info, summary, None);
+        let err = checker.check_conflicts().unwrap_err();
+        assert!(matches!(err, CommitConflictError::ConcurrentDeleteDelete));
+
+        let diagnostics = checker.diagnostics(&err, 3, 5);
+        assert_eq!(diagnostics.read_version, 3);
+        assert_eq!(diagnostics.winning_version, 5);
+        assert_eq!(diagnostics.winning_operation.as_deref(), Some("DELETE"));
+        assert_eq!(diagnostics.conflicting_files, vec![remove.path.clone()]);
+        assert!(diagnostics.conflicting_app_ids.is_empty());
+
+        let report = CommitConflictReport::new(err, diagnostics);
+        let message = report.to_string();
+        assert!(message.contains("version 5 (DELETE)"), "{message}");
+        assert!(message.contains(&remove.path), "{message}");
+    }
 }
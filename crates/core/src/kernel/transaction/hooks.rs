@@ -0,0 +1,157 @@
+//! Hooks invoked around every commit written through a log store.
+//!
+//! A [`CommitHook`] is attached to the storage configuration of a table, either via
+//! [`StorageConfig::with_commit_hook`](crate::logstore::StorageConfig::with_commit_hook) or
+//! [`DeltaTableBuilder::with_commit_hook`](crate::DeltaTableBuilder::with_commit_hook), and is
+//! invoked for every operation committing to that table.
+use std::fmt::Debug;
+use std::sync::Arc;
+
+use async_trait::async_trait;
+
+use crate::DeltaResult;
+use crate::kernel::{Action, Version};
+use crate::logstore::LogStoreRef;
+use crate::protocol::DeltaOperation;
+
+/// Custom logic run before a commit is written and after it succeeded.
+#[async_trait]
+pub trait CommitHook: Debug + Send + Sync {
+    /// Called with the finalized actions of a transaction before they are written to the log.
+    ///
+    /// The hook may add or modify actions, e.g. to inject domain metadata. Returning an error
+    /// aborts the commit before anything was written.
+    async fn pre_commit(
+        &self,
+        _log_store: &LogStoreRef,
+        _operation: &DeltaOperation,
+        _actions: &mut Vec<Action>,
+    ) -> DeltaResult<()> {
+        Ok(())
+    }
+
+    /// Called after the actions were committed as `version`, e.g. to notify a catalog.
+    ///
+    /// The commit is already durable at this point, an error is returned to the caller but does
+    /// not undo it.
+    async fn post_commit(
+        &self,
+        _log_store: &LogStoreRef,
+        _version: Version,
+        _operation: &DeltaOperation,
+        _actions: &[Action],
+    ) -> DeltaResult<()> {
+        Ok(())
+    }
+}
+
+/// Sharable reference to a [`CommitHook`]
+pub type CommitHookRef = Arc<dyn CommitHook>;
+
+#[cfg(test)]
+mod tests {
+    use parking_lot::Mutex;
+    use url::Url;
+
+    use super::*;
+    use crate::writer::test_utils::get_delta_schema;
+    use crate::{DeltaTableBuilder, DeltaTableError};
+
+    #[derive(Debug, Default)]
+    struct RecordingHook {
+        veto: bool,
+        committed: Mutex<Vec<(Version, String)>>,
+    }
+
+    #[async_trait]
+    impl CommitHook for RecordingHook {
+        async fn pre_commit(
+            &self,
+            _log_store: &LogStoreRef,
+            _operation: &DeltaOperation,
+            actions: &mut Vec<Action>,
+        ) -> DeltaResult<()> {
+            if self.veto {
+                return Err(DeltaTableError::Generic("vetoed by hook".to_string()));
+            }
+            for action in actions.iter_mut() {
+                if let Action::CommitInfo(info) = action {
+                    info.info.insert("hook".to_string(), true.into());
+                }
+            }
+            Ok(())
+        }
+
+        async fn post_commit(
+            &self,
+            _log_store: &LogStoreRef,
+            version: Version,
+            operation: &DeltaOperation,
+            _actions: &[Action],
+        ) -> DeltaResult<()> {
+            self.committed
+                .lock()
+                .push((version, operation.name().to_string()));
+            Ok(())
+        }
+    }
+
+    #[tokio::test]
+    async fn test_commit_hooks_are_invoked() -> DeltaResult<()> {
+        let hook = Arc::new(RecordingHook::default());
+        let table = DeltaTableBuilder::from_url(Url::parse("memory:///").unwrap())?
+            .with_commit_hook(hook.clone())
+            .build()?
+            .create()
+            .with_columns(get_delta_schema().fields().cloned())
+            .await?;
+
+        assert_eq!(
+            *hook.committed.lock(),
+            vec![(0, "CREATE TABLE".to_string())]
+        );
+        let info = table.history(Some(1)).await?.next().unwrap();
+        assert_eq!(info.info.get("hook"), Some(&true.into()));
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_commit_hook_of_single_commit() -> DeltaResult<()> {
+        let hook = Arc::new(RecordingHook::default());
+        let mut table = DeltaTableBuilder::from_url(Url::parse("memory:///").unwrap())?
+            .build()?
+            .create()
+            .with_columns(get_delta_schema().fields().cloned())
+            .with_commit_hook(hook.clone())
+            .await?;
+        table = table
+            .set_tbl_properties()
+            .with_properties([("delta.appendOnly".to_string(), "true".to_string())].into())
+            .await?;
+
+        assert_eq!(table.version(), Some(1));
+        assert_eq!(
+            *hook.committed.lock(),
+            vec![(0, "CREATE TABLE".to_string())]
+        );
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_pre_commit_hook_vetoes_commit() -> DeltaResult<()> {
+        let hook = Arc::new(RecordingHook {
+            veto: true,
+            ..Default::default()
+        });
+        let result = DeltaTableBuilder::from_url(Url::parse("memory:///").unwrap())?
+            .with_commit_hook(hook.clone())
+            .build()?
+            .create()
+            .with_columns(get_delta_schema().fields().cloned())
+            .await;
+
+        assert!(matches!(result, Err(DeltaTableError::Generic(msg)) if msg == "vetoed by hook"));
+        assert!(hook.committed.lock().is_empty());
+        Ok(())
+    }
+}
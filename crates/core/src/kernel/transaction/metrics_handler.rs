@@ -0,0 +1,180 @@
+//! Sink for the metrics of every operation committed through a log store.
+//!
+//! Operations record their metrics, e.g. the number of deleted rows or the time spent scanning,
+//! as `operationMetrics` in the commit info. A [`MetricsHandler`] attached via
+//! [`StorageConfig::with_metrics_handler`](crate::logstore::StorageConfig::with_metrics_handler)
+//! or [`DeltaTableBuilder::with_metrics_handler`](crate::DeltaTableBuilder::with_metrics_handler)
+//! receives them after each successful commit, along with the files touched by the commit and
+//! the number of retries it took, so they can be exported without reading the log.
+use std::collections::HashMap;
+use std::fmt::Debug;
+use std::sync::Arc;
+use std::time::Duration;
+
+use serde_json::Value;
+use uuid::Uuid;
+
+use crate::kernel::{Action, Version};
+
+/// Metrics of an operation which completed with a commit.
+#[derive(Debug, Clone, PartialEq)]
+pub struct OperationMetrics {
+    /// Name of the operation, e.g. `DELETE`
+    pub operation: String,
+    /// Id of the operation, shared with its tracing spans and object store requests
+    pub operation_id: Uuid,
+    /// Version of the commit
+    pub version: Version,
+    /// Number of data files added
+    pub num_added_files: usize,
+    /// Number of data files removed
+    pub num_removed_files: usize,
+    /// Total size of the added data files in bytes
+    pub num_added_bytes: u64,
+    /// Total size of the removed data files in bytes, if recorded in the remove actions
+    pub num_removed_bytes: u64,
+    /// Number of retries before the commit succeeded
+    pub num_retries: u64,
+    /// Time spent writing the commit, including retries
+    pub commit_duration: Duration,
+    /// Numeric metrics reported by the operation, keyed by their snake case name, e.g.
+    /// `num_deleted_rows` or `execution_time_ms`
+    pub operation_metrics: HashMap<String, u64>,
+}
+
+impl OperationMetrics {
+    pub(crate) fn new(
+        operation: String,
+        operation_id: Uuid,
+        version: Version,
+        actions: &[Action],
+        app_metadata: &HashMap<String, Value>,
+    ) -> Self {
+        let mut metrics = Self {
+            operation,
+            operation_id,
+            version,
+            num_added_files: 0,
+            num_removed_files: 0,
+            num_added_bytes: 0,
+            num_removed_bytes: 0,
+            num_retries: 0,
+            commit_duration: Duration::ZERO,
+            operation_metrics: HashMap::new(),
+        };
+        for action in actions {
+            match action {
+                Action::Add(add) => {
+                    metrics.num_added_files += 1;
+                    metrics.num_added_bytes += add.size.max(0) as u64;
+                }
+                Action::Remove(remove) => {
+                    metrics.num_removed_files += 1;
+                    metrics.num_removed_bytes += remove.size.unwrap_or_default().max(0) as u64;
+                }
+                _ => {}
+            }
+        }
+        if let Some(Value::Object(values)) = app_metadata.get("operationMetrics") {
+            metrics.operation_metrics = values
+                .iter()
+                .filter_map(|(name, value)| Some((to_snake_case(name), value.as_u64()?)))
+                .collect();
+        }
+        metrics
+    }
+
+    /// A numeric metric reported by the operation, by its snake case name
+    pub fn metric(&self, name: &str) -> Option<u64> {
+        self.operation_metrics.get(name).copied()
+    }
+
+    /// Time taken to execute the operation, if reported by it
+    pub fn execution_time(&self) -> Option<Duration> {
+        self.metric("execution_time_ms").map(Duration::from_millis)
+    }
+
+    /// Time taken to scan the table, if reported by the operation
+    pub fn scan_time(&self) -> Option<Duration> {
+        self.metric("scan_time_ms").map(Duration::from_millis)
+    }
+}
+
+/// Operations do not agree on the case of their metric names
+fn to_snake_case(name: &str) -> String {
+    let mut snake = String::with_capacity(name.len() + 4);
+    for c in name.chars() {
+        if c.is_ascii_uppercase() {
+            snake.push('_');
+            snake.push(c.to_ascii_lowercase());
+        } else {
+            snake.push(c);
+        }
+    }
+    snake
+}
+
+/// Receives the metrics of every operation committed to a table.
+pub trait MetricsHandler: Debug + Send + Sync {
+    /// Called after an operation was committed.
+    fn handle_operation_metrics(&self, metrics: &OperationMetrics);
+}
+
+/// Sharable reference to a [`MetricsHandler`]
+pub type MetricsHandlerRef = Arc<dyn MetricsHandler>;
+
+#[cfg(test)]
+mod tests {
+    use parking_lot::Mutex;
+    use url::Url;
+
+    use super::*;
+    use crate::writer::test_utils::{get_delta_schema, get_record_batch};
+    use crate::{DeltaResult, DeltaTableBuilder};
+
+    #[derive(Debug, Default)]
+    struct RecordingHandler {
+        metrics: Mutex<Vec<OperationMetrics>>,
+    }
+
+    impl MetricsHandler for RecordingHandler {
+        fn handle_operation_metrics(&self, metrics: &OperationMetrics) {
+            self.metrics.lock().push(metrics.clone());
+        }
+    }
+
+    #[test]
+    fn test_to_snake_case() {
+        assert_eq!(to_snake_case("numDeletedRows"), "num_deleted_rows");
+        assert_eq!(to_snake_case("num_added_files"), "num_added_files");
+    }
+
+    #[cfg(feature = "datafusion")]
+    #[tokio::test]
+    async fn test_metrics_handler_is_invoked() -> DeltaResult<()> {
+        let handler = Arc::new(RecordingHandler::default());
+        let table = DeltaTableBuilder::from_url(Url::parse("memory:///").unwrap())?
+            .with_metrics_handler(handler.clone())
+            .build()?
+            .create()
+            .with_columns(get_delta_schema().fields().cloned())
+            .await?;
+        let table = table.write(vec![get_record_batch(None, false)]).await?;
+        table
+            .delete()
+            .with_predicate("value > 0")
+            .await
+            .map(|_| ())?;
+
+        let metrics = handler.metrics.lock();
+        let operations: Vec<_> = metrics.iter().map(|m| m.operation.as_str()).collect();
+        assert_eq!(operations, vec!["CREATE TABLE", "WRITE", "DELETE"]);
+        assert_eq!(metrics[1].version, 1);
+        assert_eq!(metrics[1].num_added_files, 1);
+        assert!(metrics[1].num_added_bytes > 0);
+        assert_eq!(metrics[2].num_removed_files, 1);
+        assert!(metrics[2].metric("num_copied_rows").is_some());
+        assert!(metrics[2].execution_time().is_some());
+        Ok(())
+    }
+}
@@ -74,8 +74,10 @@
 //!       └───────────────────────────────┘
 //!</pre>
 use std::collections::HashMap;
+use std::num::NonZero;
 use std::str::FromStr;
 use std::sync::Arc;
+use std::time::{Duration, Instant};
 
 use bytes::Bytes;
 use chrono::Utc;
@@ -92,27 +94,41 @@ use uuid::Uuid;
 use delta_kernel::table_features::TableFeature;
 use serde::{Deserialize, Serialize};
 
-use self::conflict_checker::{TransactionInfo, WinningCommitSummary};
-use crate::errors::DeltaTableError;
+use self::conflict_checker::TransactionInfo;
+pub(crate) use self::conflict_checker::WinningCommitSummary;
+use crate::errors::{DeltaTableError, ErrorKind};
 use crate::kernel::{
     Action, CommitInfo, EagerSnapshot, IsolationLevel, Metadata, Protocol, Transaction, Version,
 };
 use crate::logstore::ObjectStoreRef;
-use crate::logstore::{CommitOrBytes, LogStoreRef};
+use crate::logstore::{CommitAttempt, CommitAttemptOutcome, CommitOrBytes, LogStoreRef};
 use crate::operations::CustomExecuteHandler;
+use crate::operations::generate::write_symlink_format_manifest;
 use crate::protocol::{DeltaOperation, operation_parameter_value};
-use crate::protocol::{cleanup_expired_logs_for, create_checkpoint_for};
+use crate::protocol::{cleanup_expired_logs_for, create_checkpoint_for, write_checksum_for_commit};
 use crate::table::config::TablePropertiesExt as _;
 use crate::table::state::DeltaTableState;
 use crate::{DeltaResult, crate_version};
 
-pub use self::conflict_checker::CommitConflictError;
+pub use self::conflict_checker::{
+    CommitConflictError, CommitConflictReport, ConflictDiagnostics, ReadSet,
+};
+pub use self::hooks::{CommitHook, CommitHookRef};
+pub use self::metrics_handler::{MetricsHandler, MetricsHandlerRef, OperationMetrics};
+pub use self::multi_table::{
+    MULTI_TABLE_TRANSACTION_DOMAIN, MultiTableCommit, MultiTableTransaction,
+};
 pub use self::protocol::INSTANCE as PROTOCOL;
+pub use self::retry::{BackoffStrategy, DEFAULT_MAX_BACKOFF, Jitter, RetryPolicy};
 
 #[cfg(test)]
 pub(crate) mod application;
 mod conflict_checker;
+mod hooks;
+mod metrics_handler;
+mod multi_table;
 mod protocol;
+mod retry;
 #[cfg(feature = "datafusion")]
 mod state;
 
@@ -191,12 +207,16 @@ pub enum TransactionError {
 
     /// Error returned when a commit conflict occurred
     #[error("Failed to commit transaction: {0}")]
-    CommitConflict(#[from] CommitConflictError),
+    CommitConflict(#[from] CommitConflictReport),
 
     /// Error returned when maximum number of commit trioals is exceeded
     #[error("Failed to commit transaction: {0}")]
     MaxCommitAttempts(i32),
 
+    /// Error returned when the commit did not succeed within the deadline of its retry policy
+    #[error("Failed to commit transaction within {0:?}")]
+    CommitDeadlineExceeded(std::time::Duration),
+
     /// The transaction includes Remove action with data change but Delta table is append-only
     #[error(
         "The transaction includes Remove action with data change but Delta table is append-only"
@@ -211,6 +231,21 @@ pub enum TransactionError {
     #[error("Table features must be specified, please specify: {0:?}")]
     TableFeaturesRequired(TableFeature),
 
+    /// Error returned when a multi-table commit failed after some of its tables were committed
+    #[error(
+        "Multi-table transaction {txn_id} was only committed to {committed} of {total} tables: {source}"
+    )]
+    MultiTableCommitIncomplete {
+        /// Id of the multi-table transaction
+        txn_id: String,
+        /// Number of tables the transaction was committed to
+        committed: usize,
+        /// Number of tables taking part in the transaction
+        total: usize,
+        /// Error which stopped the transaction
+        source: Box<DeltaTableError>,
+    },
+
     /// The transaction failed to commit due to an error in an implementation-specific layer.
     /// Currently used by DynamoDb-backed S3 log store when database operations fail.
     #[error("Transaction failed: {msg}")]
@@ -222,6 +257,34 @@ pub enum TransactionError {
     },
 }
 
+impl TransactionError {
+    /// The [kind](ErrorKind) of the error
+    pub fn kind(&self) -> ErrorKind {
+        match self {
+            Self::VersionAlreadyExists(_) => ErrorKind::CommitConflict,
+            Self::ObjectStore { source } => ErrorKind::from_object_store(source),
+            Self::CommitConflict(report) => report.error.kind(),
+            Self::MaxCommitAttempts(_) | Self::CommitDeadlineExceeded(_) => {
+                ErrorKind::CommitRetriesExhausted
+            }
+            Self::DeltaTableAppendOnly
+            | Self::UnsupportedTableFeatures(_)
+            | Self::TableFeaturesRequired(_) => ErrorKind::Protocol,
+            Self::LogStoreError { .. } => ErrorKind::Storage,
+            // some tables were committed already, so the transaction must not simply be retried
+            Self::SerializeLogJson { .. } | Self::MultiTableCommitIncomplete { .. } => {
+                ErrorKind::Internal
+            }
+        }
+    }
+}
+
+impl From<CommitConflictError> for TransactionError {
+    fn from(err: CommitConflictError) -> Self {
+        TransactionError::CommitConflict(err.into())
+    }
+}
+
 impl From<TransactionError> for DeltaTableError {
     fn from(err: TransactionError) -> Self {
         match err {
@@ -447,6 +510,15 @@ fn assign_commit_info_metadata(
     commit_info.info = app_metadata.clone();
 }
 
+/// Data files touched by a commit, recorded on its tracing span
+#[derive(Debug, Default, PartialEq)]
+struct FileTotals {
+    files_added: usize,
+    files_removed: usize,
+    bytes_added: i64,
+    bytes_removed: i64,
+}
+
 impl CommitData {
     /// Create new data to be committed
     pub fn new(
@@ -488,6 +560,25 @@ impl CommitData {
         }
     }
 
+    /// Number and total size of the data files added and removed by the commit
+    fn file_totals(&self) -> FileTotals {
+        let mut totals = FileTotals::default();
+        for action in &self.actions {
+            match action {
+                Action::Add(add) => {
+                    totals.files_added += 1;
+                    totals.bytes_added += add.size;
+                }
+                Action::Remove(remove) => {
+                    totals.files_removed += 1;
+                    totals.bytes_removed += remove.size.unwrap_or_default();
+                }
+                _ => {}
+            }
+        }
+        totals
+    }
+
     /// Obtain the byte representation of the commit.
     pub fn get_bytes(&self) -> Result<bytes::Bytes, TransactionError> {
         let mut jsons = Vec::<String>::new();
@@ -500,12 +591,16 @@ impl CommitData {
     }
 }
 
-#[derive(Clone, Debug, Copy)]
+#[derive(Clone, Debug, Copy, Default)]
 /// Properties for post commit hook.
 pub struct PostCommitHookProperties {
     create_checkpoint: bool,
     /// Override the EnableExpiredLogCleanUp setting, if None config setting is used
     cleanup_expired_logs: Option<bool>,
+    /// Override the checkpointInterval setting, if None config setting is used
+    checkpoint_interval: Option<NonZero<u64>>,
+    /// Override the checkpointIntervalDuration setting, if None config setting is used
+    checkpoint_interval_duration: Option<Duration>,
 }
 
 #[derive(Clone, Debug)]
@@ -514,9 +609,13 @@ pub struct PostCommitHookProperties {
 pub struct CommitProperties {
     pub(crate) app_metadata: HashMap<String, Value>,
     pub(crate) app_transaction: Vec<Transaction>,
-    max_retries: usize,
+    pub(crate) engine_info: Option<String>,
+    pub(crate) isolation_level: Option<IsolationLevel>,
+    pub(crate) retry_policy: Option<RetryPolicy>,
     create_checkpoint: bool,
     cleanup_expired_logs: Option<bool>,
+    checkpoint_interval: Option<NonZero<u64>>,
+    checkpoint_interval_duration: Option<Duration>,
 }
 
 impl Default for CommitProperties {
@@ -524,9 +623,13 @@ impl Default for CommitProperties {
         Self {
             app_metadata: Default::default(),
             app_transaction: Vec::new(),
-            max_retries: DEFAULT_RETRIES,
+            engine_info: None,
+            isolation_level: None,
+            retry_policy: None,
             create_checkpoint: true,
             cleanup_expired_logs: None,
+            checkpoint_interval: None,
+            checkpoint_interval_duration: None,
         }
     }
 }
@@ -541,9 +644,50 @@ impl CommitProperties {
         self
     }
 
+    /// Add a single key to the metadata to be committed, keeping previously specified keys
+    pub fn with_metadata_entry(mut self, key: impl Into<String>, value: impl Into<Value>) -> Self {
+        self.app_metadata.insert(key.into(), value.into());
+        self
+    }
+
+    /// Specify the `userMetadata` recorded in the commit info
+    pub fn with_user_metadata(self, user_metadata: impl Into<String>) -> Self {
+        self.with_metadata_entry("userMetadata", user_metadata.into())
+    }
+
+    /// Specify the `engineInfo` recorded in the commit info, replacing the default
+    /// `delta-rs:<version>`
+    pub fn with_engine_info(mut self, engine_info: impl Into<String>) -> Self {
+        self.engine_info = Some(engine_info.into());
+        self
+    }
+
+    /// Specify the isolation level of the transaction, overriding `delta.isolationLevel`.
+    ///
+    /// The level is recorded in the commit info and used when resolving conflicts with concurrent
+    /// commits. Under `WriteSerializable` concurrent blind appends do not conflict, while
+    /// `Serializable` also fails the commit if they added files the transaction should have read.
+    /// Snapshot isolation cannot be requested and falls back to the table default.
+    pub fn with_isolation_level(mut self, isolation_level: IsolationLevel) -> Self {
+        self.isolation_level = Some(isolation_level);
+        self
+    }
+
     /// Specify maximum number of times to retry the transaction before failing to commit
     pub fn with_max_retries(mut self, max_retries: usize) -> Self {
-        self.max_retries = max_retries;
+        self.retry_policy = Some(
+            self.retry_policy
+                .unwrap_or_default()
+                .with_max_retries(max_retries),
+        );
+        self
+    }
+
+    /// Specify how the transaction is retried when concurrent writers committed first.
+    ///
+    /// Overrides the table's default retry policy.
+    pub fn with_retry_policy(mut self, retry_policy: RetryPolicy) -> Self {
+        self.retry_policy = Some(retry_policy);
         self
     }
 
@@ -570,18 +714,35 @@ impl CommitProperties {
         self.cleanup_expired_logs = cleanup_expired_logs;
         self
     }
+
+    /// Create a checkpoint every `interval` commits, overriding `delta.checkpointInterval`
+    pub fn with_checkpoint_interval(mut self, interval: NonZero<u64>) -> Self {
+        self.checkpoint_interval = Some(interval);
+        self
+    }
+
+    /// Create a checkpoint once the last one is older than `interval`, overriding
+    /// `delta-rs.checkpointIntervalDuration`
+    pub fn with_checkpoint_interval_duration(mut self, interval: Duration) -> Self {
+        self.checkpoint_interval_duration = Some(interval);
+        self
+    }
 }
 
 impl From<CommitProperties> for CommitBuilder {
     fn from(value: CommitProperties) -> Self {
         CommitBuilder {
-            max_retries: value.max_retries,
+            retry_policy: value.retry_policy,
             app_metadata: value.app_metadata,
             post_commit_hook: Some(PostCommitHookProperties {
                 create_checkpoint: value.create_checkpoint,
                 cleanup_expired_logs: value.cleanup_expired_logs,
+                checkpoint_interval: value.checkpoint_interval,
+                checkpoint_interval_duration: value.checkpoint_interval_duration,
             }),
             app_transaction: value.app_transaction,
+            engine_info: value.engine_info,
+            isolation_level: value.isolation_level,
             ..Default::default()
         }
     }
@@ -592,9 +753,13 @@ pub struct CommitBuilder {
     actions: Vec<Action>,
     app_metadata: HashMap<String, Value>,
     app_transaction: Vec<Transaction>,
-    max_retries: usize,
+    engine_info: Option<String>,
+    isolation_level: Option<IsolationLevel>,
+    read_set: Option<ReadSet>,
+    retry_policy: Option<RetryPolicy>,
     post_commit_hook: Option<PostCommitHookProperties>,
     post_commit_hook_handler: Option<Arc<dyn CustomExecuteHandler>>,
+    commit_hooks: Vec<CommitHookRef>,
     operation_id: Uuid,
 }
 
@@ -604,9 +769,13 @@ impl Default for CommitBuilder {
             actions: Vec::new(),
             app_metadata: HashMap::new(),
             app_transaction: Vec::new(),
-            max_retries: DEFAULT_RETRIES,
+            engine_info: None,
+            isolation_level: None,
+            read_set: None,
+            retry_policy: None,
             post_commit_hook: None,
             post_commit_hook_handler: None,
+            commit_hooks: Vec::new(),
             operation_id: Uuid::new_v4(),
         }
     }
@@ -619,15 +788,53 @@ impl<'a> CommitBuilder {
         self
     }
 
+    /// Stage an additional action to be included in the commit
+    pub fn with_action(mut self, action: impl Into<Action>) -> Self {
+        self.actions.push(action.into());
+        self
+    }
+
     /// Metadata for the operation performed like metrics, user, and notebook
     pub fn with_app_metadata(mut self, app_metadata: HashMap<String, Value>) -> Self {
         self.app_metadata = app_metadata;
         self
     }
 
+    /// Engine information recorded in the commit info instead of the default
+    pub fn with_engine_info(mut self, engine_info: impl Into<String>) -> Self {
+        self.engine_info = Some(engine_info.into());
+        self
+    }
+
+    /// Isolation level of the transaction, defaults to the table's `delta.isolationLevel`
+    pub fn with_isolation_level(mut self, isolation_level: IsolationLevel) -> Self {
+        self.isolation_level = Some(isolation_level);
+        self
+    }
+
     /// Maximum number of times to retry the transaction before failing to commit
     pub fn with_max_retries(mut self, max_retries: usize) -> Self {
-        self.max_retries = max_retries;
+        self.retry_policy = Some(
+            self.retry_policy
+                .unwrap_or_default()
+                .with_max_retries(max_retries),
+        );
+        self
+    }
+
+    /// How the transaction is retried, defaults to the table's retry policy
+    pub fn with_retry_policy(mut self, retry_policy: RetryPolicy) -> Self {
+        self.retry_policy = Some(retry_policy);
+        self
+    }
+
+    /// Declare the files and partitions the transaction read.
+    ///
+    /// When set, conflict resolution only fails the commit if concurrent transactions changed
+    /// these files or added data to these partitions, rather than anything matching the
+    /// operation's read predicate.
+    pub fn with_read_set(mut self, read_set: ReadSet) -> Self {
+        self.read_set = Some(read_set);
         self
     }
 
@@ -652,6 +859,14 @@ impl<'a> CommitBuilder {
         self
     }
 
+    /// Attach a [`CommitHook`] invoked around this commit only.
+    ///
+    /// The hook runs after the hooks configured on the log store.
+    pub fn with_commit_hook(mut self, hook: CommitHookRef) -> Self {
+        self.commit_hooks.push(hook);
+        self
+    }
+
     /// Prepare a Commit operation using the configured builder
     pub fn build(
         self,
@@ -659,19 +874,33 @@ impl<'a> CommitBuilder {
         log_store: LogStoreRef,
         operation: DeltaOperation,
     ) -> PreCommit<'a> {
-        let data = CommitData::new(
+        let mut data = CommitData::new(
             self.actions,
             operation,
             self.app_metadata,
             self.app_transaction,
         );
+        if let Some(Action::CommitInfo(commit_info)) = data
+            .actions
+            .iter_mut()
+            .find(|action| matches!(action, Action::CommitInfo(..)))
+        {
+            if let Some(engine_info) = self.engine_info {
+                commit_info.engine_info = Some(engine_info);
+            }
+            if let Some(isolation_level) = self.isolation_level {
+                commit_info.isolation_level = Some(isolation_level);
+            }
+        }
         PreCommit {
             log_store,
             table_data,
-            max_retries: self.max_retries,
+            retry_policy: self.retry_policy,
             data,
+            read_set: self.read_set,
             post_commit_hook: self.post_commit_hook,
             post_commit_hook_handler: self.post_commit_hook_handler,
+            commit_hooks: self.commit_hooks,
             operation_id: self.operation_id,
         }
     }
@@ -682,9 +911,11 @@ pub struct PreCommit<'a> {
     log_store: LogStoreRef,
     table_data: Option<&'a dyn TableReference>,
     data: CommitData,
-    max_retries: usize,
+    read_set: Option<ReadSet>,
+    retry_policy: Option<RetryPolicy>,
     post_commit_hook: Option<PostCommitHookProperties>,
     post_commit_hook_handler: Option<Arc<dyn CustomExecuteHandler>>,
+    commit_hooks: Vec<CommitHookRef>,
     operation_id: Uuid,
 }
 
@@ -700,7 +931,7 @@ impl<'a> std::future::IntoFuture for PreCommit<'a> {
 impl<'a> PreCommit<'a> {
     /// Prepare the commit but do not finalize it
     pub fn into_prepared_commit_future(self) -> BoxFuture<'a, DeltaResult<PreparedCommit<'a>>> {
-        let this = self;
+        let mut this = self;
 
         // Write delta log entry as temporary file to storage. For the actual commit,
         // the temporary file is moved (atomic rename) to the delta log folder within `commit` function.
@@ -715,6 +946,15 @@ impl<'a> PreCommit<'a> {
         }
 
         Box::pin(async move {
+            let log_store_hooks = &this.log_store.config().options().commit_hooks;
+            for hook in log_store_hooks.iter().chain(&this.commit_hooks) {
+                hook.pre_commit(
+                    &this.log_store,
+                    &this.data.operation,
+                    &mut this.data.actions,
+                )
+                .await?;
+            }
             if let Some(table_reference) = this.table_data {
                 PROTOCOL.can_commit(table_reference, &this.data.actions, &this.data.operation)?;
             }
@@ -738,10 +978,12 @@ impl<'a> PreCommit<'a> {
                 commit_or_bytes,
                 log_store: this.log_store,
                 table_data: this.table_data,
-                max_retries: this.max_retries,
+                retry_policy: this.retry_policy,
                 data: this.data,
+                read_set: this.read_set,
                 post_commit: this.post_commit_hook,
                 post_commit_hook_handler: this.post_commit_hook_handler,
+                commit_hooks: this.commit_hooks,
                 operation_id: this.operation_id,
             })
         })
@@ -753,10 +995,12 @@ pub struct PreparedCommit<'a> {
     commit_or_bytes: CommitOrBytes,
     log_store: LogStoreRef,
     data: CommitData,
+    read_set: Option<ReadSet>,
     table_data: Option<&'a dyn TableReference>,
-    max_retries: usize,
+    retry_policy: Option<RetryPolicy>,
     post_commit: Option<PostCommitHookProperties>,
     post_commit_hook_handler: Option<Arc<dyn CustomExecuteHandler>>,
+    commit_hooks: Vec<CommitHookRef>,
     operation_id: Uuid,
 }
 
@@ -765,6 +1009,36 @@ impl PreparedCommit<'_> {
     pub fn commit_or_bytes(&self) -> &CommitOrBytes {
         &self.commit_or_bytes
     }
+
+    /// Write the commit entry for `version`, reporting the attempt to the metrics recorder of
+    /// the log store if one is configured.
+    async fn write_commit_entry(
+        &self,
+        version: Version,
+        attempt: usize,
+    ) -> Result<(), TransactionError> {
+        let started = Instant::now();
+        let result = self
+            .log_store
+            .write_commit_entry(version, self.commit_or_bytes.clone(), self.operation_id)
+            .await;
+        if let Some(recorder) = &self.log_store.config().options().metrics {
+            let outcome = match &result {
+                Ok(()) => CommitAttemptOutcome::Committed,
+                Err(TransactionError::VersionAlreadyExists(_)) => {
+                    CommitAttemptOutcome::VersionExists
+                }
+                Err(_) => CommitAttemptOutcome::Failed,
+            };
+            recorder.record_commit_attempt(&CommitAttempt {
+                version,
+                attempt,
+                duration: started.elapsed(),
+                outcome,
+            });
+        }
+        result
+    }
 }
 
 impl<'a> std::future::IntoFuture for PreparedCommit<'a> {
@@ -775,8 +1049,7 @@ impl<'a> std::future::IntoFuture for PreparedCommit<'a> {
         let this = self;
 
         Box::pin(async move {
-            let commit_or_bytes = this.commit_or_bytes;
-
+            let commit_started = Instant::now();
             let mut attempt_number: usize = 1;
 
             // Handle the case where table doesn't exist yet (initial table creation)
@@ -784,21 +1057,19 @@ impl<'a> std::future::IntoFuture for PreparedCommit<'a> {
                 table_data.eager_snapshot().clone()
             } else {
                 debug!("committing initial table version 0");
-                match this
-                    .log_store
-                    .write_commit_entry(0, commit_or_bytes.clone(), this.operation_id)
-                    .await
-                {
+                match this.write_commit_entry(0, attempt_number).await {
                     Ok(_) => {
                         return Ok(PostCommit {
                             version: 0,
                             data: this.data,
-                            create_checkpoint: false,
-                            cleanup_expired_logs: None,
+                            post_commit: PostCommitHookProperties::default(),
                             log_store: this.log_store,
                             table_data: None,
                             custom_execute_handler: this.post_commit_hook_handler,
+                            commit_hooks: this.commit_hooks,
                             metrics: CommitMetrics { num_retries: 0 },
+                            operation_id: this.operation_id,
+                            commit_duration: commit_started.elapsed(),
                         });
                     }
                     Err(TransactionError::VersionAlreadyExists(0)) => {
@@ -819,18 +1090,33 @@ impl<'a> std::future::IntoFuture for PreparedCommit<'a> {
             };
 
             let mut read_snapshot = read_snapshot;
-
+            let retry_policy = this
+                .retry_policy
+                .clone()
+                .or_else(|| read_snapshot.table_properties().commit_retry_policy())
+                .unwrap_or_default();
+            let max_retries = retry_policy.max_retries();
+            let started = Instant::now();
+
+            let totals = this.data.file_totals();
             let commit_span = info_span!(
                 "commit_with_retries",
+                operation = this.data.operation.name(),
+                operation_id = %this.operation_id,
+                table_uri = %this.log_store.root_url(),
                 base_version = read_snapshot.version(),
-                max_retries = this.max_retries,
+                max_retries = max_retries,
+                files_added = totals.files_added,
+                files_removed = totals.files_removed,
+                bytes_added = totals.bytes_added,
+                bytes_removed = totals.bytes_removed,
                 attempt = field::Empty,
                 target_version = field::Empty,
                 conflicts_checked = 0
             );
 
             async move {
-                let total_retries = this.max_retries + 1;
+                let total_retries = max_retries + 1;
                 while attempt_number <= total_retries {
                     Span::current().record("attempt", attempt_number);
                     let latest_version = this
@@ -841,16 +1127,15 @@ impl<'a> std::future::IntoFuture for PreparedCommit<'a> {
                     if latest_version > read_snapshot.version() {
                         // If max_retries are set to 0, do not try to use the conflict checker to resolve the conflict
                         // and throw immediately
-                        if this.max_retries == 0 {
+                        if max_retries == 0 {
                             warn!(
                                 base_version = read_snapshot.version(),
                                 latest_version = latest_version,
                                 "table updated but max_retries is 0, failing immediately"
                             );
-                            return Err(TransactionError::MaxCommitAttempts(
-                                this.max_retries as i32,
-                            )
-                            .into());
+                            return Err(
+                                TransactionError::MaxCommitAttempts(max_retries as i32).into()
+                            );
                         }
                         warn!(
                             base_version = read_snapshot.version(),
@@ -880,7 +1165,8 @@ impl<'a> std::future::IntoFuture for PreparedCommit<'a> {
                                 this.data.operation.read_predicate(),
                                 &this.data.actions,
                                 this.data.operation.read_whole_table(),
-                            )?;
+                            )?
+                            .with_read_set(this.read_set.as_ref());
                             let conflict_checker = ConflictChecker::new(
                                 transaction_info,
                                 summary,
@@ -895,7 +1181,15 @@ impl<'a> std::future::IntoFuture for PreparedCommit<'a> {
                                         error = %err,
                                         "conflict detected, aborting transaction"
                                     );
-                                    return Err(TransactionError::CommitConflict(err).into());
+                                    let diagnostics = conflict_checker.diagnostics(
+                                        &err,
+                                        read_snapshot.version(),
+                                        latest_version - steps + 1,
+                                    );
+                                    return Err(TransactionError::CommitConflict(
+                                        CommitConflictReport::new(err, diagnostics),
+                                    )
+                                    .into());
                                 }
                             }
                             steps -= 1;
@@ -913,11 +1207,7 @@ impl<'a> std::future::IntoFuture for PreparedCommit<'a> {
                     let version: Version = latest_version + 1;
                     Span::current().record("target_version", version);
 
-                    match this
-                        .log_store
-                        .write_commit_entry(version, commit_or_bytes.clone(), this.operation_id)
-                        .await
-                    {
+                    match this.write_commit_entry(version, attempt_number).await {
                         Ok(()) => {
                             info!(
                                 version = version,
@@ -927,20 +1217,16 @@ impl<'a> std::future::IntoFuture for PreparedCommit<'a> {
                             return Ok(PostCommit {
                                 version,
                                 data: this.data,
-                                create_checkpoint: this
-                                    .post_commit
-                                    .map(|v| v.create_checkpoint)
-                                    .unwrap_or_default(),
-                                cleanup_expired_logs: this
-                                    .post_commit
-                                    .map(|v| v.cleanup_expired_logs)
-                                    .unwrap_or_default(),
+                                post_commit: this.post_commit.unwrap_or_default(),
                                 log_store: this.log_store,
                                 table_data: Some(Box::new(read_snapshot)),
                                 custom_execute_handler: this.post_commit_hook_handler,
+                                commit_hooks: this.commit_hooks,
                                 metrics: CommitMetrics {
                                     num_retries: (attempt_number - 1) as u64,
                                 },
+                                operation_id: this.operation_id,
+                                commit_duration: commit_started.elapsed(),
                             });
                         }
                         Err(TransactionError::VersionAlreadyExists(version)) => {
@@ -952,6 +1238,24 @@ impl<'a> std::future::IntoFuture for PreparedCommit<'a> {
                             // If the version already exists, loop through again and re-check
                             // conflicts
                             attempt_number += 1;
+                            if attempt_number <= total_retries {
+                                let delay = retry_policy.delay(attempt_number - 1);
+                                if let Some(deadline) = retry_policy.deadline()
+                                    && started.elapsed() + delay > deadline
+                                {
+                                    warn!(
+                                        attempt = attempt_number,
+                                        "commit retry deadline exceeded"
+                                    );
+                                    return Err(
+                                        TransactionError::CommitDeadlineExceeded(deadline).into()
+                                    );
+                                }
+                                if !delay.is_zero() {
+                                    debug!(delay_ms = delay.as_millis() as u64, "backing off");
+                                    tokio::time::sleep(delay).await;
+                                }
+                            }
                         }
                         Err(err) => {
                             error!(
@@ -960,7 +1264,11 @@ impl<'a> std::future::IntoFuture for PreparedCommit<'a> {
                                 "commit failed, aborting"
                             );
                             this.log_store
-                                .abort_commit_entry(version, commit_or_bytes, this.operation_id)
+                                .abort_commit_entry(
+                                    version,
+                                    this.commit_or_bytes.clone(),
+                                    this.operation_id,
+                                )
                                 .await?;
                             return Err(err.into());
                         }
@@ -968,10 +1276,10 @@ impl<'a> std::future::IntoFuture for PreparedCommit<'a> {
                 }
 
                 error!(
-                    max_retries = this.max_retries,
+                    max_retries = max_retries,
                     "exceeded maximum commit attempts"
                 );
-                Err(TransactionError::MaxCommitAttempts(this.max_retries as i32).into())
+                Err(TransactionError::MaxCommitAttempts(max_retries as i32).into())
             }
             .instrument(commit_span)
             .await
@@ -985,12 +1293,14 @@ pub struct PostCommit {
     pub version: Version,
     /// The data that was committed to the log store
     pub data: CommitData,
-    create_checkpoint: bool,
-    cleanup_expired_logs: Option<bool>,
+    post_commit: PostCommitHookProperties,
     log_store: LogStoreRef,
     table_data: Option<Box<dyn TableReference>>,
     custom_execute_handler: Option<Arc<dyn CustomExecuteHandler>>,
+    commit_hooks: Vec<CommitHookRef>,
     metrics: CommitMetrics,
+    operation_id: Uuid,
+    commit_duration: Duration,
 }
 
 impl PostCommit {
@@ -1005,25 +1315,29 @@ impl PostCommit {
 
             let mut state = DeltaTableState { snapshot };
 
-            let cleanup_logs = if let Some(cleanup_logs) = self.cleanup_expired_logs {
-                cleanup_logs
-            } else {
-                state.table_config().enable_expired_log_cleanup()
-            };
+            // Tables loaded with maintenance disabled, e.g. read replicas, leave checkpointing and
+            // log cleanup to another writer
+            let maintenance = !state.load_config().skip_post_commit_maintenance;
+            let create_checkpoint = maintenance && self.post_commit.create_checkpoint;
+            let cleanup_logs = maintenance
+                && self
+                    .post_commit
+                    .cleanup_expired_logs
+                    .unwrap_or_else(|| state.table_config().enable_expired_log_cleanup());
 
             // Run arbitrary before_post_commit_hook code
             if let Some(custom_execute_handler) = &self.custom_execute_handler {
                 custom_execute_handler
                     .before_post_commit_hook(
                         &self.log_store,
-                        cleanup_logs || self.create_checkpoint,
+                        cleanup_logs || create_checkpoint,
                         post_commit_operation_id,
                     )
                     .await?
             }
 
             let mut new_checkpoint_created = false;
-            if self.create_checkpoint {
+            if create_checkpoint {
                 // Execute create checkpoint hook
                 new_checkpoint_created = self
                     .create_checkpoint(
@@ -1056,12 +1370,26 @@ impl PostCommit {
                 }
             }
 
+            if state.table_config().symlink_format_manifest_enabled() {
+                // Keep the manifests in sync for engines which only read the symlink manifest
+                write_symlink_format_manifest(&self.log_store, &state.snapshot).await?;
+            }
+
+            if state.table_config().write_checksum_file() {
+                write_checksum_for_commit(
+                    self.log_store.as_ref(),
+                    &state.snapshot,
+                    &self.data.actions,
+                )
+                .await?;
+            }
+
             // Run arbitrary after_post_commit_hook code
             if let Some(custom_execute_handler) = &self.custom_execute_handler {
                 custom_execute_handler
                     .after_post_commit_hook(
                         &self.log_store,
-                        cleanup_logs || self.create_checkpoint,
+                        cleanup_logs || create_checkpoint,
                         post_commit_operation_id,
                     )
                     .await?
@@ -1077,6 +1405,17 @@ impl PostCommit {
             let state =
                 DeltaTableState::try_new(&self.log_store, Default::default(), Some(self.version))
                     .await?;
+            if state.table_config().symlink_format_manifest_enabled() {
+                write_symlink_format_manifest(&self.log_store, &state.snapshot).await?;
+            }
+            if state.table_config().write_checksum_file() {
+                write_checksum_for_commit(
+                    self.log_store.as_ref(),
+                    &state.snapshot,
+                    &self.data.actions,
+                )
+                .await?;
+            }
             Ok((
                 state,
                 PostCommitMetrics {
@@ -1100,8 +1439,21 @@ impl PostCommit {
             return Ok(false);
         }
 
-        let checkpoint_interval = table_state.config().checkpoint_interval().get();
-        if (version + 1).is_multiple_of(checkpoint_interval) {
+        let checkpoint_interval = self
+            .post_commit
+            .checkpoint_interval
+            .unwrap_or_else(|| table_state.table_config().checkpoint_interval())
+            .get();
+        let checkpoint_interval_duration = self
+            .post_commit
+            .checkpoint_interval_duration
+            .or_else(|| table_state.table_config().checkpoint_interval_duration());
+        let due = (version + 1).is_multiple_of(checkpoint_interval)
+            || match checkpoint_interval_duration {
+                Some(interval) => last_checkpoint_older_than(log_store, interval).await?,
+                None => false,
+            };
+        if due {
             create_checkpoint_for(version, log_store.as_ref(), Some(operation_id)).await?;
             Ok(true)
         } else {
@@ -1110,6 +1462,21 @@ impl PostCommit {
     }
 }
 
+/// Whether the most recent checkpoint was written more than `interval` ago, or none exists yet
+async fn last_checkpoint_older_than(
+    log_store: &LogStoreRef,
+    interval: Duration,
+) -> DeltaResult<bool> {
+    let path = log_store.log_path().child("_last_checkpoint");
+    match log_store.object_store(None).head(&path).await {
+        Ok(meta) => Ok((Utc::now() - meta.last_modified)
+            .to_std()
+            .is_ok_and(|age| age >= interval)),
+        Err(ObjectStoreError::NotFound { .. }) => Ok(true),
+        Err(err) => Err(err.into()),
+    }
+}
+
 /// A commit that successfully completed
 pub struct FinalizedCommit {
     /// The new table state after a commit
@@ -1150,7 +1517,30 @@ impl std::future::IntoFuture for PostCommit {
         let this = self;
 
         Box::pin(async move {
-            match this.run_post_commit_hook().await {
+            let result = this.run_post_commit_hook().await;
+            if let Some(handler) = &this.log_store.config().options().metrics_handler {
+                let mut metrics = OperationMetrics::new(
+                    this.data.operation.name().to_string(),
+                    this.operation_id,
+                    this.version,
+                    &this.data.actions,
+                    &this.data.app_metadata,
+                );
+                metrics.num_retries = this.metrics.num_retries;
+                metrics.commit_duration = this.commit_duration;
+                handler.handle_operation_metrics(&metrics);
+            }
+            let log_store_hooks = &this.log_store.config().options().commit_hooks;
+            for hook in log_store_hooks.iter().chain(&this.commit_hooks) {
+                hook.post_commit(
+                    &this.log_store,
+                    this.version,
+                    &this.data.operation,
+                    &this.data.actions,
+                )
+                .await?;
+            }
+            match result {
                 Ok((snapshot, post_commit_metrics)) => Ok(FinalizedCommit {
                     snapshot,
                     version: this.version,
@@ -1171,7 +1561,7 @@ mod tests {
     use std::sync::Arc;
 
     use super::*;
-    use crate::kernel::IsolationLevel;
+    use crate::kernel::{Add, IsolationLevel, Remove};
     use crate::logstore::{LogStore, StorageConfig, default_logstore::DefaultLogStore};
     use crate::protocol::SaveMode;
     use object_store::{PutPayload, memory::InMemory};
@@ -1232,16 +1622,107 @@ mod tests {
         span.record("conflicts_checked", 2);
     }
 
+    #[test]
+    fn test_commit_data_file_totals() {
+        let actions = vec![
+            Action::Add(Add {
+                path: "a.parquet".to_string(),
+                size: 100,
+                ..Default::default()
+            }),
+            Action::Add(Add {
+                path: "b.parquet".to_string(),
+                size: 20,
+                ..Default::default()
+            }),
+            Action::Remove(Remove {
+                path: "c.parquet".to_string(),
+                size: Some(50),
+                ..Default::default()
+            }),
+            Action::Remove(Remove {
+                path: "d.parquet".to_string(),
+                size: None,
+                ..Default::default()
+            }),
+        ];
+        let data = CommitData::new(
+            actions,
+            DeltaOperation::custom("TEST"),
+            HashMap::new(),
+            vec![],
+        );
+        assert_eq!(
+            data.file_totals(),
+            FileTotals {
+                files_added: 2,
+                files_removed: 2,
+                bytes_added: 120,
+                bytes_removed: 50,
+            }
+        );
+    }
+
     #[test]
     fn test_commit_properties_with_retries() {
         let props = CommitProperties::default()
             .with_max_retries(5)
             .with_create_checkpoint(false);
 
-        assert_eq!(props.max_retries, 5);
+        assert_eq!(
+            props.retry_policy.map(|policy| policy.max_retries()),
+            Some(5)
+        );
         assert!(!props.create_checkpoint);
     }
 
+    #[test]
+    fn test_commit_properties_user_metadata_and_engine_info() {
+        let store = Arc::new(InMemory::new());
+        let url = Url::parse("mem://what/is/this").unwrap();
+        let log_store: LogStoreRef = Arc::new(DefaultLogStore::new(
+            store.clone(),
+            store,
+            crate::logstore::LogStoreConfig::new(&url, StorageConfig::default()),
+        ));
+        let props = CommitProperties::default()
+            .with_metadata(vec![("team".to_owned(), json!("lineage"))])
+            .with_metadata_entry("jobId", "job-42")
+            .with_user_metadata("nightly load")
+            .with_engine_info("my-engine/1.0");
+
+        let commit =
+            CommitBuilder::from(props).build(None, log_store, DeltaOperation::FileSystemCheck {});
+        let info = commit_info(&commit.data);
+        assert_eq!(info.user_metadata.as_deref(), Some("nightly load"));
+        assert_eq!(info.engine_info.as_deref(), Some("my-engine/1.0"));
+        assert_eq!(info.info.get("jobId"), Some(&json!("job-42")));
+        assert_eq!(info.info.get("team"), Some(&json!("lineage")));
+    }
+
+    #[test]
+    fn test_commit_properties_isolation_level() {
+        let store = Arc::new(InMemory::new());
+        let url = Url::parse("mem://what/is/this").unwrap();
+        let log_store: LogStoreRef = Arc::new(DefaultLogStore::new(
+            store.clone(),
+            store,
+            crate::logstore::LogStoreConfig::new(&url, StorageConfig::default()),
+        ));
+        let props = CommitProperties::default()
+            .with_metadata(vec![("isolationLevel".to_owned(), json!("Serializable"))])
+            .with_isolation_level(IsolationLevel::WriteSerializable);
+
+        let commit =
+            CommitBuilder::from(props).build(None, log_store, DeltaOperation::FileSystemCheck {});
+        let info = commit_info(&commit.data);
+        assert_eq!(
+            info.isolation_level,
+            Some(IsolationLevel::WriteSerializable)
+        );
+        assert!(!info.info.contains_key("isolationLevel"));
+    }
+
     #[test]
     fn test_commit_metrics() {
         let metrics = CommitMetrics { num_retries: 3 };
@@ -1462,4 +1943,107 @@ mod tests {
         assert!(!info.info.contains_key("readVersion"));
         assert!(!info.info.contains_key("isBlindAppend"));
     }
+
+    #[tokio::test]
+    async fn test_post_commit_checkpoint_policy() -> DeltaResult<()> {
+        use crate::DeltaTableConfig;
+        use crate::table::config::TableProperty;
+        use crate::writer::test_utils::get_delta_schema;
+
+        async fn commit(
+            table: &crate::DeltaTable,
+            properties: CommitProperties,
+        ) -> DeltaResult<FinalizedCommit> {
+            CommitBuilder::from(properties)
+                .build(
+                    Some(table.snapshot()?),
+                    table.log_store(),
+                    DeltaOperation::FileSystemCheck {},
+                )
+                .await
+        }
+
+        // Without any checkpoint the last one is considered expired
+        let mut table = crate::DeltaTable::new_in_memory()
+            .create()
+            .with_columns(get_delta_schema().fields().cloned())
+            .with_configuration_property(
+                TableProperty::CheckpointIntervalDuration,
+                Some("interval 1 hour"),
+            )
+            .await?;
+        let finalized = commit(&table, CommitProperties::default()).await?;
+        assert!(finalized.metrics.new_checkpoint_created);
+        table.update_state().await?;
+        let finalized = commit(&table, CommitProperties::default()).await?;
+        assert!(!finalized.metrics.new_checkpoint_created);
+
+        // Per operation overrides take precedence over the table properties
+        table.update_state().await?;
+        let properties = CommitProperties::default()
+            .with_checkpoint_interval_duration(Duration::ZERO)
+            .with_checkpoint_interval(NonZero::new(1000).unwrap());
+        let finalized = commit(&table, properties).await?;
+        assert!(finalized.metrics.new_checkpoint_created);
+
+        // Replicas leave maintenance to the primary writer
+        let config = DeltaTableConfig {
+            skip_post_commit_maintenance: true,
+            ..Default::default()
+        };
+        let mut replica = crate::DeltaTable::new(table.log_store(), config);
+        replica.load().await?;
+        let properties = CommitProperties::default()
+            .with_checkpoint_interval_duration(Duration::ZERO)
+            .with_checkpoint_interval(NonZero::new(1).unwrap());
+        let finalized = commit(&replica, properties).await?;
+        assert!(!finalized.metrics.new_checkpoint_created);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_commit_custom_operation() -> DeltaResult<()> {
+        use crate::kernel::Add;
+        use crate::writer::test_utils::get_delta_schema;
+
+        let mut table = crate::DeltaTable::new_in_memory()
+            .create()
+            .with_columns(get_delta_schema().fields().cloned())
+            .await?;
+
+        let operation = DeltaOperation::Custom {
+            name: "COMPACTION SERVICE".to_string(),
+            parameters: HashMap::from([("jobId".to_string(), "42".to_string())]),
+            predicate: None,
+            data_change: true,
+        };
+        let add = Add {
+            path: "part-00000.parquet".to_string(),
+            size: 100,
+            modification_time: 0,
+            data_change: true,
+            ..Default::default()
+        };
+        let finalized = CommitBuilder::default()
+            .with_action(add)
+            .with_action(Action::Txn(Transaction::new("compaction", 3)))
+            .build(Some(table.snapshot()?), table.log_store(), operation)
+            .await?;
+        assert_eq!(finalized.version(), 1);
+
+        table.update_state().await?;
+        assert_eq!(table.snapshot()?.log_data().num_files(), 1);
+        let info = table.history(Some(1)).await?.next().unwrap();
+        assert_eq!(info.operation.as_deref(), Some("COMPACTION SERVICE"));
+        assert_eq!(
+            info.operation_parameters.unwrap().get("jobId"),
+            Some(&json!("42"))
+        );
+        let version = table
+            .snapshot()?
+            .transaction_version(table.log_store().as_ref(), "compaction")
+            .await?;
+        assert_eq!(version, Some(3));
+        Ok(())
+    }
 }
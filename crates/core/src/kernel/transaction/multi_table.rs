@@ -0,0 +1,365 @@
+//! Commits spanning several tables.
+//!
+//! Delta Lake has no atomic commits across tables. [`MultiTableCommit`] coordinates them in two
+//! phases instead: first every commit is prepared, i.e. validated and serialized, so that most
+//! failures surface before anything was written. Then the commits are finalized one table after
+//! the other.
+//!
+//! Each commit records the id of the multi-table transaction in the
+//! [`MULTI_TABLE_TRANSACTION_DOMAIN`] domain metadata of its table. If finalizing fails midway,
+//! consumers can detect the partially applied transaction via
+//! [`MultiTableTransaction::is_complete`] and skip it, e.g. by reading the tables at the versions
+//! before it. All participating tables must support the `domainMetadata` writer feature.
+use delta_kernel::table_features::TableFeature;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use super::{CommitBuilder, CommitProperties, FinalizedCommit, PreparedCommit, TransactionError};
+use crate::kernel::{Action, DomainMetadata};
+use crate::protocol::DeltaOperation;
+use crate::table::state::DeltaTableState;
+use crate::{DeltaResult, DeltaTable, DeltaTableError};
+
+/// Domain under which the multi-table transaction of a commit is recorded
+pub const MULTI_TABLE_TRANSACTION_DOMAIN: &str = "delta-rs.multiTableTransaction";
+
+/// A transaction spanning several tables, as recorded in each participating table
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct MultiTableTransaction {
+    /// Id shared by the commits of the transaction
+    pub txn_id: String,
+    /// Locations of all tables taking part in the transaction
+    pub tables: Vec<String>,
+}
+
+impl MultiTableTransaction {
+    /// The multi-table transaction which last committed to `table`, if any
+    pub async fn latest(table: &DeltaTable) -> DeltaResult<Option<Self>> {
+        let log_store = table.log_store();
+        let Some(configuration) = table
+            .snapshot()?
+            .domain_metadata(log_store.as_ref(), MULTI_TABLE_TRANSACTION_DOMAIN)
+            .await?
+        else {
+            return Ok(None);
+        };
+        serde_json::from_str(&configuration)
+            .map(Some)
+            .map_err(|err| DeltaTableError::InvalidData {
+                message: format!(
+                    "Invalid multi-table transaction in {MULTI_TABLE_TRANSACTION_DOMAIN}: {err}"
+                ),
+            })
+    }
+
+    /// Check whether the transaction was applied to all participating tables.
+    ///
+    /// `tables` must contain the current state of every participating table. A participant whose
+    /// latest multi-table transaction differs from this one has not applied it.
+    ///
+    /// This gives no guarantee once any other writer has committed to one of the tables: a
+    /// later multi-table transaction, or any commit recording its own marker, makes a complete
+    /// transaction look incomplete, and a partially applied one can never be told apart from a
+    /// complete one if the missing table was written by a plain commit since. Only rely on it
+    /// for tables exclusively written by multi-table transactions involving all of them.
+    pub async fn is_complete(&self, tables: &[DeltaTable]) -> DeltaResult<bool> {
+        for location in &self.tables {
+            let table = tables
+                .iter()
+                .find(|table| table.log_store().root_url().as_str() == location)
+                .ok_or_else(|| {
+                    DeltaTableError::generic(format!(
+                        "Table {location} of multi-table transaction {} was not provided",
+                        self.txn_id
+                    ))
+                })?;
+            let latest = Self::latest(table).await?;
+            if latest.is_none_or(|latest| latest.txn_id != self.txn_id) {
+                return Ok(false);
+            }
+        }
+        Ok(true)
+    }
+}
+
+struct TableCommit<'a> {
+    table: &'a DeltaTable,
+    actions: Vec<Action>,
+    operation: DeltaOperation,
+    properties: CommitProperties,
+}
+
+/// Coordinates commits to several tables, see the [module documentation](self).
+pub struct MultiTableCommit<'a> {
+    txn_id: String,
+    commits: Vec<TableCommit<'a>>,
+}
+
+impl Default for MultiTableCommit<'_> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<'a> MultiTableCommit<'a> {
+    /// Create a new multi-table commit with a random transaction id
+    pub fn new() -> Self {
+        Self {
+            txn_id: Uuid::new_v4().to_string(),
+            commits: Vec::new(),
+        }
+    }
+
+    /// Use the given id for the transaction, e.g. the id of the pipeline run
+    pub fn with_txn_id(mut self, txn_id: impl Into<String>) -> Self {
+        self.txn_id = txn_id.into();
+        self
+    }
+
+    /// Commit `actions` to `table` as part of the transaction.
+    ///
+    /// Tables are committed in the order they were added.
+    pub fn with_table(
+        mut self,
+        table: &'a DeltaTable,
+        actions: Vec<Action>,
+        operation: DeltaOperation,
+        properties: CommitProperties,
+    ) -> Self {
+        self.commits.push(TableCommit {
+            table,
+            actions,
+            operation,
+            properties,
+        });
+        self
+    }
+
+    /// Id of the transaction
+    pub fn txn_id(&self) -> &str {
+        &self.txn_id
+    }
+
+    /// Prepare the commits to all tables, then finalize them.
+    ///
+    /// Fails with [`TransactionError::TableFeaturesRequired`] before anything is written if a
+    /// table does not support the `domainMetadata` writer feature.
+    ///
+    /// If finalizing fails after some tables were committed, the error is
+    /// [`TransactionError::MultiTableCommitIncomplete`] and the transaction is not complete.
+    pub async fn commit(self) -> DeltaResult<Vec<FinalizedCommit>> {
+        let marker = MultiTableTransaction {
+            txn_id: self.txn_id.clone(),
+            tables: self
+                .commits
+                .iter()
+                .map(|commit| commit.table.log_store().root_url().to_string())
+                .collect(),
+        };
+        let configuration = serde_json::to_string(&marker)
+            .map_err(|json_err| DeltaTableError::SerializeLogJson { json_err })?;
+
+        // Phase 1: validate and serialize all commits before writing any of them
+        let mut prepared: Vec<PreparedCommit<'a>> = Vec::with_capacity(self.commits.len());
+        for commit in self.commits {
+            let table: &'a DeltaTable = commit.table;
+            let result = match table.snapshot() {
+                Ok(snapshot) if !supports_domain_metadata(snapshot) => Err(
+                    TransactionError::TableFeaturesRequired(TableFeature::DomainMetadata).into(),
+                ),
+                Ok(snapshot) => {
+                    let mut actions = commit.actions;
+                    actions.push(Action::DomainMetadata(DomainMetadata {
+                        domain: MULTI_TABLE_TRANSACTION_DOMAIN.to_string(),
+                        configuration: configuration.clone(),
+                        removed: false,
+                    }));
+                    CommitBuilder::from(commit.properties)
+                        .with_actions(actions)
+                        .build(Some(snapshot), table.log_store(), commit.operation)
+                        .into_prepared_commit_future()
+                        .await
+                }
+                Err(err) => Err(err),
+            };
+            match result {
+                Ok(commit) => prepared.push(commit),
+                Err(err) => {
+                    abort_all(prepared).await;
+                    return Err(err);
+                }
+            }
+        }
+
+        // Phase 2: finalize the commits in order
+        let total = prepared.len();
+        let mut finalized = Vec::with_capacity(total);
+        for commit in prepared {
+            let result = async { commit.await?.await }.await;
+            match result {
+                Ok(commit) => finalized.push(commit),
+                Err(source) if finalized.is_empty() => return Err(source),
+                Err(source) => {
+                    return Err(TransactionError::MultiTableCommitIncomplete {
+                        txn_id: self.txn_id,
+                        committed: finalized.len(),
+                        total,
+                        source: Box::new(source),
+                    }
+                    .into());
+                }
+            }
+        }
+        Ok(finalized)
+    }
+}
+
+/// Whether domain metadata, which records the transaction, may be committed to the table
+fn supports_domain_metadata(snapshot: &DeltaTableState) -> bool {
+    snapshot
+        .protocol()
+        .writer_features()
+        .is_some_and(|features| features.contains(&TableFeature::DomainMetadata))
+}
+
+/// Remove temporary commit files of prepared but never finalized commits
+async fn abort_all(prepared: Vec<PreparedCommit<'_>>) {
+    for commit in prepared {
+        let version = commit
+            .table_data
+            .map(|table| table.eager_snapshot().version() + 1)
+            .unwrap_or_default();
+        if let Err(err) = commit
+            .log_store
+            .abort_commit_entry(version, commit.commit_or_bytes, commit.operation_id)
+            .await
+        {
+            tracing::warn!(error = %err, "failed to abort prepared commit");
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use url::Url;
+
+    use super::*;
+    use crate::DeltaTableBuilder;
+    use crate::kernel::TableFeatures;
+    use crate::writer::test_utils::get_delta_schema;
+
+    async fn create_table(location: &str) -> DeltaResult<DeltaTable> {
+        DeltaTableBuilder::from_url(Url::parse(location).unwrap())?
+            .build()?
+            .create()
+            .with_columns(get_delta_schema().fields().cloned())
+            .await?
+            .add_feature()
+            .with_feature(TableFeatures::DomainMetadata)
+            .with_allow_protocol_versions_increase(true)
+            .await
+    }
+
+    #[tokio::test]
+    async fn test_multi_table_commit_requires_domain_metadata() -> DeltaResult<()> {
+        let bronze = create_table("memory:///bronze").await?;
+        let legacy = DeltaTableBuilder::from_url(Url::parse("memory:///legacy").unwrap())?
+            .build()?
+            .create()
+            .with_columns(get_delta_schema().fields().cloned())
+            .await?;
+
+        let result = MultiTableCommit::new()
+            .with_table(
+                &bronze,
+                vec![],
+                DeltaOperation::custom("PIPELINE"),
+                CommitProperties::default(),
+            )
+            .with_table(
+                &legacy,
+                vec![],
+                DeltaOperation::custom("PIPELINE"),
+                CommitProperties::default(),
+            )
+            .commit()
+            .await;
+        assert!(matches!(
+            result,
+            Err(DeltaTableError::Transaction {
+                source: TransactionError::TableFeaturesRequired(TableFeature::DomainMetadata)
+            })
+        ));
+
+        // nothing was committed to the supported table either
+        let mut bronze = bronze;
+        bronze.update_state().await?;
+        assert_eq!(bronze.version(), Some(1));
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_multi_table_commit() -> DeltaResult<()> {
+        let bronze = create_table("memory:///bronze").await?;
+        let silver = create_table("memory:///silver").await?;
+
+        let commits = MultiTableCommit::new()
+            .with_txn_id("run-1")
+            .with_table(
+                &bronze,
+                vec![],
+                DeltaOperation::custom("PIPELINE"),
+                CommitProperties::default(),
+            )
+            .with_table(
+                &silver,
+                vec![],
+                DeltaOperation::custom("PIPELINE"),
+                CommitProperties::default(),
+            )
+            .commit()
+            .await?;
+        assert_eq!(
+            commits.iter().map(|c| c.version()).collect::<Vec<_>>(),
+            vec![2, 2]
+        );
+
+        let tables: Vec<DeltaTable> = commits
+            .into_iter()
+            .zip([&bronze, &silver])
+            .map(|(commit, table)| DeltaTable::new_with_state(table.log_store(), commit.snapshot))
+            .collect();
+        let txn = MultiTableTransaction::latest(&tables[0]).await?.unwrap();
+        assert_eq!(txn.txn_id, "run-1");
+        assert_eq!(txn.tables.len(), 2);
+        assert!(txn.is_complete(&tables).await?);
+
+        // simulate a transaction which only reached the first table
+        let partial = MultiTableTransaction {
+            txn_id: "run-2".to_string(),
+            tables: txn.tables.clone(),
+        };
+        let bronze = CommitBuilder::default()
+            .with_actions(vec![Action::DomainMetadata(DomainMetadata {
+                domain: MULTI_TABLE_TRANSACTION_DOMAIN.to_string(),
+                configuration: serde_json::to_string(&partial).unwrap(),
+                removed: false,
+            })])
+            .build(
+                Some(tables[0].snapshot()?),
+                tables[0].log_store(),
+                DeltaOperation::custom("PIPELINE"),
+            )
+            .await?;
+        let tables = vec![
+            DeltaTable::new_with_state(tables[0].log_store(), bronze.snapshot),
+            tables[1].clone(),
+        ];
+        let latest = MultiTableTransaction::latest(&tables[0]).await?.unwrap();
+        assert_eq!(latest, partial);
+        assert!(!latest.is_complete(&tables).await?);
+        assert!(latest.is_complete(&tables[..1]).await.is_err());
+        Ok(())
+    }
+}
@@ -0,0 +1,198 @@
+//! Retry policy for commits which lost the race for a table version.
+
+use std::time::Duration;
+
+use rand::RngExt as _;
+
+use super::DEFAULT_RETRIES;
+
+/// Default upper bound for the delay between two commit attempts
+pub const DEFAULT_MAX_BACKOFF: Duration = Duration::from_secs(10);
+
+/// How long to wait before retrying a commit
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum BackoffStrategy {
+    /// Retry immediately
+    #[default]
+    None,
+    /// Wait the same amount of time before every retry
+    Fixed(Duration),
+    /// Double the delay after every attempt, starting at `initial` and capped at `max`
+    Exponential {
+        /// Delay before the first retry
+        initial: Duration,
+        /// Upper bound for the delay
+        max: Duration,
+    },
+}
+
+impl BackoffStrategy {
+    /// Exponential backoff starting at `initial`, capped at [`DEFAULT_MAX_BACKOFF`]
+    pub fn exponential(initial: Duration) -> Self {
+        Self::Exponential {
+            initial,
+            max: DEFAULT_MAX_BACKOFF.max(initial),
+        }
+    }
+
+    /// The delay before the given retry, starting at 1
+    fn delay(&self, retry: u32) -> Duration {
+        match self {
+            Self::None => Duration::ZERO,
+            Self::Fixed(delay) => *delay,
+            Self::Exponential { initial, max } => initial
+                .checked_mul(2u32.saturating_pow(retry.saturating_sub(1)))
+                .map_or(*max, |delay| delay.min(*max)),
+        }
+    }
+}
+
+/// Randomization applied to backoff delays, so concurrent writers do not retry in lockstep
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Jitter {
+    /// Use the delay as is
+    #[default]
+    None,
+    /// Wait a random duration between zero and the delay
+    Full,
+    /// Wait half the delay plus a random duration up to the other half
+    Equal,
+}
+
+impl Jitter {
+    fn apply(&self, delay: Duration) -> Duration {
+        let millis = delay.as_millis() as u64;
+        if millis == 0 {
+            return delay;
+        }
+        match self {
+            Self::None => delay,
+            Self::Full => Duration::from_millis(rand::rng().random_range(0..=millis)),
+            Self::Equal => {
+                let half = millis / 2;
+                Duration::from_millis(half + rand::rng().random_range(0..=millis - half))
+            }
+        }
+    }
+}
+
+/// Controls how often and how fast a commit is retried when concurrent writers committed first.
+///
+/// The default retries up to 15 times without waiting in between.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RetryPolicy {
+    max_retries: usize,
+    backoff: BackoffStrategy,
+    jitter: Jitter,
+    deadline: Option<Duration>,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_retries: DEFAULT_RETRIES,
+            backoff: BackoffStrategy::None,
+            jitter: Jitter::None,
+            deadline: None,
+        }
+    }
+}
+
+impl RetryPolicy {
+    /// Create a policy which retries up to `max_retries` times without waiting
+    pub fn new(max_retries: usize) -> Self {
+        Self {
+            max_retries,
+            ..Default::default()
+        }
+    }
+
+    /// Maximum number of times to retry the commit
+    pub fn with_max_retries(mut self, max_retries: usize) -> Self {
+        self.max_retries = max_retries;
+        self
+    }
+
+    /// How long to wait between attempts
+    pub fn with_backoff(mut self, backoff: BackoffStrategy) -> Self {
+        self.backoff = backoff;
+        self
+    }
+
+    /// Randomization applied to the backoff delay
+    pub fn with_jitter(mut self, jitter: Jitter) -> Self {
+        self.jitter = jitter;
+        self
+    }
+
+    /// Give up once the commit did not succeed within this time, regardless of remaining retries
+    pub fn with_deadline(mut self, deadline: Duration) -> Self {
+        self.deadline = Some(deadline);
+        self
+    }
+
+    /// Maximum number of times to retry the commit
+    pub fn max_retries(&self) -> usize {
+        self.max_retries
+    }
+
+    /// Strategy used to compute the delay between attempts
+    pub fn backoff(&self) -> BackoffStrategy {
+        self.backoff
+    }
+
+    /// Randomization applied to the backoff delay
+    pub fn jitter(&self) -> Jitter {
+        self.jitter
+    }
+
+    /// Overall time limit for the commit, if any
+    pub fn deadline(&self) -> Option<Duration> {
+        self.deadline
+    }
+
+    /// The delay before the given retry, starting at 1
+    pub(crate) fn delay(&self, retry: usize) -> Duration {
+        let retry = u32::try_from(retry).unwrap_or(u32::MAX);
+        self.jitter.apply(self.backoff.delay(retry))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_backoff_delays() {
+        let policy = RetryPolicy::new(5).with_backoff(BackoffStrategy::Exponential {
+            initial: Duration::from_millis(100),
+            max: Duration::from_millis(500),
+        });
+        let delays: Vec<_> = (1..=5).map(|retry| policy.delay(retry)).collect();
+        assert_eq!(
+            delays,
+            [100, 200, 400, 500, 500]
+                .map(Duration::from_millis)
+                .to_vec()
+        );
+        assert_eq!(policy.delay(usize::MAX), Duration::from_millis(500));
+
+        let policy = RetryPolicy::default();
+        assert_eq!(policy.max_retries(), DEFAULT_RETRIES);
+        assert_eq!(policy.delay(3), Duration::ZERO);
+
+        let policy = policy.with_backoff(BackoffStrategy::Fixed(Duration::from_millis(20)));
+        assert_eq!(policy.delay(7), Duration::from_millis(20));
+    }
+
+    #[test]
+    fn test_jitter_bounds() {
+        let delay = Duration::from_millis(1000);
+        for _ in 0..100 {
+            assert!(Jitter::Full.apply(delay) <= delay);
+            let equal = Jitter::Equal.apply(delay);
+            assert!(equal >= delay / 2 && equal <= delay);
+        }
+        assert_eq!(Jitter::Full.apply(Duration::ZERO), Duration::ZERO);
+    }
+}
@@ -83,6 +83,8 @@ pub mod errors;
 pub mod kernel;
 pub mod logstore;
 pub mod operations;
+#[cfg(feature = "parquet-encryption")]
+pub mod parquet_encryption;
 pub(crate) mod parquet_utils;
 pub mod protocol;
 pub use kernel::schema;
@@ -181,6 +183,17 @@ pub async fn open_table_with_ds(
     Ok(table)
 }
 
+/// Creates and loads a DeltaTable registered in a data catalog, e.g.
+/// `open_catalog("unity", "prod.analytics.events")`.
+///
+/// See [`DeltaTableBuilder::from_catalog`] for the supported catalogs.
+pub async fn open_catalog(catalog: &str, table_name: &str) -> Result<DeltaTable, DeltaTableError> {
+    let table = DeltaTableBuilder::from_catalog(catalog, table_name)?
+        .load()
+        .await?;
+    Ok(table)
+}
+
 static CLIENT_VERSION: OnceLock<String> = OnceLock::new();
 
 /// Record the client version reported by `crate_version`.
@@ -11,8 +11,18 @@ use ::object_store::RetryConfig;
 use object_store::{ObjectStore, path::Path, prefix::PrefixStore};
 use std::collections::HashMap;
 
-use super::storage::{CertificateConfig, LimitConfig};
+use super::coordinated::{COMMIT_COORDINATOR_KEY, CommitCoordinatorRef, commit_coordinator};
+use super::lock::{LOCK_PROVIDER_KEY, LockConfig, LockProviderRef, lock_provider};
+#[cfg(feature = "delta-cache")]
+use super::storage::CacheConfig;
+use super::storage::credentials::credential_provider;
+use super::storage::{
+    CREDENTIAL_PROVIDER_KEY, CertificateConfig, CoalesceConfig, FallbackConfig, LimitConfig,
+    MultipartConfig, ProxyConfig, StorageCredentialProviderRef, StorageMetricsRecorderRef,
+    ThrottleConfig, client_options,
+};
 use super::{IORuntime, storage::runtime::RuntimeConfig};
+use crate::kernel::transaction::{CommitHookRef, MetricsHandlerRef};
 use crate::{DeltaResult, DeltaTableError};
 
 /// A configuration type that can be incrementally populated from string key/value pairs.
@@ -120,6 +130,72 @@ pub struct StorageConfig {
     /// Configuration for custom TLS root certificates.
     pub certificate: Option<CertificateConfig>,
 
+    /// Proxy configuration.
+    ///
+    /// Configuration for an HTTP(S) proxy used by the object store client.
+    pub proxy: Option<ProxyConfig>,
+
+    /// Throttle configuration.
+    ///
+    /// Configuration to limit the rate and concurrency of reads, writes and deletes.
+    pub throttle: Option<ThrottleConfig>,
+
+    /// Coalesce configuration.
+    ///
+    /// Configuration to merge nearby ranged reads of the same object into a single request.
+    pub coalesce: Option<CoalesceConfig>,
+
+    /// Fallback configuration.
+    ///
+    /// Secondary location data files missing from the table location are read from.
+    pub fallback: Option<FallbackConfig>,
+
+    /// Multipart configuration.
+    ///
+    /// Configuration of the part size, concurrency and threshold of multipart uploads.
+    pub multipart: Option<MultipartConfig>,
+
+    /// Cache configuration.
+    ///
+    /// Configuration of the local cache for log and Parquet footer reads.
+    #[cfg(feature = "delta-cache")]
+    pub cache: Option<CacheConfig>,
+
+    /// Credential provider.
+    ///
+    /// Callback used to obtain fresh credentials before the current ones expire.
+    pub credential_provider: Option<StorageCredentialProviderRef>,
+
+    /// Metrics recorder.
+    ///
+    /// Receives every request sent to the object store and every commit attempt.
+    pub metrics: Option<StorageMetricsRecorderRef>,
+
+    /// Lock provider.
+    ///
+    /// Distributed lock serializing commits on storage without atomic put-if-absent.
+    pub lock_provider: Option<LockProviderRef>,
+
+    /// Lock configuration.
+    ///
+    /// Lease and timeout of the commit lock taken from the lock provider.
+    pub lock: Option<LockConfig>,
+
+    /// Commit coordinator.
+    ///
+    /// External authority ratifying the commits to the table.
+    pub commit_coordinator: Option<CommitCoordinatorRef>,
+
+    /// Commit hooks.
+    ///
+    /// Invoked with the actions of every commit before it is written and after it succeeded.
+    pub commit_hooks: Vec<CommitHookRef>,
+
+    /// Metrics handler.
+    ///
+    /// Receives the metrics of every operation committed to the table.
+    pub metrics_handler: Option<MetricsHandlerRef>,
+
     /// Properties that are not recognized by the storage configuration.
     ///
     /// These properties are ignored by the storage configuration and can be used for custom purposes.
@@ -185,8 +261,33 @@ where
         let result = ParseResult::<CertificateConfig>::from_iter(result.unparsed);
         config.certificate = (!result.is_default).then_some(result.config);
 
+        let result = ParseResult::<ProxyConfig>::from_iter(result.unparsed);
+        config.proxy = (!result.is_default).then_some(result.config);
+
+        let result = ParseResult::<ThrottleConfig>::from_iter(result.unparsed);
+        config.throttle = (!result.is_default).then_some(result.config);
+
+        let result = ParseResult::<CoalesceConfig>::from_iter(result.unparsed);
+        config.coalesce = (!result.is_default).then_some(result.config);
+
+        let result = ParseResult::<FallbackConfig>::from_iter(result.unparsed);
+        config.fallback = (!result.is_default).then_some(result.config);
+
+        let result = ParseResult::<MultipartConfig>::from_iter(result.unparsed);
+        config.multipart = (!result.is_default).then_some(result.config);
+
+        let result = ParseResult::<LockConfig>::from_iter(result.unparsed);
+        config.lock = (!result.is_default).then_some(result.config);
+
         let remainder = result.unparsed;
 
+        #[cfg(feature = "delta-cache")]
+        let remainder = {
+            let result = ParseResult::<CacheConfig>::from_iter(remainder);
+            config.cache = (!result.is_default).then_some(result.config);
+            result.unparsed
+        };
+
         #[cfg(feature = "cloud")]
         let remainder = {
             let result = ParseResult::<RetryConfig>::from_iter(remainder);
@@ -194,6 +295,25 @@ where
             result.unparsed
         };
 
+        let mut remainder = remainder;
+        if let Some(name) = remainder.remove(CREDENTIAL_PROVIDER_KEY) {
+            config.credential_provider = credential_provider(&name);
+            if config.credential_provider.is_none() {
+                tracing::warn!("No credential provider registered with name '{name}'");
+            }
+        }
+        if let Some(name) = remainder.remove(LOCK_PROVIDER_KEY) {
+            config.lock_provider = lock_provider(&name);
+            if config.lock_provider.is_none() {
+                tracing::warn!("No lock provider registered with name '{name}'");
+            }
+        }
+        if let Some(name) = remainder.remove(COMMIT_COORDINATOR_KEY) {
+            // an unknown coordinator fails the creation of the log store, commits must never
+            // bypass it
+            config.commit_coordinator = commit_coordinator(&name);
+        }
+
         config.unknown_properties = remainder;
         config
     }
@@ -241,8 +361,40 @@ impl StorageConfig {
         let result = ParseResult::<CertificateConfig>::from_iter(result.unparsed);
         result.raise_errors()?;
         props.certificate = (!result.is_default).then_some(result.config);
+
+        let result = ParseResult::<ProxyConfig>::from_iter(result.unparsed);
+        result.raise_errors()?;
+        props.proxy = (!result.is_default).then_some(result.config);
+
+        let result = ParseResult::<ThrottleConfig>::from_iter(result.unparsed);
+        result.raise_errors()?;
+        props.throttle = (!result.is_default).then_some(result.config);
+
+        let result = ParseResult::<CoalesceConfig>::from_iter(result.unparsed);
+        result.raise_errors()?;
+        props.coalesce = (!result.is_default).then_some(result.config);
+
+        let result = ParseResult::<FallbackConfig>::from_iter(result.unparsed);
+        result.raise_errors()?;
+        props.fallback = (!result.is_default).then_some(result.config);
+
+        let result = ParseResult::<MultipartConfig>::from_iter(result.unparsed);
+        result.raise_errors()?;
+        props.multipart = (!result.is_default).then_some(result.config);
+
+        let result = ParseResult::<LockConfig>::from_iter(result.unparsed);
+        result.raise_errors()?;
+        props.lock = (!result.is_default).then_some(result.config);
         let remainder = result.unparsed;
 
+        #[cfg(feature = "delta-cache")]
+        let remainder = {
+            let result = ParseResult::<CacheConfig>::from_iter(remainder);
+            result.raise_errors()?;
+            props.cache = (!result.is_default).then_some(result.config);
+            result.unparsed
+        };
+
         #[cfg(feature = "cloud")]
         let remainder = {
             let (retry, remainder): (RetryConfig, _) = try_parse_impl(remainder)?;
@@ -250,15 +402,120 @@ impl StorageConfig {
             remainder
         };
 
+        let mut remainder = remainder;
+        if let Some(name) = remainder.remove(CREDENTIAL_PROVIDER_KEY) {
+            props.credential_provider = Some(credential_provider(&name).ok_or_else(|| {
+                DeltaTableError::Generic(format!(
+                    "No credential provider registered with name '{name}'"
+                ))
+            })?);
+        }
+        if let Some(name) = remainder.remove(LOCK_PROVIDER_KEY) {
+            props.lock_provider = Some(lock_provider(&name).ok_or_else(|| {
+                DeltaTableError::Generic(format!("No lock provider registered with name '{name}'"))
+            })?);
+        }
+        if let Some(name) = remainder.remove(COMMIT_COORDINATOR_KEY) {
+            props.commit_coordinator = Some(commit_coordinator(&name).ok_or_else(|| {
+                DeltaTableError::Generic(format!(
+                    "No commit coordinator registered with name '{name}'"
+                ))
+            })?);
+        }
+
         props.unknown_properties = remainder;
         Ok(props)
     }
 
+    /// Options scoped to a specific URL `scheme`.
+    ///
+    /// Keys of the form `<scheme>.<key>` or `<scheme>_<key>` (case-insensitive scheme) are
+    /// returned with the prefix stripped. This allows custom
+    /// [`ObjectStoreFactory`](super::ObjectStoreFactory) implementations to receive their own
+    /// configuration through the regular `storage_options`.
+    ///
+    /// ```
+    /// use deltalake_core::logstore::StorageConfig;
+    /// let config = StorageConfig::parse_options([("ceph.endpoint", "http://ceph:7480")]).unwrap();
+    /// assert_eq!(
+    ///     config.scheme_options("ceph").get("endpoint").map(String::as_str),
+    ///     Some("http://ceph:7480")
+    /// );
+    /// ```
+    pub fn scheme_options(&self, scheme: &str) -> HashMap<String, String> {
+        self.raw
+            .iter()
+            .filter_map(|(key, value)| {
+                let (prefix, rest) = key.split_at_checked(scheme.len())?;
+                if !prefix.eq_ignore_ascii_case(scheme) {
+                    return None;
+                }
+                let rest = rest.strip_prefix('.').or_else(|| rest.strip_prefix('_'))?;
+                (!rest.is_empty()).then(|| (rest.to_string(), value.clone()))
+            })
+            .collect()
+    }
+
+    /// Configuration of multipart uploads, falling back on the defaults if none was given.
+    pub fn multipart_config(&self) -> MultipartConfig {
+        self.multipart.clone().unwrap_or_default()
+    }
+
+    /// HTTP client options for the configured root certificates and proxy, if any.
+    ///
+    /// Backends apply these before their own options, so that keys understood by the object
+    /// store crate itself (e.g. `proxy_url`) are still honoured when passed verbatim.
+    pub fn client_options(&self) -> DeltaResult<Option<object_store::ClientOptions>> {
+        client_options(self.certificate.as_ref(), self.proxy.as_ref())
+    }
+
     /// Attach a dedicated IO [`IORuntime`] used to execute storage operations.
     pub fn with_io_runtime(mut self, rt: IORuntime) -> Self {
         self.runtime = Some(rt);
         self
     }
+
+    /// Attach a [`StorageCredentialProvider`](super::StorageCredentialProvider) used to refresh
+    /// credentials before they expire.
+    pub fn with_credential_provider(mut self, provider: StorageCredentialProviderRef) -> Self {
+        self.credential_provider = Some(provider);
+        self
+    }
+
+    /// Attach a [`StorageMetricsRecorder`](super::StorageMetricsRecorder) receiving the object
+    /// store requests and commit attempts of the table.
+    pub fn with_metrics_recorder(mut self, recorder: StorageMetricsRecorderRef) -> Self {
+        self.metrics = Some(recorder);
+        self
+    }
+
+    /// Attach a [`LockProvider`](super::LockProvider) serializing commits to the table, for
+    /// storage which lacks atomic put-if-absent.
+    pub fn with_lock_provider(mut self, provider: LockProviderRef) -> Self {
+        self.lock_provider = Some(provider);
+        self
+    }
+
+    /// Route the commits to the table through a
+    /// [`CommitCoordinator`](super::CommitCoordinator).
+    pub fn with_commit_coordinator(mut self, coordinator: CommitCoordinatorRef) -> Self {
+        self.commit_coordinator = Some(coordinator);
+        self
+    }
+
+    /// Attach a [`CommitHook`](crate::kernel::transaction::CommitHook) invoked around every
+    /// commit to the table. Hooks run in the order they were added.
+    pub fn with_commit_hook(mut self, hook: CommitHookRef) -> Self {
+        self.commit_hooks.push(hook);
+        self
+    }
+
+    /// Attach a [`MetricsHandler`](crate::kernel::transaction::MetricsHandler) receiving the
+    /// metrics of every operation committed to the table.
+    pub fn with_metrics_handler(mut self, handler: MetricsHandlerRef) -> Self {
+        self.metrics_handler = Some(handler);
+        self
+    }
 }
 
 pub(super) fn try_parse_impl<T, K, V, I>(options: I) -> DeltaResult<(T, HashMap<String, String>)>
@@ -299,8 +556,15 @@ pub fn parse_f64(value: &str) -> DeltaResult<f64> {
         .map_err(|_| DeltaTableError::Generic(format!("failed to parse \"{value}\" as f64")))
 }
 
+/// Parse a human readable duration (e.g. `30s`, `5m`, `1h`).
+///
+/// ```
+/// use deltalake_core::logstore::config::parse_duration;
+/// assert_eq!(parse_duration("1m").unwrap().as_secs(), 60);
+/// assert!(parse_duration("not_a_duration").is_err());
+/// ```
 #[cfg(feature = "cloud")]
-pub(crate) fn parse_duration(value: &str) -> DeltaResult<std::time::Duration> {
+pub fn parse_duration(value: &str) -> DeltaResult<std::time::Duration> {
     humantime::parse_duration(value)
         .map_err(|_| DeltaTableError::Generic(format!("failed to parse \"{value}\" as Duration")))
 }
@@ -0,0 +1,556 @@
+//! Coordinated (catalog-managed) commits.
+//!
+//! With coordinated commits, the authority on which commit owns a given version is an external
+//! [`CommitCoordinator`] (e.g. a catalog service) rather than the atomicity guarantees of the
+//! underlying object store. Writers first stage the commit under `_delta_log/_staged_commits/`,
+//! then ask the coordinator to ratify it. Ratified commits are subsequently *backfilled*, i.e.
+//! copied into their regular `_delta_log/<version>.json` location, so that readers which only
+//! understand file-system based commits can still see them.
+//!
+//! [`CoordinatedLogStore`] wraps this flow behind the regular [`LogStore`] interface. It is used
+//! for tables opened with the [`COMMIT_COORDINATOR_KEY`] storage option naming a coordinator
+//! registered via [`register_commit_coordinator`].
+use std::collections::BTreeMap;
+use std::fmt::Debug;
+use std::sync::{Arc, LazyLock};
+
+use async_trait::async_trait;
+use bytes::Bytes;
+use dashmap::DashMap;
+use object_store::{
+    Error as ObjectStoreError, ObjectStore, ObjectStoreExt as _, PutMode, path::Path,
+};
+use parking_lot::Mutex;
+use tracing::*;
+use url::Url;
+use uuid::Uuid;
+
+use super::storage::{ObjectStoreRef, utils::commit_uri_from_version};
+use super::{CommitOrBytes, LogStore, LogStoreConfig};
+use crate::kernel::Version;
+use crate::kernel::transaction::TransactionError;
+use crate::{DeltaResult, DeltaTableError};
+
+/// Directory (relative to the table root) staged commits are written to.
+pub const STAGED_COMMITS_DIR: &str = "_delta_log/_staged_commits";
+
+/// Storage option used to select a coordinator registered via [`register_commit_coordinator`].
+pub const COMMIT_COORDINATOR_KEY: &str = "commit_coordinator";
+
+/// A commit that has been ratified by a [`CommitCoordinator`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CoordinatedCommit {
+    /// Version of the table this commit produces.
+    pub version: Version,
+    /// Location of the staged commit file, relative to the table root.
+    pub file_path: Path,
+    /// Commit timestamp in milliseconds since the unix epoch.
+    pub commit_timestamp: i64,
+}
+
+/// Response of [`CommitCoordinator::get_commits`].
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct GetCommitsResponse {
+    /// Ratified commits which have not (yet) been backfilled, ordered by version.
+    pub commits: Vec<CoordinatedCommit>,
+    /// The latest version known to the coordinator, if any commits have been ratified.
+    pub latest_table_version: Option<Version>,
+}
+
+/// Client for a commit coordinator implementing the coordinated-commits protocol.
+///
+/// Implementations must guarantee that for each `(table, version)` pair at most one call to
+/// [`commit`](CommitCoordinator::commit) succeeds.
+#[async_trait]
+pub trait CommitCoordinator: Debug + Send + Sync {
+    /// Name of the coordinator, used for diagnostics.
+    fn name(&self) -> String;
+
+    /// Ratify the commit staged at `staged_path` as `version` of the table at `table_url`.
+    ///
+    /// Must fail with [`TransactionError::VersionAlreadyExists`] if another commit already
+    /// owns `version`.
+    async fn commit(
+        &self,
+        table_url: &Url,
+        version: Version,
+        staged_path: &Path,
+        commit_timestamp: i64,
+    ) -> Result<(), TransactionError>;
+
+    /// Return the ratified but not yet backfilled commits in `[start_version, end_version]`.
+    async fn get_commits(
+        &self,
+        table_url: &Url,
+        start_version: Option<Version>,
+        end_version: Option<Version>,
+    ) -> DeltaResult<GetCommitsResponse>;
+
+    /// Notify the coordinator that all commits up to and including `version` have been
+    /// backfilled into the `_delta_log` directory.
+    async fn backfill_to_version(&self, table_url: &Url, version: Version) -> DeltaResult<()>;
+}
+
+/// Sharable reference to a [`CommitCoordinator`]
+pub type CommitCoordinatorRef = Arc<dyn CommitCoordinator>;
+
+static COMMIT_COORDINATORS: LazyLock<DashMap<String, CommitCoordinatorRef>> =
+    LazyLock::new(DashMap::new);
+
+/// Register a named [`CommitCoordinator`].
+///
+/// Tables opened with the storage option [`COMMIT_COORDINATOR_KEY`] set to `name` route their
+/// commits through `coordinator`. Returns the coordinator previously registered under `name`.
+pub fn register_commit_coordinator(
+    name: impl Into<String>,
+    coordinator: CommitCoordinatorRef,
+) -> Option<CommitCoordinatorRef> {
+    COMMIT_COORDINATORS.insert(name.into(), coordinator)
+}
+
+/// Remove the [`CommitCoordinator`] registered under `name`.
+pub fn deregister_commit_coordinator(name: &str) -> Option<CommitCoordinatorRef> {
+    COMMIT_COORDINATORS
+        .remove(name)
+        .map(|(_, coordinator)| coordinator)
+}
+
+pub(crate) fn commit_coordinator(name: &str) -> Option<CommitCoordinatorRef> {
+    COMMIT_COORDINATORS
+        .get(name)
+        .map(|entry| entry.value().clone())
+}
+
+/// A [`LogStore`] which routes commits through a [`CommitCoordinator`].
+///
+/// Commits are backfilled eagerly after they have been ratified, so the regular log listing
+/// stays consistent for readers. Commits that were ratified but never backfilled (e.g. because a
+/// writer crashed) are picked up and backfilled on [`refresh`](LogStore::refresh).
+#[derive(Debug, Clone)]
+pub struct CoordinatedLogStore {
+    prefixed_store: ObjectStoreRef,
+    root_store: ObjectStoreRef,
+    config: LogStoreConfig,
+    coordinator: Arc<dyn CommitCoordinator>,
+}
+
+impl CoordinatedLogStore {
+    /// Create a new instance of [`CoordinatedLogStore`]
+    ///
+    /// # Arguments
+    ///
+    /// * `prefixed_store` - A shared reference to an [`object_store::ObjectStore`] with "/"
+    ///   pointing at delta table root (i.e. where `_delta_log` is located).
+    /// * `root_store` - A shared reference to an [`object_store::ObjectStore`] with "/"
+    ///   pointing at root of the storage system.
+    /// * `config` - Configuration of the log store.
+    /// * `coordinator` - The coordinator responsible for ratifying commits.
+    pub fn new(
+        prefixed_store: ObjectStoreRef,
+        root_store: ObjectStoreRef,
+        config: LogStoreConfig,
+        coordinator: Arc<dyn CommitCoordinator>,
+    ) -> Self {
+        Self {
+            prefixed_store,
+            root_store,
+            config,
+            coordinator,
+        }
+    }
+
+    /// The coordinator used by this log store.
+    pub fn coordinator(&self) -> &Arc<dyn CommitCoordinator> {
+        &self.coordinator
+    }
+
+    fn staged_commit_path(version: Version) -> Path {
+        Path::from(format!(
+            "{STAGED_COMMITS_DIR}/{version:020}.{}.json",
+            Uuid::new_v4()
+        ))
+    }
+
+    /// Copy all ratified commits up to `end_version` into the `_delta_log` directory.
+    async fn backfill(&self, end_version: Option<Version>) -> DeltaResult<Option<Version>> {
+        let response = self
+            .coordinator
+            .get_commits(self.root_url(), None, end_version)
+            .await?;
+        let mut backfilled = None;
+        for commit in response.commits {
+            let bytes = self
+                .prefixed_store
+                .get(&commit.file_path)
+                .await?
+                .bytes()
+                .await?;
+            let target = commit_uri_from_version(Some(commit.version));
+            match self
+                .prefixed_store
+                .put_opts(&target, bytes.clone().into(), PutMode::Create.into())
+                .await
+            {
+                Ok(_) => {}
+                Err(ObjectStoreError::AlreadyExists { .. }) => {
+                    // an earlier backfill of the same commit may have been interrupted, any
+                    // other content means the version is owned by a different commit
+                    let existing = self.prefixed_store.get(&target).await?.bytes().await?;
+                    if existing != bytes {
+                        return Err(DeltaTableError::generic(format!(
+                            "Cannot backfill ratified commit {} to {target}, the file holds a different commit",
+                            commit.version
+                        )));
+                    }
+                }
+                Err(err) => return Err(err.into()),
+            }
+            backfilled = Some(commit.version);
+        }
+        if let Some(version) = backfilled {
+            debug!(version, "backfilled coordinated commits");
+            self.coordinator
+                .backfill_to_version(self.root_url(), version)
+                .await?;
+        }
+        Ok(backfilled)
+    }
+}
+
+#[async_trait]
+impl LogStore for CoordinatedLogStore {
+    fn name(&self) -> String {
+        format!("CoordinatedLogStore({})", self.coordinator.name())
+    }
+
+    async fn refresh(&self) -> DeltaResult<()> {
+        self.backfill(None).await?;
+        Ok(())
+    }
+
+    async fn read_commit_entry(&self, version: Version) -> DeltaResult<Option<Bytes>> {
+        let response = self
+            .coordinator
+            .get_commits(self.root_url(), Some(version), Some(version))
+            .await?;
+        if let Some(commit) = response.commits.iter().find(|c| c.version == version) {
+            let bytes = self
+                .prefixed_store
+                .get(&commit.file_path)
+                .await?
+                .bytes()
+                .await?;
+            return Ok(Some(bytes));
+        }
+        super::read_commit_entry(self.prefixed_store.as_ref(), version).await
+    }
+
+    async fn write_commit_entry(
+        &self,
+        version: Version,
+        commit_or_bytes: CommitOrBytes,
+        _: Uuid,
+    ) -> Result<(), TransactionError> {
+        let CommitOrBytes::LogBytes(log_bytes) = commit_or_bytes else {
+            unreachable!() // coordinated commits always stage the commit bytes themselves
+        };
+        let staged_path = Self::staged_commit_path(version);
+        self.prefixed_store
+            .put(&staged_path, log_bytes.into())
+            .await?;
+
+        let commit_timestamp = chrono::Utc::now().timestamp_millis();
+        if let Err(err) = self
+            .coordinator
+            .commit(self.root_url(), version, &staged_path, commit_timestamp)
+            .await
+        {
+            // the staged file is never referenced, so it is safe to remove it again.
+            if let Err(delete_err) = self.prefixed_store.delete(&staged_path).await {
+                warn!(error = %delete_err, "failed to clean up staged commit");
+            }
+            return Err(err);
+        }
+
+        // the commit is durable at this point - a failed backfill will be retried on refresh.
+        if let Err(err) = self.backfill(Some(version)).await {
+            warn!(error = %err, version, "failed to backfill coordinated commit");
+        }
+        Ok(())
+    }
+
+    async fn abort_commit_entry(
+        &self,
+        _version: Version,
+        commit_or_bytes: CommitOrBytes,
+        _: Uuid,
+    ) -> Result<(), TransactionError> {
+        match &commit_or_bytes {
+            CommitOrBytes::LogBytes(_) => Ok(()),
+            _ => unreachable!(), // coordinated commits always stage the commit bytes themselves
+        }
+    }
+
+    async fn get_latest_version(&self, current_version: Version) -> DeltaResult<Version> {
+        let response = self
+            .coordinator
+            .get_commits(self.root_url(), Some(current_version), None)
+            .await?;
+        match super::get_latest_version(self, current_version).await {
+            Ok(version) => Ok(version.max(response.latest_table_version.unwrap_or(version))),
+            Err(err) => response.latest_table_version.ok_or(err),
+        }
+    }
+
+    fn object_store(&self, _: Option<Uuid>) -> Arc<dyn ObjectStore> {
+        self.prefixed_store.clone()
+    }
+
+    fn root_object_store(&self, _: Option<Uuid>) -> Arc<dyn ObjectStore> {
+        self.root_store.clone()
+    }
+
+    fn config(&self) -> &LogStoreConfig {
+        &self.config
+    }
+}
+
+/// A process-local [`CommitCoordinator`].
+///
+/// Useful for tests and for embedding applications which coordinate all writers within a single
+/// process.
+#[derive(Debug, Default)]
+pub struct InMemoryCommitCoordinator {
+    tables: Mutex<std::collections::HashMap<Url, TableCommits>>,
+}
+
+#[derive(Debug, Default)]
+struct TableCommits {
+    latest_version: Option<Version>,
+    unbackfilled: BTreeMap<Version, CoordinatedCommit>,
+}
+
+#[async_trait]
+impl CommitCoordinator for InMemoryCommitCoordinator {
+    fn name(&self) -> String {
+        "InMemoryCommitCoordinator".into()
+    }
+
+    async fn commit(
+        &self,
+        table_url: &Url,
+        version: Version,
+        staged_path: &Path,
+        commit_timestamp: i64,
+    ) -> Result<(), TransactionError> {
+        let mut tables = self.tables.lock();
+        let table = tables.entry(table_url.clone()).or_default();
+        // the first ratified commit may follow any number of file-system based commits
+        match table.latest_version {
+            Some(latest) if version <= latest => {
+                return Err(TransactionError::VersionAlreadyExists(version));
+            }
+            Some(latest) if version != latest + 1 => {
+                let msg = format!(
+                    "Cannot ratify version {version}, the latest ratified version is {latest}"
+                );
+                return Err(TransactionError::LogStoreError {
+                    source: Box::new(DeltaTableError::generic(&msg)),
+                    msg,
+                });
+            }
+            _ => {}
+        }
+        table.latest_version = Some(version);
+        table.unbackfilled.insert(
+            version,
+            CoordinatedCommit {
+                version,
+                file_path: staged_path.clone(),
+                commit_timestamp,
+            },
+        );
+        Ok(())
+    }
+
+    async fn get_commits(
+        &self,
+        table_url: &Url,
+        start_version: Option<Version>,
+        end_version: Option<Version>,
+    ) -> DeltaResult<GetCommitsResponse> {
+        let tables = self.tables.lock();
+        let Some(table) = tables.get(table_url) else {
+            return Ok(GetCommitsResponse::default());
+        };
+        let commits = table
+            .unbackfilled
+            .range(start_version.unwrap_or(0)..=end_version.unwrap_or(Version::MAX))
+            .map(|(_, commit)| commit.clone())
+            .collect();
+        Ok(GetCommitsResponse {
+            commits,
+            latest_table_version: table.latest_version,
+        })
+    }
+
+    async fn backfill_to_version(&self, table_url: &Url, version: Version) -> DeltaResult<()> {
+        let mut tables = self.tables.lock();
+        let table = tables.get_mut(table_url).ok_or_else(|| {
+            DeltaTableError::generic(format!("no coordinated commits for {table_url}"))
+        })?;
+        table.unbackfilled.retain(|v, _| *v > version);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::logstore::{StorageConfig, logstore_for};
+
+    fn coordinated_store() -> (CoordinatedLogStore, Arc<InMemoryCommitCoordinator>) {
+        let location = Url::parse("memory:///table").unwrap();
+        let base = logstore_for(&location, StorageConfig::default()).unwrap();
+        let coordinator = Arc::new(InMemoryCommitCoordinator::default());
+        let store = CoordinatedLogStore::new(
+            base.object_store(None),
+            base.root_object_store(None),
+            base.config().clone(),
+            coordinator.clone(),
+        );
+        (store, coordinator)
+    }
+
+    #[tokio::test]
+    async fn test_commit_is_ratified_and_backfilled() {
+        let (store, coordinator) = coordinated_store();
+        let bytes = Bytes::from_static(b"{\"commitInfo\":{}}\n");
+        store
+            .write_commit_entry(0, CommitOrBytes::LogBytes(bytes.clone()), Uuid::new_v4())
+            .await
+            .unwrap();
+
+        assert_eq!(store.read_commit_entry(0).await.unwrap(), Some(bytes));
+        let response = coordinator
+            .get_commits(store.root_url(), None, None)
+            .await
+            .unwrap();
+        assert!(response.commits.is_empty());
+        assert_eq!(response.latest_table_version, Some(0));
+        assert!(
+            store
+                .object_store(None)
+                .head(&commit_uri_from_version(Some(0)))
+                .await
+                .is_ok()
+        );
+    }
+
+    #[tokio::test]
+    async fn test_conflicting_commit_is_rejected() {
+        let (store, _) = coordinated_store();
+        let bytes = Bytes::from_static(b"{\"commitInfo\":{}}\n");
+        store
+            .write_commit_entry(0, CommitOrBytes::LogBytes(bytes.clone()), Uuid::new_v4())
+            .await
+            .unwrap();
+        let result = store
+            .write_commit_entry(0, CommitOrBytes::LogBytes(bytes), Uuid::new_v4())
+            .await;
+        assert!(matches!(
+            result,
+            Err(TransactionError::VersionAlreadyExists(0))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_refresh_backfills_pending_commits() {
+        let (store, coordinator) = coordinated_store();
+        let staged = CoordinatedLogStore::staged_commit_path(0);
+        store
+            .object_store(None)
+            .put(&staged, Bytes::from_static(b"{\"commitInfo\":{}}\n").into())
+            .await
+            .unwrap();
+        coordinator
+            .commit(store.root_url(), 0, &staged, 0)
+            .await
+            .unwrap();
+        assert!(
+            store
+                .object_store(None)
+                .head(&commit_uri_from_version(Some(0)))
+                .await
+                .is_err()
+        );
+
+        store.refresh().await.unwrap();
+        assert!(
+            store
+                .object_store(None)
+                .head(&commit_uri_from_version(Some(0)))
+                .await
+                .is_ok()
+        );
+        assert_eq!(store.get_latest_version(0).await.unwrap(), 0);
+    }
+
+    #[tokio::test]
+    async fn test_backfill_rejects_foreign_commit() {
+        let (store, coordinator) = coordinated_store();
+        let staged = CoordinatedLogStore::staged_commit_path(0);
+        store
+            .object_store(None)
+            .put(&staged, Bytes::from_static(b"{\"commitInfo\":{}}\n").into())
+            .await
+            .unwrap();
+        coordinator
+            .commit(store.root_url(), 0, &staged, 0)
+            .await
+            .unwrap();
+        // another writer bypassing the coordinator took the version
+        store
+            .object_store(None)
+            .put(
+                &commit_uri_from_version(Some(0)),
+                Bytes::from_static(b"{\"commitInfo\":{\"other\":1}}\n").into(),
+            )
+            .await
+            .unwrap();
+        assert!(store.refresh().await.is_err());
+        let response = coordinator
+            .get_commits(store.root_url(), None, None)
+            .await
+            .unwrap();
+        assert_eq!(response.commits.len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_version_gap_is_rejected() {
+        let coordinator = InMemoryCommitCoordinator::default();
+        let url = Url::parse("memory:///table").unwrap();
+        let path = Path::from("staged.json");
+        coordinator.commit(&url, 3, &path, 0).await.unwrap();
+        assert!(coordinator.commit(&url, 5, &path, 0).await.is_err());
+        coordinator.commit(&url, 4, &path, 0).await.unwrap();
+    }
+
+    #[test]
+    fn test_coordinator_from_storage_options() {
+        register_commit_coordinator(
+            "test-coordinator",
+            Arc::new(InMemoryCommitCoordinator::default()),
+        );
+        let location = Url::parse("memory:///table").unwrap();
+        let config =
+            StorageConfig::parse_options([(COMMIT_COORDINATOR_KEY, "test-coordinator")]).unwrap();
+        let store = logstore_for(&location, config).unwrap();
+        assert!(store.name().starts_with("CoordinatedLogStore"));
+
+        assert!(StorageConfig::parse_options([(COMMIT_COORDINATOR_KEY, "missing")]).is_err());
+        let config: StorageConfig = [(COMMIT_COORDINATOR_KEY, "missing")].into_iter().collect();
+        assert!(logstore_for(&location, config).is_err());
+        deregister_commit_coordinator("test-coordinator");
+    }
+}
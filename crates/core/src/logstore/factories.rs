@@ -4,6 +4,7 @@ use std::{
 };
 
 use dashmap::DashMap;
+use object_store::signer::Signer;
 use object_store::{DynObjectStore, path::Path};
 use url::Url;
 
@@ -28,6 +29,14 @@ pub trait ObjectStoreFactory: Send + Sync {
         url: &Url,
         config: &StorageConfig,
     ) -> DeltaResult<(ObjectStoreRef, Path)>;
+
+    /// Create a [`Signer`] producing pre-signed URLs for objects in the store at `url`.
+    ///
+    /// The signer must be rooted at the same location as the store returned by
+    /// [`parse_url_opts`](Self::parse_url_opts). Backends which cannot sign URLs return `None`.
+    fn signer(&self, _url: &Url, _config: &StorageConfig) -> DeltaResult<Option<Arc<dyn Signer>>> {
+        Ok(None)
+    }
 }
 
 #[derive(Clone, Debug, Default)]
@@ -77,6 +86,55 @@ pub fn object_store_factories() -> ObjectStoreFactoryRegistry {
         .clone()
 }
 
+/// Build the key under which factories for `scheme` are stored in the registries.
+fn scheme_key(scheme: &str) -> DeltaResult<Url> {
+    let scheme = scheme.trim_end_matches("://");
+    Url::parse(&format!("{scheme}://"))
+        .map_err(|_| DeltaTableError::InvalidTableLocation(format!("{scheme}://")))
+}
+
+/// Register an [`ObjectStoreFactory`] for all table locations using the given URL `scheme`.
+///
+/// The `scheme` may be passed with or without the trailing `://`, e.g. `"ceph"` or `"ceph://"`.
+/// Any factory previously registered for the scheme is replaced and returned.
+///
+/// ```rust
+/// # use std::sync::Arc;
+/// # use deltalake_core::logstore::*;
+/// # use deltalake_core::{DeltaResult, Path};
+/// # use url::Url;
+/// struct CephFactory;
+///
+/// impl ObjectStoreFactory for CephFactory {
+///     fn parse_url_opts(
+///         &self,
+///         url: &Url,
+///         config: &StorageConfig,
+///     ) -> DeltaResult<(ObjectStoreRef, Path)> {
+///         let _endpoint = config.scheme_options("ceph").get("endpoint").cloned();
+///         let store = Arc::new(object_store::memory::InMemory::new());
+///         Ok((store, Path::from(url.path())))
+///     }
+/// }
+///
+/// register_object_store_factory("ceph", Arc::new(CephFactory)).unwrap();
+/// ```
+pub fn register_object_store_factory(
+    scheme: &str,
+    factory: Arc<dyn ObjectStoreFactory>,
+) -> DeltaResult<Option<Arc<dyn ObjectStoreFactory>>> {
+    Ok(object_store_factories().insert(scheme_key(scheme)?, factory))
+}
+
+/// Remove the [`ObjectStoreFactory`] registered for `scheme`, returning it if present.
+pub fn deregister_object_store_factory(
+    scheme: &str,
+) -> DeltaResult<Option<Arc<dyn ObjectStoreFactory>>> {
+    Ok(object_store_factories()
+        .remove(&scheme_key(scheme)?)
+        .map(|(_, factory)| factory))
+}
+
 /// Simpler access pattern for the [ObjectStoreFactoryRegistry] to get a single store
 pub fn store_for<K, V, I>(url: &Url, options: I) -> DeltaResult<ObjectStoreRef>
 where
@@ -163,5 +221,177 @@ pub fn logstore_factories() -> LogStoreFactoryRegistry {
         .clone()
 }
 
+/// Register a [`LogStoreFactory`] for all table locations using the given URL `scheme`.
+///
+/// Schemes which only need a custom [`ObjectStoreFactory`] can register the
+/// [`default_logstore`](super::default_logstore) via [`register_default_logstore_factory`].
+/// Any factory previously registered for the scheme is replaced and returned.
+pub fn register_logstore_factory(
+    scheme: &str,
+    factory: Arc<dyn LogStoreFactory>,
+) -> DeltaResult<Option<Arc<dyn LogStoreFactory>>> {
+    Ok(logstore_factories().insert(scheme_key(scheme)?, factory))
+}
+
+/// Register the default [`LogStore`] implementation for `scheme`.
+///
+/// The default log store relies on the object store supporting atomic put-if-absent.
+pub fn register_default_logstore_factory(
+    scheme: &str,
+) -> DeltaResult<Option<Arc<dyn LogStoreFactory>>> {
+    register_logstore_factory(scheme, Arc::new(DefaultLogStoreFactory::default()))
+}
+
+/// Remove the [`LogStoreFactory`] registered for `scheme`, returning it if present.
+pub fn deregister_logstore_factory(scheme: &str) -> DeltaResult<Option<Arc<dyn LogStoreFactory>>> {
+    Ok(logstore_factories()
+        .remove(&scheme_key(scheme)?)
+        .map(|(_, factory)| factory))
+}
+
 #[cfg(test)]
-mod tests {}
+mod tests {
+    use object_store::memory::InMemory;
+
+    use super::*;
+    use crate::logstore::logstore_for;
+
+    #[derive(Default)]
+    struct RecordingFactory {
+        seen: parking_lot::Mutex<Option<HashMap<String, String>>>,
+    }
+
+    impl ObjectStoreFactory for RecordingFactory {
+        fn parse_url_opts(
+            &self,
+            url: &Url,
+            config: &StorageConfig,
+        ) -> DeltaResult<(ObjectStoreRef, Path)> {
+            *self.seen.lock() = Some(config.scheme_options(url.scheme()));
+            Ok((Arc::new(InMemory::new()), Path::from(url.path())))
+        }
+    }
+
+    #[test]
+    fn test_register_custom_scheme() {
+        let location = Url::parse("synthtest://bucket/table").unwrap();
+        assert!(logstore_for(&location, StorageConfig::default()).is_err());
+
+        let factory = Arc::new(RecordingFactory::default());
+        register_object_store_factory("synthtest://", factory.clone()).unwrap();
+        register_default_logstore_factory("synthtest").unwrap();
+
+        let options = StorageConfig::parse_options([
+            ("synthtest.endpoint", "http://localhost:1234"),
+            ("unrelated", "value"),
+        ])
+        .unwrap();
+        let store = logstore_for(&location, options).unwrap();
+        assert_eq!(store.name(), "DefaultLogStore");
+        assert_eq!(
+            factory.seen.lock().clone().unwrap(),
+            HashMap::from([("endpoint".to_string(), "http://localhost:1234".to_string())])
+        );
+
+        assert!(deregister_logstore_factory("synthtest").unwrap().is_some());
+        assert!(
+            deregister_object_store_factory("synthtest")
+                .unwrap()
+                .is_some()
+        );
+        assert!(logstore_for(&location, StorageConfig::default()).is_err());
+    }
+
+    #[derive(Debug)]
+    struct FakeSigner;
+
+    #[async_trait::async_trait]
+    impl Signer for FakeSigner {
+        async fn signed_url(
+            &self,
+            method: http::Method,
+            path: &Path,
+            expires_in: std::time::Duration,
+        ) -> object_store::Result<Url> {
+            Ok(Url::parse(&format!(
+                "https://signed.example.com/{path}?method={method}&expires={}",
+                expires_in.as_secs()
+            ))
+            .unwrap())
+        }
+    }
+
+    struct SigningFactory;
+
+    impl ObjectStoreFactory for SigningFactory {
+        fn parse_url_opts(
+            &self,
+            url: &Url,
+            _config: &StorageConfig,
+        ) -> DeltaResult<(ObjectStoreRef, Path)> {
+            Ok((Arc::new(InMemory::new()), Path::from(url.path())))
+        }
+
+        fn signer(
+            &self,
+            _url: &Url,
+            _config: &StorageConfig,
+        ) -> DeltaResult<Option<Arc<dyn Signer>>> {
+            Ok(Some(Arc::new(FakeSigner)))
+        }
+    }
+
+    #[tokio::test]
+    async fn test_presigned_urls() {
+        let location = Url::parse("signtest://bucket/path/table").unwrap();
+        register_object_store_factory("signtest", Arc::new(SigningFactory)).unwrap();
+        register_default_logstore_factory("signtest").unwrap();
+
+        let store = logstore_for(&location, StorageConfig::default()).unwrap();
+        let urls = store
+            .presigned_urls(
+                &[
+                    "part-00000.parquet".to_string(),
+                    "signtest://bucket/other/part-00001.parquet".to_string(),
+                ],
+                std::time::Duration::from_secs(300),
+            )
+            .await
+            .unwrap();
+        assert_eq!(
+            urls[0].as_str(),
+            "https://signed.example.com/path/table/part-00000.parquet?method=GET&expires=300"
+        );
+        assert_eq!(
+            urls[1].as_str(),
+            "https://signed.example.com/other/part-00001.parquet?method=GET&expires=300"
+        );
+        assert!(
+            store
+                .presigned_urls(
+                    &["signtest://elsewhere/part-00002.parquet".to_string()],
+                    Default::default()
+                )
+                .await
+                .is_err()
+        );
+
+        deregister_logstore_factory("signtest").unwrap();
+        deregister_object_store_factory("signtest").unwrap();
+
+        let location = Url::parse("memory:///table").unwrap();
+        let store = logstore_for(&location, StorageConfig::default()).unwrap();
+        assert!(
+            store
+                .presigned_urls(&["file.parquet".to_string()], Default::default())
+                .await
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn test_register_invalid_scheme() {
+        let factory = Arc::new(RecordingFactory::default());
+        assert!(register_object_store_factory("not a scheme", factory).is_err());
+    }
+}
@@ -0,0 +1,528 @@
+//! Commits serialized through a distributed lock.
+//!
+//! The [`DefaultLogStore`](super::default_logstore::DefaultLogStore) relies on the storage to
+//! atomically create `_delta_log/<version>.json` only if it does not exist yet. File systems and
+//! object stores lacking such a primitive (e.g. without atomic rename) can instead serialize
+//! commits through a [`LockProvider`], backed for example by Redis, Postgres advisory locks or
+//! ZooKeeper. While holding the lock for a table, the [`LockingLogStore`] checks that the commit
+//! does not exist yet and only then writes it.
+//!
+//! Providers can be attached programmatically via [`StorageConfig::with_lock_provider`] or
+//! registered by name via [`register_lock_provider`] and selected with the `lock_provider`
+//! storage option. The lease and timeout of the lock are set with the `lock_lease_duration` and
+//! `lock_timeout` storage options, see [`LockConfig`].
+//!
+//! Right before the commit is written, the log store checks that its lease has not elapsed, so a
+//! writer stalled past its lease never overwrites a commit of the writer which took the lock over.
+//! The lease must therefore comfortably exceed the duration of a single write to the storage.
+//!
+//! [`StorageConfig::with_lock_provider`]: super::StorageConfig::with_lock_provider
+use std::collections::HashMap;
+use std::fmt::Debug;
+use std::sync::{Arc, LazyLock};
+use std::time::{Duration, Instant};
+
+use async_trait::async_trait;
+use bytes::Bytes;
+use dashmap::DashMap;
+use deltalake_derive::DeltaConfig;
+use object_store::{Error as ObjectStoreError, ObjectStore, ObjectStoreExt as _};
+use parking_lot::Mutex;
+use tracing::*;
+use uuid::Uuid;
+
+use super::storage::{ObjectStoreRef, utils::commit_uri_from_version};
+use super::{CommitOrBytes, LogStore, LogStoreConfig};
+use crate::kernel::Version;
+use crate::kernel::transaction::TransactionError;
+use crate::{DeltaResult, DeltaTableError};
+
+/// Storage option used to select a provider registered via [`register_lock_provider`].
+pub const LOCK_PROVIDER_KEY: &str = "lock_provider";
+
+/// Default duration a commit lock is held before other writers may take it over.
+pub const DEFAULT_LOCK_LEASE: Duration = Duration::from_secs(60);
+
+/// Default time to wait for a commit lock before giving up.
+pub const DEFAULT_LOCK_TIMEOUT: Duration = Duration::from_secs(30);
+
+const LOCK_POLL_INTERVAL: Duration = Duration::from_millis(100);
+
+/// Configuration of the commit lock of a [`LockingLogStore`].
+#[derive(Debug, Clone, Default, DeltaConfig)]
+pub struct LockConfig {
+    /// Duration the commit lock is held before other writers may take it over.
+    #[delta(env = "DELTA_LOCK_LEASE_DURATION")]
+    pub lock_lease_duration: Option<Duration>,
+    /// Time to wait for the commit lock before failing the commit.
+    #[delta(env = "DELTA_LOCK_TIMEOUT")]
+    pub lock_timeout: Option<Duration>,
+}
+
+/// A distributed lock used to serialize commits to a table.
+///
+/// Implementations must guarantee that at most one owner holds a given key at any time. A lock
+/// whose lease elapsed without being released may be taken over by another owner, so a crashed
+/// writer does not block the table forever.
+#[async_trait]
+pub trait LockProvider: Debug + Send + Sync {
+    /// Name of the provider, used for diagnostics.
+    fn name(&self) -> String;
+
+    /// Try to acquire the lock `key` on behalf of `owner` for the duration of `lease`.
+    ///
+    /// If `owner` already holds the lock, its lease is renewed. Returns `false` if the lock is
+    /// currently held by another owner.
+    async fn try_acquire(&self, key: &str, owner: &str, lease: Duration) -> DeltaResult<bool>;
+
+    /// Release the lock `key` if it is held by `owner`.
+    async fn release(&self, key: &str, owner: &str) -> DeltaResult<()>;
+}
+
+/// Sharable reference to a [`LockProvider`]
+pub type LockProviderRef = Arc<dyn LockProvider>;
+
+static LOCK_PROVIDERS: LazyLock<DashMap<String, LockProviderRef>> = LazyLock::new(DashMap::new);
+
+/// Register a named [`LockProvider`].
+///
+/// The provider is used for tables opened with the storage option `lock_provider = "<name>"`.
+/// If a provider with the same name existed before, it is replaced and returned.
+pub fn register_lock_provider(
+    name: impl Into<String>,
+    provider: LockProviderRef,
+) -> Option<LockProviderRef> {
+    LOCK_PROVIDERS.insert(name.into(), provider)
+}
+
+/// Remove a previously registered [`LockProvider`].
+pub fn deregister_lock_provider(name: &str) -> Option<LockProviderRef> {
+    LOCK_PROVIDERS.remove(name).map(|(_, provider)| provider)
+}
+
+pub(crate) fn lock_provider(name: &str) -> Option<LockProviderRef> {
+    LOCK_PROVIDERS.get(name).map(|entry| entry.value().clone())
+}
+
+/// A [`LogStore`] which writes commits while holding a lock from a [`LockProvider`].
+#[derive(Debug, Clone)]
+pub struct LockingLogStore {
+    prefixed_store: ObjectStoreRef,
+    root_store: ObjectStoreRef,
+    config: LogStoreConfig,
+    provider: LockProviderRef,
+    lease: Duration,
+    timeout: Duration,
+}
+
+impl LockingLogStore {
+    /// Create a new instance of [`LockingLogStore`]
+    ///
+    /// # Arguments
+    ///
+    /// * `prefixed_store` - A shared reference to an [`object_store::ObjectStore`] with "/"
+    ///   pointing at delta table root (i.e. where `_delta_log` is located).
+    /// * `root_store` - A shared reference to an [`object_store::ObjectStore`] with "/"
+    ///   pointing at root of the storage system.
+    /// * `config` - Configuration of the log store.
+    /// * `provider` - The provider of the lock serializing commits.
+    pub fn new(
+        prefixed_store: ObjectStoreRef,
+        root_store: ObjectStoreRef,
+        config: LogStoreConfig,
+        provider: LockProviderRef,
+    ) -> Self {
+        let lock = config.options().lock.clone().unwrap_or_default();
+        Self {
+            prefixed_store,
+            root_store,
+            config,
+            provider,
+            lease: lock.lock_lease_duration.unwrap_or(DEFAULT_LOCK_LEASE),
+            timeout: lock.lock_timeout.unwrap_or(DEFAULT_LOCK_TIMEOUT),
+        }
+    }
+
+    /// Duration the lock is held before other writers may take it over
+    pub fn with_lease(mut self, lease: Duration) -> Self {
+        self.lease = lease;
+        self
+    }
+
+    /// Time to wait for the lock before failing the commit
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    /// The provider used by this log store.
+    pub fn provider(&self) -> &LockProviderRef {
+        &self.provider
+    }
+
+    /// Key of the lock guarding commits to this table
+    fn lock_key(&self) -> String {
+        format!(
+            "{}/_delta_log",
+            self.config.location.as_str().trim_end_matches('/')
+        )
+    }
+
+    /// Acquire the lock, returning when its lease elapses.
+    async fn acquire(&self, key: &str, owner: &str) -> Result<Instant, TransactionError> {
+        let deadline = Instant::now() + self.timeout;
+        loop {
+            if let Some(lease_deadline) = self.try_acquire(key, owner).await? {
+                return Ok(lease_deadline);
+            }
+            if Instant::now() + LOCK_POLL_INTERVAL > deadline {
+                return Err(lock_error(
+                    format!("Timed out acquiring commit lock {key}"),
+                    DeltaTableError::generic(format!(
+                        "lock held by another writer for more than {:?}",
+                        self.timeout
+                    )),
+                ));
+            }
+            tokio::time::sleep(LOCK_POLL_INTERVAL).await;
+        }
+    }
+
+    /// Try to acquire or renew the lock once, returning when its lease elapses.
+    async fn try_acquire(
+        &self,
+        key: &str,
+        owner: &str,
+    ) -> Result<Option<Instant>, TransactionError> {
+        // the provider starts the lease after the request was sent, so it never ends earlier
+        let requested_at = Instant::now();
+        let acquired = self
+            .provider
+            .try_acquire(key, owner, self.lease)
+            .await
+            .map_err(|err| lock_error(format!("Failed to acquire commit lock {key}"), err))?;
+        Ok(acquired.then_some(requested_at + self.lease))
+    }
+
+    async fn write_locked(
+        &self,
+        version: Version,
+        commit_or_bytes: CommitOrBytes,
+        key: &str,
+        owner: &str,
+        lease_deadline: Instant,
+    ) -> Result<(), TransactionError> {
+        // renew the lease if less than half of it is left for the commit
+        let lease_deadline = if lease_deadline.saturating_duration_since(Instant::now())
+            < self.lease / 2
+        {
+            self.try_acquire(key, owner).await?.ok_or_else(|| {
+                lock_error(
+                    format!("Lost commit lock {key}"),
+                    DeltaTableError::generic("the lease elapsed and another writer took the lock"),
+                )
+            })?
+        } else {
+            lease_deadline
+        };
+
+        let path = commit_uri_from_version(Some(version));
+        match self.prefixed_store.head(&path).await {
+            Ok(_) => return Err(TransactionError::VersionAlreadyExists(version)),
+            Err(ObjectStoreError::NotFound { .. }) => {}
+            Err(err) => return Err(err.into()),
+        }
+        // another writer may take the lock over once the lease elapsed
+        if Instant::now() >= lease_deadline {
+            return Err(lock_error(
+                format!("Lost commit lock {key}"),
+                DeltaTableError::generic(format!(
+                    "the lease of {:?} elapsed before version {version} was written",
+                    self.lease
+                )),
+            ));
+        }
+        match commit_or_bytes {
+            CommitOrBytes::LogBytes(log_bytes) => {
+                self.prefixed_store.put(&path, log_bytes.into()).await?;
+            }
+            CommitOrBytes::TmpCommit(tmp_commit) => {
+                self.prefixed_store.copy(&tmp_commit, &path).await?;
+                if let Err(err) = self.prefixed_store.delete(&tmp_commit).await {
+                    warn!(error = %err, "failed to clean up temporary commit");
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+fn lock_error(msg: String, err: DeltaTableError) -> TransactionError {
+    TransactionError::LogStoreError {
+        msg,
+        source: Box::new(err),
+    }
+}
+
+#[async_trait]
+impl LogStore for LockingLogStore {
+    fn name(&self) -> String {
+        format!("LockingLogStore({})", self.provider.name())
+    }
+
+    async fn read_commit_entry(&self, version: Version) -> DeltaResult<Option<Bytes>> {
+        super::read_commit_entry(self.prefixed_store.as_ref(), version).await
+    }
+
+    async fn write_commit_entry(
+        &self,
+        version: Version,
+        commit_or_bytes: CommitOrBytes,
+        operation_id: Uuid,
+    ) -> Result<(), TransactionError> {
+        let key = self.lock_key();
+        let owner = operation_id.to_string();
+        let lease_deadline = self.acquire(&key, &owner).await?;
+        let result = self
+            .write_locked(version, commit_or_bytes, &key, &owner, lease_deadline)
+            .await;
+        if let Err(err) = self.provider.release(&key, &owner).await {
+            // the lease expires eventually, so the table is not blocked forever
+            warn!(error = %err, key, "failed to release commit lock");
+        }
+        result
+    }
+
+    async fn abort_commit_entry(
+        &self,
+        _version: Version,
+        commit_or_bytes: CommitOrBytes,
+        _: Uuid,
+    ) -> Result<(), TransactionError> {
+        if let CommitOrBytes::TmpCommit(tmp_commit) = &commit_or_bytes {
+            self.prefixed_store.delete(tmp_commit).await?;
+        }
+        Ok(())
+    }
+
+    async fn get_latest_version(&self, current_version: Version) -> DeltaResult<Version> {
+        super::get_latest_version(self, current_version).await
+    }
+
+    fn object_store(&self, _: Option<Uuid>) -> Arc<dyn ObjectStore> {
+        self.prefixed_store.clone()
+    }
+
+    fn root_object_store(&self, _: Option<Uuid>) -> Arc<dyn ObjectStore> {
+        self.root_store.clone()
+    }
+
+    fn config(&self) -> &LogStoreConfig {
+        &self.config
+    }
+}
+
+/// A process-local [`LockProvider`].
+///
+/// Useful for tests and for embedding applications which run all writers within a single
+/// process.
+#[derive(Debug, Default)]
+pub struct InMemoryLockProvider {
+    locks: Mutex<HashMap<String, (String, Instant)>>,
+}
+
+#[async_trait]
+impl LockProvider for InMemoryLockProvider {
+    fn name(&self) -> String {
+        "InMemoryLockProvider".into()
+    }
+
+    async fn try_acquire(&self, key: &str, owner: &str, lease: Duration) -> DeltaResult<bool> {
+        let mut locks = self.locks.lock();
+        let now = Instant::now();
+        match locks.get(key) {
+            Some((holder, expires_at)) if holder != owner && *expires_at > now => Ok(false),
+            _ => {
+                locks.insert(key.to_string(), (owner.to_string(), now + lease));
+                Ok(true)
+            }
+        }
+    }
+
+    async fn release(&self, key: &str, owner: &str) -> DeltaResult<()> {
+        let mut locks = self.locks.lock();
+        if locks.get(key).is_some_and(|(holder, _)| holder == owner) {
+            locks.remove(key);
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::logstore::{StorageConfig, logstore_for};
+
+    #[tokio::test]
+    async fn test_commits_are_serialized_by_lock() {
+        let provider = Arc::new(InMemoryLockProvider::default());
+        let location = url::Url::parse("memory:///table").unwrap();
+        let config = StorageConfig::default().with_lock_provider(provider.clone());
+        let store = logstore_for(&location, config).unwrap();
+        assert_eq!(store.name(), "LockingLogStore(InMemoryLockProvider)");
+
+        let bytes = Bytes::from_static(b"{\"commitInfo\":{}}\n");
+        store
+            .write_commit_entry(0, CommitOrBytes::LogBytes(bytes.clone()), Uuid::new_v4())
+            .await
+            .unwrap();
+        assert_eq!(
+            store.read_commit_entry(0).await.unwrap(),
+            Some(bytes.clone())
+        );
+
+        let result = store
+            .write_commit_entry(0, CommitOrBytes::LogBytes(bytes), Uuid::new_v4())
+            .await;
+        assert!(matches!(
+            result,
+            Err(TransactionError::VersionAlreadyExists(0))
+        ));
+        // the lock is released after every commit, successful or not
+        assert!(provider.locks.lock().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_commit_fails_while_lock_is_held() {
+        let provider = Arc::new(InMemoryLockProvider::default());
+        let location = url::Url::parse("memory:///table").unwrap();
+        let base = logstore_for(&location, StorageConfig::default()).unwrap();
+        let store = LockingLogStore::new(
+            base.object_store(None),
+            base.root_object_store(None),
+            base.config().clone(),
+            provider.clone(),
+        )
+        .with_timeout(Duration::from_millis(200));
+
+        let key = store.lock_key();
+        assert!(
+            provider
+                .try_acquire(&key, "other", Duration::from_secs(60))
+                .await
+                .unwrap()
+        );
+        let bytes = Bytes::from_static(b"{\"commitInfo\":{}}\n");
+        let result = store
+            .write_commit_entry(0, CommitOrBytes::LogBytes(bytes.clone()), Uuid::new_v4())
+            .await;
+        assert!(matches!(
+            result,
+            Err(TransactionError::LogStoreError { .. })
+        ));
+
+        provider.release(&key, "other").await.unwrap();
+        store
+            .write_commit_entry(0, CommitOrBytes::LogBytes(bytes), Uuid::new_v4())
+            .await
+            .unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_create_table_with_lock_provider() -> DeltaResult<()> {
+        let provider = Arc::new(InMemoryLockProvider::default());
+        let table = crate::DeltaTableBuilder::from_url(url::Url::parse("memory:///").unwrap())?
+            .with_lock_provider(provider.clone())
+            .build()?
+            .create()
+            .with_columns(
+                crate::writer::test_utils::get_delta_schema()
+                    .fields()
+                    .cloned(),
+            )
+            .await?;
+        assert_eq!(table.version(), Some(0));
+        assert!(table.log_store().name().starts_with("LockingLogStore"));
+        assert!(provider.locks.lock().is_empty());
+        Ok(())
+    }
+
+    /// Grants the lock once, then reports it as taken over by another writer.
+    #[derive(Debug, Default)]
+    struct StolenLockProvider {
+        attempts: Mutex<usize>,
+    }
+
+    #[async_trait]
+    impl LockProvider for StolenLockProvider {
+        fn name(&self) -> String {
+            "StolenLockProvider".into()
+        }
+
+        async fn try_acquire(&self, _: &str, _: &str, _: Duration) -> DeltaResult<bool> {
+            let mut attempts = self.attempts.lock();
+            *attempts += 1;
+            Ok(*attempts == 1)
+        }
+
+        async fn release(&self, _: &str, _: &str) -> DeltaResult<()> {
+            Ok(())
+        }
+    }
+
+    #[tokio::test]
+    async fn test_commit_fails_after_lease_is_lost() {
+        let provider = Arc::new(StolenLockProvider::default());
+        let location = url::Url::parse("memory:///table").unwrap();
+        let base = logstore_for(&location, StorageConfig::default()).unwrap();
+        let store = LockingLogStore::new(
+            base.object_store(None),
+            base.root_object_store(None),
+            base.config().clone(),
+            provider.clone(),
+        )
+        .with_lease(Duration::from_millis(1));
+
+        let bytes = Bytes::from_static(b"{\"commitInfo\":{}}\n");
+        let result = store
+            .write_commit_entry(0, CommitOrBytes::LogBytes(bytes), Uuid::new_v4())
+            .await;
+        assert!(matches!(
+            result,
+            Err(TransactionError::LogStoreError { .. })
+        ));
+        // the lease was renewed right before the write and found to be lost
+        assert_eq!(*provider.attempts.lock(), 2);
+        assert_eq!(store.read_commit_entry(0).await.unwrap(), None);
+    }
+
+    #[test]
+    fn test_lock_config_from_storage_options() {
+        let location = url::Url::parse("memory:///table").unwrap();
+        let config =
+            StorageConfig::parse_options([("lock_lease_duration", "5s"), ("lock_timeout", "1m")])
+                .unwrap();
+        let base = logstore_for(&location, StorageConfig::default()).unwrap();
+        let store = LockingLogStore::new(
+            base.object_store(None),
+            base.root_object_store(None),
+            LogStoreConfig::new(&location, config),
+            Arc::new(InMemoryLockProvider::default()),
+        );
+        assert_eq!(store.lease, Duration::from_secs(5));
+        assert_eq!(store.timeout, Duration::from_secs(60));
+
+        assert!(StorageConfig::parse_options([("lock_timeout", "soon")]).is_err());
+    }
+
+    #[test]
+    fn test_lock_provider_from_storage_options() {
+        register_lock_provider("test-lock", Arc::new(InMemoryLockProvider::default()));
+        let config = StorageConfig::parse_options([(LOCK_PROVIDER_KEY, "test-lock")]).unwrap();
+        assert!(config.lock_provider.is_some());
+        assert!(!config.unknown_properties.contains_key(LOCK_PROVIDER_KEY));
+
+        assert!(StorageConfig::parse_options([(LOCK_PROVIDER_KEY, "missing")]).is_err());
+        deregister_lock_provider("test-lock");
+    }
+}
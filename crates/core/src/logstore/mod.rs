@@ -43,12 +43,24 @@
 //! on external integrations to provide [`ObjectStore`] and/or [`LogStore`] implementations.
 //!
 //! At runtime, deltalake needs to produce appropriate [`ObjectStore`]s to access the files
-//! discovered in a table. This is done via
+//! discovered in a table. This is done via process global registries which map URL schemes to
+//! an [`ObjectStoreFactory`] and a [`LogStoreFactory`] respectively. Integration crates
+//! (e.g. `deltalake-aws`) populate these registries in their `register_handlers` functions.
+//!
+//! Applications can register their own factories for custom URL schemes at runtime:
+//!
+//! - [`register_object_store_factory`] / [`deregister_object_store_factory`]
+//! - [`register_logstore_factory`] / [`deregister_logstore_factory`]
+//! - [`register_default_logstore_factory`] for backends with atomic put-if-absent support.
 //!
 //! ## Configuration
 //!
+//! All factories receive the parsed [`StorageConfig`]. Options which are specific to a scheme
+//! can be passed as `<scheme>.<key>` and retrieved via [`StorageConfig::scheme_options`].
+//!
 use std::collections::HashMap;
 use std::sync::{Arc, LazyLock};
+use std::time::Duration;
 
 use bytes::Bytes;
 #[cfg(feature = "datafusion")]
@@ -62,6 +74,7 @@ use delta_kernel_default_engine::executor::tokio::{
 };
 use futures::StreamExt;
 use object_store::ObjectStoreScheme;
+use object_store::signer::Signer;
 use object_store::{Error as ObjectStoreError, ObjectStore, ObjectStoreExt as _, path::Path};
 use regex::Regex;
 use serde::de::{Error, SeqAccess, Visitor};
@@ -79,21 +92,43 @@ use crate::table::normalize_table_url;
 use crate::{DeltaResult, DeltaTableError};
 
 pub use self::config::StorageConfig;
+pub use self::coordinated::{
+    COMMIT_COORDINATOR_KEY, CommitCoordinator, CommitCoordinatorRef, CoordinatedCommit,
+    CoordinatedLogStore, GetCommitsResponse, InMemoryCommitCoordinator,
+    deregister_commit_coordinator, register_commit_coordinator,
+};
 pub use self::factories::{
     LogStoreFactory, LogStoreFactoryRegistry, ObjectStoreFactory, ObjectStoreFactoryRegistry,
-    logstore_factories, object_store_factories, store_for,
+    deregister_logstore_factory, deregister_object_store_factory, logstore_factories,
+    object_store_factories, register_default_logstore_factory, register_logstore_factory,
+    register_object_store_factory, store_for,
+};
+pub use self::lock::{
+    DEFAULT_LOCK_LEASE, DEFAULT_LOCK_TIMEOUT, InMemoryLockProvider, LOCK_PROVIDER_KEY, LockConfig,
+    LockProvider, LockProviderRef, LockingLogStore, deregister_lock_provider,
+    register_lock_provider,
 };
 pub use self::storage::utils::commit_uri_from_version;
 pub use self::storage::{
-    DefaultObjectStoreRegistry, DeltaIOStorageBackend, IORuntime, ObjectStoreRef,
-    ObjectStoreRegistry, ObjectStoreRetryExt, client_options_from_certificate,
+    CREDENTIAL_PROVIDER_KEY, CoalesceConfig, CoalescingStore, CommitAttempt, CommitAttemptOutcome,
+    CommitStats, CredentialRefreshingStore, DefaultObjectStoreRegistry, DeltaIOStorageBackend,
+    FallbackConfig, FallbackStore, IORuntime, InstrumentedStore, IoMetrics, MultipartConfig,
+    MultipartWriter, ObjectStoreRef, ObjectStoreRegistry, ObjectStoreRetryExt, ProxyConfig,
+    RequestStats, StorageCredentialProvider, StorageCredentialProviderRef, StorageCredentials,
+    StorageMetricsRecorder, StorageMetricsRecorderRef, StorageRequest, StorageRequestKind,
+    ThrottleConfig, ThrottledStore, client_options, client_options_from_certificate,
+    deregister_credential_provider, register_credential_provider,
 };
+#[cfg(feature = "delta-cache")]
+pub use self::storage::{CacheConfig, CachingStore};
 /// Convenience re-export of the object store crate
 pub use ::object_store;
 
 pub mod config;
+pub(crate) mod coordinated;
 pub(crate) mod default_logstore;
 pub(crate) mod factories;
+pub(crate) mod lock;
 pub(crate) mod storage;
 
 /// Internal trait to handle object store configuration and initialization.
@@ -122,6 +157,29 @@ impl<T: LogStoreFactory + ?Sized> LogStoreFactoryExt for T {
         options: &StorageConfig,
     ) -> DeltaResult<LogStoreRef> {
         let prefixed_store = options.decorate_store(root_store.clone(), location)?;
+        if let Some(coordinator) = &options.commit_coordinator {
+            // the coordinator decides which commit owns a version, not the storage
+            return Ok(Arc::new(CoordinatedLogStore::new(
+                Arc::new(prefixed_store),
+                root_store,
+                LogStoreConfig::new(location, options.clone()),
+                coordinator.clone(),
+            )));
+        }
+        if let Some(name) = options.raw.get(COMMIT_COORDINATOR_KEY) {
+            return Err(DeltaTableError::Generic(format!(
+                "No commit coordinator registered with name '{name}'"
+            )));
+        }
+        if let Some(provider) = &options.lock_provider {
+            // storage without atomic put-if-absent serializes commits through the lock instead
+            return Ok(Arc::new(LockingLogStore::new(
+                Arc::new(prefixed_store),
+                root_store,
+                LogStoreConfig::new(location, options.clone()),
+                provider.clone(),
+            )));
+        }
         let log_store =
             self.with_options(Arc::new(prefixed_store), root_store, location, options)?;
         Ok(log_store)
@@ -159,7 +217,7 @@ pub type LogStoreRef = Arc<dyn LogStore>;
 static DELTA_LOG_PATH: LazyLock<Path> = LazyLock::new(|| Path::from("_delta_log"));
 
 pub(crate) static DELTA_LOG_REGEX: LazyLock<Regex> =
-    LazyLock::new(|| Regex::new(r"(\d{20})\.(json|checkpoint(\.\d+)?\.parquet)$").unwrap());
+    LazyLock::new(|| Regex::new(r"(\d{20})\.(json|checkpoint(\.\d+)*\.parquet)$").unwrap());
 
 /// Return the [LogStoreRef] for the provided [Url] location
 ///
@@ -203,7 +261,64 @@ pub fn logstore_for(location: &Url, storage_config: StorageConfig) -> DeltaResul
 
     if let Some(entry) = object_store_factories().get(&scheme) {
         debug!("Found a storage provider for {scheme} ({location})");
-        let (root_store, _prefix) = entry.value().parse_url_opts(location, &storage_config)?;
+        let root_store = match storage_config.credential_provider.clone() {
+            Some(provider) => Arc::new(CredentialRefreshingStore::new(
+                entry.value().clone(),
+                location.clone(),
+                storage_config.clone(),
+                provider,
+            )) as ObjectStoreRef,
+            None => entry.value().parse_url_opts(location, &storage_config)?.0,
+        };
+        let fallback_url = match &storage_config.fallback {
+            Some(fallback) => fallback.url()?,
+            None => None,
+        };
+        let root_store = match fallback_url {
+            Some(fallback_url) => {
+                let fallback_scheme = Url::parse(&format!("{}://", fallback_url.scheme()))
+                    .map_err(|_| DeltaTableError::InvalidTableLocation(fallback_url.to_string()))?;
+                let Some(fallback_entry) = object_store_factories().get(&fallback_scheme) else {
+                    return Err(DeltaTableError::InvalidTableLocation(
+                        fallback_url.to_string(),
+                    ));
+                };
+                let fallback_store = fallback_entry
+                    .value()
+                    .parse_url_opts(&fallback_url, &storage_config)?
+                    .0;
+                Arc::new(FallbackStore::new(
+                    root_store,
+                    object_store_path(location)?,
+                    fallback_store,
+                    object_store_path(&fallback_url)?,
+                )) as ObjectStoreRef
+            }
+            None => root_store,
+        };
+        let root_store = match &storage_config.metrics {
+            Some(recorder) => {
+                Arc::new(InstrumentedStore::new(root_store, recorder.clone())) as ObjectStoreRef
+            }
+            None => root_store,
+        };
+        let root_store = match &storage_config.throttle {
+            Some(throttle) => Arc::new(ThrottledStore::new(root_store, throttle)) as ObjectStoreRef,
+            None => root_store,
+        };
+        #[cfg(feature = "delta-cache")]
+        let root_store = match &storage_config.cache {
+            Some(cache) => {
+                Arc::new(CachingStore::new(root_store, location, cache.clone())) as ObjectStoreRef
+            }
+            None => root_store,
+        };
+        let root_store = match &storage_config.coalesce {
+            Some(coalesce) => {
+                Arc::new(CoalescingStore::new(root_store, coalesce.clone())) as ObjectStoreRef
+            }
+            None => root_store,
+        };
         return logstore_with(root_store, location, storage_config);
     }
 
@@ -353,6 +468,16 @@ impl LogStoreConfig {
     pub fn object_store_factory(&self) -> ObjectStoreFactoryRegistry {
         self::factories::object_store_factories()
     }
+
+    /// Returns a [`Signer`] for the store of this table, if its backend supports pre-signed URLs.
+    pub fn signer(&self) -> DeltaResult<Option<Arc<dyn Signer>>> {
+        let scheme = Url::parse(&format!("{}://", self.location.scheme()))
+            .map_err(|_| DeltaTableError::InvalidTableLocation(self.location.clone().into()))?;
+        match self.object_store_factory().get(&scheme) {
+            Some(factory) => factory.value().signer(&self.location, &self.options),
+            None => Ok(None),
+        }
+    }
 }
 
 /// Trait for critical operations required to read and write commit entries in Delta logs.
@@ -473,6 +598,34 @@ pub trait LogStore: Send + Sync + AsAny {
     /// Get configuration representing configured log store.
     fn config(&self) -> &LogStoreConfig;
 
+    /// Generate pre-signed GET URLs for the files at `locations`.
+    ///
+    /// Locations are file paths as recorded in the log, i.e. relative to the table root or fully
+    /// qualified URLs, which must be in the same bucket as the table.
+    ///
+    /// The URLs are valid for `expires_in` and grant access without any credentials, which allows
+    /// handing data files to browsers or short-lived workers without proxying their bytes.
+    async fn presigned_urls(
+        &self,
+        locations: &[String],
+        expires_in: Duration,
+    ) -> DeltaResult<Vec<Url>> {
+        let location = &self.config().location;
+        let signer = self.config().signer()?.ok_or_else(|| {
+            DeltaTableError::Generic(format!(
+                "Pre-signed URLs are not supported for the table at {location}"
+            ))
+        })?;
+        let prefix = object_store_path(location)?;
+        let paths = locations
+            .iter()
+            .map(|path| signing_path(location, &prefix, path))
+            .collect::<DeltaResult<Vec<_>>>()?;
+        Ok(signer
+            .signed_urls(http::Method::GET, &paths, expires_in)
+            .await?)
+    }
+
     #[cfg(feature = "datafusion")]
     /// Generate a unique enough url to identify the store in datafusion.
     /// The DF object store registry only cares about the scheme and the host of the url for
@@ -581,6 +734,14 @@ impl<T: LogStore + ?Sized> LogStore for Arc<T> {
         T::config(self)
     }
 
+    async fn presigned_urls(
+        &self,
+        locations: &[String],
+        expires_in: Duration,
+    ) -> DeltaResult<Vec<Url>> {
+        T::presigned_urls(self, locations, expires_in).await
+    }
+
     #[cfg(feature = "datafusion")]
     fn object_store_url(&self) -> ObjectStoreUrl {
         T::object_store_url(self)
@@ -633,6 +794,29 @@ pub(crate) fn object_store_path(table_root: &Url) -> DeltaResult<Path> {
     })
 }
 
+/// Path of the file at `location` within the bucket of the table at `root`, rooted at `prefix`.
+///
+/// Fully qualified locations are only accepted in the same bucket, since signers are bound to it.
+fn signing_path(root: &Url, prefix: &Path, location: &str) -> DeltaResult<Path> {
+    match Url::parse(location) {
+        Ok(url) => {
+            if url.scheme() != root.scheme()
+                || url.host_str() != root.host_str()
+                || url.username() != root.username()
+            {
+                return Err(DeltaTableError::Generic(format!(
+                    "Cannot pre-sign {url}, it is outside the bucket of the table at {root}"
+                )));
+            }
+            object_store_path(&url)
+        }
+        Err(_) => {
+            let path = Path::parse(location).unwrap_or_else(|_| Path::from(location));
+            Ok(prefix.parts().chain(path.parts()).collect())
+        }
+    }
+}
+
 /// Join the given `root` [Url] with the [Path] to produce a URI (String) of the two together.
 ///
 /// This is largely a convenience function to help with the nuances of empty [Path] and file [Url]s
@@ -803,6 +987,58 @@ pub async fn get_latest_version(
     Ok(segment.end_version)
 }
 
+/// Retrieve the latest version without listing the full `_delta_log` directory.
+///
+/// The `_last_checkpoint` hint (or `min_version`, whichever is larger) is used as the
+/// starting offset for a bounded suffix listing, so the cost is proportional to the number
+/// of log entries written since the last checkpoint rather than the total log size.
+#[instrument(skip(log_store), fields(min_version = ?min_version))]
+pub async fn peek_latest_version(
+    log_store: &dyn LogStore,
+    min_version: Option<Version>,
+) -> DeltaResult<Version> {
+    let storage = log_store.object_store(None);
+    let log_path = log_store.log_path().clone();
+
+    let checkpoint_hint = match storage
+        .get(&log_path.clone().join("_last_checkpoint"))
+        .await
+    {
+        Ok(res) => crate::checkpoints::parse_last_checkpoint_hint(&res.bytes().await?)
+            .map(|hint| hint.version),
+        Err(ObjectStoreError::NotFound { .. }) => None,
+        Err(err) => return Err(err.into()),
+    };
+    let start = checkpoint_hint.max(min_version);
+
+    let mut latest = None;
+    let mut entries = match start {
+        Some(start) => {
+            let offset = log_path.clone().join(format!("{start:020}"));
+            storage.list_with_offset(Some(&log_path), &offset)
+        }
+        None => storage.list(Some(&log_path)),
+    };
+    while let Some(meta) = entries.next().await {
+        let meta = meta?;
+        if let Some(version) = meta
+            .location
+            .filename()
+            .and_then(extract_version_from_filename)
+        {
+            latest = latest.max(Some(version));
+        }
+    }
+
+    match latest {
+        Some(version) => Ok(version),
+        // the hint may point past entries that have since been cleaned up, so
+        // fall back to the regular lookup before giving up.
+        None if start.is_some() => get_latest_version(log_store, 0).await,
+        None => Err(DeltaTableError::not_a_table(log_store.root_url())),
+    }
+}
+
 /// Read delta log for a specific version
 #[instrument(skip(storage), fields(version = version, path = %commit_uri_from_version(Some(version))))]
 pub async fn read_commit_entry(
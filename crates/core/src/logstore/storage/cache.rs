@@ -0,0 +1,525 @@
+//! Local caching of reads repeated on every snapshot load.
+//!
+//! [`CachingStore`] keeps the results of the following reads in a hybrid memory and disk cache:
+//!
+//! - commit and checkpoint files within the `_delta_log` directory, and
+//! - reads of the trailing bytes of Parquet files, i.e. footer and metadata fetches.
+//!
+//! Both tiers are bounded in size and evict the least recently used entries. Before an entry is
+//! served for the first time within a process, its ETag is validated against the object store,
+//! so entries persisted by a previous process (e.g. for a table which has been re-created at the
+//! same location) are never served when stale.
+use std::fmt;
+use std::ops::Range;
+use std::path::PathBuf;
+use std::sync::{Arc, LazyLock};
+
+use bytes::Bytes;
+use dashmap::DashMap;
+use deltalake_derive::DeltaConfig;
+use foyer::{
+    BlockEngineBuilder, DeviceBuilder, FsDeviceBuilder, HybridCache, HybridCacheBuilder,
+    RecoverMode,
+};
+use futures::StreamExt;
+use futures::stream::BoxStream;
+use object_store::path::Path;
+use object_store::{
+    Attributes, CopyOptions, Error as ObjectStoreError, GetOptions, GetRange, GetResult,
+    GetResultPayload, ListResult, MultipartUpload, ObjectMeta, ObjectStore, ObjectStoreExt as _,
+    PutMultipartOptions, PutOptions, PutPayload, PutResult, RenameOptions,
+    Result as ObjectStoreResult,
+};
+use serde::{Deserialize, Serialize};
+use tokio::sync::OnceCell;
+use url::Url;
+
+use super::ObjectStoreRef;
+
+/// Default capacity of the in-memory tier in bytes.
+const DEFAULT_MEMORY_SIZE: usize = 64 * 1024 * 1024;
+/// Default capacity of the on-disk tier in bytes.
+const DEFAULT_DISK_SIZE: usize = 1024 * 1024 * 1024;
+
+/// Configuration of the local cache for log and Parquet footer reads.
+///
+/// Caches are shared by all tables using the same `cache_dir` within a process, the capacities
+/// of the first table opened are used.
+#[derive(Debug, Clone, Default, DeltaConfig)]
+pub struct CacheConfig {
+    /// Directory to persist cached reads in, a temporary directory is used if not set.
+    #[delta(env = "DELTA_RS_CACHE_DIR")]
+    pub cache_dir: Option<String>,
+    /// Capacity of the in-memory tier in bytes.
+    #[delta(env = "DELTA_RS_CACHE_MEMORY_SIZE")]
+    pub cache_memory_size: Option<usize>,
+    /// Capacity of the on-disk tier in bytes.
+    #[delta(env = "DELTA_RS_CACHE_DISK_SIZE")]
+    pub cache_disk_size: Option<usize>,
+}
+
+impl CacheConfig {
+    fn memory_size(&self) -> usize {
+        self.cache_memory_size.unwrap_or(DEFAULT_MEMORY_SIZE)
+    }
+
+    fn disk_size(&self) -> usize {
+        self.cache_disk_size.unwrap_or(DEFAULT_DISK_SIZE)
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct CacheEntry {
+    data: Vec<u8>,
+    range: Range<u64>,
+    size: u64,
+    e_tag: Option<String>,
+    version: Option<String>,
+    last_modified: i64,
+}
+
+impl CacheEntry {
+    fn new(meta: &ObjectMeta, range: Range<u64>, data: &Bytes) -> Self {
+        Self {
+            data: data.to_vec(),
+            range,
+            size: meta.size,
+            e_tag: meta.e_tag.clone(),
+            version: meta.version.clone(),
+            last_modified: meta.last_modified.timestamp_millis(),
+        }
+    }
+
+    fn signature(&self) -> String {
+        signature(self.e_tag.as_deref(), self.size, self.last_modified)
+    }
+
+    fn to_result(&self, location: &Path) -> GetResult {
+        let data = Bytes::copy_from_slice(&self.data);
+        GetResult {
+            payload: GetResultPayload::Stream(futures::stream::once(async { Ok(data) }).boxed()),
+            meta: ObjectMeta {
+                location: location.clone(),
+                last_modified: chrono::DateTime::from_timestamp_millis(self.last_modified)
+                    .unwrap_or_default(),
+                size: self.size,
+                e_tag: self.e_tag.clone(),
+                version: self.version.clone(),
+            },
+            range: self.range.clone(),
+            attributes: Attributes::default(),
+        }
+    }
+}
+
+/// Identifies the version of an object, falling back to size and modification time for stores
+/// which do not report ETags.
+fn signature(e_tag: Option<&str>, size: u64, last_modified: i64) -> String {
+    match e_tag {
+        Some(e_tag) => e_tag.to_string(),
+        None => format!("{size}:{last_modified}"),
+    }
+}
+
+fn meta_signature(meta: &ObjectMeta) -> String {
+    signature(
+        meta.e_tag.as_deref(),
+        meta.size,
+        meta.last_modified.timestamp_millis(),
+    )
+}
+
+struct SharedCache {
+    cache: HybridCache<String, CacheEntry>,
+    /// Signatures of the objects validated against the object store by this process
+    validated: DashMap<String, String>,
+    /// Keeps the temporary cache directory alive, if one is used
+    _temp_dir: Option<tempfile::TempDir>,
+}
+
+impl SharedCache {
+    async fn try_new(config: &CacheConfig) -> ObjectStoreResult<Self> {
+        let (dir, temp_dir) = match &config.cache_dir {
+            Some(dir) => (PathBuf::from(dir), None),
+            None => {
+                let temp_dir = tempfile::tempdir().map_err(cache_error)?;
+                (temp_dir.path().to_path_buf(), Some(temp_dir))
+            }
+        };
+        let device = FsDeviceBuilder::new(&dir)
+            .with_capacity(config.disk_size())
+            .build()
+            .map_err(cache_error)?;
+        let cache = HybridCacheBuilder::new()
+            .memory(config.memory_size())
+            .with_weighter(|key: &String, entry: &CacheEntry| key.len() + entry.data.len())
+            .storage()
+            .with_engine_config(BlockEngineBuilder::new(device))
+            .with_recover_mode(RecoverMode::Quiet)
+            .build()
+            .await
+            .map_err(cache_error)?;
+        Ok(Self {
+            cache,
+            validated: DashMap::new(),
+            _temp_dir: temp_dir,
+        })
+    }
+}
+
+fn cache_error(e: impl std::error::Error + Send + Sync + 'static) -> ObjectStoreError {
+    ObjectStoreError::Generic {
+        store: "DeltaCache",
+        source: Box::new(e),
+    }
+}
+
+/// Caches by cache directory, the empty key is used for the temporary directory.
+static CACHES: LazyLock<DashMap<String, Arc<OnceCell<Arc<SharedCache>>>>> =
+    LazyLock::new(DashMap::new);
+
+/// Whether the read of `location` is repeated on every snapshot load and worth caching.
+fn is_log_file(location: &Path) -> bool {
+    let mut parts = location.parts().peekable();
+    while let Some(part) = parts.next() {
+        if part.as_ref() == "_delta_log" {
+            // `_last_checkpoint` is overwritten by every checkpoint
+            return parts.peek().is_some() && location.filename() != Some("_last_checkpoint");
+        }
+    }
+    false
+}
+
+/// Whether a read of `range` returned the trailing bytes of a Parquet file.
+fn is_parquet_footer(location: &Path, range: &Range<u64>, size: u64) -> bool {
+    location.extension() == Some("parquet") && range.start > 0 && range.end == size
+}
+
+fn range_key(range: Option<&GetRange>) -> String {
+    match range {
+        None => "full".to_string(),
+        Some(GetRange::Bounded(range)) => format!("{}-{}", range.start, range.end),
+        Some(GetRange::Offset(offset)) => format!("{offset}-"),
+        Some(GetRange::Suffix(suffix)) => format!("-{suffix}"),
+    }
+}
+
+fn is_plain_get(options: &GetOptions) -> bool {
+    options.if_match.is_none()
+        && options.if_none_match.is_none()
+        && options.if_modified_since.is_none()
+        && options.if_unmodified_since.is_none()
+        && options.version.is_none()
+        && !options.head
+}
+
+/// An [`ObjectStore`] caching log and Parquet footer reads in a local memory and disk cache.
+#[derive(Clone)]
+pub struct CachingStore {
+    inner: ObjectStoreRef,
+    namespace: String,
+    config: CacheConfig,
+    cache: Arc<OnceCell<Arc<SharedCache>>>,
+}
+
+impl CachingStore {
+    /// Wrap the root store of the table at `location` with a local cache.
+    pub fn new(inner: ObjectStoreRef, location: &Url, config: CacheConfig) -> Self {
+        let cache = CACHES
+            .entry(config.cache_dir.clone().unwrap_or_default())
+            .or_default()
+            .clone();
+        Self {
+            inner,
+            namespace: location[..url::Position::BeforePath].to_string(),
+            config,
+            cache,
+        }
+    }
+
+    async fn shared_cache(&self) -> ObjectStoreResult<&Arc<SharedCache>> {
+        self.cache
+            .get_or_try_init(|| async { SharedCache::try_new(&self.config).await.map(Arc::new) })
+            .await
+    }
+
+    fn object_key(&self, location: &Path) -> String {
+        format!("{}/{location}", self.namespace)
+    }
+
+    /// Forget the validated version of an object modified through this store.
+    fn invalidate(cache: &OnceCell<Arc<SharedCache>>, object_key: &str) {
+        if let Some(shared) = cache.get() {
+            shared.validated.remove(object_key);
+        }
+    }
+
+    /// Check whether `entry` still reflects the current version of the object.
+    async fn is_valid(
+        &self,
+        shared: &SharedCache,
+        location: &Path,
+        object_key: &str,
+        entry: &CacheEntry,
+    ) -> ObjectStoreResult<bool> {
+        if let Some(validated) = shared.validated.get(object_key) {
+            return Ok(*validated == entry.signature());
+        }
+        let current = match self.inner.head(location).await {
+            Ok(meta) => meta_signature(&meta),
+            Err(ObjectStoreError::NotFound { .. }) => return Ok(false),
+            Err(e) => return Err(e),
+        };
+        let valid = current == entry.signature();
+        shared.validated.insert(object_key.to_string(), current);
+        Ok(valid)
+    }
+
+    async fn cached_get(
+        &self,
+        location: &Path,
+        options: GetOptions,
+    ) -> ObjectStoreResult<GetResult> {
+        let shared = self.shared_cache().await?;
+        let object_key = self.object_key(location);
+        let key = format!("{object_key}#{}", range_key(options.range.as_ref()));
+
+        match shared.cache.get(&key).await {
+            Ok(Some(entry)) => {
+                if self
+                    .is_valid(shared, location, &object_key, entry.value())
+                    .await?
+                {
+                    tracing::trace!("Serving {location} from the local cache");
+                    return Ok(entry.value().to_result(location));
+                }
+                tracing::debug!("Evicting stale cache entry for {location}");
+                shared.cache.remove(&key);
+            }
+            Ok(None) => {}
+            Err(e) => tracing::warn!("Failed to read {location} from the local cache: {e}"),
+        }
+
+        let result = self.inner.get_opts(location, options).await?;
+        if !is_log_file(location) && !is_parquet_footer(location, &result.range, result.meta.size) {
+            return Ok(result);
+        }
+
+        let meta = result.meta.clone();
+        let range = result.range.clone();
+        let attributes = result.attributes.clone();
+        let data = result.bytes().await?;
+        if data.len() <= self.config.memory_size() {
+            shared.validated.insert(object_key, meta_signature(&meta));
+            shared
+                .cache
+                .insert(key, CacheEntry::new(&meta, range.clone(), &data));
+        }
+        Ok(GetResult {
+            payload: GetResultPayload::Stream(futures::stream::once(async { Ok(data) }).boxed()),
+            meta,
+            range,
+            attributes,
+        })
+    }
+}
+
+impl fmt::Debug for CachingStore {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("CachingStore")
+            .field("inner", &self.inner)
+            .field("config", &self.config)
+            .finish()
+    }
+}
+
+impl fmt::Display for CachingStore {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "CachingStore({})", self.inner)
+    }
+}
+
+#[async_trait::async_trait]
+impl ObjectStore for CachingStore {
+    async fn put_opts(
+        &self,
+        location: &Path,
+        payload: PutPayload,
+        options: PutOptions,
+    ) -> ObjectStoreResult<PutResult> {
+        Self::invalidate(&self.cache, &self.object_key(location));
+        self.inner.put_opts(location, payload, options).await
+    }
+
+    async fn put_multipart_opts(
+        &self,
+        location: &Path,
+        options: PutMultipartOptions,
+    ) -> ObjectStoreResult<Box<dyn MultipartUpload>> {
+        Self::invalidate(&self.cache, &self.object_key(location));
+        self.inner.put_multipart_opts(location, options).await
+    }
+
+    async fn get_opts(&self, location: &Path, options: GetOptions) -> ObjectStoreResult<GetResult> {
+        if is_plain_get(&options)
+            && (is_log_file(location) || location.extension() == Some("parquet"))
+        {
+            self.cached_get(location, options).await
+        } else {
+            self.inner.get_opts(location, options).await
+        }
+    }
+
+    fn delete_stream(
+        &self,
+        locations: BoxStream<'static, ObjectStoreResult<Path>>,
+    ) -> BoxStream<'static, ObjectStoreResult<Path>> {
+        let this = self.clone();
+        let locations = locations
+            .inspect(move |location| {
+                if let Ok(location) = location {
+                    Self::invalidate(&this.cache, &this.object_key(location));
+                }
+            })
+            .boxed();
+        self.inner.delete_stream(locations)
+    }
+
+    fn list(&self, prefix: Option<&Path>) -> BoxStream<'static, ObjectStoreResult<ObjectMeta>> {
+        self.inner.list(prefix)
+    }
+
+    fn list_with_offset(
+        &self,
+        prefix: Option<&Path>,
+        offset: &Path,
+    ) -> BoxStream<'static, ObjectStoreResult<ObjectMeta>> {
+        self.inner.list_with_offset(prefix, offset)
+    }
+
+    async fn list_with_delimiter(&self, prefix: Option<&Path>) -> ObjectStoreResult<ListResult> {
+        self.inner.list_with_delimiter(prefix).await
+    }
+
+    async fn copy_opts(
+        &self,
+        from: &Path,
+        to: &Path,
+        options: CopyOptions,
+    ) -> ObjectStoreResult<()> {
+        Self::invalidate(&self.cache, &self.object_key(to));
+        self.inner.copy_opts(from, to, options).await
+    }
+
+    async fn rename_opts(
+        &self,
+        from: &Path,
+        to: &Path,
+        options: RenameOptions,
+    ) -> ObjectStoreResult<()> {
+        Self::invalidate(&self.cache, &self.object_key(from));
+        Self::invalidate(&self.cache, &self.object_key(to));
+        self.inner.rename_opts(from, to, options).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use object_store::ObjectStoreExt;
+    use object_store::memory::InMemory;
+    use tokio::sync::mpsc::unbounded_channel;
+
+    use super::*;
+    use crate::test_utils::object_store::{
+        RecordedObjectStoreOperation, RecordedPathKind, RecordingObjectStore,
+        drain_recorded_object_store_operations,
+    };
+
+    fn caching_store(
+        inner: ObjectStoreRef,
+        dir: &tempfile::TempDir,
+    ) -> (
+        CachingStore,
+        tokio::sync::mpsc::UnboundedReceiver<RecordedObjectStoreOperation>,
+    ) {
+        let (sender, operations) = unbounded_channel();
+        let recording = Arc::new(RecordingObjectStore::with_operations(inner, sender));
+        let config = CacheConfig {
+            cache_dir: Some(dir.path().to_string_lossy().to_string()),
+            cache_memory_size: Some(1024 * 1024),
+            cache_disk_size: Some(16 * 1024 * 1024),
+        };
+        let location = Url::parse("memory:///").unwrap();
+        (CachingStore::new(recording, &location, config), operations)
+    }
+
+    #[test]
+    fn test_cacheable_paths() {
+        assert!(is_log_file(&Path::from(
+            "table/_delta_log/00000000000000000000.json"
+        )));
+        assert!(is_log_file(&Path::from(
+            "_delta_log/00000000000000000010.checkpoint.parquet"
+        )));
+        assert!(!is_log_file(&Path::from("_delta_log/_last_checkpoint")));
+        assert!(!is_log_file(&Path::from("part-00000.parquet")));
+
+        let path = Path::from("part-00000.parquet");
+        assert!(is_parquet_footer(&path, &(100..128), 128));
+        assert!(!is_parquet_footer(&path, &(0..128), 128));
+        assert!(!is_parquet_footer(&path, &(100..120), 128));
+    }
+
+    #[tokio::test]
+    async fn test_log_reads_are_cached() {
+        let dir = tempfile::tempdir().unwrap();
+        let inner: ObjectStoreRef = Arc::new(InMemory::new());
+        let path = Path::from("_delta_log/00000000000000000000.json");
+        inner.put(&path, "commit".into()).await.unwrap();
+        let (store, mut operations) = caching_store(inner, &dir);
+
+        let first = store.get(&path).await.unwrap().bytes().await.unwrap();
+        let second = store.get(&path).await.unwrap().bytes().await.unwrap();
+        assert_eq!(first, second);
+        assert_eq!(
+            drain_recorded_object_store_operations(&mut operations).await,
+            vec![RecordedObjectStoreOperation::Get(RecordedPathKind::Commit)]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_footer_reads_are_cached() {
+        let dir = tempfile::tempdir().unwrap();
+        let inner: ObjectStoreRef = Arc::new(InMemory::new());
+        let path = Path::from("part-00000.parquet");
+        inner.put(&path, vec![0u8; 128].into()).await.unwrap();
+        let (store, mut operations) = caching_store(inner, &dir);
+
+        for _ in 0..2 {
+            store.get_range(&path, 0..64).await.unwrap();
+            store.get_range(&path, 96..128).await.unwrap();
+        }
+        assert_eq!(
+            drain_recorded_object_store_operations(&mut operations).await,
+            vec![
+                RecordedObjectStoreOperation::GetRange(RecordedPathKind::Data, 0..64),
+                RecordedObjectStoreOperation::GetRange(RecordedPathKind::Data, 96..128),
+                RecordedObjectStoreOperation::GetRange(RecordedPathKind::Data, 0..64),
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_overwritten_object_is_revalidated() {
+        let dir = tempfile::tempdir().unwrap();
+        let inner: ObjectStoreRef = Arc::new(InMemory::new());
+        let path = Path::from("_delta_log/00000000000000000000.json");
+        inner.put(&path, "old".into()).await.unwrap();
+        let (store, _operations) = caching_store(inner, &dir);
+
+        store.get(&path).await.unwrap().bytes().await.unwrap();
+        store.put(&path, "new".into()).await.unwrap();
+        let data = store.get(&path).await.unwrap().bytes().await.unwrap();
+        assert_eq!(data, Bytes::from("new"));
+    }
+}
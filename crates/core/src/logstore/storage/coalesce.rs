@@ -0,0 +1,276 @@
+//! Coalescing of ranged reads.
+//!
+//! Scans of wide tables read many small column chunks from the same Parquet file. The
+//! [`CoalescingStore`] merges the byte ranges of a [`ObjectStore::get_ranges`] call which overlap
+//! or are separated by at most a configurable gap into a single request, which cuts the number
+//! of requests sent to the object store at the cost of reading a few unused bytes.
+use std::fmt;
+use std::ops::Range;
+
+use bytes::Bytes;
+use deltalake_derive::DeltaConfig;
+use futures::stream::BoxStream;
+use futures::{StreamExt, TryStreamExt};
+use object_store::path::Path;
+use object_store::{
+    CopyOptions, GetOptions, GetResult, ListResult, MultipartUpload, ObjectMeta, ObjectStore,
+    ObjectStoreExt as _, PutMultipartOptions, PutOptions, PutPayload, PutResult, RenameOptions,
+    Result as ObjectStoreResult,
+};
+
+use super::ObjectStoreRef;
+
+/// Default maximum gap in bytes between two ranges which are merged.
+const DEFAULT_COALESCE_GAP: usize = 1024 * 1024;
+/// Default maximum size in bytes of a merged range.
+const DEFAULT_COALESCE_MAX_SIZE: usize = 64 * 1024 * 1024;
+/// Default number of merged ranges fetched concurrently.
+const DEFAULT_COALESCE_CONCURRENCY: usize = 10;
+
+/// Configuration for merging ranged reads of the same object.
+#[derive(Debug, Clone, Default, DeltaConfig)]
+pub struct CoalesceConfig {
+    /// Maximum gap in bytes between two ranges which are fetched with a single request.
+    #[delta(env = "OBJECT_STORE_COALESCE_GAP")]
+    pub coalesce_gap: Option<usize>,
+    /// Maximum size in bytes of a single merged request.
+    #[delta(env = "OBJECT_STORE_COALESCE_MAX_SIZE")]
+    pub coalesce_max_size: Option<usize>,
+    /// Number of merged requests fetched concurrently.
+    #[delta(env = "OBJECT_STORE_COALESCE_CONCURRENCY")]
+    pub coalesce_concurrency: Option<usize>,
+}
+
+impl CoalesceConfig {
+    fn gap(&self) -> u64 {
+        self.coalesce_gap.unwrap_or(DEFAULT_COALESCE_GAP) as u64
+    }
+
+    fn max_size(&self) -> u64 {
+        self.coalesce_max_size.unwrap_or(DEFAULT_COALESCE_MAX_SIZE) as u64
+    }
+
+    fn concurrency(&self) -> usize {
+        self.coalesce_concurrency
+            .unwrap_or(DEFAULT_COALESCE_CONCURRENCY)
+            .max(1)
+    }
+}
+
+/// Merge `ranges` which overlap or are at most `gap` bytes apart.
+///
+/// Ranges are never merged into a range larger than `max_size`, unless a single requested
+/// range already exceeds it.
+fn merge_ranges(ranges: &[Range<u64>], gap: u64, max_size: u64) -> Vec<Range<u64>> {
+    let mut sorted = ranges.to_vec();
+    sorted.sort_unstable_by_key(|range| range.start);
+
+    let mut merged: Vec<Range<u64>> = Vec::with_capacity(sorted.len());
+    for range in sorted {
+        match merged.last_mut() {
+            Some(last)
+                if range.start <= last.end.saturating_add(gap)
+                    && range.end.max(last.end) - last.start <= max_size =>
+            {
+                last.end = last.end.max(range.end);
+            }
+            _ => merged.push(range),
+        }
+    }
+    merged
+}
+
+/// An [`ObjectStore`] merging nearby ranges of [`ObjectStore::get_ranges`] calls.
+#[derive(Clone)]
+pub struct CoalescingStore {
+    inner: ObjectStoreRef,
+    config: CoalesceConfig,
+}
+
+impl CoalescingStore {
+    /// Wrap `inner`, merging ranged reads according to `config`.
+    pub fn new(inner: ObjectStoreRef, config: CoalesceConfig) -> Self {
+        Self { inner, config }
+    }
+}
+
+impl fmt::Debug for CoalescingStore {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("CoalescingStore")
+            .field("inner", &self.inner)
+            .field("config", &self.config)
+            .finish()
+    }
+}
+
+impl fmt::Display for CoalescingStore {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "CoalescingStore({})", self.inner)
+    }
+}
+
+#[async_trait::async_trait]
+impl ObjectStore for CoalescingStore {
+    async fn put_opts(
+        &self,
+        location: &Path,
+        payload: PutPayload,
+        options: PutOptions,
+    ) -> ObjectStoreResult<PutResult> {
+        self.inner.put_opts(location, payload, options).await
+    }
+
+    async fn put_multipart_opts(
+        &self,
+        location: &Path,
+        options: PutMultipartOptions,
+    ) -> ObjectStoreResult<Box<dyn MultipartUpload>> {
+        self.inner.put_multipart_opts(location, options).await
+    }
+
+    async fn get_opts(&self, location: &Path, options: GetOptions) -> ObjectStoreResult<GetResult> {
+        self.inner.get_opts(location, options).await
+    }
+
+    async fn get_ranges(
+        &self,
+        location: &Path,
+        ranges: &[Range<u64>],
+    ) -> ObjectStoreResult<Vec<Bytes>> {
+        let merged = merge_ranges(ranges, self.config.gap(), self.config.max_size());
+        tracing::trace!(
+            "Coalesced {} ranges of {location} into {} requests",
+            ranges.len(),
+            merged.len()
+        );
+        let fetched: Vec<(Range<u64>, Bytes)> = futures::stream::iter(merged)
+            .map(|range| async move {
+                let data = self.inner.get_range(location, range.clone()).await?;
+                Ok::<_, object_store::Error>((range, data))
+            })
+            .buffered(self.config.concurrency())
+            .try_collect()
+            .await?;
+
+        Ok(ranges
+            .iter()
+            .map(|range| {
+                // Merged ranges are sorted and disjoint, the last one starting at or before the
+                // requested range contains it
+                let idx = fetched.partition_point(|(merged, _)| merged.start <= range.start) - 1;
+                let (merged, data) = &fetched[idx];
+                let start = (range.start - merged.start) as usize;
+                let end = (range.end - merged.start) as usize;
+                data.slice(start..end.min(data.len()))
+            })
+            .collect())
+    }
+
+    fn delete_stream(
+        &self,
+        locations: BoxStream<'static, ObjectStoreResult<Path>>,
+    ) -> BoxStream<'static, ObjectStoreResult<Path>> {
+        self.inner.delete_stream(locations)
+    }
+
+    fn list(&self, prefix: Option<&Path>) -> BoxStream<'static, ObjectStoreResult<ObjectMeta>> {
+        self.inner.list(prefix)
+    }
+
+    fn list_with_offset(
+        &self,
+        prefix: Option<&Path>,
+        offset: &Path,
+    ) -> BoxStream<'static, ObjectStoreResult<ObjectMeta>> {
+        self.inner.list_with_offset(prefix, offset)
+    }
+
+    async fn list_with_delimiter(&self, prefix: Option<&Path>) -> ObjectStoreResult<ListResult> {
+        self.inner.list_with_delimiter(prefix).await
+    }
+
+    async fn copy_opts(
+        &self,
+        from: &Path,
+        to: &Path,
+        options: CopyOptions,
+    ) -> ObjectStoreResult<()> {
+        self.inner.copy_opts(from, to, options).await
+    }
+
+    async fn rename_opts(
+        &self,
+        from: &Path,
+        to: &Path,
+        options: RenameOptions,
+    ) -> ObjectStoreResult<()> {
+        self.inner.rename_opts(from, to, options).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use object_store::ObjectStoreExt;
+    use object_store::memory::InMemory;
+    use tokio::sync::mpsc::unbounded_channel;
+
+    use super::*;
+    use crate::test_utils::object_store::{
+        RecordedObjectStoreOperation, RecordedPathKind, RecordingObjectStore,
+        drain_recorded_object_store_operations,
+    };
+
+    #[test]
+    fn test_merge_ranges() {
+        assert_eq!(
+            merge_ranges(&[20..30, 0..10, 5..15, 100..110], 4, 1024),
+            vec![0..15, 20..30, 100..110]
+        );
+        assert_eq!(
+            merge_ranges(&[0..10, 12..20, 100..110], 10, 1024),
+            vec![0..20, 100..110]
+        );
+        assert_eq!(
+            merge_ranges(&[0..10, 10..20, 20..30], 0, 20),
+            vec![0..20, 20..30]
+        );
+        assert_eq!(merge_ranges(&[0..50, 60..70], 100, 20), vec![0..50, 60..70]);
+        assert!(merge_ranges(&[], 10, 10).is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_get_ranges_coalesced() {
+        let inner = Arc::new(InMemory::new());
+        let path = Path::from("part-00000.parquet");
+        let data: Vec<u8> = (0..=255).collect();
+        inner.put(&path, data.clone().into()).await.unwrap();
+
+        let (sender, mut operations) = unbounded_channel();
+        let recording = Arc::new(RecordingObjectStore::with_operations(inner, sender));
+        let store = CoalescingStore::new(
+            recording,
+            CoalesceConfig {
+                coalesce_gap: Some(32),
+                ..Default::default()
+            },
+        );
+
+        let ranges = [40..48, 0..8, 10..20, 8..12, 200..256];
+        let fetched = store.get_ranges(&path, &ranges).await.unwrap();
+        for (range, bytes) in ranges.iter().zip(fetched) {
+            assert_eq!(
+                bytes.as_ref(),
+                &data[range.start as usize..range.end as usize]
+            );
+        }
+        assert_eq!(
+            drain_recorded_object_store_operations(&mut operations).await,
+            vec![
+                RecordedObjectStoreOperation::GetRange(RecordedPathKind::Data, 0..48),
+                RecordedObjectStoreOperation::GetRange(RecordedPathKind::Data, 200..256),
+            ]
+        );
+    }
+}
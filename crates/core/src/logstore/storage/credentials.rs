@@ -0,0 +1,374 @@
+//! Refreshable credentials for storage backends.
+//!
+//! Tokens minted by a central service (e.g. SAS tokens or STS session credentials) usually
+//! expire after a short period of time. Instead of rebuilding the table whenever a token rotates,
+//! a [`StorageCredentialProvider`] can be attached to the [`StorageConfig`]. Before each request
+//! the [`CredentialRefreshingStore`] checks whether the current credentials are about to expire
+//! and, if so, asks the provider for fresh ones and rebuilds the backend object store.
+//!
+//! Providers can be attached programmatically via [`StorageConfig::with_credential_provider`] or
+//! registered by name via [`register_credential_provider`] and selected with the
+//! `credential_provider` storage option.
+use std::collections::HashMap;
+use std::fmt::Debug;
+use std::ops::Range;
+use std::sync::{Arc, LazyLock};
+
+use bytes::Bytes;
+use chrono::{DateTime, TimeDelta, Utc};
+use dashmap::DashMap;
+use futures::stream::BoxStream;
+use futures::{StreamExt, TryStreamExt};
+use object_store::path::Path;
+use object_store::{
+    CopyOptions, Error as ObjectStoreError, GetOptions, GetResult, ListResult, MultipartUpload,
+    ObjectMeta, ObjectStore, PutMultipartOptions, PutOptions, PutPayload, PutResult, RenameOptions,
+    Result as ObjectStoreResult,
+};
+use tokio::sync::Mutex;
+use url::Url;
+
+use super::ObjectStoreRef;
+use crate::DeltaResult;
+use crate::logstore::StorageConfig;
+use crate::logstore::factories::ObjectStoreFactory;
+
+/// Storage option used to select a provider registered via [`register_credential_provider`].
+pub const CREDENTIAL_PROVIDER_KEY: &str = "credential_provider";
+
+/// Credentials are refreshed once they expire within this window.
+const REFRESH_WINDOW: TimeDelta = TimeDelta::minutes(5);
+
+/// Credentials issued by a [`StorageCredentialProvider`].
+#[derive(Debug, Clone, Default)]
+pub struct StorageCredentials {
+    /// Storage options carrying the credentials, e.g. `azure_storage_sas_token` or
+    /// `aws_session_token`. These are merged on top of the configured storage options.
+    pub options: HashMap<String, String>,
+    /// Point in time at which the credentials expire, `None` if they do not expire.
+    pub expires_at: Option<DateTime<Utc>>,
+}
+
+/// A callback invoked to obtain fresh credentials for a storage location.
+#[async_trait::async_trait]
+pub trait StorageCredentialProvider: Debug + Send + Sync {
+    /// Issue credentials for accessing the table at `location`.
+    async fn get_credentials(&self, location: &Url) -> DeltaResult<StorageCredentials>;
+}
+
+/// Sharable reference to a [`StorageCredentialProvider`]
+pub type StorageCredentialProviderRef = Arc<dyn StorageCredentialProvider>;
+
+static CREDENTIAL_PROVIDERS: LazyLock<DashMap<String, StorageCredentialProviderRef>> =
+    LazyLock::new(DashMap::new);
+
+/// Register a named [`StorageCredentialProvider`].
+///
+/// The provider is used for tables opened with the storage option
+/// `credential_provider = "<name>"`. If a provider with the same name existed before,
+/// it is replaced and returned.
+pub fn register_credential_provider(
+    name: impl Into<String>,
+    provider: StorageCredentialProviderRef,
+) -> Option<StorageCredentialProviderRef> {
+    CREDENTIAL_PROVIDERS.insert(name.into(), provider)
+}
+
+/// Remove a previously registered [`StorageCredentialProvider`].
+pub fn deregister_credential_provider(name: &str) -> Option<StorageCredentialProviderRef> {
+    CREDENTIAL_PROVIDERS
+        .remove(name)
+        .map(|(_, provider)| provider)
+}
+
+pub(crate) fn credential_provider(name: &str) -> Option<StorageCredentialProviderRef> {
+    CREDENTIAL_PROVIDERS
+        .get(name)
+        .map(|entry| entry.value().clone())
+}
+
+struct CachedStore {
+    store: ObjectStoreRef,
+    expires_at: Option<DateTime<Utc>>,
+}
+
+impl CachedStore {
+    fn is_stale(&self) -> bool {
+        self.expires_at
+            .is_some_and(|expiry| expiry - Utc::now() <= REFRESH_WINDOW)
+    }
+}
+
+struct RefreshState {
+    factory: Arc<dyn ObjectStoreFactory>,
+    location: Url,
+    config: StorageConfig,
+    provider: StorageCredentialProviderRef,
+    cache: Mutex<Option<CachedStore>>,
+}
+
+/// An [`ObjectStore`] which rebuilds the backend store whenever its credentials expire.
+///
+/// The store points at the root of the storage location, just like the stores created by an
+/// [`ObjectStoreFactory`].
+#[derive(Clone)]
+pub struct CredentialRefreshingStore {
+    state: Arc<RefreshState>,
+}
+
+impl CredentialRefreshingStore {
+    /// Create a new store building backend stores for `location` via `factory`.
+    pub fn new(
+        factory: Arc<dyn ObjectStoreFactory>,
+        location: Url,
+        config: StorageConfig,
+        provider: StorageCredentialProviderRef,
+    ) -> Self {
+        Self {
+            state: Arc::new(RefreshState {
+                factory,
+                location,
+                config,
+                provider,
+                cache: Mutex::new(None),
+            }),
+        }
+    }
+
+    /// Get the current backend store, refreshing credentials if required.
+    async fn store(&self) -> ObjectStoreResult<ObjectStoreRef> {
+        let state = &self.state;
+        let mut cache = state.cache.lock().await;
+        if let Some(cached) = cache.as_ref()
+            && !cached.is_stale()
+        {
+            return Ok(cached.store.clone());
+        }
+
+        tracing::debug!("Refreshing storage credentials for {}", state.location);
+        let credentials = state
+            .provider
+            .get_credentials(&state.location)
+            .await
+            .map_err(into_object_store_error)?;
+
+        let mut config = state.config.clone();
+        for (key, value) in credentials.options {
+            config.raw.insert(key.clone(), value.clone());
+            config.unknown_properties.insert(key, value);
+        }
+        let (store, _) = state
+            .factory
+            .parse_url_opts(&state.location, &config)
+            .map_err(into_object_store_error)?;
+
+        *cache = Some(CachedStore {
+            store: store.clone(),
+            expires_at: credentials.expires_at,
+        });
+        Ok(store)
+    }
+}
+
+fn into_object_store_error(err: crate::DeltaTableError) -> ObjectStoreError {
+    ObjectStoreError::Generic {
+        store: "CredentialRefreshingStore",
+        source: Box::new(err),
+    }
+}
+
+impl std::fmt::Debug for CredentialRefreshingStore {
+    fn fmt(&self, fmt: &mut std::fmt::Formatter<'_>) -> Result<(), std::fmt::Error> {
+        fmt.debug_struct("CredentialRefreshingStore")
+            .field("location", &self.state.location.as_str())
+            .field("provider", &self.state.provider)
+            .finish_non_exhaustive()
+    }
+}
+
+impl std::fmt::Display for CredentialRefreshingStore {
+    fn fmt(&self, fmt: &mut std::fmt::Formatter<'_>) -> Result<(), std::fmt::Error> {
+        write!(fmt, "CredentialRefreshingStore({})", self.state.location)
+    }
+}
+
+#[async_trait::async_trait]
+impl ObjectStore for CredentialRefreshingStore {
+    async fn put_opts(
+        &self,
+        location: &Path,
+        bytes: PutPayload,
+        options: PutOptions,
+    ) -> ObjectStoreResult<PutResult> {
+        self.store().await?.put_opts(location, bytes, options).await
+    }
+
+    async fn put_multipart_opts(
+        &self,
+        location: &Path,
+        options: PutMultipartOptions,
+    ) -> ObjectStoreResult<Box<dyn MultipartUpload>> {
+        self.store()
+            .await?
+            .put_multipart_opts(location, options)
+            .await
+    }
+
+    async fn get_opts(&self, location: &Path, options: GetOptions) -> ObjectStoreResult<GetResult> {
+        self.store().await?.get_opts(location, options).await
+    }
+
+    async fn get_ranges(
+        &self,
+        location: &Path,
+        ranges: &[Range<u64>],
+    ) -> ObjectStoreResult<Vec<Bytes>> {
+        self.store().await?.get_ranges(location, ranges).await
+    }
+
+    fn delete_stream(
+        &self,
+        locations: BoxStream<'static, ObjectStoreResult<Path>>,
+    ) -> BoxStream<'static, ObjectStoreResult<Path>> {
+        let this = self.clone();
+        futures::stream::once(async move { this.store().await })
+            .map_ok(move |store| store.delete_stream(locations))
+            .try_flatten()
+            .boxed()
+    }
+
+    fn list(&self, prefix: Option<&Path>) -> BoxStream<'static, ObjectStoreResult<ObjectMeta>> {
+        let this = self.clone();
+        let prefix = prefix.cloned();
+        futures::stream::once(async move { this.store().await })
+            .map_ok(move |store| store.list(prefix.as_ref()))
+            .try_flatten()
+            .boxed()
+    }
+
+    fn list_with_offset(
+        &self,
+        prefix: Option<&Path>,
+        offset: &Path,
+    ) -> BoxStream<'static, ObjectStoreResult<ObjectMeta>> {
+        let this = self.clone();
+        let prefix = prefix.cloned();
+        let offset = offset.clone();
+        futures::stream::once(async move { this.store().await })
+            .map_ok(move |store| store.list_with_offset(prefix.as_ref(), &offset))
+            .try_flatten()
+            .boxed()
+    }
+
+    async fn list_with_delimiter(&self, prefix: Option<&Path>) -> ObjectStoreResult<ListResult> {
+        self.store().await?.list_with_delimiter(prefix).await
+    }
+
+    async fn copy_opts(
+        &self,
+        from: &Path,
+        to: &Path,
+        options: CopyOptions,
+    ) -> ObjectStoreResult<()> {
+        self.store().await?.copy_opts(from, to, options).await
+    }
+
+    async fn rename_opts(
+        &self,
+        from: &Path,
+        to: &Path,
+        options: RenameOptions,
+    ) -> ObjectStoreResult<()> {
+        self.store().await?.rename_opts(from, to, options).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    use object_store::ObjectStoreExt;
+
+    use super::*;
+    use crate::logstore::object_store_factories;
+
+    #[derive(Debug)]
+    struct CountingProvider {
+        calls: AtomicUsize,
+        expires_in: TimeDelta,
+    }
+
+    #[async_trait::async_trait]
+    impl StorageCredentialProvider for CountingProvider {
+        async fn get_credentials(&self, _location: &Url) -> DeltaResult<StorageCredentials> {
+            let call = self.calls.fetch_add(1, Ordering::SeqCst);
+            Ok(StorageCredentials {
+                options: HashMap::from([("token".to_string(), format!("token-{call}"))]),
+                expires_at: Some(Utc::now() + self.expires_in),
+            })
+        }
+    }
+
+    fn memory_store(expires_in: TimeDelta) -> (CredentialRefreshingStore, Arc<CountingProvider>) {
+        let location = Url::parse("memory:///").unwrap();
+        let scheme = Url::parse("memory://").unwrap();
+        let factory = object_store_factories()
+            .get(&scheme)
+            .unwrap()
+            .value()
+            .clone();
+        let provider = Arc::new(CountingProvider {
+            calls: AtomicUsize::new(0),
+            expires_in,
+        });
+        let store = CredentialRefreshingStore::new(
+            factory,
+            location,
+            StorageConfig::default(),
+            provider.clone(),
+        );
+        (store, provider)
+    }
+
+    #[tokio::test]
+    async fn test_valid_credentials_are_reused() {
+        let (store, provider) = memory_store(TimeDelta::hours(1));
+        store
+            .put(&Path::from("a"), PutPayload::from_static(b"a"))
+            .await
+            .unwrap();
+        store.head(&Path::from("a")).await.unwrap();
+        let listed: Vec<_> = store.list(None).try_collect().await.unwrap();
+        assert_eq!(listed.len(), 1);
+        assert_eq!(provider.calls.load(Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test]
+    async fn test_expiring_credentials_are_refreshed() {
+        let (store, provider) = memory_store(TimeDelta::minutes(1));
+        store.list_with_delimiter(None).await.unwrap();
+        store.list_with_delimiter(None).await.unwrap();
+        assert_eq!(provider.calls.load(Ordering::SeqCst), 2);
+    }
+
+    #[test]
+    fn test_named_provider_from_options() {
+        let provider = Arc::new(CountingProvider {
+            calls: AtomicUsize::new(0),
+            expires_in: TimeDelta::hours(1),
+        });
+        register_credential_provider("test-provider", provider);
+        let config =
+            StorageConfig::parse_options([(CREDENTIAL_PROVIDER_KEY, "test-provider")]).unwrap();
+        assert!(config.credential_provider.is_some());
+        assert!(
+            !config
+                .unknown_properties
+                .contains_key(CREDENTIAL_PROVIDER_KEY)
+        );
+
+        let result = StorageConfig::parse_options([(CREDENTIAL_PROVIDER_KEY, "missing")]);
+        assert!(result.is_err());
+        deregister_credential_provider("test-provider");
+    }
+}
@@ -0,0 +1,258 @@
+//! Read-through fallback to a secondary location for data files.
+//!
+//! Data files of large tables are sometimes moved to an archive tier or only replicated to
+//! another region. [`FallbackStore`] reads data files missing from the table location from a
+//! secondary read-only location holding the same relative paths. The transaction log is always
+//! read from the table location, and writes, listings and deletes never touch the fallback.
+use std::fmt;
+
+use deltalake_derive::DeltaConfig;
+use futures::stream::BoxStream;
+use object_store::path::Path;
+use object_store::{
+    CopyOptions, Error as ObjectStoreError, GetOptions, GetResult, ListResult, MultipartUpload,
+    ObjectMeta, ObjectStore, PutMultipartOptions, PutOptions, PutPayload, PutResult, RenameOptions,
+    Result as ObjectStoreResult,
+};
+use tracing::debug;
+use url::Url;
+
+use super::{DELTA_LOG_PATH, ObjectStoreRef};
+use crate::{DeltaResult, DeltaTableError};
+
+/// Configuration of the secondary location data files are read from when they are missing.
+#[derive(Debug, Clone, Default, DeltaConfig)]
+pub struct FallbackConfig {
+    /// URL of a copy of the table, e.g. an archive bucket or a replica in another region.
+    ///
+    /// The location is accessed with the same storage options as the table itself.
+    #[delta(env = "DELTA_FALLBACK_URL")]
+    pub fallback_url: Option<String>,
+}
+
+impl FallbackConfig {
+    /// Parsed URL of the fallback location, if one is configured.
+    pub fn url(&self) -> DeltaResult<Option<Url>> {
+        self.fallback_url
+            .as_deref()
+            .map(|url| {
+                Url::parse(url).map_err(|e| {
+                    DeltaTableError::Generic(format!("Invalid fallback_url '{url}': {e}"))
+                })
+            })
+            .transpose()
+    }
+}
+
+/// An [`ObjectStore`] reading objects missing from a primary store from a fallback store.
+///
+/// Both stores are rooted at the root of their bucket or filesystem. Paths below
+/// `primary_prefix` are mapped to the same relative path below `fallback_prefix`. Objects in the
+/// `_delta_log` directory and outside of `primary_prefix` are only read from the primary store.
+#[derive(Debug, Clone)]
+pub struct FallbackStore {
+    primary: ObjectStoreRef,
+    primary_prefix: Path,
+    fallback: ObjectStoreRef,
+    fallback_prefix: Path,
+}
+
+impl FallbackStore {
+    /// Wrap `primary`, reading missing objects below `primary_prefix` from `fallback`.
+    pub fn new(
+        primary: ObjectStoreRef,
+        primary_prefix: Path,
+        fallback: ObjectStoreRef,
+        fallback_prefix: Path,
+    ) -> Self {
+        Self {
+            primary,
+            primary_prefix,
+            fallback,
+            fallback_prefix,
+        }
+    }
+
+    /// Location of `location` in the fallback store, if it may be read from there.
+    fn fallback_location(&self, location: &Path) -> Option<Path> {
+        let mut parts = location.prefix_match(&self.primary_prefix)?.peekable();
+        if parts
+            .peek()
+            .is_none_or(|first| first.as_ref() == DELTA_LOG_PATH.as_ref())
+        {
+            return None;
+        }
+        Some(parts.fold(self.fallback_prefix.clone(), |path, part| path.join(part)))
+    }
+}
+
+impl fmt::Display for FallbackStore {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "FallbackStore({}, {})", self.primary, self.fallback)
+    }
+}
+
+#[async_trait::async_trait]
+impl ObjectStore for FallbackStore {
+    async fn put_opts(
+        &self,
+        location: &Path,
+        payload: PutPayload,
+        options: PutOptions,
+    ) -> ObjectStoreResult<PutResult> {
+        self.primary.put_opts(location, payload, options).await
+    }
+
+    async fn put_multipart_opts(
+        &self,
+        location: &Path,
+        options: PutMultipartOptions,
+    ) -> ObjectStoreResult<Box<dyn MultipartUpload>> {
+        self.primary.put_multipart_opts(location, options).await
+    }
+
+    async fn get_opts(&self, location: &Path, options: GetOptions) -> ObjectStoreResult<GetResult> {
+        match self.primary.get_opts(location, options.clone()).await {
+            Err(ObjectStoreError::NotFound { path, source }) => {
+                let Some(fallback_location) = self.fallback_location(location) else {
+                    return Err(ObjectStoreError::NotFound { path, source });
+                };
+                debug!("{location} not found, reading {fallback_location} from fallback store");
+                let mut result = self.fallback.get_opts(&fallback_location, options).await?;
+                result.meta.location = location.clone();
+                Ok(result)
+            }
+            result => result,
+        }
+    }
+
+    fn delete_stream(
+        &self,
+        locations: BoxStream<'static, ObjectStoreResult<Path>>,
+    ) -> BoxStream<'static, ObjectStoreResult<Path>> {
+        self.primary.delete_stream(locations)
+    }
+
+    fn list(&self, prefix: Option<&Path>) -> BoxStream<'static, ObjectStoreResult<ObjectMeta>> {
+        self.primary.list(prefix)
+    }
+
+    fn list_with_offset(
+        &self,
+        prefix: Option<&Path>,
+        offset: &Path,
+    ) -> BoxStream<'static, ObjectStoreResult<ObjectMeta>> {
+        self.primary.list_with_offset(prefix, offset)
+    }
+
+    async fn list_with_delimiter(&self, prefix: Option<&Path>) -> ObjectStoreResult<ListResult> {
+        self.primary.list_with_delimiter(prefix).await
+    }
+
+    async fn copy_opts(
+        &self,
+        from: &Path,
+        to: &Path,
+        options: CopyOptions,
+    ) -> ObjectStoreResult<()> {
+        self.primary.copy_opts(from, to, options).await
+    }
+
+    async fn rename_opts(
+        &self,
+        from: &Path,
+        to: &Path,
+        options: RenameOptions,
+    ) -> ObjectStoreResult<()> {
+        self.primary.rename_opts(from, to, options).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use futures::StreamExt;
+    use object_store::ObjectStoreExt;
+    use object_store::memory::InMemory;
+
+    use super::*;
+
+    async fn stores() -> (ObjectStoreRef, ObjectStoreRef, FallbackStore) {
+        let primary: ObjectStoreRef = Arc::new(InMemory::new());
+        let fallback: ObjectStoreRef = Arc::new(InMemory::new());
+        for path in [
+            "archive/table/part-0.parquet",
+            "archive/table/_delta_log/0.json",
+        ] {
+            fallback
+                .put(&Path::from(path), "archived".into())
+                .await
+                .unwrap();
+        }
+        let store = FallbackStore::new(
+            primary.clone(),
+            Path::from("table"),
+            fallback.clone(),
+            Path::from("archive/table"),
+        );
+        (primary, fallback, store)
+    }
+
+    #[tokio::test]
+    async fn test_missing_data_file_is_read_from_fallback() {
+        let (primary, _, store) = stores().await;
+        let location = Path::from("table/part-0.parquet");
+        let result = store.get(&location).await.unwrap();
+        assert_eq!(result.meta.location, location);
+        assert_eq!(result.bytes().await.unwrap().as_ref(), b"archived");
+        assert_eq!(store.head(&location).await.unwrap().size, 8);
+
+        primary.put(&location, "primary".into()).await.unwrap();
+        let data = store.get(&location).await.unwrap().bytes().await.unwrap();
+        assert_eq!(data.as_ref(), b"primary");
+    }
+
+    #[tokio::test]
+    async fn test_log_and_foreign_paths_are_not_read_from_fallback() {
+        let (_, _, store) = stores().await;
+        for path in ["table/_delta_log/0.json", "other/part-0.parquet"] {
+            let err = store.head(&Path::from(path)).await.unwrap_err();
+            assert!(matches!(err, ObjectStoreError::NotFound { .. }));
+        }
+    }
+
+    #[tokio::test]
+    async fn test_writes_and_listings_only_use_primary() {
+        let (_, fallback, store) = stores().await;
+        store
+            .put(&Path::from("table/part-1.parquet"), "new".into())
+            .await
+            .unwrap();
+        assert!(
+            fallback
+                .head(&Path::from("archive/table/part-1.parquet"))
+                .await
+                .is_err()
+        );
+        let listed: Vec<_> = store
+            .list(None)
+            .map(|meta| meta.unwrap().location)
+            .collect()
+            .await;
+        assert_eq!(listed, vec![Path::from("table/part-1.parquet")]);
+    }
+
+    #[test]
+    fn test_fallback_url() {
+        let config = FallbackConfig {
+            fallback_url: Some("s3://archive/table".into()),
+        };
+        assert_eq!(config.url().unwrap().unwrap().host_str(), Some("archive"));
+        assert!(FallbackConfig::default().url().unwrap().is_none());
+        let config = FallbackConfig {
+            fallback_url: Some("not a url".into()),
+        };
+        assert!(config.url().is_err());
+    }
+}
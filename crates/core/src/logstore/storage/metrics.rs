@@ -0,0 +1,603 @@
+//! Instrumentation of object store requests and commit attempts.
+//!
+//! Object store requests are the main driver of storage cost and latency. When a
+//! [`StorageMetricsRecorder`] is attached via [`StorageConfig::with_metrics_recorder`], every
+//! request sent to the backend is reported to it, along with every attempt to write a commit.
+//! [`IoMetrics`] is a recorder aggregating counters in memory.
+//!
+//! Each request is also emitted as a `debug` event on the `deltalake::io` target. Since table
+//! operations run within tracing spans (e.g. `write_operation`), a subscriber can attribute the
+//! requests to the operation which issued them.
+//!
+//! Requests are recorded as seen by the backend store, i.e. cache hits are not counted and
+//! ranged reads merged by the [`CoalescingStore`](super::CoalescingStore) count once. Retries
+//! performed by the HTTP client of the backend are not visible, a failed request retried by
+//! delta-rs itself is recorded once per attempt.
+//!
+//! [`StorageConfig::with_metrics_recorder`]: crate::logstore::StorageConfig::with_metrics_recorder
+use std::collections::HashMap;
+use std::fmt;
+use std::future::Future;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use futures::StreamExt;
+use futures::stream::BoxStream;
+use object_store::path::Path;
+use object_store::{
+    CopyOptions, GetOptions, GetResult, ListResult, MultipartUpload, ObjectMeta, ObjectStore,
+    PutMultipartOptions, PutOptions, PutPayload, PutResult, RenameOptions,
+    Result as ObjectStoreResult, UploadPart,
+};
+use parking_lot::Mutex;
+
+use super::ObjectStoreRef;
+use crate::kernel::Version;
+
+/// Kind of a request sent to an object store.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum StorageRequestKind {
+    /// Read of (a range of) an object.
+    Get,
+    /// Read of the metadata of an object.
+    Head,
+    /// Single request write of an object.
+    Put,
+    /// Start of a multipart upload.
+    CreateMultipart,
+    /// Upload of a single part of a multipart upload.
+    PutPart,
+    /// Completion of a multipart upload.
+    CompleteMultipart,
+    /// Abort of a multipart upload.
+    AbortMultipart,
+    /// Listing of objects, possibly spanning several pages.
+    List,
+    /// Deletion of a single object.
+    Delete,
+    /// Server side copy of an object.
+    Copy,
+    /// Rename of an object.
+    Rename,
+}
+
+impl fmt::Display for StorageRequestKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Self::Get => "get",
+            Self::Head => "head",
+            Self::Put => "put",
+            Self::CreateMultipart => "create_multipart",
+            Self::PutPart => "put_part",
+            Self::CompleteMultipart => "complete_multipart",
+            Self::AbortMultipart => "abort_multipart",
+            Self::List => "list",
+            Self::Delete => "delete",
+            Self::Copy => "copy",
+            Self::Rename => "rename",
+        };
+        write!(f, "{name}")
+    }
+}
+
+/// A completed object store request.
+#[derive(Debug, Clone)]
+pub struct StorageRequest {
+    /// Kind of the request.
+    pub kind: StorageRequestKind,
+    /// Location the request was sent for, the prefix for listings.
+    pub location: Option<Path>,
+    /// Bytes read or written by the request.
+    pub bytes: u64,
+    /// Time between sending the request and receiving its response.
+    pub duration: Duration,
+    /// Whether the request succeeded.
+    pub success: bool,
+}
+
+/// Outcome of an attempt to write a commit.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CommitAttemptOutcome {
+    /// The commit was written.
+    Committed,
+    /// Another writer committed the same version first, the commit is retried.
+    VersionExists,
+    /// The commit failed with any other error.
+    Failed,
+}
+
+/// A completed attempt to write a commit to the log.
+#[derive(Debug, Clone)]
+pub struct CommitAttempt {
+    /// Version the commit was attempted for.
+    pub version: Version,
+    /// Number of the attempt, starting at 1.
+    pub attempt: usize,
+    /// Time spent writing the commit entry.
+    pub duration: Duration,
+    /// Outcome of the attempt.
+    pub outcome: CommitAttemptOutcome,
+}
+
+/// Receives the object store requests and commit attempts of a table.
+pub trait StorageMetricsRecorder: fmt::Debug + Send + Sync {
+    /// Record a completed object store request.
+    fn record_request(&self, request: &StorageRequest);
+
+    /// Record a completed attempt to write a commit.
+    fn record_commit_attempt(&self, _attempt: &CommitAttempt) {}
+}
+
+/// Sharable reference to a [`StorageMetricsRecorder`]
+pub type StorageMetricsRecorderRef = Arc<dyn StorageMetricsRecorder>;
+
+/// Aggregated statistics of one kind of request.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct RequestStats {
+    /// Number of requests sent.
+    pub requests: u64,
+    /// Number of failed requests.
+    pub errors: u64,
+    /// Total bytes read or written.
+    pub bytes: u64,
+    /// Total time spent waiting for responses.
+    pub duration: Duration,
+}
+
+/// Aggregated statistics of commit attempts.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct CommitStats {
+    /// Number of attempts to write a commit.
+    pub attempts: u64,
+    /// Number of attempts which lost against a concurrent writer and were retried.
+    pub retries: u64,
+    /// Number of attempts which failed.
+    pub failures: u64,
+    /// Total time spent writing commit entries.
+    pub duration: Duration,
+}
+
+/// A [`StorageMetricsRecorder`] aggregating request and commit statistics in memory.
+#[derive(Debug, Default)]
+pub struct IoMetrics {
+    requests: Mutex<HashMap<StorageRequestKind, RequestStats>>,
+    commits: Mutex<CommitStats>,
+}
+
+impl IoMetrics {
+    /// Statistics of the requests recorded so far, by kind of request.
+    pub fn requests(&self) -> HashMap<StorageRequestKind, RequestStats> {
+        self.requests.lock().clone()
+    }
+
+    /// Statistics of the commit attempts recorded so far.
+    pub fn commits(&self) -> CommitStats {
+        *self.commits.lock()
+    }
+
+    /// Reset all statistics, e.g. between two table operations.
+    pub fn reset(&self) {
+        self.requests.lock().clear();
+        *self.commits.lock() = CommitStats::default();
+    }
+}
+
+impl StorageMetricsRecorder for IoMetrics {
+    fn record_request(&self, request: &StorageRequest) {
+        let mut requests = self.requests.lock();
+        let stats = requests.entry(request.kind).or_default();
+        stats.requests += 1;
+        stats.errors += u64::from(!request.success);
+        stats.bytes += request.bytes;
+        stats.duration += request.duration;
+    }
+
+    fn record_commit_attempt(&self, attempt: &CommitAttempt) {
+        let mut commits = self.commits.lock();
+        commits.attempts += 1;
+        match attempt.outcome {
+            CommitAttemptOutcome::Committed => {}
+            CommitAttemptOutcome::VersionExists => commits.retries += 1,
+            CommitAttemptOutcome::Failed => commits.failures += 1,
+        }
+        commits.duration += attempt.duration;
+    }
+}
+
+fn record(recorder: &dyn StorageMetricsRecorder, request: StorageRequest) {
+    tracing::debug!(
+        target: "deltalake::io",
+        kind = %request.kind,
+        location = request.location.as_ref().map(|l| l.as_ref()).unwrap_or_default(),
+        bytes = request.bytes,
+        duration_ms = request.duration.as_millis() as u64,
+        success = request.success,
+        "object store request"
+    );
+    recorder.record_request(&request);
+}
+
+/// Time `request`, reporting it to `recorder` once it completes.
+async fn instrumented<T>(
+    recorder: &dyn StorageMetricsRecorder,
+    kind: StorageRequestKind,
+    location: Option<&Path>,
+    request: impl Future<Output = ObjectStoreResult<T>>,
+    bytes: impl FnOnce(&T) -> u64,
+) -> ObjectStoreResult<T> {
+    let started = Instant::now();
+    let result = request.await;
+    record(
+        recorder,
+        StorageRequest {
+            kind,
+            location: location.cloned(),
+            bytes: result.as_ref().map(bytes).unwrap_or_default(),
+            duration: started.elapsed(),
+            success: result.is_ok(),
+        },
+    );
+    result
+}
+
+/// Records a listing once its stream is exhausted or dropped.
+struct ListGuard {
+    recorder: StorageMetricsRecorderRef,
+    prefix: Option<Path>,
+    started: Instant,
+    success: bool,
+}
+
+impl Drop for ListGuard {
+    fn drop(&mut self) {
+        record(
+            self.recorder.as_ref(),
+            StorageRequest {
+                kind: StorageRequestKind::List,
+                location: self.prefix.take(),
+                bytes: 0,
+                duration: self.started.elapsed(),
+                success: self.success,
+            },
+        );
+    }
+}
+
+/// An [`ObjectStore`] reporting all requests sent to the inner store to a
+/// [`StorageMetricsRecorder`].
+#[derive(Clone)]
+pub struct InstrumentedStore {
+    inner: ObjectStoreRef,
+    recorder: StorageMetricsRecorderRef,
+}
+
+impl InstrumentedStore {
+    /// Wrap `inner`, reporting its requests to `recorder`.
+    pub fn new(inner: ObjectStoreRef, recorder: StorageMetricsRecorderRef) -> Self {
+        Self { inner, recorder }
+    }
+
+    fn instrumented_list(
+        &self,
+        prefix: Option<&Path>,
+        stream: BoxStream<'static, ObjectStoreResult<ObjectMeta>>,
+    ) -> BoxStream<'static, ObjectStoreResult<ObjectMeta>> {
+        let mut guard = ListGuard {
+            recorder: self.recorder.clone(),
+            prefix: prefix.cloned(),
+            started: Instant::now(),
+            success: true,
+        };
+        stream
+            .map(move |item| {
+                guard.success &= item.is_ok();
+                item
+            })
+            .boxed()
+    }
+}
+
+impl fmt::Debug for InstrumentedStore {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("InstrumentedStore")
+            .field("inner", &self.inner)
+            .field("recorder", &self.recorder)
+            .finish()
+    }
+}
+
+impl fmt::Display for InstrumentedStore {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "InstrumentedStore({})", self.inner)
+    }
+}
+
+/// A [`MultipartUpload`] reporting the upload of its parts.
+#[derive(Debug)]
+struct InstrumentedUpload {
+    inner: Box<dyn MultipartUpload>,
+    location: Path,
+    recorder: StorageMetricsRecorderRef,
+}
+
+#[async_trait::async_trait]
+impl MultipartUpload for InstrumentedUpload {
+    fn put_part(&mut self, data: PutPayload) -> UploadPart {
+        let bytes = data.content_length() as u64;
+        let part = self.inner.put_part(data);
+        let recorder = self.recorder.clone();
+        let location = self.location.clone();
+        Box::pin(async move {
+            instrumented(
+                recorder.as_ref(),
+                StorageRequestKind::PutPart,
+                Some(&location),
+                part,
+                |_| bytes,
+            )
+            .await
+        })
+    }
+
+    async fn complete(&mut self) -> ObjectStoreResult<PutResult> {
+        instrumented(
+            self.recorder.as_ref(),
+            StorageRequestKind::CompleteMultipart,
+            Some(&self.location),
+            self.inner.complete(),
+            |_| 0,
+        )
+        .await
+    }
+
+    async fn abort(&mut self) -> ObjectStoreResult<()> {
+        instrumented(
+            self.recorder.as_ref(),
+            StorageRequestKind::AbortMultipart,
+            Some(&self.location),
+            self.inner.abort(),
+            |_| 0,
+        )
+        .await
+    }
+}
+
+#[async_trait::async_trait]
+impl ObjectStore for InstrumentedStore {
+    async fn put_opts(
+        &self,
+        location: &Path,
+        payload: PutPayload,
+        options: PutOptions,
+    ) -> ObjectStoreResult<PutResult> {
+        let bytes = payload.content_length() as u64;
+        instrumented(
+            self.recorder.as_ref(),
+            StorageRequestKind::Put,
+            Some(location),
+            self.inner.put_opts(location, payload, options),
+            |_| bytes,
+        )
+        .await
+    }
+
+    async fn put_multipart_opts(
+        &self,
+        location: &Path,
+        options: PutMultipartOptions,
+    ) -> ObjectStoreResult<Box<dyn MultipartUpload>> {
+        let upload = instrumented(
+            self.recorder.as_ref(),
+            StorageRequestKind::CreateMultipart,
+            Some(location),
+            self.inner.put_multipart_opts(location, options),
+            |_| 0,
+        )
+        .await?;
+        Ok(Box::new(InstrumentedUpload {
+            inner: upload,
+            location: location.clone(),
+            recorder: self.recorder.clone(),
+        }))
+    }
+
+    async fn get_opts(&self, location: &Path, options: GetOptions) -> ObjectStoreResult<GetResult> {
+        let kind = if options.head {
+            StorageRequestKind::Head
+        } else {
+            StorageRequestKind::Get
+        };
+        instrumented(
+            self.recorder.as_ref(),
+            kind,
+            Some(location),
+            self.inner.get_opts(location, options),
+            |result| match kind {
+                StorageRequestKind::Head => 0,
+                _ => result.range.end - result.range.start,
+            },
+        )
+        .await
+    }
+
+    fn delete_stream(
+        &self,
+        locations: BoxStream<'static, ObjectStoreResult<Path>>,
+    ) -> BoxStream<'static, ObjectStoreResult<Path>> {
+        // Bulk deletes report their results per location, the time between two results is
+        // attributed to the latter.
+        let recorder = self.recorder.clone();
+        let mut last = Instant::now();
+        self.inner
+            .delete_stream(locations)
+            .map(move |result| {
+                let now = Instant::now();
+                record(
+                    recorder.as_ref(),
+                    StorageRequest {
+                        kind: StorageRequestKind::Delete,
+                        location: result.as_ref().ok().cloned(),
+                        bytes: 0,
+                        duration: now - last,
+                        success: result.is_ok(),
+                    },
+                );
+                last = now;
+                result
+            })
+            .boxed()
+    }
+
+    fn list(&self, prefix: Option<&Path>) -> BoxStream<'static, ObjectStoreResult<ObjectMeta>> {
+        self.instrumented_list(prefix, self.inner.list(prefix))
+    }
+
+    fn list_with_offset(
+        &self,
+        prefix: Option<&Path>,
+        offset: &Path,
+    ) -> BoxStream<'static, ObjectStoreResult<ObjectMeta>> {
+        self.instrumented_list(prefix, self.inner.list_with_offset(prefix, offset))
+    }
+
+    async fn list_with_delimiter(&self, prefix: Option<&Path>) -> ObjectStoreResult<ListResult> {
+        instrumented(
+            self.recorder.as_ref(),
+            StorageRequestKind::List,
+            prefix,
+            self.inner.list_with_delimiter(prefix),
+            |_| 0,
+        )
+        .await
+    }
+
+    async fn copy_opts(
+        &self,
+        from: &Path,
+        to: &Path,
+        options: CopyOptions,
+    ) -> ObjectStoreResult<()> {
+        instrumented(
+            self.recorder.as_ref(),
+            StorageRequestKind::Copy,
+            Some(to),
+            self.inner.copy_opts(from, to, options),
+            |_| 0,
+        )
+        .await
+    }
+
+    async fn rename_opts(
+        &self,
+        from: &Path,
+        to: &Path,
+        options: RenameOptions,
+    ) -> ObjectStoreResult<()> {
+        instrumented(
+            self.recorder.as_ref(),
+            StorageRequestKind::Rename,
+            Some(to),
+            self.inner.rename_opts(from, to, options),
+            |_| 0,
+        )
+        .await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use object_store::ObjectStoreExt;
+    use object_store::memory::InMemory;
+
+    use super::*;
+
+    #[tokio::test]
+    async fn test_requests_are_recorded() {
+        let metrics = Arc::new(IoMetrics::default());
+        let store = InstrumentedStore::new(Arc::new(InMemory::new()), metrics.clone());
+        let path = Path::from("part-00000.parquet");
+
+        store.put(&path, "hello world".into()).await.unwrap();
+        store.get_range(&path, 0..5).await.unwrap();
+        store.head(&path).await.unwrap();
+        assert!(store.head(&Path::from("missing")).await.is_err());
+        assert_eq!(store.list(None).count().await, 1);
+
+        let requests = metrics.requests();
+        assert_eq!(requests[&StorageRequestKind::Put].bytes, 11);
+        assert_eq!(requests[&StorageRequestKind::Get].bytes, 5);
+        assert_eq!(requests[&StorageRequestKind::Head].requests, 2);
+        assert_eq!(requests[&StorageRequestKind::Head].errors, 1);
+        assert_eq!(requests[&StorageRequestKind::List].requests, 1);
+
+        metrics.reset();
+        assert!(metrics.requests().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_multipart_parts_are_recorded() {
+        let metrics = Arc::new(IoMetrics::default());
+        let store = InstrumentedStore::new(Arc::new(InMemory::new()), metrics.clone());
+
+        let mut upload = store
+            .put_multipart(&Path::from("part-00000.parquet"))
+            .await
+            .unwrap();
+        upload.put_part(vec![0_u8; 10].into()).await.unwrap();
+        upload.put_part(vec![0_u8; 5].into()).await.unwrap();
+        upload.complete().await.unwrap();
+
+        let requests = metrics.requests();
+        assert_eq!(requests[&StorageRequestKind::CreateMultipart].requests, 1);
+        assert_eq!(requests[&StorageRequestKind::PutPart].requests, 2);
+        assert_eq!(requests[&StorageRequestKind::PutPart].bytes, 15);
+        assert_eq!(requests[&StorageRequestKind::CompleteMultipart].requests, 1);
+    }
+
+    #[tokio::test]
+    async fn test_table_operations_are_recorded() {
+        use crate::kernel::{DataType, StructField};
+        use crate::logstore::{StorageConfig, logstore_for};
+        use crate::{DeltaTable, DeltaTableConfig};
+
+        let metrics = Arc::new(IoMetrics::default());
+        let location = url::Url::parse("memory:///").unwrap();
+        let config = StorageConfig::default().with_metrics_recorder(metrics.clone());
+        let table = DeltaTable::new(
+            logstore_for(&location, config).unwrap(),
+            DeltaTableConfig::default(),
+        );
+        table
+            .create()
+            .with_columns(vec![StructField::nullable("id", DataType::LONG)])
+            .await
+            .unwrap();
+
+        assert_eq!(metrics.commits().attempts, 1);
+        assert!(metrics.requests()[&StorageRequestKind::Put].requests >= 1);
+    }
+
+    #[test]
+    fn test_commit_attempts_are_recorded() {
+        let metrics = IoMetrics::default();
+        for (attempt, outcome) in [
+            CommitAttemptOutcome::VersionExists,
+            CommitAttemptOutcome::Committed,
+        ]
+        .into_iter()
+        .enumerate()
+        {
+            metrics.record_commit_attempt(&CommitAttempt {
+                version: 1 + attempt as Version,
+                attempt: attempt + 1,
+                duration: Duration::from_millis(10),
+                outcome,
+            });
+        }
+        let commits = metrics.commits();
+        assert_eq!(commits.attempts, 2);
+        assert_eq!(commits.retries, 1);
+        assert_eq!(commits.failures, 0);
+        assert_eq!(commits.duration, Duration::from_millis(20));
+    }
+}
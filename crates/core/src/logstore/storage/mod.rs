@@ -11,11 +11,36 @@ use url::Url;
 use crate::table::normalize_table_url;
 use crate::{DeltaResult, DeltaTableError};
 
+#[cfg(feature = "delta-cache")]
+pub use cache::{CacheConfig, CachingStore};
+pub use coalesce::{CoalesceConfig, CoalescingStore};
+pub use credentials::{
+    CREDENTIAL_PROVIDER_KEY, CredentialRefreshingStore, StorageCredentialProvider,
+    StorageCredentialProviderRef, StorageCredentials, deregister_credential_provider,
+    register_credential_provider,
+};
+pub use fallback::{FallbackConfig, FallbackStore};
+pub use metrics::{
+    CommitAttempt, CommitAttemptOutcome, CommitStats, InstrumentedStore, IoMetrics, RequestStats,
+    StorageMetricsRecorder, StorageMetricsRecorderRef, StorageRequest, StorageRequestKind,
+};
+pub use multipart::{MultipartConfig, MultipartWriter};
 pub use retry_ext::ObjectStoreRetryExt;
 pub use runtime::{DeltaIOStorageBackend, IORuntime};
-
+pub use throttle::{ThrottleConfig, ThrottledStore};
+pub(crate) use tracking::WrittenFiles;
+
+#[cfg(feature = "delta-cache")]
+pub(super) mod cache;
+pub(super) mod coalesce;
+pub(super) mod credentials;
+pub(super) mod fallback;
+pub(super) mod metrics;
+pub(super) mod multipart;
 pub(super) mod retry_ext;
 pub(super) mod runtime;
+pub(super) mod throttle;
+pub(super) mod tracking;
 pub(super) mod utils;
 
 static DELTA_LOG_PATH: LazyLock<Path> = LazyLock::new(|| Path::from("_delta_log"));
@@ -104,12 +129,28 @@ pub struct LimitConfig {
 #[derive(Debug, Clone, Default, DeltaConfig)]
 pub struct CertificateConfig {
     /// Path to a PEM-encoded root certificate file for TLS connections.
-    #[delta(env = "SSL_CERT_FILE")]
+    ///
+    /// The file may contain a bundle of several certificates, all of which are trusted in
+    /// addition to the system roots.
+    #[delta(alias = "ca_bundle_path", env = "SSL_CERT_FILE")]
     pub certificate_path: Option<String>,
 }
 
-/// Read a PEM certificate file and build [`object_store::ClientOptions`] with it.
-pub fn client_options_from_certificate(path: &str) -> DeltaResult<object_store::ClientOptions> {
+/// HTTP(S) proxy used by the object store clients of a table.
+///
+/// Unlike the `HTTPS_PROXY` environment variable, this only applies to the table it is
+/// configured for.
+#[derive(Debug, Clone, Default, DeltaConfig)]
+pub struct ProxyConfig {
+    /// URL of the proxy requests are sent through, e.g. `http://proxy.corp:3128`.
+    pub proxy_url: Option<String>,
+    /// Path to a PEM-encoded CA certificate trusted for connections to the proxy.
+    pub proxy_ca_certificate_path: Option<String>,
+    /// Comma separated list of hosts and domains which are accessed without the proxy.
+    pub proxy_excludes: Option<String>,
+}
+
+fn read_pem_file(path: &str) -> DeltaResult<Vec<u8>> {
     let mut buf = Vec::new();
     std::fs::File::open(path)
         .map_err(|e| {
@@ -119,12 +160,57 @@ pub fn client_options_from_certificate(path: &str) -> DeltaResult<object_store::
         .map_err(|e| {
             DeltaTableError::Generic(format!("Failed to read certificate file '{path}': {e}"))
         })?;
-    let cert = object_store::Certificate::from_pem(&buf).map_err(|e| {
+    Ok(buf)
+}
+
+/// Read a PEM certificate file (or bundle) and build [`object_store::ClientOptions`] with it.
+pub fn client_options_from_certificate(path: &str) -> DeltaResult<object_store::ClientOptions> {
+    let buf = read_pem_file(path)?;
+    let certs = object_store::Certificate::from_pem_bundle(&buf).map_err(|e| {
         DeltaTableError::Generic(format!(
             "Failed to parse PEM certificate from '{path}': {e}"
         ))
     })?;
-    Ok(object_store::ClientOptions::new().with_root_certificate(cert))
+    if certs.is_empty() {
+        return Err(DeltaTableError::Generic(format!(
+            "No PEM certificate found in '{path}'"
+        )));
+    }
+    Ok(certs
+        .into_iter()
+        .fold(object_store::ClientOptions::new(), |options, cert| {
+            options.with_root_certificate(cert)
+        }))
+}
+
+/// Build the [`object_store::ClientOptions`] for the configured root certificates and proxy.
+///
+/// Returns `None` if neither is configured, so the defaults of the backend are kept.
+pub fn client_options(
+    certificate: Option<&CertificateConfig>,
+    proxy: Option<&ProxyConfig>,
+) -> DeltaResult<Option<object_store::ClientOptions>> {
+    let mut options = match certificate.and_then(|c| c.certificate_path.as_deref()) {
+        Some(path) => Some(client_options_from_certificate(path)?),
+        None => None,
+    };
+    if let Some(proxy) = proxy {
+        let mut proxied = options.unwrap_or_default();
+        if let Some(url) = &proxy.proxy_url {
+            proxied = proxied.with_proxy_url(url);
+        }
+        if let Some(path) = &proxy.proxy_ca_certificate_path {
+            let pem = String::from_utf8(read_pem_file(path)?).map_err(|e| {
+                DeltaTableError::Generic(format!("Invalid PEM certificate in '{path}': {e}"))
+            })?;
+            proxied = proxied.with_proxy_ca_certificate(pem);
+        }
+        if let Some(excludes) = &proxy.proxy_excludes {
+            proxied = proxied.with_proxy_excludes(excludes);
+        }
+        options = Some(proxied);
+    }
+    Ok(options)
 }
 
 #[cfg(test)]
@@ -198,6 +284,38 @@ mod tests {
         assert!(err.contains("Failed to open certificate file"));
     }
 
+    #[test]
+    fn test_client_options_without_config() {
+        assert!(client_options(None, None).unwrap().is_none());
+        let config = CertificateConfig::default();
+        assert!(client_options(Some(&config), None).unwrap().is_none());
+    }
+
+    #[test]
+    fn test_client_options_with_proxy() {
+        let mut config = ProxyConfig::default();
+        config
+            .try_update_key("proxy_url", "http://proxy.corp:3128")
+            .unwrap();
+        config
+            .try_update_key("proxy_excludes", "localhost,.internal")
+            .unwrap();
+        assert!(client_options(None, Some(&config)).unwrap().is_some());
+
+        config
+            .try_update_key("proxy_ca_certificate_path", "/nonexistent/proxy.pem")
+            .unwrap();
+        assert!(client_options(None, Some(&config)).is_err());
+    }
+
+    #[test]
+    fn test_client_options_from_empty_bundle() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("empty.pem");
+        std::fs::write(&path, "").unwrap();
+        assert!(client_options_from_certificate(path.to_str().unwrap()).is_err());
+    }
+
     #[rstest]
     fn test_certificate_config_env() {
         let _env = with_env(vec![("SSL_CERT_FILE", "/env/path.pem")]);
@@ -0,0 +1,354 @@
+//! Multipart uploads of data files.
+//!
+//! Data files are buffered in memory until they exceed the multipart threshold, smaller files
+//! are written with a single request. Larger files are uploaded in parts, several of which are
+//! in flight at the same time. Object stores limit uploads to 10,000 parts, so the part size is
+//! doubled every 1,000 parts which allows files of up to ~5TB with the default part size of 5MB.
+//!
+//! Stores requiring all parts but the last to be of equal size (e.g. Cloudflare R2) keep the
+//! part size constant, limiting files to 10,000 times the part size.
+use std::sync::OnceLock;
+
+use bytes::{Bytes, BytesMut};
+use deltalake_derive::DeltaConfig;
+use object_store::path::Path;
+use object_store::{MultipartUpload, ObjectStoreExt as _, PutResult, Result as ObjectStoreResult};
+use tokio::task::JoinSet;
+use tracing::debug;
+
+use super::ObjectStoreRef;
+
+/// Minimum size of a part (except the last one) accepted by S3 and GCS.
+const MIN_PART_SIZE: usize = 5 * 1024 * 1024;
+/// Maximum size of a part accepted by S3 and GCS.
+const MAX_PART_SIZE: usize = 5 * 1024 * 1024 * 1024;
+/// Maximum number of parts of a single upload accepted by S3 and GCS.
+const MAX_PARTS: usize = 10_000;
+/// Number of parts uploaded before the part size is doubled.
+const PARTS_PER_SIZE_STEP: usize = 1_000;
+/// Default number of parts uploaded concurrently.
+const DEFAULT_MULTIPART_CONCURRENCY: usize = 10;
+
+/// Part size configured via the legacy `DELTARS_UPLOAD_PART_SIZE` environment variable.
+fn default_part_size() -> usize {
+    static PART_SIZE: OnceLock<usize> = OnceLock::new();
+    *PART_SIZE.get_or_init(|| {
+        std::env::var("DELTARS_UPLOAD_PART_SIZE")
+            .ok()
+            .and_then(|s| s.parse::<usize>().ok())
+            .unwrap_or(MIN_PART_SIZE)
+    })
+}
+
+/// Concurrency configured via the legacy `DELTARS_MAX_CONCURRENCY_TASKS` environment variable.
+fn default_concurrency() -> usize {
+    static CONCURRENCY: OnceLock<usize> = OnceLock::new();
+    *CONCURRENCY.get_or_init(|| {
+        std::env::var("DELTARS_MAX_CONCURRENCY_TASKS")
+            .ok()
+            .and_then(|s| s.parse::<usize>().ok())
+            .unwrap_or(DEFAULT_MULTIPART_CONCURRENCY)
+    })
+}
+
+/// Configuration for multipart uploads of data files.
+#[derive(Debug, Clone, Default, DeltaConfig)]
+pub struct MultipartConfig {
+    /// Size in bytes of the parts of a multipart upload, clamped to [5MB, 5GB].
+    #[delta(env = "OBJECT_STORE_MULTIPART_PART_SIZE")]
+    pub multipart_part_size: Option<usize>,
+    /// Number of parts uploaded concurrently.
+    #[delta(env = "OBJECT_STORE_MULTIPART_CONCURRENCY")]
+    pub multipart_concurrency: Option<usize>,
+    /// Size in bytes above which files are uploaded in parts, defaults to the part size.
+    #[delta(env = "OBJECT_STORE_MULTIPART_THRESHOLD")]
+    pub multipart_threshold: Option<usize>,
+    /// Upload all parts but the last with the same size, required by some stores.
+    #[delta(env = "OBJECT_STORE_MULTIPART_EQUAL_PARTS")]
+    pub multipart_equal_parts: Option<bool>,
+}
+
+impl MultipartConfig {
+    /// Size of the first parts of an upload.
+    pub fn part_size(&self) -> usize {
+        let size = self.multipart_part_size.unwrap_or_else(default_part_size);
+        if size < MIN_PART_SIZE {
+            debug!("Multipart part size must be at least 5MB, falling back on 5MB.");
+        } else if size > MAX_PART_SIZE {
+            debug!("Multipart part size must not be higher than 5GB, capping it at 5GB.");
+        }
+        size.clamp(MIN_PART_SIZE, MAX_PART_SIZE)
+    }
+
+    /// Number of parts uploaded concurrently.
+    pub fn concurrency(&self) -> usize {
+        self.multipart_concurrency
+            .unwrap_or_else(default_concurrency)
+            .max(1)
+    }
+
+    /// Size above which files are uploaded in parts.
+    pub fn threshold(&self) -> usize {
+        self.multipart_threshold.unwrap_or_else(|| self.part_size())
+    }
+
+    /// Whether all parts but the last are uploaded with the same size.
+    pub fn equal_parts(&self) -> bool {
+        self.multipart_equal_parts.unwrap_or(false)
+    }
+
+    /// Override the values of `self` with the ones set in `other`.
+    pub fn merge(self, other: &MultipartConfig) -> Self {
+        Self {
+            multipart_part_size: other.multipart_part_size.or(self.multipart_part_size),
+            multipart_concurrency: other.multipart_concurrency.or(self.multipart_concurrency),
+            multipart_threshold: other.multipart_threshold.or(self.multipart_threshold),
+            multipart_equal_parts: other.multipart_equal_parts.or(self.multipart_equal_parts),
+        }
+    }
+}
+
+/// Size of the part with index `part_idx` of an upload starting with parts of `base` bytes.
+fn part_size_for(base: usize, part_idx: usize, equal_parts: bool) -> usize {
+    if equal_parts {
+        return base;
+    }
+    let step = (part_idx / PARTS_PER_SIZE_STEP) as u32;
+    base.saturating_mul(2_usize.saturating_pow(step))
+        .min(MAX_PART_SIZE)
+}
+
+/// A multipart upload which has been started, with the parts currently in flight.
+struct InProgressUpload {
+    upload: Box<dyn MultipartUpload>,
+    in_flight: JoinSet<ObjectStoreResult<()>>,
+    parts: usize,
+}
+
+impl InProgressUpload {
+    /// Wait until at most `max_in_flight` parts are in flight.
+    async fn wait_for_capacity(&mut self, max_in_flight: usize) -> ObjectStoreResult<()> {
+        while self.in_flight.len() > max_in_flight {
+            if let Some(result) = self.in_flight.join_next().await {
+                result.map_err(|source| object_store::Error::JoinError { source })??;
+            }
+        }
+        Ok(())
+    }
+
+    async fn put_part(&mut self, data: Bytes, concurrency: usize) -> ObjectStoreResult<()> {
+        if self.parts >= MAX_PARTS {
+            return Err(object_store::Error::Generic {
+                store: "MultipartWriter",
+                source: format!(
+                    "multipart uploads are limited to {MAX_PARTS} parts, increase the multipart part size to write larger files"
+                )
+                .into(),
+            });
+        }
+        self.wait_for_capacity(concurrency - 1).await?;
+        self.in_flight.spawn(self.upload.put_part(data.into()));
+        self.parts += 1;
+        Ok(())
+    }
+}
+
+/// Writes a single object, switching to a multipart upload once it exceeds the configured
+/// threshold.
+///
+/// Dropping the writer before [`finish`](Self::finish) aborts an upload in progress, so
+/// cancelled writes do not leave incomplete uploads behind.
+pub struct MultipartWriter {
+    store: ObjectStoreRef,
+    path: Path,
+    part_size: usize,
+    equal_parts: bool,
+    concurrency: usize,
+    threshold: usize,
+    buffer: BytesMut,
+    upload: Option<InProgressUpload>,
+}
+
+impl std::fmt::Debug for MultipartWriter {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("MultipartWriter")
+            .field("path", &self.path)
+            .field("part_size", &self.part_size)
+            .field("equal_parts", &self.equal_parts)
+            .field("concurrency", &self.concurrency)
+            .field("threshold", &self.threshold)
+            .finish_non_exhaustive()
+    }
+}
+
+impl MultipartWriter {
+    /// Create a writer for the object at `path` in `store`.
+    pub fn new(store: ObjectStoreRef, path: Path, config: &MultipartConfig) -> Self {
+        Self {
+            store,
+            path,
+            part_size: config.part_size(),
+            equal_parts: config.equal_parts(),
+            concurrency: config.concurrency(),
+            threshold: config.threshold(),
+            buffer: BytesMut::new(),
+            upload: None,
+        }
+    }
+
+    /// Buffer `data`, uploading all completely buffered parts.
+    pub async fn put(&mut self, data: Bytes) -> ObjectStoreResult<()> {
+        self.buffer.extend_from_slice(&data);
+        if self.upload.is_none() {
+            if self.buffer.len() < self.threshold {
+                return Ok(());
+            }
+            let upload = self.store.put_multipart(&self.path).await?;
+            self.upload = Some(InProgressUpload {
+                upload,
+                in_flight: JoinSet::new(),
+                parts: 0,
+            });
+        }
+        if let Some(upload) = self.upload.as_mut() {
+            loop {
+                let part_size = part_size_for(self.part_size, upload.parts, self.equal_parts);
+                if self.buffer.len() < part_size {
+                    break;
+                }
+                let part = self.buffer.split_to(part_size).freeze();
+                upload.put_part(part, self.concurrency).await?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Upload the remaining data and complete the upload.
+    ///
+    /// A failed multipart upload is aborted to clean up the parts uploaded so far.
+    pub async fn finish(&mut self) -> ObjectStoreResult<PutResult> {
+        let data = self.buffer.split().freeze();
+        let Some(mut upload) = self.upload.take() else {
+            return self.store.put(&self.path, data.into()).await;
+        };
+        let concurrency = self.concurrency;
+        let result = async {
+            if !data.is_empty() || upload.parts == 0 {
+                upload.put_part(data, concurrency).await?;
+            }
+            upload.wait_for_capacity(0).await?;
+            upload.upload.complete().await
+        }
+        .await;
+        if result.is_err() {
+            upload.in_flight.shutdown().await;
+            if let Err(err) = upload.upload.abort().await {
+                debug!("Failed to abort multipart upload of {}: {err}", self.path);
+            }
+        }
+        result
+    }
+}
+
+impl Drop for MultipartWriter {
+    fn drop(&mut self) {
+        let Some(mut upload) = self.upload.take() else {
+            return;
+        };
+        // parts in flight are aborted when their join set is dropped
+        upload.in_flight.abort_all();
+        let Ok(handle) = tokio::runtime::Handle::try_current() else {
+            debug!("No runtime to abort the multipart upload of {}", self.path);
+            return;
+        };
+        let path = self.path.clone();
+        handle.spawn(async move {
+            if let Err(err) = upload.upload.abort().await {
+                debug!("Failed to abort multipart upload of {path}: {err}");
+            }
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use object_store::ObjectStoreExt;
+    use object_store::memory::InMemory;
+
+    use super::*;
+
+    #[test]
+    fn test_part_size_for() {
+        assert_eq!(part_size_for(MIN_PART_SIZE, 0, false), MIN_PART_SIZE);
+        assert_eq!(part_size_for(MIN_PART_SIZE, 999, false), MIN_PART_SIZE);
+        assert_eq!(
+            part_size_for(MIN_PART_SIZE, 1_000, false),
+            2 * MIN_PART_SIZE
+        );
+        assert_eq!(part_size_for(MAX_PART_SIZE, 5_000, false), MAX_PART_SIZE);
+
+        // 10,000 parts starting at 5MB hold more than 5TB
+        let total: u64 = (0..MAX_PARTS)
+            .map(|idx| part_size_for(MIN_PART_SIZE, idx, false) as u64)
+            .sum();
+        assert!(total >= 5 * 1024 * 1024 * 1024 * 1024);
+
+        assert_eq!(part_size_for(MIN_PART_SIZE, 5_000, true), MIN_PART_SIZE);
+    }
+
+    #[test]
+    fn test_config_defaults_and_clamping() {
+        let config = MultipartConfig {
+            multipart_part_size: Some(1024),
+            multipart_concurrency: Some(0),
+            ..Default::default()
+        };
+        assert_eq!(config.part_size(), MIN_PART_SIZE);
+        assert_eq!(config.threshold(), MIN_PART_SIZE);
+        assert_eq!(config.concurrency(), 1);
+
+        let config = config.merge(&MultipartConfig {
+            multipart_threshold: Some(0),
+            ..Default::default()
+        });
+        assert_eq!(config.multipart_part_size, Some(1024));
+        assert_eq!(config.threshold(), 0);
+    }
+
+    #[tokio::test]
+    async fn test_small_object_is_put_once() {
+        let store = Arc::new(InMemory::new());
+        let path = Path::from("part-00000.parquet");
+        let mut writer = MultipartWriter::new(store.clone(), path.clone(), &Default::default());
+        writer.put(Bytes::from_static(b"hello ")).await.unwrap();
+        writer.put(Bytes::from_static(b"world")).await.unwrap();
+        assert!(writer.upload.is_none());
+        writer.finish().await.unwrap();
+
+        let data = store.get(&path).await.unwrap().bytes().await.unwrap();
+        assert_eq!(data.as_ref(), b"hello world");
+    }
+
+    #[tokio::test]
+    async fn test_large_object_is_uploaded_in_parts() {
+        let store = Arc::new(InMemory::new());
+        let path = Path::from("part-00000.parquet");
+        let config = MultipartConfig {
+            multipart_concurrency: Some(2),
+            multipart_threshold: Some(1024),
+            ..Default::default()
+        };
+        let mut writer = MultipartWriter::new(store.clone(), path.clone(), &config);
+        let chunk = Bytes::from(vec![7_u8; 2 * 1024 * 1024]);
+        for _ in 0..6 {
+            writer.put(chunk.clone()).await.unwrap();
+        }
+        assert_eq!(writer.upload.as_ref().unwrap().parts, 2);
+        writer.finish().await.unwrap();
+
+        let meta = store.head(&path).await.unwrap();
+        assert_eq!(meta.size, 12 * 1024 * 1024);
+    }
+}
@@ -10,11 +10,11 @@ use futures::future::BoxFuture;
 use futures::stream::BoxStream;
 use object_store::path::Path;
 use object_store::{
-    CopyOptions, Error as ObjectStoreError, GetOptions, GetResult, ListResult, ObjectMeta,
-    ObjectStore, ObjectStoreExt, PutOptions, PutPayload, PutResult, RenameOptions,
+    CopyOptions, Error as ObjectStoreError, GetOptions, GetResult, GetResultPayload, ListResult,
+    ObjectMeta, ObjectStore, ObjectStoreExt, PutOptions, PutPayload, PutResult, RenameOptions,
     Result as ObjectStoreResult,
 };
-use object_store::{MultipartUpload, PutMultipartOptions};
+use object_store::{MultipartUpload, PutMultipartOptions, UploadPart};
 use serde::{Deserialize, Serialize};
 use tokio::runtime::{Builder as RuntimeBuilder, Handle, Runtime};
 
@@ -107,6 +107,10 @@ impl IORuntime {
 }
 
 /// Wraps any object store and runs IO in it's own runtime [EXPERIMENTAL]
+///
+/// Requests, the bodies of downloads, listings and the parts of multipart uploads are all
+/// driven by the IO runtime, so CPU bound work on the caller's runtime, e.g. decoding parquet,
+/// does not stall connections to the store.
 #[derive(Clone)]
 pub struct DeltaIOStorageBackend<T: ObjectStore + Clone> {
     /// The wrapped object store that performs the actual IO.
@@ -124,6 +128,46 @@ where
     }
 }
 
+/// Run `fut` on the runtime of `handle`
+fn spawn_on<O>(
+    handle: &Handle,
+    fut: impl Future<Output = ObjectStoreResult<O>> + Send + 'static,
+) -> BoxFuture<'static, ObjectStoreResult<O>>
+where
+    O: Send + 'static,
+{
+    handle
+        .spawn(fut)
+        .unwrap_or_else(|e| match e.try_into_panic() {
+            Ok(p) => std::panic::resume_unwind(p),
+            Err(e) => Err(ObjectStoreError::JoinError { source: e }),
+        })
+        .boxed()
+}
+
+/// Poll `stream` on the runtime of `handle`, forwarding its items to the returned stream
+fn spawn_stream_on<O>(
+    handle: &Handle,
+    mut stream: BoxStream<'static, ObjectStoreResult<O>>,
+) -> BoxStream<'static, ObjectStoreResult<O>>
+where
+    O: Send + 'static,
+{
+    let (tx, rx) = tokio::sync::mpsc::channel(2);
+    handle.spawn(async move {
+        while let Some(item) = stream.next().await {
+            // the receiving stream was dropped
+            if tx.send(item).await.is_err() {
+                break;
+            }
+        }
+    });
+    futures::stream::unfold(rx, |mut rx| async move {
+        rx.recv().await.map(|item| (item, rx))
+    })
+    .boxed()
+}
+
 impl<T: ObjectStore + Clone> DeltaIOStorageBackend<T> {
     /// spawn tasks on IO runtime
     pub fn spawn_io_rt<F, O>(
@@ -137,15 +181,7 @@ impl<T: ObjectStore + Clone> DeltaIOStorageBackend<T> {
         O: Send + 'static,
     {
         let store = store.clone();
-        let fut = self
-            .rt
-            .get_handle()
-            .spawn(async move { f(&store, &path).await });
-        fut.unwrap_or_else(|e| match e.try_into_panic() {
-            Ok(p) => std::panic::resume_unwind(p),
-            Err(e) => Err(ObjectStoreError::JoinError { source: e }),
-        })
-        .boxed()
+        spawn_on(&self.rt.get_handle(), async move { f(&store, &path).await })
     }
 
     /// spawn tasks on IO runtime
@@ -163,15 +199,10 @@ impl<T: ObjectStore + Clone> DeltaIOStorageBackend<T> {
         O: Send + 'static,
     {
         let store = store.clone();
-        let fut = self
-            .rt
-            .get_handle()
-            .spawn(async move { f(&store, &from, &to).await });
-        fut.unwrap_or_else(|e| match e.try_into_panic() {
-            Ok(p) => std::panic::resume_unwind(p),
-            Err(e) => Err(ObjectStoreError::JoinError { source: e }),
-        })
-        .boxed()
+        spawn_on(
+            &self.rt.get_handle(),
+            async move { f(&store, &from, &to).await },
+        )
     }
 }
 
@@ -204,12 +235,21 @@ impl<T: ObjectStore + Clone> ObjectStore for DeltaIOStorageBackend<T> {
     }
 
     async fn get_opts(&self, location: &Path, options: GetOptions) -> ObjectStoreResult<GetResult> {
-        self.spawn_io_rt(
-            move |store, path| store.get_opts(path, options).boxed(),
-            &self.inner,
-            location.clone(),
-        )
-        .await
+        let mut result = self
+            .spawn_io_rt(
+                move |store, path| store.get_opts(path, options).boxed(),
+                &self.inner,
+                location.clone(),
+            )
+            .await?;
+        // the body is downloaded while the stream is polled
+        result.payload = match result.payload {
+            GetResultPayload::Stream(stream) => {
+                GetResultPayload::Stream(spawn_stream_on(&self.rt.get_handle(), stream))
+            }
+            payload => payload,
+        };
+        Ok(result)
     }
 
     async fn get_ranges(
@@ -254,7 +294,7 @@ impl<T: ObjectStore + Clone> ObjectStore for DeltaIOStorageBackend<T> {
     }
 
     fn list(&self, prefix: Option<&Path>) -> BoxStream<'static, ObjectStoreResult<ObjectMeta>> {
-        self.inner.list(prefix)
+        spawn_stream_on(&self.rt.get_handle(), self.inner.list(prefix))
     }
 
     fn list_with_offset(
@@ -262,11 +302,19 @@ impl<T: ObjectStore + Clone> ObjectStore for DeltaIOStorageBackend<T> {
         prefix: Option<&Path>,
         offset: &Path,
     ) -> BoxStream<'static, ObjectStoreResult<ObjectMeta>> {
-        self.inner.list_with_offset(prefix, offset)
+        spawn_stream_on(
+            &self.rt.get_handle(),
+            self.inner.list_with_offset(prefix, offset),
+        )
     }
 
     async fn list_with_delimiter(&self, prefix: Option<&Path>) -> ObjectStoreResult<ListResult> {
-        self.inner.list_with_delimiter(prefix).await
+        let store = self.inner.clone();
+        let prefix = prefix.cloned();
+        spawn_on(&self.rt.get_handle(), async move {
+            store.list_with_delimiter(prefix.as_ref()).await
+        })
+        .await
     }
 
     async fn copy_opts(
@@ -304,21 +352,120 @@ impl<T: ObjectStore + Clone> ObjectStore for DeltaIOStorageBackend<T> {
         location: &Path,
         options: PutMultipartOptions,
     ) -> ObjectStoreResult<Box<dyn MultipartUpload>> {
-        self.spawn_io_rt(
-            move |store, path| store.put_multipart_opts(path, options).boxed(),
-            &self.inner,
-            location.clone(),
-        )
-        .await
+        let upload = self
+            .spawn_io_rt(
+                move |store, path| store.put_multipart_opts(path, options).boxed(),
+                &self.inner,
+                location.clone(),
+            )
+            .await?;
+        Ok(Box::new(DeltaIOMultipartUpload {
+            inner: Some(upload),
+            handle: self.rt.get_handle(),
+        }))
+    }
+}
+
+/// A [`MultipartUpload`] uploading its parts on the IO runtime
+#[derive(Debug)]
+struct DeltaIOMultipartUpload {
+    /// The wrapped upload, taken while it completes or aborts on the IO runtime
+    inner: Option<Box<dyn MultipartUpload>>,
+    handle: Handle,
+}
+
+impl DeltaIOMultipartUpload {
+    fn interrupted() -> ObjectStoreError {
+        ObjectStoreError::Generic {
+            store: "DeltaIOStorageBackend",
+            source: "multipart upload was interrupted while completing or aborting".into(),
+        }
+    }
+
+    /// Run `f` with the wrapped upload on the IO runtime
+    async fn run<F, O>(&mut self, f: F) -> ObjectStoreResult<O>
+    where
+        F: for<'a> FnOnce(&'a mut Box<dyn MultipartUpload>) -> BoxFuture<'a, ObjectStoreResult<O>>
+            + Send
+            + 'static,
+        O: Send + 'static,
+    {
+        let mut upload = self.inner.take().ok_or_else(Self::interrupted)?;
+        let (upload, result) = self
+            .handle
+            .spawn(async move {
+                let result = f(&mut upload).await;
+                (upload, result)
+            })
+            .await
+            .map_err(|e| match e.try_into_panic() {
+                Ok(p) => std::panic::resume_unwind(p),
+                Err(e) => ObjectStoreError::JoinError { source: e },
+            })?;
+        self.inner = Some(upload);
+        result
+    }
+}
+
+#[async_trait::async_trait]
+impl MultipartUpload for DeltaIOMultipartUpload {
+    fn put_part(&mut self, data: PutPayload) -> UploadPart {
+        match self.inner.as_mut() {
+            Some(upload) => spawn_on(&self.handle, upload.put_part(data)),
+            None => Box::pin(futures::future::ready(Err(Self::interrupted()))),
+        }
+    }
+
+    async fn complete(&mut self) -> ObjectStoreResult<PutResult> {
+        self.run(|upload| upload.complete()).await
+    }
+
+    async fn abort(&mut self) -> ObjectStoreResult<()> {
+        self.run(|upload| upload.abort()).await
     }
 }
 
 #[cfg(test)]
 mod tests {
+    use futures::TryStreamExt as _;
+
     use super::*;
 
     #[tokio::test]
     async fn test_ioruntime_default() {
         let _ = IORuntime::default();
     }
+
+    #[tokio::test]
+    async fn test_io_storage_backend_roundtrip() -> ObjectStoreResult<()> {
+        let io_rt = RuntimeBuilder::new_multi_thread()
+            .worker_threads(1)
+            .enable_all()
+            .build()
+            .unwrap();
+        let inner: std::sync::Arc<dyn ObjectStore> =
+            std::sync::Arc::new(object_store::memory::InMemory::new());
+        let store = DeltaIOStorageBackend::new(inner, IORuntime::RT(io_rt.handle().clone()));
+
+        let path = Path::from("data/part-0.parquet");
+        let mut upload = store.put_multipart(&path).await?;
+        upload.put_part(PutPayload::from_static(b"hello ")).await?;
+        upload.put_part(PutPayload::from_static(b"world")).await?;
+        upload.complete().await?;
+
+        let bytes = store.get(&path).await?.bytes().await?;
+        assert_eq!(bytes.as_ref(), b"hello world");
+
+        let listed: Vec<_> = store
+            .list(Some(&Path::from("data")))
+            .map_ok(|meta| meta.location)
+            .try_collect()
+            .await?;
+        assert_eq!(listed, vec![path.clone()]);
+        let listed = store.list_with_delimiter(None).await?;
+        assert_eq!(listed.common_prefixes, vec![Path::from("data")]);
+
+        io_rt.shutdown_background();
+        Ok(())
+    }
 }
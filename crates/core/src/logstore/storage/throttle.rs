@@ -0,0 +1,488 @@
+//! Client side throttling of object store requests.
+//!
+//! Maintenance operations such as vacuum or optimize can issue requests at a rate which gets an
+//! account throttled by the storage service (e.g. S3 `503 SlowDown`), starving other readers of
+//! the same bucket. [`ThrottledStore`] limits the requests per second as well as the number of
+//! requests in flight, separately for reads, writes and deletes.
+use std::fmt;
+use std::sync::Arc;
+use std::time::Duration;
+
+use deltalake_derive::DeltaConfig;
+use futures::StreamExt;
+use futures::stream::BoxStream;
+use object_store::path::Path;
+use object_store::{
+    CopyOptions, GetOptions, GetResult, GetResultPayload, ListResult, MultipartUpload, ObjectMeta,
+    ObjectStore, PutMultipartOptions, PutOptions, PutPayload, PutResult, RenameOptions,
+    Result as ObjectStoreResult, UploadPart,
+};
+use parking_lot::Mutex;
+use tokio::sync::{OwnedSemaphorePermit, Semaphore};
+use tokio::time::Instant;
+
+use super::ObjectStoreRef;
+
+/// Configuration for client side throttling of object store requests.
+///
+/// Non-positive rates are ignored. Deletes are limited per deleted object, since bulk deletes
+/// count every object against the request rate of most storage services.
+#[derive(Debug, Clone, Default, DeltaConfig)]
+pub struct ThrottleConfig {
+    /// Maximum number of read requests (get, head, list) per second.
+    #[delta(env = "OBJECT_STORE_READ_REQUESTS_PER_SECOND")]
+    pub read_requests_per_second: Option<f64>,
+    /// Maximum number of write requests (put, multipart parts, copy, rename) per second.
+    #[delta(env = "OBJECT_STORE_WRITE_REQUESTS_PER_SECOND")]
+    pub write_requests_per_second: Option<f64>,
+    /// Maximum number of objects deleted per second.
+    #[delta(env = "OBJECT_STORE_DELETE_REQUESTS_PER_SECOND")]
+    pub delete_requests_per_second: Option<f64>,
+    /// Maximum number of read requests in flight.
+    #[delta(env = "OBJECT_STORE_MAX_CONCURRENT_READS")]
+    pub max_concurrent_reads: Option<usize>,
+    /// Maximum number of write requests in flight.
+    #[delta(env = "OBJECT_STORE_MAX_CONCURRENT_WRITES")]
+    pub max_concurrent_writes: Option<usize>,
+    /// Maximum number of deletes in flight.
+    #[delta(env = "OBJECT_STORE_MAX_CONCURRENT_DELETES")]
+    pub max_concurrent_deletes: Option<usize>,
+}
+
+/// Largest number of locations passed to a single delete stream of the inner store, matching
+/// the bulk delete limit of S3 and Azure.
+const MAX_DELETE_BATCH_SIZE: usize = 1000;
+
+/// Limits the rate and concurrency of one class of requests.
+#[derive(Debug)]
+struct RequestLimiter {
+    interval: Option<Duration>,
+    next_slot: Mutex<Instant>,
+    max_in_flight: Option<usize>,
+    in_flight: Option<Arc<Semaphore>>,
+}
+
+impl RequestLimiter {
+    fn new(requests_per_second: Option<f64>, max_in_flight: Option<usize>) -> Self {
+        let max_in_flight = max_in_flight.map(|permits| permits.max(1));
+        Self {
+            interval: requests_per_second
+                .filter(|rate| *rate > 0.0)
+                .map(|rate| Duration::from_secs_f64(1.0 / rate)),
+            next_slot: Mutex::new(Instant::now()),
+            max_in_flight,
+            in_flight: max_in_flight.map(|permits| Arc::new(Semaphore::new(permits))),
+        }
+    }
+
+    /// Wait until a request may be sent, the returned permit must be held until it completes.
+    async fn acquire(&self) -> Option<OwnedSemaphorePermit> {
+        self.acquire_many(1).await
+    }
+
+    /// Wait until `count` requests may be sent, the returned permit must be held until all of
+    /// them complete.
+    async fn acquire_many(&self, count: usize) -> Option<OwnedSemaphorePermit> {
+        let permit = self.acquire_in_flight(count).await;
+        if let Some(interval) = self.interval {
+            let slot = {
+                let mut next_slot = self.next_slot.lock();
+                let slot = (*next_slot).max(Instant::now());
+                *next_slot = slot + interval * count as u32;
+                slot
+            };
+            tokio::time::sleep_until(slot).await;
+        }
+        permit
+    }
+
+    /// Wait until `count` more requests may be in flight, without taking up the request rate.
+    async fn acquire_in_flight(&self, count: usize) -> Option<OwnedSemaphorePermit> {
+        match &self.in_flight {
+            Some(in_flight) => Some(
+                in_flight
+                    .clone()
+                    .acquire_many_owned(count as u32)
+                    .await
+                    .expect("semaphore is never closed"),
+            ),
+            None => None,
+        }
+    }
+
+    /// Largest number of requests which can be acquired at once
+    fn max_batch_size(&self) -> usize {
+        self.max_in_flight
+            .map_or(MAX_DELETE_BATCH_SIZE, |max| max.min(MAX_DELETE_BATCH_SIZE))
+    }
+}
+
+/// Delete `locations` with `delete`, throttled by the delete limiter of `limiters`.
+///
+/// Locations are handed to `delete` in batches, each holding a permit for all of its locations
+/// until the batch is processed. Stores deleting several locations with a single request thus
+/// always receive complete batches, and permits are released when the stream is dropped.
+fn throttled_delete_stream(
+    limiters: Arc<Limiters>,
+    locations: BoxStream<'static, ObjectStoreResult<Path>>,
+    delete: impl Fn(
+        BoxStream<'static, ObjectStoreResult<Path>>,
+    ) -> BoxStream<'static, ObjectStoreResult<Path>>
+    + Send
+    + Sync
+    + 'static,
+) -> BoxStream<'static, ObjectStoreResult<Path>> {
+    let delete = Arc::new(delete);
+    let batch_size = limiters.delete.max_batch_size();
+    locations
+        .ready_chunks(batch_size)
+        .map(move |batch| {
+            let limiters = limiters.clone();
+            let delete = delete.clone();
+            futures::stream::once(async move {
+                let permit = limiters.delete.acquire_many(batch.len()).await;
+                delete(futures::stream::iter(batch).boxed()).map(move |item| {
+                    let _permit = &permit;
+                    item
+                })
+            })
+            .flatten()
+        })
+        .flatten()
+        .boxed()
+}
+
+#[derive(Debug)]
+struct Limiters {
+    read: RequestLimiter,
+    write: RequestLimiter,
+    delete: RequestLimiter,
+}
+
+/// An [`ObjectStore`] limiting the rate and concurrency of requests sent to the inner store.
+#[derive(Clone)]
+pub struct ThrottledStore {
+    inner: ObjectStoreRef,
+    limiters: Arc<Limiters>,
+}
+
+impl ThrottledStore {
+    /// Wrap `inner`, throttling requests according to `config`.
+    pub fn new(inner: ObjectStoreRef, config: &ThrottleConfig) -> Self {
+        let limiters = Limiters {
+            read: RequestLimiter::new(config.read_requests_per_second, config.max_concurrent_reads),
+            write: RequestLimiter::new(
+                config.write_requests_per_second,
+                config.max_concurrent_writes,
+            ),
+            delete: RequestLimiter::new(
+                config.delete_requests_per_second,
+                config.max_concurrent_deletes,
+            ),
+        };
+        Self {
+            inner,
+            limiters: Arc::new(limiters),
+        }
+    }
+
+    /// Throttle a listing, the start of which counts against the request rate.
+    ///
+    /// Pages are requested while the next object is polled, so a permit is only held meanwhile.
+    /// Requests issued while iterating a listing, e.g. reading the listed objects, thus never
+    /// wait for the listing itself.
+    fn throttled_list(
+        &self,
+        stream: BoxStream<'static, ObjectStoreResult<ObjectMeta>>,
+    ) -> BoxStream<'static, ObjectStoreResult<ObjectMeta>> {
+        let limiters = self.limiters.clone();
+        futures::stream::unfold(
+            (stream, limiters, true),
+            |(mut stream, limiters, first)| async move {
+                let _permit = match first {
+                    true => limiters.read.acquire().await,
+                    false => limiters.read.acquire_in_flight(1).await,
+                };
+                let item = stream.next().await?;
+                Some((item, (stream, limiters, false)))
+            },
+        )
+        .boxed()
+    }
+}
+
+impl fmt::Debug for ThrottledStore {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ThrottledStore")
+            .field("inner", &self.inner)
+            .finish_non_exhaustive()
+    }
+}
+
+impl fmt::Display for ThrottledStore {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "ThrottledStore({})", self.inner)
+    }
+}
+
+/// A [`MultipartUpload`] throttling the upload of its parts as writes.
+#[derive(Debug)]
+struct ThrottledUpload {
+    inner: Box<dyn MultipartUpload>,
+    limiters: Arc<Limiters>,
+}
+
+#[async_trait::async_trait]
+impl MultipartUpload for ThrottledUpload {
+    fn put_part(&mut self, data: PutPayload) -> UploadPart {
+        let part = self.inner.put_part(data);
+        let limiters = self.limiters.clone();
+        Box::pin(async move {
+            let _permit = limiters.write.acquire().await;
+            part.await
+        })
+    }
+
+    async fn complete(&mut self) -> ObjectStoreResult<PutResult> {
+        let _permit = self.limiters.write.acquire().await;
+        self.inner.complete().await
+    }
+
+    async fn abort(&mut self) -> ObjectStoreResult<()> {
+        let _permit = self.limiters.delete.acquire().await;
+        self.inner.abort().await
+    }
+}
+
+#[async_trait::async_trait]
+impl ObjectStore for ThrottledStore {
+    async fn put_opts(
+        &self,
+        location: &Path,
+        payload: PutPayload,
+        options: PutOptions,
+    ) -> ObjectStoreResult<PutResult> {
+        let _permit = self.limiters.write.acquire().await;
+        self.inner.put_opts(location, payload, options).await
+    }
+
+    async fn put_multipart_opts(
+        &self,
+        location: &Path,
+        options: PutMultipartOptions,
+    ) -> ObjectStoreResult<Box<dyn MultipartUpload>> {
+        let upload = {
+            let _permit = self.limiters.write.acquire().await;
+            self.inner.put_multipart_opts(location, options).await?
+        };
+        Ok(Box::new(ThrottledUpload {
+            inner: upload,
+            limiters: self.limiters.clone(),
+        }))
+    }
+
+    async fn get_opts(&self, location: &Path, options: GetOptions) -> ObjectStoreResult<GetResult> {
+        let permit = self.limiters.read.acquire().await;
+        let mut result = self.inner.get_opts(location, options).await?;
+        // the body is downloaded while the payload is streamed, which holds the permit
+        result.payload = match result.payload {
+            GetResultPayload::Stream(stream) => GetResultPayload::Stream(
+                stream
+                    .map(move |chunk| {
+                        let _permit = &permit;
+                        chunk
+                    })
+                    .boxed(),
+            ),
+            payload => payload,
+        };
+        Ok(result)
+    }
+
+    fn delete_stream(
+        &self,
+        locations: BoxStream<'static, ObjectStoreResult<Path>>,
+    ) -> BoxStream<'static, ObjectStoreResult<Path>> {
+        let inner = self.inner.clone();
+        throttled_delete_stream(self.limiters.clone(), locations, move |batch| {
+            inner.delete_stream(batch)
+        })
+    }
+
+    fn list(&self, prefix: Option<&Path>) -> BoxStream<'static, ObjectStoreResult<ObjectMeta>> {
+        self.throttled_list(self.inner.list(prefix))
+    }
+
+    fn list_with_offset(
+        &self,
+        prefix: Option<&Path>,
+        offset: &Path,
+    ) -> BoxStream<'static, ObjectStoreResult<ObjectMeta>> {
+        self.throttled_list(self.inner.list_with_offset(prefix, offset))
+    }
+
+    async fn list_with_delimiter(&self, prefix: Option<&Path>) -> ObjectStoreResult<ListResult> {
+        let _permit = self.limiters.read.acquire().await;
+        self.inner.list_with_delimiter(prefix).await
+    }
+
+    async fn copy_opts(
+        &self,
+        from: &Path,
+        to: &Path,
+        options: CopyOptions,
+    ) -> ObjectStoreResult<()> {
+        let _permit = self.limiters.write.acquire().await;
+        self.inner.copy_opts(from, to, options).await
+    }
+
+    async fn rename_opts(
+        &self,
+        from: &Path,
+        to: &Path,
+        options: RenameOptions,
+    ) -> ObjectStoreResult<()> {
+        let _permit = self.limiters.write.acquire().await;
+        self.inner.rename_opts(from, to, options).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use object_store::ObjectStoreExt;
+    use object_store::memory::InMemory;
+
+    use super::*;
+
+    #[tokio::test]
+    async fn test_request_rate_is_limited() {
+        let limiter = RequestLimiter::new(Some(100.0), None);
+        let start = Instant::now();
+        for _ in 0..5 {
+            limiter.acquire().await;
+        }
+        // The first request is sent immediately, the following ones are spaced by 10ms
+        assert!(start.elapsed() >= Duration::from_millis(40));
+    }
+
+    #[tokio::test]
+    async fn test_requests_in_flight_are_limited() {
+        let limiter = RequestLimiter::new(None, Some(2));
+        let first = limiter.acquire().await;
+        let _second = limiter.acquire().await;
+        assert!(
+            tokio::time::timeout(Duration::from_millis(10), limiter.acquire())
+                .await
+                .is_err()
+        );
+        drop(first);
+        assert!(limiter.acquire().await.is_some());
+    }
+
+    #[tokio::test]
+    async fn test_delete_stream_with_batching_store() {
+        let config = ThrottleConfig {
+            max_concurrent_deletes: Some(2),
+            ..Default::default()
+        };
+        let store = ThrottledStore::new(Arc::new(InMemory::new()), &config);
+        let locations = futures::stream::iter(
+            (0..7).map(|i| Ok::<_, object_store::Error>(Path::from(format!("file-{i}")))),
+        );
+
+        // like bulk deletes, only yield once all locations passed in are processed
+        let deleted = throttled_delete_stream(store.limiters.clone(), locations.boxed(), |batch| {
+            futures::stream::once(batch.collect::<Vec<_>>())
+                .flat_map(futures::stream::iter)
+                .boxed()
+        });
+        let deleted = tokio::time::timeout(Duration::from_secs(5), deleted.count())
+            .await
+            .expect("delete stream must not deadlock");
+        assert_eq!(deleted, 7);
+
+        // permits of a dropped stream are released
+        let locations = futures::stream::iter(
+            (0..7).map(|i| Ok::<_, object_store::Error>(Path::from(format!("file-{i}")))),
+        );
+        let mut deleted = store.delete_stream(locations.boxed());
+        deleted.next().await.unwrap().unwrap();
+        drop(deleted);
+        let in_flight = store.limiters.delete.in_flight.as_ref().unwrap();
+        assert_eq!(in_flight.available_permits(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_get_permit_is_held_while_streaming() {
+        let config = ThrottleConfig {
+            max_concurrent_reads: Some(1),
+            ..Default::default()
+        };
+        let store = ThrottledStore::new(Arc::new(InMemory::new()), &config);
+        let location = Path::from("file");
+        store.put(&location, "data".into()).await.unwrap();
+
+        let result = store.get(&location).await.unwrap();
+        assert!(
+            tokio::time::timeout(Duration::from_millis(10), store.get(&location))
+                .await
+                .is_err()
+        );
+        assert_eq!(result.bytes().await.unwrap().as_ref(), b"data");
+        assert!(store.get(&location).await.is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_get_while_listing() {
+        let config = ThrottleConfig {
+            max_concurrent_reads: Some(1),
+            ..Default::default()
+        };
+        let store = ThrottledStore::new(Arc::new(InMemory::new()), &config);
+        for i in 0..3 {
+            store
+                .put(&Path::from(format!("file-{i}")), "data".into())
+                .await
+                .unwrap();
+        }
+        let read = async {
+            let mut listing = store.list(None);
+            let mut read = 0;
+            while let Some(meta) = listing.next().await {
+                store.get(&meta.unwrap().location).await.unwrap();
+                read += 1;
+            }
+            read
+        };
+        let read = tokio::time::timeout(Duration::from_secs(5), read)
+            .await
+            .expect("reads while listing must not deadlock");
+        assert_eq!(read, 3);
+    }
+
+    #[tokio::test]
+    async fn test_throttled_store_roundtrip() {
+        let config = ThrottleConfig {
+            max_concurrent_reads: Some(1),
+            max_concurrent_writes: Some(1),
+            max_concurrent_deletes: Some(1),
+            ..Default::default()
+        };
+        let store = ThrottledStore::new(Arc::new(InMemory::new()), &config);
+        for i in 0..3 {
+            store
+                .put(&Path::from(format!("file-{i}")), "data".into())
+                .await
+                .unwrap();
+        }
+        assert_eq!(store.list(None).count().await, 3);
+
+        let locations = store
+            .list(None)
+            .map(|meta| meta.map(|meta| meta.location))
+            .boxed();
+        let deleted = store.delete_stream(locations).count().await;
+        assert_eq!(deleted, 3);
+        assert_eq!(store.list(None).count().await, 0);
+    }
+}
@@ -0,0 +1,191 @@
+//! Tracking of the files written by an operation.
+//!
+//! An operation which is cancelled while it writes data files leaves the files it already
+//! finished behind, since their actions are never returned to it. [`WrittenFiles`] records every
+//! file written through the stores it wraps, so the files which did not make it into a commit can
+//! be deleted again.
+use std::collections::HashSet;
+use std::fmt;
+use std::sync::Arc;
+
+use futures::StreamExt;
+use futures::stream::BoxStream;
+use object_store::path::Path;
+use object_store::{
+    CopyOptions, GetOptions, GetResult, ListResult, MultipartUpload, ObjectMeta, ObjectStore,
+    PutMultipartOptions, PutOptions, PutPayload, PutResult, RenameOptions,
+    Result as ObjectStoreResult,
+};
+use parking_lot::Mutex;
+use tracing::debug;
+
+use super::ObjectStoreRef;
+use crate::kernel::Action;
+
+/// Files written through the stores returned by [`WrittenFiles::track`], which are not
+/// committed yet.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct WrittenFiles {
+    paths: Arc<Mutex<Vec<Path>>>,
+}
+
+impl WrittenFiles {
+    /// Wrap `store` to record the files written through it.
+    pub(crate) fn track(&self, store: ObjectStoreRef) -> ObjectStoreRef {
+        Arc::new(TrackingStore {
+            inner: store,
+            written: self.clone(),
+        })
+    }
+
+    fn record(&self, location: &Path) {
+        self.paths.lock().push(location.clone());
+    }
+
+    /// Stop tracking the data and change data files of `actions`, once they are committed.
+    pub(crate) fn untrack(&self, actions: &[Action]) {
+        let paths: HashSet<&str> = actions
+            .iter()
+            .filter_map(|action| match action {
+                Action::Add(add) => Some(add.path.as_str()),
+                Action::Cdc(cdc) => Some(cdc.path.as_str()),
+                _ => None,
+            })
+            .collect();
+        self.paths
+            .lock()
+            .retain(|path| !paths.contains(path.as_ref()));
+    }
+
+    /// Delete the tracked files from `store`, which must have the same root as the tracked
+    /// stores.
+    ///
+    /// Writers must be stopped beforehand. This is best effort, files which cannot be deleted
+    /// are left for a full vacuum.
+    pub(crate) async fn delete(&self, store: ObjectStoreRef) {
+        let paths = std::mem::take(&mut *self.paths.lock());
+        if paths.is_empty() {
+            return;
+        }
+        debug!("Deleting {} uncommitted files", paths.len());
+        let mut deleted =
+            store.delete_stream(futures::stream::iter(paths.into_iter().map(Ok)).boxed());
+        while let Some(result) = deleted.next().await {
+            if let Err(err) = result {
+                debug!("Failed to delete uncommitted file: {err}");
+            }
+        }
+    }
+}
+
+/// Object store recording the locations written to in its [`WrittenFiles`].
+#[derive(Debug)]
+struct TrackingStore {
+    inner: ObjectStoreRef,
+    written: WrittenFiles,
+}
+
+impl fmt::Display for TrackingStore {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "TrackingStore({})", self.inner)
+    }
+}
+
+#[async_trait::async_trait]
+impl ObjectStore for TrackingStore {
+    async fn put_opts(
+        &self,
+        location: &Path,
+        payload: PutPayload,
+        options: PutOptions,
+    ) -> ObjectStoreResult<PutResult> {
+        // recorded up front, the put may still complete after its future is dropped
+        self.written.record(location);
+        self.inner.put_opts(location, payload, options).await
+    }
+
+    async fn put_multipart_opts(
+        &self,
+        location: &Path,
+        options: PutMultipartOptions,
+    ) -> ObjectStoreResult<Box<dyn MultipartUpload>> {
+        self.written.record(location);
+        self.inner.put_multipart_opts(location, options).await
+    }
+
+    async fn get_opts(&self, location: &Path, options: GetOptions) -> ObjectStoreResult<GetResult> {
+        self.inner.get_opts(location, options).await
+    }
+
+    fn delete_stream(
+        &self,
+        locations: BoxStream<'static, ObjectStoreResult<Path>>,
+    ) -> BoxStream<'static, ObjectStoreResult<Path>> {
+        self.inner.delete_stream(locations)
+    }
+
+    fn list(&self, prefix: Option<&Path>) -> BoxStream<'static, ObjectStoreResult<ObjectMeta>> {
+        self.inner.list(prefix)
+    }
+
+    fn list_with_offset(
+        &self,
+        prefix: Option<&Path>,
+        offset: &Path,
+    ) -> BoxStream<'static, ObjectStoreResult<ObjectMeta>> {
+        self.inner.list_with_offset(prefix, offset)
+    }
+
+    async fn list_with_delimiter(&self, prefix: Option<&Path>) -> ObjectStoreResult<ListResult> {
+        self.inner.list_with_delimiter(prefix).await
+    }
+
+    async fn copy_opts(
+        &self,
+        from: &Path,
+        to: &Path,
+        options: CopyOptions,
+    ) -> ObjectStoreResult<()> {
+        self.written.record(to);
+        self.inner.copy_opts(from, to, options).await
+    }
+
+    async fn rename_opts(
+        &self,
+        from: &Path,
+        to: &Path,
+        options: RenameOptions,
+    ) -> ObjectStoreResult<()> {
+        self.written.record(to);
+        self.inner.rename_opts(from, to, options).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use object_store::ObjectStoreExt;
+    use object_store::memory::InMemory;
+
+    use super::*;
+    use crate::kernel::Add;
+
+    #[tokio::test]
+    async fn test_uncommitted_files_are_deleted() {
+        let store: ObjectStoreRef = Arc::new(InMemory::new());
+        let written = WrittenFiles::default();
+        let tracked = written.track(store.clone());
+
+        let committed = Path::from("part-1.parquet");
+        let uncommitted = Path::from("part-2.parquet");
+        tracked.put(&committed, "a".into()).await.unwrap();
+        tracked.put(&uncommitted, "b".into()).await.unwrap();
+        written.untrack(&[Action::Add(Add {
+            path: committed.to_string(),
+            ..Default::default()
+        })]);
+
+        written.delete(store.clone()).await;
+        assert!(store.head(&committed).await.is_ok());
+        assert!(store.head(&uncommitted).await.is_err());
+    }
+}
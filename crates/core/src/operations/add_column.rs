@@ -17,6 +17,7 @@ use crate::kernel::{
 };
 use crate::logstore::LogStoreRef;
 use crate::protocol::DeltaOperation;
+use crate::table::with_column_default;
 use crate::{DeltaResult, DeltaTable, DeltaTableError};
 
 /// Add new columns and/or nested fields to a table
@@ -25,6 +26,8 @@ pub struct AddColumnBuilder {
     snapshot: Option<EagerSnapshot>,
     /// Fields to add/merge into schema
     fields: Option<Vec<StructField>>,
+    /// Default value expressions for the added fields
+    column_defaults: Vec<(String, String)>,
     /// Delta object store for handling data files
     log_store: LogStoreRef,
     /// Additional information to add to the commit
@@ -48,6 +51,7 @@ impl AddColumnBuilder {
             snapshot,
             log_store,
             fields: None,
+            column_defaults: Vec::new(),
             commit_properties: CommitProperties::default(),
             custom_execute_handler: None,
         }
@@ -58,6 +62,19 @@ impl AddColumnBuilder {
         self.fields = Some(fields.into_iter().collect());
         self
     }
+
+    /// Specify the SQL expression used as the default value of an added column.
+    ///
+    /// Rows written before the column was added are not backfilled.
+    pub fn with_column_default(
+        mut self,
+        column: impl Into<String>,
+        default_expr: impl Into<String>,
+    ) -> Self {
+        self.column_defaults
+            .push((column.into(), default_expr.into()));
+        self
+    }
     /// Additional metadata to be added to commit info
     pub fn with_commit_properties(mut self, commit_properties: CommitProperties) -> Self {
         self.commit_properties = commit_properties;
@@ -73,9 +90,21 @@ impl AddColumnBuilder {
 
 fn plan_add_column_actions(
     snapshot: SnapshotMetadataRef<'_>,
-    fields: Vec<StructField>,
+    mut fields: Vec<StructField>,
+    column_defaults: Vec<(String, String)>,
 ) -> DeltaResult<(Vec<Action>, DeltaOperation)> {
     let mut metadata = snapshot.metadata.clone();
+    for (column, default_expr) in column_defaults {
+        let idx = fields
+            .iter()
+            .position(|field| field.name() == &column)
+            .ok_or_else(|| {
+                DeltaTableError::Generic(format!(
+                    "Cannot set a default value on column {column} which is not being added"
+                ))
+            })?;
+        fields[idx] = with_column_default(fields[idx].clone(), &default_expr);
+    }
     let fields_right = &StructType::try_new(fields.clone())?;
 
     if !fields_right
@@ -143,8 +172,11 @@ impl std::future::IntoFuture for AddColumnBuilder {
             let operation_id = this.get_operation_id();
             this.pre_execute(operation_id).await?;
 
-            let (actions, operation) =
-                plan_add_column_actions(snapshot.snapshot().metadata_state(), fields)?;
+            let (actions, operation) = plan_add_column_actions(
+                snapshot.snapshot().metadata_state(),
+                fields,
+                this.column_defaults.clone(),
+            )?;
 
             let commit = CommitBuilder::from(this.commit_properties.clone())
                 .with_actions(actions)
@@ -201,4 +233,41 @@ mod tests {
 
         Ok(())
     }
+
+    #[tokio::test]
+    async fn add_column_with_default() -> TestResult {
+        let table = DeltaTable::new_in_memory()
+            .create()
+            .with_columns([id_field()])
+            .await?;
+
+        let table = table
+            .add_columns()
+            .with_fields([added_field()])
+            .with_column_default("added", "'none'")
+            .await?;
+
+        let snapshot = table.snapshot()?;
+        let defaults = snapshot.schema().get_column_defaults()?;
+        assert_eq!(defaults.len(), 1);
+        assert_eq!(defaults[0].name, "added");
+        assert_eq!(defaults[0].get_default_expression(), "'none'");
+        assert!(
+            snapshot
+                .protocol()
+                .writer_features()
+                .is_some_and(|features| {
+                    features.contains(&crate::kernel::allow_column_defaults_feature())
+                })
+        );
+
+        let result = table
+            .add_columns()
+            .with_fields([StructField::new("other", DataType::STRING, true)])
+            .with_column_default("id", "0")
+            .await;
+        assert!(result.is_err());
+
+        Ok(())
+    }
 }
@@ -0,0 +1,181 @@
+//! Read the change data feed of a Delta table as a stream of Arrow record batches.
+//!
+//! Unlike [`CdfLoadBuilder`](super::load_cdf::CdfLoadBuilder), this reader does not depend on
+//! DataFusion. The changes are read through the kernel, which applies deletion vectors and maps
+//! physical to logical column names. Every batch carries the table columns followed by
+//! `_change_type`, `_commit_version` and `_commit_timestamp`.
+//!
+//! # Example
+//! ```rust ignore
+//! let table = open_table(Url::from_directory_path("/abs/path/to/table").unwrap()).await?;
+//! let mut changes = table.cdf_reader().with_starting_version(3).await?;
+//! while let Some(batch) = changes.try_next().await? {
+//!     println!("{} changes", batch.num_rows());
+//! }
+//! ````
+
+use delta_kernel::engine::arrow_data::ArrowEngineData;
+use delta_kernel::table_changes::TableChanges;
+use futures::future::BoxFuture;
+
+use crate::errors::{DeltaResult, DeltaTableError};
+use crate::kernel::{
+    EagerSnapshot, RecordBatchReceiverStreamBuilder, SendableRBStream, Version, resolve_snapshot,
+    spawn_blocking_with_span,
+};
+use crate::logstore::{LogStoreExt as _, LogStoreRef};
+
+/// Read the change data feed of a table without DataFusion.
+/// See this module's documentation for more information
+pub struct CdfReader {
+    /// A snapshot of the table, bounding the changes if no ending version is set
+    snapshot: Option<EagerSnapshot>,
+    /// Delta object store for handling data files
+    log_store: LogStoreRef,
+    /// Version to read from
+    starting_version: Version,
+    /// Version (inclusive) to stop reading at
+    ending_version: Option<Version>,
+}
+
+impl CdfReader {
+    /// Create a new [`CdfReader`]
+    pub(crate) fn new(log_store: LogStoreRef, snapshot: Option<EagerSnapshot>) -> Self {
+        Self {
+            snapshot,
+            log_store,
+            starting_version: 0,
+            ending_version: None,
+        }
+    }
+
+    /// Version to start at (version 0 if not provided)
+    pub fn with_starting_version(mut self, starting_version: Version) -> Self {
+        self.starting_version = starting_version;
+        self
+    }
+
+    /// Version (inclusive) to end at, the version of the table if not provided
+    pub fn with_ending_version(mut self, ending_version: Version) -> Self {
+        self.ending_version = Some(ending_version);
+        self
+    }
+}
+
+impl std::future::IntoFuture for CdfReader {
+    type Output = DeltaResult<SendableRBStream>;
+    type IntoFuture = BoxFuture<'static, Self::Output>;
+
+    fn into_future(self) -> Self::IntoFuture {
+        let this = self;
+
+        Box::pin(async move {
+            let snapshot =
+                resolve_snapshot(this.log_store.as_ref(), this.snapshot, false, None).await?;
+            let start = this.starting_version;
+            let end = this.ending_version.unwrap_or(snapshot.version());
+            if end < start {
+                return Err(DeltaTableError::ChangeDataInvalidVersionRange { start, end });
+            }
+
+            let engine = this.log_store.engine(None);
+            let table_root = this.log_store.table_root_url();
+            let task_engine = engine.clone();
+            let scan = spawn_blocking_with_span(move || {
+                TableChanges::try_new(table_root, task_engine.as_ref(), start, Some(end))?
+                    .into_scan_builder()
+                    .build()
+            })
+            .await
+            .map_err(|e| DeltaTableError::Generic(e.to_string()))??;
+
+            let mut builder = RecordBatchReceiverStreamBuilder::new(100);
+            let tx = builder.tx();
+            builder.spawn_blocking(move || {
+                for res in scan.execute(engine)? {
+                    let batch = ArrowEngineData::try_from_engine_data(res?)?.into();
+                    if tx.blocking_send(Ok(batch)).is_err() {
+                        break;
+                    }
+                }
+                Ok(())
+            });
+            Ok(builder.build())
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use arrow::array::{Array as _, AsArray as _};
+    use arrow::datatypes::{DataType, Int64Type};
+    use futures::TryStreamExt as _;
+
+    use super::*;
+    use crate::writer::test_utils::{get_delta_schema, get_record_batch};
+    use crate::writer::{DeltaWriter as _, RecordBatchWriter};
+    use crate::{DeltaTable, TableProperty};
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn test_cdf_reader() -> DeltaResult<()> {
+        let mut table = DeltaTable::new_in_memory()
+            .create()
+            .with_columns(get_delta_schema().fields().cloned())
+            .with_configuration_property(TableProperty::EnableChangeDataFeed, Some("true"))
+            .await?;
+        let batch = get_record_batch(None, false);
+        let mut writer = RecordBatchWriter::for_table(&table)?;
+        writer.write(batch.clone()).await?;
+        writer.flush_and_commit(&mut table).await?;
+        assert_eq!(table.version(), Some(1));
+
+        let batches: Vec<_> = table.clone().cdf_reader().await?.try_collect().await?;
+        let num_rows: usize = batches.iter().map(|batch| batch.num_rows()).sum();
+        assert_eq!(num_rows, batch.num_rows());
+        for changes in &batches {
+            let change_type = changes.column_by_name("_change_type").unwrap();
+            let change_type = arrow::compute::cast(change_type, &DataType::Utf8)?;
+            assert!(
+                change_type
+                    .as_string::<i32>()
+                    .iter()
+                    .all(|value| value == Some("insert"))
+            );
+            let version = changes.column_by_name("_commit_version").unwrap();
+            assert_eq!(version.null_count(), 0);
+            assert!(
+                version
+                    .as_primitive::<Int64Type>()
+                    .values()
+                    .iter()
+                    .all(|version| *version == 1)
+            );
+            assert!(changes.column_by_name("_commit_timestamp").is_some());
+        }
+
+        let batches: Vec<_> = table
+            .clone()
+            .cdf_reader()
+            .with_ending_version(0)
+            .await?
+            .try_collect()
+            .await?;
+        assert_eq!(
+            batches.iter().map(|batch| batch.num_rows()).sum::<usize>(),
+            0
+        );
+
+        let err = table
+            .cdf_reader()
+            .with_starting_version(1)
+            .with_ending_version(0)
+            .await
+            .err()
+            .unwrap();
+        assert!(matches!(
+            err,
+            DeltaTableError::ChangeDataInvalidVersionRange { start: 1, end: 0 }
+        ));
+        Ok(())
+    }
+}
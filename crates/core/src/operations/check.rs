@@ -0,0 +1,786 @@
+//! Check the integrity of the log of a table.
+//!
+//! Readers and writers trust the log: a remove without a matching add, stats which do not parse
+//! or a `_last_checkpoint` pointing to a missing checkpoint usually go unnoticed until a query
+//! returns wrong results or a reader fails. This operation replays the log, starting at the
+//! first commit or, if earlier commits were cleaned up, at the latest checkpoint before the
+//! first available commit, and reports
+//! - protocols which are inconsistent or lack features required by the table properties,
+//! - commits which cannot be parsed,
+//! - files which are added while already active, or removed while not active,
+//! - active files whose stats cannot be parsed or whose partition values do not match the schema,
+//! - checkpoints which are incomplete and a `_last_checkpoint` which does not match them.
+//!
+//! # Example
+//! ```rust ignore
+//! let table = open_table(Url::from_directory_path("/abs/path/to/table").unwrap()).await?;
+//! let (table, report) = table.check().await?;
+//! for issue in &report.issues {
+//!     println!("{issue}");
+//! }
+//! ````
+
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::fmt;
+
+use delta_kernel::table_features::{ColumnMappingMode, TableFeature};
+use futures::TryStreamExt as _;
+use futures::future::BoxFuture;
+use object_store::ObjectStoreExt as _;
+use serde::{Deserialize, Serialize};
+
+use super::verify_checkpoint::{ReplayedState, file_key, read_checkpoint};
+use crate::DeltaTable;
+use crate::errors::{DeltaResult, DeltaTableError};
+use crate::kernel::{Action, Add, EagerSnapshot, Protocol, StructType, Version, resolve_snapshot};
+use crate::logstore::{LogStore, LogStoreRef, get_actions};
+use crate::table::state::DeltaTableState;
+
+/// Check the integrity of the log of a table.
+/// See this module's documentation for more information
+pub struct CheckBuilder {
+    /// A snapshot of the table
+    snapshot: Option<EagerSnapshot>,
+    /// Delta object store for handling the log
+    log_store: LogStoreRef,
+}
+
+/// A problem found in the log
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase", tag = "type")]
+pub enum IntegrityIssue {
+    /// The versions and feature lists of the protocol contradict each other
+    InvalidProtocol {
+        /// Why the protocol is invalid
+        reason: String,
+    },
+    /// A table property is set which requires a feature the protocol does not support
+    MissingFeature {
+        /// The table property
+        property: String,
+        /// The feature required by the property
+        feature: String,
+    },
+    /// A commit could not be parsed
+    InvalidCommit {
+        /// Version of the commit
+        version: Version,
+        /// The parse error
+        error: String,
+    },
+    /// A file was added while it is already active
+    DuplicateAdd {
+        /// Version of the commit adding the file again
+        version: Version,
+        /// Path of the file
+        path: String,
+    },
+    /// A file was removed which is not active
+    RemoveWithoutAdd {
+        /// Version of the commit removing the file
+        version: Version,
+        /// Path of the file
+        path: String,
+    },
+    /// The stats of an active file cannot be parsed
+    InvalidStats {
+        /// Path of the file
+        path: String,
+        /// The parse error
+        error: String,
+    },
+    /// The partition values of an active file do not match the partition columns of the schema
+    InvalidPartitionValues {
+        /// Path of the file
+        path: String,
+        /// Why the partition values are invalid
+        reason: String,
+    },
+    /// Not all parts of a multi-part checkpoint are present
+    IncompleteCheckpoint {
+        /// Version of the checkpoint
+        version: Version,
+    },
+    /// `_last_checkpoint` cannot be parsed or does not match the checkpoint it refers to
+    InvalidLastCheckpoint {
+        /// Why `_last_checkpoint` is invalid
+        reason: String,
+    },
+    /// `_last_checkpoint` refers to a checkpoint older than the latest complete one
+    StaleLastCheckpoint {
+        /// Version referred to by `_last_checkpoint`
+        version: Version,
+        /// Version of the latest complete checkpoint
+        latest: Version,
+    },
+}
+
+impl fmt::Display for IntegrityIssue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::InvalidProtocol { reason } => write!(f, "invalid protocol: {reason}"),
+            Self::MissingFeature { property, feature } => write!(
+                f,
+                "table property {property} requires the feature {feature}, which the protocol does not support"
+            ),
+            Self::InvalidCommit { version, error } => {
+                write!(f, "commit {version} cannot be parsed: {error}")
+            }
+            Self::DuplicateAdd { version, path } => {
+                write!(
+                    f,
+                    "commit {version} adds file {path}, which is already active"
+                )
+            }
+            Self::RemoveWithoutAdd { version, path } => {
+                write!(
+                    f,
+                    "commit {version} removes file {path}, which is not active"
+                )
+            }
+            Self::InvalidStats { path, error } => {
+                write!(f, "stats of file {path} cannot be parsed: {error}")
+            }
+            Self::InvalidPartitionValues { path, reason } => {
+                write!(f, "invalid partition values of file {path}: {reason}")
+            }
+            Self::IncompleteCheckpoint { version } => {
+                write!(f, "checkpoint {version} is missing parts")
+            }
+            Self::InvalidLastCheckpoint { reason } => {
+                write!(f, "invalid _last_checkpoint: {reason}")
+            }
+            Self::StaleLastCheckpoint { version, latest } => write!(
+                f,
+                "_last_checkpoint refers to checkpoint {version}, but checkpoint {latest} is newer"
+            ),
+        }
+    }
+}
+
+/// Result of checking the log of a table
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct IntegrityReport {
+    /// Version of the checked table
+    pub version: Version,
+    /// Version of the checkpoint the replay started at, if commits were cleaned up before it
+    pub checkpoint_version: Option<Version>,
+    /// Problems found in the log
+    pub issues: Vec<IntegrityIssue>,
+}
+
+impl IntegrityReport {
+    /// Whether no problems were found
+    pub fn is_valid(&self) -> bool {
+        self.issues.is_empty()
+    }
+}
+
+impl CheckBuilder {
+    /// Create a new [`CheckBuilder`]
+    pub(crate) fn new(log_store: LogStoreRef, snapshot: Option<EagerSnapshot>) -> Self {
+        CheckBuilder {
+            snapshot,
+            log_store,
+        }
+    }
+}
+
+/// A checkpoint found in the log
+#[derive(Debug, Default)]
+struct CheckpointFiles {
+    /// Number of parts found
+    parts: usize,
+    /// Number of parts declared by the file names of multi-part checkpoints
+    expected_parts: Option<usize>,
+}
+
+impl CheckpointFiles {
+    fn is_complete(&self) -> bool {
+        self.expected_parts.is_none_or(|n| n == self.parts)
+    }
+}
+
+/// The fields of `_last_checkpoint` which are checked
+#[derive(Deserialize)]
+struct LastCheckpoint {
+    version: Version,
+    parts: Option<usize>,
+}
+
+/// List the checkpoints in the log by their version
+async fn list_checkpoints(
+    log_store: &dyn LogStore,
+) -> DeltaResult<BTreeMap<Version, CheckpointFiles>> {
+    let mut checkpoints = BTreeMap::<Version, CheckpointFiles>::new();
+    let mut files = log_store
+        .object_store(None)
+        .list(Some(log_store.log_path()));
+    while let Some(meta) = files.try_next().await? {
+        let Some(name) = meta.location.filename() else {
+            continue;
+        };
+        let mut parts = name.split('.');
+        let (Some(version), Some("checkpoint")) = (parts.next(), parts.next()) else {
+            continue;
+        };
+        let Ok(version) = version.parse::<Version>() else {
+            continue;
+        };
+        let checkpoint = checkpoints.entry(version).or_default();
+        checkpoint.parts += 1;
+        // multi-part checkpoints are named <version>.checkpoint.<part>.<parts>.parquet
+        let rest: Vec<_> = parts.collect();
+        if let [_, num_parts, "parquet"] = rest.as_slice() {
+            checkpoint.expected_parts = num_parts.parse().ok();
+        }
+    }
+    Ok(checkpoints)
+}
+
+/// Check the checkpoints against each other and `_last_checkpoint`
+async fn check_checkpoints(
+    log_store: &dyn LogStore,
+    checkpoints: &BTreeMap<Version, CheckpointFiles>,
+    version: Version,
+) -> DeltaResult<Vec<IntegrityIssue>> {
+    let mut issues: Vec<_> = checkpoints
+        .iter()
+        .filter(|(_, checkpoint)| !checkpoint.is_complete())
+        .map(|(version, _)| IntegrityIssue::IncompleteCheckpoint { version: *version })
+        .collect();
+
+    let path = log_store.log_path().child("_last_checkpoint");
+    let data = match log_store.object_store(None).get(&path).await {
+        Ok(result) => result.bytes().await?,
+        Err(object_store::Error::NotFound { .. }) => return Ok(issues),
+        Err(err) => return Err(err.into()),
+    };
+    let last_checkpoint: LastCheckpoint = match serde_json::from_slice(&data) {
+        Ok(last_checkpoint) => last_checkpoint,
+        Err(err) => {
+            issues.push(IntegrityIssue::InvalidLastCheckpoint {
+                reason: err.to_string(),
+            });
+            return Ok(issues);
+        }
+    };
+
+    let invalid = |reason: String| IntegrityIssue::InvalidLastCheckpoint { reason };
+    match checkpoints.get(&last_checkpoint.version) {
+        _ if last_checkpoint.version > version => issues.push(invalid(format!(
+            "refers to version {}, but the table is at version {version}",
+            last_checkpoint.version
+        ))),
+        None => issues.push(invalid(format!(
+            "refers to checkpoint {}, which does not exist",
+            last_checkpoint.version
+        ))),
+        Some(checkpoint) if last_checkpoint.parts.unwrap_or(1) != checkpoint.parts => {
+            issues.push(invalid(format!(
+                "declares {} parts for checkpoint {}, but {} exist",
+                last_checkpoint.parts.unwrap_or(1),
+                last_checkpoint.version,
+                checkpoint.parts
+            )))
+        }
+        Some(_) => {}
+    }
+    if let Some(latest) = checkpoints
+        .iter()
+        .rev()
+        .find(|(v, checkpoint)| **v <= version && checkpoint.is_complete())
+        .map(|(v, _)| *v)
+        && latest > last_checkpoint.version
+    {
+        issues.push(IntegrityIssue::StaleLastCheckpoint {
+            version: last_checkpoint.version,
+            latest,
+        });
+    }
+    Ok(issues)
+}
+
+/// Replay the commits from `start` to `version` on top of `state`
+async fn replay_commits(
+    log_store: &dyn LogStore,
+    state: &mut ReplayedState,
+    start: Version,
+    version: Version,
+    issues: &mut Vec<IntegrityIssue>,
+) -> DeltaResult<()> {
+    for commit in start..=version {
+        let bytes = log_store.read_commit_entry(commit).await?.ok_or_else(|| {
+            DeltaTableError::Generic(format!(
+                "Commit {commit} is missing from the log, the log cannot be checked"
+            ))
+        })?;
+        let actions = match get_actions(commit, &bytes) {
+            Ok(actions) => actions,
+            Err(err) => {
+                issues.push(IntegrityIssue::InvalidCommit {
+                    version: commit,
+                    error: err.to_string(),
+                });
+                continue;
+            }
+        };
+
+        let mut added = HashSet::new();
+        for action in actions {
+            match action {
+                Action::Add(add) => {
+                    let key = file_key(&add.path, add.deletion_vector.as_ref());
+                    // adding an active file again is allowed to update its stats or tags
+                    if !added.insert(key.clone())
+                        || (add.data_change && state.files.contains_key(&key))
+                    {
+                        issues.push(IntegrityIssue::DuplicateAdd {
+                            version: commit,
+                            path: add.path.clone(),
+                        });
+                    }
+                    state.files.insert(key, add);
+                }
+                Action::Remove(remove) => {
+                    let key = file_key(&remove.path, remove.deletion_vector.as_ref());
+                    if state.files.remove(&key).is_none() {
+                        issues.push(IntegrityIssue::RemoveWithoutAdd {
+                            version: commit,
+                            path: remove.path,
+                        });
+                    }
+                }
+                Action::Protocol(protocol) => state.protocol = Some(protocol),
+                Action::Metadata(metadata) => state.metadata = Some(metadata),
+                _ => {}
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Table properties which require a table feature, along with the protocol versions which
+/// support the feature on tables without table features
+fn required_features(
+    configuration: &HashMap<String, String>,
+) -> Vec<(&'static str, TableFeature, Option<(i32, i32)>)> {
+    let is_set = |property: &str, value: &str| {
+        configuration
+            .get(property)
+            .is_some_and(|v| v.eq_ignore_ascii_case(value))
+    };
+    let mut features = Vec::new();
+    if is_set("delta.appendOnly", "true") {
+        features.push(("delta.appendOnly", TableFeature::AppendOnly, Some((1, 2))));
+    }
+    if is_set("delta.enableChangeDataFeed", "true") {
+        features.push((
+            "delta.enableChangeDataFeed",
+            TableFeature::ChangeDataFeed,
+            Some((1, 4)),
+        ));
+    }
+    if configuration
+        .get("delta.columnMapping.mode")
+        .is_some_and(|mode| !mode.eq_ignore_ascii_case("none"))
+    {
+        features.push((
+            "delta.columnMapping.mode",
+            TableFeature::ColumnMapping,
+            Some((2, 5)),
+        ));
+    }
+    if is_set("delta.enableDeletionVectors", "true") {
+        features.push((
+            "delta.enableDeletionVectors",
+            TableFeature::DeletionVectors,
+            None,
+        ));
+    }
+    if is_set("delta.enableRowTracking", "true") {
+        features.push(("delta.enableRowTracking", TableFeature::RowTracking, None));
+    }
+    if is_set("delta.enableInCommitTimestamps", "true") {
+        features.push((
+            "delta.enableInCommitTimestamps",
+            TableFeature::InCommitTimestamp,
+            None,
+        ));
+    }
+    if is_set("delta.checkpointPolicy", "v2") {
+        features.push(("delta.checkpointPolicy", TableFeature::V2Checkpoint, None));
+    }
+    features
+}
+
+fn check_protocol(
+    protocol: &Protocol,
+    configuration: &HashMap<String, String>,
+    issues: &mut Vec<IntegrityIssue>,
+) {
+    let reader_version = protocol.min_reader_version();
+    let writer_version = protocol.min_writer_version();
+    let mut invalid = |reason: String| issues.push(IntegrityIssue::InvalidProtocol { reason });
+    match (reader_version >= 3, protocol.reader_features()) {
+        (true, None) => invalid(format!(
+            "reader version {reader_version} requires a list of reader features"
+        )),
+        (false, Some(_)) => invalid(format!(
+            "reader version {reader_version} does not support reader features"
+        )),
+        _ => {}
+    }
+    match (writer_version >= 7, protocol.writer_features()) {
+        (true, None) => invalid(format!(
+            "writer version {writer_version} requires a list of writer features"
+        )),
+        (false, Some(_)) => invalid(format!(
+            "writer version {writer_version} does not support writer features"
+        )),
+        _ => {}
+    }
+    if reader_version >= 3 && writer_version < 7 {
+        invalid(format!(
+            "reader version {reader_version} requires writer version 7, found {writer_version}"
+        ));
+    }
+    if let (Some(reader_features), Some(writer_features)) =
+        (protocol.reader_features(), protocol.writer_features())
+    {
+        for feature in reader_features {
+            if !writer_features.contains(feature) {
+                invalid(format!(
+                    "reader feature {feature} is not listed as writer feature"
+                ));
+            }
+        }
+    }
+
+    for (property, feature, legacy_versions) in required_features(configuration) {
+        let supported = match protocol.writer_features() {
+            Some(features) => features.contains(&feature),
+            None => legacy_versions.is_some_and(|(reader, writer)| {
+                reader_version >= reader && writer_version >= writer
+            }),
+        };
+        if !supported {
+            issues.push(IntegrityIssue::MissingFeature {
+                property: property.to_string(),
+                feature: feature.to_string(),
+            });
+        }
+    }
+}
+
+/// Check the partition values of a file, returning why they are invalid
+fn check_partition_values(
+    add: &Add,
+    schema: &StructType,
+    partition_columns: &[String],
+    column_mapping_mode: ColumnMappingMode,
+) -> Option<String> {
+    let mut physical_names = HashSet::new();
+    for column in partition_columns {
+        let Some(field) = schema.field(column) else {
+            return Some(format!("partition column {column} is not in the schema"));
+        };
+        let name = field.physical_name(column_mapping_mode);
+        physical_names.insert(name);
+        let Some(value) = add.partition_values.get(name) else {
+            return Some(format!("missing value of partition column {column}"));
+        };
+        let Some(value) = value else {
+            continue;
+        };
+        let Some(data_type) = field.data_type().as_primitive_opt() else {
+            return Some(format!(
+                "partition column {column} is not of a primitive type"
+            ));
+        };
+        if let Err(err) = data_type.parse_scalar(value) {
+            return Some(format!(
+                "value {value:?} of partition column {column} is not a valid {data_type}: {err}"
+            ));
+        }
+    }
+    add.partition_values
+        .keys()
+        .find(|key| !physical_names.contains(key.as_str()))
+        .map(|key| format!("{key} is not a partition column"))
+}
+
+fn check_files(
+    state: &ReplayedState,
+    column_mapping_mode: ColumnMappingMode,
+    issues: &mut Vec<IntegrityIssue>,
+) -> DeltaResult<()> {
+    let Some(metadata) = &state.metadata else {
+        return Ok(());
+    };
+    let schema = metadata.parse_schema()?;
+
+    let mut files: Vec<_> = state.files.values().collect();
+    files.sort_by(|a, b| a.path.cmp(&b.path));
+    for add in files {
+        if let Err(err) = add.get_stats() {
+            issues.push(IntegrityIssue::InvalidStats {
+                path: add.path.clone(),
+                error: err.to_string(),
+            });
+        }
+        if let Some(reason) = check_partition_values(
+            add,
+            &schema,
+            metadata.partition_columns(),
+            column_mapping_mode,
+        ) {
+            issues.push(IntegrityIssue::InvalidPartitionValues {
+                path: add.path.clone(),
+                reason,
+            });
+        }
+    }
+    Ok(())
+}
+
+impl std::future::IntoFuture for CheckBuilder {
+    type Output = DeltaResult<(DeltaTable, IntegrityReport)>;
+    type IntoFuture = BoxFuture<'static, Self::Output>;
+
+    fn into_future(self) -> Self::IntoFuture {
+        let this = self;
+
+        Box::pin(async move {
+            let snapshot =
+                resolve_snapshot(this.log_store.as_ref(), this.snapshot, false, None).await?;
+            let version = snapshot.version();
+            let log_store = this.log_store.as_ref();
+
+            let checkpoints = list_checkpoints(log_store).await?;
+            let mut issues = check_checkpoints(log_store, &checkpoints, version).await?;
+
+            // start at the latest complete checkpoint if the commits before it were cleaned up
+            let (mut state, start, checkpoint_version) =
+                if log_store.read_commit_entry(0).await?.is_some() {
+                    (ReplayedState::default(), 0, None)
+                } else {
+                    let checkpoint_version = checkpoints
+                        .iter()
+                        .rev()
+                        .find(|(v, checkpoint)| **v <= version && checkpoint.is_complete())
+                        .map(|(v, _)| *v)
+                        .ok_or_else(|| {
+                            DeltaTableError::Generic(
+                                "The first commit was cleaned up and no checkpoint replaces it, \
+                                the log cannot be checked"
+                                    .to_string(),
+                            )
+                        })?;
+                    let (state, _) = read_checkpoint(log_store, checkpoint_version).await?;
+                    (state, checkpoint_version + 1, Some(checkpoint_version))
+                };
+            replay_commits(log_store, &mut state, start, version, &mut issues).await?;
+
+            if let Some(protocol) = &state.protocol {
+                let configuration = state
+                    .metadata
+                    .as_ref()
+                    .map(|metadata| metadata.configuration().clone())
+                    .unwrap_or_default();
+                check_protocol(protocol, &configuration, &mut issues);
+            }
+            check_files(
+                &state,
+                snapshot.table_configuration().column_mapping_mode(),
+                &mut issues,
+            )?;
+
+            Ok((
+                DeltaTable::new_with_state(this.log_store, DeltaTableState::new(snapshot)),
+                IntegrityReport {
+                    version,
+                    checkpoint_version,
+                    issues,
+                },
+            ))
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use object_store::path::Path;
+
+    use super::*;
+    use crate::kernel::Remove;
+    use crate::kernel::transaction::{CommitBuilder, CommitProperties};
+    use crate::protocol::DeltaOperation;
+    use crate::writer::test_utils::{get_delta_metadata, get_delta_schema};
+
+    async fn commit(table: &DeltaTable, actions: Vec<Action>) -> DeltaResult<DeltaTable> {
+        let commit = CommitBuilder::from(CommitProperties::default())
+            .with_actions(actions)
+            .build(
+                Some(table.snapshot()?),
+                table.log_store(),
+                DeltaOperation::custom("TEST"),
+            )
+            .await?;
+        Ok(DeltaTable::new_with_state(
+            table.log_store(),
+            commit.snapshot,
+        ))
+    }
+
+    fn add(path: &str, modified: &str) -> Add {
+        Add {
+            path: path.to_string(),
+            size: 100,
+            modification_time: 0,
+            data_change: true,
+            partition_values: HashMap::from([("modified".to_string(), Some(modified.to_string()))]),
+            ..Default::default()
+        }
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn test_check() -> DeltaResult<()> {
+        let table = DeltaTable::new_in_memory()
+            .create()
+            .with_columns(get_delta_schema().fields().cloned())
+            .with_partition_columns(["modified"])
+            .await?;
+        let table = commit(&table, vec![Action::Add(add("a.parquet", "2021-02-01"))]).await?;
+        let (table, report) = table.check().await?;
+        assert!(report.is_valid(), "{:?}", report.issues);
+        assert_eq!(report.version, 1);
+        assert_eq!(report.checkpoint_version, None);
+
+        let table = commit(
+            &table,
+            vec![
+                Action::Add(add("a.parquet", "2021-02-01")),
+                Action::Remove(Remove {
+                    path: "b.parquet".to_string(),
+                    data_change: true,
+                    ..Default::default()
+                }),
+            ],
+        )
+        .await?;
+        let (table, report) = table.check().await?;
+        assert_eq!(
+            report.issues,
+            vec![
+                IntegrityIssue::DuplicateAdd {
+                    version: 2,
+                    path: "a.parquet".to_string()
+                },
+                IntegrityIssue::RemoveWithoutAdd {
+                    version: 2,
+                    path: "b.parquet".to_string()
+                },
+            ]
+        );
+
+        // _last_checkpoint refers to a checkpoint which was deleted
+        let (table, _) = table.checkpoint().await?;
+        table
+            .log_store()
+            .object_store(None)
+            .delete(&Path::from(
+                "_delta_log/00000000000000000002.checkpoint.parquet",
+            ))
+            .await?;
+        let (_, report) = table.check().await?;
+        assert_eq!(
+            report.issues[0],
+            IntegrityIssue::InvalidLastCheckpoint {
+                reason: "refers to checkpoint 2, which does not exist".to_string()
+            }
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_check_protocol() {
+        let protocol: Protocol =
+            serde_json::from_str(r#"{"minReaderVersion":1,"minWriterVersion":2}"#).unwrap();
+        let configuration =
+            HashMap::from([("delta.enableChangeDataFeed".to_string(), "true".to_string())]);
+        let mut issues = Vec::new();
+        check_protocol(&protocol, &configuration, &mut issues);
+        assert_eq!(
+            issues,
+            vec![IntegrityIssue::MissingFeature {
+                property: "delta.enableChangeDataFeed".to_string(),
+                feature: "changeDataFeed".to_string(),
+            }]
+        );
+    }
+
+    #[test]
+    fn test_check_files() -> DeltaResult<()> {
+        let mut valid = add("a.parquet", "2021-02-01");
+        valid.stats = Some(r#"{"numRecords":1}"#.to_string());
+        let mut invalid_stats = add("b.parquet", "2021-02-01");
+        invalid_stats.stats = Some("{not json".to_string());
+        let mut unknown_column = add("c.parquet", "2021-02-01");
+        unknown_column
+            .partition_values
+            .insert("id".to_string(), None);
+        let mut missing_value = add("d.parquet", "2021-02-01");
+        missing_value.partition_values.clear();
+
+        let state = ReplayedState {
+            protocol: None,
+            metadata: Some(get_delta_metadata(&["modified".to_string()])),
+            files: [valid, invalid_stats, unknown_column, missing_value]
+                .into_iter()
+                .map(|add| (add.path.clone(), add))
+                .collect(),
+        };
+        let mut issues = Vec::new();
+        check_files(&state, ColumnMappingMode::None, &mut issues)?;
+
+        assert_eq!(issues.len(), 3, "{issues:?}");
+        assert!(
+            matches!(&issues[0], IntegrityIssue::InvalidStats { path, .. } if path == "b.parquet")
+        );
+        assert_eq!(
+            issues[1],
+            IntegrityIssue::InvalidPartitionValues {
+                path: "c.parquet".to_string(),
+                reason: "id is not a partition column".to_string()
+            }
+        );
+        assert_eq!(
+            issues[2],
+            IntegrityIssue::InvalidPartitionValues {
+                path: "d.parquet".to_string(),
+                reason: "missing value of partition column modified".to_string()
+            }
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_check_partition_value_types() {
+        let schema = get_delta_schema();
+        let columns = ["value".to_string()];
+        let mut file = add("a.parquet", "2021-02-01");
+        file.partition_values = HashMap::from([("value".to_string(), Some("1".to_string()))]);
+        assert_eq!(
+            check_partition_values(&file, &schema, &columns, ColumnMappingMode::None),
+            None
+        );
+
+        file.partition_values
+            .insert("value".to_string(), Some("one".to_string()));
+        let reason =
+            check_partition_values(&file, &schema, &columns, ColumnMappingMode::None).unwrap();
+        assert!(reason.starts_with("value \"one\" of partition column value is not a valid"));
+    }
+}
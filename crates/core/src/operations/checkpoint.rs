@@ -0,0 +1,135 @@
+//! Write a checkpoint for the current version of a Delta table.
+//!
+//! Checkpoints are usually written as part of a commit, every `delta.checkpointInterval`
+//! commits. This operation writes one on demand, e.g. from a maintenance job which runs
+//! independently of the writers. If the table is already checkpointed at its current version,
+//! nothing is written unless the checkpoint is forced.
+//!
+//! # Example
+//! ```rust ignore
+//! let table = open_table(Url::from_directory_path("/abs/path/to/table").unwrap()).await?;
+//! let (table, metrics) = table.checkpoint().with_force(true).await?;
+//! ````
+
+use futures::future::BoxFuture;
+use object_store::{Error as ObjectStoreError, ObjectStoreExt as _};
+use serde::Serialize;
+use tracing::debug;
+
+use crate::DeltaTable;
+use crate::checkpoints::parse_last_checkpoint_hint;
+use crate::errors::DeltaResult;
+use crate::kernel::{EagerSnapshot, Version, resolve_snapshot};
+use crate::logstore::{LogStore, LogStoreRef};
+use crate::protocol::create_checkpoint_for;
+use crate::table::state::DeltaTableState;
+
+/// Write a checkpoint for the current table version.
+/// See this module's documentation for more information
+pub struct CheckpointBuilder {
+    /// A snapshot of the table to checkpoint
+    snapshot: Option<EagerSnapshot>,
+    /// Delta object store for handling the log
+    log_store: LogStoreRef,
+    /// Write the checkpoint even if one exists for the current version
+    force: bool,
+}
+
+/// Details of the checkpoint operation
+#[derive(Debug, Serialize)]
+pub struct CheckpointMetrics {
+    /// Version the table was checkpointed at
+    pub version: Version,
+    /// Whether a checkpoint was written, `false` if one already existed for the version
+    pub checkpoint_created: bool,
+}
+
+impl CheckpointBuilder {
+    /// Create a new [`CheckpointBuilder`]
+    pub(crate) fn new(log_store: LogStoreRef, snapshot: Option<EagerSnapshot>) -> Self {
+        CheckpointBuilder {
+            snapshot,
+            log_store,
+            force: false,
+        }
+    }
+
+    /// Write the checkpoint even if the table is already checkpointed at its current version
+    pub fn with_force(mut self, force: bool) -> Self {
+        self.force = force;
+        self
+    }
+}
+
+/// Version of the most recent checkpoint according to `_last_checkpoint`
+async fn last_checkpoint_version(log_store: &dyn LogStore) -> DeltaResult<Option<Version>> {
+    let path = log_store.log_path().child("_last_checkpoint");
+    match log_store.object_store(None).get(&path).await {
+        Ok(data) => Ok(parse_last_checkpoint_hint(&data.bytes().await?).map(|hint| hint.version)),
+        Err(ObjectStoreError::NotFound { .. }) => Ok(None),
+        Err(err) => Err(err.into()),
+    }
+}
+
+impl std::future::IntoFuture for CheckpointBuilder {
+    type Output = DeltaResult<(DeltaTable, CheckpointMetrics)>;
+    type IntoFuture = BoxFuture<'static, Self::Output>;
+
+    fn into_future(self) -> Self::IntoFuture {
+        let this = self;
+
+        Box::pin(async move {
+            let snapshot =
+                resolve_snapshot(this.log_store.as_ref(), this.snapshot, false, None).await?;
+            let version = snapshot.version();
+
+            let checkpoint_created = this.force
+                || last_checkpoint_version(this.log_store.as_ref()).await? != Some(version);
+            if checkpoint_created {
+                create_checkpoint_for(version, this.log_store.as_ref(), None).await?;
+            } else {
+                debug!("table is already checkpointed at version {version}");
+            }
+
+            Ok((
+                DeltaTable::new_with_state(this.log_store, DeltaTableState::new(snapshot)),
+                CheckpointMetrics {
+                    version,
+                    checkpoint_created,
+                },
+            ))
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use object_store::ObjectStoreExt as _;
+    use object_store::path::Path;
+
+    use super::*;
+    use crate::writer::test_utils::get_delta_schema;
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn test_checkpoint() -> DeltaResult<()> {
+        let table = DeltaTable::new_in_memory()
+            .create()
+            .with_columns(get_delta_schema().fields().cloned())
+            .await?;
+
+        let (table, metrics) = table.checkpoint().await?;
+        assert_eq!(metrics.version, 0);
+        assert!(metrics.checkpoint_created);
+        let path = Path::from("_delta_log/00000000000000000000.checkpoint.parquet");
+        let first = table.log_store().object_store(None).head(&path).await?;
+
+        let (table, metrics) = table.checkpoint().await?;
+        assert!(!metrics.checkpoint_created);
+        let meta = table.log_store().object_store(None).head(&path).await?;
+        assert_eq!(meta.last_modified, first.last_modified);
+
+        let (_, metrics) = table.checkpoint().with_force(true).await?;
+        assert!(metrics.checkpoint_created);
+        Ok(())
+    }
+}
@@ -0,0 +1,202 @@
+//! Delete expired commit and checkpoint files from the Delta log.
+//!
+//! Log files are expired once they are older than the table's `delta.logRetentionDuration`,
+//! 30 days by default, and a newer checkpoint makes them obsolete. Writers clean up the log
+//! after writing a checkpoint if `delta.enableExpiredLogCleanup` is set; this operation runs the
+//! cleanup on demand instead.
+//!
+//! # Example
+//! ```rust ignore
+//! let table = open_table(Url::from_directory_path("/abs/path/to/table").unwrap()).await?;
+//! let (table, metrics) = table.cleanup_log().with_dry_run(true).await?;
+//! println!("would delete {:?}", metrics.files_deleted);
+//! ````
+
+use chrono::{Duration, Utc};
+use futures::future::BoxFuture;
+use futures::{StreamExt, TryStreamExt};
+use serde::Serialize;
+use tracing::debug;
+
+use crate::DeltaTable;
+use crate::checkpoints::expired_log_files;
+use crate::errors::DeltaResult;
+use crate::kernel::{EagerSnapshot, resolve_snapshot};
+use crate::logstore::LogStoreRef;
+use crate::table::config::TablePropertiesExt as _;
+use crate::table::state::DeltaTableState;
+
+/// Delete expired files from the Delta log.
+/// See this module's documentation for more information
+pub struct CleanupLogBuilder {
+    /// A snapshot of the table to clean up
+    snapshot: Option<EagerSnapshot>,
+    /// Delta object store for handling the log
+    log_store: LogStoreRef,
+    /// Override the table's log retention duration
+    retention_period: Option<Duration>,
+    /// Clean up even if `delta.enableExpiredLogCleanup` is disabled
+    force: bool,
+    /// Don't delete any files. Just determine which files can be deleted
+    dry_run: bool,
+}
+
+/// Details of the log cleanup operation
+#[derive(Debug, Serialize)]
+pub struct CleanupLogMetrics {
+    /// Was this a dry run
+    pub dry_run: bool,
+    /// Log files which were deleted, or would be deleted for a dry run
+    pub files_deleted: Vec<String>,
+}
+
+impl CleanupLogBuilder {
+    /// Create a new [`CleanupLogBuilder`]
+    pub(crate) fn new(log_store: LogStoreRef, snapshot: Option<EagerSnapshot>) -> Self {
+        CleanupLogBuilder {
+            snapshot,
+            log_store,
+            retention_period: None,
+            force: false,
+            dry_run: false,
+        }
+    }
+
+    /// Override the table's `delta.logRetentionDuration`.
+    pub fn with_retention_period(mut self, retention_period: Duration) -> Self {
+        self.retention_period = Some(retention_period);
+        self
+    }
+
+    /// Clean up the log even if the table disables it via `delta.enableExpiredLogCleanup`
+    pub fn with_force(mut self, force: bool) -> Self {
+        self.force = force;
+        self
+    }
+
+    /// Only determine which files should be deleted
+    pub fn with_dry_run(mut self, dry_run: bool) -> Self {
+        self.dry_run = dry_run;
+        self
+    }
+}
+
+impl std::future::IntoFuture for CleanupLogBuilder {
+    type Output = DeltaResult<(DeltaTable, CleanupLogMetrics)>;
+    type IntoFuture = BoxFuture<'static, Self::Output>;
+
+    fn into_future(self) -> Self::IntoFuture {
+        let this = self;
+
+        Box::pin(async move {
+            let snapshot =
+                resolve_snapshot(this.log_store.as_ref(), this.snapshot, false, None).await?;
+            let config = snapshot.table_config();
+
+            let mut files = Vec::new();
+            if this.force || config.enable_expired_log_cleanup() {
+                let retention_period = match this.retention_period {
+                    Some(retention_period) => retention_period.num_milliseconds(),
+                    None => config.log_retention_duration().as_millis() as i64,
+                };
+                files = expired_log_files(
+                    snapshot.version(),
+                    this.log_store.as_ref(),
+                    Utc::now().timestamp_millis() - retention_period,
+                    None,
+                )
+                .await?;
+            } else {
+                debug!("expired log cleanup is disabled for the table");
+            }
+
+            let files_deleted = files.iter().map(|path| path.to_string()).collect();
+            if !this.dry_run && !files.is_empty() {
+                this.log_store
+                    .object_store(None)
+                    .delete_stream(futures::stream::iter(files.into_iter().map(Ok)).boxed())
+                    .try_collect::<Vec<_>>()
+                    .await?;
+            }
+
+            Ok((
+                DeltaTable::new_with_state(this.log_store, DeltaTableState::new(snapshot)),
+                CleanupLogMetrics {
+                    dry_run: this.dry_run,
+                    files_deleted,
+                },
+            ))
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use object_store::ObjectStoreExt as _;
+    use object_store::path::Path;
+
+    use super::*;
+    use crate::kernel::transaction::{CommitBuilder, CommitProperties};
+    use crate::protocol::DeltaOperation;
+    use crate::writer::test_utils::get_delta_schema;
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn test_cleanup_log() -> DeltaResult<()> {
+        let table = DeltaTable::new_in_memory()
+            .create()
+            .with_columns(get_delta_schema().fields().cloned())
+            .with_configuration_property(
+                crate::TableProperty::EnableExpiredLogCleanup,
+                Some("false"),
+            )
+            .await?;
+        let commit = CommitBuilder::from(CommitProperties::default())
+            .build(
+                Some(table.snapshot()?),
+                table.log_store(),
+                DeltaOperation::custom("NOOP"),
+            )
+            .await?;
+        let table = DeltaTable::new_with_state(table.log_store(), commit.snapshot);
+        let (table, _) = table.checkpoint().await?;
+
+        // the default retention keeps all recent files
+        let (table, metrics) = table.cleanup_log().with_force(true).await?;
+        assert!(metrics.files_deleted.is_empty());
+
+        // cleanup is disabled for the table
+        let (table, metrics) = table
+            .cleanup_log()
+            .with_retention_period(Duration::zero())
+            .await?;
+        assert!(metrics.files_deleted.is_empty());
+
+        let (table, metrics) = table
+            .cleanup_log()
+            .with_retention_period(Duration::zero())
+            .with_force(true)
+            .with_dry_run(true)
+            .await?;
+        assert!(metrics.dry_run);
+        let expired = metrics.files_deleted;
+        assert!(expired.contains(&"_delta_log/00000000000000000000.json".to_string()));
+        assert!(
+            !expired
+                .iter()
+                .any(|file| file.contains("00000000000000000001"))
+        );
+        let path = Path::from("_delta_log/00000000000000000000.json");
+        let store = table.log_store().object_store(None);
+        assert!(store.head(&path).await.is_ok());
+
+        let (_, metrics) = table
+            .cleanup_log()
+            .with_retention_period(Duration::zero())
+            .with_force(true)
+            .await?;
+        assert!(!metrics.dry_run);
+        assert_eq!(metrics.files_deleted, expired);
+        assert!(store.head(&path).await.is_err());
+        Ok(())
+    }
+}
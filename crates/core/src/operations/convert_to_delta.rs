@@ -18,6 +18,7 @@ use percent_encoding::percent_decode_str;
 use tracing::debug;
 use uuid::Uuid;
 
+use super::progress::{OperationProgress, ProgressCallback, ProgressTracker};
 use super::{CustomExecuteHandler, Operation};
 use crate::kernel::schema::cast::normalize_for_delta;
 use crate::kernel::transaction::CommitProperties;
@@ -115,6 +116,8 @@ pub struct ConvertToDeltaBuilder {
     configuration: HashMap<String, Option<String>>,
     /// Additional information to add to the commit
     commit_properties: CommitProperties,
+    /// Receives the progress of reading the Parquet footers
+    progress_callback: Option<ProgressCallback>,
     custom_execute_handler: Option<Arc<dyn CustomExecuteHandler>>,
 }
 
@@ -149,6 +152,7 @@ impl ConvertToDeltaBuilder {
             comment: None,
             configuration: Default::default(),
             commit_properties: CommitProperties::default(),
+            progress_callback: None,
             custom_execute_handler: None,
         }
     }
@@ -245,6 +249,17 @@ impl ConvertToDeltaBuilder {
         self
     }
 
+    /// Report the progress of the conversion to `callback`.
+    ///
+    /// A file counts as processed once its footer was read to collect its schema and statistics.
+    pub fn with_progress_callback(
+        mut self,
+        callback: impl Fn(&OperationProgress) + Send + Sync + 'static,
+    ) -> Self {
+        self.progress_callback = Some(ProgressCallback::new(callback));
+        self
+    }
+
     /// Set a custom execute handler, for pre and post execution
     pub fn with_custom_execute_handler(mut self, handler: Arc<dyn CustomExecuteHandler>) -> Self {
         self.custom_execute_handler = Some(handler);
@@ -313,6 +328,12 @@ impl ConvertToDeltaBuilder {
         let (num_indexed_cols, stats_columns) =
             get_num_idx_cols_and_stats_columns(None, self.configuration.clone());
 
+        let mut progress = ProgressTracker::start(
+            self.progress_callback.clone(),
+            "CONVERT TO DELTA",
+            files.len(),
+        );
+
         for file in files {
             // A HashMap from partition column to value for this parquet file only
             let mut partition_values = HashMap::new();
@@ -410,6 +431,7 @@ impl ConvertToDeltaBuilder {
             // Since Arrow schema metadata is not used to generate Delta table schema, we set the metadata field to an empty HashMap
             arrow_schema.metadata = HashMap::new();
             arrow_schemas.push(arrow_schema);
+            progress.advance(1, 0);
         }
 
         if !expected_partitions.is_empty() {
@@ -931,6 +953,42 @@ mod tests {
             .expect_err("Location is missing. Should error");
     }
 
+    #[tokio::test]
+    async fn test_convert_to_delta_progress() {
+        let root = tempdir().expect("Failed to create a temp directory");
+        let temp_dir = root
+            .path()
+            .to_str()
+            .expect("Failed to convert Path to string slice");
+        copy_files(
+            format!(
+                "{}/../test/tests/data/delta-0.8.0-partitioned",
+                env!("CARGO_MANIFEST_DIR")
+            ),
+            temp_dir,
+        );
+        let reported = Arc::new(parking_lot::Mutex::new(Vec::new()));
+        ConvertToDeltaBuilder::new()
+            .with_log_store(log_store(temp_dir))
+            .with_partition_schema(vec![
+                schema_field("year", PrimitiveType::String, true),
+                schema_field("month", PrimitiveType::String, true),
+                schema_field("day", PrimitiveType::String, true),
+            ])
+            .with_progress_callback({
+                let reported = reported.clone();
+                move |progress| reported.lock().push(*progress)
+            })
+            .await
+            .expect("Failed to convert to Delta table");
+
+        let reported = reported.lock();
+        let last = reported.last().expect("Progress should be reported");
+        assert_eq!(last.operation, "CONVERT TO DELTA");
+        assert_eq!(last.files_processed, last.files_total);
+        assert_eq!(reported.len(), last.files_total + 1);
+    }
+
     #[tokio::test]
     async fn test_empty_dir() {
         let temp_dir = tempdir().expect("Failed to create a temp directory");
@@ -12,7 +12,9 @@ use uuid::Uuid;
 
 use super::{CustomExecuteHandler, Operation};
 use crate::errors::{ColumnMappingOperation, DeltaResult, DeltaTableError};
-use crate::kernel::transaction::{CommitBuilder, CommitProperties, PROTOCOL, TableReference};
+use crate::kernel::transaction::{
+    CommitBuilder, CommitHookRef, CommitProperties, PROTOCOL, TableReference,
+};
 use crate::kernel::{
     Action, DataType, MetadataExt, ProtocolExt as _, ProtocolInner, StructField, StructType,
     new_metadata,
@@ -21,7 +23,7 @@ use crate::logstore::LogStoreRef;
 use crate::protocol::{DeltaOperation, SaveMode};
 use crate::table::builder::ensure_table_uri;
 use crate::table::config::TableProperty;
-use crate::table::normalize_table_url;
+use crate::table::{normalize_table_url, with_column_default};
 use crate::{DeltaTable, DeltaTableBuilder};
 
 #[derive(thiserror::Error, Debug)]
@@ -40,6 +42,9 @@ enum CreateError {
 
     #[error("SaveMode `append` is not allowed for create operation.")]
     AppendNotAllowed,
+
+    #[error("Cannot set a default value on unknown column: {0}")]
+    UnknownDefaultColumn(String),
 }
 
 impl From<CreateError> for DeltaTableError {
@@ -82,6 +87,7 @@ pub struct CreateBuilder {
     mode: SaveMode,
     comment: Option<String>,
     columns: Vec<StructField>,
+    column_defaults: Vec<(String, String)>,
     partition_columns: Option<Vec<String>>,
     storage_options: Option<HashMap<String, String>>,
     actions: Vec<Action>,
@@ -91,6 +97,7 @@ pub struct CreateBuilder {
     commit_properties: CommitProperties,
     raise_if_key_not_exists: bool,
     custom_execute_handler: Option<Arc<dyn CustomExecuteHandler>>,
+    commit_hooks: Vec<CommitHookRef>,
 }
 
 impl super::Operation for CreateBuilder {
@@ -119,6 +126,7 @@ impl CreateBuilder {
             mode: SaveMode::ErrorIfExists,
             comment: None,
             columns: Default::default(),
+            column_defaults: Default::default(),
             partition_columns: None,
             storage_options: None,
             actions: Default::default(),
@@ -127,6 +135,7 @@ impl CreateBuilder {
             commit_properties: CommitProperties::default(),
             raise_if_key_not_exists: true,
             custom_execute_handler: None,
+            commit_hooks: Vec::new(),
         }
     }
 
@@ -193,6 +202,20 @@ impl CreateBuilder {
         self
     }
 
+    /// Specify the SQL expression used as the default value of a column.
+    ///
+    /// Writers fill the column with this value when it is omitted from the written data.
+    /// Enables the `allowColumnDefaults` writer feature on the table.
+    pub fn with_column_default(
+        mut self,
+        column: impl Into<String>,
+        default_expr: impl Into<String>,
+    ) -> Self {
+        self.column_defaults
+            .push((column.into(), default_expr.into()));
+        self
+    }
+
     /// Specify table partitioning
     pub fn with_partition_columns(
         mut self,
@@ -269,6 +292,16 @@ impl CreateBuilder {
         self
     }
 
+    /// Attach a [`CommitHook`](crate::kernel::transaction::CommitHook) invoked around the
+    /// commit creating the table, e.g. to register the table in a catalog.
+    ///
+    /// Hooks which should also run for later commits are attached to the log store instead, see
+    /// [`DeltaTableBuilder::with_commit_hook`].
+    pub fn with_commit_hook(mut self, hook: CommitHookRef) -> Self {
+        self.commit_hooks.push(hook);
+        self
+    }
+
     /// Consume self into uninitialized table with corresponding create actions and operation meta
     pub(crate) async fn into_table_and_actions(
         mut self,
@@ -344,7 +377,15 @@ impl CreateBuilder {
             })
             .unwrap_or_else(|| current_protocol);
 
-        let schema = StructType::try_new(self.columns)?;
+        let mut columns = self.columns;
+        for (column, default_expr) in self.column_defaults {
+            let idx = columns
+                .iter()
+                .position(|field| field.name() == &column)
+                .ok_or(CreateError::UnknownDefaultColumn(column))?;
+            columns[idx] = with_column_default(columns[idx].clone(), &default_expr);
+        }
+        let schema = StructType::try_new(columns)?;
 
         let protocol = protocol
             .apply_properties_to_protocol(&configuration, self.raise_if_key_not_exists)?
@@ -419,10 +460,14 @@ impl std::future::IntoFuture for CreateBuilder {
                 None
             };
 
-            let version = CommitBuilder::from(this.commit_properties.clone())
+            let mut commit = CommitBuilder::from(this.commit_properties.clone())
                 .with_actions(actions)
                 .with_operation_id(operation_id)
-                .with_post_commit_hook_handler(handler.clone())
+                .with_post_commit_hook_handler(handler.clone());
+            for hook in &this.commit_hooks {
+                commit = commit.with_commit_hook(hook.clone());
+            }
+            let version = commit
                 .build(
                     table_state.map(|f| f as &dyn TableReference),
                     table.log_store.clone(),
@@ -445,6 +490,7 @@ impl std::future::IntoFuture for CreateBuilder {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::kernel::StructTypeExt as _;
     use crate::table::config::TableProperty;
     use crate::writer::test_utils::get_delta_schema;
     use delta_kernel::table_features::TableFeature;
@@ -588,6 +634,36 @@ mod tests {
         );
     }
 
+    #[tokio::test]
+    async fn test_create_table_with_column_default() {
+        let table = CreateBuilder::new()
+            .with_location("memory:///")
+            .with_column("id", DataType::INTEGER, true, None)
+            .with_column("status", DataType::STRING, true, None)
+            .with_column_default("status", "'active'")
+            .await
+            .unwrap();
+
+        let snapshot = table.snapshot().unwrap();
+        let defaults = snapshot.schema().get_column_defaults().unwrap();
+        assert_eq!(defaults.len(), 1);
+        assert_eq!(defaults[0].name, "status");
+        assert_eq!(defaults[0].get_default_expression(), "'active'");
+
+        let protocol = snapshot.protocol();
+        assert_eq!(protocol.min_writer_version(), 7);
+        assert!(protocol.writer_features().is_some_and(|features| {
+            features.contains(&crate::kernel::allow_column_defaults_feature())
+        }));
+
+        let result = CreateBuilder::new()
+            .with_location("memory:///")
+            .with_column("id", DataType::INTEGER, true, None)
+            .with_column_default("missing", "1")
+            .await;
+        assert!(result.is_err());
+    }
+
     #[cfg(feature = "datafusion")]
     mod datafusion_tests {
         use super::*;
@@ -59,6 +59,7 @@ use uuid::Uuid;
 
 use super::Operation;
 use super::cdc::should_write_cdc;
+use super::dry_run::{DryRunMetrics, dry_run_where_matches};
 use crate::DeltaTable;
 use crate::delta_datafusion::DeltaScanConfig;
 use crate::delta_datafusion::DeltaSessionExt;
@@ -283,6 +284,72 @@ impl DeleteBuilder {
         self.custom_execute_handler = Some(handler);
         self
     }
+
+    /// Plan the delete without executing it.
+    ///
+    /// Returns the files the delete would rewrite or remove, their size and the number of
+    /// rows it would delete. Nothing is written or committed.
+    pub async fn dry_run(self) -> DeltaResult<DryRunMetrics> {
+        let snapshot = resolve_snapshot(&self.log_store, self.snapshot.clone(), true, None).await?;
+        PROTOCOL.check_append_only(&snapshot)?;
+        PROTOCOL.can_write_to(&snapshot)?;
+
+        let session = self.resolve_session(None)?;
+        let Some(predicate) = resolve_predicate(self.predicate, &session, &snapshot)? else {
+            let full_file = collect_all_file_deletes(&self.log_store, &snapshot).await?;
+            return Ok(DryRunMetrics::from_removes(
+                &full_file.removes,
+                full_file.deleted_rows,
+            ));
+        };
+        if let Some(partition_predicate) = partition_only_predicate(&snapshot, &predicate)? {
+            let full_file = collect_partition_deletes(
+                &session,
+                &self.log_store,
+                &snapshot,
+                &partition_predicate,
+            )
+            .await?;
+            return Ok(DryRunMetrics::from_removes(
+                &full_file.removes,
+                full_file.deleted_rows,
+            ));
+        }
+        dry_run_where_matches(&session, &snapshot, self.log_store.clone(), predicate).await
+    }
+
+    fn resolve_session(&self, operation_id: Option<Uuid>) -> DeltaResult<SessionState> {
+        let (session, _) = resolve_session_state(
+            self.session.as_deref(),
+            self.session_fallback_policy,
+            || create_session().state(),
+            SessionResolveContext {
+                operation: "delete",
+                table_uri: Some(self.log_store.root_url()),
+                cdc: false,
+            },
+        )?;
+        update_datafusion_session(&session, &self.log_store, operation_id)?;
+        session.ensure_log_store_registered(self.log_store.as_ref())?;
+        Ok(session)
+    }
+}
+
+/// Resolve `predicate` against the schema of the table
+fn resolve_predicate(
+    predicate: Option<Expression>,
+    session: &SessionState,
+    snapshot: &EagerSnapshot,
+) -> DeltaResult<Option<Expr>> {
+    Ok(predicate
+        .map(|p| {
+            let scan_config = DeltaScanConfig::new_from_session(session);
+            let predicate_schema = scan_config
+                .table_schema(snapshot.table_configuration())?
+                .to_dfschema_ref()?;
+            p.resolve(session, predicate_schema)
+        })
+        .transpose()?)
 }
 
 impl std::future::IntoFuture for DeleteBuilder {
@@ -301,29 +368,8 @@ impl std::future::IntoFuture for DeleteBuilder {
             let operation_id = this.get_operation_id();
             this.pre_execute(operation_id).await?;
 
-            let (session, _) = resolve_session_state(
-                this.session.as_deref(),
-                this.session_fallback_policy,
-                || create_session().state(),
-                SessionResolveContext {
-                    operation: "delete",
-                    table_uri: Some(this.log_store.root_url()),
-                    cdc: false,
-                },
-            )?;
-            update_datafusion_session(&session, &this.log_store, Some(operation_id))?;
-            session.ensure_log_store_registered(this.log_store.as_ref())?;
-
-            let predicate = this
-                .predicate
-                .map(|p| {
-                    let scan_config = DeltaScanConfig::new_from_session(&session);
-                    let predicate_schema = scan_config
-                        .table_schema(snapshot.table_configuration())?
-                        .to_dfschema_ref()?;
-                    p.resolve(&session, predicate_schema)
-                })
-                .transpose()?;
+            let session = this.resolve_session(Some(operation_id))?;
+            let predicate = resolve_predicate(this.predicate, &session, &snapshot)?;
 
             let operation = DeltaOperation::Delete {
                 predicate: predicate.as_ref().map(fmt_expr_to_sql).transpose()?,
@@ -417,6 +463,7 @@ impl ExtensionPlanner for DeleteMetricExtensionPlanner {
     skip_all,
     fields(
         operation = "delete",
+        operation_id = %operation_id,
         version = snapshot.version(),
         table_uri = %log_store.root_url(),
     )
@@ -438,95 +485,27 @@ async fn execute(
 
     let scan_start = Instant::now();
 
-    let Some(predicate) = predicate else {
+    // Files which match as a whole are only removed, even with the change data feed enabled.
+    // Readers of the change data feed derive the deleted rows from the remove actions of commits
+    // without change data files, so change data is only written for files which are rewritten.
+    let write_cdc = should_write_cdc(&snapshot)?;
+    let predicate = match predicate {
+        Some(predicate) => predicate,
         // no predicate means all files match.
-        // cdc actions are not written when table files are removed.
-        let full_file = collect_full_file_deletes(snapshot.snapshot().active_adds(
-            log_store.as_ref(),
-            ActiveAddOptions {
-                predicate: None,
-                stats: AddStatsPolicy::RawJson,
-            },
-        ))
-        .await?;
-        metrics.num_removed_files = full_file.removed_files;
-        metrics.num_deleted_rows = full_file.deleted_rows;
-        metrics.scan_time_ms = Instant::now().duration_since(scan_start).as_millis() as u64;
-        metrics.execution_time_ms = Instant::now().duration_since(exec_start).as_millis() as u64;
-        return Ok((full_file.removes, metrics));
-    };
-
-    let skipping_pred = simplify_predicates(split_conjunction_owned(predicate.clone()))?;
-    let partition_columns = snapshot
-        .table_configuration()
-        .metadata()
-        .partition_columns()
-        .to_vec();
-    let mut props = crate::delta_datafusion::FindFilesExprProperties {
-        partition_columns,
-        ..Default::default()
+        None => {
+            let full_file = collect_all_file_deletes(&log_store, &snapshot).await?;
+            metrics.num_removed_files = full_file.removed_files;
+            metrics.num_deleted_rows = full_file.deleted_rows;
+            metrics.scan_time_ms = Instant::now().duration_since(scan_start).as_millis() as u64;
+            metrics.execution_time_ms =
+                Instant::now().duration_since(exec_start).as_millis() as u64;
+            return Ok((full_file.removes, metrics));
+        }
     };
-    for term in &skipping_pred {
-        term.visit(&mut props)?;
-        std::mem::replace(&mut props.result, Ok(()))?;
-    }
 
-    if props.partition_only {
-        let partition_predicate = conjunction(skipping_pred).unwrap_or(lit(true));
-        let full_file = match crate::delta_datafusion::engine::to_delta_predicate(
-            &partition_predicate,
-        ) {
-            Ok(delta_predicate) => {
-                // `Snapshot::files` documents predicate filtering as "best effort" file skipping
-                // because, in general, files may contain a mix of matching and non-matching rows.
-                //
-                // For partition-only predicates, partition values are constant per file, so
-                // evaluating `partition_predicate` against `partitionValues_parsed` is exact:
-                // a file either fully matches or does not. It is therefore safe to treat this as
-                // the authoritative match set for DELETE.
-                collect_full_file_deletes(snapshot.snapshot().active_adds(
-                    log_store.as_ref(),
-                    ActiveAddOptions {
-                        predicate: Some(Arc::new(delta_predicate)),
-                        stats: AddStatsPolicy::RawJson,
-                    },
-                ))
-                .await?
-            }
-            Err(err) => {
-                tracing::debug!(
-                    ?err,
-                    "Partition-only delete predicate not convertible to kernel; falling back to DataFusion evaluation"
-                );
-
-                let matching_paths = Arc::new(
-                    find_file_paths_by_partition_predicate_datafusion(
-                        session,
-                        &snapshot,
-                        &partition_predicate,
-                    )
-                    .await?,
-                );
-                collect_full_file_deletes(
-                    snapshot
-                        .snapshot()
-                        .active_adds(
-                            log_store.as_ref(),
-                            ActiveAddOptions {
-                                predicate: None,
-                                stats: AddStatsPolicy::RawJson,
-                            },
-                        )
-                        .try_filter_map(|f| {
-                            let matching_paths = Arc::clone(&matching_paths);
-                            async move { Ok(matching_paths.contains(f.path_raw()).then_some(f)) }
-                        })
-                        // Box the filtered stream so it can share the helper with direct file_views.
-                        .boxed(),
-                )
-                .await?
-            }
-        };
+    if let Some(partition_predicate) = partition_only_predicate(&snapshot, &predicate)? {
+        let full_file =
+            collect_partition_deletes(session, &log_store, &snapshot, &partition_predicate).await?;
 
         metrics.scan_time_ms = Instant::now().duration_since(scan_start).as_millis() as u64;
         metrics.num_removed_files = full_file.removed_files;
@@ -597,7 +576,7 @@ async fn execute(
         }),
     });
 
-    let (write_plan, write_cdc) = if should_write_cdc(&snapshot)? {
+    let write_plan = if write_cdc {
         // create change set entries for all records we deleted
         let cdc_deletes = files_scan
             .scan()
@@ -606,16 +585,13 @@ async fn execute(
             .filter(files_scan.predicate)?
             .with_column(CDC_COLUMN_NAME, lit("delete"))?
             .build()?;
-        (
-            rescued_data
-                .into_builder()
-                .with_column(CDC_COLUMN_NAME, lit(""))?
-                .union(cdc_deletes)?
-                .build()?,
-            true,
-        )
+        rescued_data
+            .into_builder()
+            .with_column(CDC_COLUMN_NAME, lit(""))?
+            .union(cdc_deletes)?
+            .build()?
     } else {
-        (rescued_data, false)
+        rescued_data
     };
 
     let exec = session.create_physical_plan(&write_plan).await?;
@@ -623,7 +599,7 @@ async fn execute(
     let (mut actions, _) = write_exec_plan(
         session,
         log_store.as_ref(),
-        snapshot.table_configuration(),
+        &snapshot,
         exec.clone(),
         Some(operation_id),
         target_file_size,
@@ -655,6 +631,107 @@ async fn execute(
     Ok((actions, metrics))
 }
 
+/// Remove all active files of the table
+async fn collect_all_file_deletes(
+    log_store: &LogStoreRef,
+    snapshot: &EagerSnapshot,
+) -> DeltaResult<FullFileDeleteResult> {
+    collect_full_file_deletes(snapshot.snapshot().active_adds(
+        log_store.as_ref(),
+        ActiveAddOptions {
+            predicate: None,
+            stats: AddStatsPolicy::RawJson,
+        },
+    ))
+    .await
+}
+
+/// The conjunction of the terms of `predicate` if they only reference partition columns
+fn partition_only_predicate(
+    snapshot: &EagerSnapshot,
+    predicate: &Expr,
+) -> DeltaResult<Option<Expr>> {
+    let skipping_pred = simplify_predicates(split_conjunction_owned(predicate.clone()))?;
+    let partition_columns = snapshot
+        .table_configuration()
+        .metadata()
+        .partition_columns()
+        .to_vec();
+    let mut props = crate::delta_datafusion::FindFilesExprProperties {
+        partition_columns,
+        ..Default::default()
+    };
+    for term in &skipping_pred {
+        term.visit(&mut props)?;
+        std::mem::replace(&mut props.result, Ok(()))?;
+    }
+
+    Ok(props
+        .partition_only
+        .then(|| conjunction(skipping_pred).unwrap_or(lit(true))))
+}
+
+/// Remove the files whose partition values satisfy `partition_predicate`
+async fn collect_partition_deletes(
+    session: &dyn Session,
+    log_store: &LogStoreRef,
+    snapshot: &EagerSnapshot,
+    partition_predicate: &Expr,
+) -> DeltaResult<FullFileDeleteResult> {
+    match crate::delta_datafusion::engine::to_delta_predicate(partition_predicate) {
+        Ok(delta_predicate) => {
+            // `Snapshot::files` documents predicate filtering as "best effort" file skipping
+            // because, in general, files may contain a mix of matching and non-matching rows.
+            //
+            // For partition-only predicates, partition values are constant per file, so
+            // evaluating `partition_predicate` against `partitionValues_parsed` is exact:
+            // a file either fully matches or does not. It is therefore safe to treat this as
+            // the authoritative match set for DELETE.
+            collect_full_file_deletes(snapshot.snapshot().active_adds(
+                log_store.as_ref(),
+                ActiveAddOptions {
+                    predicate: Some(Arc::new(delta_predicate)),
+                    stats: AddStatsPolicy::RawJson,
+                },
+            ))
+            .await
+        }
+        Err(err) => {
+            tracing::debug!(
+                ?err,
+                "Partition-only delete predicate not convertible to kernel; falling back to DataFusion evaluation"
+            );
+
+            let matching_paths = Arc::new(
+                find_file_paths_by_partition_predicate_datafusion(
+                    session,
+                    snapshot,
+                    partition_predicate,
+                )
+                .await?,
+            );
+            collect_full_file_deletes(
+                snapshot
+                    .snapshot()
+                    .active_adds(
+                        log_store.as_ref(),
+                        ActiveAddOptions {
+                            predicate: None,
+                            stats: AddStatsPolicy::RawJson,
+                        },
+                    )
+                    .try_filter_map(|f| {
+                        let matching_paths = Arc::clone(&matching_paths);
+                        async move { Ok(matching_paths.contains(f.path_raw()).then_some(f)) }
+                    })
+                    // Box the filtered stream so it can share the helper with direct file_views.
+                    .boxed(),
+            )
+            .await
+        }
+    }
+}
+
 async fn find_file_paths_by_partition_predicate_datafusion(
     session: &dyn Session,
     snapshot: &EagerSnapshot,
@@ -1119,6 +1196,61 @@ mod tests {
         assert_batches_sorted_eq!(&expected, &actual);
     }
 
+    #[tokio::test]
+    async fn test_delete_dry_run() -> DeltaResult<()> {
+        let schema = get_arrow_schema(&None);
+        let mut table = setup_table(Some(vec!["modified"])).await;
+        for (values, modified) in [(vec![1, 10], "2021-02-01"), (vec![20, 100], "2021-02-02")] {
+            let batch = RecordBatch::try_new(
+                Arc::clone(&schema),
+                vec![
+                    Arc::new(arrow::array::StringArray::from(vec!["A", "B"])),
+                    Arc::new(arrow::array::Int32Array::from(values)),
+                    Arc::new(arrow::array::StringArray::from(vec![modified, modified])),
+                ],
+            )?;
+            table = table
+                .write(vec![batch])
+                .with_save_mode(SaveMode::Append)
+                .await?;
+        }
+        let sizes: Vec<_> = table
+            .snapshot()?
+            .log_data()
+            .iter()
+            .map(|file| file.size() as u64)
+            .collect();
+
+        let dry_run = table
+            .clone()
+            .delete()
+            .with_predicate(col("value").gt(lit(5)))
+            .dry_run()
+            .await?;
+        assert_eq!(dry_run.files.len(), 2);
+        assert_eq!(dry_run.num_bytes, sizes.iter().sum::<u64>());
+        assert_eq!(dry_run.num_affected_rows, Some(3));
+
+        let dry_run = table
+            .clone()
+            .delete()
+            .with_predicate(col("modified").eq(lit("2021-02-01")))
+            .dry_run()
+            .await?;
+        assert_eq!(dry_run.files.len(), 1);
+        assert_eq!(dry_run.num_affected_rows, Some(2));
+
+        let dry_run = table.clone().delete().dry_run().await?;
+        assert_eq!(dry_run.files.len(), 2);
+        assert_eq!(dry_run.num_affected_rows, Some(4));
+
+        // nothing was committed
+        assert_eq!(table.version(), Some(2));
+        table.update_state().await?;
+        assert_eq!(table.version(), Some(2));
+        Ok(())
+    }
+
     #[tokio::test]
     async fn test_delete_null() {
         // Demonstrate deletion of null
@@ -2600,6 +2732,99 @@ mod tests {
         ], &batches }
     }
 
+    #[tokio::test]
+    async fn test_delete_cdc_enabled_partition_only() {
+        let schema = Arc::new(Schema::new(vec![
+            Field::new("year", DataType::Utf8, true),
+            Field::new("value", DataType::Int32, true),
+        ]));
+        let kernel_schema: StructType = schema.as_ref().try_into_kernel().unwrap();
+
+        let table: DeltaTable = DeltaTable::new_in_memory()
+            .create()
+            .with_columns(kernel_schema.fields().cloned())
+            .with_partition_columns(vec!["year"])
+            .with_configuration_property(TableProperty::EnableChangeDataFeed, Some("true"))
+            .await
+            .unwrap();
+        let batch = RecordBatch::try_new(
+            Arc::clone(&schema),
+            vec![
+                Arc::new(StringArray::from(vec![
+                    Some("2020"),
+                    Some("2020"),
+                    Some("2024"),
+                ])),
+                Arc::new(Int32Array::from(vec![Some(1), Some(2), Some(3)])),
+            ],
+        )
+        .unwrap();
+        let table = table.write(vec![batch]).await.unwrap();
+
+        // whole files match, so they are only removed and no change data is written
+        let (table, metrics) = table
+            .delete()
+            .with_predicate(col("year").eq(lit("2020")))
+            .await
+            .unwrap();
+        assert_eq!(table.version(), Some(2));
+        assert_eq!(metrics.num_deleted_rows, Some(2));
+        let bytes = table
+            .log_store()
+            .read_commit_entry(2)
+            .await
+            .unwrap()
+            .unwrap();
+        let actions = crate::logstore::get_actions(2, &bytes).unwrap();
+        assert!(
+            !actions
+                .iter()
+                .any(|action| matches!(action, Action::Cdc(_)))
+        );
+
+        let (table, _metrics) = table.delete().await.unwrap();
+        assert_eq!(table.version(), Some(3));
+        let bytes = table
+            .log_store()
+            .read_commit_entry(3)
+            .await
+            .unwrap()
+            .unwrap();
+        let actions = crate::logstore::get_actions(3, &bytes).unwrap();
+        assert!(
+            !actions
+                .iter()
+                .any(|action| matches!(action, Action::Cdc(_)))
+        );
+
+        // the deleted rows are derived from the removed files
+        let ctx = create_session().into_inner();
+        let table = table
+            .scan_cdf()
+            .with_starting_version(2)
+            .build(&ctx.state(), None)
+            .await
+            .expect("Failed to load CDF");
+        let mut batches = collect_batches(
+            table.properties().output_partitioning().partition_count(),
+            table,
+            ctx,
+        )
+        .await
+        .expect("Failed to collect batches");
+        let _: Vec<_> = batches.iter_mut().map(|b| b.remove_column(4)).collect();
+
+        assert_batches_sorted_eq! {[
+            "+-------+------+--------------+-----------------+",
+            "| value | year | _change_type | _commit_version |",
+            "+-------+------+--------------+-----------------+",
+            "| 1     | 2020 | delete       | 2               |",
+            "| 2     | 2020 | delete       | 2               |",
+            "| 3     | 2024 | delete       | 3               |",
+            "+-------+------+--------------+-----------------+",
+        ], &batches }
+    }
+
     async fn collect_batches(
         num_partitions: usize,
         stream: Arc<dyn ExecutionPlan>,
@@ -0,0 +1,112 @@
+//! Plan a DELETE or UPDATE without executing it.
+//!
+//! [`DeleteBuilder::dry_run`](super::delete::DeleteBuilder::dry_run) and
+//! [`UpdateBuilder::dry_run`](super::update::UpdateBuilder::dry_run) find the files the
+//! operation would rewrite or remove and count the affected rows, but write and commit nothing.
+//! Rows matched by a predicate on data columns are counted by scanning the candidate files, while
+//! rows of files which match as a whole are taken from the file statistics.
+
+use std::sync::Arc;
+
+use datafusion::common::exec_datafusion_err;
+use datafusion::dataframe::DataFrame;
+use datafusion::execution::context::SessionState;
+use datafusion::logical_expr::Expr;
+use futures::{StreamExt as _, TryStreamExt as _, stream};
+use serde::Serialize;
+
+use crate::DeltaResult;
+use crate::delta_datafusion::logical::LogicalPlanExt as _;
+use crate::delta_datafusion::scan_files_where_matches;
+use crate::kernel::{Action, ActiveAddOptions, AddStatsPolicy, EagerSnapshot};
+use crate::logstore::LogStoreRef;
+
+/// Outcome of planning a DELETE or UPDATE without executing it
+#[derive(Default, Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DryRunMetrics {
+    /// Paths of the files the operation would rewrite or remove, relative to the table root
+    pub files: Vec<String>,
+    /// Total size of these files in bytes
+    pub num_bytes: u64,
+    /// Number of rows the operation would delete or update.
+    ///
+    /// This is `None` if the files match as a whole and some of them lack statistics.
+    pub num_affected_rows: Option<usize>,
+}
+
+impl DryRunMetrics {
+    /// Metrics of an operation removing the files of `removes` as a whole
+    pub(crate) fn from_removes<'a>(
+        removes: impl IntoIterator<Item = &'a Action>,
+        num_affected_rows: Option<usize>,
+    ) -> Self {
+        let mut metrics = Self {
+            num_affected_rows,
+            ..Default::default()
+        };
+        for action in removes {
+            if let Action::Remove(remove) = action {
+                metrics.files.push(remove.path.clone());
+                metrics.num_bytes += remove.size.unwrap_or_default().max(0) as u64;
+            }
+        }
+        metrics
+    }
+}
+
+/// Find the files containing rows which satisfy `predicate` and count these rows
+pub(crate) async fn dry_run_where_matches(
+    session: &SessionState,
+    snapshot: &EagerSnapshot,
+    log_store: LogStoreRef,
+    predicate: Expr,
+) -> DeltaResult<DryRunMetrics> {
+    let Some(files_scan) =
+        scan_files_where_matches(session, snapshot, log_store.clone(), predicate).await?
+    else {
+        return Ok(DryRunMetrics {
+            num_affected_rows: Some(0),
+            ..Default::default()
+        });
+    };
+
+    let root_url = Arc::new(snapshot.table_configuration().table_root().clone());
+    let removes: Vec<_> = snapshot
+        .snapshot()
+        .active_adds(
+            log_store.as_ref(),
+            ActiveAddOptions {
+                predicate: Some(files_scan.delta_predicate.clone()),
+                stats: AddStatsPolicy::None,
+            },
+        )
+        .zip(stream::iter(std::iter::repeat((
+            root_url,
+            Arc::new(files_scan.files_set()),
+        ))))
+        .map(|(f, u)| f.map(|f| (f, u)))
+        .try_filter_map(|(f, (root, valid))| async move {
+            let url = root
+                .clone()
+                .join(f.path_raw())
+                .map_err(|e| exec_datafusion_err!("{e}"))?;
+            let is_valid = valid.contains(url.as_ref());
+            Ok(is_valid.then(|| Action::Remove(f.remove_action(true))))
+        })
+        .try_collect()
+        .await?;
+
+    let matches = files_scan
+        .scan()
+        .clone()
+        .into_builder()
+        .filter(files_scan.predicate.clone())?
+        .build()?;
+    let num_affected_rows = DataFrame::new(session.clone(), matches).count().await?;
+
+    Ok(DryRunMetrics::from_removes(
+        &removes,
+        Some(num_affected_rows),
+    ))
+}
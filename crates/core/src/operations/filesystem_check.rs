@@ -21,7 +21,8 @@ use std::time::UNIX_EPOCH;
 use futures::StreamExt;
 use futures::TryStreamExt;
 use futures::future::BoxFuture;
-use object_store::ObjectStore;
+use object_store::path::Path;
+use object_store::{Error as ObjectStoreError, ObjectStore, ObjectStoreExt as _};
 use serde::{Deserialize, Deserializer, Serialize, Serializer, de::Error as DeError};
 use tracing::*;
 use url::{ParseError, Url};
@@ -38,6 +39,9 @@ use crate::logstore::LogStoreRef;
 use crate::protocol::DeltaOperation;
 use crate::table::state::DeltaTableState;
 
+/// Number of missing files looked up concurrently in the fallback location
+const FALLBACK_LOOKUP_CONCURRENCY: usize = 16;
+
 /// Audit the Delta Table's active files with the underlying file system.
 /// See this module's documentation for more information
 pub struct FileSystemCheckBuilder {
@@ -178,6 +182,30 @@ impl FileSystemCheckBuilder {
                 break;
             }
         }
+
+        // Listings only cover the table location, files missing from it may still be read from
+        // the fallback location.
+        if log_store.config().options().fallback.is_some() && !files_relative.is_empty() {
+            let available: Vec<String> = futures::stream::iter(files_relative.keys().cloned())
+                .map(|path| {
+                    let object_store = object_store.clone();
+                    async move {
+                        match object_store.head(&Path::parse(&path)?).await {
+                            Ok(_) => Ok(Some(path)),
+                            Err(ObjectStoreError::NotFound { .. }) => Ok(None),
+                            Err(err) => Err(DeltaTableError::from(err)),
+                        }
+                    }
+                })
+                .buffer_unordered(FALLBACK_LOOKUP_CONCURRENCY)
+                .try_filter_map(futures::future::ok)
+                .try_collect()
+                .await?;
+            for path in available {
+                files_relative.remove(&path);
+            }
+        }
+
         info!(
             files_scanned = file_count,
             missing_files = files_relative.len(),
@@ -69,10 +69,15 @@
 //!         └── day=5
 //!             └── part-00000-c5856301-3439-4032-a6fc-22b7bc92bebb.c000.snappy.parquet
 //! ```
+//!
+//! When the table property `delta.compatibility.symlinkFormatManifest.enabled` is set to `true`,
+//! the manifests are regenerated as part of the post commit hook of every commit. Manifests of
+//! partitions which no longer contain any files are removed.
 use bytes::{BufMut, BytesMut};
-use futures::StreamExt as _;
 use futures::future::BoxFuture;
+use futures::{StreamExt as _, TryStreamExt as _};
 use std::collections::HashMap;
+use std::str::FromStr;
 use std::sync::Arc;
 
 use object_store::ObjectStoreExt as _;
@@ -86,12 +91,37 @@ use crate::logstore::object_store::PutPayload;
 use crate::table::state::DeltaTableState;
 use crate::{DeltaResult, DeltaTable, DeltaTableError};
 
+/// Directory next to the `_delta_log` holding the generated manifests
+pub(crate) const SYMLINK_FORMAT_MANIFEST_DIR: &str = "_symlink_format_manifest";
+
+/// The kind of file to generate for the table
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum GenerateMode {
+    /// Hive-compatible `_symlink_format_manifest` files as read by Presto, Trino or Athena
+    #[default]
+    SymlinkFormatManifest,
+}
+
+impl FromStr for GenerateMode {
+    type Err = DeltaTableError;
+
+    fn from_str(s: &str) -> DeltaResult<Self> {
+        match s.to_ascii_lowercase().as_str() {
+            "symlink_format_manifest" => Ok(GenerateMode::SymlinkFormatManifest),
+            _ => Err(DeltaTableError::Generic(format!(
+                "Invalid generate mode provided {s}"
+            ))),
+        }
+    }
+}
+
 /// Simple builder to generate the manifest
 #[derive(Clone)]
 pub struct GenerateBuilder {
     /// A snapshot of the table state to be generated
     snapshot: Option<EagerSnapshot>,
     log_store: LogStoreRef,
+    mode: GenerateMode,
     custom_execute_handler: Option<Arc<dyn CustomExecuteHandler>>,
 }
 
@@ -103,9 +133,22 @@ impl GenerateBuilder {
         Self {
             snapshot,
             log_store,
+            mode: GenerateMode::default(),
             custom_execute_handler: None,
         }
     }
+
+    /// Specify the kind of file to generate
+    pub fn with_mode(mut self, mode: GenerateMode) -> Self {
+        self.mode = mode;
+        self
+    }
+
+    /// Set a custom execute handler, for pre and post execution
+    pub fn with_custom_execute_handler(mut self, handler: Arc<dyn CustomExecuteHandler>) -> Self {
+        self.custom_execute_handler = Some(handler);
+        self
+    }
 }
 
 impl super::Operation for GenerateBuilder {
@@ -126,67 +169,100 @@ impl std::future::IntoFuture for GenerateBuilder {
         Box::pin(async move {
             let snapshot =
                 resolve_snapshot(this.log_store(), this.snapshot.clone(), true, None).await?;
-            let mut payloads = HashMap::new();
-            let manifest_part = PathPart::parse("manifest").expect("This is not possible");
-
-            let mut file_stream = snapshot.file_views(&this.log_store, None);
-            while let Some(add) = file_stream.next().await {
-                let add = add?;
-                let path = add.object_store_path();
-                // The output_path is more or less the tree structure as the original file, just
-                // inside the _symlink_format_manifest directory. This makes it easier to avoid
-                // messing with partition values on the action
-                let output_path = Path::from_iter(
-                    std::iter::once(PathPart::parse("_symlink_format_manifest").map_err(|e| {
-                        DeltaTableError::GenericError {
-                            source: Box::new(e),
-                        }
-                    })?)
-                    .chain(path.parts().filter(|p| path.filename() != Some(p.as_ref())))
-                    .chain(std::iter::once(manifest_part.clone())),
-                );
-                trace!("Computed output path for add action: {output_path:?}");
-                if !payloads.contains_key(&output_path) {
-                    payloads.insert(output_path.clone(), BytesMut::new());
-                }
-
-                if let Some(payload) = payloads.get_mut(&output_path) {
-                    let uri = this.log_store().to_uri(&path);
-                    trace!("Prepare {uri} for the symlink_format_manifest");
-                    payload.put(uri.as_bytes());
-                    payload.put_u8(b'\n');
+            match this.mode {
+                GenerateMode::SymlinkFormatManifest => {
+                    write_symlink_format_manifest(this.log_store(), &snapshot).await?
                 }
             }
-            debug!("Total of {} manifest files prepared", payloads.len());
-            for (path, payload) in payloads.drain() {
-                debug!(
-                    "Generated manifest for {:?} is {} bytes",
-                    path,
-                    payload.len()
-                );
-                let payload = PutPayload::from(payload.freeze());
-                this.log_store()
-                    .object_store(None)
-                    .put(&path, payload)
-                    .await?;
-            }
             Ok(DeltaTable::new_with_state(
                 this.log_store().clone(),
-                DeltaTableState::new(snapshot.clone()),
+                DeltaTableState::new(snapshot),
             ))
         })
     }
 }
 
+/// Write the `_symlink_format_manifest` files for the given snapshot.
+///
+/// Manifests of partitions which no longer contain any files are removed, so that readers
+/// relying on the manifests do not pick up files which have been removed from the table.
+pub(crate) async fn write_symlink_format_manifest(
+    log_store: &LogStoreRef,
+    snapshot: &EagerSnapshot,
+) -> DeltaResult<()> {
+    let mut payloads = HashMap::new();
+    let manifest_part = PathPart::parse("manifest").expect("This is not possible");
+
+    let mut file_stream = snapshot.file_views(log_store, None);
+    while let Some(add) = file_stream.next().await {
+        let add = add?;
+        let path = add.object_store_path();
+        // The output_path is more or less the tree structure as the original file, just
+        // inside the _symlink_format_manifest directory. This makes it easier to avoid
+        // messing with partition values on the action
+        let output_path = Path::from_iter(
+            std::iter::once(PathPart::parse(SYMLINK_FORMAT_MANIFEST_DIR).map_err(|e| {
+                DeltaTableError::GenericError {
+                    source: Box::new(e),
+                }
+            })?)
+            .chain(path.parts().filter(|p| path.filename() != Some(p.as_ref())))
+            .chain(std::iter::once(manifest_part.clone())),
+        );
+        trace!("Computed output path for add action: {output_path:?}");
+        if !payloads.contains_key(&output_path) {
+            payloads.insert(output_path.clone(), BytesMut::new());
+        }
+
+        if let Some(payload) = payloads.get_mut(&output_path) {
+            let uri = log_store.to_uri(&path);
+            trace!("Prepare {uri} for the symlink_format_manifest");
+            payload.put(uri.as_bytes());
+            payload.put_u8(b'\n');
+        }
+    }
+    debug!("Total of {} manifest files prepared", payloads.len());
+    let object_store = log_store.object_store(None);
+    let manifest_dir = Path::from(SYMLINK_FORMAT_MANIFEST_DIR);
+    let stale = object_store
+        .list(Some(&manifest_dir))
+        .try_filter(|meta| std::future::ready(!payloads.contains_key(&meta.location)))
+        .map_ok(|meta| meta.location)
+        .try_collect::<Vec<_>>()
+        .await?;
+
+    for (path, payload) in payloads.drain() {
+        debug!(
+            "Generated manifest for {:?} is {} bytes",
+            path,
+            payload.len()
+        );
+        let payload = PutPayload::from(payload.freeze());
+        object_store.put(&path, payload).await?;
+    }
+
+    if !stale.is_empty() {
+        debug!("Removing {} stale manifest files", stale.len());
+        object_store
+            .delete_stream(futures::stream::iter(stale.into_iter().map(Ok)).boxed())
+            .try_collect::<Vec<_>>()
+            .await?;
+    }
+    Ok(())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
-    use futures::StreamExt;
+    use futures::{StreamExt, TryStreamExt};
 
     use crate::DeltaTable;
     use crate::kernel::schema::{DataType, PrimitiveType};
-    use crate::kernel::{Action, Add};
+    use crate::kernel::transaction::{CommitBuilder, TableReference};
+    use crate::kernel::{Action, Add, Remove};
+    use crate::protocol::{DeltaOperation, SaveMode};
+    use crate::table::config::TableProperty;
 
     #[tokio::test]
     async fn test_generate() -> DeltaResult<()> {
@@ -269,4 +345,76 @@ mod tests {
         );
         Ok(())
     }
+
+    #[test]
+    fn test_generate_mode_from_str() {
+        assert_eq!(
+            "symlink_format_manifest".parse::<GenerateMode>().unwrap(),
+            GenerateMode::SymlinkFormatManifest
+        );
+        assert!("unknown".parse::<GenerateMode>().is_err());
+    }
+
+    #[tokio::test]
+    async fn test_manifest_written_on_create_and_updated_after_commit() -> DeltaResult<()> {
+        let add = |partition: &str| Add {
+            path: format!("locale={partition}/some-files.parquet"),
+            partition_values: HashMap::from([("locale".to_string(), Some(partition.to_string()))]),
+            data_change: true,
+            ..Default::default()
+        };
+        let table = DeltaTable::new_in_memory()
+            .create()
+            .with_column("id", DataType::Primitive(PrimitiveType::Long), true, None)
+            .with_column(
+                "locale",
+                DataType::Primitive(PrimitiveType::String),
+                true,
+                None,
+            )
+            .with_partition_columns(vec!["locale"])
+            .with_configuration_property(TableProperty::SymlinkFormatManifestEnabled, Some("true"))
+            .with_actions(vec![Action::Add(add("us"))])
+            .await?;
+
+        let manifests = |table: &DeltaTable| {
+            table
+                .log_store()
+                .object_store(None)
+                .list(Some(&Path::from(SYMLINK_FORMAT_MANIFEST_DIR)))
+                .map_ok(|meta| meta.location)
+                .try_collect::<Vec<Path>>()
+        };
+        assert_eq!(
+            manifests(&table).await?,
+            vec![Path::from("_symlink_format_manifest/locale=us/manifest")]
+        );
+
+        let actions = vec![
+            Action::Remove(Remove {
+                path: add("us").path,
+                data_change: true,
+                ..Default::default()
+            }),
+            Action::Add(add("ca")),
+        ];
+        CommitBuilder::default()
+            .with_actions(actions)
+            .build(
+                table.state.as_ref().map(|s| s as &dyn TableReference),
+                table.log_store(),
+                DeltaOperation::Write {
+                    mode: SaveMode::Overwrite,
+                    partition_by: None,
+                    predicate: None,
+                },
+            )
+            .await?;
+
+        assert_eq!(
+            manifests(&table).await?,
+            vec![Path::from("_symlink_format_manifest/locale=ca/manifest")]
+        );
+        Ok(())
+    }
 }
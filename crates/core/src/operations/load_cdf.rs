@@ -10,6 +10,7 @@
 //! let provider = DeltaCdfTableProvider::try_new(builder)?;
 //! let df = ctx.read_table(provider).await?;
 
+use std::collections::HashMap;
 use std::sync::Arc;
 use std::time::SystemTime;
 
@@ -28,18 +29,19 @@ use datafusion::physical_plan::ExecutionPlan;
 use datafusion::physical_plan::projection::ProjectionExec;
 use datafusion::physical_plan::union::UnionExec;
 use delta_kernel::table_features::ColumnMappingMode;
+use futures::TryStreamExt as _;
 use tracing::log;
 
-use crate::DeltaTableError;
 use crate::delta_datafusion::{
     DataFusionMixins, DeltaSessionExt, extract_partition_only_predicate,
 };
 use crate::errors::{ColumnMappingOperation, DeltaResult};
 use crate::kernel::transaction::PROTOCOL;
-use crate::kernel::{
-    Action, Add, AddCDCFile, CommitInfo, EagerSnapshot, Version, resolve_snapshot,
-};
+use crate::kernel::{Action, Add, AddCDCFile, EagerSnapshot, Version, resolve_snapshot};
 use crate::logstore::{LogStoreRef, get_actions};
+#[cfg(feature = "parquet-encryption")]
+use crate::parquet_encryption::KeyManagementEncryptionFactory;
+use crate::{DeltaTableConfig, DeltaTableError};
 use crate::{delta_datafusion::cdf::*, kernel::Remove};
 
 /// Builder for create a read of change data feeds for delta tables
@@ -66,6 +68,8 @@ pub struct CdfLoadBuilder {
     /// conjuncts are ignored here and row-level correctness must be enforced by a
     /// separate `FilterExec` wrapped around the resulting plan.
     filter: Option<Expr>,
+    /// Only read data files added by commits, for tables without change data
+    appends_only: bool,
 }
 
 impl std::fmt::Debug for CdfLoadBuilder {
@@ -78,10 +82,19 @@ impl std::fmt::Debug for CdfLoadBuilder {
             .field("starting_timestamp", &self.starting_timestamp)
             .field("ending_timestamp", &self.ending_timestamp)
             .field("allow_out_of_range", &self.allow_out_of_range)
+            .field("appends_only", &self.appends_only)
             .finish()
     }
 }
 
+/// Timestamp of a commit, preferring its in-commit timestamp over the writer's wall clock time
+fn commit_timestamp(actions: &[Action]) -> Option<i64> {
+    actions.iter().find_map(|action| match action {
+        Action::CommitInfo(info) => info.in_commit_timestamp.or(info.timestamp),
+        _ => None,
+    })
+}
+
 impl CdfLoadBuilder {
     /// Create a new [`CdfLoadBuilder`]
     pub(crate) fn new(log_store: LogStoreRef, snapshot: Option<EagerSnapshot>) -> Self {
@@ -95,6 +108,7 @@ impl CdfLoadBuilder {
             allow_out_of_range: false,
             session: None,
             filter: None,
+            appends_only: false,
         }
     }
 
@@ -147,25 +161,123 @@ impl CdfLoadBuilder {
         self
     }
 
-    #[inline]
-    fn timestamp_in_range(action: &Action, ts: DateTime<Utc>) -> bool {
-        matches!(action, Action::CommitInfo(CommitInfo { in_commit_timestamp: Some(t), .. }) if ts.timestamp_millis() <= *t)
-            || matches!(action, Action::CommitInfo(CommitInfo { timestamp: Some(t), .. }) if ts.timestamp_millis() <= *t)
+    /// Only read the data files added by each commit as inserts.
+    ///
+    /// Change data files and removed files are ignored, so this can be used to read the
+    /// appends of tables that do not record change data.
+    pub(crate) fn with_appends_only(mut self) -> Self {
+        self.appends_only = true;
+        self
     }
 
-    async fn calculate_earliest_version(&self, snapshot: &EagerSnapshot) -> DeltaResult<Version> {
-        let ts = self.starting_timestamp.unwrap_or(DateTime::UNIX_EPOCH);
-        for v in 0..snapshot.version() {
-            if let Ok(Some(bytes)) = self.log_store.read_commit_entry(v).await
-                && let Ok(actions) = get_actions(v, &bytes)
-                && actions
-                    .iter()
-                    .any(|action| Self::timestamp_in_range(action, ts))
+    /// Commit timestamps of all versions up to `latest_version` still available in the log
+    async fn commit_timestamps(&self, latest_version: Version) -> Vec<(Version, i64)> {
+        let mut timestamps = Vec::new();
+        for version in 0..=latest_version {
+            if let Ok(Some(bytes)) = self.log_store.read_commit_entry(version).await
+                && let Ok(actions) = get_actions(version, &bytes)
+                && let Some(timestamp) = commit_timestamp(&actions)
             {
-                return Ok(v);
+                timestamps.push((version, timestamp));
+            }
+        }
+        timestamps
+    }
+
+    /// Resolve the starting and ending timestamps to the first and last version committed
+    /// between them. Returns `None` if the timestamps are out of range and out of range reads
+    /// are allowed.
+    async fn resolve_timestamp_range(
+        &self,
+        latest_version: Version,
+    ) -> DeltaResult<Option<(Version, Version)>> {
+        let timestamps = self.commit_timestamps(latest_version).await;
+        let (Some(&(_, earliest)), Some(&(_, latest))) = (timestamps.first(), timestamps.last())
+        else {
+            return Ok(Some((0, latest_version)));
+        };
+        let to_datetime = |millis| DateTime::from_timestamp_millis(millis).unwrap_or_default();
+        let out_of_range = |err| {
+            if self.allow_out_of_range {
+                Ok(None)
+            } else {
+                Err(err)
+            }
+        };
+
+        if let Some(timestamp) = self.starting_timestamp
+            && timestamp.timestamp_millis() > latest
+        {
+            return out_of_range(DeltaTableError::ChangeDataTimestampGreaterThanCommit {
+                timestamp,
+                earliest_timestamp: to_datetime(earliest),
+                latest_timestamp: to_datetime(latest),
+            });
+        }
+        if let Some(timestamp) = self.ending_timestamp
+            && timestamp.timestamp_millis() < earliest
+        {
+            return out_of_range(DeltaTableError::ChangeDataTimestampBeforeFirstCommit {
+                timestamp,
+                earliest_timestamp: to_datetime(earliest),
+                latest_timestamp: to_datetime(latest),
+            });
+        }
+
+        let start = self
+            .starting_timestamp
+            .and_then(|timestamp| {
+                timestamps
+                    .iter()
+                    .find(|(_, t)| *t >= timestamp.timestamp_millis())
+            })
+            .map_or(0, |(version, _)| *version);
+        let end = self
+            .ending_timestamp
+            .and_then(|timestamp| {
+                timestamps
+                    .iter()
+                    .rev()
+                    .find(|(_, t)| *t <= timestamp.timestamp_millis())
+            })
+            .map_or(latest_version, |(version, _)| *version);
+        Ok(Some((start, end)))
+    }
+
+    /// Fill in the partition values and size of removes written without extended file metadata.
+    ///
+    /// Deletes are synthesized by reading the removed files, which requires both. They are taken
+    /// from the add actions of the removed files in the table version preceding the removal.
+    async fn complete_removes(&self, version: Version, removes: &mut [Remove]) -> DeltaResult<()> {
+        let mut incomplete: HashMap<String, &mut Remove> = removes
+            .iter_mut()
+            .filter(|remove| remove.partition_values.is_none() || remove.size.is_none())
+            .map(|remove| (remove.path.clone(), remove))
+            .collect();
+        let Some(previous_version) = version.checked_sub(1).filter(|_| !incomplete.is_empty())
+        else {
+            return Ok(());
+        };
+
+        let config = DeltaTableConfig {
+            require_files: false,
+            skip_stats: true,
+            ..Default::default()
+        };
+        let snapshot =
+            EagerSnapshot::try_new(self.log_store.as_ref(), config, Some(previous_version)).await?;
+        let mut files = snapshot.file_views(self.log_store.as_ref(), None);
+        while let Some(file) = files.try_next().await? {
+            let add = file.to_add();
+            if let Some(remove) = incomplete.remove(&add.path) {
+                remove.partition_values.get_or_insert(add.partition_values);
+                remove.size.get_or_insert(add.size);
+                if incomplete.is_empty() {
+                    break;
+                }
             }
         }
-        Ok(0)
+        Ok(())
     }
 
     /// This is a rust version of https://github.com/delta-io/delta/blob/master/spark/src/main/scala/org/apache/spark/sql/delta/commands/cdc/CDCReader.scala#L418
@@ -174,7 +286,6 @@ impl CdfLoadBuilder {
     /// than I have right now. I plan to extend the checks once we have a stable state of the initial implementation.
     async fn determine_files_to_read(
         &self,
-        snapshot: &EagerSnapshot,
         partition_pruning: Option<&PartitionPruningPredicate>,
     ) -> DeltaResult<(
         Vec<CdcDataSpec<AddCDCFile>>,
@@ -184,11 +295,7 @@ impl CdfLoadBuilder {
         if self.starting_version.is_none() && self.starting_timestamp.is_none() {
             return Err(DeltaTableError::NoStartingVersionOrTimestamp);
         }
-        let start = if let Some(s) = self.starting_version {
-            s
-        } else {
-            self.calculate_earliest_version(snapshot).await?
-        };
+        let mut start = self.starting_version.unwrap_or(0);
 
         let mut change_files: Vec<CdcDataSpec<AddCDCFile>> = vec![];
         let mut add_files: Vec<CdcDataSpec<Add>> = vec![];
@@ -205,6 +312,22 @@ impl CdfLoadBuilder {
 
         let mut end = self.ending_version.unwrap_or(latest_version);
 
+        if self.starting_timestamp.is_some() || self.ending_timestamp.is_some() {
+            let Some((first, last)) = self.resolve_timestamp_range(latest_version).await? else {
+                return Ok((change_files, add_files, remove_files));
+            };
+            if self.starting_version.is_none() {
+                start = first;
+            }
+            if self.ending_version.is_none() {
+                // no commit between the timestamps
+                if last < start {
+                    return Ok((change_files, add_files, remove_files));
+                }
+                end = last;
+            }
+        }
+
         if end > latest_version {
             end = latest_version;
         }
@@ -229,32 +352,6 @@ impl CdfLoadBuilder {
             .ending_timestamp
             .unwrap_or(DateTime::from(SystemTime::now()));
 
-        // Check that starting_timestamp is within boundaries of the latest version
-        let latest_snapshot_bytes = self
-            .log_store
-            .read_commit_entry(latest_version)
-            .await?
-            .ok_or(DeltaTableError::InvalidVersion(latest_version))?;
-
-        let latest_version_actions: Vec<Action> =
-            get_actions(latest_version, &latest_snapshot_bytes)?;
-        let latest_version_commit = latest_version_actions
-            .iter()
-            .find(|a| matches!(a, Action::CommitInfo(_)));
-
-        if let Some(Action::CommitInfo(CommitInfo {
-            timestamp: Some(latest_timestamp),
-            ..
-        })) = latest_version_commit
-            && starting_timestamp.timestamp_millis() > *latest_timestamp
-        {
-            return if self.allow_out_of_range {
-                Ok((change_files, add_files, remove_files))
-            } else {
-                Err(DeltaTableError::ChangeDataTimestampGreaterThanCommit { ending_timestamp })
-            };
-        }
-
         log::debug!(
             "starting timestamp = {starting_timestamp:?}, ending timestamp = {ending_timestamp:?}"
         );
@@ -275,36 +372,19 @@ impl CdfLoadBuilder {
             if self.starting_timestamp.is_some() || self.ending_timestamp.is_some() {
                 // TODO: fallback on other actions for timestamps because CommitInfo action is optional
                 // theoretically.
-                let version_commit = version_actions
-                    .iter()
-                    .find(|a| matches!(a, Action::CommitInfo(_)));
-
-                match version_commit {
-                    Some(Action::CommitInfo(CommitInfo {
-                        in_commit_timestamp: Some(t),
-                        ..
-                    })) if starting_timestamp.timestamp_millis() > *t
-                        || *t > ending_timestamp.timestamp_millis() =>
-                    {
-                        log::debug!("Version: {version} skipped, due to in commit timestamp");
-                        continue;
-                    }
-                    Some(Action::CommitInfo(CommitInfo {
-                        timestamp: Some(t), ..
-                    })) if starting_timestamp.timestamp_millis() > *t
-                        || *t > ending_timestamp.timestamp_millis() =>
-                    {
-                        log::debug!("Version: {version} skipped, due to commit timestamp");
-                        continue;
-                    }
-                    _ => {}
+                if let Some(t) = commit_timestamp(&version_actions)
+                    && (starting_timestamp.timestamp_millis() > t
+                        || t > ending_timestamp.timestamp_millis())
+                {
+                    log::debug!("Version: {version} skipped, due to commit timestamp");
+                    continue;
                 }
             }
 
             for action in &version_actions {
                 match action {
-                    Action::Cdc(f) => cdc_actions.push(f.clone()),
-                    Action::Metadata(md) => {
+                    Action::Cdc(f) if !self.appends_only => cdc_actions.push(f.clone()),
+                    Action::Metadata(md) if !self.appends_only => {
                         log::info!("Metadata: {md:?}");
                         if let Some(key) = &md.configuration().get("delta.enableChangeDataFeed") {
                             let key = key.to_lowercase();
@@ -343,10 +423,10 @@ impl CdfLoadBuilder {
                     })
                     .collect::<Vec<Add>>();
 
-                let remove_actions = version_actions
+                let mut remove_actions = version_actions
                     .iter()
                     .filter_map(|r| match r {
-                        Action::Remove(r) if r.data_change => Some(r.clone()),
+                        Action::Remove(r) if r.data_change && !self.appends_only => Some(r.clone()),
                         _ => None,
                     })
                     .collect::<Vec<Remove>>();
@@ -360,6 +440,7 @@ impl CdfLoadBuilder {
                 }
 
                 if !remove_actions.is_empty() {
+                    self.complete_removes(version, &mut remove_actions).await?;
                     log::debug!(
                         "Located {} cdf actions for version: {version}",
                         remove_actions.len(),
@@ -483,7 +564,7 @@ impl CdfLoadBuilder {
         let partition_pruning =
             self.partition_pruning_predicate(session, &schema, partition_values)?;
         let (cdc, add, remove) = self
-            .determine_files_to_read(&snapshot, partition_pruning.as_ref())
+            .determine_files_to_read(partition_pruning.as_ref())
             .await?;
         session.ensure_log_store_registered(self.log_store.as_ref())?;
 
@@ -563,6 +644,16 @@ impl CdfLoadBuilder {
             remove_source = remove_source.with_predicate(Arc::clone(filters));
         }
 
+        #[cfg(feature = "parquet-encryption")]
+        if let Some(encryption) = &snapshot.load_config().parquet_encryption {
+            let factory = Arc::new(KeyManagementEncryptionFactory::new(
+                encryption.client().clone(),
+            ));
+            cdc_source = cdc_source.with_encryption_factory(factory.clone());
+            add_source = add_source.with_encryption_factory(factory.clone());
+            remove_source = remove_source.with_encryption_factory(factory);
+        }
+
         let cdc_scan: Arc<dyn ExecutionPlan> = DataSourceExec::from_data_source(
             FileScanConfigBuilder::new(self.log_store.object_store_url(), Arc::new(cdc_source))
                 .with_file_groups(cdc_file_groups.into_values().map(FileGroup::from).collect())
@@ -629,9 +720,11 @@ pub(crate) mod tests {
     use datafusion::physical_plan::{collect, displayable};
     use datafusion::prelude::SessionContext;
     use delta_kernel::engine::arrow_conversion::TryIntoArrow as _;
+    use futures::TryStreamExt;
     use itertools::Itertools;
 
     use crate::delta_datafusion::cdf::scan::DeltaCdfTableProvider;
+    use crate::kernel::transaction::CommitBuilder;
     use crate::test_utils::TestSchemas;
     use crate::writer::test_utils::TestResult;
     use crate::{DeltaTable, TableProperty};
@@ -1361,6 +1454,65 @@ pub(crate) mod tests {
         Ok(())
     }
 
+    #[tokio::test]
+    async fn test_load_ending_timestamp_before_first_commit() -> TestResult {
+        let ctx = SessionContext::new();
+        let ending_timestamp = NaiveDateTime::from_str("2013-12-22T17:10:21.675")?;
+        let table_path = Path::new("../test/tests/data/cdf-table-non-partitioned");
+        let table_uri = Url::from_directory_path(std::fs::canonicalize(table_path)?).unwrap();
+        let builder = DeltaTable::try_from_url(table_uri)
+            .await?
+            .scan_cdf()
+            .with_starting_version(0)
+            .with_ending_timestamp(ending_timestamp.and_utc());
+
+        let err = builder.clone().build(&ctx.state(), None).await.unwrap_err();
+        assert!(matches!(
+            err,
+            DeltaTableError::ChangeDataTimestampBeforeFirstCommit { .. }
+        ));
+        assert!(err.to_string().contains("valid range is"));
+
+        let table = builder
+            .with_allow_out_of_range()
+            .build(&ctx.state(), None)
+            .await?;
+        let batches = collect(table, ctx.task_ctx()).await?;
+        assert!(batches.is_empty());
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_load_timestamp_range_respects_ict() -> TestResult {
+        let ctx = SessionContext::new();
+        let table_path = Path::new("../test/tests/data/cdc_ict_table");
+        let starting_timestamp = NaiveDateTime::from_str("2026-07-12T16:36:52.000")?;
+        let ending_timestamp = NaiveDateTime::from_str("2026-07-12T16:36:53.000")?;
+        let table_uri = Url::from_directory_path(std::fs::canonicalize(table_path)?).unwrap();
+        let table = DeltaTable::try_from_url(table_uri)
+            .await?
+            .scan_cdf()
+            .with_starting_timestamp(starting_timestamp.and_utc())
+            .with_ending_timestamp(ending_timestamp.and_utc())
+            .build(&ctx.state(), None)
+            .await?;
+
+        let batches = collect(table, ctx.task_ctx()).await?;
+        assert_batches_sorted_eq! {
+            [
+                "+------+-----+-----------+--------------+-----------------+-------------------------+",
+                "| name | age | birthyear | _change_type | _commit_version | _commit_timestamp       |",
+                "+------+-----+-----------+--------------+-----------------+-------------------------+",
+                "| Dan  | 14  | 1995      | delete       | 2               | 2026-07-12T16:36:52.175 |",
+                "| Dave | 22  | 1995      | delete       | 2               | 2026-07-12T16:36:52.175 |",
+                "+------+-----+-----------+--------------+-----------------+-------------------------+",
+            ],
+            &batches
+        }
+        Ok(())
+    }
+
     #[tokio::test]
     async fn test_load_timestamp_out_of_range_with_flag() -> TestResult {
         let ctx = SessionContext::new();
@@ -1535,4 +1687,70 @@ pub(crate) mod tests {
         assert!(cdc_actions.is_empty());
         Ok(())
     }
+
+    #[tokio::test]
+    async fn test_synthesize_deletes_from_removes_without_extended_metadata() -> TestResult {
+        let delta_schema = TestSchemas::simple();
+        let table: DeltaTable = DeltaTable::new_in_memory()
+            .create()
+            .with_columns(delta_schema.fields().cloned())
+            .with_partition_columns(["id"])
+            .with_configuration_property(TableProperty::EnableChangeDataFeed, Some("true"))
+            .await?;
+        let schema: Arc<Schema> = Arc::new(delta_schema.try_into_arrow()?);
+        let batch = RecordBatch::try_new(
+            Arc::clone(&schema),
+            vec![
+                Arc::new(StringArray::from(vec![Some("1"), Some("2")])),
+                Arc::new(Int32Array::from(vec![Some(1), Some(2)])),
+                Arc::new(StringArray::from(vec![Some("yes"), Some("no")])),
+            ],
+        )?;
+        let table = table.write(vec![batch]).await?;
+
+        // remove all files like a writer which does not record extended file metadata
+        let removes: Vec<Action> = table
+            .snapshot()?
+            .file_views(table.log_store().as_ref(), None)
+            .map_ok(|file| {
+                Action::Remove(Remove {
+                    path: file.to_add().path,
+                    deletion_timestamp: Some(0),
+                    data_change: true,
+                    ..Default::default()
+                })
+            })
+            .try_collect()
+            .await?;
+        assert_eq!(removes.len(), 2);
+        let commit = CommitBuilder::default()
+            .with_actions(removes)
+            .build(
+                Some(table.snapshot()?),
+                table.log_store(),
+                crate::protocol::DeltaOperation::custom("DELETE"),
+            )
+            .await?;
+        let table = DeltaTable::new_with_state(table.log_store(), commit.snapshot);
+        assert_eq!(table.version(), Some(2));
+
+        let ctx = SessionContext::new();
+        let cdf_scan = table
+            .scan_cdf()
+            .with_starting_version(2)
+            .build(&ctx.state(), None)
+            .await?;
+        let mut batches = collect(cdf_scan, ctx.task_ctx()).await?;
+        let _: Vec<_> = batches.iter_mut().map(|b| b.remove_column(5)).collect();
+
+        assert_batches_sorted_eq! {[
+            "+-------+----------+----+--------------+-----------------+",
+            "| value | modified | id | _change_type | _commit_version |",
+            "+-------+----------+----+--------------+-----------------+",
+            "| 1     | yes      | 1  | delete       | 2               |",
+            "| 2     | no       | 2  | delete       | 2               |",
+            "+-------+----------+----+--------------+-----------------+",
+        ], &batches }
+        Ok(())
+    }
 }
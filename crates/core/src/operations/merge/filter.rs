@@ -2,13 +2,18 @@
 use std::collections::HashMap;
 use std::sync::Arc;
 
+use arrow::array::{Array, AsArray as _};
 use arrow::compute::concat_batches;
+use arrow::datatypes::{DataType, UInt64Type};
 use datafusion::catalog::Session;
 use datafusion::common::tree_node::{Transformed, TreeNode};
 use datafusion::common::{ScalarValue, TableReference};
-use datafusion::functions_aggregate::expr_fn::{max, min};
+use datafusion::functions_aggregate::expr_fn::{approx_distinct, array_agg, max, min};
 use datafusion::logical_expr::expr::{InList, Placeholder};
-use datafusion::logical_expr::{Aggregate, Between, BinaryExpr, Expr, LogicalPlan, Operator, lit};
+use datafusion::logical_expr::{
+    Aggregate, Between, BinaryExpr, Expr, ExprFunctionExt as _, ExprSchemable as _, LogicalPlan,
+    Operator, lit,
+};
 use datafusion::physical_plan::ExecutionPlan;
 use either::{Left, Right};
 use futures::TryStreamExt as _;
@@ -17,6 +22,10 @@ use itertools::Itertools;
 use crate::kernel::EagerSnapshot;
 use crate::{DeltaResult, DeltaTableError};
 
+/// Maximum number of distinct source keys collected to replace the range of an equality join
+/// key when skipping target files.
+const MAX_KEY_SET_SIZE: u64 = 1000;
+
 #[derive(Debug)]
 enum ReferenceTableCheck {
     HasReference(String),
@@ -89,6 +98,7 @@ fn construct_placeholder(
             expr: source_expr,
             alias: placeholder_name,
             is_aggregate: false,
+            key: None,
         });
 
         Some(replaced)
@@ -121,11 +131,13 @@ fn construct_placeholder(
                     expr: min(source_expr.clone()),
                     alias: name_min,
                     is_aggregate: true,
+                    key: Some(source_expr.clone()),
                 });
                 placeholders.push(PredicatePlaceholder {
                     expr: max(source_expr),
                     alias: name_max,
                     is_aggregate: true,
+                    key: None,
                 });
                 Some(replaced)
             }
@@ -151,6 +163,19 @@ pub(crate) struct PredicatePlaceholder {
     pub expr: Expr,
     pub alias: String,
     pub is_aggregate: bool,
+    /// The source side of an equality with a target column, set on the placeholder for the
+    /// lower bound of the range the target column is generalized to.
+    pub key: Option<Expr>,
+}
+
+/// Filters on the target table derived from the merge predicate and the source data.
+#[derive(Debug)]
+pub(crate) struct EarlyFilter {
+    /// Filter matching all target rows the merge may touch, used for conflict detection.
+    pub predicate: Expr,
+    /// A tighter version of `predicate` used to skip target files, where the ranges of equality
+    /// join keys are replaced with the distinct source keys if there are few enough of them.
+    pub file_skipping_predicate: Expr,
 }
 
 /// Takes the predicate provided and does three things:
@@ -309,6 +334,7 @@ pub(crate) fn generalize_filter(
                         expr: other,
                         alias: placeholder_name,
                         is_aggregate: true,
+                        key: None,
                     });
                     Some(placeholder)
                 } else {
@@ -329,7 +355,7 @@ pub(crate) async fn try_construct_early_filter(
     source_name: &TableReference,
     target_name: &TableReference,
     streaming_source: bool,
-) -> DeltaResult<Option<Expr>> {
+) -> DeltaResult<Option<EarlyFilter>> {
     let table_metadata = table_snapshot.metadata();
     let partition_columns = table_metadata.partition_columns();
 
@@ -347,18 +373,39 @@ pub(crate) async fn try_construct_early_filter(
         Some(filter) => {
             if placeholders.is_empty() || streaming_source {
                 // if we haven't recognised any source predicates in the join predicate, return our filter with static only predicates
-                Ok(Some(filter))
+                Ok(Some(EarlyFilter {
+                    predicate: filter.clone(),
+                    file_skipping_predicate: filter,
+                }))
             } else {
+                // Equality join keys we can estimate the number of distinct source values of,
+                // named like their range placeholders without the suffix.
+                let keys = placeholders
+                    .iter()
+                    .filter_map(|p| {
+                        let name = p.alias.strip_suffix("_min")?;
+                        let key = p.key.as_ref()?;
+                        let data_type = key.get_type(source.schema().as_ref()).ok()?;
+                        supports_key_set(&data_type).then(|| (name.to_owned(), key.clone()))
+                    })
+                    .collect_vec();
+
                 // if we have some filters, which depend on the source df, then collect the placeholders values from the source data
                 // We aggregate the distinct values for partitions with the group_columns and stats(min, max) for dynamic filter as agg_columns
                 // Can be translated into `SELECT partition1 as part1_0, min(id) as id_1_min, max(id) as id_1_max FROM source GROUP BY partition1`
-                let (agg_columns, group_columns) = placeholders.into_iter().partition_map(|p| {
-                    if p.is_aggregate {
-                        Left(p.expr.alias(p.alias))
-                    } else {
-                        Right(p.expr.alias(p.alias))
-                    }
-                });
+                let (mut agg_columns, group_columns): (Vec<_>, Vec<_>) =
+                    placeholders.into_iter().partition_map(|p| {
+                        if p.is_aggregate {
+                            Left(p.expr.alias(p.alias))
+                        } else {
+                            Right(p.expr.alias(p.alias))
+                        }
+                    });
+                agg_columns.extend(
+                    keys.iter().map(|(name, key)| {
+                        approx_distinct(key.clone()).alias(format!("{name}_ndv"))
+                    }),
+                );
                 let distinct_partitions = LogicalPlan::Aggregate(Aggregate::try_new(
                     source.clone().into(),
                     group_columns,
@@ -368,13 +415,15 @@ pub(crate) async fn try_construct_early_filter(
                     .create_physical_plan(&distinct_partitions)
                     .await?;
                 let items = execute_plan_to_batch(session_state, execution_plan).await?;
+                let key_sets = collect_key_sets(session_state, source, &keys, &items).await?;
                 let placeholder_names = items
                     .schema()
                     .fields()
                     .iter()
                     .map(|f| f.name().to_owned())
                     .collect_vec();
-                let expr = (0..items.num_rows())
+                let (predicates, file_skipping_predicates): (Vec<_>, Vec<_>) = (0..items
+                    .num_rows())
                     .map(|i| {
                         let replacements = placeholder_names
                             .iter()
@@ -384,17 +433,149 @@ pub(crate) async fn try_construct_early_filter(
                                 Ok((placeholder.clone(), value))
                             })
                             .try_collect::<_, _, DeltaTableError>()?;
-                        Ok(replace_placeholders(filter.clone(), &replacements))
+                        let file_skipping_predicate =
+                            replace_ranges_with_key_sets(filter.clone(), &replacements, &key_sets);
+                        Ok((
+                            replace_placeholders(filter.clone(), &replacements),
+                            replace_placeholders(file_skipping_predicate, &replacements),
+                        ))
                     })
                     .collect::<DeltaResult<Vec<_>>>()?
                     .into_iter()
-                    .reduce(Expr::or);
-                Ok(expr)
+                    .unzip();
+                Ok(predicates
+                    .into_iter()
+                    .reduce(Expr::or)
+                    .zip(file_skipping_predicates.into_iter().reduce(Expr::or))
+                    .map(|(predicate, file_skipping_predicate)| EarlyFilter {
+                        predicate,
+                        file_skipping_predicate,
+                    }))
             }
         }
     }
 }
 
+/// Whether the distinct values of a join key of `data_type` can be collected into a key set.
+fn supports_key_set(data_type: &DataType) -> bool {
+    data_type.is_integer()
+        || matches!(
+            data_type,
+            DataType::Utf8
+                | DataType::LargeUtf8
+                | DataType::Utf8View
+                | DataType::Date32
+                | DataType::Date64
+        )
+}
+
+/// Collect the distinct source values of the equality join `keys` whose estimated number of
+/// distinct values, summed over all rows of `ranges`, is at most [`MAX_KEY_SET_SIZE`].
+async fn collect_key_sets(
+    session_state: &dyn Session,
+    source: &LogicalPlan,
+    keys: &[(String, Expr)],
+    ranges: &arrow::record_batch::RecordBatch,
+) -> DeltaResult<HashMap<String, Vec<ScalarValue>>> {
+    let keys = keys
+        .iter()
+        .filter(|(name, _)| {
+            ranges
+                .column_by_name(&format!("{name}_ndv"))
+                .and_then(|ndv| ndv.as_primitive_opt::<UInt64Type>())
+                .is_some_and(|ndv| ndv.iter().flatten().sum::<u64>() <= MAX_KEY_SET_SIZE)
+        })
+        .collect_vec();
+    if keys.is_empty() {
+        return Ok(HashMap::new());
+    }
+
+    let aggregates = keys
+        .iter()
+        .map(|(name, key)| Ok(array_agg(key.clone()).distinct().build()?.alias(name)))
+        .collect::<DeltaResult<Vec<_>>>()?;
+    let distinct_keys = LogicalPlan::Aggregate(Aggregate::try_new(
+        source.clone().into(),
+        vec![],
+        aggregates,
+    )?);
+    let execution_plan = session_state.create_physical_plan(&distinct_keys).await?;
+    let items = execute_plan_to_batch(session_state, execution_plan).await?;
+
+    let mut key_sets = HashMap::new();
+    for (name, _) in keys {
+        let Some(values) = items
+            .column_by_name(name)
+            .and_then(|values| values.as_list_opt::<i32>())
+            .filter(|values| values.len() == 1 && values.is_valid(0))
+            .map(|values| values.value(0))
+        else {
+            continue;
+        };
+        // The estimate may be off, the set of keys is only used when it is small after all.
+        if values.len() as u64 > MAX_KEY_SET_SIZE {
+            continue;
+        }
+        let values = (0..values.len())
+            .filter(|i| values.is_valid(*i))
+            .map(|i| ScalarValue::try_from_array(&values, i))
+            .try_collect()?;
+        key_sets.insert(name.clone(), values);
+    }
+    Ok(key_sets)
+}
+
+/// Replace the ranges `target BETWEEN $<key>_min AND $<key>_max` of equality join keys with
+/// the distinct source values of the key within the range, if they were collected.
+fn replace_ranges_with_key_sets(
+    expr: Expr,
+    replacements: &HashMap<String, ScalarValue>,
+    key_sets: &HashMap<String, Vec<ScalarValue>>,
+) -> Expr {
+    expr.transform(&|expr| match expr {
+        Expr::Between(Between {
+            expr,
+            negated: false,
+            low,
+            high,
+        }) => {
+            let values = match (low.as_ref(), high.as_ref()) {
+                (Expr::Placeholder(low), Expr::Placeholder(high)) => low
+                    .id
+                    .strip_suffix("_min")
+                    .and_then(|name| key_sets.get(name))
+                    .zip(replacements.get(&low.id))
+                    .zip(replacements.get(&high.id))
+                    .map(|((keys, low), high)| {
+                        keys.iter()
+                            .filter(|key| *key >= low && *key <= high)
+                            .map(|key| lit(key.clone()))
+                            .collect_vec()
+                    })
+                    .unwrap_or_default(),
+                _ => vec![],
+            };
+            if values.is_empty() {
+                Ok(Transformed::no(Expr::Between(Between {
+                    expr,
+                    negated: false,
+                    low,
+                    high,
+                })))
+            } else {
+                Ok(Transformed::yes(Expr::InList(InList {
+                    expr,
+                    list: values,
+                    negated: false,
+                })))
+            }
+        }
+        _ => Ok(Transformed::no(expr)),
+    })
+    .unwrap()
+    .data
+}
+
 async fn execute_plan_to_batch(
     state: &dyn Session,
     plan: Arc<dyn ExecutionPlan>,
@@ -486,7 +667,8 @@ mod tests {
             false,
         )
         .await
-        .unwrap();
+        .unwrap()
+        .map(|filter| filter.predicate);
 
         assert!(pred.is_some());
 
@@ -577,7 +759,8 @@ mod tests {
             false,
         )
         .await
-        .unwrap();
+        .unwrap()
+        .map(|filter| filter.predicate);
 
         assert!(pred.is_some());
 
@@ -585,6 +768,69 @@ mod tests {
         assert_eq!(pred.unwrap(), filter);
     }
 
+    #[tokio::test]
+    async fn test_try_construct_early_filter_with_key_set() {
+        let schema = get_arrow_schema(&None);
+        let table = setup_table(Some(vec!["modified"])).await;
+
+        let ctx = SessionContext::new();
+        let batch = RecordBatch::try_new(
+            Arc::clone(&schema),
+            vec![
+                Arc::new(arrow::array::StringArray::from(vec!["B", "X", "X"])),
+                Arc::new(arrow::array::Int32Array::from(vec![10, 20, 30])),
+                Arc::new(arrow::array::StringArray::from(vec![
+                    "2023-07-04",
+                    "2023-07-04",
+                    "2023-07-04",
+                ])),
+            ],
+        )
+        .unwrap();
+        let source = ctx.read_batch(batch).unwrap();
+
+        let source_name = TableReference::parse_str("source");
+        let target_name = TableReference::parse_str("target");
+
+        let source = LogicalPlanBuilder::scan(
+            source_name.clone(),
+            provider_as_source(source.into_view()),
+            None,
+        )
+        .unwrap()
+        .build()
+        .unwrap();
+
+        let join_predicate = make_join_predicate(&source_name, &target_name);
+
+        let filter = try_construct_early_filter(
+            join_predicate,
+            table.snapshot().unwrap().snapshot(),
+            &ctx.state(),
+            &source,
+            &source_name,
+            &target_name,
+            false,
+        )
+        .await
+        .unwrap()
+        .unwrap();
+
+        let target_id = col(Column::new(Some(target_name.clone()), "id"));
+        assert_eq!(
+            filter.predicate,
+            target_id.clone().between(lit("B"), lit("X"))
+        );
+        let Expr::InList(in_list) = filter.file_skipping_predicate else {
+            panic!("expected the range to be replaced with the source keys");
+        };
+        assert_eq!(*in_list.expr, target_id);
+        assert!(!in_list.negated);
+        let mut keys = in_list.list;
+        keys.sort_by_key(|key| key.to_string());
+        assert_eq!(keys, vec![lit("B"), lit("X")]);
+    }
+
     #[tokio::test]
     async fn test_try_construct_early_filter_with_partition_and_range() {
         let schema = get_arrow_schema(&None);
@@ -635,7 +881,8 @@ mod tests {
             false,
         )
         .await
-        .unwrap();
+        .unwrap()
+        .map(|filter| filter.predicate);
 
         assert!(pred.is_some());
 
@@ -698,7 +945,8 @@ mod tests {
             false,
         )
         .await
-        .unwrap();
+        .unwrap()
+        .map(|filter| filter.predicate);
 
         assert!(pred.is_some());
 
@@ -767,7 +1015,8 @@ mod tests {
             false,
         )
         .await
-        .unwrap();
+        .unwrap()
+        .map(|filter| filter.predicate);
 
         assert!(pred.is_some());
 
@@ -839,7 +1088,8 @@ mod tests {
             false,
         )
         .await
-        .unwrap();
+        .unwrap()
+        .map(|filter| filter.predicate);
 
         assert!(pred.is_some());
 
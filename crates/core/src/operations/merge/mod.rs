@@ -29,6 +29,7 @@
 //! ````
 use std::collections::HashMap;
 use std::fmt::Debug;
+use std::num::{NonZeroU64, NonZeroUsize};
 use std::ops::Deref;
 use std::sync::Arc;
 use std::time::Instant;
@@ -65,6 +66,7 @@ use filter::try_construct_early_filter;
 use futures::{TryStreamExt as _, future::BoxFuture};
 use parquet::file::properties::WriterProperties;
 use serde::Serialize;
+use tokio_util::sync::CancellationToken;
 use tracing::*;
 use uuid::Uuid;
 
@@ -72,16 +74,18 @@ use self::barrier::{MergeBarrier, MergeBarrierExec};
 use self::validation::{
     MergeValidation, MergeValidationExec, build_duplicate_match_validation_plan,
 };
-use super::{CustomExecuteHandler, Operation};
+use super::{CustomExecuteHandler, Operation, ensure_not_cancelled, run_until_cancelled};
 use crate::delta_datafusion::expr::fmt_expr_to_sql;
 use crate::delta_datafusion::logical::MetricObserver;
-use crate::delta_datafusion::physical::{MetricObserverExec, find_metric_node, get_metric};
+use crate::delta_datafusion::physical::{
+    MetricObserverExec, find_metric_node, get_metric, spilled_bytes,
+};
 use crate::delta_datafusion::planner::DeltaPlanner;
 use crate::delta_datafusion::utils::coerce_predicate_literals;
 use crate::delta_datafusion::{
     DataFusionMixins, DeltaColumn, DeltaScanExec, DeltaScanNext, SessionFallbackPolicy,
-    SessionResolveContext, create_session, normalize_path_as_file_id, resolve_file_column_name,
-    resolve_session_state, update_datafusion_session,
+    SessionResolveContext, create_session_state_with_spill_config, normalize_path_as_file_id,
+    resolve_file_column_name, resolve_session_state, update_datafusion_session,
 };
 use crate::delta_datafusion::{Expression, into_expr, maybe_into_expr};
 use crate::kernel::schema::cast::{merge_arrow_field, merge_arrow_schema};
@@ -90,6 +94,7 @@ use crate::kernel::{
     Action, ActiveAddOptions, AddStatsPolicy, EagerSnapshot, StructTypeExt, new_metadata,
     resolve_snapshot,
 };
+use crate::logstore::storage::WrittenFiles;
 use crate::logstore::{LogStore, LogStoreRef};
 use crate::operations::cdc::*;
 use crate::operations::merge::barrier::find_node;
@@ -173,13 +178,24 @@ pub struct MergeBuilder {
     /// Datafusion session state relevant for executing the input plan
     state: Option<Arc<dyn Session>>,
     session_fallback_policy: SessionFallbackPolicy,
+    /// Bytes kept in memory before spilling to disk, if no session is provided
+    max_spill_size: Option<usize>,
+    /// Disk space available for spill files, if no session is provided
+    max_temp_directory_size: Option<u64>,
     /// Properties passed to underlying parquet writer for when files are rewritten
     writer_properties: Option<WriterProperties>,
+    /// Size above which rewritten data files are split.
+    /// If None, the `delta.targetFileSize` table property is used.
+    target_file_size: Option<NonZeroU64>,
+    /// Maximum number of rows written to a single data file
+    max_rows_per_file: Option<NonZeroUsize>,
     /// Additional information to add to the commit
     commit_properties: CommitProperties,
     /// safe_cast determines how data types that do not match the underlying table are handled
     /// By default an error is returned
     safe_cast: bool,
+    /// Abort the merge once cancelled
+    cancellation_token: Option<CancellationToken>,
     custom_execute_handler: Option<Arc<dyn CustomExecuteHandler>>,
 }
 
@@ -210,14 +226,19 @@ impl MergeBuilder {
             target_alias: None,
             state: None,
             session_fallback_policy: SessionFallbackPolicy::default(),
+            max_spill_size: None,
+            max_temp_directory_size: None,
             commit_properties: CommitProperties::default(),
             writer_properties: None,
+            target_file_size: None,
+            max_rows_per_file: None,
             merge_schema: false,
             match_operations: Vec::new(),
             not_match_operations: Vec::new(),
             not_match_source_operations: Vec::new(),
             safe_cast: false,
             streaming: false,
+            cancellation_token: None,
             custom_execute_handler: None,
         }
     }
@@ -434,6 +455,25 @@ impl MergeBuilder {
         self
     }
 
+    /// Limit the memory used to join the source with the target to `max_spill_size` bytes,
+    /// spilling to disk beyond it instead of failing. The join is planned as a sort merge join,
+    /// as hash joins cannot spill.
+    ///
+    /// Only applies when no session is set via [`with_session_state`](Self::with_session_state),
+    /// whose runtime environment is used as is.
+    pub fn with_max_spill_size(mut self, max_spill_size: usize) -> Self {
+        self.max_spill_size = Some(max_spill_size);
+        self
+    }
+
+    /// Limit the disk space used for spill files to `max_temp_directory_size` bytes.
+    ///
+    /// Only applies when no session is set via [`with_session_state`](Self::with_session_state).
+    pub fn with_max_temp_directory_size(mut self, max_temp_directory_size: u64) -> Self {
+        self.max_temp_directory_size = Some(max_temp_directory_size);
+        self
+    }
+
     /// Additional metadata to be added to commit info
     pub fn with_commit_properties(mut self, commit_properties: CommitProperties) -> Self {
         self.commit_properties = commit_properties;
@@ -446,6 +486,20 @@ impl MergeBuilder {
         self
     }
 
+    /// Specify the target file size for data files written by the merge.
+    ///
+    /// Defaults to the `delta.targetFileSize` table property.
+    pub fn with_target_file_size(mut self, target_file_size: NonZeroU64) -> Self {
+        self.target_file_size = Some(target_file_size);
+        self
+    }
+
+    /// Specify the maximum number of rows written to a single data file.
+    pub fn with_max_rows_per_file(mut self, max_rows_per_file: NonZeroUsize) -> Self {
+        self.max_rows_per_file = Some(max_rows_per_file);
+        self
+    }
+
     /// Specify whether MERGE uses safe casts when casting update and insert
     /// expressions to the table's schema. When enabled, failed casts yield null
     /// for target columns that allow null values. When disabled, failed casts
@@ -467,6 +521,16 @@ impl MergeBuilder {
         self
     }
 
+    /// Abort the merge once `token` is cancelled.
+    ///
+    /// The merge fails with [`DeltaTableError::Cancelled`] unless it already started to commit,
+    /// and the files it wrote are deleted. Deleting is best effort, files which cannot be deleted
+    /// are left for a full vacuum.
+    pub fn with_cancellation_token(mut self, token: CancellationToken) -> Self {
+        self.cancellation_token = Some(token);
+        self
+    }
+
     /// Set a custom execute handler, for pre and post execution
     pub fn with_custom_execute_handler(mut self, handler: Arc<dyn CustomExecuteHandler>) -> Self {
         self.custom_execute_handler = Some(handler);
@@ -701,6 +765,8 @@ pub struct MergeMetrics {
     pub scan_time_ms: u64,
     /// Time taken to rewrite the matched files
     pub rewrite_time_ms: u64,
+    /// Number of bytes spilled to disk while joining the source with the target
+    pub spilled_bytes: u64,
 }
 #[derive(Clone, Debug)]
 pub(crate) struct MergeMetricExtensionPlanner {}
@@ -850,7 +916,15 @@ fn build_merge_barrier_validation_plan(
 }
 
 #[allow(clippy::too_many_arguments)]
-#[tracing::instrument(skip_all, fields(operation = "merge", version = snapshot.version(), table_uri = %log_store.root_url()))]
+#[tracing::instrument(
+    skip_all,
+    fields(
+        operation = "merge",
+        operation_id = %operation_id,
+        version = snapshot.version(),
+        table_uri = %log_store.root_url(),
+    )
+)]
 async fn execute(
     predicate: Expression,
     mut source: DataFrame,
@@ -858,6 +932,8 @@ async fn execute(
     snapshot: EagerSnapshot,
     state: SessionState,
     writer_properties: Option<WriterProperties>,
+    target_file_size: Option<NonZeroU64>,
+    max_rows_per_file: Option<NonZeroUsize>,
     mut commit_properties: CommitProperties,
     safe_cast: bool,
     streaming: bool,
@@ -868,6 +944,7 @@ async fn execute(
     not_match_target_operations: Vec<MergeOperationConfig>,
     not_match_source_operations: Vec<MergeOperationConfig>,
     operation_id: Uuid,
+    cancellation_token: Option<&CancellationToken>,
     handle: Option<&Arc<dyn CustomExecuteHandler>>,
 ) -> DeltaResult<(EagerSnapshot, MergeMetrics)> {
     info!(
@@ -971,7 +1048,7 @@ async fn execute(
     // In the case where there are partition columns in the join predicate, we can scan the source table
     // to get the distinct list of partitions affected and constrain the search to those.
 
-    let target_subset_filter: Option<Expr> = if !not_match_source_operations.is_empty() {
+    let early_filter = if !not_match_source_operations.is_empty() {
         // It's only worth trying to create an early filter where there are no `when_not_matched_source` operators, since
         // that implies a full scan
         None
@@ -986,12 +1063,23 @@ async fn execute(
             streaming,
         )
         .await?
-    }
-    .map(|e| normalize_target_subset_filter(target.schema().clone(), e))
-    .transpose()?;
+    };
+    let (target_subset_filter, file_skipping_filter) = match early_filter {
+        Some(filter) => (
+            Some(normalize_target_subset_filter(
+                target.schema().clone(),
+                filter.predicate,
+            )?),
+            Some(normalize_target_subset_filter(
+                target.schema().clone(),
+                filter.file_skipping_predicate,
+            )?),
+        ),
+        None => (None, None),
+    };
 
     // Predicate will be used for conflict detection
-    let commit_predicate = match target_subset_filter.clone() {
+    let commit_predicate = match target_subset_filter {
         None => None, // No predicate means it's a full table merge
         Some(some_filter) => {
             let predict_expr = match &target_alias {
@@ -1004,9 +1092,10 @@ async fn execute(
 
     debug!("Using target subset filter: {commit_predicate:?}");
 
-    // Apply the early filter only to file skipping.
+    // Apply the early filter only to file skipping. Its tighter variant narrows equality join
+    // keys down to the distinct source keys where possible.
     let file_skipping_predicates =
-        build_file_skipping_predicates(target_subset_filter, target_alias.as_deref());
+        build_file_skipping_predicates(file_skipping_filter, target_alias.as_deref());
     let needs_duplicate_match_validation = !match_operations.is_empty();
 
     let target_provider = {
@@ -1594,29 +1683,44 @@ async fn execute(
         .ok_or_else(err)?;
 
     let table_partition_cols = current_metadata.partition_columns().to_vec();
-    let writer_stats_config = WriterStatsConfig::from_config(snapshot.table_configuration());
+    let writer_stats_config = WriterStatsConfig::from_config(snapshot.table_configuration())
+        .with_multipart_config(log_store.config().options().multipart_config());
 
-    let (mut actions, write_plan_metrics) = write_execution_plan_v2(
+    // the files written so far are deleted again if the merge is cancelled before it commits
+    let written_files = WrittenFiles::default();
+    let write_files = write_execution_plan_v2(
         Some(&snapshot),
         &state,
-        write,
+        write.clone(),
         table_partition_cols.to_vec(),
-        log_store.object_store(Some(operation_id)),
-        Some(snapshot.table_properties().target_file_size()),
+        written_files.track(log_store.object_store(Some(operation_id))),
+        Some(target_file_size.unwrap_or_else(|| snapshot.table_properties().target_file_size())),
+        None,
+        max_rows_per_file,
         None,
+        vec![],
         writer_properties.clone(),
         writer_stats_config.clone(),
         None,
         should_cdc, // if true, write execution plan splits batches in [normal, cdc] data before writing
         None,
-    )
-    .await?;
+    );
+    let (mut actions, write_plan_metrics) =
+        match run_until_cancelled(cancellation_token, write_files).await {
+            Ok(result) => result,
+            Err(DeltaTableError::Cancelled) => {
+                written_files.delete(log_store.object_store(None)).await;
+                return Err(DeltaTableError::Cancelled);
+            }
+            Err(err) => return Err(err),
+        };
     if let Some(schema_metadata) = schema_action {
         actions.push(schema_metadata);
     }
 
     metrics.rewrite_time_ms = write_plan_metrics.write_time_ms;
     metrics.scan_time_ms = write_plan_metrics.scan_time_ms;
+    metrics.spilled_bytes = spilled_bytes(&write);
     metrics.num_target_files_added = actions.len();
 
     let survivors = barrier
@@ -1722,6 +1826,10 @@ async fn execute(
         return Ok((snapshot, metrics));
     }
 
+    if let Err(err) = ensure_not_cancelled(cancellation_token) {
+        written_files.delete(log_store.object_store(None)).await;
+        return Err(err);
+    }
     let commit = CommitBuilder::from(commit_properties)
         .with_actions(actions)
         .with_operation_id(operation_id)
@@ -1889,7 +1997,17 @@ impl std::future::IntoFuture for MergeBuilder {
             let (state, _) = resolve_session_state(
                 this.state.as_deref(),
                 this.session_fallback_policy,
-                || create_session().state(),
+                || {
+                    let mut state = create_session_state_with_spill_config(
+                        this.max_spill_size,
+                        this.max_temp_directory_size,
+                    );
+                    if this.max_spill_size.is_some() {
+                        // hash joins keep the build side in memory, sort merge joins can spill
+                        state.config_mut().options_mut().optimizer.prefer_hash_join = false;
+                    }
+                    state
+                },
                 SessionResolveContext {
                     operation: "merge",
                     table_uri: Some(this.log_store.root_url()),
@@ -1906,6 +2024,8 @@ impl std::future::IntoFuture for MergeBuilder {
                 snapshot,
                 state,
                 this.writer_properties,
+                this.target_file_size,
+                this.max_rows_per_file,
                 this.commit_properties,
                 this.safe_cast,
                 this.streaming,
@@ -1916,6 +2036,7 @@ impl std::future::IntoFuture for MergeBuilder {
                 this.not_match_operations,
                 this.not_match_source_operations,
                 operation_id,
+                this.cancellation_token.as_ref(),
                 this.custom_execute_handler.as_ref(),
             )
             .await?;
@@ -1970,6 +2091,7 @@ mod tests {
     use regex::Regex;
     use serde_json::json;
     use std::collections::HashMap;
+    use std::num::NonZeroUsize;
     use std::ops::Neg;
     use std::sync::Arc;
     use url::Url;
@@ -2051,6 +2173,65 @@ mod tests {
         assert_batches_sorted_eq!(&expected, &actual);
     }
 
+    #[tokio::test]
+    async fn test_merge_early_filter_skips_files_between_source_keys() {
+        let schema = get_arrow_schema(&None);
+        let mut table = setup_table(None).await;
+        for ids in [["A", "B"], ["M", "N"], ["X", "Y"]] {
+            let batch = RecordBatch::try_new(
+                Arc::clone(&schema),
+                vec![
+                    Arc::new(arrow::array::StringArray::from(ids.to_vec())),
+                    Arc::new(arrow::array::Int32Array::from(vec![1, 1])),
+                    Arc::new(arrow::array::StringArray::from(vec![
+                        "2021-02-01",
+                        "2021-02-01",
+                    ])),
+                ],
+            )
+            .unwrap();
+            table = table
+                .write(vec![batch])
+                .with_save_mode(SaveMode::Append)
+                .await
+                .unwrap();
+        }
+        assert_eq!(table.snapshot().unwrap().log_data().num_files(), 3);
+
+        let ctx = SessionContext::new();
+        let batch = RecordBatch::try_new(
+            Arc::clone(&schema),
+            vec![
+                Arc::new(arrow::array::StringArray::from(vec!["B", "X"])),
+                Arc::new(arrow::array::Int32Array::from(vec![999, 999])),
+                Arc::new(arrow::array::StringArray::from(vec![
+                    "2021-02-01",
+                    "2021-02-01",
+                ])),
+            ],
+        )
+        .unwrap();
+        let source = ctx.read_batch(batch).unwrap();
+
+        let (table, metrics) = table
+            .merge(source, col("target.id").eq(col("source.id")))
+            .with_source_alias("source")
+            .with_target_alias("target")
+            .when_matched_update(|update| update.update("value", col("source.value")))
+            .unwrap()
+            .await
+            .unwrap();
+
+        // The range of source keys covers all files, the keys themselves only two of them.
+        assert_eq!(metrics.num_target_files_scanned, 2);
+        assert_eq!(metrics.num_target_files_skipped_during_scan, 1);
+        assert_eq!(metrics.num_target_rows_updated, 2);
+
+        let last_commit = table.last_commit().await.unwrap();
+        let parameters = last_commit.operation_parameters.clone().unwrap();
+        assert_eq!(parameters["predicate"], json!("id >= 'B' AND id <= 'X'"));
+    }
+
     #[tokio::test]
     async fn test_merge_metrics_derive_skipped_files_when_scan_skip_metric_missing() {
         let schema = get_arrow_schema(&None);
@@ -3218,6 +3399,147 @@ mod tests {
         assert_merge(table, metrics).await;
     }
 
+    #[tokio::test]
+    async fn test_merge_with_max_spill_size() {
+        let (table, source) = setup().await;
+
+        let (table, metrics) = table
+            .merge(source, col("target.id").eq(col("source.id")))
+            .with_source_alias("source")
+            .with_target_alias("target")
+            .with_max_spill_size(16 * 1024 * 1024)
+            .when_matched_update(|update| {
+                update
+                    .update("value", col("source.value"))
+                    .update("modified", col("source.modified"))
+            })
+            .unwrap()
+            .when_not_matched_by_source_update(|update| {
+                update
+                    .predicate(col("target.value").eq(lit(1)))
+                    .update("value", col("target.value") + lit(1))
+            })
+            .unwrap()
+            .when_not_matched_insert(|insert| {
+                insert
+                    .set("id", col("source.id"))
+                    .set("value", col("source.value"))
+                    .set("modified", col("source.modified"))
+            })
+            .unwrap()
+            .await
+            .unwrap();
+
+        // the tables fit into memory
+        assert_eq!(metrics.spilled_bytes, 0);
+        assert_merge(table, metrics).await;
+    }
+
+    #[tokio::test]
+    async fn test_merge_with_max_rows_per_file() {
+        let (table, source) = setup().await;
+
+        let (table, metrics) = table
+            .merge(source, col("target.id").eq(col("source.id")))
+            .with_source_alias("source")
+            .with_target_alias("target")
+            .with_max_rows_per_file(NonZeroUsize::new(1).unwrap())
+            .when_matched_update(|update| {
+                update
+                    .update("value", col("source.value"))
+                    .update("modified", col("source.modified"))
+            })
+            .unwrap()
+            .when_not_matched_by_source_update(|update| {
+                update
+                    .predicate(col("target.value").eq(lit(1)))
+                    .update("value", col("target.value") + lit(1))
+            })
+            .unwrap()
+            .when_not_matched_insert(|insert| {
+                insert
+                    .set("id", col("source.id"))
+                    .set("value", col("source.value"))
+                    .set("modified", col("source.modified"))
+            })
+            .unwrap()
+            .await
+            .unwrap();
+
+        assert_eq!(metrics.num_target_files_added, 5);
+        assert_eq!(table.snapshot().unwrap().log_data().num_files(), 5);
+        assert_merge(table, metrics).await;
+    }
+
+    #[tokio::test]
+    async fn test_merge_recomputes_generated_columns_on_update() {
+        let table = DeltaTable::new_in_memory()
+            .create()
+            .with_columns(vec![
+                StructField::new("id", DataType::INTEGER, true),
+                StructField::new("value", DataType::INTEGER, true),
+                StructField::new("doubled", DataType::INTEGER, true).with_metadata([(
+                    "delta.generationExpression",
+                    delta_kernel::schema::MetadataValue::String("value * 2".to_string()),
+                )]),
+            ])
+            .await
+            .unwrap();
+
+        let ctx = SessionContext::new();
+        let schema = Arc::new(ArrowSchema::new(vec![
+            Field::new("id", ArrowDataType::Int32, true),
+            Field::new("value", ArrowDataType::Int32, true),
+        ]));
+        let batch = RecordBatch::try_new(
+            schema.clone(),
+            vec![
+                Arc::new(arrow::array::Int32Array::from(vec![1, 2])),
+                Arc::new(arrow::array::Int32Array::from(vec![1, 2])),
+            ],
+        )
+        .unwrap();
+        let table = table.write(vec![batch]).await.unwrap();
+
+        let source = RecordBatch::try_new(
+            schema,
+            vec![
+                Arc::new(arrow::array::Int32Array::from(vec![1, 3])),
+                Arc::new(arrow::array::Int32Array::from(vec![10, 3])),
+            ],
+        )
+        .unwrap();
+        let (table, _metrics) = table
+            .merge(
+                ctx.read_batch(source).unwrap(),
+                col("target.id").eq(col("source.id")),
+            )
+            .with_source_alias("source")
+            .with_target_alias("target")
+            .when_matched_update(|update| update.update("value", col("source.value")))
+            .unwrap()
+            .when_not_matched_insert(|insert| {
+                insert
+                    .set("id", col("source.id"))
+                    .set("value", col("source.value"))
+            })
+            .unwrap()
+            .await
+            .unwrap();
+
+        let expected = vec![
+            "+----+-------+---------+",
+            "| id | value | doubled |",
+            "+----+-------+---------+",
+            "| 1  | 10    | 20      |",
+            "| 2  | 2     | 4       |",
+            "| 3  | 3     | 6       |",
+            "+----+-------+---------+",
+        ];
+        let actual = get_data(&table).await;
+        assert_batches_sorted_eq!(&expected, &actual);
+    }
+
     #[tokio::test]
     async fn test_merge_strict_cast_errors_on_invalid_update_value() {
         let schema = get_arrow_schema(&None);
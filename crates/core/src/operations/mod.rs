@@ -17,26 +17,43 @@ use async_trait::async_trait;
 #[cfg(feature = "datafusion")]
 pub use datafusion::physical_plan::common::collect as collect_sendable_stream;
 use delta_kernel::table_properties::{DataSkippingNumIndexedCols, TableProperties};
+use futures::StreamExt as _;
+use object_store::path::Path;
+pub use tokio_util::sync::CancellationToken;
+use tracing::debug;
 use url::Url;
 use uuid::Uuid;
 
 use self::{
-    add_column::AddColumnBuilder, add_feature::AddTableFeatureBuilder, create::CreateBuilder,
-    drop_column_not_null::DropColumnNotNullBuilder, filesystem_check::FileSystemCheckBuilder,
+    add_column::AddColumnBuilder, add_feature::AddTableFeatureBuilder, cdf_reader::CdfReader,
+    check::CheckBuilder, checkpoint::CheckpointBuilder, cleanup_log::CleanupLogBuilder,
+    create::CreateBuilder, drop_column_not_null::DropColumnNotNullBuilder,
+    filesystem_check::FileSystemCheckBuilder, recompute_stats::RecomputeStatsBuilder,
     restore::RestoreBuilder, set_tbl_properties::SetTablePropertiesBuilder,
     update_field_metadata::UpdateFieldMetadataBuilder,
     update_table_metadata::UpdateTableMetadataBuilder, vacuum::VacuumBuilder,
+    verify_checkpoint::VerifyCheckpointBuilder,
 };
 #[cfg(feature = "datafusion")]
 use self::{
-    constraints::ConstraintBuilder, delete::DeleteBuilder, drop_constraints::DropConstraintBuilder,
-    load::LoadBuilder, load_cdf::CdfLoadBuilder, merge::MergeBuilder, optimize::OptimizeBuilder,
-    update::UpdateBuilder, write::WriteBuilder,
+    constraints::ConstraintBuilder,
+    delete::DeleteBuilder,
+    drop_constraints::DropConstraintBuilder,
+    load::LoadBuilder,
+    load_cdf::CdfLoadBuilder,
+    merge::MergeBuilder,
+    optimize::OptimizeBuilder,
+    update::UpdateBuilder,
+    write::WriteBuilder,
+    write::ingest::{CsvReadOptions, IngestSource, JsonReadOptions},
 };
 use crate::DeltaTable;
 #[cfg(feature = "datafusion")]
+use crate::delta_datafusion::DeltaCdfStreamExec;
+#[cfg(feature = "datafusion")]
 use crate::delta_datafusion::Expression;
 use crate::errors::{DeltaResult, DeltaTableError};
+use crate::kernel::{Action, Version};
 use crate::logstore::LogStoreRef;
 use crate::operations::generate::GenerateBuilder;
 use crate::table::builder::DeltaTableBuilder;
@@ -44,16 +61,23 @@ use crate::table::config::{DEFAULT_NUM_INDEX_COLS, TablePropertiesExt as _};
 
 pub mod add_column;
 pub mod add_feature;
+pub mod cdf_reader;
+pub mod check;
+pub mod checkpoint;
+pub mod cleanup_log;
 pub mod convert_to_delta;
 pub mod create;
 pub mod drop_column_not_null;
 pub mod drop_constraints;
 pub mod filesystem_check;
 pub mod generate;
+pub mod progress;
+pub mod recompute_stats;
 pub mod restore;
 pub mod update_field_metadata;
 pub mod update_table_metadata;
 pub mod vacuum;
+pub mod verify_checkpoint;
 
 #[cfg(feature = "datafusion")]
 mod cdc;
@@ -62,6 +86,8 @@ pub mod constraints;
 #[cfg(feature = "datafusion")]
 pub mod delete;
 #[cfg(feature = "datafusion")]
+pub mod dry_run;
+#[cfg(feature = "datafusion")]
 mod load;
 #[cfg(feature = "datafusion")]
 pub mod load_cdf;
@@ -146,6 +172,46 @@ impl DeltaTable {
         FileSystemCheckBuilder::new(self.log_store(), self.state.clone().map(|s| s.snapshot))
     }
 
+    /// Write a checkpoint for the current table version
+    #[must_use]
+    pub fn checkpoint(self) -> CheckpointBuilder {
+        CheckpointBuilder::new(self.log_store(), self.state.clone().map(|s| s.snapshot))
+    }
+
+    /// Delete expired files from the table log
+    #[must_use]
+    pub fn cleanup_log(self) -> CleanupLogBuilder {
+        CleanupLogBuilder::new(self.log_store(), self.state.clone().map(|s| s.snapshot))
+    }
+
+    /// Check the integrity of the log, returning a report of the problems found
+    #[must_use]
+    pub fn check(self) -> CheckBuilder {
+        CheckBuilder::new(self.log_store(), self.state.clone().map(|s| s.snapshot))
+    }
+
+    /// Verify the checkpoint at `version` against the commits it summarizes
+    #[must_use]
+    pub fn verify_checkpoint(self, version: Version) -> VerifyCheckpointBuilder {
+        VerifyCheckpointBuilder::new(
+            self.log_store(),
+            self.state.clone().map(|s| s.snapshot),
+            version,
+        )
+    }
+
+    /// Recompute missing file statistics from the Parquet footers of the data files
+    #[must_use]
+    pub fn recompute_stats(self) -> RecomputeStatsBuilder {
+        RecomputeStatsBuilder::new(self.log_store(), self.state.clone().map(|s| s.snapshot))
+    }
+
+    /// Read the change data feed of the table as Arrow record batches, without DataFusion
+    #[must_use]
+    pub fn cdf_reader(self) -> CdfReader {
+        CdfReader::new(self.log_store(), self.state.clone().map(|s| s.snapshot))
+    }
+
     /// Enable a table feature for a table
     #[must_use]
     pub fn add_feature(self) -> AddTableFeatureBuilder {
@@ -205,6 +271,16 @@ impl DeltaTable {
         CdfLoadBuilder::new(self.log_store(), self.state.map(|s| s.snapshot))
     }
 
+    /// Continuously read the changes of the table starting at `starting_version`, returning
+    /// an unbounded [`DeltaCdfStreamExec`] that picks up new commits as they arrive.
+    pub fn stream_cdf(&self, starting_version: Version) -> DeltaResult<DeltaCdfStreamExec> {
+        DeltaCdfStreamExec::try_new(
+            self.log_store(),
+            self.snapshot()?.snapshot().clone(),
+            starting_version,
+        )
+    }
+
     /// Write the given record batches to the table, returning a [`WriteBuilder`].
     #[must_use]
     pub fn write(self, batches: impl IntoIterator<Item = RecordBatch>) -> WriteBuilder {
@@ -212,6 +288,33 @@ impl DeltaTable {
             .with_input_batches(batches)
     }
 
+    /// Parse CSV data from `source` and write it to the table, returning a [`WriteBuilder`].
+    ///
+    /// See [`write::ingest`] for how the schema of the data is determined.
+    pub fn write_csv(
+        self,
+        source: impl Into<IngestSource>,
+        options: CsvReadOptions,
+    ) -> DeltaResult<WriteBuilder> {
+        let table_schema = self.state.as_ref().map(|s| s.snapshot.arrow_schema());
+        let batches = write::ingest::read_csv(source.into(), options, table_schema)?;
+        Ok(self.write(batches))
+    }
+
+    /// Parse newline delimited JSON data from `source` and write it to the table, returning a
+    /// [`WriteBuilder`].
+    ///
+    /// See [`write::ingest`] for how the schema of the data is determined.
+    pub fn write_ndjson(
+        self,
+        source: impl Into<IngestSource>,
+        options: JsonReadOptions,
+    ) -> DeltaResult<WriteBuilder> {
+        let table_schema = self.state.as_ref().map(|s| s.snapshot.arrow_schema());
+        let batches = write::ingest::read_ndjson(source.into(), options, table_schema)?;
+        Ok(self.write(batches))
+    }
+
     /// Audit active files with files present on the filesystem
     #[must_use]
     pub fn optimize<'a>(self) -> OptimizeBuilder<'a> {
@@ -315,6 +418,57 @@ pub(crate) trait Operation: std::future::IntoFuture {
     }
 }
 
+/// Drive `future` until it completes or `token` is cancelled, in which case the future is
+/// dropped and [`DeltaTableError::Cancelled`] is returned.
+///
+/// Only work which precedes the commit may be raced against the token, a commit in flight must
+/// complete to tell whether it succeeded.
+pub(crate) async fn run_until_cancelled<T>(
+    token: Option<&CancellationToken>,
+    future: impl Future<Output = DeltaResult<T>>,
+) -> DeltaResult<T> {
+    let Some(token) = token else {
+        return future.await;
+    };
+    tokio::select! {
+        biased;
+        _ = token.cancelled() => Err(DeltaTableError::Cancelled),
+        result = future => result,
+    }
+}
+
+/// Fail with [`DeltaTableError::Cancelled`] if `token` was cancelled, checked before committing.
+pub(crate) fn ensure_not_cancelled(token: Option<&CancellationToken>) -> DeltaResult<()> {
+    match token {
+        Some(token) if token.is_cancelled() => Err(DeltaTableError::Cancelled),
+        _ => Ok(()),
+    }
+}
+
+/// Delete the data and change data files written for `actions`, which will not be committed.
+///
+/// This is best effort, files which cannot be deleted are left for a full vacuum.
+pub(crate) async fn delete_uncommitted_files(log_store: &LogStoreRef, actions: &[Action]) {
+    let paths: Vec<_> = actions
+        .iter()
+        .filter_map(|action| match action {
+            Action::Add(add) => Some(add.path.as_str()),
+            Action::Cdc(cdc) => Some(cdc.path.as_str()),
+            _ => None,
+        })
+        .map(|path| Path::parse(path).unwrap_or_else(|_| Path::from(path)))
+        .map(Ok)
+        .collect();
+    let mut deleted = log_store
+        .object_store(None)
+        .delete_stream(futures::stream::iter(paths).boxed());
+    while let Some(result) = deleted.next().await {
+        if let Err(err) = result {
+            debug!("Failed to delete uncommitted file: {err}");
+        }
+    }
+}
+
 /// High level interface for executing commands against a DeltaTable
 #[deprecated(note = "Use methods directly on DeltaTable instead, e.g. `delta_table.create()`")]
 pub struct DeltaOps(pub DeltaTable);
@@ -426,6 +580,28 @@ impl DeltaOps {
             .with_input_batches(batches)
     }
 
+    /// Parse CSV data and write it to the Delta table
+    #[cfg(feature = "datafusion")]
+    #[deprecated(note = "Use [`DeltaTable::write_csv`] instead")]
+    pub fn write_csv(
+        self,
+        source: impl Into<IngestSource>,
+        options: CsvReadOptions,
+    ) -> DeltaResult<WriteBuilder> {
+        self.0.write_csv(source, options)
+    }
+
+    /// Parse newline delimited JSON data and write it to the Delta table
+    #[cfg(feature = "datafusion")]
+    #[deprecated(note = "Use [`DeltaTable::write_ndjson`] instead")]
+    pub fn write_ndjson(
+        self,
+        source: impl Into<IngestSource>,
+        options: JsonReadOptions,
+    ) -> DeltaResult<WriteBuilder> {
+        self.0.write_ndjson(source, options)
+    }
+
     /// Vacuum stale files from delta table
     #[must_use]
     #[deprecated(note = "Use [`DeltaTable::vacuum`] instead")]
@@ -440,6 +616,48 @@ impl DeltaOps {
         FileSystemCheckBuilder::new(self.0.log_store, self.0.state.map(|s| s.snapshot))
     }
 
+    /// Write a checkpoint for the current table version
+    #[must_use]
+    #[deprecated(note = "Use [`DeltaTable::checkpoint`] instead")]
+    pub fn checkpoint(self) -> CheckpointBuilder {
+        CheckpointBuilder::new(self.0.log_store, self.0.state.map(|s| s.snapshot))
+    }
+
+    /// Delete expired files from the table log
+    #[must_use]
+    #[deprecated(note = "Use [`DeltaTable::cleanup_log`] instead")]
+    pub fn cleanup_log(self) -> CleanupLogBuilder {
+        CleanupLogBuilder::new(self.0.log_store, self.0.state.map(|s| s.snapshot))
+    }
+
+    /// Check the integrity of the log, returning a report of the problems found
+    #[must_use]
+    #[deprecated(note = "Use [`DeltaTable::check`] instead")]
+    pub fn check(self) -> CheckBuilder {
+        CheckBuilder::new(self.0.log_store, self.0.state.map(|s| s.snapshot))
+    }
+
+    /// Verify the checkpoint at `version` against the commits it summarizes
+    #[must_use]
+    #[deprecated(note = "Use [`DeltaTable::verify_checkpoint`] instead")]
+    pub fn verify_checkpoint(self, version: Version) -> VerifyCheckpointBuilder {
+        VerifyCheckpointBuilder::new(self.0.log_store, self.0.state.map(|s| s.snapshot), version)
+    }
+
+    /// Recompute missing file statistics from the Parquet footers of the data files
+    #[must_use]
+    #[deprecated(note = "Use [`DeltaTable::recompute_stats`] instead")]
+    pub fn recompute_stats(self) -> RecomputeStatsBuilder {
+        RecomputeStatsBuilder::new(self.0.log_store, self.0.state.map(|s| s.snapshot))
+    }
+
+    /// Read the change data feed of the table as Arrow record batches, without DataFusion
+    #[must_use]
+    #[deprecated(note = "Use [`DeltaTable::cdf_reader`] instead")]
+    pub fn cdf_reader(self) -> CdfReader {
+        CdfReader::new(self.0.log_store, self.0.state.map(|s| s.snapshot))
+    }
+
     /// Audit active files with files present on the filesystem
     #[cfg(feature = "datafusion")]
     #[must_use]
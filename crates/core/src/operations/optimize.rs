@@ -12,6 +12,11 @@
 //! optimized files. Optimize does not delete files from storage. To delete
 //! files that were removed, call `vacuum` on [`DeltaTable`].
 //!
+//! Change data files in `_change_data` are not compacted. The `cdc` actions of past commits
+//! reference them by path, and committed versions cannot be rewritten, so readers of the change
+//! data feed would never read a compacted file. Expired change data files are deleted by vacuum
+//! instead, see [`VacuumBuilder::with_expire_change_data`](super::vacuum::VacuumBuilder::with_expire_change_data).
+//!
 //! See [`OptimizeBuilder`] for configuration.
 //!
 //! # Example
@@ -22,7 +27,7 @@
 
 use std::collections::HashMap;
 use std::fmt;
-use std::num::NonZeroU64;
+use std::num::{NonZeroU64, NonZeroUsize};
 use std::sync::Arc;
 use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
 
@@ -30,6 +35,7 @@ use arrow::array::RecordBatch;
 use arrow::datatypes::SchemaRef;
 use datafusion::catalog::Session;
 use datafusion::execution::context::{SessionContext, SessionState};
+use datafusion::physical_plan::{ExecutionPlan, execute_stream};
 use delta_kernel::engine::arrow_conversion::TryIntoArrow as _;
 use delta_kernel::expressions::Scalar;
 use delta_kernel::table_features::ColumnMappingMode;
@@ -44,21 +50,37 @@ use parquet::basic::{Compression, ZstdLevel};
 use parquet::errors::ParquetError;
 use parquet::file::properties::WriterProperties;
 use serde::{Deserialize, Deserializer, Serialize, Serializer, de::Error as DeError};
+use tokio_util::sync::CancellationToken;
+use tokio_util::task::AbortOnDropHandle;
 use tracing::*;
 use uuid::Uuid;
 
+use super::progress::{OperationProgress, ProgressCallback, ProgressTracker};
+use super::write::WriterStatsConfig;
 use super::write::writer::{PartitionWriter, PartitionWriterConfig};
-use super::{CustomExecuteHandler, Operation};
+use super::{
+    CustomExecuteHandler, Operation, delete_uncommitted_files, ensure_not_cancelled,
+    run_until_cancelled,
+};
 use crate::delta_datafusion::{
     DataFusionMixins, DeltaScanConfig, DeltaScanNext, SessionFallbackPolicy, SessionResolveContext,
-    create_session_state_with_spill_config, resolve_session_state, update_datafusion_session,
+    create_session_state_with_spill_config, physical::spilled_bytes, resolve_session_state,
+    update_datafusion_session,
 };
 use crate::errors::{ColumnMappingOperation, DeltaResult, DeltaTableError};
-use crate::kernel::transaction::{CommitBuilder, CommitProperties, DEFAULT_RETRIES, PROTOCOL};
+use crate::kernel::transaction::{
+    CommitBuilder, CommitConflictError, CommitConflictReport, CommitProperties,
+    ConflictDiagnostics, PROTOCOL, ReadSet, TransactionError, WinningCommitSummary,
+};
 use crate::kernel::{Action, Add, DataType, PartitionsExt, Remove, StructType, Version};
 use crate::kernel::{EagerSnapshot, resolve_snapshot};
-use crate::logstore::{LogStore, LogStoreRef, ObjectStoreRef};
-use crate::parquet_utils::default_writer_properties;
+use crate::logstore::storage::WrittenFiles;
+use crate::logstore::{LogStore, LogStoreRef, MultipartConfig, ObjectStoreRef};
+#[cfg(feature = "parquet-encryption")]
+use crate::parquet_encryption::encrypt_writer_properties;
+use crate::parquet_utils::{
+    ColumnWriterProperties, apply_column_writer_properties, default_writer_properties,
+};
 use crate::protocol::DeltaOperation;
 use crate::table::config::TablePropertiesExt as _;
 use crate::table::state::DeltaTableState;
@@ -114,6 +136,10 @@ pub struct Metrics {
     pub preserved_stable_order: bool,
     /// Largest count of adjacent input files in one bin
     pub max_bin_span_files: usize,
+    /// Number of bytes spilled to disk while sorting the rows of a z-order
+    pub spilled_bytes: u64,
+    /// Number of rewritten bins not committed, since a concurrent transaction removed their files
+    pub num_bins_skipped: u64,
 }
 
 #[derive(Debug, Deserialize)]
@@ -137,6 +163,10 @@ struct MetricsSerde {
     preserved_stable_order: Option<bool>,
     #[serde(default)]
     max_bin_span_files: usize,
+    #[serde(default)]
+    spilled_bytes: u64,
+    #[serde(default)]
+    num_bins_skipped: u64,
 }
 
 impl From<MetricsSerde> for Metrics {
@@ -160,6 +190,8 @@ impl From<MetricsSerde> for Metrics {
             planner_strategy: value.planner_strategy,
             preserved_stable_order,
             max_bin_span_files: value.max_bin_span_files,
+            spilled_bytes: value.spilled_bytes,
+            num_bins_skipped: value.num_bins_skipped,
         }
     }
 }
@@ -229,6 +261,8 @@ pub struct PartialMetrics {
     pub files_removed: MetricDetails,
     /// The number of batches written
     pub num_batches: u64,
+    /// Number of bytes spilled to disk while reading the partition
+    pub spilled_bytes: u64,
 }
 
 impl Metrics {
@@ -239,6 +273,7 @@ impl Metrics {
         self.files_added.add(&partial.files_added);
         self.files_removed.add(&partial.files_removed);
         self.num_batches += partial.num_batches;
+        self.spilled_bytes += partial.spilled_bytes;
     }
 
     fn apply_planner_stats(&mut self, planner_stats: &PlannerStats) {
@@ -283,8 +318,12 @@ pub struct OptimizeBuilder<'a> {
     filters: &'a [PartitionFilter],
     /// Desired file size after bin-packing files
     target_size: Option<NonZeroU64>,
+    /// Maximum number of rows written to a single file
+    max_rows_per_file: Option<NonZeroUsize>,
     /// Properties passed to underlying parquet writer
     writer_properties: Option<WriterProperties>,
+    /// Properties of the parquet writer overridden for individual columns
+    column_writer_properties: HashMap<String, ColumnWriterProperties>,
     /// Commit properties and configuration
     commit_properties: CommitProperties,
     /// Maximum number of concurrent tasks (default is number of cpus)
@@ -294,8 +333,18 @@ pub struct OptimizeBuilder<'a> {
     /// Datafusion session state relevant for executing the input plan
     session: Option<Arc<dyn Session>>,
     session_fallback_policy: SessionFallbackPolicy,
+    /// Bytes kept in memory before spilling to disk, if no session is provided
+    max_spill_size: Option<usize>,
+    /// Disk space available for spill files, if no session is provided
+    max_temp_directory_size: Option<u64>,
     min_commit_interval: Option<Duration>,
+    /// Commit every rewritten bin on its own
+    commit_per_bin: bool,
+    /// Skip bins whose files were removed concurrently instead of failing
+    skip_conflicting_bins: bool,
     custom_execute_handler: Option<Arc<dyn CustomExecuteHandler>>,
+    cancellation_token: Option<CancellationToken>,
+    progress_callback: Option<ProgressCallback>,
 }
 
 impl super::Operation for OptimizeBuilder<'_> {
@@ -315,14 +364,22 @@ impl<'a> OptimizeBuilder<'a> {
             log_store,
             filters: &[],
             target_size: None,
+            max_rows_per_file: None,
             writer_properties: None,
+            column_writer_properties: HashMap::new(),
             commit_properties: CommitProperties::default(),
             max_concurrent_tasks: num_cpus::get(),
             optimize_type: OptimizeType::Compact,
             min_commit_interval: None,
+            commit_per_bin: false,
+            skip_conflicting_bins: false,
             session: None,
             session_fallback_policy: SessionFallbackPolicy::default(),
+            max_spill_size: None,
+            max_temp_directory_size: None,
             custom_execute_handler: None,
+            cancellation_token: None,
+            progress_callback: None,
         }
     }
 
@@ -344,12 +401,32 @@ impl<'a> OptimizeBuilder<'a> {
         self
     }
 
+    /// Set the maximum number of rows written to a single file
+    pub fn with_max_rows_per_file(mut self, max_rows_per_file: NonZeroUsize) -> Self {
+        self.max_rows_per_file = Some(max_rows_per_file);
+        self
+    }
+
     /// Writer properties passed to parquet writer
     pub fn with_writer_properties(mut self, writer_properties: WriterProperties) -> Self {
         self.writer_properties = Some(writer_properties);
         self
     }
 
+    /// Override the writer properties of a single column in rewritten files.
+    ///
+    /// The settings take precedence over the [writer properties](Self::with_writer_properties).
+    /// Nested columns are identified by their field names separated by dots.
+    pub fn with_column_writer_properties(
+        mut self,
+        column: impl Into<String>,
+        properties: ColumnWriterProperties,
+    ) -> Self {
+        self.column_writer_properties
+            .insert(column.into(), properties);
+        self
+    }
+
     /// Additional information to write to the commit
     pub fn with_commit_properties(mut self, commit_properties: CommitProperties) -> Self {
         self.commit_properties = commit_properties;
@@ -371,18 +448,78 @@ impl<'a> OptimizeBuilder<'a> {
         self
     }
 
+    /// Limit the memory used to sort the rows of a z-order to `max_spill_size` bytes, spilling
+    /// to disk beyond it instead of failing.
+    ///
+    /// Only applies when no session is set via [`with_session_state`](Self::with_session_state),
+    /// whose runtime environment is used as is.
+    pub fn with_max_spill_size(mut self, max_spill_size: usize) -> Self {
+        self.max_spill_size = Some(max_spill_size);
+        self
+    }
+
+    /// Limit the disk space used for spill files to `max_temp_directory_size` bytes.
+    ///
+    /// Only applies when no session is set via [`with_session_state`](Self::with_session_state).
+    pub fn with_max_temp_directory_size(mut self, max_temp_directory_size: u64) -> Self {
+        self.max_temp_directory_size = Some(max_temp_directory_size);
+        self
+    }
+
     /// Min commit interval
     pub fn with_min_commit_interval(mut self, min_commit_interval: Duration) -> Self {
         self.min_commit_interval = Some(min_commit_interval);
         self
     }
 
+    /// Commit every rewritten bin as soon as it is written, instead of all bins at the end or
+    /// after the [`with_min_commit_interval`](Self::with_min_commit_interval).
+    ///
+    /// Every commit only declares the files of its bin as read, so optimizations of disjoint
+    /// partitions, e.g. by several workers, can commit concurrently.
+    pub fn with_commit_per_bin(mut self, commit_per_bin: bool) -> Self {
+        self.commit_per_bin = commit_per_bin;
+        self
+    }
+
+    /// Skip the rewritten files of a commit whose input files were removed by a concurrent
+    /// transaction, instead of failing the optimization.
+    ///
+    /// The skipped files are deleted and counted in [`Metrics::num_bins_skipped`]. Combine with
+    /// [`with_commit_per_bin`](Self::with_commit_per_bin) to only skip the conflicting bins.
+    pub fn with_skip_conflicting_bins(mut self, skip_conflicting_bins: bool) -> Self {
+        self.skip_conflicting_bins = skip_conflicting_bins;
+        self
+    }
+
     /// Set a custom execute handler, for pre and post execution
     pub fn with_custom_execute_handler(mut self, handler: Arc<dyn CustomExecuteHandler>) -> Self {
         self.custom_execute_handler = Some(handler);
         self
     }
 
+    /// Abort the optimization once `token` is cancelled.
+    ///
+    /// Rewrites in progress are stopped and the files rewritten since the last commit are
+    /// deleted. Commits made before the cancellation, see
+    /// [`with_min_commit_interval`](Self::with_min_commit_interval), are kept. Deleting is best
+    /// effort, files which cannot be deleted are left for a full vacuum.
+    pub fn with_cancellation_token(mut self, token: CancellationToken) -> Self {
+        self.cancellation_token = Some(token);
+        self
+    }
+
+    /// Report the progress of the optimization to `callback`.
+    ///
+    /// Progress is reported once the files to rewrite are planned and after each rewritten bin.
+    pub fn with_progress_callback(
+        mut self,
+        callback: impl Fn(&OperationProgress) + Send + Sync + 'static,
+    ) -> Self {
+        self.progress_callback = Some(ProgressCallback::new(callback));
+        self
+    }
+
     /// Set the DataFusion session used for planning and execution.
     ///
     /// The provided `session` should wrap a concrete `datafusion::execution::context::SessionState`.
@@ -427,13 +564,24 @@ impl<'a> std::future::IntoFuture for OptimizeBuilder<'a> {
             let operation_id = this.get_operation_id();
             this.pre_execute(operation_id).await?;
 
-            let writer_properties = this.writer_properties.unwrap_or_else(|| {
-                default_writer_properties(Compression::ZSTD(ZstdLevel::try_new(4).unwrap()))
-            });
+            let writer_properties = apply_column_writer_properties(
+                this.writer_properties.unwrap_or_else(|| {
+                    default_writer_properties(Compression::ZSTD(ZstdLevel::try_new(4).unwrap()))
+                }),
+                &this.column_writer_properties,
+            );
+            #[cfg(feature = "parquet-encryption")]
+            let writer_properties =
+                encrypt_writer_properties(snapshot.load_config(), writer_properties)?;
             let (session, _) = resolve_session_state(
                 this.session.as_deref(),
                 this.session_fallback_policy,
-                || create_session_state_with_spill_config(None, None),
+                || {
+                    create_session_state_with_spill_config(
+                        this.max_spill_size,
+                        this.max_temp_directory_size,
+                    )
+                },
                 SessionResolveContext {
                     operation: "optimize",
                     table_uri: Some(this.log_store.root_url()),
@@ -449,7 +597,12 @@ impl<'a> std::future::IntoFuture for OptimizeBuilder<'a> {
                 writer_properties,
                 session,
             )
-            .await?;
+            .await?
+            .with_max_rows_per_file(this.max_rows_per_file)
+            .with_commit_per_bin(this.commit_per_bin)
+            .with_skip_conflicting_bins(this.skip_conflicting_bins)
+            .with_cancellation_token(this.cancellation_token.clone())
+            .with_progress_callback(this.progress_callback.clone());
 
             let metrics = plan
                 .execute(
@@ -561,6 +714,14 @@ pub struct MergePlan {
     read_table_version: Version,
     /// Session state used for provider owned rewrite scans.
     read_session: Arc<SessionState>,
+    /// Token to abort the execution of the plan
+    cancellation_token: Option<CancellationToken>,
+    /// Receives the progress of the execution of the plan
+    progress_callback: Option<ProgressCallback>,
+    /// Commit every rewritten bin on its own
+    commit_per_bin: bool,
+    /// Skip commits whose files were removed concurrently instead of failing
+    skip_conflicting_bins: bool,
 }
 
 #[derive(Debug, Clone, Default)]
@@ -593,7 +754,7 @@ impl PlannerStats {
 }
 
 /// Parameters passed to individual merge tasks
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct MergeTaskParameters {
     /// Schema of written files
     file_schema: SchemaRef,
@@ -601,10 +762,16 @@ pub struct MergeTaskParameters {
     writer_properties: WriterProperties,
     /// Input parameters for the optimize operation
     input_parameters: OptimizeInput,
+    /// Maximum number of rows written to a single file
+    max_rows_per_file: Option<NonZeroUsize>,
     /// Num index cols to collect stats for
     num_indexed_cols: DataSkippingNumIndexedCols,
     /// Stats columns, specific columns to collect stats from, takes precedence over num_indexed_cols
     stats_columns: Option<Vec<String>>,
+    /// Columns to collect approximate distinct counts for
+    distinct_count_columns: Option<Vec<String>>,
+    /// Part size, concurrency and threshold of multipart uploads
+    multipart_config: MultipartConfig,
 }
 
 /// A stream of record batches, with a ParquetError on failure.
@@ -652,6 +819,37 @@ impl SelectedFileScanFactory {
 }
 
 impl MergePlan {
+    /// Limit the number of rows written to a single file when rewriting files.
+    pub fn with_max_rows_per_file(mut self, max_rows_per_file: Option<NonZeroUsize>) -> Self {
+        Arc::make_mut(&mut self.task_parameters).max_rows_per_file = max_rows_per_file;
+        self
+    }
+
+    /// Abort the execution of the plan once `token` is cancelled, before the next commit.
+    pub fn with_cancellation_token(mut self, token: Option<CancellationToken>) -> Self {
+        self.cancellation_token = token;
+        self
+    }
+
+    /// Report the progress of the execution of the plan to `callback`.
+    pub fn with_progress_callback(mut self, callback: Option<ProgressCallback>) -> Self {
+        self.progress_callback = callback;
+        self
+    }
+
+    /// Commit every rewritten bin on its own, see [`OptimizeBuilder::with_commit_per_bin`].
+    pub fn with_commit_per_bin(mut self, commit_per_bin: bool) -> Self {
+        self.commit_per_bin = commit_per_bin;
+        self
+    }
+
+    /// Skip commits whose input files were removed concurrently, see
+    /// [`OptimizeBuilder::with_skip_conflicting_bins`].
+    pub fn with_skip_conflicting_bins(mut self, skip_conflicting_bins: bool) -> Self {
+        self.skip_conflicting_bins = skip_conflicting_bins;
+        self
+    }
+
     /// Rewrites files in a single partition.
     ///
     /// Returns a vector of add and remove actions, as well as the partial metrics
@@ -687,6 +885,7 @@ impl MergePlan {
             files_added: MetricDetails::default(),
             files_removed,
             num_batches: 0,
+            spilled_bytes: 0,
         };
 
         // Next, initialize the writer
@@ -703,7 +902,10 @@ impl MergePlan {
             None,
             None,
             None,
-        )?;
+        )?
+        .with_max_rows_per_file(task_parameters.max_rows_per_file)
+        .with_multipart_config(&task_parameters.multipart_config)
+        .with_distinct_count_columns(task_parameters.distinct_count_columns.clone());
         let mut writer = PartitionWriter::try_with_config(
             object_store,
             writer_config,
@@ -766,11 +968,14 @@ impl MergePlan {
     }
 
     /// Datafusion-based z-order read.
+    ///
+    /// The executed plan is returned along with the stream, to read its metrics once the
+    /// stream is consumed.
     async fn read_zorder(
         files: MergeBin,
         context: Arc<zorder::ZOrderExecContext>,
         scan_factory: SelectedFileScanFactory,
-    ) -> Result<BoxStream<'static, Result<RecordBatch, ParquetError>>, DeltaTableError> {
+    ) -> Result<(ParquetReadStream, Arc<dyn ExecutionPlan>), DeltaTableError> {
         use datafusion::functions::core::expr_ext::FieldAccessor;
         use datafusion::logical_expr::expr::ScalarFunction;
         use datafusion::logical_expr::{Expr, ScalarUDF, ident};
@@ -797,20 +1002,27 @@ impl MergePlan {
         ));
         let df = df.sort(vec![expr.sort(true, true)])?;
 
-        let stream = df
-            .execute_stream()
-            .await?
+        let plan = df.create_physical_plan().await?;
+        let stream = execute_stream(plan.clone(), context.ctx.task_ctx())?
             .map_err(|err| {
                 ParquetError::General(format!("Z-order failed while scanning data: {err}"))
             })
             .boxed();
 
-        Ok(stream)
+        Ok((stream, plan))
     }
 
     /// Perform the operations outlined in the plan.
     #[allow(clippy::too_many_arguments)]
-    #[instrument(skip_all, fields(operation = "optimize", version = snapshot.version()))]
+    #[instrument(
+        skip_all,
+        fields(
+            operation = "optimize",
+            operation_id = %operation_id,
+            version = snapshot.version(),
+            table_uri = %log_store.root_url(),
+        )
+    )]
     pub async fn execute(
         mut self,
         log_store: LogStoreRef,
@@ -822,9 +1034,21 @@ impl MergePlan {
         handle: Option<&Arc<dyn CustomExecuteHandler>>,
     ) -> Result<Metrics, DeltaTableError> {
         let operations = std::mem::take(&mut self.operations);
+        let files_total = match &operations {
+            OptimizeOperations::Compact(bins) => bins
+                .values()
+                .flat_map(|(_, bins)| bins)
+                .map(MergeBin::len)
+                .sum(),
+            OptimizeOperations::ZOrder(_, bins) => bins.values().map(|(_, bin)| bin.len()).sum(),
+        };
+        let mut progress =
+            ProgressTracker::start(self.progress_callback.clone(), "OPTIMIZE", files_total);
         let read_session = self.read_session.clone();
         info!("starting optimize execution");
-        let object_store = log_store.object_store(Some(operation_id));
+        // files rewritten since the last commit are deleted again if the optimization is cancelled
+        let written_files = WrittenFiles::default();
+        let object_store = written_files.track(log_store.object_store(Some(operation_id)));
         update_datafusion_session(
             read_session.as_ref(),
             log_store.as_ref(),
@@ -863,14 +1087,16 @@ impl MergePlan {
                             scan_factory.clone(),
                         );
 
-                        let rewrite_result = tokio::task::spawn(Self::rewrite_files(
-                            task_parameters.clone(),
-                            partition,
-                            files,
-                            object_store.clone(),
-                            batch_stream,
-                            true,
-                        ));
+                        // rewrites are aborted when the stream is dropped, e.g. on cancellation
+                        let rewrite_result =
+                            AbortOnDropHandle::new(tokio::task::spawn(Self::rewrite_files(
+                                task_parameters.clone(),
+                                partition,
+                                files,
+                                object_store.clone(),
+                                batch_stream,
+                                true,
+                            )));
                         util::flatten_join_error(rewrite_result)
                     })
                     .buffered(max_concurrent_tasks)
@@ -895,21 +1121,31 @@ impl MergePlan {
                 // For each rewrite evaluate the predicate and then modify each expression
                 // to either compute the new value or obtain the old one then write these batches
                 let log_store = log_store.clone();
+                let written_files = written_files.clone();
                 futures::stream::iter(bins)
                     .map(move |(_, (partition, files))| {
-                        let batch_stream = Self::read_zorder(
-                            files.clone(),
-                            exec_context.clone(),
-                            scan_factory.clone(),
-                        );
-                        let rewrite_result = tokio::task::spawn(Self::rewrite_files(
-                            task_parameters.clone(),
-                            partition,
-                            files,
-                            log_store.object_store(Some(operation_id)),
-                            batch_stream,
-                            false,
-                        ));
+                        let exec_context = exec_context.clone();
+                        let scan_factory = scan_factory.clone();
+                        let task_parameters = task_parameters.clone();
+                        let object_store =
+                            written_files.track(log_store.object_store(Some(operation_id)));
+                        let rewrite_result =
+                            AbortOnDropHandle::new(tokio::task::spawn(async move {
+                                let (batch_stream, plan) =
+                                    Self::read_zorder(files.clone(), exec_context, scan_factory)
+                                        .await?;
+                                let (actions, mut metrics) = Self::rewrite_files(
+                                    task_parameters,
+                                    partition,
+                                    files,
+                                    object_store,
+                                    futures::future::ready(Ok(batch_stream)),
+                                    false,
+                                )
+                                .await?;
+                                metrics.spilled_bytes = spilled_bytes(&plan);
+                                Ok::<_, DeltaTableError>((actions, metrics))
+                            }));
                         util::flatten_join_error(rewrite_result)
                     })
                     .buffer_unordered(max_concurrent_tasks)
@@ -929,33 +1165,74 @@ impl MergePlan {
         orig_metrics.apply_planner_stats(&self.planner_stats);
         let mut buffered_metrics = orig_metrics.clone();
         let mut total_metrics = orig_metrics.clone();
+        // metrics of the bins since the last commit, only counted once they are committed
+        let mut uncommitted_metrics = vec![];
 
         let mut last_commit = Instant::now();
         let mut commits_made = 0;
+        // Every commit is based on the snapshot of the previous one, so it is only checked for
+        // conflicts with the transactions since then. The files removed by the concurrent
+        // transactions checked before are recorded, so bins rewriting them still conflict.
+        let mut concurrent_removals: HashMap<String, Version> = HashMap::new();
         let mut snapshot = snapshot.clone();
+        let token = self.cancellation_token.clone();
         loop {
-            let next = stream.next().await.transpose()?;
+            let next =
+                run_until_cancelled(token.as_ref(), async { stream.next().await.transpose() })
+                    .await;
+            let next = match next {
+                Ok(next) => next,
+                Err(err) => {
+                    // abort the rewrites in progress, then delete the files rewritten since the
+                    // last commit, which are not referenced by the table
+                    drop(stream);
+                    written_files.delete(log_store.object_store(None)).await;
+                    return Err(err);
+                }
+            };
 
             let end = next.is_none();
 
             if let Some((partial_actions, partial_metrics)) = next {
                 debug!("Recording metrics for a completed partition");
+                progress.advance(
+                    partial_metrics.num_files_removed as usize,
+                    partial_metrics.files_added.total_size as u64,
+                );
                 actions.extend(partial_actions);
                 buffered_metrics.add(&partial_metrics);
-                total_metrics.add(&partial_metrics);
+                uncommitted_metrics.push(partial_metrics);
             }
 
             let now = Instant::now();
-            let mature = match min_commit_interval {
-                None => false,
-                Some(i) => now.duration_since(last_commit) > i,
-            };
+            let mature = self.commit_per_bin
+                || match min_commit_interval {
+                    None => false,
+                    Some(i) => now.duration_since(last_commit) > i,
+                };
             if !actions.is_empty() && (mature || end) {
+                if let Err(err) = ensure_not_cancelled(token.as_ref()) {
+                    drop(stream);
+                    written_files.delete(log_store.object_store(None)).await;
+                    return Err(err);
+                }
                 let actions = std::mem::take(&mut actions);
                 last_commit = now;
 
                 let mut properties = CommitProperties::default();
                 properties.app_metadata = commit_properties.app_metadata.clone();
+                properties.engine_info = commit_properties.engine_info.clone();
+                properties.isolation_level = commit_properties.isolation_level;
+                let retry_policy = commit_properties
+                    .retry_policy
+                    .clone()
+                    .or_else(|| snapshot.table_properties().commit_retry_policy())
+                    .unwrap_or_default();
+                properties.retry_policy = Some(
+                    retry_policy
+                        .clone()
+                        .with_max_retries(retry_policy.max_retries() + commits_made),
+                );
                 properties
                     .app_metadata
                     .insert("readVersion".to_owned(), self.read_table_version.into());
@@ -971,19 +1248,62 @@ impl MergePlan {
 
                 debug!("committing {} actions", actions.len());
 
-                let commit = CommitBuilder::from(properties)
-                    .with_actions(actions)
-                    .with_operation_id(operation_id)
-                    .with_post_commit_hook_handler(handle.cloned())
-                    .with_max_retries(DEFAULT_RETRIES + commits_made)
-                    .build(
-                        Some(&snapshot),
-                        log_store.clone(),
-                        self.task_parameters.input_parameters.clone().try_into()?,
-                    )
-                    .await?;
-                snapshot = commit.snapshot().snapshot;
-                commits_made += 1;
+                // Only the rewritten files were read, so concurrent changes to other files or
+                // partitions do not conflict with this commit
+                let read_set = ReadSet::from_removed_files(&actions);
+                let base_version = snapshot.version();
+                let result = match concurrent_removal_conflict(
+                    &actions,
+                    &concurrent_removals,
+                    self.read_table_version,
+                ) {
+                    Some(err) => Err(err),
+                    None => {
+                        CommitBuilder::from(properties)
+                            .with_read_set(read_set)
+                            .with_actions(actions.clone())
+                            .with_operation_id(operation_id)
+                            .with_post_commit_hook_handler(handle.cloned())
+                            .build(
+                                Some(&snapshot),
+                                log_store.clone(),
+                                self.task_parameters.input_parameters.clone().try_into()?,
+                            )
+                            .await
+                    }
+                };
+                match result {
+                    Ok(commit) => {
+                        written_files.untrack(&actions);
+                        for version in base_version + 1..commit.version() {
+                            let summary = WinningCommitSummary::try_new(
+                                log_store.as_ref(),
+                                version - 1,
+                                version,
+                            )
+                            .await?;
+                            for remove in summary.removed_files() {
+                                concurrent_removals.insert(remove.path, version);
+                            }
+                        }
+                        snapshot = commit.snapshot().snapshot;
+                        commits_made += 1;
+                        for partial_metrics in uncommitted_metrics.drain(..) {
+                            total_metrics.add(&partial_metrics);
+                        }
+                    }
+                    Err(err) if self.skip_conflicting_bins && is_concurrent_removal(&err) => {
+                        warn!(
+                            "skipping {} rewritten bins, their files were removed concurrently: {err}",
+                            uncommitted_metrics.len()
+                        );
+                        total_metrics.num_bins_skipped += uncommitted_metrics.len() as u64;
+                        uncommitted_metrics.clear();
+                        delete_uncommitted_files(&log_store, &actions).await;
+                        written_files.untrack(&actions);
+                    }
+                    Err(err) => return Err(err),
+                }
             }
 
             if end {
@@ -1004,6 +1324,51 @@ impl MergePlan {
     }
 }
 
+/// Whether the commit failed since a concurrent transaction removed files it rewrites
+fn is_concurrent_removal(err: &DeltaTableError) -> bool {
+    matches!(
+        err,
+        DeltaTableError::Transaction {
+            source: TransactionError::CommitConflict(report),
+        } if matches!(
+            report.error,
+            CommitConflictError::ConcurrentDeleteRead | CommitConflictError::ConcurrentDeleteDelete
+        )
+    )
+}
+
+/// Conflict of a commit rewriting files which were removed by one of the `concurrent_removals`,
+/// the transactions committed since the plan was read which earlier commits were rebased over
+fn concurrent_removal_conflict(
+    actions: &[Action],
+    concurrent_removals: &HashMap<String, Version>,
+    read_version: Version,
+) -> Option<DeltaTableError> {
+    let conflicting = actions
+        .iter()
+        .filter_map(|action| match action {
+            Action::Remove(remove) => concurrent_removals
+                .get(&remove.path)
+                .map(|version| (remove.path.clone(), *version)),
+            _ => None,
+        })
+        .collect_vec();
+    let winning_version = conflicting.iter().map(|(_, version)| *version).min()?;
+    let diagnostics = ConflictDiagnostics {
+        read_version,
+        winning_version,
+        conflicting_files: conflicting.into_iter().map(|(path, _)| path).collect(),
+        ..Default::default()
+    };
+    Some(
+        TransactionError::CommitConflict(CommitConflictReport::new(
+            CommitConflictError::ConcurrentDeleteRead,
+            diagnostics,
+        ))
+        .into(),
+    )
+}
+
 /// Build a Plan on which files to merge together. See [OptimizeBuilder]
 #[instrument(skip_all, fields(operation = "create_merge_plan", version = snapshot.version()))]
 pub async fn create_merge_plan(
@@ -1060,15 +1425,23 @@ pub async fn create_merge_plan(
             file_schema,
             writer_properties,
             input_parameters,
+            max_rows_per_file: None,
             num_indexed_cols: snapshot.table_properties().num_indexed_cols(),
             stats_columns: snapshot
                 .table_properties()
                 .data_skipping_stats_columns
                 .as_ref()
                 .map(|v| v.iter().map(|v| v.to_string()).collect::<Vec<String>>()),
+            distinct_count_columns: WriterStatsConfig::from_config(snapshot.table_configuration())
+                .distinct_count_columns,
+            multipart_config: log_store.config().options().multipart_config(),
         }),
         read_table_version: snapshot.version(),
         read_session: Arc::new(session),
+        cancellation_token: None,
+        progress_callback: None,
+        commit_per_bin: false,
+        skip_conflicting_bins: false,
     })
 }
 
@@ -0,0 +1,115 @@
+//! Progress reporting for long running maintenance operations.
+//!
+//! Optimize, vacuum and convert to delta accept a callback, which is invoked with an
+//! [`OperationProgress`] once the work of the operation is planned and after each file, or
+//! group of files, was processed. The callback runs on the task driving the operation and should
+//! return quickly, e.g. by updating a progress bar.
+//!
+//! # Example
+//! ```rust ignore
+//! let (table, metrics) = table
+//!     .optimize()
+//!     .with_progress_callback(|progress| {
+//!         println!("{}/{} files", progress.files_processed, progress.files_total)
+//!     })
+//!     .await?;
+//! ```
+
+use std::fmt::{self, Debug};
+use std::sync::Arc;
+
+/// Progress of an operation
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct OperationProgress {
+    /// Name of the operation, e.g. `OPTIMIZE`
+    pub operation: &'static str,
+    /// Number of files processed so far
+    pub files_processed: usize,
+    /// Number of files the operation will process
+    pub files_total: usize,
+    /// Number of bytes written so far
+    pub bytes_written: u64,
+}
+
+/// Callback receiving the progress of an operation
+#[derive(Clone)]
+pub struct ProgressCallback(Arc<dyn Fn(&OperationProgress) + Send + Sync>);
+
+impl ProgressCallback {
+    /// Create a new [`ProgressCallback`]
+    pub fn new(callback: impl Fn(&OperationProgress) + Send + Sync + 'static) -> Self {
+        Self(Arc::new(callback))
+    }
+}
+
+impl Debug for ProgressCallback {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("ProgressCallback")
+    }
+}
+
+/// Accumulates the progress of an operation and reports it to the callback, if any
+pub(crate) struct ProgressTracker {
+    callback: Option<ProgressCallback>,
+    progress: OperationProgress,
+}
+
+impl ProgressTracker {
+    /// Report that `operation` is about to process `files_total` files
+    pub(crate) fn start(
+        callback: Option<ProgressCallback>,
+        operation: &'static str,
+        files_total: usize,
+    ) -> Self {
+        let tracker = Self {
+            callback,
+            progress: OperationProgress {
+                operation,
+                files_processed: 0,
+                files_total,
+                bytes_written: 0,
+            },
+        };
+        tracker.report();
+        tracker
+    }
+
+    /// Report that `files` more files were processed, writing `bytes_written` bytes
+    pub(crate) fn advance(&mut self, files: usize, bytes_written: u64) {
+        self.progress.files_processed += files;
+        self.progress.bytes_written += bytes_written;
+        self.report();
+    }
+
+    fn report(&self) {
+        if let Some(ProgressCallback(callback)) = &self.callback {
+            callback(&self.progress);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use parking_lot::Mutex;
+
+    use super::*;
+
+    #[test]
+    fn test_progress_tracker() {
+        let reported = Arc::new(Mutex::new(Vec::new()));
+        let callback = ProgressCallback::new({
+            let reported = reported.clone();
+            move |progress| reported.lock().push(*progress)
+        });
+
+        let mut tracker = ProgressTracker::start(Some(callback), "OPTIMIZE", 3);
+        tracker.advance(2, 100);
+        tracker.advance(1, 50);
+
+        let reported = reported.lock();
+        let processed: Vec<_> = reported.iter().map(|p| p.files_processed).collect();
+        assert_eq!(processed, vec![0, 2, 3]);
+        assert_eq!(reported[2].bytes_written, 150);
+        assert!(reported.iter().all(|p| p.files_total == 3));
+    }
+}
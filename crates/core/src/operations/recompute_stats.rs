@@ -0,0 +1,322 @@
+//! Recompute the statistics of the active files of a Delta table from their Parquet footers.
+//!
+//! Data skipping relies on the `numRecords`, `minValues`, `maxValues` and `nullCount` statistics
+//! stored in the add actions. Tables converted from Parquet, or written by engines which do not
+//! collect statistics, lack them for some or all files. This operation reads the footer of every
+//! active file and re-adds the files whose statistics are missing or cover fewer columns than the
+//! footer provides. Data files are not rewritten.
+//!
+//! # Example
+//! ```rust ignore
+//! let table = open_table(Url::from_directory_path("/abs/path/to/table").unwrap()).await?;
+//! let (table, metrics) = table.recompute_stats().await?;
+//! ````
+
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use delta_kernel::expressions::Scalar;
+use delta_kernel::schema::DataType;
+use futures::future::BoxFuture;
+use futures::{StreamExt, TryStreamExt};
+use indexmap::IndexMap;
+use parquet::arrow::arrow_reader::ArrowReaderOptions;
+use parquet::arrow::async_reader::{ParquetObjectReader, ParquetRecordBatchStreamBuilder};
+use serde::Serialize;
+
+use super::{CustomExecuteHandler, Operation, get_num_idx_cols_and_stats_columns};
+use crate::DeltaTable;
+use crate::errors::{DeltaResult, DeltaTableError};
+use crate::kernel::transaction::{CommitBuilder, CommitProperties, ReadSet};
+use crate::kernel::{Action, Add, EagerSnapshot, resolve_snapshot};
+use crate::logstore::LogStoreRef;
+#[cfg(feature = "parquet-encryption")]
+use crate::parquet_encryption::file_decryption_properties;
+use crate::protocol::{DeltaOperation, Stats};
+use crate::table::state::DeltaTableState;
+use crate::writer::stats::stats_from_parquet_metadata;
+
+/// Recompute the statistics of the active files of a table.
+/// See this module's documentation for more information
+pub struct RecomputeStatsBuilder {
+    /// A snapshot of the table
+    snapshot: Option<EagerSnapshot>,
+    /// Delta object store for handling data files
+    log_store: LogStoreRef,
+    /// Recompute the statistics of all files, not only of files with missing statistics
+    force: bool,
+    /// Max number of footers read concurrently
+    max_concurrent_tasks: usize,
+    /// Commit properties and configuration
+    commit_properties: CommitProperties,
+    custom_execute_handler: Option<Arc<dyn CustomExecuteHandler>>,
+}
+
+/// Metrics of the stats recomputation
+#[derive(Default, Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RecomputeStatsMetrics {
+    /// Number of active files whose footers were read
+    pub num_files_scanned: usize,
+    /// Number of files re-added with fresh statistics
+    pub num_files_updated: usize,
+}
+
+impl super::Operation for RecomputeStatsBuilder {
+    fn log_store(&self) -> &LogStoreRef {
+        &self.log_store
+    }
+    fn get_custom_execute_handler(&self) -> Option<Arc<dyn CustomExecuteHandler>> {
+        self.custom_execute_handler.clone()
+    }
+}
+
+impl RecomputeStatsBuilder {
+    /// Create a new [`RecomputeStatsBuilder`]
+    pub(crate) fn new(log_store: LogStoreRef, snapshot: Option<EagerSnapshot>) -> Self {
+        RecomputeStatsBuilder {
+            snapshot,
+            log_store,
+            force: false,
+            max_concurrent_tasks: num_cpus::get(),
+            commit_properties: CommitProperties::default(),
+            custom_execute_handler: None,
+        }
+    }
+
+    /// Recompute the statistics of all files, even if they look complete
+    pub fn with_force(mut self, force: bool) -> Self {
+        self.force = force;
+        self
+    }
+
+    /// Max number of Parquet footers read concurrently
+    pub fn with_max_concurrent_tasks(mut self, max_concurrent_tasks: usize) -> Self {
+        self.max_concurrent_tasks = max_concurrent_tasks;
+        self
+    }
+
+    /// Additional information to write to the commit
+    pub fn with_commit_properties(mut self, commit_properties: CommitProperties) -> Self {
+        self.commit_properties = commit_properties;
+        self
+    }
+
+    /// Set a custom execute handler, for pre and post execution
+    pub fn with_custom_execute_handler(mut self, handler: Arc<dyn CustomExecuteHandler>) -> Self {
+        self.custom_execute_handler = Some(handler);
+        self
+    }
+}
+
+/// Options reading the footers of the data files, which are decrypted with the parquet
+/// encryption configured for the table.
+#[cfg_attr(not(feature = "parquet-encryption"), allow(unused_variables))]
+fn reader_options(snapshot: &EagerSnapshot) -> DeltaResult<ArrowReaderOptions> {
+    let options = ArrowReaderOptions::new();
+    #[cfg(feature = "parquet-encryption")]
+    if let Some(encryption) = &snapshot.load_config().parquet_encryption {
+        let decryption = file_decryption_properties(encryption.client().clone())?;
+        return Ok(options.with_file_decryption_properties(decryption));
+    }
+    Ok(options)
+}
+
+/// Whether the statistics of `add` lack values which `fresh` provides
+fn is_incomplete(add: &Add, fresh: &Stats) -> bool {
+    match add.get_stats() {
+        Ok(Some(current)) => {
+            current.min_values.len() < fresh.min_values.len()
+                || current.max_values.len() < fresh.max_values.len()
+                || current.null_count.len() < fresh.null_count.len()
+        }
+        _ => true,
+    }
+}
+
+impl std::future::IntoFuture for RecomputeStatsBuilder {
+    type Output = DeltaResult<(DeltaTable, RecomputeStatsMetrics)>;
+    type IntoFuture = BoxFuture<'static, Self::Output>;
+
+    fn into_future(self) -> Self::IntoFuture {
+        let this = self;
+
+        Box::pin(async move {
+            let snapshot =
+                resolve_snapshot(this.log_store.as_ref(), this.snapshot.clone(), true, None)
+                    .await?;
+            let (num_indexed_cols, stats_columns) = get_num_idx_cols_and_stats_columns(
+                Some(snapshot.table_properties()),
+                HashMap::new(),
+            );
+            // only the names of partition columns are relevant, they are excluded from stats
+            let partition_columns: IndexMap<String, Scalar> = snapshot
+                .metadata()
+                .partition_columns()
+                .iter()
+                .map(|column| (column.clone(), Scalar::Null(DataType::STRING)))
+                .collect();
+
+            let reader_options = reader_options(&snapshot)?;
+            let object_store = this.log_store.object_store(None);
+            let files: Vec<_> = snapshot
+                .file_views(this.log_store.as_ref(), None)
+                .map_ok(|file| (file.object_store_path(), file.to_add()))
+                .try_collect()
+                .await?;
+            let num_files_scanned = files.len();
+
+            let force = this.force;
+            let updated: Vec<Add> = futures::stream::iter(files)
+                .map(|(path, add)| {
+                    let object_store = object_store.clone();
+                    let partition_columns = &partition_columns;
+                    let stats_columns = &stats_columns;
+                    let reader_options = reader_options.clone();
+                    async move {
+                        let reader = ParquetObjectReader::new(object_store, path)
+                            .with_file_size(add.size as u64);
+                        let builder = ParquetRecordBatchStreamBuilder::new_with_options(
+                            reader,
+                            reader_options,
+                        )
+                        .await?;
+                        let stats = stats_from_parquet_metadata(
+                            partition_columns,
+                            builder.metadata(),
+                            num_indexed_cols,
+                            stats_columns,
+                        )
+                        .map_err(DeltaTableError::from)?;
+                        if !force && !is_incomplete(&add, &stats) {
+                            return Ok(None);
+                        }
+                        Ok::<_, DeltaTableError>(Some(Add {
+                            data_change: false,
+                            stats: Some(serde_json::to_string(&stats)?),
+                            ..add
+                        }))
+                    }
+                })
+                .buffer_unordered(this.max_concurrent_tasks.max(1))
+                .try_filter_map(|add| futures::future::ready(Ok(add)))
+                .try_collect()
+                .await?;
+
+            let metrics = RecomputeStatsMetrics {
+                num_files_scanned,
+                num_files_updated: updated.len(),
+            };
+            if updated.is_empty() {
+                return Ok((
+                    DeltaTable::new_with_state(this.log_store, DeltaTableState::new(snapshot)),
+                    metrics,
+                ));
+            }
+
+            let operation_id = this.get_operation_id();
+            this.pre_execute(operation_id).await?;
+
+            let mut commit_properties = this.commit_properties.clone();
+            commit_properties.app_metadata.insert(
+                "operationMetrics".to_owned(),
+                serde_json::to_value(&metrics)?,
+            );
+            // a concurrent removal of a file must not be undone by re-adding it
+            let read_set = ReadSet::from_files(&updated);
+            let commit = CommitBuilder::from(commit_properties)
+                .with_operation_id(operation_id)
+                .with_post_commit_hook_handler(this.get_custom_execute_handler())
+                .with_read_set(read_set)
+                .with_actions(updated.into_iter().map(Action::Add).collect())
+                .build(
+                    Some(&snapshot),
+                    this.log_store.clone(),
+                    DeltaOperation::RecomputeStats { force },
+                )
+                .await?;
+
+            this.post_execute(operation_id).await?;
+
+            Ok((
+                DeltaTable::new_with_state(this.log_store, commit.snapshot),
+                metrics,
+            ))
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use object_store::ObjectStoreExt as _;
+    use object_store::path::Path;
+    use parquet::arrow::ArrowWriter;
+
+    use super::*;
+    use crate::writer::test_utils::{get_delta_schema, get_record_batch};
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn test_recompute_stats() -> DeltaResult<()> {
+        let table = DeltaTable::new_in_memory()
+            .create()
+            .with_columns(get_delta_schema().fields().cloned())
+            .await?;
+
+        // add a data file without statistics, as convert to delta of a foreign table would
+        let batch = get_record_batch(None, false);
+        let mut data = Vec::new();
+        let mut writer = ArrowWriter::try_new(&mut data, batch.schema(), None)?;
+        writer.write(&batch)?;
+        writer.close()?;
+        let size = data.len() as i64;
+        table
+            .log_store()
+            .object_store(None)
+            .put(&Path::from("part-00000.parquet"), data.into())
+            .await?;
+        let add = Add {
+            path: "part-00000.parquet".to_string(),
+            size,
+            modification_time: 0,
+            data_change: true,
+            ..Default::default()
+        };
+        let commit = CommitBuilder::default()
+            .with_actions(vec![Action::Add(add)])
+            .build(
+                Some(table.snapshot()?),
+                table.log_store(),
+                DeltaOperation::custom("ADD"),
+            )
+            .await?;
+        let table = DeltaTable::new_with_state(table.log_store(), commit.snapshot);
+
+        let (table, metrics) = table.recompute_stats().await?;
+        assert_eq!(metrics.num_files_scanned, 1);
+        assert_eq!(metrics.num_files_updated, 1);
+        assert_eq!(table.version(), Some(2));
+
+        let files: Vec<_> = table
+            .snapshot()?
+            .file_views(table.log_store().as_ref(), None)
+            .try_collect()
+            .await?;
+        assert_eq!(files.len(), 1);
+        assert_eq!(files[0].num_records(), Some(batch.num_rows()));
+        let stats: Stats = serde_json::from_str(&files[0].stats().unwrap()).unwrap();
+        assert!(stats.min_values.contains_key("value"));
+        assert_eq!(stats.null_count.len(), 3);
+
+        // statistics are complete now
+        let (table, metrics) = table.recompute_stats().await?;
+        assert_eq!(metrics.num_files_updated, 0);
+        assert_eq!(table.version(), Some(2));
+
+        let (table, metrics) = table.recompute_stats().with_force(true).await?;
+        assert_eq!(metrics.num_files_updated, 1);
+        assert_eq!(table.version(), Some(3));
+        let info = table.history(Some(1)).await?.next().unwrap();
+        assert_eq!(info.operation.as_deref(), Some("COMPUTE STATS"));
+        Ok(())
+    }
+}
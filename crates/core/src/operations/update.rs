@@ -39,10 +39,11 @@ use futures::{StreamExt as _, TryStreamExt as _, future::BoxFuture, stream};
 use itertools::Itertools as _;
 use parquet::file::properties::WriterProperties;
 use serde::Serialize;
-use tracing::log::*;
 use uuid::Uuid;
 
+use super::dry_run::{DryRunMetrics, dry_run_where_matches};
 use super::write::WriterStatsConfig;
+use super::write::generated_columns::{gc_is_enabled, recompute_generated_columns};
 use super::{
     CustomExecuteHandler, Operation,
     write::execution::{write_execution_plan, write_execution_plan_cdc},
@@ -65,7 +66,7 @@ use crate::{
         resolve_session_state,
     },
     kernel::{
-        Action, ActiveAddOptions, AddStatsPolicy, EagerSnapshot,
+        Action, ActiveAddOptions, AddStatsPolicy, EagerSnapshot, StructTypeExt as _,
         transaction::{CommitBuilder, CommitProperties, PROTOCOL},
     },
     table::config::TablePropertiesExt,
@@ -217,6 +218,58 @@ impl UpdateBuilder {
         self.custom_execute_handler = Some(handler);
         self
     }
+
+    /// Plan the update without executing it.
+    ///
+    /// Returns the files the update would rewrite, their size and the number of rows it would
+    /// update. Nothing is written or committed.
+    pub async fn dry_run(self) -> DeltaResult<DryRunMetrics> {
+        let snapshot = resolve_snapshot(&self.log_store, self.snapshot.clone(), true, None).await?;
+        PROTOCOL.check_append_only(&snapshot)?;
+        PROTOCOL.can_write_to(&snapshot)?;
+
+        if self.updates.is_empty() {
+            return Ok(DryRunMetrics {
+                num_affected_rows: Some(0),
+                ..Default::default()
+            });
+        }
+        let state = self.resolve_session(None)?;
+        let predicate = resolve_predicate(self.predicate, &state, &snapshot)?;
+        dry_run_where_matches(&state, &snapshot, self.log_store.clone(), predicate).await
+    }
+
+    fn resolve_session(&self, operation_id: Option<Uuid>) -> DeltaResult<SessionState> {
+        let (state, _) = resolve_session_state(
+            self.session.as_deref(),
+            self.session_fallback_policy,
+            || create_session().state(),
+            SessionResolveContext {
+                operation: "update",
+                table_uri: Some(self.log_store.root_url()),
+                cdc: false,
+            },
+        )?;
+        update_datafusion_session(&state, &self.log_store, operation_id)?;
+        state.ensure_log_store_registered(self.log_store.as_ref())?;
+        Ok(state)
+    }
+}
+
+/// Resolve `predicate` against the schema of the table, matching all rows if it is not set
+fn resolve_predicate(
+    predicate: Option<Expression>,
+    state: &SessionState,
+    snapshot: &EagerSnapshot,
+) -> DeltaResult<Expr> {
+    let Some(predicate) = predicate else {
+        return Ok(lit(true));
+    };
+    let scan_config = DeltaScanConfig::new_from_session(state);
+    let predicate_schema = scan_config
+        .table_schema(snapshot.table_configuration())?
+        .to_dfschema_ref()?;
+    Ok(predicate.resolve(state, predicate_schema)?)
 }
 
 #[derive(Clone, Debug)]
@@ -268,6 +321,7 @@ impl ExtensionPlanner for UpdateMetricExtensionPlanner {
     skip_all,
     fields(
         operation = "update",
+        operation_id = %operation_id,
         version = snapshot.version(),
         table_uri = %log_store.root_url(),
     )
@@ -365,15 +419,31 @@ async fn execute(
         })
         .try_collect()?;
 
-    let plan_updated = LogicalPlanBuilder::new(plan_with_metrics)
+    let mut plan_updated = LogicalPlanBuilder::new(plan_with_metrics)
         .project(expressions.clone())?
+        .build()?;
+
+    // Generated columns not assigned explicitly follow the new values of their base columns.
+    if gc_is_enabled(snapshot) {
+        let explicit_updates: Vec<_> = updates.keys().cloned().collect();
+        plan_updated = recompute_generated_columns(
+            session,
+            plan_updated,
+            &snapshot.schema().get_generated_columns()?,
+            &explicit_updates,
+            col(UPDATE_PREDICATE_COLNAME).is_true(),
+        )?;
+    }
+
+    let plan_updated = LogicalPlanBuilder::new(plan_updated)
         .drop_columns([UPDATE_PREDICATE_COLNAME])?
         .build()?;
 
     let physical_plan = session.create_physical_plan(&plan_updated).await?;
     let tracker = CDCTracker::new(files_scan.scan().clone(), plan_updated);
 
-    let writer_stats_config = WriterStatsConfig::from_config(snapshot.table_configuration());
+    let writer_stats_config = WriterStatsConfig::from_config(snapshot.table_configuration())
+        .with_multipart_config(log_store.config().options().multipart_config());
     let mut actions = write_execution_plan(
         Some(snapshot),
         session,
@@ -427,28 +497,23 @@ async fn execute(
 
     metrics.execution_time_ms = Instant::now().duration_since(exec_start).as_millis() as u64;
 
-    if let Ok(true) = should_write_cdc(snapshot) {
-        match tracker.collect() {
-            Ok(cdc_plan) => {
-                let cdc_exec = session.create_physical_plan(&cdc_plan).await?;
-                let cdc_actions = write_execution_plan_cdc(
-                    Some(snapshot),
-                    session,
-                    cdc_exec,
-                    table_partition_cols.to_vec(),
-                    log_store.object_store(Some(operation_id)),
-                    Some(snapshot.table_properties().target_file_size()),
-                    None,
-                    writer_properties,
-                    writer_stats_config,
-                )
-                .await?;
-                actions.extend(cdc_actions);
-            }
-            Err(err) => {
-                error!("Failed to collect CDC batches: {err:#?}");
-            }
-        };
+    // the commit must not succeed without the change data of the updated rows
+    if should_write_cdc(snapshot)? {
+        let cdc_plan = tracker.collect()?;
+        let cdc_exec = session.create_physical_plan(&cdc_plan).await?;
+        let cdc_actions = write_execution_plan_cdc(
+            Some(snapshot),
+            session,
+            cdc_exec,
+            table_partition_cols.to_vec(),
+            log_store.object_store(Some(operation_id)),
+            Some(snapshot.table_properties().target_file_size()),
+            None,
+            writer_properties,
+            writer_stats_config,
+        )
+        .await?;
+        actions.extend(cdc_actions);
     }
 
     Ok((actions, metrics))
@@ -470,18 +535,7 @@ impl std::future::IntoFuture for UpdateBuilder {
             let operation_id = this.get_operation_id();
             this.pre_execute(operation_id).await?;
 
-            let (state, _) = resolve_session_state(
-                this.session.as_deref(),
-                this.session_fallback_policy,
-                || create_session().state(),
-                SessionResolveContext {
-                    operation: "update",
-                    table_uri: Some(this.log_store.root_url()),
-                    cdc: false,
-                },
-            )?;
-            update_datafusion_session(&state, &this.log_store, Some(operation_id))?;
-            state.ensure_log_store_registered(this.log_store.as_ref())?;
+            let state = this.resolve_session(Some(operation_id))?;
 
             if this.updates.is_empty() {
                 return Ok((
@@ -490,18 +544,7 @@ impl std::future::IntoFuture for UpdateBuilder {
                 ));
             }
 
-            let predicate = this
-                .predicate
-                .map(|p| {
-                    let scan_config = DeltaScanConfig::new_from_session(&state);
-                    let predicate_schema = scan_config
-                        .table_schema(snapshot.table_configuration())?
-                        .to_dfschema_ref()?;
-                    p.resolve(&state, predicate_schema)
-                })
-                .transpose()?;
-
-            let predicate = predicate.unwrap_or(lit(true));
+            let predicate = resolve_predicate(this.predicate, &state, &snapshot)?;
             let operation = DeltaOperation::Update {
                 predicate: Some(fmt_expr_to_sql(&predicate)?),
             };
@@ -1,6 +1,6 @@
 use super::*;
 
-use crate::kernel::{Action, PrimitiveType, StructField, StructType};
+use crate::kernel::{Action, MetadataValue, PrimitiveType, StructField, StructType};
 use crate::kernel::{DataType as DeltaDataType, ProtocolInner};
 use crate::writer::test_utils::datafusion::{get_data, write_batch};
 use crate::writer::test_utils::{
@@ -216,6 +216,57 @@ async fn test_update_no_predicate() {
     assert_batches_sorted_eq!(&expected, &actual);
 }
 
+#[tokio::test]
+async fn test_update_dry_run() {
+    let schema = get_arrow_schema(&None);
+    let table = setup_table(None).await;
+
+    let batch = RecordBatch::try_new(
+        Arc::clone(&schema),
+        vec![
+            Arc::new(arrow::array::StringArray::from(vec!["A", "B", "A", "A"])),
+            Arc::new(arrow::array::Int32Array::from(vec![1, 10, 10, 100])),
+            Arc::new(arrow::array::StringArray::from(vec![
+                "2021-02-02",
+                "2021-02-02",
+                "2021-02-02",
+                "2021-02-02",
+            ])),
+        ],
+    )
+    .unwrap();
+
+    let table = write_batch(table, batch).await;
+    assert_eq!(table.version(), Some(1));
+
+    let dry_run = table
+        .clone()
+        .update()
+        .with_predicate(col("value").eq(lit(10)))
+        .with_update("modified", lit("2023-05-14"))
+        .dry_run()
+        .await
+        .unwrap();
+    assert_eq!(dry_run.files.len(), 1);
+    assert!(dry_run.num_bytes > 0);
+    assert_eq!(dry_run.num_affected_rows, Some(2));
+
+    let dry_run = table
+        .clone()
+        .update()
+        .with_predicate(col("value").eq(lit(1000)))
+        .with_update("modified", lit("2023-05-14"))
+        .dry_run()
+        .await
+        .unwrap();
+    assert!(dry_run.files.is_empty());
+    assert_eq!(dry_run.num_affected_rows, Some(0));
+
+    let mut table = table;
+    table.update_state().await.unwrap();
+    assert_eq!(table.version(), Some(1));
+}
+
 #[tokio::test]
 async fn test_update_non_partition() {
     let schema = get_arrow_schema(&None);
@@ -958,3 +1009,60 @@ async fn test_update_cdc_enabled_partitions() {
     "+-------+------+------------------+-----------------+",
     ], &batches }
 }
+
+#[tokio::test]
+async fn test_update_recomputes_generated_columns() {
+    let table = DeltaTable::new_in_memory()
+        .create()
+        .with_columns(vec![
+            StructField::new("id", DeltaDataType::INTEGER, true),
+            StructField::new("value", DeltaDataType::INTEGER, true),
+            StructField::new("doubled", DeltaDataType::INTEGER, true).with_metadata([(
+                "delta.generationExpression",
+                MetadataValue::String("value * 2".to_string()),
+            )]),
+        ])
+        .await
+        .unwrap();
+
+    let schema = Arc::new(Schema::new(vec![
+        Field::new("id", DataType::Int32, true),
+        Field::new("value", DataType::Int32, true),
+    ]));
+    let batch = RecordBatch::try_new(
+        schema,
+        vec![
+            Arc::new(Int32Array::from(vec![1, 2])),
+            Arc::new(Int32Array::from(vec![1, 2])),
+        ],
+    )
+    .unwrap();
+    let table = table.write(vec![batch]).await.unwrap();
+
+    let (table, metrics) = table
+        .update()
+        .with_predicate(col("id").eq(lit(1)))
+        .with_update("value", lit(10))
+        .await
+        .unwrap();
+    assert_eq!(metrics.num_updated_rows, 1);
+
+    let expected = vec![
+        "+----+-------+---------+",
+        "| id | value | doubled |",
+        "+----+-------+---------+",
+        "| 1  | 10    | 20      |",
+        "| 2  | 2     | 4       |",
+        "+----+-------+---------+",
+    ];
+    let actual = get_data(&table).await;
+    assert_batches_sorted_eq!(&expected, &actual);
+
+    // Explicit values for generated columns must still match their expression.
+    let result = table
+        .update()
+        .with_predicate(col("id").eq(lit(2)))
+        .with_update("doubled", lit(5))
+        .await;
+    assert!(result.is_err());
+}
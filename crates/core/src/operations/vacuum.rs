@@ -12,6 +12,21 @@
 //! When you run vacuum then you cannot use time travel to a version older than
 //! the specified retention period.
 //!
+//! Change data files in `_change_data` are never referenced by the table state, so the lite
+//! mode only deletes them if asked to with [`VacuumBuilder::with_expire_change_data`]. They
+//! are expired once they are older than the retention period, like orphaned files in full mode.
+//!
+//! Vacuum can be cancelled with [`VacuumBuilder::with_cancellation_token`]. Once the files to
+//! delete were announced with the `VACUUM START` commit, cancellation stops deleting further
+//! files and the `VACUUM END` commit records the files deleted so far.
+//!
+//! Long-running readers can protect the files of the version they read by registering a reader
+//! lease, an application transaction created with [`reader_lease`] and added to any commit, e.g.
+//! with [`CommitProperties::with_application_transaction`]. Once enabled with
+//! [`VacuumBuilder::with_reader_lease_duration`], vacuum keeps the files of every version pinned
+//! by a lease renewed within the lease duration, as if they were passed to
+//! [`VacuumBuilder::with_keep_versions`].
+//!
 //! Warning: Vacuum does not support partitioned tables on Windows. This is due
 //! to Windows not using unix style paths. See #682
 //!
@@ -30,13 +45,16 @@ use futures::future::{BoxFuture, ready};
 use futures::{StreamExt, TryStreamExt};
 use object_store::{Error, ObjectStore, path::Path};
 use serde::Serialize;
+use tokio_util::sync::CancellationToken;
 use tracing::*;
 
-use super::{CustomExecuteHandler, Operation};
+use super::progress::{OperationProgress, ProgressCallback, ProgressTracker};
+use super::{CustomExecuteHandler, Operation, ensure_not_cancelled, run_until_cancelled};
 use crate::errors::{DeltaResult, DeltaTableError};
 use crate::kernel::transaction::{CommitBuilder, CommitProperties};
 use crate::kernel::{
-    ActiveAddOptions, AddStatsPolicy, EagerSnapshot, TombstoneView, Version, resolve_snapshot,
+    ActiveAddOptions, AddStatsPolicy, EagerSnapshot, TombstoneView, Transaction, Version,
+    resolve_snapshot,
 };
 use crate::logstore::{LogStore, LogStoreRef};
 use crate::protocol::DeltaOperation;
@@ -44,6 +62,25 @@ use crate::table::config::TablePropertiesExt as _;
 use crate::table::state::DeltaTableState;
 use crate::{DeltaTable, DeltaTableConfig};
 
+/// Directory of the change data files, relative to the table root
+const CHANGE_DATA_DIR: &str = "_change_data";
+
+/// Prefix of the application ids of reader leases
+pub const READER_LEASE_APP_ID_PREFIX: &str = "delta-rs.readerLease.";
+
+/// Create a lease for the reader `reader_id` pinning the table `version`.
+///
+/// Committing the lease registers it, committing another lease of the same reader renews or
+/// moves it. The lease is stamped with the current time, so it expires once it was not renewed
+/// for the lease duration configured on the vacuum.
+pub fn reader_lease(reader_id: impl AsRef<str>, version: Version) -> Transaction {
+    Transaction::new_with_last_update(
+        format!("{READER_LEASE_APP_ID_PREFIX}{}", reader_id.as_ref()),
+        version as i64,
+        Some(Utc::now().timestamp_millis()),
+    )
+}
+
 /// Errors that can occur during vacuum
 #[derive(thiserror::Error, Debug)]
 enum VacuumError {
@@ -108,8 +145,16 @@ pub struct VacuumBuilder {
     dry_run: bool,
     /// Mode of vacuum that should be run
     mode: VacuumMode,
+    /// Delete change data files older than the retention period in lite mode
+    expire_change_data: bool,
     /// Override the source of time
     clock: Option<Arc<dyn Clock>>,
+    /// Keep the files of versions pinned by reader leases renewed within this duration
+    reader_lease_duration: Option<Duration>,
+    /// Stop the vacuum once cancelled
+    cancellation_token: Option<CancellationToken>,
+    /// Receives the progress of the deletion
+    progress_callback: Option<ProgressCallback>,
     /// Additional information to add to the commit
     commit_properties: CommitProperties,
     custom_execute_handler: Option<Arc<dyn CustomExecuteHandler>>,
@@ -165,7 +210,11 @@ impl VacuumBuilder {
             keep_versions: None,
             dry_run: false,
             mode: VacuumMode::Lite,
+            expire_change_data: false,
             clock: None,
+            reader_lease_duration: None,
+            cancellation_token: None,
+            progress_callback: None,
             commit_properties: CommitProperties::default(),
             custom_execute_handler: None,
         }
@@ -191,6 +240,16 @@ impl VacuumBuilder {
         self
     }
 
+    /// Delete change data files older than the retention period.
+    ///
+    /// Full mode always deletes them, as they are not referenced by the table state. This is
+    /// the only way to clean up change data files: optimize does not compact them, since the
+    /// `cdc` actions of past commits reference them by path.
+    pub fn with_expire_change_data(mut self, expire_change_data: bool) -> Self {
+        self.expire_change_data = expire_change_data;
+        self
+    }
+
     /// Only determine which files should be deleted
     pub fn with_dry_run(mut self, dry_run: bool) -> Self {
         self.dry_run = dry_run;
@@ -203,6 +262,14 @@ impl VacuumBuilder {
         self
     }
 
+    /// Keep the files of the versions pinned by reader leases renewed within `lease_duration`.
+    ///
+    /// Leases are registered with [`reader_lease`]. Leases without a timestamp never expire.
+    pub fn with_reader_lease_duration(mut self, lease_duration: Duration) -> Self {
+        self.reader_lease_duration = Some(lease_duration);
+        self
+    }
+
     /// add a time source for testing
     #[doc(hidden)]
     pub fn with_clock(mut self, clock: Arc<dyn Clock>) -> Self {
@@ -210,6 +277,24 @@ impl VacuumBuilder {
         self
     }
 
+    /// Stop the vacuum once `token` is cancelled.
+    ///
+    /// Before the `VACUUM START` commit, the vacuum fails with [`DeltaTableError::Cancelled`].
+    /// Afterwards no further files are deleted, and the metrics list the files deleted so far.
+    pub fn with_cancellation_token(mut self, token: CancellationToken) -> Self {
+        self.cancellation_token = Some(token);
+        self
+    }
+
+    /// Report the progress of the deletion of files to `callback`.
+    pub fn with_progress_callback(
+        mut self,
+        callback: impl Fn(&OperationProgress) + Send + Sync + 'static,
+    ) -> Self {
+        self.progress_callback = Some(ProgressCallback::new(callback));
+        self
+    }
+
     /// Additional metadata to be added to commit info
     pub fn with_commit_properties(mut self, commit_properties: CommitProperties) -> Self {
         self.commit_properties = commit_properties;
@@ -254,10 +339,23 @@ impl VacuumBuilder {
             None => Utc::now().timestamp_millis(),
         };
 
-        let keep_files = match &self.keep_versions {
+        let mut keep_versions = self.keep_versions.clone();
+        if let Some(lease_duration) = self.reader_lease_duration {
+            let leased_versions =
+                leased_versions(snapshot, &self.log_store, now_millis, lease_duration).await?;
+            if !leased_versions.is_empty() {
+                info!("Keeping the files of the versions {leased_versions:?} pinned by readers");
+                keep_versions
+                    .get_or_insert_with(Vec::new)
+                    .extend(leased_versions);
+            }
+        }
+
+        let keep_files = match &keep_versions {
             Some(versions) => {
                 let mut sorted_versions = versions.clone();
                 sorted_versions.sort();
+                sorted_versions.dedup();
                 let mut sorted_versions = sorted_versions.into_iter();
                 match sorted_versions.next() {
                     Some(initial_version) => {
@@ -333,6 +431,26 @@ impl VacuumBuilder {
             }
         }
 
+        if self.expire_change_data && self.mode == VacuumMode::Lite {
+            let object_store = self.log_store.object_store(None);
+            let prefix = Path::from(CHANGE_DATA_DIR);
+            let mut change_data_files = object_store.list(Some(&prefix));
+            while let Some(obj_meta) = change_data_files.next().await {
+                let obj_meta = obj_meta.map_err(DeltaTableError::from)?;
+                file_count += 1;
+                let file_age_millis = now_millis - obj_meta.last_modified.timestamp_millis();
+                if file_age_millis < retention_period.num_milliseconds() {
+                    continue;
+                }
+                debug!(
+                    "The change data file {:?} is expired and will be vacuumed",
+                    &obj_meta.location
+                );
+                files_to_delete.push(obj_meta.location);
+                file_sizes.push(obj_meta.size as i64);
+            }
+        }
+
         if self.mode == VacuumMode::Full {
             let object_store = self.log_store.object_store(None);
 
@@ -415,47 +533,76 @@ impl std::future::IntoFuture for VacuumBuilder {
 
     fn into_future(self) -> Self::IntoFuture {
         let this = self;
-        Box::pin(async move {
-            let snapshot =
-                resolve_snapshot(&this.log_store, this.snapshot.clone(), true, None).await?;
-            let plan = this.create_vacuum_plan(&snapshot).await?;
-
-            if this.dry_run {
-                return Ok((
-                    DeltaTable::new_with_state(this.log_store, DeltaTableState::new(snapshot)),
-                    VacuumMetrics {
-                        files_deleted: plan.files_to_delete.iter().map(|f| f.to_string()).collect(),
-                        dry_run: true,
-                    },
-                ));
-            }
-
-            let operation_id = this.get_operation_id();
-            this.pre_execute(operation_id).await?;
-
-            let result = plan
-                .execute(
-                    this.log_store.clone(),
-                    &snapshot,
-                    this.commit_properties.clone(),
-                    operation_id,
-                    this.get_custom_execute_handler(),
-                )
+        let span = info_span!(
+            "vacuum_operation",
+            operation = "vacuum",
+            operation_id = field::Empty,
+            table_uri = %this.log_store.root_url(),
+            mode = ?this.mode,
+            dry_run = this.dry_run,
+            version = field::Empty,
+            files_removed = field::Empty,
+            bytes_removed = field::Empty
+        );
+        Box::pin(
+            async move {
+                let snapshot =
+                    resolve_snapshot(&this.log_store, this.snapshot.clone(), true, None).await?;
+                let token = this.cancellation_token.as_ref();
+                let plan = run_until_cancelled(token, async {
+                    Ok(this.create_vacuum_plan(&snapshot).await?)
+                })
                 .await?;
+                Span::current()
+                    .record("version", snapshot.version())
+                    .record("files_removed", plan.files_to_delete.len())
+                    .record("bytes_removed", plan.file_sizes.iter().sum::<i64>());
+
+                if this.dry_run {
+                    return Ok((
+                        DeltaTable::new_with_state(this.log_store, DeltaTableState::new(snapshot)),
+                        VacuumMetrics {
+                            files_deleted: plan
+                                .files_to_delete
+                                .iter()
+                                .map(|f| f.to_string())
+                                .collect(),
+                            dry_run: true,
+                        },
+                    ));
+                }
 
-            this.post_execute(operation_id).await?;
-
-            Ok(match result {
-                Some((snapshot, metrics)) => (
-                    DeltaTable::new_with_state(this.log_store, snapshot),
-                    metrics,
-                ),
-                None => (
-                    DeltaTable::new_with_state(this.log_store, DeltaTableState::new(snapshot)),
-                    Default::default(),
-                ),
-            })
-        })
+                let operation_id = this.get_operation_id();
+                Span::current().record("operation_id", field::display(operation_id));
+                this.pre_execute(operation_id).await?;
+
+                let result = plan
+                    .execute(
+                        this.log_store.clone(),
+                        &snapshot,
+                        this.commit_properties.clone(),
+                        operation_id,
+                        this.get_custom_execute_handler(),
+                        this.cancellation_token.clone(),
+                        this.progress_callback.clone(),
+                    )
+                    .await?;
+
+                this.post_execute(operation_id).await?;
+
+                Ok(match result {
+                    Some((snapshot, metrics)) => (
+                        DeltaTable::new_with_state(this.log_store, snapshot),
+                        metrics,
+                    ),
+                    None => (
+                        DeltaTable::new_with_state(this.log_store, DeltaTableState::new(snapshot)),
+                        Default::default(),
+                    ),
+                })
+            }
+            .instrument(span),
+        )
     }
 }
 
@@ -475,6 +622,7 @@ struct VacuumPlan {
 
 impl VacuumPlan {
     /// Execute the vacuum plan and delete files from underlying storage
+    #[allow(clippy::too_many_arguments)]
     pub async fn execute(
         self,
         store: LogStoreRef,
@@ -482,6 +630,8 @@ impl VacuumPlan {
         mut commit_properties: CommitProperties,
         operation_id: uuid::Uuid,
         handle: Option<Arc<dyn CustomExecuteHandler>>,
+        cancellation_token: Option<CancellationToken>,
+        progress_callback: Option<ProgressCallback>,
     ) -> Result<Option<(DeltaTableState, VacuumMetrics)>, DeltaTableError> {
         if self.files_to_delete.is_empty() {
             return Ok(None);
@@ -493,10 +643,6 @@ impl VacuumPlan {
             default_retention_millis: self.default_retention_millis,
         };
 
-        let end_operation = DeltaOperation::VacuumEnd {
-            status: String::from("COMPLETED"), // Maybe this should be FAILED when vacuum has error during the files, not sure how to check for this
-        };
-
         let start_metrics = VacuumStartOperationMetrics {
             num_files_to_delete: self.files_to_delete.len() as i64,
             size_of_data_to_delete: self.file_sizes.iter().sum(),
@@ -505,11 +651,13 @@ impl VacuumPlan {
         // Begin VACUUM START COMMIT
         let mut start_props = CommitProperties::default();
         start_props.app_metadata = commit_properties.app_metadata.clone();
+        start_props.engine_info = commit_properties.engine_info.clone();
         start_props.app_metadata.insert(
             "operationMetrics".to_owned(),
             serde_json::to_value(start_metrics)?,
         );
 
+        ensure_not_cancelled(cancellation_token.as_ref())?;
         let last_commit = CommitBuilder::from(start_props)
             .with_operation_id(operation_id)
             .with_post_commit_hook_handler(handle.clone())
@@ -517,7 +665,16 @@ impl VacuumPlan {
             .await?;
         // Finish VACUUM START COMMIT
 
+        let num_files_to_delete = self.files_to_delete.len();
+        let mut progress = ProgressTracker::start(progress_callback, "VACUUM", num_files_to_delete);
         let locations = futures::stream::iter(self.files_to_delete)
+            .take_while(move |_| {
+                ready(
+                    !cancellation_token
+                        .as_ref()
+                        .is_some_and(CancellationToken::is_cancelled),
+                )
+            })
             .map(Result::Ok)
             .boxed();
 
@@ -529,9 +686,18 @@ impl VacuumPlan {
                 Err(Error::NotFound { path, .. }) => Ok(path),
                 Err(err) => Err(err),
             })
+            .inspect_ok(|_| progress.advance(1, 0))
             .try_collect::<Vec<_>>()
             .await?;
 
+        let end_operation = DeltaOperation::VacuumEnd {
+            status: String::from(if files_deleted.len() < num_files_to_delete {
+                "CANCELLED"
+            } else {
+                "COMPLETED" // Maybe this should be FAILED when vacuum has error during the files, not sure how to check for this
+            }),
+        };
+
         // Create end metadata
         let end_metrics = VacuumEndOperationMetrics {
             num_deleted_files: files_deleted.len() as i64,
@@ -583,7 +749,7 @@ fn is_hidden_directory(partition_columns: &[String], path: &Path) -> Result<bool
     let path_name = path.to_string();
     Ok((path_name.starts_with('.') || path_name.starts_with('_'))
         && !path_name.starts_with("_delta_index")
-        && !path_name.starts_with("_change_data")
+        && !path_name.starts_with(CHANGE_DATA_DIR)
         && !partition_columns
             .iter()
             .any(|partition_column| path_name.starts_with(partition_column)))
@@ -605,6 +771,25 @@ fn ok_to_delete(
     )
 }
 
+/// The table versions pinned by reader leases renewed within `lease_duration`
+async fn leased_versions(
+    snapshot: &EagerSnapshot,
+    log_store: &dyn LogStore,
+    now_millis: i64,
+    lease_duration: Duration,
+) -> DeltaResult<Vec<Version>> {
+    let transactions = snapshot.application_transactions(log_store).await?;
+    Ok(transactions
+        .into_values()
+        .filter(|txn| txn.app_id.starts_with(READER_LEASE_APP_ID_PREFIX))
+        .filter(|txn| match txn.last_updated {
+            Some(last_updated) => now_millis - last_updated < lease_duration.num_milliseconds(),
+            None => true,
+        })
+        .filter_map(|txn| Version::try_from(txn.version).ok())
+        .collect())
+}
+
 async fn collect_full_mode_tombstones(
     snapshot: &EagerSnapshot,
     tombstone_retention_timestamp: i64,
@@ -1108,6 +1293,127 @@ mod tests {
         Ok(())
     }
 
+    #[tokio::test]
+    async fn test_vacuum_lite_expires_change_data() -> DeltaResult<()> {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let table_path = temp_dir.path().to_str().unwrap();
+        let table = create_initialized_table(table_path, &[]).await;
+        let current_time = SystemTime::now();
+        let current_time_millis =
+            current_time.duration_since(UNIX_EPOCH).unwrap().as_millis() as i64;
+
+        std::fs::create_dir(temp_dir.path().join(CHANGE_DATA_DIR)).unwrap();
+        let stale_path = "_change_data/cdc-old.parquet";
+        std::fs::write(temp_dir.path().join(stale_path), b"stale change data").unwrap();
+        set_last_modified(
+            &temp_dir.path().join(stale_path),
+            current_time - StdDuration::from_secs(10),
+        );
+        let recent_path = "_change_data/cdc-recent.parquet";
+        std::fs::write(temp_dir.path().join(recent_path), b"recent change data").unwrap();
+
+        let vacuum = |expire_change_data| {
+            VacuumBuilder::new(
+                table.log_store(),
+                Some(table.snapshot().unwrap().snapshot.clone()),
+            )
+            .with_retention_period(Duration::seconds(5))
+            .with_dry_run(true)
+            .with_enforce_retention_duration(false)
+            .with_expire_change_data(expire_change_data)
+            .with_clock(Arc::new(MockClock::new(current_time_millis)))
+        };
+
+        let (_table, result) = vacuum(false).await?;
+        assert!(result.files_deleted.is_empty());
+
+        let (_table, result) = vacuum(true).await?;
+        assert_eq!(result.files_deleted, vec![stale_path.to_string()]);
+        Ok(())
+    }
+
+    #[cfg(feature = "datafusion")]
+    #[tokio::test]
+    async fn test_vacuum_keeps_files_of_reader_leases() -> DeltaResult<()> {
+        use crate::writer::test_utils::get_record_batch;
+
+        let table = DeltaTable::new_in_memory()
+            .write(vec![get_record_batch(None, false)])
+            .await?;
+        let leased_version = table.version().unwrap();
+        let leased_files: Vec<String> = table
+            .snapshot()?
+            .log_data()
+            .into_iter()
+            .map(|file| file.object_store_path().to_string())
+            .collect();
+        // the overwrite removes the files the reader still needs
+        let table = table
+            .write(vec![get_record_batch(None, false)])
+            .with_save_mode(SaveMode::Overwrite)
+            .with_commit_properties(
+                CommitProperties::default()
+                    .with_application_transaction(reader_lease("reader", leased_version)),
+            )
+            .await?;
+
+        let now_millis = Utc::now().timestamp_millis() + 1_000;
+        let vacuum = |lease_duration| {
+            VacuumBuilder::new(
+                table.log_store(),
+                Some(table.snapshot().unwrap().snapshot.clone()),
+            )
+            .with_retention_period(Duration::zero())
+            .with_dry_run(true)
+            .with_enforce_retention_duration(false)
+            .with_reader_lease_duration(lease_duration)
+            .with_clock(Arc::new(MockClock::new(now_millis)))
+        };
+
+        let (_table, result) = vacuum(Duration::hours(1)).await?;
+        assert!(result.files_deleted.is_empty());
+
+        // the lease expired, nothing protects the removed files anymore
+        let (_table, result) = vacuum(Duration::zero()).await?;
+        let mut files_deleted = result.files_deleted;
+        files_deleted.sort();
+        let mut leased_files = leased_files;
+        leased_files.sort();
+        assert_eq!(files_deleted, leased_files);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_vacuum_cancelled() -> DeltaResult<()> {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let table_path = temp_dir.path().to_str().unwrap();
+        let table = create_initialized_table(table_path, &[]).await;
+        let current_time = SystemTime::now();
+        let current_time_millis =
+            current_time.duration_since(UNIX_EPOCH).unwrap().as_millis() as i64;
+
+        std::fs::create_dir(temp_dir.path().join(CHANGE_DATA_DIR)).unwrap();
+        let stale_path = temp_dir.path().join("_change_data/cdc-old.parquet");
+        std::fs::write(&stale_path, b"stale change data").unwrap();
+        set_last_modified(&stale_path, current_time - StdDuration::from_secs(10));
+
+        let token = CancellationToken::new();
+        token.cancel();
+        let result = VacuumBuilder::new(
+            table.log_store(),
+            Some(table.snapshot().unwrap().snapshot.clone()),
+        )
+        .with_retention_period(Duration::seconds(5))
+        .with_enforce_retention_duration(false)
+        .with_expire_change_data(true)
+        .with_clock(Arc::new(MockClock::new(current_time_millis)))
+        .with_cancellation_token(token)
+        .await;
+        assert!(matches!(result, Err(DeltaTableError::Cancelled)));
+        assert!(stale_path.exists());
+        Ok(())
+    }
+
     /// Test that recently written uncommitted files are protected from deletion in Full mode
     /// This tests the fix for the race condition where concurrent writer's files could be deleted
     #[tokio::test]
@@ -0,0 +1,425 @@
+//! Verify the integrity of a checkpoint against the commits it summarizes.
+//!
+//! Readers trust checkpoints blindly: a checkpoint which lost add actions or carries a stale
+//! protocol silently changes the table for everyone loading it. This operation replays the JSON
+//! commits up to the checkpointed version and compares the reconstructed state, i.e. the active
+//! files, protocol and metadata, with the contents of the checkpoint.
+//!
+//! All commits up to the checkpointed version must still be present in the log.
+//!
+//! # Example
+//! ```rust ignore
+//! let table = open_table(Url::from_directory_path("/abs/path/to/table").unwrap()).await?;
+//! let (table, report) = table.verify_checkpoint(100).await?;
+//! for discrepancy in &report.discrepancies {
+//!     println!("{discrepancy}");
+//! }
+//! ````
+
+use std::collections::HashMap;
+use std::fmt;
+
+use arrow_json::LineDelimitedWriter;
+use futures::TryStreamExt;
+use futures::future::BoxFuture;
+use object_store::path::Path;
+use object_store::{ObjectMeta, ObjectStoreExt as _};
+use parquet::arrow::async_reader::{ParquetObjectReader, ParquetRecordBatchStreamBuilder};
+use serde::Serialize;
+use serde_json::{Deserializer, Value};
+
+use crate::DeltaTable;
+use crate::errors::{DeltaResult, DeltaTableError};
+use crate::kernel::{
+    Action, Add, DeletionVectorDescriptor, EagerSnapshot, Metadata, Protocol, Version,
+    resolve_snapshot,
+};
+use crate::logstore::{LogStore, LogStoreRef, ObjectStoreRef, get_actions};
+use crate::table::state::DeltaTableState;
+
+/// Verify a checkpoint against the commits it summarizes.
+/// See this module's documentation for more information
+pub struct VerifyCheckpointBuilder {
+    /// A snapshot of the table
+    snapshot: Option<EagerSnapshot>,
+    /// Delta object store for handling the log
+    log_store: LogStoreRef,
+    /// Version of the checkpoint to verify
+    version: Version,
+}
+
+/// A difference between a checkpoint and the state reconstructed from the commits
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase", tag = "type")]
+pub enum CheckpointDiscrepancy {
+    /// A file which is active according to the commits is missing from the checkpoint
+    MissingFile {
+        /// Path of the file
+        path: String,
+    },
+    /// The checkpoint contains a file which is not active according to the commits
+    UnexpectedFile {
+        /// Path of the file
+        path: String,
+    },
+    /// The size, partition values or deletion vector of a file differ from its add action
+    FileMismatch {
+        /// Path of the file
+        path: String,
+    },
+    /// The protocol in the checkpoint differs from the one in the commits
+    ProtocolMismatch {
+        /// Protocol according to the commits
+        expected: Option<Protocol>,
+        /// Protocol in the checkpoint
+        actual: Option<Protocol>,
+    },
+    /// The metadata in the checkpoint differs from the one in the commits
+    MetadataMismatch {
+        /// Metadata according to the commits
+        expected: Option<Box<Metadata>>,
+        /// Metadata in the checkpoint
+        actual: Option<Box<Metadata>>,
+    },
+}
+
+impl fmt::Display for CheckpointDiscrepancy {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::MissingFile { path } => write!(f, "file {path} is missing from the checkpoint"),
+            Self::UnexpectedFile { path } => {
+                write!(f, "file {path} is in the checkpoint but not active")
+            }
+            Self::FileMismatch { path } => {
+                write!(f, "file {path} differs between the checkpoint and the log")
+            }
+            Self::ProtocolMismatch { expected, actual } => write!(
+                f,
+                "protocol differs, expected {expected:?} but the checkpoint contains {actual:?}"
+            ),
+            Self::MetadataMismatch { .. } => {
+                write!(f, "metadata differs between the checkpoint and the log")
+            }
+        }
+    }
+}
+
+/// Result of verifying a checkpoint
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct CheckpointVerificationReport {
+    /// Version of the verified checkpoint
+    pub version: Version,
+    /// Files the checkpoint consists of, including sidecars
+    pub checkpoint_files: Vec<String>,
+    /// Differences between the checkpoint and the commits
+    pub discrepancies: Vec<CheckpointDiscrepancy>,
+}
+
+impl CheckpointVerificationReport {
+    /// Whether the checkpoint matches the commits
+    pub fn is_valid(&self) -> bool {
+        self.discrepancies.is_empty()
+    }
+}
+
+/// Table state derived from a sequence of actions
+#[derive(Default)]
+pub(super) struct ReplayedState {
+    pub(super) protocol: Option<Protocol>,
+    pub(super) metadata: Option<Metadata>,
+    pub(super) files: HashMap<String, Add>,
+}
+
+impl ReplayedState {
+    fn apply(&mut self, action: Action) {
+        match action {
+            Action::Protocol(protocol) => self.protocol = Some(protocol),
+            Action::Metadata(metadata) => self.metadata = Some(metadata),
+            Action::Add(add) => {
+                self.files
+                    .insert(file_key(&add.path, add.deletion_vector.as_ref()), add);
+            }
+            Action::Remove(remove) => {
+                self.files
+                    .remove(&file_key(&remove.path, remove.deletion_vector.as_ref()));
+            }
+            _ => {}
+        }
+    }
+}
+
+/// Files are identified by their path and deletion vector
+pub(super) fn file_key(path: &str, deletion_vector: Option<&DeletionVectorDescriptor>) -> String {
+    match deletion_vector {
+        Some(dv) => format!(
+            "{path}#{}{}@{}",
+            dv.storage_type,
+            dv.path_or_inline_dv,
+            dv.offset.unwrap_or_default()
+        ),
+        None => path.to_string(),
+    }
+}
+
+impl VerifyCheckpointBuilder {
+    /// Create a new [`VerifyCheckpointBuilder`]
+    pub(crate) fn new(
+        log_store: LogStoreRef,
+        snapshot: Option<EagerSnapshot>,
+        version: Version,
+    ) -> Self {
+        VerifyCheckpointBuilder {
+            snapshot,
+            log_store,
+            version,
+        }
+    }
+}
+
+/// Parse rows of a checkpoint file into actions, following sidecar references
+async fn read_checkpoint_file(
+    store: &ObjectStoreRef,
+    log_path: &Path,
+    meta: ObjectMeta,
+    state: &mut ReplayedState,
+    checkpoint_files: &mut Vec<String>,
+) -> DeltaResult<()> {
+    // Parquet checkpoints are converted to JSON rows, which share the format of commit files
+    let rows = if meta.location.as_ref().ends_with(".json") {
+        store.get(&meta.location).await?.bytes().await?.to_vec()
+    } else {
+        let reader = ParquetObjectReader::new(store.clone(), meta.location.clone())
+            .with_file_size(meta.size);
+        let mut batches = ParquetRecordBatchStreamBuilder::new(reader)
+            .await?
+            .build()?;
+        let mut rows = Vec::new();
+        let mut writer = LineDelimitedWriter::new(&mut rows);
+        while let Some(batch) = batches.try_next().await? {
+            writer.write(&batch)?;
+        }
+        writer.finish()?;
+        rows
+    };
+    checkpoint_files.push(meta.location.to_string());
+
+    let invalid = |err: serde_json::Error| DeltaTableError::InvalidData {
+        message: format!("Invalid action in checkpoint {}: {err}", meta.location),
+    };
+    let mut sidecars = Vec::new();
+    for row in Deserializer::from_slice(&rows).into_iter::<Value>() {
+        let row = row.map_err(invalid)?;
+        let Some(fields) = row.as_object() else {
+            continue;
+        };
+        if let Some(sidecar) = fields.get("sidecar") {
+            let Some(path) = sidecar.get("path").and_then(Value::as_str) else {
+                continue;
+            };
+            sidecars.push(log_path.clone().join("_sidecars").join(path));
+        } else if !fields.is_empty() && !fields.contains_key("checkpointMetadata") {
+            // tombstones in a checkpoint never refer to active files
+            match serde_json::from_value(row).map_err(invalid)? {
+                Action::Remove(_) => {}
+                action => state.apply(action),
+            }
+        }
+    }
+
+    for sidecar in sidecars {
+        let meta = store.head(&sidecar).await?;
+        Box::pin(read_checkpoint_file(
+            store,
+            log_path,
+            meta,
+            state,
+            checkpoint_files,
+        ))
+        .await?;
+    }
+    Ok(())
+}
+
+/// Reconstruct the table state at `version` from the checkpoint for that version
+pub(super) async fn read_checkpoint(
+    log_store: &dyn LogStore,
+    version: Version,
+) -> DeltaResult<(ReplayedState, Vec<String>)> {
+    let store = log_store.object_store(None);
+    let log_path = log_store.log_path();
+    let prefix = format!("{version:020}.checkpoint.");
+    let checkpoints: Vec<ObjectMeta> = store
+        .list(Some(log_path))
+        .try_filter(|meta| {
+            let name = meta.location.filename().unwrap_or_default();
+            futures::future::ready(
+                name.starts_with(&prefix)
+                    && (name.ends_with(".parquet") || name.ends_with(".json")),
+            )
+        })
+        .try_collect()
+        .await?;
+    if checkpoints.is_empty() {
+        return Err(DeltaTableError::Generic(format!(
+            "No checkpoint found for version {version}"
+        )));
+    }
+
+    let mut state = ReplayedState::default();
+    let mut checkpoint_files = Vec::new();
+    for meta in checkpoints {
+        read_checkpoint_file(&store, log_path, meta, &mut state, &mut checkpoint_files).await?;
+    }
+    Ok((state, checkpoint_files))
+}
+
+/// Reconstruct the table state at `version` by replaying all commits
+async fn replay_commits(log_store: &dyn LogStore, version: Version) -> DeltaResult<ReplayedState> {
+    let mut state = ReplayedState::default();
+    for commit in 0..=version {
+        let bytes = log_store.read_commit_entry(commit).await?.ok_or_else(|| {
+            DeltaTableError::Generic(format!(
+                "Commit {commit} is missing from the log, the checkpoint cannot be verified"
+            ))
+        })?;
+        for action in get_actions(commit, &bytes)? {
+            state.apply(action);
+        }
+    }
+    Ok(state)
+}
+
+fn compare(expected: ReplayedState, mut actual: ReplayedState) -> Vec<CheckpointDiscrepancy> {
+    let mut discrepancies = Vec::new();
+    if expected.protocol != actual.protocol {
+        discrepancies.push(CheckpointDiscrepancy::ProtocolMismatch {
+            expected: expected.protocol,
+            actual: actual.protocol,
+        });
+    }
+    if expected.metadata != actual.metadata {
+        discrepancies.push(CheckpointDiscrepancy::MetadataMismatch {
+            expected: expected.metadata.map(Box::new),
+            actual: actual.metadata.map(Box::new),
+        });
+    }
+
+    let mut files: Vec<_> = expected.files.into_iter().collect();
+    files.sort_by(|(a, _), (b, _)| a.cmp(b));
+    for (key, add) in files {
+        match actual.files.remove(&key) {
+            None => discrepancies.push(CheckpointDiscrepancy::MissingFile { path: add.path }),
+            Some(other)
+                if other.size != add.size
+                    || other.partition_values != add.partition_values
+                    || other.deletion_vector != add.deletion_vector =>
+            {
+                discrepancies.push(CheckpointDiscrepancy::FileMismatch { path: add.path })
+            }
+            Some(_) => {}
+        }
+    }
+    let mut unexpected: Vec<_> = actual.files.into_values().map(|add| add.path).collect();
+    unexpected.sort();
+    discrepancies.extend(
+        unexpected
+            .into_iter()
+            .map(|path| CheckpointDiscrepancy::UnexpectedFile { path }),
+    );
+    discrepancies
+}
+
+impl std::future::IntoFuture for VerifyCheckpointBuilder {
+    type Output = DeltaResult<(DeltaTable, CheckpointVerificationReport)>;
+    type IntoFuture = BoxFuture<'static, Self::Output>;
+
+    fn into_future(self) -> Self::IntoFuture {
+        let this = self;
+
+        Box::pin(async move {
+            let snapshot =
+                resolve_snapshot(this.log_store.as_ref(), this.snapshot, false, None).await?;
+
+            let (checkpoint, checkpoint_files) =
+                read_checkpoint(this.log_store.as_ref(), this.version).await?;
+            let replayed = replay_commits(this.log_store.as_ref(), this.version).await?;
+
+            Ok((
+                DeltaTable::new_with_state(this.log_store, DeltaTableState::new(snapshot)),
+                CheckpointVerificationReport {
+                    version: this.version,
+                    checkpoint_files,
+                    discrepancies: compare(replayed, checkpoint),
+                },
+            ))
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::kernel::transaction::{CommitBuilder, CommitProperties};
+    use crate::protocol::DeltaOperation;
+    use crate::writer::test_utils::get_delta_schema;
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn test_verify_checkpoint() -> DeltaResult<()> {
+        let table = DeltaTable::new_in_memory()
+            .create()
+            .with_columns(get_delta_schema().fields().cloned())
+            .await?;
+        let (table, _) = table.checkpoint().await?;
+
+        let add = Add {
+            path: "part-00000.parquet".to_string(),
+            size: 100,
+            modification_time: 0,
+            data_change: true,
+            ..Default::default()
+        };
+        let commit = CommitBuilder::from(CommitProperties::default())
+            .with_actions(vec![Action::Add(add)])
+            .build(
+                Some(table.snapshot()?),
+                table.log_store(),
+                DeltaOperation::custom("ADD"),
+            )
+            .await?;
+        let table = DeltaTable::new_with_state(table.log_store(), commit.snapshot);
+
+        let (table, report) = table.verify_checkpoint(0).await?;
+        assert!(report.is_valid(), "{:?}", report.discrepancies);
+        assert_eq!(
+            report.checkpoint_files,
+            vec!["_delta_log/00000000000000000000.checkpoint.parquet".to_string()]
+        );
+        assert!(table.clone().verify_checkpoint(1).await.is_err());
+
+        // a checkpoint for version 1 which lost the add action
+        let store = table.log_store().object_store(None);
+        store
+            .copy(
+                &Path::from("_delta_log/00000000000000000000.checkpoint.parquet"),
+                &Path::from("_delta_log/00000000000000000001.checkpoint.parquet"),
+            )
+            .await?;
+        let (table, report) = table.verify_checkpoint(1).await?;
+        assert_eq!(
+            report.discrepancies,
+            vec![CheckpointDiscrepancy::MissingFile {
+                path: "part-00000.parquet".to_string()
+            }]
+        );
+
+        store
+            .delete(&Path::from(
+                "_delta_log/00000000000000000001.checkpoint.parquet",
+            ))
+            .await?;
+        let (table, _) = table.checkpoint().await?;
+        let (_, report) = table.verify_checkpoint(1).await?;
+        assert!(report.is_valid(), "{:?}", report.discrepancies);
+        Ok(())
+    }
+}
@@ -0,0 +1,117 @@
+use datafusion::catalog::Session;
+use datafusion::logical_expr::{LogicalPlan, LogicalPlanBuilder, col};
+use datafusion::prelude::cast;
+use delta_kernel::engine::arrow_conversion::TryIntoArrow as _;
+use tracing::debug;
+
+use crate::{
+    DeltaResult, delta_datafusion::expr::parse_predicate_expression, kernel::EagerSnapshot,
+    kernel::allow_column_defaults_feature, table::ColumnDefault,
+};
+
+/// check if the writer version is able to write column defaults
+#[inline]
+pub fn cd_is_enabled(snapshot: &EagerSnapshot) -> bool {
+    snapshot
+        .protocol()
+        .writer_features()
+        .is_some_and(|features| features.contains(&allow_column_defaults_feature()))
+}
+
+/// Add the default value of every column with a default that is missing from the plan.
+///
+/// Columns provided by the source are left untouched, even when they contain nulls.
+pub fn with_column_defaults(
+    session: &dyn Session,
+    plan: LogicalPlan,
+    column_defaults: &[ColumnDefault],
+) -> DeltaResult<LogicalPlan> {
+    let missing: Vec<_> = column_defaults
+        .iter()
+        .filter(|default| {
+            plan.schema()
+                .field_with_unqualified_name(&default.name)
+                .is_err()
+        })
+        .collect();
+    if missing.is_empty() {
+        return Ok(plan);
+    }
+
+    let mut projection: Vec<_> = plan
+        .schema()
+        .fields()
+        .iter()
+        .map(|f| col(f.name()))
+        .collect();
+
+    for column_default in missing {
+        debug!(
+            "Filling missing column {} with its default.",
+            column_default.name
+        );
+        let expr = parse_predicate_expression(
+            plan.schema(),
+            column_default.get_default_expression(),
+            session,
+        )?;
+        projection.push(
+            cast(expr, (&column_default.data_type).try_into_arrow()?).alias(&column_default.name),
+        );
+    }
+
+    Ok(LogicalPlanBuilder::new(plan).project(projection)?.build()?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use arrow::array::Int32Array;
+    use arrow::datatypes::{DataType as ArrowDataType, Field as ArrowField, Schema};
+    use arrow_array::RecordBatch;
+    use datafusion::assert_batches_eq;
+    use datafusion::catalog::MemTable;
+    use datafusion::datasource::provider_as_source;
+    use datafusion::prelude::{DataFrame, SessionContext};
+    use delta_kernel::schema::DataType as KernelDataType;
+    use std::sync::Arc;
+
+    fn create_test_plan() -> LogicalPlan {
+        let schema = Arc::new(Schema::new(vec![ArrowField::new(
+            "id",
+            ArrowDataType::Int32,
+            false,
+        )]));
+        let batch =
+            RecordBatch::try_new(schema, vec![Arc::new(Int32Array::from(vec![1, 2]))]).unwrap();
+        let source = provider_as_source(Arc::new(
+            MemTable::try_new(batch.schema(), vec![vec![batch]]).unwrap(),
+        ));
+        LogicalPlanBuilder::scan("test", source, None)
+            .unwrap()
+            .build()
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_with_column_defaults_fills_missing_columns() {
+        let ctx = SessionContext::new();
+        let defaults = vec![
+            ColumnDefault::new("id", "0", &KernelDataType::INTEGER),
+            ColumnDefault::new("status", "'active'", &KernelDataType::STRING),
+        ];
+
+        let plan = with_column_defaults(&ctx.state(), create_test_plan(), &defaults).unwrap();
+        let batches = DataFrame::new(ctx.state(), plan).collect().await.unwrap();
+
+        let expected = [
+            "+----+--------+",
+            "| id | status |",
+            "+----+--------+",
+            "| 1  | active |",
+            "| 2  | active |",
+            "+----+--------+",
+        ];
+        assert_batches_eq!(&expected, &batches);
+    }
+}
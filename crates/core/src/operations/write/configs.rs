@@ -3,15 +3,20 @@ use delta_kernel::{
 };
 
 use crate::kernel::arrow::engine_ext::stats_table_properties;
+use crate::logstore::MultipartConfig;
 use crate::table::config::TablePropertiesExt as _;
 
-/// Configuration for the writer on how to collect stats
+/// Configuration for the writer on how to collect stats and upload data files
 #[derive(Clone)]
 pub struct WriterStatsConfig {
     /// Number of columns to collect stats for, idx based
     pub num_indexed_cols: DataSkippingNumIndexedCols,
     /// Optional list of columns which to collect stats for, takes precedende over num_index_cols
     pub stats_columns: Option<Vec<String>>,
+    /// Optional list of columns which to collect approximate distinct counts for
+    pub distinct_count_columns: Option<Vec<String>>,
+    /// Part size, concurrency and threshold of multipart uploads
+    pub multipart_config: MultipartConfig,
 }
 
 impl WriterStatsConfig {
@@ -23,9 +28,23 @@ impl WriterStatsConfig {
         Self {
             num_indexed_cols,
             stats_columns,
+            distinct_count_columns: None,
+            multipart_config: MultipartConfig::default(),
         }
     }
 
+    /// Collect approximate distinct counts for the top-level `columns` of the written files
+    pub fn with_distinct_count_columns(mut self, columns: Option<Vec<String>>) -> Self {
+        self.distinct_count_columns = columns;
+        self
+    }
+
+    /// Upload data files with the multipart settings of `config`
+    pub fn with_multipart_config(mut self, config: MultipartConfig) -> Self {
+        self.multipart_config = config;
+        self
+    }
+
     /// Derive writer statistics configuration from a table's [`TableConfiguration`].
     pub fn from_config(config: &TableConfiguration) -> Self {
         let properties = stats_table_properties(
@@ -33,12 +52,26 @@ impl WriterStatsConfig {
             config.table_properties(),
             config.column_mapping_mode(),
         );
+        let schema = config.logical_schema();
+        let distinct_count_columns = properties.distinct_count_columns().map(|columns| {
+            columns
+                .iter()
+                .filter_map(|column| schema.field(column))
+                .map(|field| {
+                    field
+                        .physical_name(config.column_mapping_mode())
+                        .to_string()
+                })
+                .collect()
+        });
         Self {
             num_indexed_cols: properties.num_indexed_cols(),
             stats_columns: properties
                 .data_skipping_stats_columns
                 .as_ref()
                 .map(|columns| columns.iter().map(|c| c.to_string()).collect()),
+            distinct_count_columns,
+            multipart_config: MultipartConfig::default(),
         }
     }
 }
@@ -101,4 +134,30 @@ mod tests {
         let config = WriterStatsConfig::from_config(&table_config);
         assert_eq!(config.stats_columns, Some(vec!["a".to_string()]));
     }
+
+    #[test]
+    fn from_config_resolves_distinct_count_columns() {
+        let logical_schema = StructType::try_new([
+            column_mapping_test_field("a", "col_a", 1),
+            column_mapping_test_field("b", "col_b", 2),
+        ])
+        .unwrap();
+        let table_config = build_test_table_configuration(
+            logical_schema,
+            vec![],
+            HashMap::from([
+                ("delta.columnMapping.mode".to_string(), "name".to_string()),
+                (
+                    "delta-rs.dataSkippingDistinctCountColumns".to_string(),
+                    "b, missing".to_string(),
+                ),
+            ]),
+        );
+
+        let config = WriterStatsConfig::from_config(&table_config);
+        assert_eq!(
+            config.distinct_count_columns,
+            Some(vec!["col_b".to_string()])
+        );
+    }
 }
@@ -1,4 +1,4 @@
-use std::num::NonZeroU64;
+use std::num::{NonZeroU64, NonZeroUsize};
 use std::sync::{Arc, OnceLock};
 
 use arrow::datatypes::Schema;
@@ -9,19 +9,24 @@ use datafusion::datasource::MemTable;
 use datafusion::execution::context::SessionContext;
 use datafusion::logical_expr::{Expr, col, lit, when};
 use datafusion::physical_expr::expressions::col as physical_col;
+use datafusion::physical_expr::{LexOrdering, PhysicalSortExpr};
 use datafusion::physical_plan::projection::ProjectionExec;
 use datafusion::physical_plan::repartition::RepartitionExec;
+use datafusion::physical_plan::sorts::sort::SortExec;
+use datafusion::physical_plan::sorts::sort_preserving_merge::SortPreservingMergeExec;
 use datafusion::physical_plan::{
     ExecutionPlan, ExecutionPlanProperties, Partitioning, SendableRecordBatchStream,
     execute_stream_partitioned,
 };
 use delta_kernel::engine::arrow_conversion::TryIntoKernel as _;
-use delta_kernel::table_configuration::TableConfiguration;
 use futures::{StreamExt as _, TryStreamExt as _};
 use object_store::prefix::PrefixStore;
+#[cfg(feature = "parquet-encryption")]
+use parquet::basic::Compression;
 use parquet::file::properties::WriterProperties;
 use tokio::sync::mpsc;
 use tokio::task::JoinSet;
+use tokio_util::task::AbortOnDropHandle;
 use tracing::log::*;
 use uuid::Uuid;
 
@@ -35,6 +40,10 @@ use crate::kernel::{Action, Add, AddCDCFile, EagerSnapshot, StructType, StructTy
 use crate::logstore::{LogStore, ObjectStoreRef};
 use crate::operations::cdc::CDC_COLUMN_NAME;
 use crate::operations::write::WriterStatsConfig;
+#[cfg(feature = "parquet-encryption")]
+use crate::parquet_encryption::encrypt_writer_properties;
+#[cfg(feature = "parquet-encryption")]
+use crate::parquet_utils::default_writer_properties;
 
 const DEFAULT_WRITER_BATCH_CHANNEL_SIZE: usize = 10;
 const WRITER_TASK_CLOSED_UNEXPECTEDLY_MSG: &str = "Writer task closed unexpectedly";
@@ -275,6 +284,9 @@ struct WriteSinkConfig {
     object_store: ObjectStoreRef,
     target_file_size: Option<NonZeroU64>,
     write_batch_size: Option<usize>,
+    max_rows_per_file: Option<NonZeroUsize>,
+    max_open_writers: Option<NonZeroUsize>,
+    sort_columns: Vec<String>,
     writer_properties: Option<WriterProperties>,
     writer_stats_config: WriterStatsConfig,
     column_mapping: Option<ColumnMappingState>,
@@ -377,6 +389,9 @@ pub(crate) async fn write_execution_plan(
         object_store,
         target_file_size,
         write_batch_size,
+        None,
+        None,
+        vec![],
         writer_properties,
         writer_stats_config,
         None,
@@ -396,6 +411,9 @@ pub(crate) async fn write_execution_plan_v2(
     object_store: ObjectStoreRef,
     target_file_size: Option<NonZeroU64>,
     write_batch_size: Option<usize>,
+    max_rows_per_file: Option<NonZeroUsize>,
+    max_open_writers: Option<NonZeroUsize>,
+    sort_columns: Vec<String>,
     writer_properties: Option<WriterProperties>,
     writer_stats_config: WriterStatsConfig,
     predicate: Option<Expr>,
@@ -436,11 +454,23 @@ pub(crate) async fn write_execution_plan_v2(
         plan = drop_internal_column(plan, insert_marker_column)?;
     }
 
+    #[cfg(feature = "parquet-encryption")]
+    let writer_properties = match snapshot.map(|snapshot| snapshot.load_config()) {
+        Some(config) if config.parquet_encryption.is_some() => Some(encrypt_writer_properties(
+            config,
+            writer_properties.unwrap_or_else(|| default_writer_properties(Compression::SNAPPY)),
+        )?),
+        _ => writer_properties,
+    };
+
     let sink_config = WriteSinkConfig {
         partition_columns,
         object_store,
         target_file_size,
         write_batch_size,
+        max_rows_per_file,
+        max_open_writers,
+        sort_columns,
         writer_properties,
         writer_stats_config,
         column_mapping: snapshot
@@ -480,7 +510,7 @@ fn drop_internal_column(
 pub(crate) async fn write_exec_plan(
     session: &dyn Session,
     log_store: &dyn LogStore,
-    table_config: &TableConfiguration,
+    snapshot: &EagerSnapshot,
     exec: Arc<dyn ExecutionPlan>,
     operation_id: Option<Uuid>,
     target_file_size: Option<NonZeroU64>,
@@ -496,13 +526,20 @@ pub(crate) async fn write_exec_plan(
             .into_writer_properties_builder()?
             .build(),
     };
-    let stats_config = WriterStatsConfig::from_config(table_config);
+    #[cfg(feature = "parquet-encryption")]
+    let writer_properties = encrypt_writer_properties(snapshot.load_config(), writer_properties)?;
+    let table_config = snapshot.table_configuration();
+    let stats_config = WriterStatsConfig::from_config(table_config)
+        .with_multipart_config(log_store.config().options().multipart_config());
     let object_store = log_store.object_store(operation_id);
     let sink_config = WriteSinkConfig {
         partition_columns: table_config.metadata().partition_columns().to_vec(),
         object_store,
         target_file_size,
         write_batch_size: None,
+        max_rows_per_file: None,
+        max_open_writers: None,
+        sort_columns: vec![],
         writer_properties: Some(writer_properties),
         writer_stats_config: stats_config,
         column_mapping: ColumnMappingState::from_table_config(table_config),
@@ -527,7 +564,7 @@ pub(crate) async fn write_streams(
     let worker_count = streams.len();
     let (tx, mut rx) = mpsc::channel::<RecordBatch>(channel_size());
 
-    let mut writer_handle = tokio::task::spawn(async move {
+    let mut writer_handle = AbortOnDropHandle::new(tokio::task::spawn(async move {
         let mut writer = DeltaWriter::new(object_store, config);
         let mut total_write_ms: u64 = 0;
         let mut rows_written: u64 = 0;
@@ -539,7 +576,7 @@ pub(crate) async fn write_streams(
         }
         let adds = writer.close().await?;
         Ok::<(Vec<Add>, u64, u64), DeltaTableError>((adds, total_write_ms, rows_written))
-    });
+    }));
 
     let mut worker_set = JoinSet::new();
     for mut stream in streams {
@@ -683,6 +720,44 @@ fn repartition_by_partition_columns(
     )?))
 }
 
+/// Translate logical sort column names to the physical names used by column-mapped tables.
+fn physical_sort_columns(
+    sort_columns: Vec<String>,
+    column_mapping: &Option<ColumnMappingState>,
+) -> DeltaResult<Vec<String>> {
+    match column_mapping {
+        Some(state) => state.physical_sort_columns(&sort_columns),
+        None => Ok(sort_columns),
+    }
+}
+
+/// Sorts the rows of each output stream by `sort_columns` so files are written in order.
+/// Unless `preserve_partitioning` is set, the sorted streams are merged into a single stream.
+/// Sorting spills to disk when the session's memory pool is exhausted.
+/// Returns the plan unchanged if no sort columns are given.
+fn sort_by_columns(
+    plan: Arc<dyn ExecutionPlan>,
+    sort_columns: &[String],
+    preserve_partitioning: bool,
+) -> DeltaResult<Arc<dyn ExecutionPlan>> {
+    let schema = plan.schema();
+    let sort_exprs = sort_columns
+        .iter()
+        .map(|name| physical_col(name, &schema).map(PhysicalSortExpr::new_default))
+        .collect::<Result<Vec<_>, _>>()?;
+    let Some(ordering) = LexOrdering::new(sort_exprs) else {
+        return Ok(plan);
+    };
+
+    let partition_count = plan.output_partitioning().partition_count();
+    let sort: Arc<dyn ExecutionPlan> =
+        Arc::new(SortExec::new(ordering.clone(), plan).with_preserve_partitioning(true));
+    if preserve_partitioning || partition_count <= 1 {
+        return Ok(sort);
+    }
+    Ok(Arc::new(SortPreservingMergeExec::new(ordering, sort)))
+}
+
 async fn write_data_plan(
     session: &dyn Session,
     plan: Arc<dyn ExecutionPlan>,
@@ -693,10 +768,14 @@ async fn write_data_plan(
         object_store,
         target_file_size,
         write_batch_size,
+        max_rows_per_file,
+        max_open_writers,
+        sort_columns,
         writer_properties,
         writer_stats_config,
         column_mapping,
     } = sink_config;
+    let sort_columns = physical_sort_columns(sort_columns, &column_mapping)?;
     let (plan, partition_columns, random_prefix_length) =
         apply_column_mapping_to_plan(plan, partition_columns, &column_mapping)?;
     let config = WriterConfig::new(
@@ -708,10 +787,15 @@ async fn write_data_plan(
         writer_stats_config.num_indexed_cols,
         writer_stats_config.stats_columns.clone(),
     )
-    .with_random_prefix_length(random_prefix_length);
+    .with_random_prefix_length(random_prefix_length)
+    .with_max_rows_per_file(max_rows_per_file)
+    .with_max_open_writers(max_open_writers)
+    .with_multipart_config(writer_stats_config.multipart_config.clone())
+    .with_distinct_count_columns(writer_stats_config.distinct_count_columns.clone());
 
     // For unpartitioned writes, centralize writer behavior through write_streams.
     if partition_columns.is_empty() {
+        let plan = sort_by_columns(plan, &sort_columns, false)?;
         let partition_streams = execute_stream_partitioned(plan, session.task_ctx())?;
         let scan_start = std::time::Instant::now();
         let (adds, stream_metrics) = write_streams(partition_streams, object_store, config).await?;
@@ -731,6 +815,7 @@ async fn write_data_plan(
     }
 
     let plan = repartition_by_partition_columns(plan, &partition_columns)?;
+    let plan = sort_by_columns(plan, &sort_columns, true)?;
     let partition_streams = execute_stream_partitioned(plan, session.task_ctx())?;
     let scan_start = std::time::Instant::now();
 
@@ -792,10 +877,14 @@ async fn write_cdc_plan(
         object_store,
         target_file_size,
         write_batch_size,
+        max_rows_per_file,
+        max_open_writers,
+        sort_columns,
         writer_properties,
         writer_stats_config,
         column_mapping,
     } = sink_config;
+    let sort_columns = physical_sort_columns(sort_columns, &column_mapping)?;
     let (plan, partition_columns, random_prefix_length) =
         apply_column_mapping_to_plan(plan, partition_columns, &column_mapping)?;
     let cdf_store = Arc::new(PrefixStore::new(object_store.clone(), "_change_data"));
@@ -825,7 +914,11 @@ async fn write_cdc_plan(
         writer_stats_config.num_indexed_cols,
         writer_stats_config.stats_columns.clone(),
     )
-    .with_random_prefix_length(random_prefix_length);
+    .with_random_prefix_length(random_prefix_length)
+    .with_max_rows_per_file(max_rows_per_file)
+    .with_max_open_writers(max_open_writers)
+    .with_multipart_config(writer_stats_config.multipart_config.clone())
+    .with_distinct_count_columns(writer_stats_config.distinct_count_columns.clone());
 
     let cdf_config = WriterConfig::new(
         cdf_schema.clone(),
@@ -836,14 +929,18 @@ async fn write_cdc_plan(
         writer_stats_config.num_indexed_cols,
         writer_stats_config.stats_columns.clone(),
     )
-    .with_random_prefix_length(random_prefix_length);
+    .with_random_prefix_length(random_prefix_length)
+    .with_max_rows_per_file(max_rows_per_file)
+    .with_max_open_writers(max_open_writers)
+    .with_multipart_config(writer_stats_config.multipart_config.clone());
 
     // Keep the previous single-writer fan-in path for unpartitioned tables.
     if partition_columns.is_empty() {
+        let plan = sort_by_columns(plan, &sort_columns, false)?;
         let (tx_normal, mut rx_normal) = mpsc::channel::<RecordBatch>(channel_size());
         let (tx_cdf, mut rx_cdf) = mpsc::channel::<RecordBatch>(channel_size());
 
-        let normal_writer_handle = tokio::task::spawn(async move {
+        let normal_writer_handle = AbortOnDropHandle::new(tokio::task::spawn(async move {
             let mut writer = DeltaWriter::new(object_store, normal_config);
             let mut total_write_ms: u64 = 0;
             while let Some(batch) = rx_normal.recv().await {
@@ -853,9 +950,9 @@ async fn write_cdc_plan(
             }
             let adds = writer.close().await?;
             Ok::<(Vec<Add>, u64), DeltaTableError>((adds, total_write_ms))
-        });
+        }));
 
-        let cdf_writer_handle = tokio::task::spawn(async move {
+        let cdf_writer_handle = AbortOnDropHandle::new(tokio::task::spawn(async move {
             let mut writer = DeltaWriter::new(cdf_store, cdf_config);
             let mut total_write_ms: u64 = 0;
             while let Some(batch) = rx_cdf.recv().await {
@@ -865,7 +962,7 @@ async fn write_cdc_plan(
             }
             let adds = writer.close().await?;
             Ok::<(Vec<Add>, u64), DeltaTableError>((adds, total_write_ms))
-        });
+        }));
 
         let partition_streams = execute_stream_partitioned(plan, session.task_ctx())?;
         let mut worker_handles = Vec::with_capacity(partition_streams.len());
@@ -876,7 +973,7 @@ async fn write_cdc_plan(
             let txc = tx_cdf.clone();
             let session_ctx = SessionContext::new();
 
-            let h = tokio::task::spawn(async move {
+            let h = AbortOnDropHandle::new(tokio::task::spawn(async move {
                 while let Some(maybe_batch) = partition_stream.next().await {
                     let batch = maybe_batch?;
 
@@ -934,7 +1031,7 @@ async fn write_cdc_plan(
                     }
                 }
                 Ok::<(), DeltaTableError>(())
-            });
+            }));
 
             worker_handles.push(h);
         }
@@ -1010,6 +1107,7 @@ async fn write_cdc_plan(
     }
 
     let plan = repartition_by_partition_columns(plan, &partition_columns)?;
+    let plan = sort_by_columns(plan, &sort_columns, true)?;
     let partition_streams = execute_stream_partitioned(plan, session.task_ctx())?;
     let scan_start = std::time::Instant::now();
     let mut join_set = JoinSet::new();
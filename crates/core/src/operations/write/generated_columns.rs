@@ -1,7 +1,7 @@
 use arrow_schema::Schema;
 use datafusion::catalog::Session;
-use datafusion::common::{Result, ScalarValue};
-use datafusion::logical_expr::{ExprSchemable, LogicalPlan, LogicalPlanBuilder, col, when};
+use datafusion::common::{Column, Result, ScalarValue};
+use datafusion::logical_expr::{Expr, ExprSchemable, LogicalPlan, LogicalPlanBuilder, col, when};
 use datafusion::prelude::DataFrame;
 use datafusion::prelude::{cast, lit};
 use delta_kernel::engine::arrow_conversion::TryIntoArrow as _;
@@ -92,6 +92,42 @@ pub fn with_generated_columns(
     LogicalPlanBuilder::new(plan).project(projection)?.build()
 }
 
+/// Recompute generated columns from the current values of the columns they derive from.
+///
+/// Only rows for which `condition` holds are recomputed, and columns listed in `skip` are
+/// left as provided so they can still be validated against their generation expression.
+pub(crate) fn recompute_generated_columns(
+    session: &dyn Session,
+    plan: LogicalPlan,
+    generated_cols: &[GeneratedColumn],
+    skip: &[String],
+    condition: Expr,
+) -> DeltaResult<LogicalPlan> {
+    let projection: Vec<_> = plan
+        .schema()
+        .fields()
+        .iter()
+        .map(|field| {
+            let name = field.name();
+            match generated_cols
+                .iter()
+                .find(|gc| gc.get_name() == name && !skip.contains(&gc.name))
+            {
+                Some(generated_col) => {
+                    let expr =
+                        parse_generated_column_expression(plan.schema(), generated_col, session)?;
+                    Ok(when(condition.clone(), expr)
+                        .otherwise(col(Column::from_name(name)))?
+                        .alias(name))
+                }
+                None => Ok(col(Column::from_name(name))),
+            }
+        })
+        .collect::<DeltaResult<_>>()?;
+
+    Ok(LogicalPlanBuilder::new(plan).project(projection)?.build()?)
+}
+
 /// Add generated column expressions to a dataframe
 pub fn add_missing_generated_columns(
     mut df: DataFrame,
@@ -138,10 +174,11 @@ pub fn add_generated_columns(
             parse_generated_column_expression(df.schema(), generated_col, session)?;
         let col_name = generated_col.get_name();
 
+        // Always recompute, so rows whose base columns changed in an update clause do not
+        // keep a stale value from the target.
         df = df.clone().with_column(
-            generated_col.get_name(),
-            when(col(col_name).is_null(), generation_expr)
-                .otherwise(col(col_name))?
+            col_name,
+            generation_expr
                 .cast_to(&((&generated_col.data_type).try_into_arrow()?), df.schema())?,
         )?
     }
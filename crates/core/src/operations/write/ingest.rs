@@ -0,0 +1,422 @@
+//! Parse CSV and newline delimited JSON files into record batches ready to be written.
+//!
+//! Schemas are inferred from the data unless one is provided explicitly. When writing into an
+//! existing table, columns which are also present in the table are parsed using the table's
+//! type, so e.g. integer columns are not widened to `Int64` and timestamp strings are read
+//! as timestamps. All other columns are normalized to Delta compatible types, schema
+//! evolution is then left to the [`WriteBuilder`](super::WriteBuilder).
+//!
+//! # Example
+//! ```rust ignore
+//! let table = table
+//!     .write_csv("data/orders.csv", CsvReadOptions::default())?
+//!     .with_save_mode(SaveMode::Append)
+//!     .await?;
+//! ```
+
+use std::fs::File;
+use std::io::{BufReader, Cursor, Read, Seek, SeekFrom};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use arrow::array::RecordBatch;
+use arrow::csv::reader::Format;
+use arrow_json::reader::infer_json_schema;
+use arrow_schema::{Schema, SchemaRef as ArrowSchemaRef};
+
+use crate::DeltaResult;
+use crate::kernel::schema::cast::normalize_for_delta;
+
+/// Default number of rows per parsed record batch
+pub const DEFAULT_BATCH_SIZE: usize = 8192;
+
+/// Where to read the data to ingest from
+pub enum IngestSource {
+    /// A file on the local filesystem
+    Path(PathBuf),
+    /// Any reader, e.g. a decompression stream or stdin.
+    ///
+    /// If no schema is provided, the data is buffered in memory to infer the schema.
+    Reader(Box<dyn Read + Send>),
+}
+
+impl IngestSource {
+    /// Create a source from any reader
+    pub fn from_reader(reader: impl Read + Send + 'static) -> Self {
+        Self::Reader(Box::new(reader))
+    }
+
+    fn open(self) -> DeltaResult<SeekableSource> {
+        Ok(match self {
+            Self::Path(path) => SeekableSource::File(BufReader::new(File::open(path)?)),
+            Self::Reader(mut reader) => {
+                let mut buffer = Vec::new();
+                reader.read_to_end(&mut buffer)?;
+                SeekableSource::Memory(Cursor::new(buffer))
+            }
+        })
+    }
+}
+
+impl From<PathBuf> for IngestSource {
+    fn from(path: PathBuf) -> Self {
+        Self::Path(path)
+    }
+}
+
+impl From<&Path> for IngestSource {
+    fn from(path: &Path) -> Self {
+        Self::Path(path.to_path_buf())
+    }
+}
+
+impl From<&str> for IngestSource {
+    fn from(path: &str) -> Self {
+        Self::Path(PathBuf::from(path))
+    }
+}
+
+impl From<String> for IngestSource {
+    fn from(path: String) -> Self {
+        Self::Path(PathBuf::from(path))
+    }
+}
+
+enum SeekableSource {
+    File(BufReader<File>),
+    Memory(Cursor<Vec<u8>>),
+}
+
+impl Read for SeekableSource {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        match self {
+            Self::File(reader) => reader.read(buf),
+            Self::Memory(reader) => reader.read(buf),
+        }
+    }
+}
+
+impl std::io::BufRead for SeekableSource {
+    fn fill_buf(&mut self) -> std::io::Result<&[u8]> {
+        match self {
+            Self::File(reader) => reader.fill_buf(),
+            Self::Memory(reader) => reader.fill_buf(),
+        }
+    }
+
+    fn consume(&mut self, amt: usize) {
+        match self {
+            Self::File(reader) => reader.consume(amt),
+            Self::Memory(reader) => reader.consume(amt),
+        }
+    }
+}
+
+impl Seek for SeekableSource {
+    fn seek(&mut self, pos: SeekFrom) -> std::io::Result<u64> {
+        match self {
+            Self::File(reader) => reader.seek(pos),
+            Self::Memory(reader) => reader.seek(pos),
+        }
+    }
+}
+
+/// Options controlling how CSV data is parsed
+#[derive(Debug, Clone)]
+pub struct CsvReadOptions {
+    schema: Option<ArrowSchemaRef>,
+    has_header: bool,
+    delimiter: u8,
+    quote: Option<u8>,
+    batch_size: usize,
+    max_infer_records: Option<usize>,
+}
+
+impl Default for CsvReadOptions {
+    fn default() -> Self {
+        Self {
+            schema: None,
+            has_header: true,
+            delimiter: b',',
+            quote: None,
+            batch_size: DEFAULT_BATCH_SIZE,
+            max_infer_records: Some(1000),
+        }
+    }
+}
+
+impl CsvReadOptions {
+    /// Parse the data with this schema instead of inferring one
+    pub fn with_schema(mut self, schema: ArrowSchemaRef) -> Self {
+        self.schema = Some(schema);
+        self
+    }
+
+    /// Whether the first line contains the column names, defaults to `true`
+    pub fn with_header(mut self, has_header: bool) -> Self {
+        self.has_header = has_header;
+        self
+    }
+
+    /// Field delimiter, defaults to `,`
+    pub fn with_delimiter(mut self, delimiter: u8) -> Self {
+        self.delimiter = delimiter;
+        self
+    }
+
+    /// Quote character, defaults to `"`
+    pub fn with_quote(mut self, quote: u8) -> Self {
+        self.quote = Some(quote);
+        self
+    }
+
+    /// Number of rows per record batch
+    pub fn with_batch_size(mut self, batch_size: usize) -> Self {
+        self.batch_size = batch_size;
+        self
+    }
+
+    /// Number of records used to infer the schema, `None` reads all records
+    pub fn with_max_infer_records(mut self, max_infer_records: Option<usize>) -> Self {
+        self.max_infer_records = max_infer_records;
+        self
+    }
+
+    fn format(&self) -> Format {
+        let format = Format::default()
+            .with_header(self.has_header)
+            .with_delimiter(self.delimiter);
+        match self.quote {
+            Some(quote) => format.with_quote(quote),
+            None => format,
+        }
+    }
+}
+
+/// Options controlling how newline delimited JSON data is parsed
+#[derive(Debug, Clone)]
+pub struct JsonReadOptions {
+    schema: Option<ArrowSchemaRef>,
+    batch_size: usize,
+    max_infer_records: Option<usize>,
+}
+
+impl Default for JsonReadOptions {
+    fn default() -> Self {
+        Self {
+            schema: None,
+            batch_size: DEFAULT_BATCH_SIZE,
+            max_infer_records: Some(1000),
+        }
+    }
+}
+
+impl JsonReadOptions {
+    /// Parse the data with this schema instead of inferring one
+    pub fn with_schema(mut self, schema: ArrowSchemaRef) -> Self {
+        self.schema = Some(schema);
+        self
+    }
+
+    /// Number of rows per record batch
+    pub fn with_batch_size(mut self, batch_size: usize) -> Self {
+        self.batch_size = batch_size;
+        self
+    }
+
+    /// Number of records used to infer the schema, `None` reads all records
+    pub fn with_max_infer_records(mut self, max_infer_records: Option<usize>) -> Self {
+        self.max_infer_records = max_infer_records;
+        self
+    }
+}
+
+/// Parse CSV data, coercing columns to the types of `table_schema` where present.
+pub(crate) fn read_csv(
+    source: IngestSource,
+    options: CsvReadOptions,
+    table_schema: Option<ArrowSchemaRef>,
+) -> DeltaResult<Vec<RecordBatch>> {
+    let (schema, reader) = match options.schema.clone() {
+        Some(schema) => (schema, source),
+        None => {
+            let mut reader = source.open()?;
+            let (inferred, _) = options
+                .format()
+                .infer_schema(&mut reader, options.max_infer_records)?;
+            reader.rewind()?;
+            (
+                coerce_schema(inferred, table_schema.as_ref()),
+                IngestSource::from_reader(reader),
+            )
+        }
+    };
+
+    let reader: Box<dyn Read + Send> = match reader {
+        IngestSource::Path(path) => Box::new(File::open(path)?),
+        IngestSource::Reader(reader) => reader,
+    };
+    let mut builder = arrow::csv::ReaderBuilder::new(schema.clone())
+        .with_header(options.has_header)
+        .with_delimiter(options.delimiter)
+        .with_batch_size(options.batch_size);
+    if let Some(quote) = options.quote {
+        builder = builder.with_quote(quote);
+    }
+    let batches = builder.build(reader)?.collect::<Result<Vec<_>, _>>()?;
+    Ok(non_empty(batches, schema))
+}
+
+/// Parse newline delimited JSON data, coercing columns to the types of `table_schema` where
+/// present.
+pub(crate) fn read_ndjson(
+    source: IngestSource,
+    options: JsonReadOptions,
+    table_schema: Option<ArrowSchemaRef>,
+) -> DeltaResult<Vec<RecordBatch>> {
+    let (schema, reader) = match options.schema {
+        Some(schema) => {
+            let reader: Box<dyn std::io::BufRead + Send> = match source {
+                IngestSource::Path(path) => Box::new(BufReader::new(File::open(path)?)),
+                IngestSource::Reader(reader) => Box::new(BufReader::new(reader)),
+            };
+            (schema, reader)
+        }
+        None => {
+            let mut reader = source.open()?;
+            let (inferred, _) = infer_json_schema(&mut reader, options.max_infer_records)?;
+            reader.rewind()?;
+            let reader: Box<dyn std::io::BufRead + Send> = Box::new(reader);
+            (coerce_schema(inferred, table_schema.as_ref()), reader)
+        }
+    };
+
+    let batches = arrow_json::ReaderBuilder::new(schema.clone())
+        .with_batch_size(options.batch_size)
+        .build(reader)?
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(non_empty(batches, schema))
+}
+
+/// Use the table's definition for every inferred column the table already has, and normalize
+/// the remaining columns for Delta.
+fn coerce_schema(inferred: Schema, table_schema: Option<&ArrowSchemaRef>) -> ArrowSchemaRef {
+    let inferred = normalize_for_delta(&Arc::new(inferred));
+    let Some(table_schema) = table_schema else {
+        return inferred;
+    };
+    let fields: Vec<_> = inferred
+        .fields()
+        .iter()
+        .map(|field| match table_schema.field_with_name(field.name()) {
+            Ok(table_field) => Arc::new(table_field.clone()),
+            Err(_) => field.clone(),
+        })
+        .collect();
+    Arc::new(Schema::new(fields))
+}
+
+/// Empty inputs still produce a batch, so the schema is known to the write.
+fn non_empty(batches: Vec<RecordBatch>, schema: ArrowSchemaRef) -> Vec<RecordBatch> {
+    if batches.is_empty() {
+        vec![RecordBatch::new_empty(schema)]
+    } else {
+        batches
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use arrow_schema::{DataType, Field, TimeUnit};
+
+    const CSV_DATA: &str = "id,value,modified\n1,a,2021-02-01T00:00:00\n2,b,2021-02-02T00:00:00\n";
+
+    #[test]
+    fn test_read_csv_infers_schema() {
+        let batches = read_csv(
+            IngestSource::from_reader(Cursor::new(CSV_DATA)),
+            CsvReadOptions::default(),
+            None,
+        )
+        .unwrap();
+
+        assert_eq!(batches.len(), 1);
+        assert_eq!(batches[0].num_rows(), 2);
+        let schema = batches[0].schema();
+        assert_eq!(schema.field(0).data_type(), &DataType::Int64);
+        assert_eq!(schema.field(1).data_type(), &DataType::Utf8);
+        assert_eq!(
+            schema.field(2).data_type(),
+            &DataType::Timestamp(TimeUnit::Microsecond, None)
+        );
+    }
+
+    #[test]
+    fn test_read_csv_coerces_to_table_schema() {
+        let table_schema = Arc::new(Schema::new(vec![
+            Field::new("id", DataType::Int32, true),
+            Field::new("value", DataType::Utf8, true),
+        ]));
+        let batches = read_csv(
+            IngestSource::from_reader(Cursor::new(CSV_DATA)),
+            CsvReadOptions::default(),
+            Some(table_schema),
+        )
+        .unwrap();
+
+        let schema = batches[0].schema();
+        assert_eq!(schema.field(0).data_type(), &DataType::Int32);
+        assert_eq!(schema.field(2).name(), "modified");
+    }
+
+    #[test]
+    fn test_read_csv_with_options() {
+        let schema = Arc::new(Schema::new(vec![
+            Field::new("id", DataType::Int32, true),
+            Field::new("value", DataType::Utf8, true),
+        ]));
+        let batches = read_csv(
+            IngestSource::from_reader(Cursor::new("1;a\n2;b\n3;c\n")),
+            CsvReadOptions::default()
+                .with_schema(schema.clone())
+                .with_header(false)
+                .with_delimiter(b';')
+                .with_batch_size(2),
+            None,
+        )
+        .unwrap();
+
+        assert_eq!(batches.len(), 2);
+        assert_eq!(batches[0].schema(), schema);
+    }
+
+    #[test]
+    fn test_read_ndjson() {
+        let data = "{\"id\": 1, \"value\": \"a\"}\n{\"id\": 2, \"value\": null}\n";
+        let table_schema = Arc::new(Schema::new(vec![Field::new("id", DataType::Int32, true)]));
+        let batches = read_ndjson(
+            IngestSource::from_reader(Cursor::new(data)),
+            JsonReadOptions::default(),
+            Some(table_schema),
+        )
+        .unwrap();
+
+        assert_eq!(batches[0].num_rows(), 2);
+        let schema = batches[0].schema();
+        assert_eq!(schema.field(0).data_type(), &DataType::Int32);
+        assert_eq!(schema.field(1).data_type(), &DataType::Utf8);
+    }
+
+    #[test]
+    fn test_read_empty_input() {
+        let batches = read_ndjson(
+            IngestSource::from_reader(Cursor::new("")),
+            JsonReadOptions::default(),
+            None,
+        )
+        .unwrap();
+        assert_eq!(batches.len(), 1);
+        assert_eq!(batches[0].num_rows(), 0);
+    }
+}
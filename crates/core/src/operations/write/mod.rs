@@ -26,7 +26,7 @@
 //! ````
 
 use std::collections::HashMap;
-use std::num::NonZeroU64;
+use std::num::{NonZeroU64, NonZeroUsize};
 use std::str::FromStr;
 use std::sync::Arc;
 use std::time::Instant;
@@ -40,9 +40,10 @@ use datafusion::logical_expr::{LogicalPlan, LogicalPlanBuilder, UNNAMED_TABLE};
 use delta_kernel::engine::arrow_conversion::TryIntoKernel as _;
 use delta_kernel::table_features::ColumnMappingMode;
 use futures::future::BoxFuture;
+use parquet::basic::Compression;
 use parquet::file::properties::WriterProperties;
 use serde::{Deserialize, Serialize};
-use tracing::Instrument;
+use tracing::{Instrument, Span, field};
 use url::Url;
 
 pub use self::configs::WriterStatsConfig;
@@ -58,16 +59,21 @@ use crate::delta_datafusion::{
     resolve_session_state, update_datafusion_session,
 };
 use crate::errors::{DeltaResult, DeltaTableError};
-use crate::kernel::schema::cast::normalize_for_delta;
+use crate::kernel::schema::cast::{CoercionPolicy, normalize_for_delta};
 use crate::kernel::transaction::{CommitBuilder, CommitProperties, PROTOCOL, TableReference};
 use crate::kernel::{Action, EagerSnapshot, StructType};
 use crate::logstore::LogStoreRef;
+use crate::parquet_utils::{
+    ColumnWriterProperties, apply_column_writer_properties, default_writer_properties,
+};
 use crate::protocol::{DeltaOperation, SaveMode};
 
+pub(crate) mod column_defaults;
 /// Configuration types controlling how data and statistics are written.
 pub mod configs;
 pub(crate) mod execution;
 pub(crate) mod generated_columns;
+pub mod ingest;
 pub(crate) mod metrics;
 mod plan;
 pub(crate) mod schema_evolution;
@@ -91,6 +97,9 @@ pub(crate) enum WriteError {
 
     #[error("Partition column(s) not found in write schema: {}", columns.join(", "))]
     MissingPartitionColumns { columns: Vec<String> },
+
+    #[error("Sort column(s) not found in write schema: {}", columns.join(", "))]
+    MissingSortColumns { columns: Vec<String> },
 }
 
 impl From<WriteError> for DeltaTableError {
@@ -146,12 +155,22 @@ pub struct WriteBuilder {
     target_file_size: Option<Option<NonZeroU64>>,
     /// Number of records to be written in single batch to underlying writer
     write_batch_size: Option<usize>,
+    /// Maximum number of rows written to a single data file
+    max_rows_per_file: Option<NonZeroUsize>,
+    /// Columns by which rows are sorted within each data file
+    sort_columns: Vec<String>,
+    /// Maximum number of partition files written concurrently by each writer
+    max_open_partition_writers: Option<NonZeroUsize>,
+    /// Sort the input by the partition columns before writing
+    sort_by_partition: bool,
     /// whether to overwrite the schema or to merge it. None means to fail on schmema drift
     schema_mode: Option<SchemaMode>,
-    /// how to handle cast failures, either return NULL (safe=true) or return ERR (safe=false)
-    safe_cast: bool,
+    /// how incoming types are reconciled with the table schema
+    coercion_policy: CoercionPolicy,
     /// Parquet writer properties
     writer_properties: Option<WriterProperties>,
+    /// Parquet writer properties overridden for individual columns
+    column_writer_properties: HashMap<String, ColumnWriterProperties>,
     /// Additional information to add to the commit
     commit_properties: CommitProperties,
     /// Name of the table, only used when table doesn't exist yet
@@ -201,9 +220,14 @@ impl WriteBuilder {
             predicate: None,
             target_file_size: None,
             write_batch_size: None,
-            safe_cast: false,
+            max_rows_per_file: None,
+            sort_columns: Vec::new(),
+            max_open_partition_writers: None,
+            sort_by_partition: false,
+            coercion_policy: CoercionPolicy::default(),
             schema_mode: None,
             writer_properties: None,
+            column_writer_properties: HashMap::new(),
             commit_properties: CommitProperties::default(),
             name: None,
             description: None,
@@ -288,10 +312,60 @@ impl WriteBuilder {
         self
     }
 
+    /// Specify the maximum number of rows written to a single data file.
+    ///
+    /// Files are split once they reach either this limit or the target file size.
+    pub fn with_max_rows_per_file(mut self, max_rows_per_file: NonZeroUsize) -> Self {
+        self.max_rows_per_file = Some(max_rows_per_file);
+        self
+    }
+
+    /// Sort the rows of every written data file by the given columns in ascending order.
+    ///
+    /// Sorting clusters similar values within files, which tightens the min/max statistics
+    /// used for file skipping. Large inputs are spilled to disk while sorting.
+    pub fn with_sort_order(mut self, columns: impl IntoIterator<Item = impl Into<String>>) -> Self {
+        self.sort_columns = columns.into_iter().map(Into::into).collect();
+        self
+    }
+
+    /// Limit the number of partition files each writer keeps open at the same time.
+    ///
+    /// When writing to many partitions, the file of the least recently written partition is
+    /// finished once the limit is reached, which bounds the memory used for buffering. Without
+    /// [`with_partition_sort`](Self::with_partition_sort) this may produce several files per
+    /// partition if the input is not grouped by partition.
+    pub fn with_max_open_partition_writers(mut self, max_open_writers: NonZeroUsize) -> Self {
+        self.max_open_partition_writers = Some(max_open_writers);
+        self
+    }
+
+    /// Sort the input by the partition columns before writing.
+    ///
+    /// Every partition is then written in one go, so limiting the open partition writers does
+    /// not split partitions into additional files. Large inputs are spilled to disk while
+    /// sorting.
+    pub fn with_partition_sort(mut self, sort_by_partition: bool) -> Self {
+        self.sort_by_partition = sort_by_partition;
+        self
+    }
+
     /// Specify the safety of the casting operation
     /// how to handle cast failures, either return NULL (safe=true) or return ERR (safe=false)
+    ///
+    /// This is a shorthand for [`CoercionPolicy::LossyCast`] and [`CoercionPolicy::SafeCast`].
     pub fn with_cast_safety(mut self, safe: bool) -> Self {
-        self.safe_cast = safe;
+        self.coercion_policy = if safe {
+            CoercionPolicy::LossyCast
+        } else {
+            CoercionPolicy::SafeCast
+        };
+        self
+    }
+
+    /// Specify how the types of the input are reconciled with the table schema
+    pub fn with_coercion_policy(mut self, coercion_policy: CoercionPolicy) -> Self {
+        self.coercion_policy = coercion_policy;
         self
     }
 
@@ -301,6 +375,35 @@ impl WriteBuilder {
         self
     }
 
+    /// Override the writer properties of a single column.
+    ///
+    /// The settings take precedence over the [writer properties](Self::with_writer_properties).
+    /// Nested columns are identified by their field names separated by dots.
+    pub fn with_column_writer_properties(
+        mut self,
+        column: impl Into<String>,
+        properties: ColumnWriterProperties,
+    ) -> Self {
+        self.column_writer_properties
+            .insert(column.into(), properties);
+        self
+    }
+
+    /// Writer properties with the column settings applied
+    fn resolved_writer_properties(&self) -> Option<WriterProperties> {
+        if self.column_writer_properties.is_empty() {
+            return self.writer_properties.clone();
+        }
+        let writer_properties = self
+            .writer_properties
+            .clone()
+            .unwrap_or_else(|| default_writer_properties(Compression::SNAPPY));
+        Some(apply_column_writer_properties(
+            writer_properties,
+            &self.column_writer_properties,
+        ))
+    }
+
     /// Additional metadata to be added to commit info
     pub fn with_commit_properties(mut self, commit_properties: CommitProperties) -> Self {
         self.commit_properties = commit_properties;
@@ -474,6 +577,7 @@ impl std::future::IntoFuture for WriteBuilder {
             async move {
                 // Runs pre execution handler.
                 let operation_id = this.get_operation_id();
+                Span::current().record("operation_id", field::display(operation_id));
                 this.pre_execute(operation_id).await?;
 
                 let mut metrics = WriteMetrics::default();
@@ -509,12 +613,12 @@ impl std::future::IntoFuture for WriteBuilder {
                     source,
                     mode: this.mode,
                     schema_mode: this.schema_mode,
-                    safe_cast: this.safe_cast,
+                    coercion_policy: this.coercion_policy,
                     partition_columns: partition_columns.clone(),
                     predicate: this.predicate,
                     target_file_size: this.target_file_size,
                     write_batch_size: this.write_batch_size,
-                    writer_properties: this.writer_properties.clone(),
+                    writer_properties: this.resolved_writer_properties(),
                     configuration: &this.configuration,
                 })?;
 
@@ -561,6 +665,20 @@ impl std::future::IntoFuture for WriteBuilder {
                 let (sink_plan, contains_cdc, insert_marker_column) =
                     overwrite_plan.build_sink_plan()?;
                 let source_plan = session.create_physical_plan(&sink_plan).await?;
+                plan::validate_sort_columns_in_schema(
+                    &this.sort_columns,
+                    source_plan.schema().as_ref(),
+                )?;
+
+                let sort_columns = if this.sort_by_partition {
+                    partition_columns
+                        .iter()
+                        .cloned()
+                        .chain(this.sort_columns)
+                        .collect()
+                } else {
+                    this.sort_columns
+                };
 
                 // Here we need to validate if the new data conforms to a predicate if one is provided
                 let (add_actions, _) = write_execution_plan_v2(
@@ -571,8 +689,13 @@ impl std::future::IntoFuture for WriteBuilder {
                     this.log_store.object_store(Some(operation_id)).clone(),
                     target_file_size,
                     write_batch_size,
+                    this.max_rows_per_file,
+                    this.max_open_partition_writers,
+                    sort_columns,
                     writer_properties,
-                    writer_stats_config,
+                    writer_stats_config.with_multipart_config(
+                        this.log_store.config().options().multipart_config(),
+                    ),
                     exact_validation,
                     contains_cdc,
                     insert_marker_column,
@@ -626,6 +749,8 @@ impl std::future::IntoFuture for WriteBuilder {
                     )
                     .await?;
 
+                Span::current().record("version", commit.version);
+
                 if let Some(handler) = this.custom_execute_handler {
                     handler.post_execute(&this.log_store, operation_id).await?;
                 }
@@ -635,8 +760,10 @@ impl std::future::IntoFuture for WriteBuilder {
             .instrument(tracing::info_span!(
                 "write_operation",
                 operation = "write",
+                operation_id = field::Empty,
                 mode = ?mode,
-                table_uri = %table_uri
+                table_uri = %table_uri,
+                version = field::Empty
             )),
         )
     }
@@ -801,6 +928,36 @@ mod tests {
             .expect_err("Remove action is included when Delta table is append-only. Should error");
     }
 
+    #[tokio::test]
+    async fn test_write_distinct_count_sketches() {
+        let table = DeltaTable::new_in_memory()
+            .create()
+            .with_columns(get_delta_schema().fields().cloned())
+            .with_partition_columns(["modified"])
+            .with_configuration_property(
+                TableProperty::DataSkippingDistinctCountColumns,
+                Some("id, value"),
+            )
+            .await
+            .unwrap();
+        let table = table
+            .write(vec![get_record_batch(None, false)])
+            .await
+            .unwrap();
+
+        let snapshot = table.snapshot().unwrap();
+        let log_data = snapshot.log_data();
+        assert_eq!(log_data.num_files(), 2);
+        for file in log_data.iter() {
+            let columns = file.distinct_count_sketches().into_keys().sorted();
+            assert_eq!(columns.collect_vec(), vec!["id", "value"]);
+        }
+        assert_eq!(
+            log_data.distinct_counts(),
+            HashMap::from([("id".to_string(), 2), ("value".to_string(), 11)])
+        );
+    }
+
     #[tokio::test]
     async fn test_create_write() {
         let table_schema = get_delta_schema();
@@ -1818,7 +1975,12 @@ mod tests {
             .write(vec![new_batch])
             .with_save_mode(SaveMode::Append)
             .await;
-        assert!(table.is_err());
+        let err = table.expect_err("writing a different schema should fail");
+        assert!(
+            err.to_string()
+                .contains("removed field id, removed field value, removed field modified, added field inserted_by of type string"),
+            "{err}"
+        );
     }
 
     #[tokio::test]
@@ -3866,4 +4028,322 @@ mod tests {
         let total_rows: usize = batches.iter().map(|b| b.num_rows()).sum();
         assert_eq!(total_rows, 2);
     }
+
+    #[tokio::test]
+    async fn test_write_with_column_writer_properties() -> TestResult {
+        use parquet::arrow::async_reader::{ParquetObjectReader, ParquetRecordBatchStreamBuilder};
+        use parquet::basic::ZstdLevel;
+
+        let batch = get_record_batch(None, false);
+        let table = DeltaTable::new_in_memory()
+            .write(vec![batch])
+            .with_column_writer_properties(
+                "value",
+                ColumnWriterProperties::new()
+                    .with_compression(Compression::ZSTD(ZstdLevel::try_new(9)?))
+                    .with_dictionary_enabled(false),
+            )
+            .with_column_writer_properties(
+                "id",
+                ColumnWriterProperties::new().with_bloom_filter_fpp(0.01),
+            )
+            .await?;
+
+        for path in table.get_files_by_partitions(&[]).await? {
+            let reader = ParquetObjectReader::new(table.log_store().object_store(None), path);
+            let builder = ParquetRecordBatchStreamBuilder::new(reader).await?;
+            let row_group = builder.metadata().row_group(0);
+            let column = |name: &str| {
+                row_group
+                    .columns()
+                    .iter()
+                    .find(|column| column.column_path().string() == name)
+                    .unwrap()
+            };
+            assert!(matches!(
+                column("value").compression(),
+                Compression::ZSTD(_)
+            ));
+            assert_eq!(column("modified").compression(), Compression::SNAPPY);
+            assert!(column("id").bloom_filter_offset().is_some());
+            assert!(column("modified").bloom_filter_offset().is_none());
+        }
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_write_with_max_rows_per_file() -> TestResult {
+        let batch = get_record_batch(None, false);
+        assert_eq!(batch.num_rows(), 11);
+        let table = DeltaTable::new_in_memory()
+            .write(vec![batch])
+            .with_max_rows_per_file(NonZeroUsize::new(4).unwrap())
+            .await?;
+        assert_eq!(table.snapshot()?.log_data().num_files(), 3);
+
+        let batches = get_data(&table).await;
+        let total_rows: usize = batches.iter().map(|b| b.num_rows()).sum();
+        assert_eq!(total_rows, 11);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_write_with_bounded_partition_writers() -> TestResult {
+        let batch = get_record_batch(None, false);
+        let table = DeltaTable::new_in_memory()
+            .write(vec![batch.clone(), batch.clone(), batch])
+            .with_partition_columns(["modified"])
+            .with_max_open_partition_writers(NonZeroUsize::new(1).unwrap())
+            .with_partition_sort(true)
+            .await?;
+
+        // sorted input writes every partition at once, so eviction never splits a partition
+        assert_eq!(table.snapshot()?.log_data().num_files(), 2);
+        let batches = get_data(&table).await;
+        let total_rows: usize = batches.iter().map(|b| b.num_rows()).sum();
+        assert_eq!(total_rows, 33);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_write_with_sort_order() -> TestResult {
+        use arrow::array::AsArray;
+        use arrow::compute::concat_batches;
+        use arrow::datatypes::Int32Type;
+        use parquet::arrow::async_reader::{ParquetObjectReader, ParquetRecordBatchStreamBuilder};
+
+        let batch = get_record_batch(None, false);
+        let table = DeltaTable::new_in_memory()
+            .write(vec![batch])
+            .with_sort_order(["id", "value"])
+            .await?;
+
+        let files = table.get_files_by_partitions(&[]).await?;
+        assert_eq!(files.len(), 1);
+        let reader =
+            ParquetObjectReader::new(table.log_store().object_store(None), files[0].clone());
+        let batches = ParquetRecordBatchStreamBuilder::new(reader)
+            .await?
+            .build()?
+            .try_collect::<Vec<_>>()
+            .await?;
+        let written = concat_batches(&batches[0].schema(), &batches)?;
+
+        let ids = written.column_by_name("id").unwrap().as_string::<i32>();
+        let values = written
+            .column_by_name("value")
+            .unwrap()
+            .as_primitive::<Int32Type>();
+        let rows = ids.iter().zip(values.iter()).collect::<Vec<_>>();
+        let mut sorted = rows.clone();
+        sorted.sort();
+        assert_eq!(rows, sorted);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_write_with_unknown_sort_column_fails() -> TestResult {
+        let batch = get_record_batch(None, false);
+        let result = DeltaTable::new_in_memory()
+            .write(vec![batch])
+            .with_sort_order(["missing"])
+            .await;
+
+        let err = result.expect_err("writing with an unknown sort column should fail");
+        assert!(
+            err.to_string()
+                .contains("Sort column(s) not found in write schema: missing")
+        );
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_write_view_and_large_types() -> TestResult {
+        let batch = get_record_batch(None, false);
+        let table = DeltaTable::new_in_memory()
+            .write(vec![batch.clone()])
+            .with_save_mode(SaveMode::ErrorIfExists)
+            .await?;
+
+        let view_schema = Arc::new(ArrowSchema::new(vec![
+            Field::new("id", DataType::Utf8View, true),
+            Field::new("value", DataType::Int32, true),
+            Field::new("modified", DataType::LargeUtf8, true),
+        ]));
+        let view_batch =
+            crate::kernel::schema::cast::cast_record_batch(&batch, view_schema, false, false)?;
+        let table = table
+            .write(vec![view_batch])
+            .with_save_mode(SaveMode::Append)
+            .await?;
+        assert_eq!(table.version(), Some(1));
+
+        let actual = get_data_sorted(&table, "id, value, modified").await;
+        let expected = get_data_sorted(
+            &DeltaTable::new_in_memory()
+                .write(vec![batch.clone(), batch])
+                .await?,
+            "id, value, modified",
+        )
+        .await;
+        assert_eq!(actual, expected);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_write_fills_missing_columns_with_defaults() -> TestResult {
+        let table = DeltaTable::new_in_memory()
+            .create()
+            .with_column("id", crate::kernel::DataType::INTEGER, true, None)
+            .with_column("status", crate::kernel::DataType::STRING, true, None)
+            .with_column_default("status", "'active'")
+            .await?;
+
+        let schema = Arc::new(ArrowSchema::new(vec![Field::new(
+            "id",
+            DataType::Int32,
+            true,
+        )]));
+        let batch = RecordBatch::try_new(schema, vec![Arc::new(Int32Array::from(vec![1, 2]))])?;
+        let table = table.write(vec![batch]).await?;
+
+        let expected = [
+            "+----+--------+",
+            "| id | status |",
+            "+----+--------+",
+            "| 1  | active |",
+            "| 2  | active |",
+            "+----+--------+",
+        ];
+        let actual = get_data(&table).await;
+        assert_batches_sorted_eq!(&expected, &actual);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_write_csv_and_ndjson() -> TestResult {
+        use super::ingest::{CsvReadOptions, IngestSource, JsonReadOptions};
+
+        let table = DeltaTable::new_in_memory()
+            .create()
+            .with_column("id", crate::kernel::DataType::INTEGER, true, None)
+            .with_column("value", crate::kernel::DataType::STRING, true, None)
+            .await?;
+
+        let csv = "id,value\n1,a\n2,b\n";
+        let table = table
+            .write_csv(
+                IngestSource::from_reader(std::io::Cursor::new(csv)),
+                CsvReadOptions::default(),
+            )?
+            .with_save_mode(SaveMode::Append)
+            .await?;
+        assert_eq!(table.version(), Some(1));
+
+        let ndjson = "{\"id\": 3, \"value\": \"c\"}\n{\"id\": 4}\n";
+        let table = table
+            .write_ndjson(
+                IngestSource::from_reader(std::io::Cursor::new(ndjson)),
+                JsonReadOptions::default(),
+            )?
+            .with_save_mode(SaveMode::Append)
+            .await?;
+        assert_eq!(table.version(), Some(2));
+
+        let expected = [
+            "+----+-------+",
+            "| id | value |",
+            "+----+-------+",
+            "| 1  | a     |",
+            "| 2  | b     |",
+            "| 3  | c     |",
+            "| 4  |       |",
+            "+----+-------+",
+        ];
+        let actual = get_data(&table).await;
+        assert_batches_sorted_eq!(&expected, &actual);
+        assert_eq!(
+            table
+                .snapshot()?
+                .snapshot()
+                .arrow_schema()
+                .field(0)
+                .data_type(),
+            &DataType::Int32
+        );
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_write_coercion_policy() -> TestResult {
+        let schema = Arc::new(ArrowSchema::new(vec![
+            Field::new("id", DataType::Utf8, true),
+            Field::new("value", DataType::Int32, true),
+        ]));
+        let batch = RecordBatch::try_new(
+            schema,
+            vec![
+                Arc::new(StringArray::from(vec!["A"])),
+                Arc::new(Int32Array::from(vec![1])),
+            ],
+        )?;
+        let table = DeltaTable::new_in_memory().write(vec![batch]).await?;
+
+        let wide_schema = Arc::new(ArrowSchema::new(vec![
+            Field::new("id", DataType::Utf8, true),
+            Field::new("value", DataType::Int64, true),
+        ]));
+        let wide_batch = RecordBatch::try_new(
+            wide_schema,
+            vec![
+                Arc::new(StringArray::from(vec!["B", "C"])),
+                Arc::new(Int64Array::from(vec![2, i64::MAX])),
+            ],
+        )?;
+
+        let result = table
+            .clone()
+            .write(vec![wide_batch.clone()])
+            .with_coercion_policy(CoercionPolicy::Strict)
+            .await;
+        assert!(
+            result.is_err(),
+            "Strict coercion should reject Int64 values"
+        );
+
+        let result = table
+            .clone()
+            .write(vec![wide_batch.clone()])
+            .with_coercion_policy(CoercionPolicy::SafeCast)
+            .await;
+        assert!(result.is_err(), "Overflowing values should fail the write");
+
+        let table = table
+            .write(vec![wide_batch])
+            .with_coercion_policy(CoercionPolicy::LossyCast)
+            .await?;
+        assert_eq!(table.version(), Some(1));
+
+        let expected = [
+            "+----+-------+",
+            "| id | value |",
+            "+----+-------+",
+            "| A  | 1     |",
+            "| B  | 2     |",
+            "| C  |       |",
+            "+----+-------+",
+        ];
+        let actual = get_data(&table).await;
+        assert_batches_sorted_eq!(&expected, &actual);
+
+        Ok(())
+    }
 }
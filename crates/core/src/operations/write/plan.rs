@@ -9,7 +9,7 @@ use std::num::NonZeroU64;
 use std::sync::Arc;
 use std::time::{SystemTime, UNIX_EPOCH};
 
-use arrow_schema::Schema;
+use arrow_schema::{ArrowError, Schema};
 use datafusion::catalog::Session;
 use datafusion::common::{Column, ScalarValue};
 use datafusion::logical_expr::{
@@ -22,6 +22,7 @@ use itertools::Itertools as _;
 use parquet::file::properties::WriterProperties;
 use uuid::Uuid;
 
+use super::column_defaults::{cd_is_enabled, with_column_defaults};
 use super::configs::WriterStatsConfig;
 use super::generated_columns::{gc_is_enabled, with_generated_columns};
 use super::metrics::SOURCE_COUNT_ID;
@@ -32,15 +33,16 @@ use crate::delta_datafusion::{
     DataFusionMixins, Expression, analyze_predicate_for_find_files, scan_files_where_matches,
 };
 use crate::errors::{DeltaResult, DeltaTableError};
-use crate::kernel::schema::cast::{merge_arrow_schema, normalize_for_delta};
+use crate::kernel::schema::cast::{CoercionPolicy, merge_arrow_schema, normalize_for_delta};
 use crate::kernel::{
     Action, ActiveAddOptions, Add, AddStatsPolicy, DeletionVectorDescriptor, EagerSnapshot,
-    Metadata, ProtocolExt as _, Remove, StructType, StructTypeExt,
+    Metadata, ProtocolExt as _, Remove, SchemaDiff, StructType, StructTypeExt,
 };
-use crate::logstore::LogStoreRef;
+use crate::logstore::{LogStoreRef, MultipartConfig};
 use crate::operations::cdc::{CDC_COLUMN_NAME, should_write_cdc};
 use crate::operations::{get_num_idx_cols_and_stats_columns, get_target_file_size};
 use crate::protocol::SaveMode;
+use crate::table::config::TableProperty;
 
 /// Schema and protocol actions required before the sink executes the write.
 #[derive(Default)]
@@ -92,7 +94,7 @@ pub(super) struct WritePreparationInput<'a> {
     pub(super) source: LogicalPlan,
     pub(super) mode: SaveMode,
     pub(super) schema_mode: Option<SchemaMode>,
-    pub(super) safe_cast: bool,
+    pub(super) coercion_policy: CoercionPolicy,
     pub(super) partition_columns: Vec<String>,
     pub(super) predicate: Option<Expression>,
     pub(super) target_file_size: Option<Option<NonZeroU64>>,
@@ -270,7 +272,7 @@ pub(super) fn prepare_write(input: WritePreparationInput<'_>) -> DeltaResult<Pre
         mut source,
         mode,
         schema_mode,
-        safe_cast,
+        coercion_policy,
         partition_columns,
         predicate,
         target_file_size,
@@ -287,6 +289,13 @@ pub(super) fn prepare_write(input: WritePreparationInput<'_>) -> DeltaResult<Pre
         normalize_for_delta(source.schema().inner())
     };
 
+    // Defaults are filled first so generation expressions can reference defaulted columns.
+    if let Some(snapshot) = snapshot
+        && cd_is_enabled(snapshot)
+    {
+        source = with_column_defaults(session, source, &snapshot.schema().get_column_defaults()?)?;
+    }
+
     if let Some(snapshot) = snapshot
         && gc_is_enabled(snapshot)
     {
@@ -307,7 +316,11 @@ pub(super) fn prepare_write(input: WritePreparationInput<'_>) -> DeltaResult<Pre
             .zip(original_schema.fields().iter())
             .map(|(target, original)| {
                 if target.data_type() != original.data_type() {
-                    let cast_fn = if safe_cast { try_cast } else { cast };
+                    let cast_fn = if coercion_policy.null_on_cast_failure() {
+                        try_cast
+                    } else {
+                        cast
+                    };
                     cast_fn(
                         Expr::Column(Column::from_name(target.name())),
                         target.data_type().clone(),
@@ -327,18 +340,38 @@ pub(super) fn prepare_write(input: WritePreparationInput<'_>) -> DeltaResult<Pre
     if let Some(snapshot) = snapshot {
         let table_schema = snapshot.input_schema();
 
+        if !(mode == SaveMode::Overwrite && schema_mode == Some(SchemaMode::Overwrite)) {
+            for field in source_schema.fields() {
+                if let Ok(table_field) = table_schema.field_with_name(field.name()) {
+                    coercion_policy.check_field(
+                        field.name(),
+                        field.data_type(),
+                        table_field.data_type(),
+                    )?;
+                }
+            }
+        }
+
         if let Err(schema_err) = try_cast_schema(source_schema.fields(), table_schema.fields()) {
             schema_drift = true;
+            let changes = source_schema
+                .as_ref()
+                .try_into_kernel()
+                .map(|source: StructType| snapshot.schema().diff(&source))
+                .ok();
             if mode == SaveMode::Overwrite && schema_mode == Some(SchemaMode::Overwrite) {
                 new_schema = None;
             } else if schema_mode == Some(SchemaMode::Merge) {
+                if let Some(changes) = &changes {
+                    tracing::debug!(%changes, "merging the source schema into the table schema");
+                }
                 new_schema = Some(merge_arrow_schema(
                     table_schema.clone(),
                     source_schema.clone(),
                     schema_drift,
                 )?);
             } else {
-                return Err(schema_err.into());
+                return Err(with_schema_changes(schema_err, changes.as_ref()).into());
             }
         } else if mode == SaveMode::Overwrite && schema_mode == Some(SchemaMode::Overwrite) {
             new_schema = None;
@@ -352,10 +385,23 @@ pub(super) fn prepare_write(input: WritePreparationInput<'_>) -> DeltaResult<Pre
     }
 
     if let Some(new_schema) = new_schema.as_ref() {
+        coercion_policy.warn_lossy_casts(new_schema.fields().iter().filter_map(|field| {
+            let source_field = source_schema.field_with_name(field.name()).ok()?;
+            Some((
+                field.name().as_str(),
+                source_field.data_type(),
+                field.data_type(),
+            ))
+        }));
+
         let mut schema_evolution_projection = Vec::with_capacity(new_schema.fields().len());
         for field in new_schema.fields() {
             if source_schema.index_of(field.name()).is_ok() {
-                let cast_fn = if safe_cast { try_cast } else { cast };
+                let cast_fn = if coercion_policy.null_on_cast_failure() {
+                    try_cast
+                } else {
+                    cast
+                };
                 schema_evolution_projection.push(
                     cast_fn(
                         Expr::Column(Column::from_name(field.name())),
@@ -698,6 +744,21 @@ fn build_exec_options(
         target_file_size.unwrap_or_else(|| Some(get_target_file_size(config, configuration)));
     let (num_indexed_cols, stats_columns) =
         get_num_idx_cols_and_stats_columns(config, configuration.clone());
+    let distinct_count_columns = match snapshot {
+        Some(snapshot) => {
+            WriterStatsConfig::from_config(snapshot.table_configuration()).distinct_count_columns
+        }
+        None => configuration
+            .get(TableProperty::DataSkippingDistinctCountColumns.as_ref())
+            .and_then(|value| value.as_deref())
+            .map(|value| {
+                value
+                    .split(',')
+                    .map(|column| column.trim().to_string())
+                    .filter(|column| !column.is_empty())
+                    .collect()
+            }),
+    };
 
     WriteExecOptions {
         partition_columns,
@@ -707,6 +768,8 @@ fn build_exec_options(
         writer_stats_config: WriterStatsConfig {
             num_indexed_cols,
             stats_columns,
+            distinct_count_columns,
+            multipart_config: MultipartConfig::default(),
         },
     }
 }
@@ -726,6 +789,23 @@ fn resolve_exact_validation(
         .transpose()?)
 }
 
+pub(super) fn validate_sort_columns_in_schema(
+    sort_columns: &[String],
+    schema: &Schema,
+) -> DeltaResult<()> {
+    let missing = sort_columns
+        .iter()
+        .filter(|column| schema.index_of(column.as_str()).is_err())
+        .cloned()
+        .collect::<Vec<_>>();
+
+    if missing.is_empty() {
+        Ok(())
+    } else {
+        Err(WriteError::MissingSortColumns { columns: missing }.into())
+    }
+}
+
 fn validate_partition_columns_in_schema(
     partition_columns: &[String],
     schema: &Schema,
@@ -743,6 +823,16 @@ fn validate_partition_columns_in_schema(
     }
 }
 
+/// Append the changes from the table schema to the source schema to a schema mismatch
+fn with_schema_changes(err: ArrowError, changes: Option<&SchemaDiff>) -> ArrowError {
+    match (err, changes) {
+        (ArrowError::SchemaError(msg), Some(changes)) if !changes.is_empty() => {
+            ArrowError::SchemaError(format!("{msg}. Changes to the table schema: {changes}"))
+        }
+        (err, _) => err,
+    }
+}
+
 fn metadata_with_schema_and_partition_columns(
     metadata: &Metadata,
     schema: &StructType,
@@ -903,7 +993,7 @@ mod tests {
             source: source_plan_for_batch(batch),
             mode: SaveMode::Append,
             schema_mode: Some(SchemaMode::Merge),
-            safe_cast: false,
+            coercion_policy: CoercionPolicy::default(),
             partition_columns: vec![],
             predicate: None,
             target_file_size: None,
@@ -945,7 +1035,7 @@ mod tests {
             source: source_plan_for_batch(batch),
             mode: SaveMode::Overwrite,
             schema_mode: Some(SchemaMode::Overwrite),
-            safe_cast: false,
+            coercion_policy: CoercionPolicy::default(),
             partition_columns: vec![],
             predicate: None,
             target_file_size: None,
@@ -970,7 +1060,7 @@ mod tests {
             source: source_plan_for_batch(get_record_batch(None, false)),
             mode: SaveMode::Overwrite,
             schema_mode: None,
-            safe_cast: false,
+            coercion_policy: CoercionPolicy::default(),
             partition_columns: vec![],
             predicate: Some(col("id").eq(lit("A")).into()),
             target_file_size: None,
@@ -1006,7 +1096,7 @@ mod tests {
             source: source_plan_for_batch(get_record_batch(None, false)),
             mode: SaveMode::Append,
             schema_mode: None,
-            safe_cast: false,
+            coercion_policy: CoercionPolicy::default(),
             partition_columns: vec![],
             predicate: Some(col("id").eq(lit("A")).into()),
             target_file_size: None,
@@ -1045,7 +1135,7 @@ mod tests {
             source: source_plan_for_batch(get_record_batch(None, false)),
             mode: SaveMode::Append,
             schema_mode: None,
-            safe_cast: false,
+            coercion_policy: CoercionPolicy::default(),
             partition_columns: vec![],
             predicate: None,
             target_file_size: None,
@@ -1091,7 +1181,7 @@ mod tests {
             source: source_plan_for_batch(batch),
             mode: SaveMode::Overwrite,
             schema_mode: None,
-            safe_cast: false,
+            coercion_policy: CoercionPolicy::default(),
             partition_columns: vec![],
             predicate: Some(col("id").eq(lit("missing")).into()),
             target_file_size: None,
@@ -1147,7 +1237,7 @@ mod tests {
             source: source_plan_for_batch(batch),
             mode: SaveMode::Overwrite,
             schema_mode: None,
-            safe_cast: false,
+            coercion_policy: CoercionPolicy::default(),
             partition_columns: vec!["id".to_string()],
             predicate: Some(col("id").eq(lit("A")).into()),
             target_file_size: None,
@@ -1213,7 +1303,7 @@ mod tests {
             source: source_plan_for_batch(batch),
             mode: SaveMode::Overwrite,
             schema_mode: None,
-            safe_cast: false,
+            coercion_policy: CoercionPolicy::default(),
             partition_columns: vec![],
             predicate: None,
             target_file_size: None,
@@ -1275,7 +1365,7 @@ mod tests {
             source: source_plan_for_batch(batch),
             mode: SaveMode::Overwrite,
             schema_mode: None,
-            safe_cast: false,
+            coercion_policy: CoercionPolicy::default(),
             partition_columns: vec![],
             predicate: Some(col("value").eq(lit(3)).into()),
             target_file_size: None,
@@ -1,30 +1,33 @@
 //! Abstractions and implementations for writing data to delta tables
 
 use std::collections::HashMap;
-use std::num::NonZeroU64;
-use std::sync::OnceLock;
+use std::num::{NonZeroU64, NonZeroUsize};
 
 use arrow_array::RecordBatch;
 use arrow_schema::{ArrowError, SchemaRef as ArrowSchemaRef};
+use bytes::Bytes;
 use delta_kernel::expressions::Scalar;
 use delta_kernel::table_properties::DataSkippingNumIndexedCols;
-use futures::{StreamExt, TryStreamExt};
+use futures::future::BoxFuture;
+use futures::{FutureExt, StreamExt, TryStreamExt};
 use indexmap::IndexMap;
-use object_store::buffered::BufWriter;
 use object_store::path::Path;
 use parquet::arrow::AsyncArrowWriter;
-use parquet::arrow::async_writer::ParquetObjectWriter;
+use parquet::arrow::async_writer::AsyncFileWriter;
 use parquet::basic::Compression;
-use parquet::file::properties::WriterProperties;
+use parquet::errors::ParquetError;
+use parquet::file::properties::{EnabledStatistics, WriterProperties};
+use parquet::schema::types::ColumnPath;
 use tokio::task::JoinSet;
 use tracing::*;
 
 use crate::errors::{DeltaResult, DeltaTableError};
+use crate::kernel::distinct_count::DistinctCountSketch;
 use crate::kernel::{Add, PartitionsExt};
-use crate::logstore::ObjectStoreRef;
+use crate::logstore::{MultipartConfig, MultipartWriter, ObjectStoreRef};
 use crate::parquet_utils::default_writer_properties;
 use crate::writer::record_batch::{PartitionResult, divide_by_partition_values};
-use crate::writer::stats::create_add;
+use crate::writer::stats::{add_distinct_count_sketches, create_add};
 use crate::writer::utils::{
     arrow_schema_without_partitions, next_data_path, record_batch_without_partitions,
 };
@@ -32,46 +35,11 @@ use crate::writer::utils::{
 use parquet::file::metadata::ParquetMetaData;
 
 const DEFAULT_WRITE_BATCH_SIZE: usize = 1024;
-const DEFAULT_UPLOAD_PART_SIZE: usize = 1024 * 1024 * 5;
-const DEFAULT_MAX_CONCURRENCY_TASKS: usize = 10;
-
-fn upload_part_size() -> usize {
-    static UPLOAD_SIZE: OnceLock<usize> = OnceLock::new();
-    *UPLOAD_SIZE.get_or_init(|| {
-        std::env::var("DELTARS_UPLOAD_PART_SIZE")
-            .ok()
-            .and_then(|s| s.parse::<usize>().ok())
-            .map(|size| {
-                if size < DEFAULT_UPLOAD_PART_SIZE {
-                    // Minimum part size in GCS and S3
-                    debug!("DELTARS_UPLOAD_PART_SIZE must be at least 5MB, therefore falling back on default of 5MB.");
-                    DEFAULT_UPLOAD_PART_SIZE
-                } else if size > 1024 * 1024 * 1024 * 5 {
-                    // Maximum part size in GCS and S3
-                    debug!("DELTARS_UPLOAD_PART_SIZE must not be higher than 5GB, therefore capping it at 5GB.");
-                    1024 * 1024 * 1024 * 5
-                } else {
-                    size
-                }
-            })
-            .unwrap_or(DEFAULT_UPLOAD_PART_SIZE)
-    })
-}
-
-fn get_max_concurrency_tasks() -> usize {
-    static MAX_CONCURRENCY_TASKS: OnceLock<usize> = OnceLock::new();
-    *MAX_CONCURRENCY_TASKS.get_or_init(|| {
-        std::env::var("DELTARS_MAX_CONCURRENCY_TASKS")
-            .ok()
-            .and_then(|s| s.parse::<usize>().ok())
-            .unwrap_or(DEFAULT_MAX_CONCURRENCY_TASKS)
-    })
-}
 
 /// Upload a parquet file to object store and return metadata for creating an Add action
 #[instrument(skip(arrow_writer), fields(rows = 0, size = 0))]
 async fn upload_parquet_file(
-    mut arrow_writer: AsyncArrowWriter<ParquetObjectWriter>,
+    mut arrow_writer: AsyncArrowWriter<MultipartWriter>,
     path: Path,
 ) -> DeltaResult<(Path, usize, ParquetMetaData)> {
     let metadata = arrow_writer.finish().await?;
@@ -139,6 +107,9 @@ pub struct WriterConfig {
     /// Row chunks passed to parquet writer. This and the internal parquet writer settings
     /// determine how fine granular we can track / control the size of resulting files.
     write_batch_size: usize,
+    /// Maximum number of rows written to a single file.
+    /// If None, files are only split based on `target_file_size`.
+    max_rows_per_file: Option<NonZeroUsize>,
     /// Num index cols to collect stats for
     num_indexed_cols: DataSkippingNumIndexedCols,
     /// Stats columns, specific columns to collect stats from, takes precedence over num_indexed_cols
@@ -146,6 +117,13 @@ pub struct WriterConfig {
     /// When set, write data files under a random prefix directory of this length instead of
     /// Hive-style partition dirs — keeps physical (UUID) column names out of paths under CM.
     random_prefix_length: Option<usize>,
+    /// Part size, concurrency and threshold of multipart uploads
+    multipart_config: MultipartConfig,
+    /// Maximum number of partition files written concurrently.
+    /// If None, a file is kept open for every partition until the writer is closed.
+    max_open_writers: Option<NonZeroUsize>,
+    /// Top-level columns to collect approximate distinct counts for
+    distinct_count_columns: Option<Vec<String>>,
 }
 
 impl WriterConfig {
@@ -169,9 +147,13 @@ impl WriterConfig {
             writer_properties,
             target_file_size,
             write_batch_size,
+            max_rows_per_file: None,
             num_indexed_cols,
             stats_columns,
             random_prefix_length: None,
+            multipart_config: MultipartConfig::default(),
+            max_open_writers: None,
+            distinct_count_columns: None,
         }
     }
 
@@ -182,6 +164,36 @@ impl WriterConfig {
         self
     }
 
+    /// Start a new data file once a file contains `max_rows` rows.
+    pub fn with_max_rows_per_file(mut self, max_rows: Option<NonZeroUsize>) -> Self {
+        self.max_rows_per_file = max_rows;
+        self
+    }
+
+    /// Configure the multipart uploads of data files.
+    pub fn with_multipart_config(mut self, config: MultipartConfig) -> Self {
+        self.multipart_config = config;
+        self
+    }
+
+    /// Keep at most `max_open_writers` partition files open at the same time.
+    ///
+    /// Once the limit is reached, the file of the least recently written partition is
+    /// finished and uploaded in the background before a new partition is opened. Input which is
+    /// not sorted by the partition columns may then produce several files per partition.
+    pub fn with_max_open_writers(mut self, max_open_writers: Option<NonZeroUsize>) -> Self {
+        self.max_open_writers = max_open_writers;
+        self
+    }
+
+    /// Collect approximate distinct counts for the top-level `columns` of every written file.
+    ///
+    /// The sketches are stored in the `distinctCountSketches` of the file statistics.
+    pub fn with_distinct_count_columns(mut self, columns: Option<Vec<String>>) -> Self {
+        self.distinct_count_columns = columns;
+        self
+    }
+
     /// Schema of files written to disk
     pub fn file_schema(&self) -> ArrowSchemaRef {
         arrow_schema_without_partitions(&self.table_schema, &self.partition_columns)
@@ -194,8 +206,10 @@ pub struct DeltaWriter {
     object_store: ObjectStoreRef,
     /// configuration for the writers
     config: WriterConfig,
-    /// partition writers for individual partitions
-    partition_writers: HashMap<Path, PartitionWriter>,
+    /// partition writers for individual partitions, from least to most recently used
+    partition_writers: IndexMap<Path, PartitionWriter>,
+    /// partition writers which were evicted and are finishing their files
+    closing_writers: JoinSet<DeltaResult<Vec<Add>>>,
 }
 
 impl DeltaWriter {
@@ -204,7 +218,8 @@ impl DeltaWriter {
         Self {
             object_store,
             config,
-            partition_writers: HashMap::new(),
+            partition_writers: IndexMap::new(),
+            closing_writers: JoinSet::new(),
         }
     }
 
@@ -239,11 +254,18 @@ impl DeltaWriter {
         let record_batch =
             record_batch_without_partitions(&record_batch, &self.config.partition_columns)?;
 
-        match self.partition_writers.get_mut(&partition_key) {
-            Some(writer) => {
+        match self.partition_writers.get_full_mut(&partition_key) {
+            Some((index, _, writer)) => {
                 writer.write(&record_batch).await?;
+                let last = self.partition_writers.len() - 1;
+                self.partition_writers.move_index(index, last);
             }
             None => {
+                if let Some(max_open_writers) = self.config.max_open_writers {
+                    while self.partition_writers.len() >= max_open_writers.get() {
+                        self.close_least_recently_used().await?;
+                    }
+                }
                 let prefix_override = match self.config.random_prefix_length {
                     Some(length) => Some(Path::parse(random_prefix(length))?),
                     None => None,
@@ -256,7 +278,10 @@ impl DeltaWriter {
                     Some(self.config.write_batch_size),
                     None,
                     prefix_override,
-                )?;
+                )?
+                .with_max_rows_per_file(self.config.max_rows_per_file)
+                .with_multipart_config(&self.config.multipart_config)
+                .with_distinct_count_columns(self.config.distinct_count_columns.clone());
                 let mut writer = PartitionWriter::try_with_config(
                     self.object_store.clone(),
                     config,
@@ -271,6 +296,35 @@ impl DeltaWriter {
         Ok(())
     }
 
+    /// Finish the file of the least recently written partition in the background.
+    ///
+    /// At most as many files as writers may be open are finished at once, waiting for the
+    /// oldest one to complete first.
+    async fn close_least_recently_used(&mut self) -> DeltaResult<()> {
+        let Some((partition_key, writer)) = self.partition_writers.shift_remove_index(0) else {
+            return Ok(());
+        };
+        let max_closing = self.config.max_open_writers.map_or(1, NonZeroUsize::get);
+        while self.closing_writers.len() >= max_closing {
+            self.join_closing_writer().await?;
+        }
+        debug!(
+            "Closing writer for partition {partition_key} to stay within the open writer limit."
+        );
+        self.closing_writers.spawn(writer.close());
+        Ok(())
+    }
+
+    async fn join_closing_writer(&mut self) -> DeltaResult<Option<Vec<Add>>> {
+        match self.closing_writers.join_next().await {
+            Some(Ok(result)) => result.map(Some),
+            Some(Err(err)) => Err(DeltaTableError::GenericError {
+                source: Box::new(err),
+            }),
+            None => Ok(None),
+        }
+    }
+
     /// Buffers record batches in-memory per partition up to appx. `target_file_size` for a partition.
     /// Flushes data to storage once a full file can be written.
     ///
@@ -289,7 +343,7 @@ impl DeltaWriter {
     /// This will flush all remaining data.
     pub async fn close(mut self) -> DeltaResult<Vec<Add>> {
         let writers = std::mem::take(&mut self.partition_writers);
-        let actions = futures::stream::iter(writers)
+        let mut actions = futures::stream::iter(writers)
             .map(|(_, writer)| async move {
                 let writer_actions = writer.close().await?;
                 Ok::<_, DeltaTableError>(writer_actions)
@@ -301,6 +355,10 @@ impl DeltaWriter {
             })
             .await?;
 
+        while let Some(evicted_actions) = self.join_closing_writer().await? {
+            actions.extend(evicted_actions);
+        }
+
         Ok(actions)
     }
 }
@@ -329,8 +387,13 @@ pub struct PartitionWriterConfig {
     /// Row chunks passed to parquet writer. This and the internal parquet writer settings
     /// determine how fine granular we can track / control the size of resulting files.
     write_batch_size: usize,
-    /// Concurrency level for writing to object store
-    max_concurrency_tasks: usize,
+    /// Maximum number of rows written to a single file.
+    /// If None, files are only split based on `target_file_size`.
+    max_rows_per_file: Option<NonZeroUsize>,
+    /// Part size, concurrency and threshold of multipart uploads
+    multipart_config: MultipartConfig,
+    /// Top-level columns to collect approximate distinct counts for
+    distinct_count_columns: Option<Vec<String>>,
 }
 
 impl PartitionWriterConfig {
@@ -359,27 +422,71 @@ impl PartitionWriterConfig {
             writer_properties,
             target_file_size,
             write_batch_size,
-            max_concurrency_tasks: max_concurrency_tasks.unwrap_or_else(get_max_concurrency_tasks),
+            max_rows_per_file: None,
+            multipart_config: MultipartConfig {
+                multipart_concurrency: max_concurrency_tasks,
+                ..Default::default()
+            },
+            distinct_count_columns: None,
         })
     }
+
+    /// Start a new data file once a file contains `max_rows` rows.
+    pub fn with_max_rows_per_file(mut self, max_rows: Option<NonZeroUsize>) -> Self {
+        self.max_rows_per_file = max_rows;
+        self
+    }
+
+    /// Configure the multipart uploads of data files.
+    ///
+    /// An explicitly passed `max_concurrency_tasks` takes precedence over the concurrency in
+    /// `config`.
+    pub fn with_multipart_config(mut self, config: &MultipartConfig) -> Self {
+        self.multipart_config = config.clone().merge(&self.multipart_config);
+        self
+    }
+
+    /// Collect approximate distinct counts for the top-level `columns` of every written file.
+    pub fn with_distinct_count_columns(mut self, columns: Option<Vec<String>>) -> Self {
+        self.distinct_count_columns = columns;
+        self
+    }
+}
+
+impl AsyncFileWriter for MultipartWriter {
+    fn write(&mut self, bs: Bytes) -> BoxFuture<'_, parquet::errors::Result<()>> {
+        async move {
+            self.put(bs)
+                .await
+                .map_err(|err| ParquetError::External(Box::new(err)))
+        }
+        .boxed()
+    }
+
+    fn complete(&mut self) -> BoxFuture<'_, parquet::errors::Result<()>> {
+        async move {
+            self.finish()
+                .await
+                .map(|_| ())
+                .map_err(|err| ParquetError::External(Box::new(err)))
+        }
+        .boxed()
+    }
 }
 
 enum LazyArrowWriter {
     Initialized(Path, ObjectStoreRef, PartitionWriterConfig),
-    Writing(Path, AsyncArrowWriter<ParquetObjectWriter>),
+    Writing(Path, AsyncArrowWriter<MultipartWriter>),
 }
 
 impl LazyArrowWriter {
     async fn write_batch(&mut self, batch: &RecordBatch) -> DeltaResult<()> {
         match self {
             LazyArrowWriter::Initialized(path, object_store, config) => {
-                let writer = ParquetObjectWriter::from_buf_writer(
-                    BufWriter::with_capacity(
-                        object_store.clone(),
-                        path.clone(),
-                        upload_part_size(),
-                    )
-                    .with_max_concurrency(config.max_concurrency_tasks),
+                let writer = MultipartWriter::new(
+                    object_store.clone(),
+                    path.clone(),
+                    &config.multipart_config,
                 );
                 let mut arrow_writer = AsyncArrowWriter::try_new(
                     writer,
@@ -417,11 +524,17 @@ pub struct PartitionWriter {
     config: PartitionWriterConfig,
     writer: LazyArrowWriter,
     part_counter: usize,
+    /// Rows written to the file currently being buffered
+    rows_in_file: usize,
     /// Num index cols to collect stats for
     num_indexed_cols: DataSkippingNumIndexedCols,
     /// Stats columns, specific columns to collect stats from, takes precedence over num_indexed_cols
     stats_columns: Option<Vec<String>>,
     in_flight_writers: JoinSet<DeltaResult<(Path, usize, ParquetMetaData)>>,
+    /// Distinct count sketches of the file currently being buffered
+    file_sketches: HashMap<String, DistinctCountSketch>,
+    /// Distinct count sketches of the files already flushed, by path
+    completed_sketches: HashMap<Path, HashMap<String, DistinctCountSketch>>,
 }
 
 impl PartitionWriter {
@@ -442,9 +555,12 @@ impl PartitionWriter {
             config,
             writer,
             part_counter: 0,
+            rows_in_file: 0,
             num_indexed_cols,
             stats_columns,
             in_flight_writers: JoinSet::new(),
+            file_sketches: HashMap::new(),
+            completed_sketches: HashMap::new(),
         })
     }
 
@@ -472,15 +588,47 @@ impl PartitionWriter {
         let next_path = self.next_data_path();
         let new_writer = Self::create_writer(self.object_store.clone(), next_path, &self.config)?;
         let state = std::mem::replace(&mut self.writer, new_writer);
+        self.rows_in_file = 0;
 
         if let LazyArrowWriter::Writing(path, arrow_writer) = state {
+            self.complete_sketches(path.clone());
             self.in_flight_writers
                 .spawn(upload_parquet_file(arrow_writer, path));
         }
         Ok(())
     }
 
-    /// Buffers record batches in-memory up to appx. `target_file_size`.
+    fn update_sketches(&mut self, batch: &RecordBatch) -> DeltaResult<()> {
+        let Some(columns) = &self.config.distinct_count_columns else {
+            return Ok(());
+        };
+        for column in columns {
+            // columns written without statistics, e.g. encrypted ones, get no sketch either
+            let statistics = self
+                .config
+                .writer_properties
+                .statistics_enabled(&ColumnPath::from(column.as_str()));
+            if statistics == EnabledStatistics::None {
+                continue;
+            }
+            if let Some(array) = batch.column_by_name(column) {
+                self.file_sketches
+                    .entry(column.clone())
+                    .or_default()
+                    .update(array.as_ref())?;
+            }
+        }
+        Ok(())
+    }
+
+    fn complete_sketches(&mut self, path: Path) {
+        let sketches = std::mem::take(&mut self.file_sketches);
+        if !sketches.is_empty() {
+            self.completed_sketches.insert(path, sketches);
+        }
+    }
+
+    /// Buffers record batches in-memory up to appx. `target_file_size` or `max_rows_per_file`.
     /// Flushes data to storage once a full file can be written.
     ///
     /// The `close` method has to be invoked to write all data still buffered
@@ -495,11 +643,29 @@ impl PartitionWriter {
         }
 
         let max_offset = batch.num_rows();
-        for offset in (0..max_offset).step_by(self.config.write_batch_size) {
-            let length = usize::min(self.config.write_batch_size, max_offset - offset);
-            self.writer
-                .write_batch(&batch.slice(offset, length))
-                .await?;
+        let mut offset = 0;
+        while offset < max_offset {
+            let mut length = usize::min(self.config.write_batch_size, max_offset - offset);
+            if let Some(max_rows) = self.config.max_rows_per_file {
+                // never write more rows into the current file than it has room for.
+                length = usize::min(length, max_rows.get() - self.rows_in_file);
+            }
+            let slice = batch.slice(offset, length);
+            self.writer.write_batch(&slice).await?;
+            self.update_sketches(&slice)?;
+            self.rows_in_file += length;
+            offset += length;
+
+            if let Some(max_rows) = self.config.max_rows_per_file
+                && self.rows_in_file >= max_rows.get()
+            {
+                debug!(
+                    "Writing file with {} rows in background.",
+                    self.rows_in_file
+                );
+                self.reset_writer()?;
+                continue;
+            }
             if let Some(target_file_size) = self.config.target_file_size {
                 let estimated_size = self.writer.estimated_size();
                 // flush currently buffered data to disk once we meet or exceed the target file size.
@@ -518,6 +684,10 @@ impl PartitionWriter {
     /// This will flush any remaining data and collect all Add actions from background tasks.
     pub async fn close(mut self) -> DeltaResult<Vec<Add>> {
         if let LazyArrowWriter::Writing(path, arrow_writer) = self.writer {
+            let sketches = std::mem::take(&mut self.file_sketches);
+            if !sketches.is_empty() {
+                self.completed_sketches.insert(path.clone(), sketches);
+            }
             self.in_flight_writers
                 .spawn(upload_parquet_file(arrow_writer, path));
         }
@@ -542,7 +712,7 @@ impl PartitionWriter {
         let adds = results
             .into_iter()
             .map(|(path, file_size, metadata)| {
-                create_add(
+                let mut add = create_add(
                     &self.config.partition_values,
                     path.to_string(),
                     file_size as i64,
@@ -552,7 +722,15 @@ impl PartitionWriter {
                 )
                 .map_err(|err| WriteError::CreateAdd {
                     source: Box::new(err),
-                })
+                })?;
+                if let Some(sketches) = self.completed_sketches.remove(&path) {
+                    add_distinct_count_sketches(&mut add, sketches).map_err(|err| {
+                        WriteError::CreateAdd {
+                            source: Box::new(err),
+                        }
+                    })?;
+                }
+                Ok::<_, WriteError>(add)
             })
             .collect::<Result<Vec<_>, _>>()?;
 
@@ -734,6 +912,55 @@ mod tests {
         assert!(target_file_count >= adds.len() as i32 - 1)
     }
 
+    #[tokio::test]
+    async fn test_max_open_writers() {
+        let schema = Arc::new(ArrowSchema::new(vec![
+            Field::new("id", DataType::Utf8, true),
+            Field::new("value", DataType::Int32, true),
+        ]));
+        let batch = |id: &str| {
+            RecordBatch::try_new(
+                schema.clone(),
+                vec![
+                    Arc::new(StringArray::from(vec![id; 10])),
+                    Arc::new(Int32Array::from((0..10).collect::<Vec<i32>>())),
+                ],
+            )
+            .unwrap()
+        };
+
+        let object_store = DeltaTableBuilder::from_url(url::Url::parse("memory:///").unwrap())
+            .unwrap()
+            .build_storage()
+            .unwrap()
+            .object_store(None);
+        let config = WriterConfig::new(
+            schema.clone(),
+            vec!["id".to_string()],
+            None,
+            None,
+            None,
+            DataSkippingNumIndexedCols::NumColumns(DEFAULT_NUM_INDEX_COLS),
+            None,
+        )
+        .with_max_open_writers(NonZeroUsize::new(2));
+        let mut writer = DeltaWriter::new(object_store, config);
+
+        for id in ["A", "B", "A", "C", "A", "B"] {
+            writer.write(&batch(id)).await.unwrap();
+            assert!(writer.partition_writers.len() <= 2);
+        }
+
+        // B is evicted when C is opened and reopened afterwards, A stays open throughout
+        let adds = writer.close().await.unwrap();
+        let mut partitions = adds
+            .iter()
+            .map(|add| add.partition_values["id"].clone().unwrap())
+            .collect::<Vec<_>>();
+        partitions.sort();
+        assert_eq!(partitions, ["A", "B", "B", "C"]);
+    }
+
     #[tokio::test]
     async fn test_unflushed_row_group_size() {
         let base_int = Arc::new(Int32Array::from((0..10000).collect::<Vec<i32>>()));
@@ -798,6 +1025,46 @@ mod tests {
         assert_eq!(adds.len(), 1);
     }
 
+    #[tokio::test]
+    async fn test_max_rows_per_file() {
+        let base_int = Arc::new(Int32Array::from((0..2500_i32).collect::<Vec<i32>>()));
+        let base_str = Arc::new(StringArray::from(vec!["A"; 2500]));
+        let schema = Arc::new(ArrowSchema::new(vec![
+            Field::new("id", DataType::Utf8, true),
+            Field::new("value", DataType::Int32, true),
+        ]));
+        let batch = RecordBatch::try_new(schema, vec![base_str, base_int]).unwrap();
+
+        let object_store = DeltaTableBuilder::from_url(url::Url::parse("memory:///").unwrap())
+            .unwrap()
+            .build_storage()
+            .unwrap()
+            .object_store(None);
+        // the write batch size does not line up with the row limit, so files must be split
+        // within a single chunk.
+        let config = WriterConfig::new(
+            batch.schema(),
+            vec![],
+            None,
+            None,
+            Some(700),
+            DataSkippingNumIndexedCols::NumColumns(DEFAULT_NUM_INDEX_COLS),
+            None,
+        )
+        .with_max_rows_per_file(NonZeroUsize::new(1000));
+        let mut writer = DeltaWriter::new(object_store, config);
+        writer.write(&batch).await.unwrap();
+        writer.write(&batch.slice(0, 100)).await.unwrap();
+
+        let adds = writer.close().await.unwrap();
+        let mut num_records = adds
+            .iter()
+            .map(|add| add.get_stats().unwrap().unwrap().num_records)
+            .collect::<Vec<_>>();
+        num_records.sort();
+        assert_eq!(num_records, vec![600, 1000, 1000]);
+    }
+
     #[test]
     fn test_sort_completed_writes_by_path() {
         let mut results = vec![
@@ -0,0 +1,460 @@
+//! Parquet modular encryption of the data files of a table.
+//!
+//! Columns are encrypted with keys referenced by a key id. The key ids are stored in the
+//! metadata of the encrypted files and resolved to keys through a [`KeyManagementClient`] when
+//! the files are read, so files written with different keys are read with the same client.
+//!
+//! Encryption is configured with [`ParquetEncryption`], either for all operations on a table via
+//! `DeltaTableBuilder::with_parquet_encryption`, or applied to the [`WriterProperties`] passed to
+//! individual writers. Operations on a table configured with encryption also decrypt the files
+//! they read, other scans decrypt data files with the client set via
+//! `DeltaScanConfig::with_key_management`.
+//!
+//! File statistics are stored unencrypted in the Delta log, so no parquet statistics or bloom
+//! filters are written for encrypted columns and they are left out of the file statistics.
+use std::collections::BTreeMap;
+use std::fmt::Debug;
+use std::sync::Arc;
+
+use parquet::encryption::decrypt::{FileDecryptionProperties, KeyRetriever};
+use parquet::encryption::encrypt::FileEncryptionProperties;
+use parquet::errors::ParquetError;
+use parquet::file::properties::{EnabledStatistics, WriterProperties};
+use parquet::schema::types::ColumnPath;
+
+use crate::{DeltaResult, DeltaTableConfig, DeltaTableError};
+
+/// Source of the keys used to encrypt and decrypt data files, e.g. a key management service.
+pub trait KeyManagementClient: Send + Sync + Debug {
+    /// Return the AES key identified by `key_id`
+    fn get_key(&self, key_id: &str) -> DeltaResult<Vec<u8>>;
+}
+
+/// Encryption of the data files written with a set of [`WriterProperties`].
+#[derive(Debug, Clone)]
+pub struct ParquetEncryption {
+    client: Arc<dyn KeyManagementClient>,
+    footer_key_id: String,
+    column_key_ids: BTreeMap<String, String>,
+    plaintext_footer: bool,
+}
+
+impl ParquetEncryption {
+    /// Encrypt the file footer with the key `footer_key_id`.
+    ///
+    /// Unless keys are set for individual columns, all columns are encrypted with the footer key.
+    pub fn new(client: Arc<dyn KeyManagementClient>, footer_key_id: impl Into<String>) -> Self {
+        Self {
+            client,
+            footer_key_id: footer_key_id.into(),
+            column_key_ids: BTreeMap::new(),
+            plaintext_footer: false,
+        }
+    }
+
+    /// Encrypt `column` with the key `key_id`.
+    ///
+    /// Columns are identified by their name in the data files, nested fields are separated by
+    /// dots. Once a column has a key of its own, columns without a key are written unencrypted.
+    pub fn with_column_key(mut self, column: impl Into<String>, key_id: impl Into<String>) -> Self {
+        self.column_key_ids.insert(column.into(), key_id.into());
+        self
+    }
+
+    /// Write the footer unencrypted, so readers without the footer key can still read the
+    /// schema and the unencrypted columns. The footer is signed with the footer key.
+    pub fn with_plaintext_footer(mut self, plaintext_footer: bool) -> Self {
+        self.plaintext_footer = plaintext_footer;
+        self
+    }
+
+    /// The key management client resolving the key ids
+    pub fn client(&self) -> &Arc<dyn KeyManagementClient> {
+        &self.client
+    }
+
+    /// The properties encrypting a file, keys are fetched from the key management client.
+    pub fn file_encryption_properties(&self) -> DeltaResult<Arc<FileEncryptionProperties>> {
+        let footer_key = self.client.get_key(&self.footer_key_id)?;
+        let mut builder = FileEncryptionProperties::builder(footer_key)
+            .with_footer_key_metadata(self.footer_key_id.as_bytes().to_vec())
+            .with_plaintext_footer(self.plaintext_footer);
+        for (column, key_id) in &self.column_key_ids {
+            builder = builder.with_column_key_and_metadata(
+                column,
+                self.client.get_key(key_id)?,
+                key_id.as_bytes().to_vec(),
+            );
+        }
+        Ok(builder.build()?.into())
+    }
+
+    /// Encrypt the files written with `writer_properties`.
+    ///
+    /// Statistics and bloom filters are disabled for the encrypted columns, they would otherwise
+    /// end up in plaintext in the file statistics of the Delta log.
+    pub fn apply(&self, writer_properties: WriterProperties) -> DeltaResult<WriterProperties> {
+        let mut builder = writer_properties
+            .into_builder()
+            .with_file_encryption_properties(self.file_encryption_properties()?);
+        if self.column_key_ids.is_empty() {
+            // all columns are encrypted with the footer key
+            builder = builder
+                .set_statistics_enabled(EnabledStatistics::None)
+                .set_bloom_filter_enabled(false);
+        }
+        for column in self.column_key_ids.keys() {
+            let path = ColumnPath::new(column.split('.').map(String::from).collect());
+            builder = builder
+                .set_column_statistics_enabled(path.clone(), EnabledStatistics::None)
+                .set_column_bloom_filter_enabled(path, false);
+        }
+        Ok(builder.build())
+    }
+}
+
+/// Encrypt the files written with `writer_properties` with the encryption configured for the
+/// table, unless the caller already configured encryption of their own.
+pub(crate) fn encrypt_writer_properties(
+    config: &DeltaTableConfig,
+    writer_properties: WriterProperties,
+) -> DeltaResult<WriterProperties> {
+    match &config.parquet_encryption {
+        Some(encryption) if writer_properties.file_encryption_properties().is_none() => {
+            encryption.apply(writer_properties)
+        }
+        _ => Ok(writer_properties),
+    }
+}
+
+/// The properties decrypting files encrypted with the keys of `client`.
+pub fn file_decryption_properties(
+    client: Arc<dyn KeyManagementClient>,
+) -> DeltaResult<Arc<FileDecryptionProperties>> {
+    let retriever = Arc::new(KeyIdRetriever { client });
+    Ok(FileDecryptionProperties::with_key_retriever(retriever)
+        .build()?
+        .into())
+}
+
+/// Resolves the key ids stored as key metadata in encrypted files
+struct KeyIdRetriever {
+    client: Arc<dyn KeyManagementClient>,
+}
+
+impl KeyRetriever for KeyIdRetriever {
+    fn retrieve_key(&self, key_metadata: &[u8]) -> parquet::errors::Result<Vec<u8>> {
+        let key_id = std::str::from_utf8(key_metadata).map_err(|_| {
+            ParquetError::General("Key metadata of encrypted file is not a key id".to_string())
+        })?;
+        self.client
+            .get_key(key_id)
+            .map_err(|err| ParquetError::External(Box::new(err)))
+    }
+}
+
+#[cfg(feature = "datafusion")]
+pub(crate) use self::scan::KeyManagementEncryptionFactory;
+
+#[cfg(feature = "datafusion")]
+mod scan {
+    use std::sync::Arc;
+
+    use arrow_schema::SchemaRef;
+    use datafusion::config::EncryptionFactoryOptions;
+    use datafusion::error::Result;
+    use datafusion::execution::parquet_encryption::EncryptionFactory;
+    use object_store::path::Path;
+    use parquet::encryption::decrypt::FileDecryptionProperties;
+    use parquet::encryption::encrypt::FileEncryptionProperties;
+
+    use super::{KeyManagementClient, file_decryption_properties};
+
+    /// Decrypts the data files read by a parquet scan
+    #[derive(Debug)]
+    pub(crate) struct KeyManagementEncryptionFactory {
+        client: Arc<dyn KeyManagementClient>,
+    }
+
+    impl KeyManagementEncryptionFactory {
+        pub(crate) fn new(client: Arc<dyn KeyManagementClient>) -> Self {
+            Self { client }
+        }
+    }
+
+    #[async_trait::async_trait]
+    impl EncryptionFactory for KeyManagementEncryptionFactory {
+        async fn get_file_encryption_properties(
+            &self,
+            _config: &EncryptionFactoryOptions,
+            _schema: &SchemaRef,
+            _file_path: &Path,
+        ) -> Result<Option<Arc<FileEncryptionProperties>>> {
+            // files are encrypted by the writer properties of the writing operation
+            Ok(None)
+        }
+
+        async fn get_file_decryption_properties(
+            &self,
+            _config: &EncryptionFactoryOptions,
+            _file_path: &Path,
+        ) -> Result<Option<Arc<FileDecryptionProperties>>> {
+            Ok(Some(file_decryption_properties(self.client.clone())?))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use arrow::array::AsArray as _;
+    use arrow::datatypes::Int32Type;
+    use futures::TryStreamExt;
+    use object_store::path::Path;
+    use parquet::arrow::arrow_reader::ArrowReaderOptions;
+    use parquet::arrow::async_reader::{ParquetObjectReader, ParquetRecordBatchStreamBuilder};
+
+    use super::*;
+    use crate::DeltaTable;
+    use crate::writer::test_utils::{create_initialized_table, get_record_batch};
+    use crate::writer::{DeltaWriter, RecordBatchWriter};
+
+    #[derive(Debug)]
+    struct InMemoryKeys(HashMap<String, Vec<u8>>);
+
+    impl KeyManagementClient for InMemoryKeys {
+        fn get_key(&self, key_id: &str) -> DeltaResult<Vec<u8>> {
+            self.0
+                .get(key_id)
+                .cloned()
+                .ok_or_else(|| DeltaTableError::Generic(format!("Unknown key {key_id}")))
+        }
+    }
+
+    fn client() -> Arc<dyn KeyManagementClient> {
+        Arc::new(InMemoryKeys(HashMap::from([
+            ("footer".to_string(), b"0123456789012345".to_vec()),
+            ("value".to_string(), b"1234567890123450".to_vec()),
+        ])))
+    }
+
+    async fn read_values(
+        table: &DeltaTable,
+        path: &str,
+        decryption: Option<Arc<FileDecryptionProperties>>,
+    ) -> DeltaResult<Vec<i32>> {
+        let reader = ParquetObjectReader::new(table.object_store(), Path::from(path));
+        let mut options = ArrowReaderOptions::new();
+        if let Some(decryption) = decryption {
+            options = options.with_file_decryption_properties(decryption);
+        }
+        let batches: Vec<_> = ParquetRecordBatchStreamBuilder::new_with_options(reader, options)
+            .await?
+            .build()?
+            .try_collect()
+            .await?;
+        Ok(batches
+            .iter()
+            .flat_map(|batch| {
+                batch
+                    .column_by_name("value")
+                    .unwrap()
+                    .as_primitive::<Int32Type>()
+                    .values()
+                    .to_vec()
+            })
+            .collect())
+    }
+
+    #[tokio::test]
+    async fn test_encrypted_write_and_read() {
+        let table_dir = tempfile::tempdir().unwrap();
+        let table = create_initialized_table(table_dir.path().to_str().unwrap(), &[]).await;
+
+        let encryption =
+            ParquetEncryption::new(client(), "footer").with_column_key("value", "value");
+        let writer_properties = encryption
+            .apply(WriterProperties::builder().build())
+            .unwrap();
+        let mut writer = RecordBatchWriter::for_table(&table)
+            .unwrap()
+            .with_writer_properties(writer_properties);
+        writer.write(get_record_batch(None, false)).await.unwrap();
+        let adds = writer.flush().await.unwrap();
+        assert_eq!(adds.len(), 1);
+
+        let decryption = file_decryption_properties(client()).unwrap();
+        let values = read_values(&table, &adds[0].path, Some(decryption))
+            .await
+            .unwrap();
+        assert_eq!(values, (1..=11).collect::<Vec<_>>());
+
+        // the footer is encrypted, so the file can not be read without keys
+        assert!(read_values(&table, &adds[0].path, None).await.is_err());
+    }
+
+    #[cfg(feature = "datafusion")]
+    #[tokio::test]
+    async fn test_scan_encrypted_table() {
+        use datafusion::prelude::SessionContext;
+
+        use crate::delta_datafusion::{DeltaScanConfig, DeltaScanNext};
+
+        let table_dir = tempfile::tempdir().unwrap();
+        let mut table = create_initialized_table(table_dir.path().to_str().unwrap(), &[]).await;
+        let writer_properties = ParquetEncryption::new(client(), "footer")
+            .apply(WriterProperties::builder().build())
+            .unwrap();
+        let mut writer = RecordBatchWriter::for_table(&table)
+            .unwrap()
+            .with_writer_properties(writer_properties);
+        writer.write(get_record_batch(None, false)).await.unwrap();
+        writer.flush_and_commit(&mut table).await.unwrap();
+
+        let snapshot = table.snapshot().unwrap().snapshot().clone();
+        let ctx = SessionContext::new();
+        let config = DeltaScanConfig::new().with_key_management(client());
+        let provider = DeltaScanNext::new(snapshot.clone(), config)
+            .unwrap()
+            .with_log_store(table.log_store());
+        ctx.register_table("encrypted", Arc::new(provider)).unwrap();
+        let batches = ctx
+            .sql("select value from encrypted")
+            .await
+            .unwrap()
+            .collect()
+            .await
+            .unwrap();
+        let mut values: Vec<i32> = batches
+            .iter()
+            .flat_map(|batch| {
+                batch
+                    .column(0)
+                    .as_primitive::<Int32Type>()
+                    .values()
+                    .to_vec()
+            })
+            .collect();
+        values.sort();
+        assert_eq!(values, (1..=11).collect::<Vec<_>>());
+
+        let provider = DeltaScanNext::new(snapshot, DeltaScanConfig::new())
+            .unwrap()
+            .with_log_store(table.log_store());
+        ctx.register_table("plain", Arc::new(provider)).unwrap();
+        let result = ctx
+            .sql("select value from plain")
+            .await
+            .unwrap()
+            .collect()
+            .await;
+        assert!(result.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_encrypted_columns_have_no_stats() {
+        let table_dir = tempfile::tempdir().unwrap();
+        let table = create_initialized_table(table_dir.path().to_str().unwrap(), &[]).await;
+
+        let writer_properties = ParquetEncryption::new(client(), "footer")
+            .with_column_key("value", "value")
+            .apply(WriterProperties::builder().build())
+            .unwrap();
+        let mut writer = RecordBatchWriter::for_table(&table)
+            .unwrap()
+            .with_writer_properties(writer_properties);
+        writer.write(get_record_batch(None, false)).await.unwrap();
+        let adds = writer.flush().await.unwrap();
+
+        let stats = adds[0].get_stats().unwrap().unwrap();
+        assert_eq!(stats.num_records, 11);
+        assert!(stats.min_values.contains_key("id"));
+        assert!(!stats.min_values.contains_key("value"));
+        assert!(!stats.max_values.contains_key("value"));
+        assert!(!stats.null_count.contains_key("value"));
+    }
+
+    #[cfg(feature = "datafusion")]
+    #[tokio::test]
+    async fn test_operations_on_encrypted_table() {
+        use datafusion::prelude::{SessionContext, col, lit};
+
+        use crate::DeltaTableBuilder;
+        use crate::delta_datafusion::{DeltaScanConfig, DeltaScanNext};
+
+        let table_dir = tempfile::tempdir().unwrap();
+        let table = create_initialized_table(table_dir.path().to_str().unwrap(), &[]).await;
+        let table = DeltaTableBuilder::from_url(table.table_url().clone())
+            .unwrap()
+            .with_parquet_encryption(
+                ParquetEncryption::new(client(), "footer").with_column_key("value", "value"),
+            )
+            .load()
+            .await
+            .unwrap();
+
+        let table = table
+            .write(vec![get_record_batch(None, false)])
+            .await
+            .unwrap();
+        // the delete reads the encrypted file to rewrite the remaining rows
+        let (table, metrics) = table
+            .delete()
+            .with_predicate(col("value").gt(lit(5)))
+            .await
+            .unwrap();
+        assert_eq!(metrics.num_deleted_rows, Some(6));
+        let (table, metrics) = table.recompute_stats().with_force(true).await.unwrap();
+        assert_eq!(metrics.num_files_updated, 1);
+
+        let files = table.get_files_by_partitions(&[]).await.unwrap();
+        assert_eq!(files.len(), 1);
+        assert!(read_values(&table, files[0].as_ref(), None).await.is_err());
+
+        // scans of the table decrypt with the client of the table
+        let snapshot = table.snapshot().unwrap().snapshot().clone();
+        let provider = DeltaScanNext::new(snapshot, DeltaScanConfig::new())
+            .unwrap()
+            .with_log_store(table.log_store());
+        let ctx = SessionContext::new();
+        ctx.register_table("encrypted", Arc::new(provider)).unwrap();
+        let batches = ctx
+            .sql("select value from encrypted")
+            .await
+            .unwrap()
+            .collect()
+            .await
+            .unwrap();
+        let mut values: Vec<i32> = batches
+            .iter()
+            .flat_map(|batch| {
+                batch
+                    .column(0)
+                    .as_primitive::<Int32Type>()
+                    .values()
+                    .to_vec()
+            })
+            .collect();
+        values.sort();
+        assert_eq!(values, (1..=5).collect::<Vec<_>>());
+    }
+
+    #[cfg(feature = "datafusion")]
+    #[test]
+    fn test_scan_config_with_key_management_serializes() {
+        use crate::delta_datafusion::DeltaScanConfig;
+
+        let config = DeltaScanConfig::new().with_key_management(client());
+        let serialized = serde_json::to_string(&config).unwrap();
+        let config: DeltaScanConfig = serde_json::from_str(&serialized).unwrap();
+        assert!(config.key_management.is_none());
+    }
+
+    #[test]
+    fn test_unknown_key() {
+        let encryption =
+            ParquetEncryption::new(client(), "footer").with_column_key("id", "missing");
+        assert!(encryption.file_encryption_properties().is_err());
+    }
+}
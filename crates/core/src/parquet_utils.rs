@@ -1,5 +1,6 @@
-use parquet::basic::Compression;
-use parquet::file::properties::WriterProperties;
+use parquet::basic::{Compression, Encoding};
+use parquet::file::properties::{EnabledStatistics, WriterProperties};
+use parquet::schema::types::ColumnPath;
 
 pub(crate) fn default_writer_properties(compression: Compression) -> WriterProperties {
     WriterProperties::builder()
@@ -8,10 +9,119 @@ pub(crate) fn default_writer_properties(compression: Compression) -> WriterPrope
         .build()
 }
 
+/// Parquet writer settings for a single column.
+///
+/// Options that are not set fall back to the writer properties the column settings are
+/// applied to.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ColumnWriterProperties {
+    compression: Option<Compression>,
+    dictionary_enabled: Option<bool>,
+    encoding: Option<Encoding>,
+    statistics_enabled: Option<EnabledStatistics>,
+    bloom_filter_enabled: Option<bool>,
+    bloom_filter_fpp: Option<f64>,
+    bloom_filter_ndv: Option<u64>,
+}
+
+impl ColumnWriterProperties {
+    /// Create column settings that do not override any writer property
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Compression codec and level of the column
+    pub fn with_compression(mut self, compression: Compression) -> Self {
+        self.compression = Some(compression);
+        self
+    }
+
+    /// Whether dictionary encoding is enabled for the column
+    pub fn with_dictionary_enabled(mut self, enabled: bool) -> Self {
+        self.dictionary_enabled = Some(enabled);
+        self
+    }
+
+    /// Encoding of the column when dictionary encoding is disabled or falls back
+    ///
+    /// # Panics
+    ///
+    /// Writing panics if a dictionary encoding is passed, use
+    /// [`with_dictionary_enabled`](Self::with_dictionary_enabled) instead.
+    pub fn with_encoding(mut self, encoding: Encoding) -> Self {
+        self.encoding = Some(encoding);
+        self
+    }
+
+    /// Level of statistics collected for the column
+    pub fn with_statistics_enabled(mut self, statistics: EnabledStatistics) -> Self {
+        self.statistics_enabled = Some(statistics);
+        self
+    }
+
+    /// Whether a bloom filter is written for the column
+    pub fn with_bloom_filter_enabled(mut self, enabled: bool) -> Self {
+        self.bloom_filter_enabled = Some(enabled);
+        self
+    }
+
+    /// False positive probability of the bloom filter, enables the bloom filter
+    pub fn with_bloom_filter_fpp(mut self, fpp: f64) -> Self {
+        self.bloom_filter_fpp = Some(fpp);
+        self
+    }
+
+    /// Expected number of distinct values of the bloom filter, enables the bloom filter
+    pub fn with_bloom_filter_ndv(mut self, ndv: u64) -> Self {
+        self.bloom_filter_ndv = Some(ndv);
+        self
+    }
+}
+
+/// Apply per column settings on top of `writer_properties`.
+///
+/// Columns are identified by their name in the data files, nested fields are separated by dots.
+pub(crate) fn apply_column_writer_properties<'a>(
+    writer_properties: WriterProperties,
+    columns: impl IntoIterator<Item = (&'a String, &'a ColumnWriterProperties)>,
+) -> WriterProperties {
+    let mut columns = columns.into_iter().peekable();
+    if columns.peek().is_none() {
+        return writer_properties;
+    }
+    let mut builder = writer_properties.into_builder();
+    for (column, properties) in columns {
+        let path = ColumnPath::new(column.split('.').map(String::from).collect());
+        if let Some(compression) = properties.compression {
+            builder = builder.set_column_compression(path.clone(), compression);
+        }
+        if let Some(enabled) = properties.dictionary_enabled {
+            builder = builder.set_column_dictionary_enabled(path.clone(), enabled);
+        }
+        if let Some(encoding) = properties.encoding {
+            builder = builder.set_column_encoding(path.clone(), encoding);
+        }
+        if let Some(statistics) = properties.statistics_enabled {
+            builder = builder.set_column_statistics_enabled(path.clone(), statistics);
+        }
+        if let Some(enabled) = properties.bloom_filter_enabled {
+            builder = builder.set_column_bloom_filter_enabled(path.clone(), enabled);
+        }
+        if let Some(fpp) = properties.bloom_filter_fpp {
+            builder = builder.set_column_bloom_filter_fpp(path.clone(), fpp);
+        }
+        if let Some(ndv) = properties.bloom_filter_ndv {
+            builder = builder.set_column_bloom_filter_ndv(path, ndv);
+        }
+    }
+    builder.build()
+}
+
 #[cfg(test)]
 mod tests {
+    use parquet::basic::ZstdLevel;
+
     use super::*;
-    use parquet::schema::types::ColumnPath;
 
     #[test]
     fn default_writer_properties_sets_created_by_and_compression() {
@@ -26,4 +136,55 @@ mod tests {
             Compression::SNAPPY
         );
     }
+
+    #[test]
+    fn apply_column_writer_properties_overrides_only_configured_columns() {
+        let columns = [
+            (
+                "payload".to_string(),
+                ColumnWriterProperties::new()
+                    .with_compression(Compression::ZSTD(ZstdLevel::try_new(9).unwrap()))
+                    .with_dictionary_enabled(false),
+            ),
+            (
+                "key.id".to_string(),
+                ColumnWriterProperties::new()
+                    .with_bloom_filter_fpp(0.01)
+                    .with_bloom_filter_ndv(1000)
+                    .with_statistics_enabled(EnabledStatistics::Chunk),
+            ),
+        ];
+        let writer_properties = apply_column_writer_properties(
+            default_writer_properties(Compression::SNAPPY),
+            columns
+                .iter()
+                .map(|(column, properties)| (column, properties)),
+        );
+
+        let payload = ColumnPath::from("payload");
+        assert_eq!(
+            writer_properties.compression(&payload),
+            Compression::ZSTD(ZstdLevel::try_new(9).unwrap())
+        );
+        assert!(!writer_properties.dictionary_enabled(&payload));
+        assert!(
+            writer_properties
+                .bloom_filter_properties(&payload)
+                .is_none()
+        );
+
+        let key = ColumnPath::new(vec!["key".to_string(), "id".to_string()]);
+        assert_eq!(writer_properties.compression(&key), Compression::SNAPPY);
+        let bloom_filter = writer_properties.bloom_filter_properties(&key).unwrap();
+        assert_eq!(bloom_filter.fpp, 0.01);
+        assert_eq!(bloom_filter.ndv, 1000);
+        assert_eq!(
+            writer_properties.statistics_enabled(&key),
+            EnabledStatistics::Chunk
+        );
+        assert_eq!(
+            writer_properties.created_by(),
+            format!("delta-rs version {}", crate::crate_version())
+        );
+    }
 }
@@ -1,19 +1,29 @@
 //! Implementation for writing delta checkpoints.
 
-use std::sync::LazyLock;
+use std::num::NonZero;
+use std::sync::{Arc, LazyLock};
 
 use delta_kernel::last_checkpoint_hint::LastCheckpointHint;
 use url::Url;
 
+use arrow::array::RecordBatch;
 use chrono::{TimeZone, Utc};
 use delta_kernel::snapshot::Snapshot;
 use futures::{StreamExt, TryStreamExt};
+use object_store::ObjectStoreExt as _;
+use object_store::path::Path;
+use parquet::arrow::AsyncArrowWriter;
 use regex::Regex;
-use tracing::{debug, error};
+use tokio::task::JoinSet;
+use tracing::{debug, error, warn};
 use uuid::Uuid;
 
 use crate::kernel::{Version, spawn_blocking_with_span};
-use crate::logstore::{DELTA_LOG_REGEX, LogStore};
+use crate::logstore::{
+    DELTA_LOG_REGEX, LogStore, MultipartConfig, MultipartWriter, ObjectStoreRef,
+};
+use crate::protocol::checksum::CHECKSUM_REGEX;
+use crate::protocol::to_rb;
 use crate::table::config::TablePropertiesExt as _;
 use crate::{DeltaResult, DeltaTableError};
 use crate::{DeltaTable, open_table_with_version};
@@ -21,25 +31,38 @@ use crate::{DeltaTable, open_table_with_version};
 static CHECKPOINT_REGEX: LazyLock<Regex> =
     LazyLock::new(|| Regex::new(r"_delta_log/(\d{20})\.(checkpoint).*$").unwrap());
 
-/// Creates checkpoint for a given table version, table state and object store
-#[tracing::instrument(skip(log_store), fields(operation = "checkpoint", version = version, table_uri = %log_store.root_url()))]
-pub(crate) async fn create_checkpoint_for(
+async fn load_snapshot(
     version: Version,
     log_store: &dyn LogStore,
     operation_id: Option<Uuid>,
-) -> DeltaResult<()> {
+) -> DeltaResult<Arc<Snapshot>> {
     let table_root = log_store.transaction_url(operation_id)?;
     let engine = log_store.engine(operation_id);
-
-    let task_engine = engine.clone();
-    let snapshot = spawn_blocking_with_span(move || {
+    spawn_blocking_with_span(move || {
         Snapshot::builder_for(table_root)
             .at_version(version)
-            .build(task_engine.as_ref())
+            .build(engine.as_ref())
     })
     .await
-    .map_err(|e| DeltaTableError::Generic(e.to_string()))??;
+    .map_err(|e| DeltaTableError::Generic(e.to_string()))?
+    .map_err(Into::into)
+}
+
+/// Creates checkpoint for a given table version, table state and object store
+///
+/// The checkpoint is split into parts if the table sets `delta-rs.checkpointPartSize`.
+#[tracing::instrument(skip(log_store), fields(operation = "checkpoint", version = version, table_uri = %log_store.root_url()))]
+pub(crate) async fn create_checkpoint_for(
+    version: Version,
+    log_store: &dyn LogStore,
+    operation_id: Option<Uuid>,
+) -> DeltaResult<()> {
+    let snapshot = load_snapshot(version, log_store, operation_id).await?;
+    if let Some(part_size) = snapshot.table_properties().checkpoint_part_size() {
+        return write_multi_part_checkpoint(snapshot, log_store, part_size, operation_id).await;
+    }
 
+    let engine = log_store.engine(operation_id);
     snapshot.checkpoint(engine.as_ref(), None)?;
     Ok(())
 }
@@ -51,6 +74,160 @@ pub async fn create_checkpoint(table: &DeltaTable, operation_id: Option<Uuid>) -
     Ok(())
 }
 
+/// Creates a classic checkpoint at the current table version, split into parts of at most
+/// `part_size` actions each. The parts are written in parallel.
+///
+/// Tables using v2 checkpoints get a single checkpoint file instead, as multi-part checkpoints
+/// are only defined for classic checkpoints.
+pub async fn create_multi_part_checkpoint(
+    table: &DeltaTable,
+    part_size: NonZero<usize>,
+    operation_id: Option<Uuid>,
+) -> DeltaResult<()> {
+    let log_store = table.log_store.as_ref();
+    let snapshot = load_snapshot(table.snapshot()?.version(), log_store, operation_id).await?;
+    write_multi_part_checkpoint(snapshot, log_store, part_size, operation_id).await
+}
+
+/// Path of part `part` (starting at 1) of a multi-part checkpoint with `num_parts` parts
+fn checkpoint_part_path(version: Version, part: usize, num_parts: usize) -> Path {
+    if num_parts == 1 {
+        Path::from(format!("_delta_log/{version:020}.checkpoint.parquet"))
+    } else {
+        Path::from(format!(
+            "_delta_log/{version:020}.checkpoint.{part:010}.{num_parts:010}.parquet"
+        ))
+    }
+}
+
+fn tmp_checkpoint_part_path(token: Uuid, part: usize) -> Path {
+    Path::from(format!("_delta_log/_checkpoint_{token}.{part}.parquet.tmp"))
+}
+
+async fn write_checkpoint_part(
+    store: ObjectStoreRef,
+    path: Path,
+    batches: Vec<RecordBatch>,
+    config: MultipartConfig,
+) -> DeltaResult<()> {
+    let schema = batches[0].schema();
+    let mut writer =
+        AsyncArrowWriter::try_new(MultipartWriter::new(store, path, &config), schema, None)?;
+    for batch in &batches {
+        writer.write(batch).await?;
+    }
+    writer.close().await?;
+    Ok(())
+}
+
+async fn write_multi_part_checkpoint(
+    snapshot: Arc<Snapshot>,
+    log_store: &dyn LogStore,
+    part_size: NonZero<usize>,
+    operation_id: Option<Uuid>,
+) -> DeltaResult<()> {
+    let version = snapshot.version();
+    let engine = log_store.engine(operation_id);
+    let writer = snapshot.clone().create_checkpoint_writer()?;
+    let classic_name = format!("{version:020}.checkpoint.parquet");
+    if !writer.checkpoint_path()?.path().ends_with(&classic_name) {
+        debug!("table uses v2 checkpoints, writing a single checkpoint file");
+        snapshot.checkpoint(engine.as_ref(), None)?;
+        return Ok(());
+    }
+    let mut data = writer.checkpoint_data(engine.as_ref())?;
+
+    let store = log_store.object_store(operation_id);
+    let config = log_store.config().options().multipart_config();
+    let token = Uuid::new_v4();
+    let mut tasks = JoinSet::new();
+    let mut part = Vec::new();
+    let mut part_rows = 0;
+    let mut num_parts = 0;
+    let mut num_actions = 0;
+
+    // Parts are written to temporary files while the actions are read, since their final names
+    // depend on the total number of parts.
+    let result: DeltaResult<()> = async {
+        loop {
+            let (batch, data_next) = spawn_blocking_with_span(move || {
+                let Some(batch) = data.next() else {
+                    return Ok::<_, DeltaTableError>((None, data));
+                };
+                Ok((Some(to_rb(batch?)?), data))
+            })
+            .await
+            .map_err(|e| DeltaTableError::Generic(e.to_string()))??;
+            data = data_next;
+
+            let done = batch.is_none();
+            if let Some(batch) = batch.filter(|batch| batch.num_rows() > 0) {
+                part_rows += batch.num_rows();
+                num_actions += batch.num_rows();
+                part.push(batch);
+            }
+            if !part.is_empty() && (done || part_rows >= part_size.get()) {
+                num_parts += 1;
+                tasks.spawn(write_checkpoint_part(
+                    store.clone(),
+                    tmp_checkpoint_part_path(token, num_parts),
+                    std::mem::take(&mut part),
+                    config.clone(),
+                ));
+                part_rows = 0;
+            }
+            if done {
+                break;
+            }
+        }
+        while let Some(result) = tasks.join_next().await {
+            result.map_err(|e| DeltaTableError::Generic(e.to_string()))??;
+        }
+
+        futures::stream::iter(1..=num_parts)
+            .map(|part| {
+                let store = store.clone();
+                async move {
+                    let tmp_path = tmp_checkpoint_part_path(token, part);
+                    store
+                        .copy(&tmp_path, &checkpoint_part_path(version, part, num_parts))
+                        .await?;
+                    store.delete(&tmp_path).await?;
+                    Ok::<_, DeltaTableError>(())
+                }
+            })
+            .buffer_unordered(config.concurrency())
+            .try_collect::<Vec<_>>()
+            .await?;
+        Ok(())
+    }
+    .await;
+
+    if let Err(err) = result {
+        tasks.abort_all();
+        for part in 1..=num_parts {
+            if let Err(err) = store.delete(&tmp_checkpoint_part_path(token, part)).await {
+                warn!(error = %err, "failed to clean up temporary checkpoint part");
+            }
+        }
+        return Err(err);
+    }
+
+    let hint = serde_json::json!({
+        "version": version,
+        "size": num_actions,
+        "parts": (num_parts > 1).then_some(num_parts),
+    });
+    store
+        .put(
+            &Path::from("_delta_log/_last_checkpoint"),
+            hint.to_string().into_bytes().into(),
+        )
+        .await?;
+    debug!("Wrote checkpoint for version {version} in {num_parts} parts");
+    Ok(())
+}
+
 /// Delete expires log files before given version from table. The table log retention is based on
 /// the `logRetentionDuration` property of the Delta Table, 30 days by default.
 pub async fn cleanup_metadata(
@@ -112,12 +289,31 @@ pub async fn create_checkpoint_from_table_url_and_cleanup(
 /// See also: https://github.com/delta-io/delta-rs/issues/3692 for background on
 /// why cleanup must align to an existing checkpoint.
 pub async fn cleanup_expired_logs_for(
-    mut keep_version: Version,
+    keep_version: Version,
     log_store: &dyn LogStore,
     cutoff_timestamp: i64,
     operation_id: Option<Uuid>,
 ) -> DeltaResult<usize> {
     debug!("called cleanup_expired_logs_for");
+    let locations =
+        expired_log_files(keep_version, log_store, cutoff_timestamp, operation_id).await?;
+    let deleted = log_store
+        .object_store(operation_id)
+        .delete_stream(futures::stream::iter(locations.into_iter().map(Ok)).boxed())
+        .try_collect::<Vec<_>>()
+        .await?;
+
+    debug!("Deleted {} expired logs", deleted.len());
+    Ok(deleted.len())
+}
+
+/// List the log files [`cleanup_expired_logs_for`] would delete, without deleting them.
+pub(crate) async fn expired_log_files(
+    mut keep_version: Version,
+    log_store: &dyn LogStore,
+    cutoff_timestamp: i64,
+    operation_id: Option<Uuid>,
+) -> DeltaResult<Vec<Path>> {
     let object_store = log_store.object_store(operation_id);
     let log_path = log_store.log_path();
 
@@ -173,14 +369,15 @@ pub async fn cleanup_expired_logs_for(
             "Not cleaning metadata files, could not find a checkpoint with version <= keep_version ({})",
             keep_version
         );
-        return Ok(0);
+        return Ok(Vec::new());
     };
 
     debug!("safe_checkpoint_version: {}", safe_checkpoint_version);
 
-    // Step 4: Delete DELTA_LOG files where log_ver < safe_checkpoint_version && ts <= cutoff_timestamp
-    let locations = futures::stream::iter(log_entries.into_iter())
-        .filter_map(move |meta: Result<crate::ObjectMeta, _>| async move {
+    // Step 4: Select DELTA_LOG and checksum files where log_ver < safe_checkpoint_version && ts <= cutoff_timestamp
+    let locations = log_entries
+        .into_iter()
+        .filter_map(|meta: Result<crate::ObjectMeta, _>| {
             let meta = match meta {
                 Ok(m) => m,
                 Err(err) => {
@@ -189,7 +386,9 @@ pub async fn cleanup_expired_logs_for(
                 }
             };
             let path_str = meta.location.as_ref();
-            let captures = DELTA_LOG_REGEX.captures(path_str)?;
+            let captures = DELTA_LOG_REGEX
+                .captures(path_str)
+                .or_else(|| CHECKSUM_REGEX.captures(path_str))?;
             let ts = meta.last_modified.timestamp_millis();
             let log_ver_str = captures.get(1).unwrap().as_str();
             let Ok(log_ver) = log_ver_str.parse::<Version>() else {
@@ -197,20 +396,13 @@ pub async fn cleanup_expired_logs_for(
             };
             if log_ver < safe_checkpoint_version && ts <= cutoff_timestamp {
                 debug!("file to delete: {:?}", meta.location);
-                Some(Ok(meta.location))
+                Some(meta.location)
             } else {
                 None
             }
         })
-        .boxed();
-
-    let deleted = object_store
-        .delete_stream(locations)
-        .try_collect::<Vec<_>>()
-        .await?;
-
-    debug!("Deleted {} expired logs", deleted.len());
-    Ok(deleted.len())
+        .collect();
+    Ok(locations)
 }
 
 /// Parse `_last_checkpoint` JSON bytes into a [`LastCheckpointHint`].
@@ -549,6 +741,57 @@ mod tests {
             assert!(res.is_ok());
         }
 
+        #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+        async fn test_create_multi_part_checkpoint() -> DeltaResult<()> {
+            let mut table = setup_table().await;
+            let version = table.version().unwrap();
+            let num_files = table.snapshot()?.log_data().num_files();
+
+            create_multi_part_checkpoint(&table, NonZero::new(1).unwrap(), None).await?;
+
+            let log_path = table.log_store().log_path().clone();
+            let store = table.log_store().object_store(None);
+            let mut parts: Vec<String> = store
+                .list(Some(&log_path))
+                .map_ok(|meta| meta.location.filename().unwrap_or_default().to_string())
+                .try_filter(|name| futures::future::ready(name.contains(".checkpoint.")))
+                .try_collect()
+                .await?;
+            parts.sort();
+            let num_parts = parts.len();
+            assert!(num_parts > 1, "Expected multiple parts, got {parts:?}");
+            for (idx, name) in parts.iter().enumerate() {
+                assert_eq!(
+                    name,
+                    &format!(
+                        "{version:020}.checkpoint.{:010}.{num_parts:010}.parquet",
+                        idx + 1
+                    )
+                );
+                assert_eq!(
+                    crate::logstore::extract_version_from_filename(name),
+                    Some(version)
+                );
+            }
+
+            let last_checkpoint = read_last_checkpoint(store.as_ref(), &log_path)
+                .await?
+                .expect("Expected checkpoint hint");
+            assert_eq!(last_checkpoint.version, version);
+            assert_eq!(last_checkpoint.parts, Some(num_parts));
+
+            // The table must be readable from the checkpoint parts alone
+            for commit in 0..=version {
+                store
+                    .delete(&log_path.clone().join(format!("{commit:020}.json")))
+                    .await?;
+            }
+            table.load().await?;
+            assert_eq!(table.version(), Some(version));
+            assert_eq!(table.snapshot()?.log_data().num_files(), num_files);
+            Ok(())
+        }
+
         #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
         async fn test_struct_with_single_list_field() {
             // you need another column otherwise the entire stats struct is empty
@@ -0,0 +1,402 @@
+//! Version checksum (`<version>.crc`) files.
+//!
+//! A version checksum records a summary of the table state at a given version, such as the
+//! total size and number of active files, next to the commit in `_delta_log`. Readers can use it
+//! to answer these questions without replaying the log and to verify the state they loaded.
+//!
+//! See the [protocol](https://github.com/delta-io/delta/blob/master/PROTOCOL.md#version-checksum-file)
+//! for details.
+
+use std::sync::LazyLock;
+
+use delta_kernel::table_features::TableFeature;
+use object_store::path::Path;
+use object_store::{Error as ObjectStoreError, ObjectStoreExt as _, PutPayload};
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use tracing::debug;
+
+use crate::kernel::{Action, EagerSnapshot, Metadata, Protocol, Version};
+use crate::logstore::LogStore;
+use crate::{DeltaResult, DeltaTableError};
+
+pub(crate) static CHECKSUM_REGEX: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"(\d{20})\.crc$").unwrap());
+
+/// Summary of the table state at a specific version, as stored in `<version>.crc`
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct VersionChecksum {
+    /// The transaction id of the commit which produced this version
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub txn_id: Option<String>,
+    /// Total size of all active data files in bytes
+    pub table_size_bytes: i64,
+    /// Number of active data files
+    pub num_files: i64,
+    /// Number of metadata actions, always 1 for a valid table
+    pub num_metadata: i64,
+    /// Number of protocol actions, always 1 for a valid table
+    pub num_protocol: i64,
+    /// The in-commit timestamp of the version, if enabled
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub in_commit_timestamp_opt: Option<i64>,
+    /// The table metadata at this version
+    pub metadata: Metadata,
+    /// The table protocol at this version
+    pub protocol: Protocol,
+}
+
+impl VersionChecksum {
+    fn empty(snapshot: &EagerSnapshot) -> Self {
+        Self {
+            txn_id: None,
+            table_size_bytes: 0,
+            num_files: 0,
+            num_metadata: 1,
+            num_protocol: 1,
+            in_commit_timestamp_opt: None,
+            metadata: snapshot.metadata().clone(),
+            protocol: snapshot.protocol().clone(),
+        }
+    }
+
+    /// Compute the checksum from a snapshot which has its files loaded
+    pub fn try_from_snapshot(snapshot: &EagerSnapshot) -> DeltaResult<Self> {
+        if !snapshot.load_config().require_files {
+            return Err(DeltaTableError::NotInitializedWithFiles(
+                "version checksum".to_string(),
+            ));
+        }
+        let log_data = snapshot.log_data();
+        Ok(Self {
+            table_size_bytes: log_data.iter().map(|file| file.size()).sum(),
+            num_files: log_data.num_files() as i64,
+            ..Self::empty(snapshot)
+        })
+    }
+
+    /// Compute the checksum of the next version from this one and the actions committed in it.
+    ///
+    /// An add without data change in a commit which removes no files re-adds an active file,
+    /// e.g. with recomputed statistics, and replaces it. Returns `None` if the result cannot be
+    /// derived from the actions alone, e.g. when a remove action does not carry the size of the
+    /// removed file.
+    pub fn apply_actions<'a>(
+        &self,
+        actions: impl IntoIterator<Item = &'a Action>,
+        snapshot: &EagerSnapshot,
+    ) -> Option<Self> {
+        let actions: Vec<_> = actions.into_iter().collect();
+        let removes_files = actions
+            .iter()
+            .any(|action| matches!(action, Action::Remove(_)));
+        let mut next = Self {
+            table_size_bytes: self.table_size_bytes,
+            num_files: self.num_files,
+            in_commit_timestamp_opt: in_commit_timestamp(&actions, snapshot),
+            ..Self::empty(snapshot)
+        };
+        for action in actions {
+            match action {
+                // files only change without data change when they are rearranged into new files
+                Action::Add(add) if !add.data_change && !removes_files => {}
+                Action::Add(add) => {
+                    next.table_size_bytes += add.size;
+                    next.num_files += 1;
+                }
+                Action::Remove(remove) => {
+                    next.table_size_bytes -= remove.size?;
+                    next.num_files -= 1;
+                }
+                _ => {}
+            }
+        }
+        Some(next)
+    }
+
+    /// Verify that the snapshot matches this checksum.
+    ///
+    /// The snapshot must have its files loaded.
+    pub fn verify(&self, snapshot: &EagerSnapshot) -> DeltaResult<()> {
+        let actual = Self::try_from_snapshot(snapshot)?;
+        let mut mismatches = Vec::new();
+        if self.table_size_bytes != actual.table_size_bytes {
+            mismatches.push(format!(
+                "tableSizeBytes: expected {}, found {}",
+                self.table_size_bytes, actual.table_size_bytes
+            ));
+        }
+        if self.num_files != actual.num_files {
+            mismatches.push(format!(
+                "numFiles: expected {}, found {}",
+                self.num_files, actual.num_files
+            ));
+        }
+        if self.metadata != actual.metadata {
+            mismatches.push("metadata differs".to_string());
+        }
+        if self.protocol != actual.protocol {
+            mismatches.push("protocol differs".to_string());
+        }
+        if mismatches.is_empty() {
+            Ok(())
+        } else {
+            Err(DeltaTableError::InvalidData {
+                message: format!(
+                    "Version checksum does not match table state at version {}: {}",
+                    snapshot.version(),
+                    mismatches.join(", ")
+                ),
+            })
+        }
+    }
+}
+
+/// The in-commit timestamp of a commit consisting of `actions`, if enabled for the table
+fn in_commit_timestamp(actions: &[&Action], snapshot: &EagerSnapshot) -> Option<i64> {
+    if !snapshot
+        .table_configuration()
+        .is_feature_enabled(&TableFeature::InCommitTimestamp)
+    {
+        return None;
+    }
+    actions.iter().find_map(|action| match action {
+        Action::CommitInfo(info) => info.in_commit_timestamp.or(info.timestamp),
+        _ => None,
+    })
+}
+
+/// Path of the checksum file for the given version
+pub fn checksum_path(version: Version) -> Path {
+    Path::from("_delta_log").join(format!("{version:020}.crc"))
+}
+
+/// Read the checksum file for `version`, returning `None` if it does not exist
+pub async fn read_checksum(
+    log_store: &dyn LogStore,
+    version: Version,
+) -> DeltaResult<Option<VersionChecksum>> {
+    let object_store = log_store.object_store(None);
+    match object_store.get(&checksum_path(version)).await {
+        Ok(res) => {
+            let bytes = res.bytes().await?;
+            let checksum =
+                serde_json::from_slice(&bytes).map_err(|e| DeltaTableError::InvalidData {
+                    message: format!("Invalid version checksum for version {version}: {e}"),
+                })?;
+            Ok(Some(checksum))
+        }
+        Err(ObjectStoreError::NotFound { .. }) => Ok(None),
+        Err(err) => Err(err.into()),
+    }
+}
+
+/// Write the checksum file for `version`, replacing any existing one
+pub async fn write_checksum(
+    log_store: &dyn LogStore,
+    version: Version,
+    checksum: &VersionChecksum,
+) -> DeltaResult<()> {
+    let payload = serde_json::to_vec(checksum)
+        .map_err(|json_err| DeltaTableError::SerializeLogJson { json_err })?;
+    log_store
+        .object_store(None)
+        .put(&checksum_path(version), PutPayload::from(payload))
+        .await?;
+    debug!("Wrote version checksum for version {version}");
+    Ok(())
+}
+
+/// Write the checksum for the version of `snapshot`, which was produced by committing `actions`.
+///
+/// The checksum is computed from the snapshot when its files are loaded, otherwise it is derived
+/// from the checksum of the previous version. Returns `false` if neither was possible.
+pub(crate) async fn write_checksum_for_commit(
+    log_store: &dyn LogStore,
+    snapshot: &EagerSnapshot,
+    actions: &[Action],
+) -> DeltaResult<bool> {
+    let version = snapshot.version();
+    let checksum = if snapshot.load_config().require_files {
+        let actions: Vec<_> = actions.iter().collect();
+        Some(VersionChecksum {
+            in_commit_timestamp_opt: in_commit_timestamp(&actions, snapshot),
+            ..VersionChecksum::try_from_snapshot(snapshot)?
+        })
+    } else if version == 0 {
+        VersionChecksum::empty(snapshot).apply_actions(actions, snapshot)
+    } else {
+        read_checksum(log_store, version - 1)
+            .await?
+            .and_then(|previous| previous.apply_actions(actions, snapshot))
+    };
+
+    match checksum {
+        Some(checksum) => {
+            write_checksum(log_store, version, &checksum).await?;
+            Ok(true)
+        }
+        None => {
+            debug!("Skipping version checksum for version {version}, unable to compute it");
+            Ok(false)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::DeltaTable;
+    use crate::table::config::TableProperty;
+    use crate::writer::test_utils::get_delta_schema;
+
+    async fn create_table(write_checksum: bool) -> DeltaTable {
+        let table_schema = get_delta_schema();
+        let table = DeltaTable::new_in_memory()
+            .create()
+            .with_columns(table_schema.fields().cloned())
+            .with_configuration_property(
+                TableProperty::WriteChecksumFile,
+                Some(write_checksum.to_string()),
+            )
+            .await
+            .unwrap();
+        assert_eq!(table.version(), Some(0));
+        table
+    }
+
+    #[test]
+    fn test_checksum_path() {
+        let path = checksum_path(12);
+        assert_eq!(path.as_ref(), "_delta_log/00000000000000000012.crc");
+        assert!(CHECKSUM_REGEX.is_match(path.as_ref()));
+    }
+
+    #[cfg(feature = "datafusion")]
+    #[tokio::test]
+    async fn test_write_checksum_after_commit() -> DeltaResult<()> {
+        let table = create_table(true).await;
+        let log_store = table.log_store();
+        let checksum = read_checksum(log_store.as_ref(), 0).await?.unwrap();
+        assert_eq!(checksum.num_files, 0);
+        assert_eq!(checksum.table_size_bytes, 0);
+
+        let batch = crate::writer::test_utils::get_record_batch(None, false);
+        let table = table.write(vec![batch]).await?;
+        let snapshot = table.snapshot()?.snapshot();
+        let checksum = read_checksum(log_store.as_ref(), 1).await?.unwrap();
+        assert_eq!(checksum.num_files, snapshot.log_data().num_files() as i64);
+        assert!(checksum.table_size_bytes > 0);
+        checksum.verify(snapshot)?;
+        assert_eq!(table.version_checksum().await?, Some(checksum));
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_no_checksum_without_property() -> DeltaResult<()> {
+        let table = create_table(false).await;
+        assert!(table.version_checksum().await?.is_none());
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_verify_detects_mismatch() -> DeltaResult<()> {
+        let table = create_table(true).await;
+        let snapshot = table.snapshot()?.snapshot();
+
+        let mut checksum = read_checksum(table.log_store().as_ref(), 0).await?.unwrap();
+        checksum.verify(snapshot)?;
+        checksum.num_files += 1;
+        checksum.table_size_bytes += 10;
+        let err = checksum.verify(snapshot).unwrap_err();
+        assert!(err.to_string().contains("numFiles"));
+        assert!(err.to_string().contains("tableSizeBytes"));
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_apply_actions_counts_readd_as_replace() -> DeltaResult<()> {
+        let table = create_table(true).await;
+        let snapshot = table.snapshot()?.snapshot();
+        let checksum = VersionChecksum::try_from_snapshot(snapshot)?;
+        let add = |path: &str, data_change| {
+            Action::Add(crate::kernel::Add {
+                path: path.to_string(),
+                size: 10,
+                data_change,
+                ..Default::default()
+            })
+        };
+
+        let appended = checksum
+            .apply_actions(&[add("part-0.parquet", true)], snapshot)
+            .unwrap();
+        assert_eq!(appended.num_files, 1);
+        assert_eq!(appended.table_size_bytes, 10);
+
+        // recomputing statistics re-adds the active file
+        let readded = appended
+            .apply_actions(&[add("part-0.parquet", false)], snapshot)
+            .unwrap();
+        assert_eq!(readded.num_files, 1);
+        assert_eq!(readded.table_size_bytes, 10);
+
+        // compaction rearranges removed files into new ones
+        let remove = Action::Remove(crate::kernel::Remove {
+            path: "part-0.parquet".to_string(),
+            size: Some(10),
+            data_change: false,
+            ..Default::default()
+        });
+        let compacted = readded
+            .apply_actions(&[remove, add("part-1.parquet", false)], snapshot)
+            .unwrap();
+        assert_eq!(compacted.num_files, 1);
+        assert_eq!(compacted.table_size_bytes, 10);
+        assert_eq!(compacted.in_commit_timestamp_opt, None);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_load_verifies_checksum() -> DeltaResult<()> {
+        let table = create_table(true).await;
+        let log_store = table.log_store();
+        let config = crate::DeltaTableConfig {
+            verify_checksum: true,
+            ..Default::default()
+        };
+        EagerSnapshot::try_new(log_store.as_ref(), config.clone(), None).await?;
+
+        let mut checksum = read_checksum(log_store.as_ref(), 0).await?.unwrap();
+        checksum.num_files += 1;
+        write_checksum(log_store.as_ref(), 0, &checksum).await?;
+        let err = EagerSnapshot::try_new(log_store.as_ref(), config, None)
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("numFiles"));
+
+        // files are not verified unless requested
+        EagerSnapshot::try_new(log_store.as_ref(), Default::default(), None).await?;
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_apply_actions_requires_remove_size() -> DeltaResult<()> {
+        let table = create_table(true).await;
+        let snapshot = table.snapshot()?.snapshot();
+        let checksum = VersionChecksum::try_from_snapshot(snapshot)?;
+
+        let remove = crate::kernel::Remove {
+            path: "part-0.parquet".to_string(),
+            size: None,
+            ..Default::default()
+        };
+        assert!(
+            checksum
+                .apply_actions(&[Action::Remove(remove)], snapshot)
+                .is_none()
+        );
+        Ok(())
+    }
+}
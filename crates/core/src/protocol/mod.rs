@@ -19,14 +19,17 @@ use url::Url;
 
 use crate::crate_version;
 use crate::errors::{DeltaResult, DeltaTableError};
+use crate::kernel::distinct_count::DistinctCountSketch;
 use crate::kernel::{
     Add, CommitInfo, Metadata, Protocol, Remove, StructField, TableFeatures, Version,
 };
 
 pub mod checkpoints;
+pub mod checksum;
 pub mod log_compaction;
 
 pub(crate) use checkpoints::{cleanup_expired_logs_for, create_checkpoint_for};
+pub(crate) use checksum::write_checksum_for_commit;
 
 /// Struct used to represent minValues and maxValues in add action statistics.
 #[derive(Serialize, Deserialize, Debug, PartialEq, Eq)]
@@ -98,6 +101,10 @@ pub struct Stats {
     pub max_values: HashMap<String, ColumnValueStat>,
     /// The number of null values for all columns.
     pub null_count: HashMap<String, ColumnCountStat>,
+    /// Sketches of the distinct values of the columns configured in
+    /// `delta-rs.dataSkippingDistinctCountColumns`.
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub distinct_count_sketches: HashMap<String, DistinctCountSketch>,
 }
 
 /// Statistics associated with Add actions contained in the Delta log.
@@ -115,6 +122,10 @@ struct PartialStats {
     pub max_values: Option<HashMap<String, ColumnValueStat>>,
     /// The number of null values for all columns.
     pub null_count: Option<HashMap<String, ColumnCountStat>>,
+    /// Sketches of the distinct values of the columns configured in
+    /// `delta-rs.dataSkippingDistinctCountColumns`.
+    #[serde(default)]
+    pub distinct_count_sketches: HashMap<String, DistinctCountSketch>,
 }
 
 impl PartialStats {
@@ -128,6 +139,7 @@ impl PartialStats {
             min_values: min_values.unwrap_or_default(),
             max_values: max_values.unwrap_or_default(),
             null_count: null_count.unwrap_or_default(),
+            distinct_count_sketches: take(&mut self.distinct_count_sketches),
         }
     }
 }
@@ -384,6 +396,27 @@ pub enum DeltaOperation {
         /// The name of the column whose `NOT NULL` constraint was dropped
         column: StructField,
     },
+
+    /// Recompute the statistics of data files from their Parquet footers
+    #[serde(rename_all = "camelCase")]
+    RecomputeStats {
+        /// Whether files with complete statistics were recomputed as well
+        force: bool,
+    },
+
+    /// An operation defined by an application which stages its own actions, see
+    /// [`CommitBuilder`](crate::kernel::transaction::CommitBuilder).
+    #[serde(rename_all = "camelCase")]
+    Custom {
+        /// Name of the operation recorded in the commit info
+        name: String,
+        /// Parameters recorded in the commit info
+        parameters: HashMap<String, String>,
+        /// The predicate the operation read the table with, used when resolving conflicts
+        predicate: Option<String>,
+        /// Whether the operation changes the data contained in the table
+        data_change: bool,
+    },
 }
 
 impl DeltaOperation {
@@ -414,11 +447,39 @@ impl DeltaOperation {
             DeltaOperation::UpdateFieldMetadata { .. } => "UPDATE FIELD METADATA",
             DeltaOperation::UpdateTableMetadata { .. } => "UPDATE TABLE METADATA",
             DeltaOperation::DropColumnNotNull { .. } => "CHANGE COLUMN",
+            DeltaOperation::RecomputeStats { .. } => "COMPUTE STATS",
+            DeltaOperation::Custom { name, .. } => name,
+        }
+    }
+
+    /// A custom operation with the given name which changes the data of the table
+    pub fn custom(name: impl Into<String>) -> Self {
+        DeltaOperation::Custom {
+            name: name.into(),
+            parameters: HashMap::new(),
+            predicate: None,
+            data_change: true,
         }
     }
 
     /// Parameters configured for operation.
     pub fn operation_parameters(&self) -> DeltaResult<HashMap<String, Value>> {
+        if let Self::Custom {
+            parameters,
+            predicate,
+            ..
+        } = self
+        {
+            return Ok(parameters
+                .iter()
+                .map(|(key, value)| (key.clone(), Value::String(value.clone())))
+                .chain(
+                    predicate.iter().map(|predicate| {
+                        ("predicate".to_string(), Value::String(predicate.clone()))
+                    }),
+                )
+                .collect());
+        }
         let value = serde_json::to_value(self)?;
         if let Value::Object(mut operation) = value {
             if let Some(Value::Object(parameters)) = operation.values_mut().next() {
@@ -442,6 +503,7 @@ impl DeltaOperation {
             | Self::UpdateFieldMetadata { .. }
             | Self::UpdateTableMetadata { .. }
             | Self::DropColumnNotNull { .. }
+            | Self::RecomputeStats { .. }
             | Self::SetTableProperties { .. }
             | Self::AddColumn { .. }
             | Self::AddFeature { .. }
@@ -457,6 +519,7 @@ impl DeltaOperation {
             | Self::Merge { .. }
             | Self::Update { .. }
             | Self::Restore { .. } => true,
+            Self::Custom { data_change, .. } => *data_change,
         }
     }
 
@@ -479,6 +542,7 @@ impl DeltaOperation {
             Self::Delete { predicate, .. } => predicate.clone(),
             Self::Update { predicate, .. } => predicate.clone(),
             Self::Merge { predicate, .. } => predicate.clone(),
+            Self::Custom { predicate, .. } => predicate.clone(),
             _ => None,
         }
     }
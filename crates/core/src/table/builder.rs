@@ -13,8 +13,14 @@ use url::Url;
 
 use super::normalize_table_url;
 use crate::kernel::Version;
-use crate::logstore::storage::IORuntime;
-use crate::logstore::{LogStoreRef, StorageConfig, object_store_factories};
+use crate::kernel::transaction::{CommitHookRef, MetricsHandlerRef};
+use crate::logstore::storage::{DeltaIOStorageBackend, IORuntime};
+use crate::logstore::{
+    CommitCoordinatorRef, LockProviderRef, LogStoreRef, StorageConfig,
+    StorageCredentialProviderRef, object_store_factories,
+};
+#[cfg(feature = "parquet-encryption")]
+use crate::parquet_encryption::ParquetEncryption;
 use crate::{DeltaResult, DeltaTable, DeltaTableError};
 
 /// possible version specifications for loading a delta table
@@ -62,10 +68,45 @@ pub struct DeltaTableConfig {
     #[serde(default)]
     pub skip_stats: bool,
 
+    /// Skip checkpointing and expired log cleanup after commits made through this table.
+    /// This defaults to `false`.
+    ///
+    /// Use for read replicas or secondary writers when another process is responsible for
+    /// maintaining the log.
+    #[serde(default)]
+    pub skip_post_commit_maintenance: bool,
+
+    /// Upper bound in bytes for the file actions kept in memory by the snapshot.
+    /// This defaults to `None`, i.e. no limit.
+    ///
+    /// The active files are collected batch by batch while the log is replayed. Once they exceed
+    /// the budget, loading the table with files fails with
+    /// [`DeltaTableError::MemoryBudgetExceeded`]. Such tables can still be loaded with
+    /// `require_files` set to `false`, which streams the file actions from the log on demand.
+    ///
+    /// The budget only covers the collected file actions. The files removed by the commits
+    /// since the last checkpoint are tracked in memory by the log replay and are not spilled.
+    #[serde(default)]
+    pub max_materialized_files_bytes: Option<usize>,
+
+    /// Verify the loaded files against the version checksum (`<version>.crc`) of the loaded
+    /// version, if the table wrote one. This defaults to `false`.
+    ///
+    /// Loading fails with [`DeltaTableError::InvalidData`] if the table size, number of files,
+    /// protocol or metadata differ from the checksum. Only applies when files are loaded.
+    #[serde(default)]
+    pub verify_checksum: bool,
+
     #[serde(skip_serializing, skip_deserializing)]
     #[delta(skip)]
     /// When a runtime handler is provided, all IO tasks are spawn in that handle
     pub io_runtime: Option<IORuntime>,
+
+    #[cfg(feature = "parquet-encryption")]
+    #[serde(skip_serializing, skip_deserializing)]
+    #[delta(skip)]
+    /// Encrypt the data files written by operations on the table, and decrypt the files they read
+    pub parquet_encryption: Option<ParquetEncryption>,
 }
 
 impl Default for DeltaTableConfig {
@@ -75,7 +116,12 @@ impl Default for DeltaTableConfig {
             log_buffer_size: num_cpus::get() * 4,
             log_batch_size: 1024,
             skip_stats: false,
+            skip_post_commit_maintenance: false,
+            max_materialized_files_bytes: None,
+            verify_checksum: false,
             io_runtime: None,
+            #[cfg(feature = "parquet-encryption")]
+            parquet_encryption: None,
         }
     }
 }
@@ -86,6 +132,9 @@ impl PartialEq for DeltaTableConfig {
             && self.log_buffer_size == other.log_buffer_size
             && self.log_batch_size == other.log_batch_size
             && self.skip_stats == other.skip_stats
+            && self.skip_post_commit_maintenance == other.skip_post_commit_maintenance
+            && self.max_materialized_files_bytes == other.max_materialized_files_bytes
+            && self.verify_checksum == other.verify_checksum
     }
 }
 
@@ -101,6 +150,11 @@ pub struct DeltaTableBuilder {
     version: DeltaVersion,
     storage_options: Option<HashMap<String, String>>,
     allow_http: Option<bool>,
+    credential_provider: Option<StorageCredentialProviderRef>,
+    lock_provider: Option<LockProviderRef>,
+    commit_coordinator: Option<CommitCoordinatorRef>,
+    commit_hooks: Vec<CommitHookRef>,
+    metrics_handler: Option<MetricsHandlerRef>,
     table_config: DeltaTableConfig,
 }
 
@@ -130,10 +184,28 @@ impl DeltaTableBuilder {
             version: DeltaVersion::default(),
             storage_options: None,
             allow_http: None,
+            credential_provider: None,
+            lock_provider: None,
+            commit_coordinator: None,
+            commit_hooks: Vec::new(),
+            metrics_handler: None,
             table_config: DeltaTableConfig::default(),
         })
     }
 
+    /// Creates `DeltaTableBuilder` for a table registered in a data catalog
+    ///
+    /// The table is addressed by its name within the catalog, e.g. `prod.analytics.events` in
+    /// `unity`, and opened through the handler registered for the catalog, which resolves the
+    /// storage location and acquires the credentials to access it. This is the same as passing a
+    /// `uc://`, `glue://` or `hms://` URL to [`DeltaTableBuilder::from_url`].
+    ///
+    /// The catalog handlers are registered by their crates, e.g. via the `unity-experimental`,
+    /// `glue` and `hms` features of the `deltalake` crate.
+    pub fn from_catalog(catalog: &str, table_name: &str) -> DeltaResult<Self> {
+        Self::from_url(catalog_table_url(catalog, table_name)?)
+    }
+
     /// Sets `require_files=false` to the builder
     pub fn without_files(mut self) -> Self {
         self.table_config.require_files = false;
@@ -147,6 +219,26 @@ impl DeltaTableBuilder {
         self
     }
 
+    /// Sets `skip_post_commit_maintenance` to the builder. See
+    /// [`DeltaTableConfig::skip_post_commit_maintenance`].
+    pub fn with_skip_post_commit_maintenance(mut self, skip: bool) -> Self {
+        self.table_config.skip_post_commit_maintenance = skip;
+        self
+    }
+
+    /// Sets `max_materialized_files_bytes` to the builder. See
+    /// [`DeltaTableConfig::max_materialized_files_bytes`].
+    pub fn with_max_materialized_files_bytes(mut self, max_bytes: usize) -> Self {
+        self.table_config.max_materialized_files_bytes = Some(max_bytes);
+        self
+    }
+
+    /// Sets `verify_checksum` to the builder. See [`DeltaTableConfig::verify_checksum`].
+    pub fn with_verify_checksum(mut self, verify: bool) -> Self {
+        self.table_config.verify_checksum = verify;
+        self
+    }
+
     /// Sets `version` to the builder
     pub fn with_version(mut self, version: Version) -> Self {
         self.version = DeltaVersion::Version(version);
@@ -236,12 +328,62 @@ impl DeltaTableBuilder {
         self
     }
 
-    /// Provide a custom runtime handle or runtime config
+    /// Provide a custom runtime handle or runtime config.
+    ///
+    /// The object store requests of the table, including those of all operations run on it, are
+    /// executed on this runtime rather than the one driving the operation.
     pub fn with_io_runtime(mut self, io_runtime: IORuntime) -> Self {
         self.table_config.io_runtime = Some(io_runtime);
         self
     }
 
+    /// Encrypt the data files written by operations on the table with `encryption`.
+    ///
+    /// Operations reading data files, e.g. merge, update or optimize, decrypt them with the key
+    /// management client of `encryption`.
+    #[cfg(feature = "parquet-encryption")]
+    pub fn with_parquet_encryption(mut self, encryption: ParquetEncryption) -> Self {
+        self.table_config.parquet_encryption = Some(encryption);
+        self
+    }
+
+    /// Provide a callback to obtain fresh storage credentials before the current ones expire.
+    ///
+    /// The returned credentials are merged on top of the storage options, see
+    /// [`StorageCredentialProvider`](crate::logstore::StorageCredentialProvider).
+    pub fn with_credential_provider(mut self, provider: StorageCredentialProviderRef) -> Self {
+        self.credential_provider = Some(provider);
+        self
+    }
+
+    /// Serialize commits through a distributed lock, for storage which lacks atomic
+    /// put-if-absent, see [`LockProvider`](crate::logstore::LockProvider).
+    pub fn with_lock_provider(mut self, provider: LockProviderRef) -> Self {
+        self.lock_provider = Some(provider);
+        self
+    }
+
+    /// Route commits through an external authority, see
+    /// [`CommitCoordinator`](crate::logstore::CommitCoordinator).
+    pub fn with_commit_coordinator(mut self, coordinator: CommitCoordinatorRef) -> Self {
+        self.commit_coordinator = Some(coordinator);
+        self
+    }
+
+    /// Attach a [`CommitHook`](crate::kernel::transaction::CommitHook) invoked around every
+    /// commit made through the built table.
+    pub fn with_commit_hook(mut self, hook: CommitHookRef) -> Self {
+        self.commit_hooks.push(hook);
+        self
+    }
+
+    /// Attach a [`MetricsHandler`](crate::kernel::transaction::MetricsHandler) receiving the
+    /// metrics of every operation committed through the built table.
+    pub fn with_metrics_handler(mut self, handler: MetricsHandlerRef) -> Self {
+        self.metrics_handler = Some(handler);
+        self
+    }
+
     /// Storage options for configuring backend object store
     pub fn storage_options(&self) -> HashMap<String, String> {
         let mut storage_options = self.storage_options.clone().unwrap_or_default();
@@ -262,10 +404,33 @@ impl DeltaTableBuilder {
         if let Some(io_runtime) = self.table_config.io_runtime.clone() {
             storage_config = storage_config.with_io_runtime(io_runtime);
         }
+        if let Some(provider) = self.credential_provider.clone() {
+            storage_config = storage_config.with_credential_provider(provider);
+        }
+        if let Some(provider) = self.lock_provider.clone() {
+            storage_config = storage_config.with_lock_provider(provider);
+        }
+        if let Some(coordinator) = self.commit_coordinator.clone() {
+            storage_config = storage_config.with_commit_coordinator(coordinator);
+        }
+        for hook in &self.commit_hooks {
+            storage_config = storage_config.with_commit_hook(hook.clone());
+        }
+        if let Some(handler) = self.metrics_handler.clone() {
+            storage_config = storage_config.with_metrics_handler(handler);
+        }
 
         if let Some((store, _url)) = self.storage_backend.as_ref() {
             debug!("Loading a logstore with a custom store: {store:?}");
-            crate::logstore::logstore_with(store.clone(), &self.table_url, storage_config)
+            // stores of the registered factories apply the IO runtime themselves
+            let store = match &storage_config.runtime {
+                Some(runtime) => {
+                    Arc::new(DeltaIOStorageBackend::new(store.clone(), runtime.clone()))
+                        as Arc<DynObjectStore>
+                }
+                None => store.clone(),
+            };
+            crate::logstore::logstore_with(store, &self.table_url, storage_config)
         } else {
             // If there has been no backend defined just default to the normal logstore look up
             debug!(
@@ -297,6 +462,29 @@ impl DeltaTableBuilder {
     }
 }
 
+/// The URL addressing `table_name` in `catalog`, e.g. `uc://prod.analytics.events`
+fn catalog_table_url(catalog: &str, table_name: &str) -> DeltaResult<Url> {
+    let catalog = catalog.to_ascii_lowercase();
+    let scheme = match catalog.as_str() {
+        "unity" => "uc",
+        "hive" => "hms",
+        other => other,
+    };
+    let registered = Url::parse(&format!("{scheme}://"))
+        .map(|url| object_store_factories().contains_key(&url))
+        .unwrap_or(false);
+    if !registered {
+        return Err(DeltaTableError::InvalidTableLocation(format!(
+            "No handler is registered for the {catalog} catalog, is its crate or feature enabled?"
+        )));
+    }
+    Url::parse(&format!("{scheme}://{table_name}")).map_err(|err| {
+        DeltaTableError::InvalidTableLocation(format!(
+            "Invalid name {table_name} of a table in the {catalog} catalog: {err}"
+        ))
+    })
+}
+
 enum UriType {
     LocalPath(PathBuf),
     Url(Url),
@@ -795,6 +983,20 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_builder_from_catalog() {
+        object_store_factories().insert(
+            Url::parse("testcatalog://").unwrap(),
+            Arc::new(DefaultObjectStoreFactory::default()),
+        );
+        let builder = DeltaTableBuilder::from_catalog("TestCatalog", "database.table").unwrap();
+        assert_eq!(builder.table_url.as_str(), "testcatalog://database.table");
+
+        // the handlers of the catalogs are not registered by the core crate
+        let err = DeltaTableBuilder::from_catalog("unity", "prod.analytics.events").unwrap_err();
+        assert!(matches!(err, DeltaTableError::InvalidTableLocation(_)));
+    }
+
     #[test]
     fn test_create_builder_from_non_existent_path() {
         let tmp_dir = tempfile::tempdir().unwrap();
@@ -1,7 +1,7 @@
-//! Constraints and generated column mappings
+//! Constraints, generated column and column default mappings
 use serde::{Deserialize, Serialize};
 
-use crate::kernel::DataType;
+use crate::kernel::{DataType, MetadataValue, StructField};
 use crate::table::DataCheck;
 use std::any::Any;
 
@@ -81,3 +81,43 @@ impl DataCheck for GeneratedColumn {
         self
     }
 }
+
+/// Column metadata key holding the SQL expression of a column's default value.
+pub const COLUMN_DEFAULT_KEY: &str = "CURRENT_DEFAULT";
+
+/// A column with a default value, filled in by writers when the column is omitted
+#[derive(Eq, PartialEq, Debug, Clone)]
+pub struct ColumnDefault {
+    /// The name of the column.
+    pub name: String,
+    /// The SQL string that produces the default value.
+    pub default_expr: String,
+    /// Data Type
+    pub data_type: DataType,
+}
+
+impl ColumnDefault {
+    /// Create a new column default
+    pub fn new(field_name: &str, default_expr: &str, data_type: &DataType) -> Self {
+        Self {
+            name: field_name.to_string(),
+            default_expr: default_expr.to_string(),
+            data_type: data_type.clone(),
+        }
+    }
+
+    /// Returns the SQL expression used as this column's default value.
+    pub fn get_default_expression(&self) -> &str {
+        &self.default_expr
+    }
+}
+
+/// Store `default_expr` as the default value of `field` in its column metadata.
+pub(crate) fn with_column_default(field: StructField, default_expr: &str) -> StructField {
+    let mut metadata = field.metadata().clone();
+    metadata.insert(
+        COLUMN_DEFAULT_KEY.to_string(),
+        MetadataValue::String(default_expr.to_string()),
+    );
+    field.with_metadata(metadata)
+}
@@ -8,6 +8,7 @@ use delta_kernel::table_properties::{DataSkippingNumIndexedCols, IsolationLevel,
 
 use super::Constraint;
 use crate::errors::DeltaTableError;
+use crate::kernel::transaction::{BackoffStrategy, DEFAULT_MAX_BACKOFF, Jitter, RetryPolicy};
 
 /// Typed property keys that can be defined on a delta table
 ///
@@ -119,6 +120,37 @@ pub enum TableProperty {
 
     /// 'classic' for classic Delta Lake checkpoints. 'v2' for v2 checkpoints.
     CheckpointPolicy,
+
+    /// true for Delta Lake to update the `_symlink_format_manifest` files after every commit,
+    /// so that engines such as Presto or Athena always read the latest version of the table.
+    SymlinkFormatManifestEnabled,
+
+    /// true for delta-rs to write a `<version>.crc` version checksum file after every commit.
+    WriteChecksumFile,
+
+    /// Default number of times delta-rs retries a commit which conflicts with concurrent writers.
+    CommitMaxRetries,
+
+    /// Initial delay between commit retries, doubled after every attempt, e.g. `interval 100 milliseconds`.
+    CommitRetryBackoff,
+
+    /// Upper bound for the delay between commit retries.
+    CommitRetryMaxBackoff,
+
+    /// Time after which delta-rs stops retrying a commit.
+    CommitRetryDeadline,
+
+    /// Maximum age of the latest checkpoint before delta-rs writes a new one after a commit,
+    /// in addition to `delta.checkpointInterval`, e.g. `interval 10 minutes`.
+    CheckpointIntervalDuration,
+
+    /// Maximum number of actions per checkpoint file. Larger checkpoints are written as
+    /// multi-part checkpoints whose parts are written in parallel.
+    CheckpointPartSize,
+
+    /// Comma-separated list of columns for which delta-rs collects approximate distinct counts
+    /// in the file statistics.
+    DataSkippingDistinctCountColumns,
 }
 
 impl AsRef<str> for TableProperty {
@@ -148,6 +180,17 @@ impl AsRef<str> for TableProperty {
             Self::SetTransactionRetentionDuration => "delta.setTransactionRetentionDuration",
             Self::TargetFileSize => "delta.targetFileSize",
             Self::TuneFileSizesForRewrites => "delta.tuneFileSizesForRewrites",
+            Self::SymlinkFormatManifestEnabled => {
+                "delta.compatibility.symlinkFormatManifest.enabled"
+            }
+            Self::WriteChecksumFile => "delta-rs.writeChecksumFile",
+            Self::CommitMaxRetries => "delta-rs.commit.maxRetries",
+            Self::CommitRetryBackoff => "delta-rs.commit.retryBackoff",
+            Self::CommitRetryMaxBackoff => "delta-rs.commit.retryMaxBackoff",
+            Self::CommitRetryDeadline => "delta-rs.commit.retryDeadline",
+            Self::CheckpointIntervalDuration => "delta-rs.checkpointIntervalDuration",
+            Self::CheckpointPartSize => "delta-rs.checkpointPartSize",
+            Self::DataSkippingDistinctCountColumns => "delta-rs.dataSkippingDistinctCountColumns",
         }
     }
 }
@@ -185,6 +228,19 @@ impl FromStr for TableProperty {
             "delta.setTransactionRetentionDuration" => Ok(Self::SetTransactionRetentionDuration),
             "delta.targetFileSize" => Ok(Self::TargetFileSize),
             "delta.tuneFileSizesForRewrites" => Ok(Self::TuneFileSizesForRewrites),
+            "delta.compatibility.symlinkFormatManifest.enabled" => {
+                Ok(Self::SymlinkFormatManifestEnabled)
+            }
+            "delta-rs.writeChecksumFile" => Ok(Self::WriteChecksumFile),
+            "delta-rs.commit.maxRetries" => Ok(Self::CommitMaxRetries),
+            "delta-rs.commit.retryBackoff" => Ok(Self::CommitRetryBackoff),
+            "delta-rs.commit.retryMaxBackoff" => Ok(Self::CommitRetryMaxBackoff),
+            "delta-rs.commit.retryDeadline" => Ok(Self::CommitRetryDeadline),
+            "delta-rs.checkpointIntervalDuration" => Ok(Self::CheckpointIntervalDuration),
+            "delta-rs.checkpointPartSize" => Ok(Self::CheckpointPartSize),
+            "delta-rs.dataSkippingDistinctCountColumns" => {
+                Ok(Self::DataSkippingDistinctCountColumns)
+            }
             _ => Err(DeltaTableError::Generic("unknown config key".into())),
         }
     }
@@ -245,6 +301,24 @@ pub trait TablePropertiesExt {
 
     /// The list of constraints (e.g. CHECK constraints) declared on the table.
     fn get_constraints(&self) -> Vec<Constraint>;
+
+    /// Whether the `_symlink_format_manifest` should be regenerated after every commit.
+    fn symlink_format_manifest_enabled(&self) -> bool;
+
+    /// Whether a version checksum file should be written after every commit.
+    fn write_checksum_file(&self) -> bool;
+
+    /// The table's default commit retry policy, if any of its properties are set.
+    fn commit_retry_policy(&self) -> Option<RetryPolicy>;
+
+    /// Maximum age of the latest checkpoint before a commit triggers a new one, if set.
+    fn checkpoint_interval_duration(&self) -> Option<Duration>;
+
+    /// Maximum number of actions per checkpoint file, if checkpoints should be split into parts.
+    fn checkpoint_part_size(&self) -> Option<NonZero<usize>>;
+
+    /// Columns for which approximate distinct counts are collected, if any.
+    fn distinct_count_columns(&self) -> Option<Vec<String>>;
 }
 
 impl TablePropertiesExt for TableProperties {
@@ -309,6 +383,73 @@ impl TablePropertiesExt for TableProperties {
             })
             .collect()
     }
+
+    fn symlink_format_manifest_enabled(&self) -> bool {
+        // TODO: upstream parsing of the compatibility properties to delta-kernel
+        self.unknown_properties
+            .get(TableProperty::SymlinkFormatManifestEnabled.as_ref())
+            .and_then(|value| value.to_ascii_lowercase().parse().ok())
+            .unwrap_or(false)
+    }
+
+    fn write_checksum_file(&self) -> bool {
+        self.unknown_properties
+            .get(TableProperty::WriteChecksumFile.as_ref())
+            .and_then(|value| value.to_ascii_lowercase().parse().ok())
+            .unwrap_or(false)
+    }
+
+    fn commit_retry_policy(&self) -> Option<RetryPolicy> {
+        let get = |key: TableProperty| self.unknown_properties.get(key.as_ref());
+        let interval = |key: TableProperty| get(key).and_then(|value| parse_interval(value).ok());
+
+        let max_retries = get(TableProperty::CommitMaxRetries).and_then(|value| value.parse().ok());
+        let backoff = interval(TableProperty::CommitRetryBackoff);
+        let max_backoff = interval(TableProperty::CommitRetryMaxBackoff);
+        let deadline = interval(TableProperty::CommitRetryDeadline);
+        if max_retries.is_none() && backoff.is_none() && deadline.is_none() {
+            return None;
+        }
+
+        let mut policy = RetryPolicy::default();
+        if let Some(max_retries) = max_retries {
+            policy = policy.with_max_retries(max_retries);
+        }
+        if let Some(initial) = backoff {
+            let max = max_backoff.unwrap_or(DEFAULT_MAX_BACKOFF).max(initial);
+            policy = policy
+                .with_backoff(BackoffStrategy::Exponential { initial, max })
+                .with_jitter(Jitter::Full);
+        }
+        if let Some(deadline) = deadline {
+            policy = policy.with_deadline(deadline);
+        }
+        Some(policy)
+    }
+
+    fn checkpoint_interval_duration(&self) -> Option<Duration> {
+        self.unknown_properties
+            .get(TableProperty::CheckpointIntervalDuration.as_ref())
+            .and_then(|value| parse_interval(value).ok())
+    }
+
+    fn checkpoint_part_size(&self) -> Option<NonZero<usize>> {
+        self.unknown_properties
+            .get(TableProperty::CheckpointPartSize.as_ref())
+            .and_then(|value| value.parse().ok())
+    }
+
+    fn distinct_count_columns(&self) -> Option<Vec<String>> {
+        self.unknown_properties
+            .get(TableProperty::DataSkippingDistinctCountColumns.as_ref())
+            .map(|value| {
+                value
+                    .split(',')
+                    .map(|column| column.trim().to_string())
+                    .filter(|column| !column.is_empty())
+                    .collect()
+            })
+    }
 }
 
 const SECONDS_PER_MINUTE: u64 = 60;
@@ -480,4 +621,60 @@ mod tests {
             )
         );
     }
+
+    #[test]
+    fn checkpoint_interval_duration_test() {
+        let mut properties = TableProperties::default();
+        assert_eq!(properties.checkpoint_interval_duration(), None);
+
+        properties.unknown_properties.insert(
+            TableProperty::CheckpointIntervalDuration
+                .as_ref()
+                .to_string(),
+            "interval 15 minutes".to_string(),
+        );
+        assert_eq!(
+            properties.checkpoint_interval_duration(),
+            Some(Duration::from_secs(900))
+        );
+    }
+
+    #[test]
+    fn commit_retry_policy_test() {
+        let properties = |entries: &[(TableProperty, &str)]| TableProperties {
+            unknown_properties: entries
+                .iter()
+                .map(|(key, value)| (key.as_ref().to_string(), value.to_string()))
+                .collect(),
+            ..Default::default()
+        };
+
+        assert_eq!(properties(&[]).commit_retry_policy(), None);
+
+        let policy = properties(&[(TableProperty::CommitMaxRetries, "50")])
+            .commit_retry_policy()
+            .unwrap();
+        assert_eq!(policy, RetryPolicy::new(50));
+
+        let policy = properties(&[
+            (
+                TableProperty::CommitRetryBackoff,
+                "interval 100 milliseconds",
+            ),
+            (TableProperty::CommitRetryMaxBackoff, "interval 5 seconds"),
+            (TableProperty::CommitRetryDeadline, "interval 2 minutes"),
+        ])
+        .commit_retry_policy()
+        .unwrap();
+        assert_eq!(policy.max_retries(), RetryPolicy::default().max_retries());
+        assert_eq!(
+            policy.backoff(),
+            BackoffStrategy::Exponential {
+                initial: Duration::from_millis(100),
+                max: Duration::from_secs(5),
+            }
+        );
+        assert_eq!(policy.jitter(), Jitter::Full);
+        assert_eq!(policy.deadline(), Some(Duration::from_secs(120)));
+    }
 }
@@ -4,6 +4,7 @@ use std::cmp::{Ordering, min};
 use std::fmt;
 use std::fmt::Formatter;
 use std::sync::Arc;
+use std::time::Duration;
 
 use chrono::{DateTime, Utc};
 use futures::future::ready;
@@ -23,11 +24,13 @@ use crate::logstore::{
     extract_version_from_filename,
 };
 use crate::partitions::PartitionFilter;
+use crate::protocol::checksum::{VersionChecksum, read_checksum};
 use crate::{DeltaResult, DeltaTableBuilder, DeltaTableError};
 
 mod blind;
 pub mod builder;
 pub mod config;
+pub mod source;
 pub mod state;
 
 mod columns;
@@ -180,6 +183,15 @@ impl DeltaTable {
             .await
     }
 
+    /// Returns the latest available version of the table using a bounded listing.
+    ///
+    /// Unlike [`DeltaTable::get_latest_version`], this only lists `_delta_log` entries at or
+    /// after the `_last_checkpoint` hint (or the currently loaded version), which keeps the
+    /// cost low for tables with very long logs. The loaded state is not modified.
+    pub async fn peek_latest_version(&self) -> Result<Version, DeltaTableError> {
+        crate::logstore::peek_latest_version(self.log_store.as_ref(), self.version()).await
+    }
+
     /// Currently loaded version of the table - if any.
     ///
     /// This will return the latest version of the table if it has been loaded.
@@ -341,6 +353,23 @@ impl DeltaTable {
             .collect())
     }
 
+    /// Returns pre-signed GET URLs for the active files matching the provided `PartitionFilter`s.
+    ///
+    /// The URLs are valid for `expires_in`, see
+    /// [`LogStore::presigned_urls`](crate::logstore::LogStore::presigned_urls).
+    pub async fn get_presigned_file_urls_by_partitions(
+        &self,
+        filters: &[PartitionFilter],
+        expires_in: Duration,
+    ) -> DeltaResult<Vec<Url>> {
+        let files: Vec<String> = self
+            .get_active_add_actions_by_partitions(filters)
+            .map_ok(|file| file.path().into_owned())
+            .try_collect()
+            .await?;
+        self.log_store.presigned_urls(&files, expires_in).await
+    }
+
     /// Returns a URIs for all active files present in the current table version.
     pub fn get_file_uris(&self) -> DeltaResult<impl Iterator<Item = String> + '_> {
         Ok(self
@@ -368,6 +397,17 @@ impl DeltaTable {
         self.state.as_ref().ok_or(DeltaTableError::NotInitialized)
     }
 
+    /// Read the version checksum (`<version>.crc`) of the currently loaded version, if present.
+    ///
+    /// The checksum provides the table size and number of files without loading the file
+    /// actions. Use [`VersionChecksum::verify`] to validate a loaded state against it.
+    ///
+    /// [`VersionChecksum::verify`]: crate::protocol::checksum::VersionChecksum::verify
+    pub async fn version_checksum(&self) -> DeltaResult<Option<VersionChecksum>> {
+        let version = self.version().ok_or(DeltaTableError::NotInitialized)?;
+        read_checksum(self.log_store.as_ref(), version).await
+    }
+
     /// Time travel Delta table to the latest version that's created at or before provided
     /// `datetime` argument.
     ///
@@ -614,6 +654,40 @@ mod tests {
         assert!(result.is_ok());
     }
 
+    #[tokio::test]
+    async fn peek_latest_version_matches_listing() {
+        let (mut dt, _tmp_dir) = create_test_table().await;
+        assert_eq!(dt.peek_latest_version().await.unwrap(), 0);
+
+        let table = DeltaTableBuilder::from_url(dt.table_url().clone())
+            .unwrap()
+            .load()
+            .await
+            .unwrap();
+        let table = table
+            .set_tbl_properties()
+            .with_properties(
+                [(
+                    "delta.logRetentionDuration".to_string(),
+                    "interval 30 days".to_string(),
+                )]
+                .into(),
+            )
+            .await
+            .unwrap();
+        crate::checkpoints::create_checkpoint(&table, None)
+            .await
+            .unwrap();
+        assert_eq!(dt.peek_latest_version().await.unwrap(), 1);
+        assert_eq!(dt.version(), Some(0));
+
+        dt.load().await.unwrap();
+        assert_eq!(
+            dt.peek_latest_version().await.unwrap(),
+            dt.get_latest_version().await.unwrap()
+        );
+    }
+
     async fn create_test_table() -> (DeltaTable, TempDir) {
         let tmp_dir = tempfile::tempdir().unwrap();
         let table_dir = tmp_dir.path().join("test_create");
@@ -0,0 +1,273 @@
+//! Incremental source for streaming frameworks consuming the files appended to a Delta table.
+//!
+//! A [`DeltaSource`] identifies its position in the table with a [`DeltaSourceOffset`]: the
+//! table version and the index of a file among the files added by that version. Offsets are
+//! derived from the log only, so reading between the same two offsets always returns the same
+//! files, and frameworks can checkpoint the offset to resume where they left off.
+//!
+//! # Example
+//! ```rust ignore
+//! let source = DeltaSource::new(table.log_store(), 0).with_max_files_per_trigger(10);
+//! let mut offset = None;
+//! while let Some(end) = source.latest_offset(offset.as_ref()).await? {
+//!     for file in source.read_between(offset.as_ref(), &end).await? {
+//!         // read `file.add.path` relative to the table root
+//!     }
+//!     offset = Some(end);
+//! }
+//! ```
+
+use serde::{Deserialize, Serialize};
+
+use crate::kernel::{Action, Add, Version};
+use crate::logstore::{LogStoreRef, get_actions};
+use crate::{DeltaResult, DeltaTableError};
+
+/// Position of a [`DeltaSource`] in the table log.
+///
+/// Offsets are ordered by version first, then by index.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DeltaSourceOffset {
+    /// Table version of the last consumed file
+    pub version: Version,
+    /// Index of the last consumed file among the files added by `version`, `-1` if none was
+    pub index: i64,
+}
+
+impl DeltaSourceOffset {
+    fn new(version: Version, index: i64) -> Self {
+        Self { version, index }
+    }
+}
+
+/// A data file appended to the table, as returned by [`DeltaSource::read_between`]
+#[derive(Debug, Clone, PartialEq)]
+pub struct DeltaSourceFile {
+    /// Offset of the file
+    pub offset: DeltaSourceOffset,
+    /// The add action of the file
+    pub add: Add,
+}
+
+/// Reads the files appended to a table incrementally, see the [module documentation](self).
+#[derive(Debug, Clone)]
+pub struct DeltaSource {
+    log_store: LogStoreRef,
+    starting_version: Version,
+    max_files_per_trigger: Option<usize>,
+    max_bytes_per_trigger: Option<u64>,
+    ignore_changes: bool,
+}
+
+impl DeltaSource {
+    /// Read the files added by commits from `starting_version` (inclusive) on
+    pub fn new(log_store: LogStoreRef, starting_version: Version) -> Self {
+        Self {
+            log_store,
+            starting_version,
+            max_files_per_trigger: None,
+            max_bytes_per_trigger: None,
+            ignore_changes: false,
+        }
+    }
+
+    /// Maximum number of files between two offsets returned by [`Self::latest_offset`]
+    pub fn with_max_files_per_trigger(mut self, max_files: usize) -> Self {
+        self.max_files_per_trigger = Some(max_files.max(1));
+        self
+    }
+
+    /// Soft limit of the total size of the files between two offsets returned by
+    /// [`Self::latest_offset`]. At least one file is admitted, even if it exceeds the limit.
+    pub fn with_max_bytes_per_trigger(mut self, max_bytes: u64) -> Self {
+        self.max_bytes_per_trigger = Some(max_bytes);
+        self
+    }
+
+    /// Emit the files added by commits which also remove data, e.g. updates, instead of failing.
+    ///
+    /// Removed files are ignored, so rewritten rows are read again.
+    pub fn with_ignore_changes(mut self, ignore_changes: bool) -> Self {
+        self.ignore_changes = ignore_changes;
+        self
+    }
+
+    /// The offset up to which the next batch should read, starting after `start`.
+    ///
+    /// The batch is bounded by the rate limits of the source. Returns `None` if there were no
+    /// new commits since `start`; pass `None` as `start` to begin at the starting version.
+    pub async fn latest_offset(
+        &self,
+        start: Option<&DeltaSourceOffset>,
+    ) -> DeltaResult<Option<DeltaSourceOffset>> {
+        let start = self.resolve_start(start);
+        let latest_version = match self.log_store.get_latest_version(start.version).await {
+            Ok(version) => version,
+            Err(DeltaTableError::InvalidVersion(_)) => return Ok(None),
+            Err(err) => return Err(err),
+        };
+
+        let mut end = start;
+        let mut num_files = 0;
+        let mut num_bytes = 0;
+        for version in start.version..=latest_version {
+            let adds = self.version_files(version).await?;
+            for (index, add) in adds.iter().enumerate() {
+                let offset = DeltaSourceOffset::new(version, index as i64);
+                if offset <= start {
+                    continue;
+                }
+                let files_exceeded = self
+                    .max_files_per_trigger
+                    .is_some_and(|max_files| num_files >= max_files);
+                let bytes_exceeded = self
+                    .max_bytes_per_trigger
+                    .is_some_and(|max_bytes| num_files > 0 && num_bytes >= max_bytes);
+                if files_exceeded || bytes_exceeded {
+                    return Ok(Some(end).filter(|end| *end > start));
+                }
+                num_files += 1;
+                num_bytes += add.size.max(0) as u64;
+                end = offset;
+            }
+            // versions without files are consumed as a whole
+            if adds.is_empty() {
+                end = end.max(DeltaSourceOffset::new(version, -1));
+            }
+        }
+        Ok(Some(end).filter(|end| *end > start))
+    }
+
+    /// The files after `start` up to and including `end`.
+    ///
+    /// Pass `None` as `start` to read from the starting version of the source.
+    pub async fn read_between(
+        &self,
+        start: Option<&DeltaSourceOffset>,
+        end: &DeltaSourceOffset,
+    ) -> DeltaResult<Vec<DeltaSourceFile>> {
+        let start = self.resolve_start(start);
+        let mut files = Vec::new();
+        for version in start.version..=end.version {
+            for (index, add) in self.version_files(version).await?.into_iter().enumerate() {
+                let offset = DeltaSourceOffset::new(version, index as i64);
+                if offset > start && offset <= *end {
+                    files.push(DeltaSourceFile { offset, add });
+                }
+            }
+        }
+        Ok(files)
+    }
+
+    fn resolve_start(&self, start: Option<&DeltaSourceOffset>) -> DeltaSourceOffset {
+        start
+            .copied()
+            .unwrap_or(DeltaSourceOffset::new(self.starting_version, -1))
+    }
+
+    /// The files added by `version`, in the order of the commit
+    async fn version_files(&self, version: Version) -> DeltaResult<Vec<Add>> {
+        let bytes = self
+            .log_store
+            .read_commit_entry(version)
+            .await?
+            .ok_or(DeltaTableError::InvalidVersion(version))?;
+        let mut adds = Vec::new();
+        for action in get_actions(version, &bytes)? {
+            match action {
+                Action::Add(add) if add.data_change => adds.push(add),
+                Action::Remove(remove) if remove.data_change && !self.ignore_changes => {
+                    return Err(DeltaTableError::Generic(format!(
+                        "Version {version} removes data file {}, which the source cannot \
+                         emit. Set ignore_changes to read the files it adds anyway",
+                        remove.path
+                    )));
+                }
+                _ => {}
+            }
+        }
+        Ok(adds)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::DeltaTable;
+    use crate::kernel::Remove;
+    use crate::kernel::transaction::CommitBuilder;
+    use crate::protocol::DeltaOperation;
+    use crate::writer::test_utils::{get_delta_schema, get_record_batch};
+    use crate::writer::{DeltaWriter as _, RecordBatchWriter};
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn test_delta_source() -> DeltaResult<()> {
+        let mut table = DeltaTable::new_in_memory()
+            .create()
+            .with_columns(get_delta_schema().fields().cloned())
+            .await?;
+        for _ in 0..3 {
+            let mut writer = RecordBatchWriter::for_table(&table)?;
+            writer.write(get_record_batch(None, false)).await?;
+            writer.flush_and_commit(&mut table).await?;
+        }
+        assert_eq!(table.version(), Some(3));
+
+        let source = DeltaSource::new(table.log_store(), 0).with_max_files_per_trigger(2);
+        let first = source.latest_offset(None).await?.unwrap();
+        assert_eq!(first, DeltaSourceOffset::new(2, 0));
+        let files = source.read_between(None, &first).await?;
+        assert_eq!(
+            files
+                .iter()
+                .map(|file| file.offset.version)
+                .collect::<Vec<_>>(),
+            vec![1, 2]
+        );
+        // offsets are reproducible
+        assert_eq!(source.read_between(None, &first).await?, files);
+
+        let second = source.latest_offset(Some(&first)).await?.unwrap();
+        assert_eq!(second, DeltaSourceOffset::new(3, 0));
+        assert_eq!(source.read_between(Some(&first), &second).await?.len(), 1);
+        assert_eq!(source.latest_offset(Some(&second)).await?, None);
+
+        // the soft byte limit admits one file at a time
+        let source = DeltaSource::new(table.log_store(), 0).with_max_bytes_per_trigger(1);
+        assert_eq!(
+            source.latest_offset(None).await?,
+            Some(DeltaSourceOffset::new(1, 0))
+        );
+
+        // removing data is not an append
+        let remove = Remove {
+            path: files[0].add.path.clone(),
+            deletion_timestamp: Some(0),
+            data_change: true,
+            ..Default::default()
+        };
+        CommitBuilder::default()
+            .with_actions(vec![Action::Remove(remove)])
+            .build(
+                Some(table.snapshot()?),
+                table.log_store(),
+                DeltaOperation::custom("DELETE"),
+            )
+            .await?;
+        let source = DeltaSource::new(table.log_store(), 0);
+        assert!(source.latest_offset(Some(&second)).await.is_err());
+        let source = source.with_ignore_changes(true);
+        assert_eq!(
+            source.latest_offset(Some(&second)).await?,
+            Some(DeltaSourceOffset::new(4, -1))
+        );
+        assert!(
+            source
+                .read_between(Some(&second), &DeltaSourceOffset::new(4, -1))
+                .await?
+                .is_empty()
+        );
+        Ok(())
+    }
+}
@@ -1,5 +1,6 @@
 //! The module for delta table state.
 
+use std::collections::HashMap;
 use std::sync::Arc;
 
 use arrow::array::new_null_array;
@@ -22,10 +23,10 @@ use crate::kernel::arrow::engine_ext::{ExpressionEvaluatorExt, SnapshotExt};
 use crate::kernel::snapshot::FIELD_STATS_PARSED;
 use crate::kernel::{
     ARROW_HANDLER, DataType, EagerSnapshot, LogDataHandler, Metadata, Protocol, Snapshot,
-    TombstoneView, Version,
+    TombstoneView, Transaction, Version,
 };
 use crate::logstore::LogStore;
-use crate::{DeltaResult, DeltaTableError};
+use crate::{DeltaResult, DeltaTableError, PartitionFilter};
 
 /// State snapshot currently held by the Delta Table instance.
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -94,7 +95,6 @@ impl DeltaTableState {
     pub async fn from_actions(actions: Vec<Action>) -> DeltaResult<Self> {
         use crate::kernel::transaction::CommitData;
         use crate::protocol::{DeltaOperation, SaveMode};
-        use std::collections::HashMap;
 
         let metadata = actions
             .iter()
@@ -153,6 +153,41 @@ impl DeltaTableState {
         self.snapshot.transaction_version(log_store, app_id).await
     }
 
+    /// Get the latest transaction for the given application ID.
+    ///
+    /// Returns `None` if the application ID is not found.
+    pub async fn application_transaction(
+        &self,
+        log_store: &dyn LogStore,
+        app_id: impl ToString,
+    ) -> DeltaResult<Option<Transaction>> {
+        self.snapshot
+            .application_transaction(log_store, app_id)
+            .await
+    }
+
+    /// Get the latest transaction of every application ID, keyed by application ID.
+    pub async fn application_transactions(
+        &self,
+        log_store: &dyn LogStore,
+    ) -> DeltaResult<HashMap<String, Transaction>> {
+        self.snapshot.application_transactions(log_store).await
+    }
+
+    /// Statistics of the partitions matching `filters`, aggregated from the add actions.
+    ///
+    /// See [`EagerSnapshot::partition_stats`] for the layout of the returned batch.
+    pub async fn partition_stats(
+        &self,
+        log_store: &dyn LogStore,
+        filters: &[PartitionFilter],
+        columns: &[&str],
+    ) -> DeltaResult<RecordBatch> {
+        self.snapshot
+            .partition_stats(log_store, filters, columns)
+            .await
+    }
+
     /// Obtain the Eager snapshot of the state
     pub fn snapshot(&self) -> &EagerSnapshot {
         &self.snapshot
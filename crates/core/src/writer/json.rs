@@ -2,10 +2,14 @@
 use std::collections::HashMap;
 use std::sync::Arc;
 
-use arrow::datatypes::{Schema as ArrowSchema, SchemaRef as ArrowSchemaRef};
+use arrow::compute::{filter_record_batch, not};
+use arrow::datatypes::{
+    DataType as ArrowDataType, Schema as ArrowSchema, SchemaRef as ArrowSchemaRef,
+};
 use arrow::record_batch::*;
+use arrow_json::reader::infer_json_schema_from_iterator;
 use bytes::Bytes;
-use delta_kernel::engine::arrow_conversion::TryIntoArrow as _;
+use delta_kernel::engine::arrow_conversion::{TryIntoArrow as _, TryIntoKernel as _};
 use delta_kernel::expressions::Scalar;
 use indexmap::IndexMap;
 use itertools::Itertools;
@@ -21,10 +25,12 @@ use super::utils::{
     arrow_schema_without_partitions, next_data_path, record_batch_from_message,
     record_batch_without_partitions,
 };
-use super::{DeltaWriter, DeltaWriterError, WriteMode, ensure_legacy_writer_supports_table};
+use super::{
+    DeltaWriter, DeltaWriterError, WriteMode, WriteValidator, ensure_legacy_writer_supports_table,
+};
 use crate::DeltaTable;
 use crate::errors::DeltaTableError;
-use crate::kernel::{Add, PartitionsExt, scalars::ScalarExt};
+use crate::kernel::{Action, Add, PartitionsExt, StructType, Version, scalars::ScalarExt};
 use crate::logstore::ObjectStoreRetryExt;
 use crate::parquet_utils::default_writer_properties;
 use crate::table::builder::DeltaTableBuilder;
@@ -33,6 +39,23 @@ use crate::writer::utils::ShareableBuffer;
 
 type BadValue = (Value, ParquetError);
 
+/// Defines how the [JsonWriter] handles records which can not be written to the table,
+/// e.g. because a value does not match the type of its column or the record violates a
+/// constraint of the table.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub enum BadRecordPolicy {
+    /// Write all valid records and return an error listing the bad records. Records violating
+    /// a constraint of the table fail the whole write instead.
+    #[default]
+    Fail,
+    /// Write all valid records and discard the bad records
+    Drop,
+    /// Write all valid records and collect the bad records in newline delimited JSON files
+    /// below the given path relative to the table root. The files are written on flush and
+    /// every line holds the original `record` along with the `error` it caused.
+    DeadLetter(Path),
+}
+
 /// Writes messages to a delta lake table.
 #[derive(Debug)]
 pub struct JsonWriter {
@@ -42,6 +65,15 @@ pub struct JsonWriter {
     writer_properties: WriterProperties,
     partition_columns: Vec<String>,
     arrow_writers: HashMap<String, DataArrowWriter>,
+    /// Writers whose schema was superseded by a schema evolution but that still hold data
+    retired_writers: Vec<DataArrowWriter>,
+    /// Whether new fields were added to the schema since the last commit
+    schema_evolved: bool,
+    bad_record_policy: BadRecordPolicy,
+    /// Bad records waiting to be written to the dead letter path
+    dead_letters: Vec<BadValue>,
+    /// Checks written records against the constraints of the table
+    validator: WriteValidator,
 }
 
 /// Writes messages to an underlying arrow buffer.
@@ -58,13 +90,33 @@ pub(crate) struct DataArrowWriter {
 impl DataArrowWriter {
     /// Writes the given JSON buffer and updates internal state accordingly.
     /// This method buffers the write stream internally so it can be invoked for many json buffers and flushed after the appropriate number of bytes has been written.
+    ///
+    /// When `quarantine_invalid_records` is set, records which can not be decoded into the
+    /// schema are reported as a partial write instead of failing the whole buffer.
     async fn write_values(
         &mut self,
         partition_columns: &[String],
         arrow_schema: Arc<ArrowSchema>,
         json_buffer: Vec<Value>,
+        quarantine_invalid_records: bool,
+        validator: &mut WriteValidator,
     ) -> Result<(), DeltaWriterError> {
-        let record_batch = record_batch_from_message(arrow_schema.clone(), json_buffer.as_slice())?;
+        let record_batch =
+            match record_batch_from_message(arrow_schema.clone(), json_buffer.as_slice()) {
+                Ok(record_batch) => record_batch,
+                Err(DeltaTableError::Arrow { source }) if quarantine_invalid_records => {
+                    return self
+                        .write_partial(
+                            partition_columns,
+                            arrow_schema,
+                            json_buffer,
+                            ParquetError::ArrowError(source.to_string()),
+                            validator,
+                        )
+                        .await;
+                }
+                Err(err) => return Err(err.into()),
+            };
 
         if record_batch.schema() != arrow_schema {
             return Err(DeltaWriterError::SchemaMismatch {
@@ -73,16 +125,32 @@ impl DataArrowWriter {
             });
         }
 
+        let (record_batch, json_buffer, violations) = if quarantine_invalid_records {
+            split_invalid_rows(record_batch, json_buffer, validator)?
+        } else {
+            (record_batch, json_buffer, Vec::new())
+        };
+        if record_batch.num_rows() == 0 {
+            return with_violations(Ok(()), violations);
+        }
+
         let result = self
-            .write_record_batch(partition_columns, record_batch)
+            .write_record_batch(partition_columns, record_batch, validator)
             .await;
 
-        if let Err(DeltaWriterError::Parquet { source }) = result {
-            self.write_partial(partition_columns, arrow_schema, json_buffer, source)
-                .await
+        let result = if let Err(DeltaWriterError::Parquet { source }) = result {
+            self.write_partial(
+                partition_columns,
+                arrow_schema,
+                json_buffer,
+                source,
+                validator,
+            )
+            .await
         } else {
             result
-        }
+        };
+        with_violations(result, violations)
     }
 
     async fn write_partial(
@@ -91,17 +159,23 @@ impl DataArrowWriter {
         arrow_schema: Arc<ArrowSchema>,
         json_buffer: Vec<Value>,
         parquet_error: ParquetError,
+        validator: &mut WriteValidator,
     ) -> Result<(), DeltaWriterError> {
         warn!(
             "Failed with parquet error while writing record batch. Attempting quarantine of bad records."
         );
-        let (good, bad) = quarantine_failed_parquet_rows(arrow_schema.clone(), json_buffer)?;
-        let record_batch = record_batch_from_message(arrow_schema, good.as_slice())?;
-        self.write_record_batch(partition_columns, record_batch)
-            .await?;
+        let (good, mut bad) = quarantine_failed_parquet_rows(arrow_schema.clone(), json_buffer)?;
+        if !good.is_empty() {
+            let record_batch = record_batch_from_message(arrow_schema, good.as_slice())?;
+            let (record_batch, _, violations) = split_invalid_rows(record_batch, good, validator)?;
+            bad.extend(violations);
+            if record_batch.num_rows() > 0 {
+                self.write_record_batch(partition_columns, record_batch, validator)
+                    .await?;
+            }
+        }
         info!(
-            "Wrote {} good records to record batch and quarantined {} bad records.",
-            good.len(),
+            "Quarantined {} bad records, the remaining records were written to the record batch.",
             bad.len()
         );
         Err(DeltaWriterError::PartialParquetWrite {
@@ -112,11 +186,17 @@ impl DataArrowWriter {
 
     /// Writes the record batch in-memory and updates internal state accordingly.
     /// This method buffers the write stream internally so it can be invoked for many record batches and flushed after the appropriate number of bytes has been written.
+    ///
+    /// Batches violating the constraints of the table are rejected as a whole, rows to be
+    /// quarantined must be split off with [`split_invalid_rows`] first.
     async fn write_record_batch(
         &mut self,
         partition_columns: &[String],
         record_batch: RecordBatch,
+        validator: &mut WriteValidator,
     ) -> Result<(), DeltaWriterError> {
+        validator.validate(&record_batch)?;
+
         if self.partition_values.is_empty() {
             let partition_values = extract_partition_values(partition_columns, &record_batch)?;
             self.partition_values = partition_values;
@@ -199,6 +279,8 @@ impl JsonWriter {
 
         // Initialize writer properties for the underlying arrow writer
         let writer_properties = default_writer_properties(parquet::basic::Compression::SNAPPY);
+        let validator =
+            WriteValidator::new(Some(table.snapshot()?.snapshot().table_configuration()));
 
         Ok(Self {
             table,
@@ -206,6 +288,11 @@ impl JsonWriter {
             writer_properties,
             partition_columns: partition_columns.unwrap_or_default(),
             arrow_writers: HashMap::new(),
+            retired_writers: Vec::new(),
+            schema_evolved: false,
+            bad_record_policy: BadRecordPolicy::default(),
+            dead_letters: Vec::new(),
+            validator,
         })
     }
 
@@ -226,26 +313,82 @@ impl JsonWriter {
             partition_columns,
             schema_ref: None,
             arrow_writers: HashMap::new(),
+            retired_writers: Vec::new(),
+            schema_evolved: false,
+            bad_record_policy: BadRecordPolicy::default(),
+            dead_letters: Vec::new(),
+            validator: WriteValidator::new(Some(
+                table.snapshot()?.snapshot().table_configuration(),
+            )),
         })
     }
 
+    /// Set how records which can not be written to the table are handled.
+    /// Defaults to [BadRecordPolicy::Fail].
+    pub fn with_bad_record_policy(mut self, policy: BadRecordPolicy) -> Self {
+        self.bad_record_policy = policy;
+        self
+    }
+
     /// Returns the current byte length of the in memory buffer.
     /// This may be used by the caller to decide when to finalize the file write.
     pub fn buffer_len(&self) -> usize {
-        self.arrow_writers.values().map(|w| w.buffer.len()).sum()
+        self.writers().map(|w| w.buffer.len()).sum()
     }
 
     /// Returns the number of records held in the current buffer.
     pub fn buffered_record_batch_count(&self) -> usize {
-        self.arrow_writers
-            .values()
-            .map(|w| w.buffered_record_batch_count)
-            .sum()
+        self.writers().map(|w| w.buffered_record_batch_count).sum()
     }
 
     /// Resets internal state.
     pub fn reset(&mut self) {
         self.arrow_writers.clear();
+        self.retired_writers.clear();
+        self.dead_letters.clear();
+    }
+
+    fn writers(&self) -> impl Iterator<Item = &DataArrowWriter> {
+        self.arrow_writers
+            .values()
+            .chain(self.retired_writers.iter())
+    }
+
+    /// Adds top-level fields of `values` which are not yet part of the schema to the schema.
+    ///
+    /// Fields for which only null values were seen are not added until a value is observed.
+    fn evolve_schema(&mut self, values: &[Value]) -> Result<(), DeltaWriterError> {
+        let current = self.arrow_schema();
+        let inferred = infer_json_schema_from_iterator(values.iter().map(Ok))?;
+        let new_fields = inferred
+            .fields()
+            .iter()
+            .filter(|field| current.field_with_name(field.name()).is_err())
+            .filter(|field| field.data_type() != &ArrowDataType::Null)
+            .map(|field| field.as_ref().clone().with_nullable(true))
+            .collect::<Vec<_>>();
+
+        if new_fields.is_empty() {
+            return Ok(());
+        }
+        debug!(
+            "Adding fields {:?} to the schema of the JsonWriter",
+            new_fields.iter().map(|f| f.name()).collect_vec()
+        );
+
+        let fields = current
+            .fields()
+            .iter()
+            .map(|field| field.as_ref().clone())
+            .chain(new_fields)
+            .collect::<Vec<_>>();
+        let schema = ArrowSchema::new_with_metadata(fields, current.metadata().clone());
+        // Fail early if the inferred types can not be represented in a delta table
+        let _: StructType = (&schema).try_into_kernel()?;
+
+        self.schema_ref = Some(Arc::new(schema));
+        self.schema_evolved = true;
+        Ok(())
     }
 
     /// Returns the user-defined arrow schema representation or the schema defined for the wrapped
@@ -314,30 +457,46 @@ impl DeltaWriter<Vec<Value>> for JsonWriter {
         values: Vec<Value>,
         mode: WriteMode,
     ) -> Result<(), DeltaTableError> {
-        if mode != WriteMode::Default {
-            warn!(
-                "The JsonWriter does not currently support non-default write modes, falling back to default mode"
-            );
+        if mode == WriteMode::MergeSchema {
+            self.evolve_schema(&values)?;
         }
         let mut partial_writes: Vec<(Value, ParquetError)> = Vec::new();
         let arrow_schema = self.arrow_schema();
         let divided = self.divide_by_partition_values(values)?;
         let partition_columns = self.partition_columns.clone();
         let writer_properties = self.writer_properties.clone();
+        let file_schema = arrow_schema_without_partitions(&arrow_schema, &partition_columns);
+        let quarantine_invalid_records = self.bad_record_policy != BadRecordPolicy::Fail;
 
         for (key, values) in divided {
             match self.arrow_writers.get_mut(&key) {
-                Some(writer) => {
+                Some(writer) if writer.arrow_schema == file_schema => {
                     let result = writer
-                        .write_values(&partition_columns, arrow_schema.clone(), values)
+                        .write_values(
+                            &partition_columns,
+                            arrow_schema.clone(),
+                            values,
+                            quarantine_invalid_records,
+                            &mut self.validator,
+                        )
                         .await;
                     collect_partial_write_failure(&mut partial_writes, result)?;
                 }
-                None => {
-                    let schema = arrow_schema_without_partitions(&arrow_schema, &partition_columns);
-                    let mut writer = DataArrowWriter::new(schema, writer_properties.clone())?;
+                _ => {
+                    // Data buffered with a previous schema is kept in its own file
+                    if let Some(writer) = self.arrow_writers.remove(&key) {
+                        self.retired_writers.push(writer);
+                    }
+                    let mut writer =
+                        DataArrowWriter::new(file_schema.clone(), writer_properties.clone())?;
                     let result = writer
-                        .write_values(&partition_columns, arrow_schema.clone(), values)
+                        .write_values(
+                            &partition_columns,
+                            arrow_schema.clone(),
+                            values,
+                            quarantine_invalid_records,
+                            &mut self.validator,
+                        )
                         .await;
                     collect_partial_write_failure(&mut partial_writes, result)?;
                     self.arrow_writers.insert(key, writer);
@@ -345,6 +504,20 @@ impl DeltaWriter<Vec<Value>> for JsonWriter {
             }
         }
 
+        match &self.bad_record_policy {
+            BadRecordPolicy::Fail => {}
+            BadRecordPolicy::Drop => {
+                if !partial_writes.is_empty() {
+                    warn!("Dropped {} bad records", partial_writes.len());
+                }
+                return Ok(());
+            }
+            BadRecordPolicy::DeadLetter(_) => {
+                self.dead_letters.extend(partial_writes);
+                return Ok(());
+            }
+        }
+
         if !partial_writes.is_empty() {
             return Err(DeltaWriterError::PartialParquetWrite {
                 sample_error: match &partial_writes[0].1 {
@@ -374,12 +547,19 @@ impl DeltaWriter<Vec<Value>> for JsonWriter {
     /// the written data files
     #[instrument(skip(self), fields(writer_count = 0))]
     async fn flush(&mut self) -> Result<Vec<Add>, DeltaTableError> {
-        let writers = std::mem::take(&mut self.arrow_writers);
+        self.flush_dead_letters().await?;
+
+        let writers = std::mem::take(&mut self.arrow_writers)
+            .into_values()
+            .chain(std::mem::take(&mut self.retired_writers))
+            // writers only holding bad records do not produce a data file
+            .filter(|writer| writer.buffered_record_batch_count > 0)
+            .collect_vec();
         let mut actions = Vec::with_capacity(writers.len());
 
         Span::current().record("writer_count", writers.len());
 
-        for (_, writer) in writers {
+        for writer in writers {
             let metadata = writer.arrow_writer.close()?;
             let prefix = writer.partition_values.hive_partition_path();
             let prefix = Path::parse(prefix)?;
@@ -413,6 +593,54 @@ impl DeltaWriter<Vec<Value>> for JsonWriter {
         debug!(actions_count = actions.len(), "flush completed");
         Ok(actions)
     }
+
+    /// Flush the internal write buffers to files in the delta table folder structure
+    /// and commit the changes to the Delta log, creating a new table version.
+    ///
+    /// Fields added by [WriteMode::MergeSchema] writes are committed as a new table schema.
+    async fn flush_and_commit(
+        &mut self,
+        table: &mut DeltaTable,
+    ) -> Result<Version, DeltaTableError> {
+        let mut actions: Vec<Action> = self.flush().await?.drain(..).map(Action::Add).collect();
+
+        if self.schema_evolved {
+            let schema: StructType = self.arrow_schema().try_into_kernel()?;
+            let metadata = table.snapshot()?.metadata().clone().with_schema(&schema)?;
+            actions.push(Action::Metadata(metadata));
+        }
+        let version = super::flush_and_commit(actions, table, None).await?;
+        self.schema_evolved = false;
+        Ok(version)
+    }
+}
+
+impl JsonWriter {
+    /// Writes the collected bad records to a new file below the dead letter path.
+    async fn flush_dead_letters(&mut self) -> Result<(), DeltaTableError> {
+        let BadRecordPolicy::DeadLetter(prefix) = &self.bad_record_policy else {
+            return Ok(());
+        };
+        if self.dead_letters.is_empty() {
+            return Ok(());
+        }
+
+        let mut buffer = Vec::new();
+        for (record, error) in &self.dead_letters {
+            let line = serde_json::json!({ "record": record, "error": error.to_string() });
+            serde_json::to_writer(&mut buffer, &line)?;
+            buffer.push(b'\n');
+        }
+        let path = prefix.child(format!("part-{}.json", Uuid::new_v4()));
+
+        debug!(path = %path, records = self.dead_letters.len(), "writing dead letter file");
+        self.table
+            .object_store()
+            .put_with_retries(&path, Bytes::from(buffer).into(), 15)
+            .await?;
+        self.dead_letters.clear();
+        Ok(())
+    }
 }
 
 fn collect_partial_write_failure(
@@ -428,6 +656,65 @@ fn collect_partial_write_failure(
     }
 }
 
+/// Error recorded for records violating the constraints of the table.
+const CONSTRAINT_VIOLATION: &str =
+    "record violates the non-nullable columns, invariants or CHECK constraints of the table";
+
+/// Split the rows violating the constraints of the table off `record_batch` and the `values`
+/// it was decoded from, returning the violating values as bad records.
+fn split_invalid_rows(
+    record_batch: RecordBatch,
+    values: Vec<Value>,
+    validator: &mut WriteValidator,
+) -> Result<(RecordBatch, Vec<Value>, Vec<BadValue>), DeltaWriterError> {
+    let Some(invalid) = validator.invalid_rows(&record_batch)? else {
+        return Ok((record_batch, values, Vec::new()));
+    };
+    let record_batch = filter_record_batch(&record_batch, &not(&invalid)?)?;
+    let (bad, good): (Vec<_>, Vec<_>) = values
+        .into_iter()
+        .zip(invalid.values().iter())
+        .partition(|(_, invalid)| *invalid);
+    let good = good.into_iter().map(|(value, _)| value).collect();
+    let bad = bad
+        .into_iter()
+        .map(|(value, _)| {
+            (
+                value,
+                ParquetError::General(CONSTRAINT_VIOLATION.to_string()),
+            )
+        })
+        .collect();
+    Ok((record_batch, good, bad))
+}
+
+/// Report the records in `violations` as skipped along with the outcome of writing the others.
+fn with_violations(
+    result: Result<(), DeltaWriterError>,
+    violations: Vec<BadValue>,
+) -> Result<(), DeltaWriterError> {
+    if violations.is_empty() {
+        return result;
+    }
+    match result {
+        Ok(()) => Err(DeltaWriterError::PartialParquetWrite {
+            skipped_values: violations,
+            sample_error: ParquetError::General(CONSTRAINT_VIOLATION.to_string()),
+        }),
+        Err(DeltaWriterError::PartialParquetWrite {
+            mut skipped_values,
+            sample_error,
+        }) => {
+            skipped_values.extend(violations);
+            Err(DeltaWriterError::PartialParquetWrite {
+                skipped_values,
+                sample_error,
+            })
+        }
+        Err(err) => Err(err),
+    }
+}
+
 fn quarantine_failed_parquet_rows(
     arrow_schema: Arc<ArrowSchema>,
     values: Vec<Value>,
@@ -437,7 +724,14 @@ fn quarantine_failed_parquet_rows(
 
     for value in values {
         let record_batch =
-            record_batch_from_message(arrow_schema.clone(), std::slice::from_ref(&value))?;
+            match record_batch_from_message(arrow_schema.clone(), std::slice::from_ref(&value)) {
+                Ok(record_batch) => record_batch,
+                Err(DeltaTableError::Arrow { source }) => {
+                    bad.push((value, ParquetError::ArrowError(source.to_string())));
+                    continue;
+                }
+                Err(err) => return Err(err.into()),
+            };
         let buffer = ShareableBuffer::default();
         let mut writer = ArrowWriter::try_new(buffer.clone(), arrow_schema.clone(), None)?;
 
@@ -728,6 +1022,166 @@ mod tests {
         }
     }
 
+    #[tokio::test]
+    async fn test_json_write_merge_schema() {
+        let table_dir = tempfile::tempdir().unwrap();
+        let mut table = get_test_table(&table_dir).await;
+        let mut writer = JsonWriter::for_table(&table).unwrap();
+
+        let data = serde_json::json!(
+            {
+                "id" : "A",
+                "value": 42,
+                "modified": "2021-02-01",
+                "postcode": 12345,
+                "unknown": null
+            }
+        );
+        writer
+            .write_with_mode(vec![data], WriteMode::MergeSchema)
+            .await
+            .unwrap();
+        writer.flush_and_commit(&mut table).await.unwrap();
+        assert_eq!(table.version(), Some(1));
+
+        let schema = table.snapshot().unwrap().schema();
+        let field_names = schema.fields().map(|f| f.name().as_str()).collect_vec();
+        assert_eq!(field_names, vec!["id", "value", "modified", "postcode"]);
+
+        // Once committed the new field is part of the schema for default writes
+        let data = serde_json::json!(
+            {
+                "id" : "B",
+                "value": 7,
+                "modified": "2021-02-02",
+                "postcode": 54321
+            }
+        );
+        writer.write(vec![data]).await.unwrap();
+        writer.flush_and_commit(&mut table).await.unwrap();
+        assert_eq!(table.version(), Some(2));
+    }
+
+    #[tokio::test]
+    async fn test_bad_record_policy_drop() {
+        let table_dir = tempfile::tempdir().unwrap();
+        let table = get_test_table(&table_dir).await;
+        let mut writer = JsonWriter::for_table(&table)
+            .unwrap()
+            .with_bad_record_policy(BadRecordPolicy::Drop);
+
+        let good = serde_json::json!({"id" : "A", "value": 42, "modified": "2021-02-01"});
+        let bad = serde_json::json!({"id" : "B", "value": "abc", "modified": "2021-02-01"});
+        writer.write(vec![good, bad]).await.unwrap();
+
+        let add_actions = writer.flush().await.unwrap();
+        assert_eq!(add_actions.len(), 1);
+        assert_eq!(add_actions[0].get_stats().unwrap().unwrap().num_records, 1);
+    }
+
+    #[tokio::test]
+    async fn test_bad_record_policy_dead_letter() {
+        let table_dir = tempfile::tempdir().unwrap();
+        let table = get_test_table(&table_dir).await;
+        let mut writer = JsonWriter::for_table(&table)
+            .unwrap()
+            .with_bad_record_policy(BadRecordPolicy::DeadLetter(Path::from("_dead_letters")));
+
+        let bad = serde_json::json!({"id" : "B", "value": "abc", "modified": "2021-02-01"});
+        writer.write(vec![bad.clone()]).await.unwrap();
+
+        // only bad records were written, so no data file is created
+        let add_actions = writer.flush().await.unwrap();
+        assert!(add_actions.is_empty());
+
+        let entries = std::fs::read_dir(table_dir.path().join("_dead_letters"))
+            .unwrap()
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap();
+        assert_eq!(entries.len(), 1);
+        let content = std::fs::read_to_string(entries[0].path()).unwrap();
+        let lines = content.lines().collect_vec();
+        assert_eq!(lines.len(), 1);
+        let line: Value = serde_json::from_str(lines[0]).unwrap();
+        assert_eq!(line["record"], bad);
+        assert!(line["error"].as_str().is_some_and(|e| !e.is_empty()));
+    }
+
+    #[cfg(feature = "datafusion")]
+    #[tokio::test]
+    async fn test_write_enforces_constraints() {
+        let table_dir = tempfile::tempdir().unwrap();
+        let table = get_test_table(&table_dir)
+            .await
+            .add_constraint()
+            .with_constraint("value_positive", "value > 0")
+            .await
+            .unwrap();
+        let mut writer = JsonWriter::for_table(&table).unwrap();
+
+        let invalid = serde_json::json!({"id" : "A", "value": -1, "modified": "2021-02-01"});
+        let result = writer.write(vec![invalid]).await;
+        assert!(
+            matches!(result, Err(DeltaTableError::InvalidData { .. })),
+            "Expected the constraint to reject the record: {result:?}"
+        );
+
+        let valid = serde_json::json!({"id" : "A", "value": 1, "modified": "2021-02-01"});
+        writer.write(vec![valid]).await.unwrap();
+        let add_actions = writer.flush().await.unwrap();
+        assert_eq!(add_actions.len(), 1);
+        assert_eq!(add_actions[0].get_stats().unwrap().unwrap().num_records, 1);
+    }
+
+    #[cfg(feature = "datafusion")]
+    #[tokio::test]
+    async fn test_constraint_violations_follow_bad_record_policy() {
+        let table_dir = tempfile::tempdir().unwrap();
+        let table = get_test_table(&table_dir)
+            .await
+            .add_constraint()
+            .with_constraint("value_positive", "value > 0")
+            .await
+            .unwrap();
+        let mut writer = JsonWriter::for_table(&table)
+            .unwrap()
+            .with_bad_record_policy(BadRecordPolicy::DeadLetter(Path::from("_dead_letters")));
+
+        let valid = serde_json::json!({"id" : "A", "value": 1, "modified": "2021-02-01"});
+        let invalid = serde_json::json!({"id" : "B", "value": -1, "modified": "2021-02-01"});
+        let malformed = serde_json::json!({"id" : "C", "value": "abc", "modified": "2021-02-01"});
+        writer
+            .write(vec![valid, invalid.clone(), malformed.clone()])
+            .await
+            .unwrap();
+
+        let add_actions = writer.flush().await.unwrap();
+        assert_eq!(add_actions.len(), 1);
+        assert_eq!(add_actions[0].get_stats().unwrap().unwrap().num_records, 1);
+
+        let entries = std::fs::read_dir(table_dir.path().join("_dead_letters"))
+            .unwrap()
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap();
+        assert_eq!(entries.len(), 1);
+        let content = std::fs::read_to_string(entries[0].path()).unwrap();
+        let lines: Vec<Value> = content
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect();
+        assert_eq!(lines.len(), 2);
+        let violation = lines
+            .iter()
+            .find(|line| line["record"] == invalid)
+            .expect("the constraint violation is dead lettered");
+        assert!(
+            violation["error"]
+                .as_str()
+                .is_some_and(|e| e.contains(CONSTRAINT_VIOLATION))
+        );
+        assert!(lines.iter().any(|line| line["record"] == malformed));
+    }
+
     #[cfg(feature = "datafusion")]
     #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
     async fn test_json_write_checkpoint() {
@@ -1,7 +1,9 @@
 //! Abstractions and implementations for writing data to delta tables
 
 use arrow::{datatypes::FieldRef, datatypes::SchemaRef, error::ArrowError};
+use arrow_array::{BooleanArray, RecordBatch};
 use async_trait::async_trait;
+use delta_kernel::table_configuration::TableConfiguration;
 use object_store::Error as ObjectStoreError;
 use parquet::errors::ParquetError;
 use serde_json::Value;
@@ -13,11 +15,14 @@ use crate::kernel::transaction::{CommitBuilder, CommitProperties};
 use crate::kernel::{Action, Add, Version};
 use crate::protocol::{ColumnCountStat, DeltaOperation, SaveMode};
 
-pub use json::JsonWriter;
+pub use crate::parquet_utils::ColumnWriterProperties;
+pub use json::{BadRecordPolicy, JsonWriter};
 pub use record_batch::RecordBatchWriter;
+pub use sink::DeltaSink;
 
 pub mod json;
 pub mod record_batch;
+pub mod sink;
 pub(crate) mod stats;
 pub mod utils;
 
@@ -43,6 +48,98 @@ pub(crate) fn ensure_legacy_writer_supports_table(
     Ok(())
 }
 
+/// Enforces the non-nullable columns, invariants, CHECK constraints and generated columns of a
+/// table on the batches buffered by a writer.
+///
+/// Checking requires the `datafusion` feature. Without it writes to tables with invariants,
+/// CHECK constraints or generated columns fail, since their rows cannot be checked.
+#[derive(Debug, Clone)]
+pub(crate) struct WriteValidator {
+    #[cfg(feature = "datafusion")]
+    validator: Option<crate::delta_datafusion::BatchValidator>,
+    /// What of the table cannot be checked without the `datafusion` feature, if anything.
+    #[cfg(not(feature = "datafusion"))]
+    unchecked: Option<&'static str>,
+}
+
+impl WriteValidator {
+    /// Validate batches against `table_configuration`, nothing is checked if the configuration
+    /// of the table is unknown.
+    pub(crate) fn new(table_configuration: Option<&TableConfiguration>) -> Self {
+        Self {
+            #[cfg(feature = "datafusion")]
+            validator: table_configuration
+                .map(|config| crate::delta_datafusion::BatchValidator::new(config.clone())),
+            #[cfg(not(feature = "datafusion"))]
+            unchecked: table_configuration.and_then(unchecked_table_requirements),
+        }
+    }
+
+    /// Fail if the table has requirements which cannot be checked in this build.
+    fn ensure_checkable(&self) -> Result<(), DeltaTableError> {
+        #[cfg(not(feature = "datafusion"))]
+        if let Some(requirements) = self.unchecked {
+            return Err(DeltaTableError::Generic(format!(
+                "Cannot write to a table with {requirements}, delta-rs must be built with the \
+                 'datafusion' feature to enforce them"
+            )));
+        }
+        Ok(())
+    }
+
+    /// Fail with [`DeltaTableError::InvalidData`] if a row of `batch` violates the constraints
+    /// of the table.
+    #[cfg_attr(not(feature = "datafusion"), allow(unused_variables))]
+    pub(crate) fn validate(&mut self, batch: &RecordBatch) -> Result<(), DeltaTableError> {
+        self.ensure_checkable()?;
+        #[cfg(feature = "datafusion")]
+        if let Some(validator) = self.validator.as_mut() {
+            validator.validate(batch)?;
+        }
+        Ok(())
+    }
+
+    /// Mask of the rows of `batch` violating the constraints of the table, `None` if all rows
+    /// are valid.
+    #[cfg_attr(not(feature = "datafusion"), allow(unused_variables))]
+    pub(crate) fn invalid_rows(
+        &mut self,
+        batch: &RecordBatch,
+    ) -> Result<Option<BooleanArray>, DeltaTableError> {
+        self.ensure_checkable()?;
+        #[cfg(feature = "datafusion")]
+        if let Some(validator) = self.validator.as_mut() {
+            return validator.invalid_rows(batch);
+        }
+        Ok(None)
+    }
+}
+
+/// Requirements of the table which are only enforced with the `datafusion` feature.
+#[cfg(not(feature = "datafusion"))]
+fn unchecked_table_requirements(config: &TableConfiguration) -> Option<&'static str> {
+    use crate::kernel::StructTypeExt as _;
+    use crate::table::config::TablePropertiesExt as _;
+    use delta_kernel::table_features::TableFeature;
+
+    let schema = config.logical_schema();
+    if config.is_feature_enabled(&TableFeature::Invariants)
+        && schema.get_invariants().is_ok_and(|i| !i.is_empty())
+    {
+        Some("invariants")
+    } else if config.is_feature_enabled(&TableFeature::CheckConstraints)
+        && !config.table_properties().get_constraints().is_empty()
+    {
+        Some("CHECK constraints")
+    } else if config.is_feature_enabled(&TableFeature::GeneratedColumns)
+        && schema.get_generated_columns().is_ok_and(|g| !g.is_empty())
+    {
+        Some("generated columns")
+    } else {
+        None
+    }
+}
+
 /// Enum representing an error when calling [`DeltaWriter`].
 #[derive(thiserror::Error, Debug)]
 pub(crate) enum DeltaWriterError {
@@ -27,16 +27,23 @@ use super::utils::{
     ShareableBuffer, arrow_schema_without_partitions, next_data_path,
     record_batch_without_partitions,
 };
-use super::{DeltaWriter, DeltaWriterError, WriteMode, ensure_legacy_writer_supports_table};
+use super::{
+    DeltaWriter, DeltaWriterError, WriteMode, WriteValidator, ensure_legacy_writer_supports_table,
+};
 use crate::DeltaTable;
 use crate::errors::DeltaTableError;
-use crate::kernel::schema::cast::normalize_for_delta;
+use crate::kernel::schema::cast::{
+    CoercionPolicy, cast_record_batch, is_same_delta_schema, normalize_for_delta,
+    with_dictionary_encoding,
+};
 use crate::kernel::schema::merge_arrow_schema;
 use crate::kernel::transaction::CommitProperties;
 use crate::kernel::{Action, Add, PartitionsExt, scalars::ScalarExt};
 use crate::kernel::{MetadataExt as _, Version};
 use crate::logstore::ObjectStoreRetryExt;
-use crate::parquet_utils::default_writer_properties;
+use crate::parquet_utils::{
+    ColumnWriterProperties, apply_column_writer_properties, default_writer_properties,
+};
 use crate::table::builder::DeltaTableBuilder;
 use crate::table::config::DEFAULT_NUM_INDEX_COLS;
 
@@ -52,6 +59,10 @@ pub struct RecordBatchWriter {
     num_indexed_cols: DataSkippingNumIndexedCols,
     stats_columns: Option<Vec<String>>,
     commit_properties: Option<CommitProperties>,
+    column_writer_properties: HashMap<String, ColumnWriterProperties>,
+    coercion_policy: CoercionPolicy,
+    /// Checks written batches against the constraints of the table, if its metadata is known
+    validator: WriteValidator,
 }
 
 impl std::fmt::Debug for RecordBatchWriter {
@@ -129,6 +140,15 @@ impl RecordBatchWriter {
         self
     }
 
+    /// Specify how the types of written batches are reconciled with the table schema.
+    ///
+    /// Defaults to [`CoercionPolicy::Strict`], casts are only applied when writing with
+    /// [`WriteMode::Default`].
+    pub fn with_coercion_policy(mut self, coercion_policy: CoercionPolicy) -> Self {
+        self.coercion_policy = coercion_policy;
+        self
+    }
+
     /// Creates a [`RecordBatchWriter`] to write data to provided Delta Table
     pub fn for_table(table: &DeltaTable) -> Result<Self, DeltaTableError> {
         ensure_legacy_writer_supports_table(table, "RecordBatchWriter")?;
@@ -165,6 +185,11 @@ impl RecordBatchWriter {
                 .get("delta.dataSkippingStatsColumns")
                 .map(|v| v.split(',').map(|s| s.to_string()).collect()),
             commit_properties: None,
+            column_writer_properties: HashMap::new(),
+            coercion_policy: CoercionPolicy::Strict,
+            validator: WriteValidator::new(Some(
+                table.snapshot()?.snapshot().table_configuration(),
+            )),
         })
     }
 
@@ -209,6 +234,9 @@ impl RecordBatchWriter {
                 .get("delta.dataSkippingStatsColumns")
                 .map(|v| v.split(',').map(|s| s.to_string()).collect()),
             commit_properties: None,
+            column_writer_properties: HashMap::new(),
+            coercion_policy: CoercionPolicy::Strict,
+            validator: WriteValidator::new(Some(table.snapshot().table_configuration())),
         })
     }
 
@@ -220,6 +248,12 @@ impl RecordBatchWriter {
         writer_properties: WriterProperties,
     ) -> Self {
         let schema = normalize_for_delta(&schema);
+        let validator = WriteValidator::new(
+            delta_table
+                .snapshot()
+                .ok()
+                .map(|snapshot| snapshot.snapshot().table_configuration()),
+        );
 
         Self {
             storage: delta_table.object_store(),
@@ -243,6 +277,9 @@ impl RecordBatchWriter {
                 .get("delta.dataSkippingStatsColumns")
                 .map(|v| v.split(',').map(|s| s.to_string()).collect()),
             commit_properties: None,
+            column_writer_properties: HashMap::new(),
+            coercion_policy: CoercionPolicy::Strict,
+            validator,
         }
     }
 
@@ -285,11 +322,18 @@ impl RecordBatchWriter {
         let written_schema = match self.arrow_writers.get_mut(&partition_key) {
             Some(writer) => writer.write(&record_batch, mode)?,
             None => {
+                let table_schema = arrow_schema_without_partitions(
+                    &self.arrow_schema_ref,
+                    &self.partition_columns,
+                );
+                // Files take the encoding of their first batch, e.g. to keep dictionary arrays.
+                let file_schema = if is_same_delta_schema(&record_batch.schema(), &table_schema) {
+                    record_batch.schema()
+                } else {
+                    table_schema
+                };
                 let mut writer = PartitionWriter::new(
-                    arrow_schema_without_partitions(
-                        &self.arrow_schema_ref,
-                        &self.partition_columns,
-                    ),
+                    file_schema,
                     partition_values.clone(),
                     self.writer_properties.clone(),
                 )?;
@@ -308,9 +352,68 @@ impl RecordBatchWriter {
         }
     }
 
+    /// Cast `values` to the table's types if the coercion policy allows it.
+    ///
+    /// Batches with other columns than the table are returned as they are.
+    fn coerce_to_table_types(&self, values: RecordBatch) -> Result<RecordBatch, DeltaTableError> {
+        let schema = values.schema();
+        let casts: Option<Vec<_>> = self
+            .arrow_schema_ref
+            .fields()
+            .iter()
+            .map(|target| {
+                let field = schema.field_with_name(target.name()).ok()?;
+                Some((
+                    target.name().as_str(),
+                    field.data_type(),
+                    target.data_type(),
+                ))
+            })
+            .collect();
+        let Some(casts) = casts.filter(|casts| casts.len() == schema.fields().len()) else {
+            return Ok(values);
+        };
+
+        for (name, from, to) in casts.iter() {
+            if !arrow_cast::can_cast_types(from, to) {
+                return Err(ArrowError::SchemaError(format!(
+                    "Cannot cast field {name} from {from} to {to}"
+                ))
+                .into());
+            }
+        }
+        self.coercion_policy.warn_lossy_casts(casts);
+
+        cast_record_batch(
+            &values,
+            self.arrow_schema_ref.clone(),
+            self.coercion_policy.null_on_cast_failure(),
+            false,
+        )
+    }
+
     /// Sets the writer properties for the underlying arrow writer.
+    ///
+    /// Settings configured with [`with_column_writer_properties`](Self::with_column_writer_properties)
+    /// take precedence over the given properties.
     pub fn with_writer_properties(mut self, writer_properties: WriterProperties) -> Self {
-        self.writer_properties = writer_properties;
+        self.writer_properties =
+            apply_column_writer_properties(writer_properties, &self.column_writer_properties);
+        self
+    }
+
+    /// Override the writer properties of a single column.
+    ///
+    /// Nested columns are identified by their field names separated by dots.
+    pub fn with_column_writer_properties(
+        mut self,
+        column: impl Into<String>,
+        properties: ColumnWriterProperties,
+    ) -> Self {
+        let column = column.into();
+        self.writer_properties =
+            apply_column_writer_properties(self.writer_properties, [(&column, &properties)]);
+        self.column_writer_properties.insert(column, properties);
         self
     }
 
@@ -319,7 +422,7 @@ impl RecordBatchWriter {
         values: &RecordBatch,
     ) -> Result<Vec<PartitionResult>, DeltaWriterError> {
         divide_by_partition_values(
-            arrow_schema_without_partitions(&self.arrow_schema_ref, &self.partition_columns),
+            arrow_schema_without_partitions(&values.schema(), &self.partition_columns),
             self.partition_columns.clone(),
             values,
         )
@@ -351,8 +454,27 @@ impl DeltaWriter<RecordBatch> for RecordBatchWriter {
 
         let values = if values.schema() != self.arrow_schema_ref {
             let normalized = normalize_for_delta(&values.schema());
-            if normalized != values.schema() {
-                crate::kernel::schema::cast::cast_record_batch(&values, normalized, true, false)?
+            let values = if normalized != values.schema() {
+                cast_record_batch(&values, normalized, true, false)?
+            } else {
+                values
+            };
+            // Large and view string/binary arrays are written with the table's encoding, while
+            // dictionary arrays outside of partition columns are passed through as they are.
+            if values.schema() != self.arrow_schema_ref
+                && is_same_delta_schema(&values.schema(), &self.arrow_schema_ref)
+            {
+                let target = with_dictionary_encoding(
+                    &self.arrow_schema_ref,
+                    &values.schema(),
+                    &self.partition_columns,
+                );
+                cast_record_batch(&values, target, true, false)?
+            } else if mode == WriteMode::Default
+                && self.coercion_policy != CoercionPolicy::Strict
+                && values.schema() != self.arrow_schema_ref
+            {
+                self.coerce_to_table_types(values)?
             } else {
                 values
             }
@@ -360,6 +482,8 @@ impl DeltaWriter<RecordBatch> for RecordBatchWriter {
             values
         };
 
+        self.validator.validate(&values)?;
+
         for result in self.divide_by_partition_values(&values)? {
             let maybe_evolved_schema = self
                 .write_partition(result.record_batch, &result.partition_values, mode)
@@ -479,6 +603,20 @@ impl PartitionWriter {
         record_batch: &RecordBatch,
         mode: WriteMode,
     ) -> Result<ArrowSchemaRef, DeltaWriterError> {
+        // Batches which only differ in their encoding are cast to the encoding of the file
+        let cast_batch = if record_batch.schema() != self.arrow_schema
+            && is_same_delta_schema(&record_batch.schema(), &self.arrow_schema)
+        {
+            Some(cast_record_batch(
+                record_batch,
+                self.arrow_schema.clone(),
+                true,
+                false,
+            )?)
+        } else {
+            None
+        };
+        let record_batch = cast_batch.as_ref().unwrap_or(record_batch);
         let merged_batch = if record_batch.schema() != self.arrow_schema {
             match mode {
                 WriteMode::MergeSchema => {
@@ -629,7 +767,7 @@ fn lexsort_to_indices(arrays: &[ArrayRef]) -> UInt32Array {
 #[cfg(test)]
 mod tests {
     use arrow::json::ReaderBuilder;
-    use arrow_schema::Schema as ArrowSchema;
+    use arrow_schema::{DataType, Field, Schema as ArrowSchema};
     use delta_kernel::schema::StructType;
 
     use crate::DeltaResult;
@@ -653,6 +791,101 @@ mod tests {
         assert!(writer.buffer_len() > 0);
     }
 
+    #[tokio::test]
+    async fn test_write_view_and_large_types() {
+        let table_dir = tempfile::tempdir().unwrap();
+        let table_path = table_dir.path().to_str().unwrap();
+        let partition_cols = vec!["modified".to_string()];
+        let mut table = create_initialized_table(table_path, &partition_cols).await;
+
+        let batch = get_record_batch(None, false);
+        let view_schema = Arc::new(ArrowSchema::new(vec![
+            Field::new("id", DataType::Utf8View, true),
+            Field::new("value", DataType::Int32, true),
+            Field::new("modified", DataType::LargeUtf8, true),
+        ]));
+        let batch = cast_record_batch(&batch, view_schema, false, false).unwrap();
+
+        let mut writer = RecordBatchWriter::for_table(&table).unwrap();
+        writer.write(batch).await.unwrap();
+        let version = writer.flush_and_commit(&mut table).await.unwrap();
+        assert_eq!(version, 1);
+        assert_eq!(table.snapshot().unwrap().log_data().num_files(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_write_with_coercion_policy() {
+        let table_dir = tempfile::tempdir().unwrap();
+        let table_path = table_dir.path().to_str().unwrap();
+        let mut table = create_initialized_table(table_path, &[]).await;
+
+        let batch = get_record_batch(None, false);
+        let wide_schema = Arc::new(ArrowSchema::new(vec![
+            Field::new("id", DataType::Utf8, true),
+            Field::new("value", DataType::Int64, true),
+            Field::new("modified", DataType::Utf8, true),
+        ]));
+        let batch = cast_record_batch(&batch, wide_schema, false, false).unwrap();
+
+        let mut writer = RecordBatchWriter::for_table(&table).unwrap();
+        let result = writer.write(batch.clone()).await;
+        assert!(
+            matches!(result, Err(DeltaTableError::SchemaMismatch { .. })),
+            "Strict coercion should reject Int64 values for an Int32 column: {result:?}"
+        );
+
+        let mut writer = RecordBatchWriter::for_table(&table)
+            .unwrap()
+            .with_coercion_policy(CoercionPolicy::SafeCast);
+        writer.write(batch).await.unwrap();
+        let version = writer.flush_and_commit(&mut table).await.unwrap();
+        assert_eq!(version, 1);
+    }
+
+    #[tokio::test]
+    async fn test_write_dictionary_arrays_natively() {
+        let table_dir = tempfile::tempdir().unwrap();
+        let table_path = table_dir.path().to_str().unwrap();
+        let partition_cols = vec!["modified".to_string()];
+        let mut table = create_initialized_table(table_path, &partition_cols).await;
+
+        let dictionary_type =
+            DataType::Dictionary(Box::new(DataType::Int32), Box::new(DataType::Utf8));
+        let dictionary_schema = Arc::new(ArrowSchema::new(vec![
+            Field::new("id", dictionary_type.clone(), true),
+            Field::new("value", DataType::Int32, true),
+            Field::new("modified", dictionary_type.clone(), true),
+        ]));
+        let batch = cast_record_batch(
+            &get_record_batch(None, false),
+            dictionary_schema,
+            false,
+            false,
+        )
+        .unwrap();
+
+        let mut writer = RecordBatchWriter::for_table(&table).unwrap();
+        writer.write(batch).await.unwrap();
+        // plain batches are encoded to match files that already hold dictionary arrays
+        writer.write(get_record_batch(None, false)).await.unwrap();
+
+        assert_eq!(writer.arrow_writers.len(), 2);
+        for partition_writer in writer.arrow_writers.values() {
+            assert_eq!(
+                partition_writer
+                    .arrow_schema
+                    .field_with_name("id")
+                    .unwrap()
+                    .data_type(),
+                &dictionary_type
+            );
+        }
+
+        let version = writer.flush_and_commit(&mut table).await.unwrap();
+        assert_eq!(version, 1);
+        assert_eq!(table.snapshot().unwrap().log_data().num_files(), 2);
+    }
+
     #[tokio::test]
     async fn test_record_batch_writer_for_table_defaults_include_delta_rs_created_by() {
         let table_dir = tempfile::tempdir().unwrap();
@@ -1279,6 +1512,37 @@ mod tests {
         }
     }
 
+    #[cfg(not(feature = "datafusion"))]
+    #[tokio::test]
+    async fn test_write_to_table_with_invariants_requires_datafusion() {
+        let delta_schema: StructType = serde_json::from_str(
+            r#"{
+            "type": "struct",
+            "fields": [
+                {"name": "id", "type": "string", "nullable": true, "metadata": {}},
+                {"name": "value", "type": "integer", "nullable": true, "metadata": {
+                    "delta.invariants": "{\"expression\": { \"expression\": \"value < 10\"} }"
+                }},
+                {"name": "modified", "type": "string", "nullable": true, "metadata": {}}
+            ]
+        }"#,
+        )
+        .unwrap();
+        let table = DeltaTable::new_in_memory()
+            .create()
+            .with_columns(delta_schema.fields().cloned())
+            .await
+            .unwrap();
+
+        let mut writer = RecordBatchWriter::for_table(&table).unwrap();
+        let result = writer.write(get_record_batch(None, false)).await;
+        assert!(
+            matches!(&result, Err(DeltaTableError::Generic(msg)) if msg.contains("invariants")),
+            "Expected unchecked invariants to fail the write: {result:?}"
+        );
+        assert_eq!(writer.buffered_record_batch_count(), 0);
+    }
+
     #[cfg(feature = "datafusion")]
     mod datafusion_tests {
         use super::*;
@@ -1394,5 +1658,31 @@ mod tests {
                     .unwrap()
             );
         }
+
+        #[tokio::test]
+        async fn test_write_enforces_constraints() {
+            let table_dir = tempfile::tempdir().unwrap();
+            let table_path = table_dir.path().to_str().unwrap();
+            let partition_cols = vec!["modified".to_string()];
+            let table = create_initialized_table(table_path, &partition_cols)
+                .await
+                .add_constraint()
+                .with_constraint("value_lt_10", "value < 10")
+                .await
+                .unwrap();
+            let batch = get_record_batch(None, false);
+
+            let mut writer = RecordBatchWriter::for_table(&table).unwrap();
+            let result = writer.write(batch.clone()).await;
+            assert!(
+                matches!(result, Err(DeltaTableError::InvalidData { .. })),
+                "Expected the constraint to reject the batch: {result:?}"
+            );
+            assert_eq!(writer.buffered_record_batch_count(), 0);
+
+            writer.write(batch.slice(0, 9)).await.unwrap();
+            let adds = writer.flush().await.unwrap();
+            assert_eq!(adds.len(), 2);
+        }
     }
 }
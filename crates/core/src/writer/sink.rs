@@ -0,0 +1,278 @@
+//! Exactly-once streaming sink for Delta tables
+//!
+//! The [`DeltaSink`] buffers record batches from a stream and commits them to the table when
+//! either the buffered data exceeds a size threshold or a flush interval has elapsed.
+//!
+//! Every batch is tagged with an epoch, a monotonically increasing number such as a source
+//! offset. Each commit records the highest epoch it contains as an application transaction
+//! (`txn` action) for the sink's application id, atomically with the data. After a crash the
+//! source can be rewound to [`DeltaSink::last_committed_epoch`], and batches from epochs which
+//! were already committed are skipped, so replays never duplicate data.
+//!
+//! # Example
+//! ```rust ignore
+//! let sink = DeltaSink::try_new(table, "orders-consumer")
+//!     .await?
+//!     .with_max_buffer_bytes(64 * 1024 * 1024)
+//!     .with_flush_interval(Duration::from_secs(30));
+//! let table = sink.consume(stream_of_offsets_and_batches).await?;
+//! ```
+
+use std::time::{Duration, Instant};
+
+use arrow_array::RecordBatch;
+use futures::{Stream, StreamExt as _};
+use tracing::debug;
+
+use super::{DeltaWriter as _, RecordBatchWriter};
+use crate::kernel::transaction::CommitProperties;
+use crate::kernel::{Action, Transaction, Version};
+use crate::{DeltaResult, DeltaTable};
+
+/// Default amount of buffered data after which the sink commits
+pub const DEFAULT_MAX_BUFFER_BYTES: usize = 128 * 1024 * 1024;
+
+/// A sink which writes a stream of record batches into a table with exactly-once semantics.
+///
+/// See the [module level documentation](self) for details.
+pub struct DeltaSink {
+    table: DeltaTable,
+    writer: RecordBatchWriter,
+    app_id: String,
+    committed_epoch: Option<i64>,
+    pending_epoch: Option<i64>,
+    max_buffer_bytes: usize,
+    flush_interval: Option<Duration>,
+    last_flush: Instant,
+    commit_properties: CommitProperties,
+}
+
+impl DeltaSink {
+    /// Create a new sink for `table`, resuming from the last epoch committed by `app_id`.
+    pub async fn try_new(table: DeltaTable, app_id: impl Into<String>) -> DeltaResult<Self> {
+        let app_id = app_id.into();
+        let writer = RecordBatchWriter::for_table(&table)?;
+        let committed_epoch = table
+            .snapshot()?
+            .transaction_version(table.log_store().as_ref(), &app_id)
+            .await?;
+        Ok(Self {
+            table,
+            writer,
+            app_id,
+            committed_epoch,
+            pending_epoch: None,
+            max_buffer_bytes: DEFAULT_MAX_BUFFER_BYTES,
+            flush_interval: None,
+            last_flush: Instant::now(),
+            commit_properties: CommitProperties::default(),
+        })
+    }
+
+    /// Commit once the buffered data exceeds this many bytes
+    pub fn with_max_buffer_bytes(mut self, max_buffer_bytes: usize) -> Self {
+        self.max_buffer_bytes = max_buffer_bytes;
+        self
+    }
+
+    /// Commit buffered data at least this often
+    pub fn with_flush_interval(mut self, flush_interval: Duration) -> Self {
+        self.flush_interval = Some(flush_interval);
+        self
+    }
+
+    /// Additional properties for every commit, the sink adds its own application transaction
+    pub fn with_commit_properties(mut self, commit_properties: CommitProperties) -> Self {
+        self.commit_properties = commit_properties;
+        self
+    }
+
+    /// The application id used to track committed epochs
+    pub fn app_id(&self) -> &str {
+        &self.app_id
+    }
+
+    /// The highest epoch durably committed to the table, if any.
+    ///
+    /// Sources should resume from the epoch following this one after a restart.
+    pub fn last_committed_epoch(&self) -> Option<i64> {
+        self.committed_epoch
+    }
+
+    /// The table as of the last commit made by this sink
+    pub fn table(&self) -> &DeltaTable {
+        &self.table
+    }
+
+    /// Buffer a batch belonging to `epoch`, committing if a flush trigger is met.
+    ///
+    /// Batches from epochs which were already committed are skipped. Returns the table version
+    /// if this call resulted in a commit.
+    pub async fn write(&mut self, epoch: i64, batch: RecordBatch) -> DeltaResult<Option<Version>> {
+        if self
+            .committed_epoch
+            .is_some_and(|committed| epoch <= committed)
+        {
+            debug!(
+                "Skipping batch from epoch {epoch}, epoch {:?} is already committed",
+                self.committed_epoch
+            );
+            return Ok(None);
+        }
+
+        self.writer.write(batch).await?;
+        self.pending_epoch = self.pending_epoch.max(Some(epoch));
+
+        if self.should_flush() {
+            self.flush().await
+        } else {
+            Ok(None)
+        }
+    }
+
+    /// Commit all buffered data, recording the highest buffered epoch.
+    ///
+    /// Returns `None` if there was nothing to commit.
+    pub async fn flush(&mut self) -> DeltaResult<Option<Version>> {
+        self.last_flush = Instant::now();
+        let Some(epoch) = self.pending_epoch else {
+            return Ok(None);
+        };
+
+        let adds: Vec<_> = self
+            .writer
+            .flush()
+            .await?
+            .into_iter()
+            .map(Action::Add)
+            .collect();
+        let commit_properties = self
+            .commit_properties
+            .clone()
+            .with_application_transaction(Transaction::new(&self.app_id, epoch));
+        let version =
+            super::flush_and_commit(adds, &mut self.table, Some(commit_properties)).await?;
+
+        self.committed_epoch = Some(epoch);
+        self.pending_epoch = None;
+        Ok(Some(version))
+    }
+
+    /// Write all `(epoch, batch)` pairs from `stream`, committing as flush triggers are met.
+    ///
+    /// The flush interval is also honored while waiting for new batches. Remaining data is
+    /// committed once the stream ends.
+    pub async fn consume<S>(mut self, stream: S) -> DeltaResult<DeltaTable>
+    where
+        S: Stream<Item = DeltaResult<(i64, RecordBatch)>>,
+    {
+        let mut stream = std::pin::pin!(stream);
+        loop {
+            let next = match self.flush_interval {
+                Some(interval) => {
+                    let remaining = interval.saturating_sub(self.last_flush.elapsed());
+                    match tokio::time::timeout(remaining, stream.next()).await {
+                        Ok(next) => next,
+                        Err(_) => {
+                            self.flush().await?;
+                            continue;
+                        }
+                    }
+                }
+                None => stream.next().await,
+            };
+            match next {
+                Some(item) => {
+                    let (epoch, batch) = item?;
+                    self.write(epoch, batch).await?;
+                }
+                None => break,
+            }
+        }
+        self.flush().await?;
+        Ok(self.table)
+    }
+
+    fn should_flush(&self) -> bool {
+        self.writer.buffer_len() >= self.max_buffer_bytes
+            || self
+                .flush_interval
+                .is_some_and(|interval| self.last_flush.elapsed() >= interval)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::writer::test_utils::{create_initialized_table, get_record_batch};
+
+    #[tokio::test]
+    async fn test_sink_commits_epochs() -> DeltaResult<()> {
+        let table_dir = tempfile::tempdir().unwrap();
+        let table_path = table_dir.path().to_str().unwrap();
+        let table = create_initialized_table(table_path, &[]).await;
+
+        let mut sink = DeltaSink::try_new(table, "test-app").await?;
+        assert_eq!(sink.last_committed_epoch(), None);
+
+        assert_eq!(sink.write(1, get_record_batch(None, false)).await?, None);
+        assert_eq!(sink.write(2, get_record_batch(None, false)).await?, None);
+        assert_eq!(sink.flush().await?, Some(1));
+        assert_eq!(sink.last_committed_epoch(), Some(2));
+        assert_eq!(sink.flush().await?, None);
+
+        let table = sink.table().clone();
+        let txn_version = table
+            .snapshot()?
+            .transaction_version(table.log_store().as_ref(), "test-app")
+            .await?;
+        assert_eq!(txn_version, Some(2));
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_sink_skips_replayed_epochs() -> DeltaResult<()> {
+        let table_dir = tempfile::tempdir().unwrap();
+        let table_path = table_dir.path().to_str().unwrap();
+        let table = create_initialized_table(table_path, &[]).await;
+
+        let batches = |epochs: Vec<i64>| {
+            futures::stream::iter(
+                epochs
+                    .into_iter()
+                    .map(|epoch| Ok((epoch, get_record_batch(None, false)))),
+            )
+        };
+
+        let sink = DeltaSink::try_new(table, "test-app").await?;
+        let table = sink.consume(batches(vec![1, 2])).await?;
+        let files = table.snapshot()?.log_data().num_files();
+        assert_eq!(table.version(), Some(1));
+
+        // Simulate a restart which replays the committed epochs before new data arrives
+        let sink = DeltaSink::try_new(table, "test-app").await?;
+        assert_eq!(sink.last_committed_epoch(), Some(2));
+        let table = sink.consume(batches(vec![1, 2])).await?;
+        assert_eq!(table.version(), Some(1));
+        assert_eq!(table.snapshot()?.log_data().num_files(), files);
+
+        let sink = DeltaSink::try_new(table, "test-app").await?;
+        let table = sink.consume(batches(vec![2, 3])).await?;
+        assert_eq!(table.version(), Some(2));
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_sink_flushes_on_buffer_size() -> DeltaResult<()> {
+        let table_dir = tempfile::tempdir().unwrap();
+        let table_path = table_dir.path().to_str().unwrap();
+        let table = create_initialized_table(table_path, &[]).await;
+
+        let mut sink = DeltaSink::try_new(table, "test-app")
+            .await?
+            .with_max_buffer_bytes(1);
+        assert_eq!(sink.write(1, get_record_batch(None, false)).await?, Some(1));
+        assert_eq!(sink.last_committed_epoch(), Some(1));
+        Ok(())
+    }
+}
@@ -21,6 +21,7 @@ use parquet::{
 use tracing::warn;
 
 use super::*;
+use crate::kernel::distinct_count::DistinctCountSketch;
 use crate::kernel::{Add, scalars::ScalarExt};
 use crate::protocol::{ColumnValueStat, Stats};
 
@@ -73,6 +74,19 @@ pub(crate) fn create_add(
     })
 }
 
+/// Stores the distinct count `sketches` of the columns of a file in the statistics of its [`Add`].
+pub(crate) fn add_distinct_count_sketches(
+    add: &mut Add,
+    sketches: HashMap<String, DistinctCountSketch>,
+) -> Result<(), DeltaTableError> {
+    let Some(mut stats) = add.get_stats()? else {
+        return Ok(());
+    };
+    stats.distinct_count_sketches = sketches;
+    add.stats = Some(serde_json::to_string(&stats)?);
+    Ok(())
+}
+
 // As opposed to `stats_from_file_metadata` which operates on `parquet::format::FileMetaData`,
 // this function produces the stats by reading the metadata from already written out files.
 //
@@ -253,6 +267,7 @@ fn stats_from_metadata(
         max_values,
         num_records: num_rows,
         null_count,
+        distinct_count_sketches: HashMap::new(),
     })
 }
 
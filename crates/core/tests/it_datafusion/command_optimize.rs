@@ -1,4 +1,4 @@
-use std::num::NonZeroU64;
+use std::num::{NonZeroU64, NonZeroUsize};
 use std::time::Duration;
 use std::{
     collections::BTreeSet,
@@ -19,6 +19,7 @@ use deltalake_core::kernel::{Action, Add, DataType, PrimitiveType, StructField,
 use deltalake_core::logstore::{
     CommitOrBytes, LogStore, LogStoreConfig, LogStoreRef, ObjectStoreRef, get_actions,
 };
+use deltalake_core::operations::CancellationToken;
 use deltalake_core::operations::optimize::{
     MetricDetails, Metrics, OptimizeType, PlannerStrategy, create_merge_plan,
 };
@@ -645,6 +646,35 @@ async fn test_optimize_non_partitioned_table() -> Result<(), Box<dyn Error>> {
     Ok(())
 }
 
+#[tokio::test]
+async fn test_optimize_with_max_rows_per_file() -> Result<(), Box<dyn Error>> {
+    let context = setup_test(false).await?;
+    let mut dt = context.table;
+    let mut writer = RecordBatchWriter::for_table(&dt)?;
+
+    for x in 1..=4 {
+        write(
+            &mut writer,
+            &mut dt,
+            tuples_to_batch(vec![(x, 1), (x, 2), (x, 3)], "2022-05-22")?,
+        )
+        .await?;
+    }
+    assert_eq!(dt.snapshot().unwrap().log_data().num_files(), 4);
+
+    let (dt, metrics) = dt
+        .optimize()
+        .with_target_size(NonZeroU64::new(2_000_000).unwrap())
+        .with_max_rows_per_file(NonZeroUsize::new(5).unwrap())
+        .await?;
+
+    assert_eq!(metrics.num_files_removed, 4);
+    assert_eq!(metrics.num_files_added, 3);
+    assert_eq!(dt.snapshot().unwrap().log_data().num_files(), 3);
+
+    Ok(())
+}
+
 #[tokio::test]
 async fn test_write_default_writer_properties_include_delta_rs_created_by()
 -> Result<(), Box<dyn Error>> {
@@ -722,6 +752,34 @@ async fn test_optimize_default_writer_properties_include_delta_rs_created_by()
     Ok(())
 }
 
+#[tokio::test]
+async fn test_optimize_cancelled() -> Result<(), Box<dyn Error>> {
+    let context = setup_test(false).await?;
+    let mut dt = context.table;
+    let mut writer = RecordBatchWriter::for_table(&dt)?;
+    for batch in [vec![(1, 2), (1, 3)], vec![(2, 1), (2, 3)]] {
+        write(&mut writer, &mut dt, tuples_to_batch(batch, "2022-05-22")?).await?;
+    }
+    let version = dt.version();
+
+    let token = CancellationToken::new();
+    token.cancel();
+    let result = dt.clone().optimize().with_cancellation_token(token).await;
+    assert!(matches!(result, Err(DeltaTableError::Cancelled)));
+
+    dt.update_state().await?;
+    assert_eq!(dt.version(), version);
+    let data_files = std::fs::read_dir(context.tmp_dir.path())?
+        .filter(|entry| {
+            entry
+                .as_ref()
+                .is_ok_and(|entry| entry.file_name().to_string_lossy().ends_with(".parquet"))
+        })
+        .count();
+    assert_eq!(data_files, 2);
+    Ok(())
+}
+
 async fn write(
     writer: &mut RecordBatchWriter,
     table: &mut DeltaTable,
@@ -1176,6 +1234,83 @@ async fn test_commit_interval() -> Result<(), Box<dyn Error>> {
     Ok(())
 }
 
+#[tokio::test]
+/// Validate that bins whose files were removed concurrently are skipped when committing per bin
+async fn test_commit_per_bin_skips_conflicting_bins() -> Result<(), Box<dyn Error>> {
+    let context = setup_test(true).await?;
+    let mut dt = context.table;
+    let mut writer = RecordBatchWriter::for_table(&dt)?;
+
+    for partition in ["2022-05-22", "2022-05-23"] {
+        for _i in 0..2 {
+            write(
+                &mut writer,
+                &mut dt,
+                tuples_to_batch(vec![(1, 2), (1, 3), (1, 4)], partition)?,
+            )
+            .await?;
+        }
+    }
+
+    let version = dt.version().unwrap();
+
+    let df_context: SessionContext = DeltaSessionContext::default().into();
+    let plan = create_merge_plan(
+        &dt.log_store(),
+        OptimizeType::Compact,
+        dt.snapshot()?.snapshot(),
+        &[],
+        None,
+        WriterProperties::builder().build(),
+        df_context.state(),
+    )
+    .await?
+    .with_commit_per_bin(true)
+    .with_skip_conflicting_bins(true);
+
+    // a concurrent delete removes a file of one of the bins
+    let uri = context.tmp_dir.path().to_str().to_owned().unwrap();
+    let table_url = ensure_table_uri(uri).unwrap();
+    let other_dt = deltalake_core::open_table(table_url).await?;
+    let add = other_dt
+        .snapshot()?
+        .log_data()
+        .into_iter()
+        .find(|file| {
+            file.partition_values_map().get("date") == Some(&Some("2022-05-22".to_string()))
+        })
+        .unwrap();
+    CommitBuilder::default()
+        .with_actions(vec![Action::Remove(add.remove_action(true))])
+        .build(
+            Some(other_dt.snapshot()?),
+            other_dt.log_store(),
+            DeltaOperation::Delete { predicate: None },
+        )
+        .await?;
+
+    let metrics = plan
+        .execute(
+            dt.log_store(),
+            dt.snapshot()?.snapshot(),
+            1,
+            None,
+            CommitProperties::default(),
+            Uuid::new_v4(),
+            None,
+        )
+        .await?;
+    assert_eq!(metrics.num_bins_skipped, 1);
+    assert_eq!(metrics.num_files_added, 1);
+    assert_eq!(metrics.num_files_removed, 2);
+
+    dt.update_state().await?;
+    assert_eq!(dt.version().unwrap(), version + 2);
+    // the remaining file of the skipped bin is still active
+    assert_eq!(dt.snapshot()?.log_data().num_files(), 2);
+    Ok(())
+}
+
 #[tokio::test]
 /// Validate that bin packing is idempotent.
 async fn test_idempotent() -> Result<(), Box<dyn Error>> {
@@ -1269,6 +1404,8 @@ async fn test_idempotent_metrics() -> Result<(), Box<dyn Error>> {
         planner_strategy: PlannerStrategy::PreserveLocality,
         preserved_stable_order: true,
         max_bin_span_files: 0,
+        spilled_bytes: 0,
+        num_bins_skipped: 0,
         files_added: expected_metric_details.clone(),
         files_removed: expected_metric_details,
     };
@@ -1928,6 +2065,41 @@ async fn test_zorder_unpartitioned() -> Result<(), Box<dyn Error>> {
     Ok(())
 }
 
+#[tokio::test]
+async fn test_zorder_with_max_spill_size() -> Result<(), Box<dyn Error>> {
+    let context = setup_test(false).await?;
+    let mut dt = context.table;
+    let mut writer = RecordBatchWriter::for_table(&dt)?;
+
+    for date in ["1970-01-01", "1970-01-04"] {
+        write(
+            &mut writer,
+            &mut dt,
+            tuples_to_batch(vec![(2, 1), (1, 2), (1, 4)], date)?,
+        )
+        .await?;
+    }
+
+    let (dt, metrics) = dt
+        .optimize()
+        .with_type(OptimizeType::ZOrder(vec!["x".to_string(), "y".to_string()]))
+        .with_max_spill_size(16 * 1024 * 1024)
+        .with_max_temp_directory_size(64 * 1024 * 1024)
+        .await?;
+
+    assert_eq!(metrics.num_files_added, 1);
+    assert_eq!(metrics.num_files_removed, 2);
+    // the rows fit into memory
+    assert_eq!(metrics.spilled_bytes, 0);
+
+    let commit_info: Vec<_> = dt.history(Some(1)).await?.collect();
+    assert_eq!(
+        commit_info[0].info["operationMetrics"]["spilledBytes"],
+        json!(0)
+    );
+    Ok(())
+}
+
 #[tokio::test]
 async fn test_zorder_partitioned() -> Result<(), Box<dyn Error>> {
     let context = setup_test(true).await?;
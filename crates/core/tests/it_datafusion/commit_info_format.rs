@@ -1,12 +1,17 @@
 #![allow(dead_code)]
 
 use crate::fs_common;
-use deltalake_core::crate_version;
+use arrow_array::{Int32Array, RecordBatch};
+use arrow_schema::{DataType, Field, Schema as ArrowSchema};
+use chrono::Duration;
+use datafusion::prelude::{col, lit};
 use deltalake_core::kernel::Action;
-use deltalake_core::kernel::transaction::CommitBuilder;
+use deltalake_core::kernel::transaction::{CommitBuilder, CommitProperties};
 use deltalake_core::protocol::{DeltaOperation, SaveMode};
+use deltalake_core::{DeltaTable, crate_version};
 use serde_json::json;
 use std::error::Error;
+use std::sync::Arc;
 
 #[tokio::test]
 async fn test_commit_info_engine_info() -> Result<(), Box<dyn Error>> {
@@ -41,3 +46,59 @@ async fn test_commit_info_engine_info() -> Result<(), Box<dyn Error>> {
 
     Ok(())
 }
+
+#[tokio::test]
+async fn test_commit_properties_on_every_operation() -> Result<(), Box<dyn Error>> {
+    let props = || {
+        CommitProperties::default()
+            .with_metadata_entry("jobId", "job-42")
+            .with_user_metadata("nightly load")
+            .with_engine_info("lineage-engine/1.0")
+    };
+    let batch = |values: Vec<i32>| {
+        let schema = Arc::new(ArrowSchema::new(vec![Field::new(
+            "value",
+            DataType::Int32,
+            true,
+        )]));
+        RecordBatch::try_new(schema, vec![Arc::new(Int32Array::from(values))])
+    };
+
+    let table = DeltaTable::new_in_memory()
+        .write(vec![batch(vec![1, 2, 3])?])
+        .with_commit_properties(props())
+        .await?;
+    let table = table
+        .write(vec![batch(vec![4, 5, 6])?])
+        .with_save_mode(SaveMode::Append)
+        .with_commit_properties(props())
+        .await?;
+    let (table, _) = table
+        .delete()
+        .with_predicate(col("value").eq(lit(1)))
+        .with_commit_properties(props())
+        .await?;
+    let (table, _) = table
+        .update()
+        .with_predicate(col("value").eq(lit(2)))
+        .with_update("value", lit(20))
+        .with_commit_properties(props())
+        .await?;
+    let (table, _) = table.optimize().with_commit_properties(props()).await?;
+    let (table, _) = table
+        .vacuum()
+        .with_retention_period(Duration::zero())
+        .with_enforce_retention_duration(false)
+        .with_commit_properties(props())
+        .await?;
+
+    let history: Vec<_> = table.history(None).await?.collect();
+    assert!(history.len() >= 6);
+    for commit in history {
+        assert_eq!(commit.info.get("jobId"), Some(&json!("job-42")));
+        assert_eq!(commit.user_metadata.as_deref(), Some("nightly load"));
+        assert_eq!(commit.engine_info.as_deref(), Some("lineage-engine/1.0"));
+    }
+
+    Ok(())
+}
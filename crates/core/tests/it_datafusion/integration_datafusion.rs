@@ -208,6 +208,60 @@ mod local {
         Ok(())
     }
 
+    #[tokio::test]
+    async fn test_datafusion_sql_registration_time_travel() -> Result<()> {
+        let schema = Arc::new(ArrowSchema::new(vec![ArrowField::new(
+            "id",
+            ArrowDataType::Int64,
+            true,
+        )]));
+        let batches = [vec![1, 2], vec![3]]
+            .into_iter()
+            .map(|values| {
+                RecordBatch::try_new(schema.clone(), vec![Arc::new(Int64Array::from(values))])
+            })
+            .collect::<std::result::Result<Vec<_>, _>>()?;
+        let (table_dir, _) = prepare_table(batches, SaveMode::Append, vec![]).await;
+        let location = table_dir.path().to_str().unwrap();
+
+        let count_rows = |options: &'static str| async move {
+            let ctx = context_with_delta_table_factory();
+            ctx.sql(&format!(
+                "CREATE EXTERNAL TABLE demo STORED AS DELTATABLE OPTIONS ({options}) LOCATION '{location}'"
+            ))
+            .await?;
+            let batches = ctx
+                .sql("SELECT count(*) FROM demo")
+                .await?
+                .collect()
+                .await?;
+            let count = batches[0]
+                .column(0)
+                .as_any()
+                .downcast_ref::<Int64Array>()
+                .unwrap()
+                .value(0);
+            Ok::<_, DataFusionError>(count)
+        };
+
+        assert_eq!(count_rows("'version' '1'").await?, 2);
+        assert_eq!(count_rows("'VERSION' '2'").await?, 3);
+        assert_eq!(count_rows("'timestamp' '2999-01-01T00:00:00Z'").await?, 3);
+        assert!(count_rows("'version' 'latest'").await.is_err());
+        assert!(
+            count_rows("'timestamp' '2000-01-01T00:00:00Z'")
+                .await
+                .is_err()
+        );
+        assert!(
+            count_rows("'version' '1', 'timestamp' '2999-01-01T00:00:00Z'")
+                .await
+                .is_err()
+        );
+
+        Ok(())
+    }
+
     async fn assert_registered_table_has_no_view_types(
         ctx: &SessionContext,
         table_name: &str,
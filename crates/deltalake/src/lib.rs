@@ -10,12 +10,18 @@ compile_error!("You must enable at least one of the features: `rustls` or `nativ
 pub use deltalake_aws as aws;
 #[cfg(feature = "azure")]
 pub use deltalake_azure as azure;
+#[cfg(feature = "glue")]
+pub use deltalake_catalog_glue as glue_catalog;
+#[cfg(feature = "hms")]
+pub use deltalake_catalog_hms as hms_catalog;
 #[cfg(feature = "unity-experimental")]
 pub use deltalake_catalog_unity as unity_catalog;
 #[cfg(feature = "gcs")]
 pub use deltalake_gcp as gcp;
 #[cfg(feature = "hdfs")]
 pub use deltalake_hdfs as hdfs;
+#[cfg(feature = "http")]
+pub use deltalake_http as http;
 #[cfg(feature = "lakefs")]
 pub use deltalake_lakefs as lakefs;
 #[cfg(feature = "opendal")]
@@ -53,6 +59,14 @@ mod __deltalake_auto_register_hdfs {
     }
 }
 
+#[cfg(feature = "http")]
+mod __deltalake_auto_register_http {
+    #[ctor::ctor]
+    fn register() {
+        crate::http::register_handlers(None);
+    }
+}
+
 #[cfg(feature = "opendal")]
 mod __deltalake_auto_register_opendal {
     #[ctor::ctor]
@@ -69,6 +83,22 @@ mod __deltalake_auto_register_lakefs {
     }
 }
 
+#[cfg(feature = "glue")]
+mod __deltalake_auto_register_glue {
+    #[ctor::ctor]
+    fn register() {
+        crate::glue_catalog::register_handlers(None);
+    }
+}
+
+#[cfg(feature = "hms")]
+mod __deltalake_auto_register_hms {
+    #[ctor::ctor]
+    fn register() {
+        crate::hms_catalog::register_handlers(None);
+    }
+}
+
 #[cfg(feature = "unity-experimental")]
 mod __deltalake_auto_register_unity {
     #[ctor::ctor]
@@ -0,0 +1,105 @@
+/*
+ * C interface of delta-rs, see the documentation of the deltalake-ffi crate.
+ *
+ * Functions which can fail take a `DeltaError **error` as last argument. On failure they return
+ * NULL, false or -1 and, unless `error` is NULL, store an error in it which must be released
+ * with delta_error_free. Strings returned by the library are released with delta_string_free.
+ */
+#ifndef DELTALAKE_H
+#define DELTALAKE_H
+
+#include <stdbool.h>
+#include <stdint.h>
+
+#ifdef __cplusplus
+extern "C" {
+#endif
+
+#ifndef ARROW_C_DATA_INTERFACE
+#define ARROW_C_DATA_INTERFACE
+
+#define ARROW_FLAG_DICTIONARY_ORDERED 1
+#define ARROW_FLAG_NULLABLE 2
+#define ARROW_FLAG_MAP_KEYS_SORTED 4
+
+struct ArrowSchema {
+  const char* format;
+  const char* name;
+  const char* metadata;
+  int64_t flags;
+  int64_t n_children;
+  struct ArrowSchema** children;
+  struct ArrowSchema* dictionary;
+  void (*release)(struct ArrowSchema*);
+  void* private_data;
+};
+
+struct ArrowArray {
+  int64_t length;
+  int64_t null_count;
+  int64_t offset;
+  int64_t n_buffers;
+  int64_t n_children;
+  const void** buffers;
+  struct ArrowArray** children;
+  struct ArrowArray* dictionary;
+  void (*release)(struct ArrowArray*);
+  void* private_data;
+};
+
+#endif /* ARROW_C_DATA_INTERFACE */
+
+#ifndef ARROW_C_STREAM_INTERFACE
+#define ARROW_C_STREAM_INTERFACE
+
+struct ArrowArrayStream {
+  int (*get_schema)(struct ArrowArrayStream*, struct ArrowSchema* out);
+  int (*get_next)(struct ArrowArrayStream*, struct ArrowArray* out);
+  const char* (*get_last_error)(struct ArrowArrayStream*);
+  void (*release)(struct ArrowArrayStream*);
+  void* private_data;
+};
+
+#endif /* ARROW_C_STREAM_INTERFACE */
+
+typedef struct DeltaTableHandle DeltaTableHandle;
+typedef struct DeltaError DeltaError;
+
+/* Open the table at `uri`, a URL or local path. `storage_options` is an optional JSON object,
+ * the latest version is loaded if `version` is negative. */
+DeltaTableHandle* delta_table_open(const char* uri, const char* storage_options, int64_t version,
+                                   DeltaError** error);
+void delta_table_free(DeltaTableHandle* table);
+
+/* Version of the loaded snapshot */
+int64_t delta_table_version(const DeltaTableHandle* table, DeltaError** error);
+
+/* Metadata and protocol of the loaded snapshot as JSON object */
+char* delta_table_metadata(const DeltaTableHandle* table, DeltaError** error);
+
+/* Export the schema of the loaded snapshot, released through `out->release` */
+bool delta_table_arrow_schema(const DeltaTableHandle* table, struct ArrowSchema* out,
+                              DeltaError** error);
+
+/* URIs of the data files as JSON array, `partition_filters` is an optional JSON array of
+ * [column, op, value] triples */
+char* delta_table_file_uris(const DeltaTableHandle* table, const char* partition_filters,
+                            DeltaError** error);
+
+/* Append all batches of `stream`, which is consumed, and return the version of the commit */
+int64_t delta_table_append(DeltaTableHandle* table, struct ArrowArrayStream* stream,
+                           DeltaError** error);
+
+/* Code and message of an error, owned by the error */
+const char* delta_error_code(const DeltaError* error);
+const char* delta_error_message(const DeltaError* error);
+bool delta_error_is_retryable(const DeltaError* error);
+void delta_error_free(DeltaError* error);
+
+void delta_string_free(char* s);
+
+#ifdef __cplusplus
+}
+#endif
+
+#endif /* DELTALAKE_H */
@@ -0,0 +1,535 @@
+//! C ABI for embedding delta-rs into non-Rust engines.
+//!
+//! Tables are opened into an opaque [`DeltaTableHandle`], which exposes the metadata of the
+//! loaded snapshot, the files of the table and appends. Schemas and data cross the boundary
+//! through the [Arrow C Data Interface](https://arrow.apache.org/docs/format/CDataInterface.html),
+//! everything else as NUL terminated UTF-8 strings, with structured values encoded as JSON.
+//!
+//! Functions which can fail take a `DeltaError **error` as last argument. On failure they return
+//! `NULL`, `false` or `-1` and, unless `error` is `NULL`, store an error in it which must be
+//! released with [`delta_error_free`]. Strings returned by the library are released with
+//! [`delta_string_free`] and tables with [`delta_table_free`]. All calls block the calling
+//! thread until the operation completed; IO is driven by a runtime owned by the library.
+//!
+//! The matching declarations are in `include/deltalake.h`.
+#![allow(clippy::missing_safety_doc)]
+
+use std::collections::HashMap;
+use std::ffi::{CStr, CString, c_char};
+use std::panic::{AssertUnwindSafe, catch_unwind};
+use std::ptr;
+use std::sync::OnceLock;
+
+use arrow::datatypes::Schema as ArrowSchema;
+use arrow::ffi::FFI_ArrowSchema;
+use arrow::ffi_stream::{ArrowArrayStreamReader, FFI_ArrowArrayStream};
+use delta_kernel::engine::arrow_conversion::TryIntoArrow as _;
+use deltalake::errors::{DeltaTableError, ErrorKind};
+use deltalake::table::builder::parse_table_uri;
+use deltalake::writer::{DeltaWriter as _, RecordBatchWriter};
+use deltalake::{DeltaTable, DeltaTableBuilder, PartitionFilter};
+use serde_json::{Value, json};
+use tokio::runtime::Runtime;
+
+/// A table opened through [`delta_table_open`]
+pub struct DeltaTableHandle {
+    table: DeltaTable,
+}
+
+/// An error returned to the caller
+#[derive(Debug)]
+pub struct DeltaError {
+    kind: ErrorKind,
+    code: CString,
+    message: CString,
+}
+
+impl DeltaError {
+    fn new(kind: ErrorKind, message: impl Into<String>) -> Self {
+        Self {
+            kind,
+            code: CString::new(kind.code()).unwrap_or_default(),
+            message: to_cstring(message.into()),
+        }
+    }
+
+    fn invalid_argument(message: impl Into<String>) -> Self {
+        Self::new(ErrorKind::InvalidArgument, message)
+    }
+}
+
+impl From<DeltaTableError> for DeltaError {
+    fn from(err: DeltaTableError) -> Self {
+        Self::new(err.kind(), err.to_string())
+    }
+}
+
+impl From<arrow::error::ArrowError> for DeltaError {
+    fn from(err: arrow::error::ArrowError) -> Self {
+        DeltaTableError::from(err).into()
+    }
+}
+
+type FfiResult<T> = Result<T, DeltaError>;
+
+fn runtime() -> &'static Runtime {
+    static RUNTIME: OnceLock<Runtime> = OnceLock::new();
+    RUNTIME.get_or_init(|| {
+        tokio::runtime::Builder::new_multi_thread()
+            .thread_name("deltalake-ffi")
+            .enable_all()
+            .build()
+            .expect("Failed to create the runtime of the FFI layer")
+    })
+}
+
+/// Run `f`, reporting failures and panics through `error` and returning `on_error` for them
+unsafe fn ffi_call<T>(
+    error: *mut *mut DeltaError,
+    on_error: T,
+    f: impl FnOnce() -> FfiResult<T>,
+) -> T {
+    let err = match catch_unwind(AssertUnwindSafe(f)) {
+        Ok(Ok(value)) => return value,
+        Ok(Err(err)) => err,
+        Err(panic) => {
+            let message = panic
+                .downcast_ref::<&str>()
+                .map(|s| s.to_string())
+                .or_else(|| panic.downcast_ref::<String>().cloned())
+                .unwrap_or_else(|| "delta-rs panicked".to_string());
+            DeltaError::new(ErrorKind::Internal, message)
+        }
+    };
+    if !error.is_null() {
+        unsafe { *error = Box::into_raw(Box::new(err)) };
+    }
+    on_error
+}
+
+fn to_cstring(s: String) -> CString {
+    CString::new(s).unwrap_or_else(|err| {
+        let mut bytes = err.into_vec();
+        bytes.retain(|b| *b != 0);
+        CString::new(bytes).unwrap_or_default()
+    })
+}
+
+unsafe fn read_str<'a>(s: *const c_char, name: &str) -> FfiResult<&'a str> {
+    if s.is_null() {
+        return Err(DeltaError::invalid_argument(format!(
+            "{name} must not be NULL"
+        )));
+    }
+    unsafe { CStr::from_ptr(s) }
+        .to_str()
+        .map_err(|_| DeltaError::invalid_argument(format!("{name} is not valid UTF-8")))
+}
+
+unsafe fn read_json(s: *const c_char, name: &str) -> FfiResult<Option<Value>> {
+    if s.is_null() {
+        return Ok(None);
+    }
+    let s = unsafe { read_str(s, name) }?;
+    serde_json::from_str(s)
+        .map(Some)
+        .map_err(|err| DeltaError::invalid_argument(format!("{name} is not valid JSON: {err}")))
+}
+
+unsafe fn table_ref<'a>(table: *const DeltaTableHandle) -> FfiResult<&'a DeltaTable> {
+    unsafe { table.as_ref() }
+        .map(|handle| &handle.table)
+        .ok_or_else(|| DeltaError::invalid_argument("table must not be NULL"))
+}
+
+fn storage_options(options: Option<Value>) -> FfiResult<HashMap<String, String>> {
+    let Some(options) = options else {
+        return Ok(HashMap::new());
+    };
+    let Value::Object(options) = options else {
+        return Err(DeltaError::invalid_argument(
+            "storage_options must be a JSON object",
+        ));
+    };
+    Ok(options
+        .into_iter()
+        .map(|(key, value)| match value {
+            Value::String(value) => (key, value),
+            value => (key, value.to_string()),
+        })
+        .collect())
+}
+
+/// Parse filters given as JSON array of `[column, op, value]` triples, where `value` is an
+/// array of strings for the `in` and `not in` operators
+fn partition_filters(filters: Option<Value>) -> FfiResult<Vec<PartitionFilter>> {
+    let Some(filters) = filters else {
+        return Ok(Vec::new());
+    };
+    let invalid = |filter: &Value| {
+        DeltaError::from(DeltaTableError::InvalidPartitionFilter {
+            partition_filter: filter.to_string(),
+        })
+    };
+    let Value::Array(filters) = &filters else {
+        return Err(invalid(&filters));
+    };
+    filters
+        .iter()
+        .map(|filter| {
+            let [Value::String(key), Value::String(op), value] =
+                filter.as_array().map(Vec::as_slice).unwrap_or_default()
+            else {
+                return Err(invalid(filter));
+            };
+            let filter = match value {
+                Value::String(value) => {
+                    PartitionFilter::try_from((key.as_str(), op.as_str(), value.as_str()))?
+                }
+                Value::Array(values) => {
+                    let values = values
+                        .iter()
+                        .map(|v| v.as_str().ok_or_else(|| invalid(filter)))
+                        .collect::<FfiResult<Vec<_>>>()?;
+                    PartitionFilter::try_from((key.as_str(), op.as_str(), values.as_slice()))?
+                }
+                _ => return Err(invalid(filter)),
+            };
+            Ok(filter)
+        })
+        .collect()
+}
+
+/// Open the table at `uri`, a URL or local path.
+///
+/// `storage_options` is an optional JSON object of options for the object store, e.g.
+/// `{"AWS_REGION": "us-east-1"}`. The latest version is loaded if `version` is negative.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn delta_table_open(
+    uri: *const c_char,
+    storage_options: *const c_char,
+    version: i64,
+    error: *mut *mut DeltaError,
+) -> *mut DeltaTableHandle {
+    unsafe {
+        ffi_call(error, ptr::null_mut(), || {
+            let uri = read_str(uri, "uri")?;
+            let options = self::storage_options(read_json(storage_options, "storage_options")?)?;
+            let mut builder =
+                DeltaTableBuilder::from_url(parse_table_uri(uri)?)?.with_storage_options(options);
+            if version >= 0 {
+                builder = builder.with_version(version as u64);
+            }
+            let table = runtime().block_on(builder.load())?;
+            Ok(Box::into_raw(Box::new(DeltaTableHandle { table })))
+        })
+    }
+}
+
+/// Release a table returned by [`delta_table_open`]
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn delta_table_free(table: *mut DeltaTableHandle) {
+    if !table.is_null() {
+        drop(unsafe { Box::from_raw(table) });
+    }
+}
+
+/// Version of the loaded snapshot of the table
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn delta_table_version(
+    table: *const DeltaTableHandle,
+    error: *mut *mut DeltaError,
+) -> i64 {
+    unsafe {
+        ffi_call(error, -1, || {
+            Ok(table_ref(table)?.snapshot()?.version() as i64)
+        })
+    }
+}
+
+/// Metadata and protocol of the loaded snapshot as JSON object.
+///
+/// The object has the keys `version`, `id`, `name`, `description`, `partitionColumns`,
+/// `configuration`, `createdTime`, `minReaderVersion`, `minWriterVersion` and `schema`, the
+/// latter being the Delta schema of the table.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn delta_table_metadata(
+    table: *const DeltaTableHandle,
+    error: *mut *mut DeltaError,
+) -> *mut c_char {
+    unsafe {
+        ffi_call(error, ptr::null_mut(), || {
+            let snapshot = table_ref(table)?.snapshot()?;
+            let metadata = snapshot.metadata();
+            let protocol = snapshot.protocol();
+            let value = json!({
+                "version": snapshot.version(),
+                "id": metadata.id(),
+                "name": metadata.name(),
+                "description": metadata.description(),
+                "partitionColumns": metadata.partition_columns(),
+                "configuration": metadata.configuration(),
+                "createdTime": metadata.created_time(),
+                "minReaderVersion": protocol.min_reader_version(),
+                "minWriterVersion": protocol.min_writer_version(),
+                "schema": snapshot.schema().as_ref(),
+            });
+            Ok(to_cstring(value.to_string()).into_raw())
+        })
+    }
+}
+
+/// Export the schema of the loaded snapshot into `out` as Arrow schema.
+///
+/// The caller owns the exported schema and must release it through its `release` callback.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn delta_table_arrow_schema(
+    table: *const DeltaTableHandle,
+    out: *mut FFI_ArrowSchema,
+    error: *mut *mut DeltaError,
+) -> bool {
+    unsafe {
+        ffi_call(error, false, || {
+            if out.is_null() {
+                return Err(DeltaError::invalid_argument("out must not be NULL"));
+            }
+            let schema: ArrowSchema = table_ref(table)?
+                .snapshot()?
+                .schema()
+                .as_ref()
+                .try_into_arrow()?;
+            ptr::write(out, FFI_ArrowSchema::try_from(&schema)?);
+            Ok(true)
+        })
+    }
+}
+
+/// URIs of the data files of the loaded snapshot as JSON array of strings.
+///
+/// `partition_filters` is an optional JSON array of `[column, op, value]` triples, which must
+/// all hold for the partition values of a file, e.g. `[["year", "=", "2024"]]`. The supported
+/// operators are `=`, `!=`, `<`, `<=`, `>`, `>=` and, with an array of values, `in` and
+/// `not in`.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn delta_table_file_uris(
+    table: *const DeltaTableHandle,
+    partition_filters: *const c_char,
+    error: *mut *mut DeltaError,
+) -> *mut c_char {
+    unsafe {
+        ffi_call(error, ptr::null_mut(), || {
+            let table = table_ref(table)?;
+            let filters =
+                self::partition_filters(read_json(partition_filters, "partition_filters")?)?;
+            let uris = runtime().block_on(table.get_file_uris_by_partitions(&filters))?;
+            Ok(to_cstring(Value::from(uris).to_string()).into_raw())
+        })
+    }
+}
+
+/// Append all batches of `stream` to the table and return the version of the commit.
+///
+/// The schema of the stream must match the schema of the table. The stream is consumed, i.e.
+/// released by this function, also on failure. On success the table is updated to the new
+/// version.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn delta_table_append(
+    table: *mut DeltaTableHandle,
+    stream: *mut FFI_ArrowArrayStream,
+    error: *mut *mut DeltaError,
+) -> i64 {
+    unsafe {
+        ffi_call(error, -1, || {
+            if stream.is_null() {
+                return Err(DeltaError::invalid_argument("stream must not be NULL"));
+            }
+            let reader = ArrowArrayStreamReader::from_raw(stream)?;
+            let handle = table
+                .as_mut()
+                .ok_or_else(|| DeltaError::invalid_argument("table must not be NULL"))?;
+            let version = runtime().block_on(async {
+                let mut writer = RecordBatchWriter::for_table(&handle.table)?;
+                for batch in reader {
+                    writer.write(batch?).await?;
+                }
+                writer.flush_and_commit(&mut handle.table).await
+            })?;
+            Ok(version as i64)
+        })
+    }
+}
+
+/// Stable code of the kind of the error, e.g. `DELTA_COMMIT_CONFLICT`.
+///
+/// The string is owned by the error.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn delta_error_code(error: *const DeltaError) -> *const c_char {
+    unsafe { error.as_ref() }.map_or(ptr::null(), |err| err.code.as_ptr())
+}
+
+/// Message of the error, owned by the error
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn delta_error_message(error: *const DeltaError) -> *const c_char {
+    unsafe { error.as_ref() }.map_or(ptr::null(), |err| err.message.as_ptr())
+}
+
+/// Whether running the failed operation again may succeed
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn delta_error_is_retryable(error: *const DeltaError) -> bool {
+    unsafe { error.as_ref() }.is_some_and(|err| err.kind.is_retryable())
+}
+
+/// Release an error
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn delta_error_free(error: *mut DeltaError) {
+    if !error.is_null() {
+        drop(unsafe { Box::from_raw(error) });
+    }
+}
+
+/// Release a string returned by the library
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn delta_string_free(s: *mut c_char) {
+    if !s.is_null() {
+        drop(unsafe { CString::from_raw(s) });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use arrow::array::{Int32Array, RecordBatch, RecordBatchIterator, StringArray};
+    use deltalake::kernel::{DataType, PrimitiveType, StructField};
+
+    use super::*;
+
+    fn take_string(s: *mut c_char) -> String {
+        assert!(!s.is_null());
+        let value = unsafe { CStr::from_ptr(s) }.to_str().unwrap().to_string();
+        unsafe { delta_string_free(s) };
+        value
+    }
+
+    fn create_table(path: &str) {
+        runtime()
+            .block_on(async {
+                DeltaTableBuilder::from_url(deltalake::ensure_table_uri(path)?)?
+                    .build()?
+                    .create()
+                    .with_columns(vec![
+                        StructField::new("id", DataType::Primitive(PrimitiveType::Integer), true),
+                        StructField::new("part", DataType::Primitive(PrimitiveType::String), true),
+                    ])
+                    .with_partition_columns(["part"])
+                    .await
+            })
+            .unwrap();
+    }
+
+    fn append(table: *mut DeltaTableHandle, part: &str) -> i64 {
+        let schema = Arc::new(ArrowSchema::new(vec![
+            arrow::datatypes::Field::new("id", arrow::datatypes::DataType::Int32, true),
+            arrow::datatypes::Field::new("part", arrow::datatypes::DataType::Utf8, true),
+        ]));
+        let batch = RecordBatch::try_new(
+            schema.clone(),
+            vec![
+                Arc::new(Int32Array::from(vec![1, 2])),
+                Arc::new(StringArray::from(vec![part, part])),
+            ],
+        )
+        .unwrap();
+        let reader = RecordBatchIterator::new(vec![Ok(batch)], schema);
+        let mut stream = FFI_ArrowArrayStream::new(Box::new(reader));
+        let mut error = ptr::null_mut();
+        let version = unsafe { delta_table_append(table, &mut stream, &mut error) };
+        assert!(error.is_null());
+        version
+    }
+
+    #[test]
+    fn test_open_append_and_list() {
+        let tmp = tempfile::tempdir().unwrap();
+        let path = tmp.path().to_str().unwrap();
+        create_table(path);
+
+        let uri = CString::new(path).unwrap();
+        let mut error = ptr::null_mut();
+        let table = unsafe { delta_table_open(uri.as_ptr(), ptr::null(), -1, &mut error) };
+        assert!(error.is_null());
+        assert_eq!(unsafe { delta_table_version(table, &mut error) }, 0);
+
+        assert_eq!(append(table, "a"), 1);
+        assert_eq!(append(table, "b"), 2);
+        assert_eq!(unsafe { delta_table_version(table, &mut error) }, 2);
+
+        let metadata: Value = serde_json::from_str(&take_string(unsafe {
+            delta_table_metadata(table, &mut error)
+        }))
+        .unwrap();
+        assert_eq!(metadata["version"], 2);
+        assert_eq!(metadata["partitionColumns"], json!(["part"]));
+        assert_eq!(metadata["schema"]["fields"][0]["name"], "id");
+
+        let mut schema = FFI_ArrowSchema::empty();
+        assert!(unsafe { delta_table_arrow_schema(table, &mut schema, &mut error) });
+        let schema = ArrowSchema::try_from(&schema).unwrap();
+        assert_eq!(schema.fields().len(), 2);
+
+        let files = |filters: Option<&str>| -> Vec<String> {
+            let filters = filters.map(|f| CString::new(f).unwrap());
+            let filters = filters.as_ref().map_or(ptr::null(), |f| f.as_ptr());
+            let mut error = ptr::null_mut();
+            let uris = unsafe { delta_table_file_uris(table, filters, &mut error) };
+            assert!(error.is_null());
+            serde_json::from_str(&take_string(uris)).unwrap()
+        };
+        assert_eq!(files(None).len(), 2);
+        let filtered = files(Some(r#"[["part", "=", "b"]]"#));
+        assert_eq!(filtered.len(), 1);
+        assert!(filtered[0].contains("part=b"));
+        assert_eq!(files(Some(r#"[["part", "in", ["a", "b"]]]"#)).len(), 2);
+
+        let reopened = unsafe { delta_table_open(uri.as_ptr(), ptr::null(), 1, &mut error) };
+        assert!(error.is_null());
+        assert_eq!(unsafe { delta_table_version(reopened, &mut error) }, 1);
+
+        unsafe {
+            delta_table_free(reopened);
+            delta_table_free(table);
+        }
+    }
+
+    #[test]
+    fn test_errors() {
+        let mut error = ptr::null_mut();
+        let table = unsafe { delta_table_open(ptr::null(), ptr::null(), -1, &mut error) };
+        assert!(table.is_null());
+        let code = unsafe { CStr::from_ptr(delta_error_code(error)) };
+        assert_eq!(code.to_str().unwrap(), "DELTA_INVALID_ARGUMENT");
+        assert!(!unsafe { delta_error_is_retryable(error) });
+        unsafe { delta_error_free(error) };
+
+        let tmp = tempfile::tempdir().unwrap();
+        create_table(tmp.path().to_str().unwrap());
+        let uri = CString::new(tmp.path().to_str().unwrap()).unwrap();
+        let mut error = ptr::null_mut();
+        let table = unsafe { delta_table_open(uri.as_ptr(), ptr::null(), -1, &mut error) };
+
+        let filters = CString::new(r#"[["part", "~", "a"]]"#).unwrap();
+        let uris = unsafe { delta_table_file_uris(table, filters.as_ptr(), &mut error) };
+        assert!(uris.is_null());
+        let message = unsafe { CStr::from_ptr(delta_error_message(error)) };
+        assert!(
+            message
+                .to_str()
+                .unwrap()
+                .contains("Invalid partition filter")
+        );
+        unsafe {
+            delta_error_free(error);
+            delta_table_free(table);
+        }
+    }
+}
@@ -0,0 +1,280 @@
+//! Narrowed credentials for Google Cloud Storage.
+//!
+//! Multi-tenant services often hand out credentials which only grant access to a subset of a
+//! bucket. Two mechanisms are supported:
+//!
+//! - **Credential access boundaries**: the token issued by the configured credential chain is
+//!   exchanged at the Security Token Service for a downscoped token, restricted by the configured
+//!   access boundary. Downscoped tokens are cached and exchanged again before they expire.
+//! - **HMAC keys**: the bucket is accessed through the S3 compatible XML API using an HMAC access
+//!   id and secret.
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use deltalake_core::logstore::object_store::gcp::{GcpCredential, GcpCredentialProvider};
+use object_store::CredentialProvider;
+use object_store::client::HttpClient;
+use serde::Deserialize;
+use tokio::sync::Mutex;
+
+use crate::error::{Error, Result};
+
+/// JSON document describing the credential access boundary used to downscope tokens.
+///
+/// Either the full options document (`{"accessBoundary": {...}}`) or only the access
+/// boundary itself (`{"accessBoundaryRules": [...]}`) may be passed.
+pub const GOOGLE_CREDENTIAL_ACCESS_BOUNDARY: &str = "GOOGLE_CREDENTIAL_ACCESS_BOUNDARY";
+/// Endpoint of the Security Token Service used to exchange downscoped tokens.
+pub const GOOGLE_STS_ENDPOINT: &str = "GOOGLE_STS_ENDPOINT";
+/// Access id of an HMAC key used to access the bucket through the XML API.
+pub const GOOGLE_HMAC_ACCESS_ID: &str = "GOOGLE_HMAC_ACCESS_ID";
+/// Secret of an HMAC key used to access the bucket through the XML API.
+pub const GOOGLE_HMAC_SECRET: &str = "GOOGLE_HMAC_SECRET";
+/// Endpoint of the S3 compatible XML API used with HMAC keys.
+pub const GOOGLE_HMAC_ENDPOINT: &str = "GOOGLE_HMAC_ENDPOINT";
+
+pub(crate) const DEFAULT_STS_ENDPOINT: &str = "https://sts.googleapis.com/v1/token";
+pub(crate) const DEFAULT_HMAC_ENDPOINT: &str = "https://storage.googleapis.com";
+
+const TOKEN_EXCHANGE_GRANT_TYPE: &str = "urn:ietf:params:oauth:grant-type:token-exchange";
+const ACCESS_TOKEN_TYPE: &str = "urn:ietf:params:oauth:token-type:access_token";
+
+/// Downscoped tokens are exchanged again once they expire within this window.
+const REFRESH_SKEW: Duration = Duration::from_secs(300);
+
+/// Look up an option by key (case-insensitive), falling back to the environment.
+pub(crate) fn lookup_option(options: &HashMap<String, String>, key: &str) -> Option<String> {
+    options
+        .iter()
+        .find(|(k, _)| k.eq_ignore_ascii_case(key))
+        .map(|(_, v)| v.clone())
+        .or_else(|| std::env::var(key).ok())
+}
+
+/// Parse a credential access boundary into the `options` document sent to the token service.
+pub(crate) fn parse_access_boundary(value: &str) -> Result<String> {
+    let boundary: serde_json::Value = serde_json::from_str(value)
+        .map_err(|e| Error::Parse(format!("invalid credential access boundary: {e}")))?;
+    let options = match boundary {
+        serde_json::Value::Object(ref obj) if obj.contains_key("accessBoundary") => boundary,
+        serde_json::Value::Object(ref obj) if obj.contains_key("accessBoundaryRules") => {
+            serde_json::json!({ "accessBoundary": boundary })
+        }
+        _ => {
+            return Err(Error::Parse(
+                "credential access boundary must contain 'accessBoundaryRules'".into(),
+            ));
+        }
+    };
+    Ok(options.to_string())
+}
+
+/// HMAC key used to access the bucket through the S3 compatible XML API.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct HmacKey {
+    pub access_id: String,
+    pub secret: String,
+    pub endpoint: String,
+}
+
+impl HmacKey {
+    /// Read the HMAC key from the options, returns `None` if no key is configured.
+    pub(crate) fn from_options(options: &HashMap<String, String>) -> Result<Option<Self>> {
+        let access_id = lookup_option(options, GOOGLE_HMAC_ACCESS_ID);
+        let secret = lookup_option(options, GOOGLE_HMAC_SECRET);
+        match (access_id, secret) {
+            (Some(access_id), Some(secret)) => Ok(Some(Self {
+                access_id,
+                secret,
+                endpoint: lookup_option(options, GOOGLE_HMAC_ENDPOINT)
+                    .unwrap_or_else(|| DEFAULT_HMAC_ENDPOINT.to_string()),
+            })),
+            (None, None) => Ok(None),
+            _ => Err(Error::Parse(format!(
+                "both {GOOGLE_HMAC_ACCESS_ID} and {GOOGLE_HMAC_SECRET} must be provided"
+            ))),
+        }
+    }
+}
+
+#[derive(Deserialize)]
+struct TokenExchangeResponse {
+    access_token: String,
+    expires_in: Option<u64>,
+}
+
+struct CachedToken {
+    credential: Arc<GcpCredential>,
+    expires_at: Option<Instant>,
+}
+
+impl CachedToken {
+    fn is_stale(&self) -> bool {
+        self.expires_at.is_some_and(|expiry| {
+            expiry
+                .checked_duration_since(Instant::now())
+                .is_none_or(|remaining| remaining <= REFRESH_SKEW)
+        })
+    }
+}
+
+/// A [`GcpCredentialProvider`] issuing tokens narrowed by a credential access boundary.
+pub(crate) struct DownscopedCredentialProvider {
+    source: GcpCredentialProvider,
+    options: String,
+    endpoint: String,
+    client: HttpClient,
+    cache: Mutex<Option<CachedToken>>,
+}
+
+impl std::fmt::Debug for DownscopedCredentialProvider {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("DownscopedCredentialProvider")
+            .field("endpoint", &self.endpoint)
+            .finish_non_exhaustive()
+    }
+}
+
+impl DownscopedCredentialProvider {
+    /// Create a provider exchanging tokens issued by `source` for downscoped tokens.
+    ///
+    /// The token exchange is sent through `client`, which should be built from the client
+    /// options of the storage so proxies, certificates and the IO runtime apply to it as well.
+    pub(crate) fn new(
+        source: GcpCredentialProvider,
+        options: String,
+        endpoint: String,
+        client: HttpClient,
+    ) -> Self {
+        Self {
+            source,
+            options,
+            endpoint,
+            client,
+            cache: Mutex::new(None),
+        }
+    }
+
+    async fn exchange(&self, subject_token: &str) -> object_store::Result<TokenExchangeResponse> {
+        let body = url::form_urlencoded::Serializer::new(String::new())
+            .extend_pairs([
+                ("grant_type", TOKEN_EXCHANGE_GRANT_TYPE),
+                ("subject_token_type", ACCESS_TOKEN_TYPE),
+                ("requested_token_type", ACCESS_TOKEN_TYPE),
+                ("subject_token", subject_token),
+                ("options", self.options.as_str()),
+            ])
+            .finish();
+        let request = http::Request::post(&self.endpoint)
+            .header(
+                http::header::CONTENT_TYPE,
+                "application/x-www-form-urlencoded",
+            )
+            .body(body.into())
+            .map_err(sts_error)?;
+        let response = self.client.execute(request).await.map_err(sts_error)?;
+        let status = response.status();
+        let body = response.into_body().bytes().await.map_err(sts_error)?;
+        if !status.is_success() {
+            return Err(sts_error(format!(
+                "token exchange failed with status {status}: {}",
+                String::from_utf8_lossy(&body)
+            )));
+        }
+        serde_json::from_slice(&body).map_err(sts_error)
+    }
+}
+
+fn sts_error(err: impl Into<Box<dyn std::error::Error + Send + Sync>>) -> object_store::Error {
+    object_store::Error::Generic {
+        store: "GCS",
+        source: err.into(),
+    }
+}
+
+#[async_trait::async_trait]
+impl CredentialProvider for DownscopedCredentialProvider {
+    type Credential = GcpCredential;
+
+    async fn get_credential(&self) -> object_store::Result<Arc<GcpCredential>> {
+        let mut cache = self.cache.lock().await;
+        if let Some(cached) = cache.as_ref()
+            && !cached.is_stale()
+        {
+            return Ok(cached.credential.clone());
+        }
+
+        tracing::debug!("Exchanging GCS token for a downscoped token");
+        let source = self.source.get_credential().await?;
+        let response = self.exchange(&source.bearer).await?;
+        let credential = Arc::new(GcpCredential {
+            bearer: response.access_token,
+        });
+        *cache = Some(CachedToken {
+            credential: credential.clone(),
+            expires_at: response
+                .expires_in
+                .map(|secs| Instant::now() + Duration::from_secs(secs)),
+        });
+        Ok(credential)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_access_boundary() {
+        let rules = r#"{"accessBoundaryRules":[{"availableResource":"//storage.googleapis.com/projects/_/buckets/b","availablePermissions":["inRole:roles/storage.objectViewer"]}]}"#;
+        let options: serde_json::Value =
+            serde_json::from_str(&parse_access_boundary(rules).unwrap()).unwrap();
+        assert!(options["accessBoundary"]["accessBoundaryRules"].is_array());
+
+        let wrapped = format!(r#"{{"accessBoundary":{rules}}}"#);
+        let options: serde_json::Value =
+            serde_json::from_str(&parse_access_boundary(&wrapped).unwrap()).unwrap();
+        assert!(options["accessBoundary"]["accessBoundaryRules"].is_array());
+
+        assert!(parse_access_boundary("not json").is_err());
+        assert!(parse_access_boundary("{}").is_err());
+    }
+
+    #[test]
+    fn test_hmac_key_from_options() {
+        let options = HashMap::from([
+            (
+                "google_hmac_access_id".to_string(),
+                "GOOG1EXAMPLE".to_string(),
+            ),
+            ("google_hmac_secret".to_string(), "secret".to_string()),
+        ]);
+        let key = HmacKey::from_options(&options).unwrap().unwrap();
+        assert_eq!(key.access_id, "GOOG1EXAMPLE");
+        assert_eq!(key.endpoint, DEFAULT_HMAC_ENDPOINT);
+
+        let options = HashMap::from([(
+            "google_hmac_access_id".to_string(),
+            "GOOG1EXAMPLE".to_string(),
+        )]);
+        assert!(HmacKey::from_options(&options).is_err());
+    }
+
+    #[test]
+    fn test_cached_token_staleness() {
+        let credential = Arc::new(GcpCredential {
+            bearer: "token".into(),
+        });
+        let fresh = CachedToken {
+            credential: credential.clone(),
+            expires_at: Some(Instant::now() + Duration::from_secs(3600)),
+        };
+        assert!(!fresh.is_stale());
+        let expiring = CachedToken {
+            credential,
+            expires_at: Some(Instant::now() + Duration::from_secs(60)),
+        };
+        assert!(expiring.is_stale());
+    }
+}
@@ -3,17 +3,24 @@ use std::str::FromStr;
 use std::sync::Arc;
 
 use deltalake_core::logstore::object_store::ObjectStoreScheme;
+use deltalake_core::logstore::object_store::aws::{AmazonS3Builder, S3CopyIfNotExists};
 use deltalake_core::logstore::object_store::gcp::{GoogleCloudStorageBuilder, GoogleConfigKey};
 use deltalake_core::logstore::{LogStore, LogStoreFactory, default_logstore, logstore_factories};
 use deltalake_core::logstore::{
-    ObjectStoreFactory, ObjectStoreRef, StorageConfig, client_options_from_certificate,
-    object_store_factories,
+    ObjectStoreFactory, ObjectStoreRef, StorageConfig, object_store_factories,
 };
 use deltalake_core::{DeltaResult, DeltaTableError, Path};
-use object_store::client::SpawnedReqwestConnector;
+use object_store::client::{HttpClient, HttpConnector, ReqwestConnector, SpawnedReqwestConnector};
+use object_store::signer::Signer;
 use url::Url;
 
+use crate::credential::{
+    DEFAULT_STS_ENDPOINT, DownscopedCredentialProvider, GOOGLE_CREDENTIAL_ACCESS_BOUNDARY,
+    GOOGLE_STS_ENDPOINT, HmacKey, lookup_option, parse_access_boundary,
+};
+
 mod config;
+pub mod credential;
 pub mod error;
 mod storage;
 
@@ -37,42 +44,120 @@ impl GcpOptions for HashMap<String, String> {
 #[derive(Clone, Default, Debug)]
 pub struct GcpFactory {}
 
+/// Configure the builder for the bucket at `url`, without any credential downscoping.
+fn gcs_builder(url: &Url, config: &StorageConfig) -> DeltaResult<GoogleCloudStorageBuilder> {
+    let mut builder = GoogleCloudStorageBuilder::new().with_url(url.to_string());
+    builder = builder.with_retry(config.retry.clone());
+
+    if let Some(runtime) = &config.runtime {
+        builder = builder.with_http_connector(SpawnedReqwestConnector::new(runtime.get_handle()));
+    }
+
+    if let Some(client_options) = config.client_options()? {
+        builder = builder.with_client_options(client_options);
+    }
+
+    let config = config::GcpConfigHelper::try_new(config.raw.as_gcp_options())?.build()?;
+
+    for (key, value) in config.iter() {
+        builder = builder.with_config(*key, value.clone());
+    }
+    Ok(builder)
+}
+
 impl ObjectStoreFactory for GcpFactory {
     fn parse_url_opts(
         &self,
         url: &Url,
         config: &StorageConfig,
     ) -> DeltaResult<(ObjectStoreRef, Path)> {
-        let mut builder = GoogleCloudStorageBuilder::new().with_url(url.to_string());
-        builder = builder.with_retry(config.retry.clone());
-
-        if let Some(runtime) = &config.runtime {
-            builder =
-                builder.with_http_connector(SpawnedReqwestConnector::new(runtime.get_handle()));
-        }
-
-        if let Some(ref cert_config) = config.certificate
-            && let Some(ref path) = cert_config.certificate_path
-        {
-            builder = builder.with_client_options(client_options_from_certificate(path)?);
-        }
-
-        let config = config::GcpConfigHelper::try_new(config.raw.as_gcp_options())?.build()?;
-
         let (_, path) =
             ObjectStoreScheme::parse(url).map_err(|e| DeltaTableError::GenericError {
                 source: Box::new(e),
             })?;
         let prefix = Path::parse(path)?;
 
-        for (key, value) in config.iter() {
-            builder = builder.with_config(*key, value.clone());
+        if let Some(key) = HmacKey::from_options(&config.raw)? {
+            let inner = hmac_builder(url, config, key)?.build()?;
+            let store = crate::storage::GcsStorageBackend::try_new(Arc::new(inner))?;
+            return Ok((Arc::new(store), prefix));
         }
 
-        let store = crate::storage::GcsStorageBackend::try_new(Arc::new(builder.build()?))?;
+        let access_boundary = lookup_option(&config.raw, GOOGLE_CREDENTIAL_ACCESS_BOUNDARY)
+            .map(|boundary| parse_access_boundary(&boundary))
+            .transpose()?;
+        let sts_endpoint = lookup_option(&config.raw, GOOGLE_STS_ENDPOINT)
+            .unwrap_or_else(|| DEFAULT_STS_ENDPOINT.to_string());
+
+        let builder = gcs_builder(url, config)?;
+        let inner = builder.clone().build()?;
+        let inner = match access_boundary {
+            Some(options) => {
+                let provider = DownscopedCredentialProvider::new(
+                    inner.credentials().clone(),
+                    options,
+                    sts_endpoint,
+                    http_client(config)?,
+                );
+                builder.with_credentials(Arc::new(provider)).build()?
+            }
+            None => inner,
+        };
+
+        let store = crate::storage::GcsStorageBackend::try_new(Arc::new(inner))?;
 
         Ok((Arc::new(store), prefix))
     }
+
+    /// Pre-signed URLs are always signed with the credentials of the configured service account
+    /// or HMAC key, since downscoped tokens cannot sign URLs.
+    fn signer(&self, url: &Url, config: &StorageConfig) -> DeltaResult<Option<Arc<dyn Signer>>> {
+        if let Some(key) = HmacKey::from_options(&config.raw)? {
+            return Ok(Some(Arc::new(hmac_builder(url, config, key)?.build()?)));
+        }
+        Ok(Some(Arc::new(gcs_builder(url, config)?.build()?)))
+    }
+}
+
+/// Configure a builder accessing the bucket through the S3 compatible XML API using an HMAC key.
+fn hmac_builder(url: &Url, config: &StorageConfig, key: HmacKey) -> DeltaResult<AmazonS3Builder> {
+    let bucket = url
+        .host_str()
+        .ok_or_else(|| DeltaTableError::InvalidTableLocation(url.to_string()))?;
+    let mut builder = AmazonS3Builder::new()
+        .with_endpoint(key.endpoint)
+        .with_bucket_name(bucket)
+        .with_region("auto")
+        .with_access_key_id(key.access_id)
+        .with_secret_access_key(key.secret)
+        // the XML API rejects copies onto existing objects with this precondition
+        .with_copy_if_not_exists(S3CopyIfNotExists::Header(
+            "x-goog-if-generation-match".to_string(),
+            "0".to_string(),
+        ))
+        .with_retry(config.retry.clone());
+
+    if let Some(runtime) = &config.runtime {
+        builder = builder.with_http_connector(SpawnedReqwestConnector::new(runtime.get_handle()));
+    }
+
+    if let Some(client_options) = config.client_options()? {
+        builder = builder.with_client_options(client_options);
+    }
+
+    Ok(builder)
+}
+
+/// HTTP client for requests sent outside of the object store, e.g. to the token service.
+fn http_client(config: &StorageConfig) -> DeltaResult<HttpClient> {
+    let client_options = config.client_options()?.unwrap_or_default();
+    let client = match &config.runtime {
+        Some(runtime) => {
+            SpawnedReqwestConnector::new(runtime.get_handle()).connect(&client_options)?
+        }
+        None => ReqwestConnector::default().connect(&client_options)?,
+    };
+    Ok(client)
 }
 
 impl LogStoreFactory for GcpFactory {
@@ -120,4 +205,27 @@ mod tests {
             .unwrap();
         assert_eq!(logstore.name(), "DefaultLogStore");
     }
+
+    #[test]
+    fn test_gcp_factory_hmac_key() {
+        let factory = GcpFactory {};
+        let location = Url::parse("gs://bucket/path/to/table").unwrap();
+        let config = StorageConfig::parse_options([
+            ("google_hmac_access_id", "GOOG1EXAMPLE"),
+            ("google_hmac_secret", "secret"),
+        ])
+        .unwrap();
+        let (store, prefix) = factory.parse_url_opts(&location, &config).unwrap();
+        assert_eq!(store.to_string(), "GcsStorageBackend");
+        assert_eq!(prefix, Path::from("path/to/table"));
+    }
+
+    #[test]
+    fn test_gcp_factory_invalid_access_boundary() {
+        let factory = GcpFactory {};
+        let location = Url::parse("gs://bucket/table").unwrap();
+        let config =
+            StorageConfig::parse_options([("google_credential_access_boundary", "{}")]).unwrap();
+        assert!(factory.parse_url_opts(&location, &config).is_err());
+    }
 }
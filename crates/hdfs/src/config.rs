@@ -0,0 +1,260 @@
+//! Resolution of Hadoop client configuration from storage options.
+//!
+//! Besides plain Hadoop properties (e.g. `dfs.ha.namenodes.<nameservice>`), the following
+//! delta-rs specific options are understood:
+//!
+//! - [`HADOOP_CONF_DIR`]: directory containing `core-site.xml` and `hdfs-site.xml`. Properties
+//!   defined there are loaded for this table only, explicitly passed options take precedence.
+//! - [`HDFS_KERBEROS_CCACHE`]: Kerberos credential cache holding the TGT of the client.
+//! - [`HDFS_KERBEROS_KEYTAB`]: client keytab used to acquire the TGT without running `kinit`.
+//!
+//! The Kerberos library loaded by the HDFS client only reads its credentials from the environment
+//! of the process (`KRB5CCNAME`, `KRB5_CLIENT_KTNAME`), so the Kerberos options are applied to
+//! the environment and must agree between all tables of a process.
+use std::collections::HashMap;
+use std::path::Path as StdPath;
+use std::sync::Mutex;
+
+use deltalake_core::{DeltaResult, DeltaTableError};
+use serde::Deserialize;
+use url::Url;
+
+/// Directory to load `core-site.xml` and `hdfs-site.xml` from.
+pub const HADOOP_CONF_DIR: &str = "HADOOP_CONF_DIR";
+
+/// Kerberos credential cache, e.g. `FILE:/tmp/krb5cc_1000`.
+pub const HDFS_KERBEROS_CCACHE: &str = "HDFS_KERBEROS_CCACHE";
+/// Kerberos client keytab used to obtain credentials.
+pub const HDFS_KERBEROS_KEYTAB: &str = "HDFS_KERBEROS_KEYTAB";
+
+/// Kerberos options mapped to the environment variable read by the Kerberos library.
+const KERBEROS_OPTIONS: [(&str, &str); 2] = [
+    (HDFS_KERBEROS_CCACHE, "KRB5CCNAME"),
+    (HDFS_KERBEROS_KEYTAB, "KRB5_CLIENT_KTNAME"),
+];
+
+/// Serializes checking and setting the Kerberos environment.
+static KERBEROS_ENV: Mutex<()> = Mutex::new(());
+
+const CONFIG_FILES: [&str; 2] = ["core-site.xml", "hdfs-site.xml"];
+
+#[derive(Deserialize)]
+struct HadoopConfiguration {
+    #[serde(rename = "property", default)]
+    properties: Vec<HadoopProperty>,
+}
+
+#[derive(Deserialize)]
+struct HadoopProperty {
+    name: String,
+    value: Option<String>,
+}
+
+fn option<'a>(options: &'a HashMap<String, String>, key: &str) -> Option<&'a String> {
+    options
+        .iter()
+        .find(|(k, _)| k.eq_ignore_ascii_case(key))
+        .map(|(_, v)| v)
+}
+
+fn is_delta_option(key: &str) -> bool {
+    [HADOOP_CONF_DIR, HDFS_KERBEROS_CCACHE, HDFS_KERBEROS_KEYTAB]
+        .iter()
+        .any(|k| k.eq_ignore_ascii_case(key))
+}
+
+/// Parse the properties of a Hadoop `*-site.xml` file.
+fn parse_site_xml(xml: &str) -> DeltaResult<HashMap<String, String>> {
+    let config: HadoopConfiguration =
+        quick_xml::de::from_str(xml).map_err(|e| DeltaTableError::GenericError {
+            source: Box::new(e),
+        })?;
+    Ok(config
+        .properties
+        .into_iter()
+        .filter_map(|p| Some((p.name.trim().to_string(), p.value?.trim().to_string())))
+        .collect())
+}
+
+fn load_conf_dir(dir: &StdPath) -> DeltaResult<HashMap<String, String>> {
+    let mut config = HashMap::new();
+    for file in CONFIG_FILES {
+        let path = dir.join(file);
+        if !path.is_file() {
+            continue;
+        }
+        let xml = std::fs::read_to_string(&path).map_err(|e| {
+            DeltaTableError::Generic(format!("failed to read {}: {e}", path.display()))
+        })?;
+        config.extend(parse_site_xml(&xml)?);
+    }
+    Ok(config)
+}
+
+/// Configure the Kerberos library via its environment.
+///
+/// The variables apply to the whole process, so an option conflicting with a variable which is
+/// already set, either by the user or for another table, is rejected instead of redirecting the
+/// credentials of the tables opened before.
+fn configure_kerberos(options: &HashMap<String, String>) -> DeltaResult<()> {
+    let _guard = KERBEROS_ENV.lock().unwrap_or_else(|err| err.into_inner());
+    for (key, variable) in KERBEROS_OPTIONS {
+        let Some(value) = option(options, key) else {
+            continue;
+        };
+        match std::env::var(variable) {
+            Ok(current) if &current == value => {}
+            Ok(current) => {
+                return Err(DeltaTableError::Generic(format!(
+                    "HDFS option '{key}={value}' conflicts with '{variable}={current}', Kerberos credentials are shared by all tables of the process"
+                )));
+            }
+            Err(_) => unsafe {
+                std::env::set_var(variable, value);
+            },
+        }
+    }
+    Ok(())
+}
+
+/// Ensure an HA nameservice referenced by `url` can be resolved with the given configuration.
+fn validate_nameservice(url: &Url, config: &HashMap<String, String>) -> DeltaResult<()> {
+    let Some(nameservice) = url.host_str().filter(|_| url.port().is_none()) else {
+        return Ok(());
+    };
+    let Some(namenodes) = config.get(&format!("dfs.ha.namenodes.{nameservice}")) else {
+        return Ok(());
+    };
+    for namenode in namenodes
+        .split(',')
+        .map(str::trim)
+        .filter(|n| !n.is_empty())
+    {
+        let key = format!("dfs.namenode.rpc-address.{nameservice}.{namenode}");
+        if !config.contains_key(&key) {
+            return Err(DeltaTableError::Generic(format!(
+                "HDFS nameservice '{nameservice}' lists namenode '{namenode}', but '{key}' is not configured"
+            )));
+        }
+    }
+    Ok(())
+}
+
+/// Resolve the Hadoop client configuration for the table at `url`.
+pub(crate) fn resolve_config(
+    url: &Url,
+    options: &HashMap<String, String>,
+) -> DeltaResult<HashMap<String, String>> {
+    configure_kerberos(options)?;
+    let mut config = match option(options, HADOOP_CONF_DIR) {
+        Some(dir) => load_conf_dir(StdPath::new(dir))?,
+        None => HashMap::new(),
+    };
+    config.extend(
+        options
+            .iter()
+            .filter(|(k, _)| !is_delta_option(k))
+            .map(|(k, v)| (k.clone(), v.clone())),
+    );
+    validate_nameservice(url, &config)?;
+    Ok(config)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serial_test::serial;
+
+    const HDFS_SITE: &str = r#"<?xml version="1.0"?>
+<configuration>
+  <property>
+    <name>dfs.ha.namenodes.ns</name>
+    <value>nn1,nn2</value>
+  </property>
+  <property>
+    <name>dfs.namenode.rpc-address.ns.nn1</name>
+    <value>nn1.example.com:8020</value>
+  </property>
+  <property>
+    <name>dfs.namenode.rpc-address.ns.nn2</name>
+    <value>nn2.example.com:8020</value>
+  </property>
+</configuration>"#;
+
+    #[test]
+    fn test_parse_site_xml() {
+        let config = parse_site_xml(HDFS_SITE).unwrap();
+        assert_eq!(config.len(), 3);
+        assert_eq!(config["dfs.ha.namenodes.ns"], "nn1,nn2");
+    }
+
+    #[test]
+    fn test_resolve_config_from_conf_dir() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join("hdfs-site.xml"), HDFS_SITE).unwrap();
+        let url = Url::parse("hdfs://ns/table").unwrap();
+        let options = HashMap::from([
+            (
+                "hadoop_conf_dir".to_string(),
+                dir.path().to_string_lossy().to_string(),
+            ),
+            (
+                "dfs.namenode.rpc-address.ns.nn2".to_string(),
+                "other.example.com:8020".to_string(),
+            ),
+        ]);
+        let config = resolve_config(&url, &options).unwrap();
+        assert_eq!(
+            config["dfs.namenode.rpc-address.ns.nn2"],
+            "other.example.com:8020"
+        );
+        assert!(!config.contains_key("hadoop_conf_dir"));
+    }
+
+    #[test]
+    #[serial]
+    fn test_kerberos_options() {
+        unsafe {
+            std::env::remove_var("KRB5_CLIENT_KTNAME");
+        }
+        let url = Url::parse("hdfs://ns/table").unwrap();
+        let options = HashMap::from([(
+            "hdfs_kerberos_keytab".to_string(),
+            "/etc/client.keytab".to_string(),
+        )]);
+        let config = resolve_config(&url, &options).unwrap();
+        assert!(!config.contains_key("hdfs_kerberos_keytab"));
+        assert_eq!(
+            std::env::var("KRB5_CLIENT_KTNAME").unwrap(),
+            "/etc/client.keytab"
+        );
+        // the same keytab can be used by other tables
+        assert!(resolve_config(&url, &options).is_ok());
+
+        let options = HashMap::from([(
+            HDFS_KERBEROS_KEYTAB.to_string(),
+            "/etc/other.keytab".to_string(),
+        )]);
+        assert!(resolve_config(&url, &options).is_err());
+        assert_eq!(
+            std::env::var("KRB5_CLIENT_KTNAME").unwrap(),
+            "/etc/client.keytab"
+        );
+        unsafe {
+            std::env::remove_var("KRB5_CLIENT_KTNAME");
+        }
+    }
+
+    #[test]
+    fn test_validate_nameservice() {
+        let url = Url::parse("hdfs://ns/table").unwrap();
+        let mut config = parse_site_xml(HDFS_SITE).unwrap();
+        assert!(validate_nameservice(&url, &config).is_ok());
+
+        config.remove("dfs.namenode.rpc-address.ns.nn2");
+        assert!(validate_nameservice(&url, &config).is_err());
+
+        let url = Url::parse("hdfs://ns:8020/table").unwrap();
+        assert!(validate_nameservice(&url, &config).is_ok());
+    }
+}
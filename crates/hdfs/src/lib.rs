@@ -8,6 +8,8 @@ use deltalake_core::{DeltaResult, Path};
 use hdfs_native_object_store::HdfsObjectStoreBuilder;
 use url::Url;
 
+pub mod config;
+
 #[derive(Clone, Default, Debug)]
 pub struct HdfsFactory {}
 
@@ -17,9 +19,10 @@ impl ObjectStoreFactory for HdfsFactory {
         url: &Url,
         config: &StorageConfig,
     ) -> DeltaResult<(ObjectStoreRef, Path)> {
+        let hadoop_config = config::resolve_config(url, &config.raw)?;
         let mut builder = HdfsObjectStoreBuilder::new()
             .with_url(url.as_str())
-            .with_config(&config.raw);
+            .with_config(&hadoop_config);
 
         if let Some(runtime) = &config.runtime {
             builder = builder.with_io_runtime(runtime.get_handle());
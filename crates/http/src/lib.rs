@@ -0,0 +1,94 @@
+//! Read-only access to Delta tables served over HTTP(S).
+//!
+//! Tables can be published on any static file server or CDN supporting range requests, without
+//! granting readers cloud credentials. Writes are rejected, and since static servers cannot list
+//! directories the transaction log is discovered by probing for commit files.
+use std::sync::Arc;
+
+use deltalake_core::logstore::object_store::ObjectStoreScheme;
+use deltalake_core::logstore::object_store::http::HttpBuilder;
+use deltalake_core::logstore::{
+    LogStore, LogStoreFactory, ObjectStoreFactory, ObjectStoreRef, StorageConfig, default_logstore,
+    logstore_factories, object_store_factories,
+};
+use deltalake_core::{DeltaResult, DeltaTableError, Path};
+use object_store::client::SpawnedReqwestConnector;
+use url::Url;
+
+mod storage;
+
+#[derive(Clone, Default, Debug)]
+pub struct HttpFactory {}
+
+impl ObjectStoreFactory for HttpFactory {
+    fn parse_url_opts(
+        &self,
+        url: &Url,
+        config: &StorageConfig,
+    ) -> DeltaResult<(ObjectStoreRef, Path)> {
+        let (_, path) =
+            ObjectStoreScheme::parse(url).map_err(|e| DeltaTableError::GenericError {
+                source: Box::new(e),
+            })?;
+        let prefix = Path::parse(path)?;
+
+        let mut builder = HttpBuilder::new()
+            .with_url(&url[..url::Position::BeforePath])
+            .with_retry(config.retry.clone());
+
+        if let Some(runtime) = &config.runtime {
+            builder =
+                builder.with_http_connector(SpawnedReqwestConnector::new(runtime.get_handle()));
+        }
+
+        if let Some(client_options) = config.client_options()? {
+            builder = builder.with_client_options(client_options);
+        }
+
+        let store = storage::HttpStorageBackend::new(Arc::new(builder.build()?));
+        Ok((Arc::new(store), prefix))
+    }
+}
+
+impl LogStoreFactory for HttpFactory {
+    fn with_options(
+        &self,
+        prefixed_store: ObjectStoreRef,
+        root_store: ObjectStoreRef,
+        location: &Url,
+        options: &StorageConfig,
+    ) -> DeltaResult<Arc<dyn LogStore>> {
+        Ok(default_logstore(
+            prefixed_store,
+            root_store,
+            location,
+            options,
+        ))
+    }
+}
+
+/// Register an [ObjectStoreFactory] for HTTP(S) [Url] schemes
+pub fn register_handlers(_additional_prefixes: Option<Url>) {
+    let factory = Arc::new(HttpFactory {});
+    for scheme in ["http", "https"].iter() {
+        let url = Url::parse(&format!("{scheme}://")).unwrap();
+        object_store_factories().insert(url.clone(), factory.clone());
+        logstore_factories().insert(url.clone(), factory.clone());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_http_factory() {
+        let factory = HttpFactory {};
+        let location = Url::parse("https://data.example.com/datasets/table").unwrap();
+        let (store, prefix) = factory
+            .parse_url_opts(&location, &StorageConfig::default())
+            .unwrap();
+        assert!(store.to_string().starts_with("HttpStorageBackend("));
+        assert_eq!(prefix, Path::from("datasets/table"));
+    }
+}
@@ -0,0 +1,383 @@
+//! Read-only storage backend for tables served over HTTP(S).
+//!
+//! Static file servers and CDNs serve objects and byte ranges, but generally cannot list
+//! directories. Listings of the `_delta_log` directory are therefore emulated: the last checkpoint
+//! is looked up in `_last_checkpoint` and commits are probed one version after the other until a
+//! commit is missing. Logs whose first commits were cleaned up without a `_last_checkpoint`, as
+//! well as all other listings, are forwarded to the server, which only succeeds for servers
+//! supporting WebDAV.
+use std::fmt;
+
+use deltalake_core::Path;
+use deltalake_core::logstore::ObjectStoreRef;
+use futures::stream::BoxStream;
+use futures::{StreamExt, TryStreamExt};
+use object_store::{
+    CopyOptions, Error as ObjectStoreError, GetOptions, GetResult, ListResult, MultipartUpload,
+    ObjectMeta, ObjectStore, ObjectStoreExt, PutMultipartOptions, PutOptions, PutPayload,
+    PutResult, RenameOptions, Result as ObjectStoreResult,
+};
+use serde::Deserialize;
+use tracing::debug;
+
+const STORE: &str = "HttpStorageBackend";
+const DELTA_LOG: &str = "_delta_log";
+const LAST_CHECKPOINT: &str = "_last_checkpoint";
+
+/// The fields of `_last_checkpoint` needed to locate the checkpoint files.
+#[derive(Debug, Deserialize)]
+struct LastCheckpoint {
+    version: u64,
+    parts: Option<u64>,
+}
+
+/// An [`ObjectStore`] serving the objects of a table over HTTP(S), rejecting all writes.
+pub(crate) struct HttpStorageBackend {
+    inner: ObjectStoreRef,
+}
+
+impl HttpStorageBackend {
+    pub(crate) fn new(inner: ObjectStoreRef) -> Self {
+        Self { inner }
+    }
+
+    fn read_only<T>(operation: &str) -> ObjectStoreResult<T> {
+        Err(ObjectStoreError::NotSupported {
+            source: format!("{operation} is not supported, tables served over HTTP are read-only")
+                .into(),
+        })
+    }
+
+    /// Metadata of `location`, or `None` if it does not exist.
+    async fn head_if_exists(&self, location: &Path) -> ObjectStoreResult<Option<ObjectMeta>> {
+        match self.inner.head(location).await {
+            Ok(meta) => Ok(Some(meta)),
+            Err(ObjectStoreError::NotFound { .. }) => Ok(None),
+            Err(err) => Err(err),
+        }
+    }
+
+    async fn last_checkpoint(&self, log_dir: &Path) -> ObjectStoreResult<Option<LastCheckpoint>> {
+        let location = log_dir.clone().join(LAST_CHECKPOINT);
+        let data = match self.inner.get(&location).await {
+            Ok(result) => result.bytes().await?,
+            Err(ObjectStoreError::NotFound { .. }) => return Ok(None),
+            Err(err) => return Err(err),
+        };
+        let checkpoint = serde_json::from_slice(&data).map_err(|e| ObjectStoreError::Generic {
+            store: STORE,
+            source: Box::new(e),
+        })?;
+        Ok(Some(checkpoint))
+    }
+
+    /// List the log files in `log_dir` sorting after `offset` by probing for them.
+    async fn probe_log(
+        &self,
+        log_dir: Path,
+        offset: Option<Path>,
+    ) -> ObjectStoreResult<Vec<ObjectMeta>> {
+        let from_version = offset
+            .as_ref()
+            .and_then(|offset| offset.filename())
+            .and_then(|name| name.get(..20))
+            .and_then(|version| version.parse::<u64>().ok())
+            .unwrap_or_default();
+        let mut files = Vec::new();
+
+        let last_checkpoint = self.last_checkpoint(&log_dir).await?;
+        let mut version = from_version;
+        if let Some(checkpoint) = &last_checkpoint
+            && checkpoint.version >= from_version
+        {
+            let names = match checkpoint.parts {
+                Some(parts) if parts > 1 => (1..=parts)
+                    .map(|part| {
+                        format!(
+                            "{:020}.checkpoint.{part:010}.{parts:010}.parquet",
+                            checkpoint.version
+                        )
+                    })
+                    .collect(),
+                _ => vec![format!("{:020}.checkpoint.parquet", checkpoint.version)],
+            };
+            for name in names {
+                if let Some(meta) = self.head_if_exists(&log_dir.clone().join(name)).await? {
+                    files.push(meta);
+                }
+            }
+            // Commits up to the checkpoint may have been cleaned up, the checkpoint holds
+            // the state of the table at its version.
+            version = checkpoint.version;
+        }
+
+        let mut commits = 0;
+        loop {
+            let location = log_dir.clone().join(format!("{version:020}.json"));
+            let Some(meta) = self.head_if_exists(&location).await? else {
+                break;
+            };
+            files.push(meta);
+            commits += 1;
+            version += 1;
+        }
+        debug!("probed {commits} commits in {log_dir} starting at version {from_version}");
+
+        if commits == 0 && last_checkpoint.is_none() && offset.is_none() {
+            // Without `_last_checkpoint` probing starts at version 0, which no longer exists once
+            // the log was cleaned up. Only servers supporting listings can locate the log then.
+            return self
+                .inner
+                .list(Some(&log_dir))
+                .try_collect()
+                .await
+                .map_err(|err| ObjectStoreError::Generic {
+                    store: STORE,
+                    source: format!(
+                        "{log_dir} has no commit at version 0 nor a {LAST_CHECKPOINT} file, and \
+                         could not be listed: {err}"
+                    )
+                    .into(),
+                });
+        }
+
+        if last_checkpoint.is_some()
+            && let Some(meta) = self
+                .head_if_exists(&log_dir.clone().join(LAST_CHECKPOINT))
+                .await?
+        {
+            files.push(meta);
+        }
+
+        files.sort_by(|a, b| a.location.cmp(&b.location));
+        if let Some(offset) = offset {
+            files.retain(|meta| meta.location > offset);
+        }
+        Ok(files)
+    }
+
+    fn list_log(
+        &self,
+        log_dir: Path,
+        offset: Option<Path>,
+    ) -> BoxStream<'static, ObjectStoreResult<ObjectMeta>> {
+        let store = Self::new(self.inner.clone());
+        futures::stream::once(async move { store.probe_log(log_dir, offset).await })
+            .map_ok(|files| futures::stream::iter(files.into_iter().map(Ok)))
+            .try_flatten()
+            .boxed()
+    }
+}
+
+/// Whether `prefix` is the `_delta_log` directory of a table.
+fn is_log_dir(prefix: Option<&Path>) -> bool {
+    prefix
+        .and_then(|prefix| prefix.parts().last())
+        .is_some_and(|part| part.as_ref() == DELTA_LOG)
+}
+
+impl fmt::Debug for HttpStorageBackend {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("HttpStorageBackend")
+            .field("inner", &self.inner)
+            .finish()
+    }
+}
+
+impl fmt::Display for HttpStorageBackend {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "HttpStorageBackend({})", self.inner)
+    }
+}
+
+#[async_trait::async_trait]
+impl ObjectStore for HttpStorageBackend {
+    async fn put_opts(
+        &self,
+        _location: &Path,
+        _payload: PutPayload,
+        _options: PutOptions,
+    ) -> ObjectStoreResult<PutResult> {
+        Self::read_only("put")
+    }
+
+    async fn put_multipart_opts(
+        &self,
+        _location: &Path,
+        _options: PutMultipartOptions,
+    ) -> ObjectStoreResult<Box<dyn MultipartUpload>> {
+        Self::read_only("put_multipart")
+    }
+
+    async fn get_opts(&self, location: &Path, options: GetOptions) -> ObjectStoreResult<GetResult> {
+        self.inner.get_opts(location, options).await
+    }
+
+    fn delete_stream(
+        &self,
+        locations: BoxStream<'static, ObjectStoreResult<Path>>,
+    ) -> BoxStream<'static, ObjectStoreResult<Path>> {
+        locations
+            .map(|location| location.and_then(|_| Self::read_only("delete")))
+            .boxed()
+    }
+
+    fn list(&self, prefix: Option<&Path>) -> BoxStream<'static, ObjectStoreResult<ObjectMeta>> {
+        match prefix {
+            Some(log_dir) if is_log_dir(prefix) => self.list_log(log_dir.clone(), None),
+            _ => self.inner.list(prefix),
+        }
+    }
+
+    fn list_with_offset(
+        &self,
+        prefix: Option<&Path>,
+        offset: &Path,
+    ) -> BoxStream<'static, ObjectStoreResult<ObjectMeta>> {
+        match prefix {
+            Some(log_dir) if is_log_dir(prefix) => {
+                self.list_log(log_dir.clone(), Some(offset.clone()))
+            }
+            _ => self.inner.list_with_offset(prefix, offset),
+        }
+    }
+
+    async fn list_with_delimiter(&self, prefix: Option<&Path>) -> ObjectStoreResult<ListResult> {
+        match prefix {
+            Some(log_dir) if is_log_dir(prefix) => Ok(ListResult {
+                common_prefixes: vec![],
+                objects: self.probe_log(log_dir.clone(), None).await?,
+            }),
+            _ => self.inner.list_with_delimiter(prefix).await,
+        }
+    }
+
+    async fn copy_opts(
+        &self,
+        _from: &Path,
+        _to: &Path,
+        _options: CopyOptions,
+    ) -> ObjectStoreResult<()> {
+        Self::read_only("copy")
+    }
+
+    async fn rename_opts(
+        &self,
+        _from: &Path,
+        _to: &Path,
+        _options: RenameOptions,
+    ) -> ObjectStoreResult<()> {
+        Self::read_only("rename")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use object_store::memory::InMemory;
+
+    use super::*;
+
+    async fn store_with(files: &[&str]) -> HttpStorageBackend {
+        let inner = Arc::new(InMemory::new());
+        for file in files {
+            inner.put(&Path::from(*file), "{}".into()).await.unwrap();
+        }
+        HttpStorageBackend::new(inner)
+    }
+
+    async fn listed(store: &HttpStorageBackend, offset: Option<&str>) -> Vec<String> {
+        let log_dir = Path::from("table/_delta_log");
+        let files = match offset {
+            Some(offset) => store.list_with_offset(Some(&log_dir), &Path::from(offset)),
+            None => store.list(Some(&log_dir)),
+        };
+        files
+            .map_ok(|meta| meta.location.filename().unwrap().to_string())
+            .try_collect()
+            .await
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_log_listing_probes_commits() {
+        let store = store_with(&[
+            "table/_delta_log/00000000000000000000.json",
+            "table/_delta_log/00000000000000000001.json",
+            "table/_delta_log/00000000000000000003.json",
+        ])
+        .await;
+        assert_eq!(
+            listed(&store, None).await,
+            vec![
+                "00000000000000000000.json".to_string(),
+                "00000000000000000001.json".to_string()
+            ]
+        );
+        assert_eq!(
+            listed(&store, Some("table/_delta_log/00000000000000000000.json")).await,
+            vec!["00000000000000000001.json".to_string()]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_log_listing_starts_at_last_checkpoint() {
+        let store = store_with(&[
+            "table/_delta_log/00000000000000000002.checkpoint.parquet",
+            "table/_delta_log/00000000000000000002.json",
+            "table/_delta_log/00000000000000000003.json",
+        ])
+        .await;
+        store
+            .inner
+            .put(
+                &Path::from("table/_delta_log/_last_checkpoint"),
+                r#"{"version":2,"size":4}"#.into(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(
+            listed(&store, None).await,
+            vec![
+                "00000000000000000002.checkpoint.parquet".to_string(),
+                "00000000000000000002.json".to_string(),
+                "00000000000000000003.json".to_string(),
+                "_last_checkpoint".to_string(),
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_log_listing_without_last_checkpoint_lists_cleaned_up_log() {
+        let store = store_with(&[
+            "table/_delta_log/00000000000000000002.checkpoint.parquet",
+            "table/_delta_log/00000000000000000002.json",
+            "table/_delta_log/00000000000000000003.json",
+        ])
+        .await;
+        assert_eq!(
+            listed(&store, None).await,
+            vec![
+                "00000000000000000002.checkpoint.parquet".to_string(),
+                "00000000000000000002.json".to_string(),
+                "00000000000000000003.json".to_string(),
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_writes_are_rejected() {
+        let store = store_with(&[]).await;
+        let err = store
+            .put(&Path::from("table/part-0.parquet"), "data".into())
+            .await
+            .unwrap_err();
+        assert!(matches!(err, ObjectStoreError::NotSupported { .. }));
+        let err = store
+            .copy(&Path::from("a"), &Path::from("b"))
+            .await
+            .unwrap_err();
+        assert!(matches!(err, ObjectStoreError::NotSupported { .. }));
+    }
+}
@@ -1,3 +1,5 @@
+use std::collections::HashMap;
+
 use dashmap::DashMap;
 use deltalake_core::DeltaResult;
 use deltalake_core::kernel::{Version, transaction::TransactionError};
@@ -36,6 +38,8 @@ pub struct LakeFSClient {
     http_client: Client,
     /// Holds the running delta lake operations, each operation propagates the operation ID into execution handler.
     transactions: DashMap<Uuid, String>,
+    /// Commit IDs the transaction branches have been created from.
+    base_commits: DashMap<Uuid, String>,
 }
 
 impl LakeFSClient {
@@ -45,6 +49,7 @@ impl LakeFSClient {
             config,
             http_client,
             transactions: DashMap::new(),
+            base_commits: DashMap::new(),
         }
     }
 
@@ -77,7 +82,13 @@ impl LakeFSClient {
         // Handle the response
         match response.status() {
             StatusCode::CREATED => {
-                // Branch created successfully
+                // Branch created successfully, the response contains the commit it points at
+                let base_commit = response.text().await.unwrap_or_default();
+                let base_commit = base_commit.trim().trim_matches('"');
+                if !base_commit.is_empty() {
+                    self.base_commits
+                        .insert(operation_id, base_commit.to_string());
+                }
                 let new_url =
                     Url::parse(&format!("lakefs://{repo}/{transaction_branch}/{table}")).unwrap();
                 Ok((new_url, transaction_branch))
@@ -141,6 +152,19 @@ impl LakeFSClient {
         branch: String,
         commit_message: String,
         allow_empty: bool,
+    ) -> DeltaResult<()> {
+        self.commit_with_metadata(repo, branch, commit_message, allow_empty, HashMap::new())
+            .await
+    }
+
+    /// Commit the staged changes of `branch`, attaching `metadata` to the LakeFS commit.
+    pub async fn commit_with_metadata(
+        &self,
+        repo: String,
+        branch: String,
+        commit_message: String,
+        allow_empty: bool,
+        metadata: HashMap<String, String>,
     ) -> DeltaResult<()> {
         let request_url = format!(
             "{}/api/v1/repositories/{repo}/branches/{branch}/commits",
@@ -150,6 +174,7 @@ impl LakeFSClient {
         let body = json!({
             "message": commit_message,
             "allow_empty": allow_empty,
+            "metadata": metadata,
         });
 
         debug!("Committing to LakeFS Branch: '{branch}' in repo: '{repo}'");
@@ -300,8 +325,14 @@ impl LakeFSClient {
         Ok(transaction_branch)
     }
 
+    /// Commit ID the transaction branch of `id` has been created from, if known.
+    pub fn get_transaction_base(&self, id: Uuid) -> Option<String> {
+        self.base_commits.get(&id).map(|v| v.to_string())
+    }
+
     pub fn clear_transaction(&self, id: Uuid) {
         self.transactions.remove(&id);
+        self.base_commits.remove(&id);
         debug!("{}", format!("LakeFS Transaction `{id}` has been removed."));
     }
 
@@ -363,9 +394,39 @@ mod tests {
         let (new_url, branch_name) = result.unwrap();
         assert_eq!(branch_name, format!("delta-tx-{operation_id}"));
         assert!(new_url.as_str().contains("lakefs://test_repo"));
+        assert!(client.get_transaction_base(operation_id).is_none());
         mock.assert();
     }
 
+    #[test]
+    fn test_create_branch_records_base_commit() {
+        let mut server = mockito::Server::new();
+        let mock = server
+            .mock("POST", "/api/v1/repositories/test_repo/branches")
+            .with_status(StatusCode::CREATED.as_u16().into())
+            .with_body("a1b2c3d4")
+            .create();
+
+        let config = LakeFSConfig::new(
+            server.url(),
+            "test_user".to_string(),
+            "test_pass".to_string(),
+        );
+        let client = LakeFSClient::with_config(config);
+        let operation_id = Uuid::new_v4();
+        let source_url = Url::parse("lakefs://test_repo/main/table").unwrap();
+
+        rt().block_on(async { client.create_branch(&source_url, operation_id).await })
+            .unwrap();
+        mock.assert();
+        assert_eq!(
+            client.get_transaction_base(operation_id).as_deref(),
+            Some("a1b2c3d4")
+        );
+        client.clear_transaction(operation_id);
+        assert!(client.get_transaction_base(operation_id).is_none());
+    }
+
     #[test]
     fn test_delete_branch() {
         let mut server = mockito::Server::new();
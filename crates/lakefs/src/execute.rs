@@ -1,8 +1,6 @@
 use async_trait::async_trait;
 use deltalake_core::{
-    DeltaResult, DeltaTableError,
-    logstore::{LogStore as _, LogStoreRef},
-    operations::CustomExecuteHandler,
+    DeltaResult, DeltaTableError, logstore::LogStoreRef, operations::CustomExecuteHandler,
 };
 use tracing::debug;
 use uuid::Uuid;
@@ -24,20 +22,13 @@ impl CustomExecuteHandler for LakeFSCustomExecuteHandler {
             ))
         }
     }
-    // Not required for LakeFS
+    // LakeFS Log store post execution of delta operation (delete the transaction branch)
     async fn post_execute(&self, log_store: &LogStoreRef, operation_id: Uuid) -> DeltaResult<()> {
         debug!("Running LakeFS post execution inside delta operation");
         if let Some(lakefs_store) = log_store.clone().as_any().downcast_ref::<LakeFSLogStore>() {
-            let (repo, _, _) = lakefs_store
-                .client
-                .decompose_url(lakefs_store.config().location().to_string());
-            let result = lakefs_store
-                .client
-                .delete_branch(repo, lakefs_store.client.get_transaction(operation_id)?)
-                .await
-                .map_err(|e| DeltaTableError::Transaction { source: e });
-            lakefs_store.client.clear_transaction(operation_id);
-            result
+            // The transaction branch has either been merged or is discarded, a failed commit may
+            // already have rolled it back.
+            lakefs_store.rollback(operation_id).await
         } else {
             Err(DeltaTableError::generic(
                 "LakeFSPreEcuteHandler is used, but no LakeFSLogStore has been found",
@@ -1,7 +1,8 @@
 //! LakeFS and similar tooling for delta-rs
 //!
 //! This module also contains the [LakeFSLogStore] implementation for delta operations executed in transaction branches
-//! where deltalake commits only happen when the branch can be safely merged.
+//! where deltalake commits only happen when the branch can be safely merged. Operations executed
+//! without the [LakeFSCustomExecuteHandler] commit directly on the configured branch.
 
 pub mod client;
 pub mod errors;
@@ -1,4 +1,5 @@
 //! Default implementation of [`LakeFSLogStore`] for LakeFS
+use std::collections::HashMap;
 use std::sync::{Arc, OnceLock};
 
 use bytes::Bytes;
@@ -11,7 +12,8 @@ use deltalake_core::{
 };
 use deltalake_core::{DeltaTableError, kernel::Version, logstore::*};
 use object_store::{Error as ObjectStoreError, ObjectStore, ObjectStoreExt as _, PutOptions};
-use tracing::debug;
+use serde_json::{Value, json};
+use tracing::{debug, warn};
 use url::Url;
 use uuid::Uuid;
 
@@ -126,10 +128,23 @@ impl LakeFSLogStore {
         )?))
     }
 
+    /// The transaction branch of `operation_id`, `None` if the operation runs without one.
+    fn transaction_branch(&self, operation_id: Uuid) -> Option<String> {
+        self.client.get_transaction(operation_id).ok()
+    }
+
     fn get_transaction_objectstore(
         &self,
         operation_id: Uuid,
     ) -> DeltaResult<(String, ObjectStoreRef, ObjectStoreRef)> {
+        // Operations never write to the target branch directly, its staging area is shared with
+        // every other writer of the branch.
+        if self.transaction_branch(operation_id).is_none() {
+            return Err(DeltaTableError::generic(format!(
+                "LakeFS operation {operation_id} has no transaction branch, operations must be \
+                 executed with the LakeFSCustomExecuteHandler"
+            )));
+        }
         let transaction_url =
             self.get_transaction_url(operation_id, self.config.location().to_string())?;
         Ok((
@@ -162,6 +177,35 @@ impl LakeFSLogStore {
         Ok(())
     }
 
+    /// Discard the transaction branch of `operation_id` and everything written to it.
+    pub async fn rollback(&self, operation_id: Uuid) -> DeltaResult<()> {
+        let Some(transaction_branch) = self.transaction_branch(operation_id) else {
+            return Ok(());
+        };
+        let (repo, _, _) = self
+            .client
+            .decompose_url(self.config.location().to_string());
+        let result = self.client.delete_branch(repo, transaction_branch).await;
+        self.client.clear_transaction(operation_id);
+        result.map_err(|source| DeltaTableError::Transaction { source })
+    }
+
+    /// LakeFS references of a commit, recorded under `lakeFS` in the `commitInfo` action.
+    fn lakefs_commit_info(&self, operation_id: Uuid) -> Value {
+        let (repo, target_branch, _) = self
+            .client
+            .decompose_url(self.config.location().to_string());
+        let branch = self
+            .transaction_branch(operation_id)
+            .unwrap_or_else(|| target_branch.clone());
+        json!({
+            "repository": repo,
+            "branch": branch,
+            "targetBranch": target_branch,
+            "baseCommitId": self.client.get_transaction_base(operation_id),
+        })
+    }
+
     pub async fn commit_merge(&self, operation_id: Uuid) -> DeltaResult<()> {
         let (transaction_url, _, _) = self.get_transaction_objectstore(operation_id)?;
 
@@ -259,77 +303,89 @@ impl LogStore for LakeFSLogStore {
         commit_or_bytes: CommitOrBytes,
         operation_id: Uuid,
     ) -> Result<(), TransactionError> {
-        let (transaction_url, store, _root_store) = self
-            .get_transaction_objectstore(operation_id)
-            .map_err(|e| TransactionError::LogStoreError {
+        let CommitOrBytes::LogBytes(log_bytes) = commit_or_bytes else {
+            unreachable!() // Default log store should never get a tmp_commit, since this is for conditional put stores
+        };
+        let (_, store, _root_store) =
+            self.get_transaction_objectstore(operation_id)
+                .map_err(|e| TransactionError::LogStoreError {
+                    msg: e.to_string(),
+                    source: Box::new(e),
+                })?;
+        let log_bytes = annotate_commit_info(&log_bytes, self.lakefs_commit_info(operation_id));
+
+        // Put commit
+        store
+            .put_opts(
+                &commit_uri_from_version(Some(version)),
+                log_bytes.into(),
+                put_options().clone(),
+            )
+            .await
+            .map_err(|err| -> TransactionError {
+                match err {
+                    ObjectStoreError::AlreadyExists { .. } => {
+                        TransactionError::VersionAlreadyExists(version)
+                    }
+                    _ => TransactionError::from(err),
+                }
+            })?;
+
+        let (repo, target_branch, table) = self
+            .client
+            .decompose_url(self.config.location().to_string());
+        let metadata = HashMap::from([
+            ("delta.version".to_string(), version.to_string()),
+            ("delta.operationId".to_string(), operation_id.to_string()),
+        ]);
+
+        let transaction_branch = self.client.get_transaction(operation_id)?;
+
+        // Do LakeFS Commit
+        if let Err(e) = self
+            .client
+            .commit_with_metadata(
+                repo.clone(),
+                transaction_branch.clone(),
+                format!("Delta commit {{ table: {table}, version: {version}}}"),
+                false,
+                metadata,
+            )
+            .await
+        {
+            self.rollback_after_failure(operation_id).await;
+            return Err(TransactionError::LogStoreError {
                 msg: e.to_string(),
                 source: Box::new(e),
-            })?;
+            });
+        }
 
-        match commit_or_bytes {
-            CommitOrBytes::LogBytes(log_bytes) => {
-                // Put commit
+        // Try LakeFS Branch merge of transaction branch in source branch
+        match self
+            .client
+            .merge(
+                repo,
+                target_branch,
+                transaction_branch,
+                version,
+                format!("Finished deltalake transaction {{ table: {table}, version: {version} }}"),
+                false,
+            )
+            .await
+        {
+            Ok(_) => Ok(()),
+            Err(TransactionError::VersionAlreadyExists(version)) => {
                 store
-                    .put_opts(
-                        &commit_uri_from_version(Some(version)),
-                        log_bytes.into(),
-                        put_options().clone(),
-                    )
-                    .await
-                    .map_err(|err| -> TransactionError {
-                        match err {
-                            ObjectStoreError::AlreadyExists { .. } => {
-                                TransactionError::VersionAlreadyExists(version)
-                            }
-                            _ => TransactionError::from(err),
-                        }
-                    })?;
-
-                // Do LakeFS Commit
-                let (repo, transaction_branch, table) = self.client.decompose_url(transaction_url);
-                self.client
-                    .commit(
-                        repo,
-                        transaction_branch,
-                        format!("Delta commit {{ table: {table}, version: {version}}}"),
-                        false,
-                    )
-                    .await
-                    .map_err(|e| TransactionError::LogStoreError {
-                        msg: e.to_string(),
-                        source: Box::new(e),
-                    })?;
-
-                // Try LakeFS Branch merge of transaction branch in source branch
-                let (repo, target_branch, table) = self
-                    .client
-                    .decompose_url(self.config.location().to_string());
-                match self
-                    .client
-                    .merge(
-                        repo,
-                        target_branch,
-                        self.client.get_transaction(operation_id)?,
-                        version,
-                        format!("Finished deltalake transaction {{ table: {table}, version: {version} }}"),
-                        false,
-                    )
+                    .delete(&commit_uri_from_version(Some(version)))
                     .await
-                {
-                    Ok(_) => Ok(()),
-                    Err(TransactionError::VersionAlreadyExists(version)) => {
-                        store
-                            .delete(&commit_uri_from_version(Some(version)))
-                            .await
-                            .map_err(TransactionError::from)?;
-                        return Err(TransactionError::VersionAlreadyExists(version));
-                    }
-                    Err(err) => Err(err),
-                }?;
+                    .map_err(TransactionError::from)?;
+                Err(TransactionError::VersionAlreadyExists(version))
             }
-            _ => unreachable!(), // Default log store should never get a tmp_commit, since this is for conditional put stores
-        };
-        Ok(())
+            Err(err) => {
+                self.rollback_after_failure(operation_id).await;
+                Err(err)
+            }
+        }
     }
 
     async fn abort_commit_entry(
@@ -340,14 +396,12 @@ impl LogStore for LakeFSLogStore {
     ) -> Result<(), TransactionError> {
         match &commit_or_bytes {
             CommitOrBytes::LogBytes(_) => {
-                let (repo, _, _) = self
-                    .client
-                    .decompose_url(self.config.location().to_string());
-                self.client
-                    .delete_branch(repo, self.client.get_transaction(operation_id)?)
-                    .await?;
-                self.client.clear_transaction(operation_id);
-                Ok(())
+                self.rollback(operation_id)
+                    .await
+                    .map_err(|e| TransactionError::LogStoreError {
+                        msg: e.to_string(),
+                        source: Box::new(e),
+                    })
             }
             _ => unreachable!(), // Default log store should never get a tmp_commit, since this is for conditional put stores
         }
@@ -397,6 +451,37 @@ impl LogStore for LakeFSLogStore {
     }
 }
 
+impl LakeFSLogStore {
+    /// Best effort rollback, the original error is more relevant to the caller.
+    async fn rollback_after_failure(&self, operation_id: Uuid) {
+        if let Err(err) = self.rollback(operation_id).await {
+            warn!("Failed to roll back LakeFS transaction {operation_id}: {err}");
+        }
+    }
+}
+
+/// Record `info` under `lakeFS` in the `commitInfo` action of a serialized commit.
+fn annotate_commit_info(log_bytes: &Bytes, info: Value) -> Bytes {
+    let Ok(text) = std::str::from_utf8(log_bytes) else {
+        return log_bytes.clone();
+    };
+    let mut annotated = text
+        .lines()
+        .map(|line| match serde_json::from_str::<Value>(line) {
+            Ok(mut action) if action.get("commitInfo").is_some_and(Value::is_object) => {
+                action["commitInfo"]["lakeFS"] = info.clone();
+                action.to_string()
+            }
+            _ => line.to_string(),
+        })
+        .collect::<Vec<_>>()
+        .join("\n");
+    if text.ends_with('\n') {
+        annotated.push('\n');
+    }
+    Bytes::from(annotated)
+}
+
 fn put_options() -> &'static PutOptions {
     static PUT_OPTS: OnceLock<PutOptions> = OnceLock::new();
     PUT_OPTS.get_or_init(|| PutOptions {
@@ -408,4 +493,25 @@ fn put_options() -> &'static PutOptions {
 }
 
 #[cfg(test)]
-mod tests {}
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_annotate_commit_info() {
+        let log = Bytes::from(
+            "{\"commitInfo\":{\"operation\":\"WRITE\"}}\n{\"add\":{\"path\":\"a.parquet\"}}\n",
+        );
+        let annotated = annotate_commit_info(&log, json!({"branch": "delta-tx-1"}));
+        let text = std::str::from_utf8(&annotated).unwrap();
+        assert!(text.ends_with('\n'));
+
+        let lines: Vec<Value> = text
+            .lines()
+            .map(|l| serde_json::from_str(l).unwrap())
+            .collect();
+        assert_eq!(lines.len(), 2);
+        assert_eq!(lines[0]["commitInfo"]["operation"], "WRITE");
+        assert_eq!(lines[0]["commitInfo"]["lakeFS"]["branch"], "delta-tx-1");
+        assert_eq!(lines[1]["add"]["path"], "a.parquet");
+    }
+}
@@ -75,6 +75,9 @@ impl ObjectStoreFactory for LakeFSObjectStoreFactory {
             builder =
                 builder.with_http_connector(SpawnedReqwestConnector::new(runtime.get_handle()));
         }
+        if let Some(client_options) = config.client_options()? {
+            builder = builder.with_client_options(client_options);
+        }
 
         let config = options
             .clone()
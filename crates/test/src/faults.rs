@@ -0,0 +1,378 @@
+//! An [`ObjectStore`] wrapper injecting storage failures.
+//!
+//! [`FaultInjectingStore`] wraps any store (usually an [`InMemory`](object_store::memory::InMemory)
+//! store) and can be configured to add latency, fail requests with server errors, lose races on
+//! conditional writes to a concurrent writer and persist only part of an object. Faults can be
+//! reconfigured at any time, clones of the store share the same configuration.
+use std::collections::VecDeque;
+use std::fmt;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use bytes::Bytes;
+use deltalake_core::logstore::object_store::path::Path;
+use deltalake_core::logstore::object_store::{
+    CopyMode, CopyOptions, Error as ObjectStoreError, GetOptions, GetResult, ListResult,
+    MultipartUpload, ObjectMeta, ObjectStore, ObjectStoreExt as _, PutMode, PutMultipartOptions,
+    PutOptions, PutPayload, PutResult, RenameOptions, RenameTargetMode,
+    Result as ObjectStoreResult,
+};
+use deltalake_core::logstore::ObjectStoreRef;
+use futures::stream::BoxStream;
+use futures::StreamExt;
+
+const STORE: &str = "FaultInjectingStore";
+
+type PathFilter = Arc<dyn Fn(&Path) -> bool + Send + Sync>;
+
+#[derive(Default)]
+struct Faults {
+    latency: Option<Duration>,
+    fail_every: Option<usize>,
+    fail_next: usize,
+    races: VecDeque<Bytes>,
+    truncate_next: Option<usize>,
+    filter: Option<PathFilter>,
+    requests: usize,
+    injected: usize,
+}
+
+impl Faults {
+    fn applies_to(&self, location: &Path) -> bool {
+        self.filter.as_ref().is_none_or(|filter| filter(location))
+    }
+}
+
+/// An [`ObjectStore`] injecting configurable faults into the requests sent to an inner store.
+#[derive(Clone)]
+pub struct FaultInjectingStore {
+    inner: ObjectStoreRef,
+    faults: Arc<Mutex<Faults>>,
+}
+
+impl FaultInjectingStore {
+    /// Wrap `inner`, initially without injecting any faults.
+    pub fn new(inner: ObjectStoreRef) -> Self {
+        Self {
+            inner,
+            faults: Default::default(),
+        }
+    }
+
+    /// Delay every request by `latency`.
+    pub fn set_latency(&self, latency: Option<Duration>) -> &Self {
+        self.faults.lock().unwrap().latency = latency;
+        self
+    }
+
+    /// Fail every `n`-th request with an internal server error.
+    pub fn fail_every(&self, n: Option<usize>) -> &Self {
+        self.faults.lock().unwrap().fail_every = n.filter(|n| *n > 0);
+        self
+    }
+
+    /// Fail the next `n` requests with an internal server error.
+    pub fn fail_next(&self, n: usize) -> &Self {
+        self.faults.lock().unwrap().fail_next = n;
+        self
+    }
+
+    /// Let the next conditional write (put, copy or rename if not exists) lose a race.
+    ///
+    /// Right before the write is forwarded, a concurrent writer creates the target with `data`,
+    /// so the write fails with [`AlreadyExists`](ObjectStoreError::AlreadyExists). Calling this
+    /// several times queues up several lost races.
+    pub fn race_next_create(&self, data: impl Into<Bytes>) -> &Self {
+        self.faults.lock().unwrap().races.push_back(data.into());
+        self
+    }
+
+    /// Persist only the first `len` bytes of the next put, which then fails.
+    ///
+    /// This mimics a connection dropped during an upload to a store without atomic writes.
+    pub fn truncate_next_put(&self, len: usize) -> &Self {
+        self.faults.lock().unwrap().truncate_next = Some(len);
+        self
+    }
+
+    /// Only inject faults into requests for locations matching `filter`.
+    pub fn set_filter(&self, filter: impl Fn(&Path) -> bool + Send + Sync + 'static) -> &Self {
+        self.faults.lock().unwrap().filter = Some(Arc::new(filter));
+        self
+    }
+
+    /// Stop injecting any faults.
+    pub fn clear(&self) -> &Self {
+        let mut faults = self.faults.lock().unwrap();
+        *faults = Faults {
+            requests: faults.requests,
+            injected: faults.injected,
+            ..Default::default()
+        };
+        self
+    }
+
+    /// Number of requests received by this store.
+    pub fn request_count(&self) -> usize {
+        self.faults.lock().unwrap().requests
+    }
+
+    /// Number of requests failed by injected errors.
+    pub fn injected_failure_count(&self) -> usize {
+        self.faults.lock().unwrap().injected
+    }
+
+    /// Account for a request, sleeping for the configured latency and failing it if requested.
+    async fn before_request(&self, location: Option<&Path>) -> ObjectStoreResult<()> {
+        let (latency, fail) = {
+            let mut faults = self.faults.lock().unwrap();
+            faults.requests += 1;
+            if !location.is_none_or(|location| faults.applies_to(location)) {
+                return Ok(());
+            }
+            let fail = if faults.fail_next > 0 {
+                faults.fail_next -= 1;
+                true
+            } else {
+                faults
+                    .fail_every
+                    .is_some_and(|n| faults.requests.is_multiple_of(n))
+            };
+            if fail {
+                faults.injected += 1;
+            }
+            (faults.latency, fail)
+        };
+        if let Some(latency) = latency {
+            tokio::time::sleep(latency).await;
+        }
+        if fail {
+            return Err(ObjectStoreError::Generic {
+                store: STORE,
+                source: "injected HTTP status server error (500 Internal Server Error)".into(),
+            });
+        }
+        Ok(())
+    }
+
+    /// Create `location` on behalf of a concurrent writer if a lost race is queued.
+    async fn maybe_race(&self, location: &Path) -> ObjectStoreResult<()> {
+        let data = {
+            let mut faults = self.faults.lock().unwrap();
+            if !faults.applies_to(location) {
+                return Ok(());
+            }
+            faults.races.pop_front()
+        };
+        if let Some(data) = data {
+            self.inner.put(location, data.into()).await?;
+        }
+        Ok(())
+    }
+
+    fn take_truncation(&self, location: &Path) -> Option<usize> {
+        let mut faults = self.faults.lock().unwrap();
+        if faults.applies_to(location) {
+            faults.truncate_next.take()
+        } else {
+            None
+        }
+    }
+
+    /// Apply latency and errors to the start of a listing.
+    fn faulty_list(
+        &self,
+        prefix: Option<Path>,
+        stream: BoxStream<'static, ObjectStoreResult<ObjectMeta>>,
+    ) -> BoxStream<'static, ObjectStoreResult<ObjectMeta>> {
+        let store = self.clone();
+        futures::stream::once(async move {
+            match store.before_request(prefix.as_ref()).await {
+                Ok(()) => stream,
+                Err(err) => futures::stream::once(async move { Err(err) }).boxed(),
+            }
+        })
+        .flatten()
+        .boxed()
+    }
+}
+
+impl fmt::Debug for FaultInjectingStore {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("FaultInjectingStore")
+            .field("inner", &self.inner)
+            .finish_non_exhaustive()
+    }
+}
+
+impl fmt::Display for FaultInjectingStore {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "FaultInjectingStore({})", self.inner)
+    }
+}
+
+#[async_trait::async_trait]
+impl ObjectStore for FaultInjectingStore {
+    async fn put_opts(
+        &self,
+        location: &Path,
+        payload: PutPayload,
+        options: PutOptions,
+    ) -> ObjectStoreResult<PutResult> {
+        self.before_request(Some(location)).await?;
+        if matches!(options.mode, PutMode::Create) {
+            self.maybe_race(location).await?;
+        }
+        if let Some(len) = self.take_truncation(location) {
+            let data = Bytes::from(payload);
+            let data = data.slice(..len.min(data.len()));
+            self.inner.put(location, data.into()).await?;
+            return Err(ObjectStoreError::Generic {
+                store: STORE,
+                source: format!("injected partial write of {len} bytes to {location}").into(),
+            });
+        }
+        self.inner.put_opts(location, payload, options).await
+    }
+
+    async fn put_multipart_opts(
+        &self,
+        location: &Path,
+        options: PutMultipartOptions,
+    ) -> ObjectStoreResult<Box<dyn MultipartUpload>> {
+        self.before_request(Some(location)).await?;
+        self.inner.put_multipart_opts(location, options).await
+    }
+
+    async fn get_opts(&self, location: &Path, options: GetOptions) -> ObjectStoreResult<GetResult> {
+        self.before_request(Some(location)).await?;
+        self.inner.get_opts(location, options).await
+    }
+
+    fn delete_stream(
+        &self,
+        locations: BoxStream<'static, ObjectStoreResult<Path>>,
+    ) -> BoxStream<'static, ObjectStoreResult<Path>> {
+        let store = self.clone();
+        let locations = locations
+            .then(move |location| {
+                let store = store.clone();
+                async move {
+                    let location = location?;
+                    store.before_request(Some(&location)).await?;
+                    Ok(location)
+                }
+            })
+            .boxed();
+        self.inner.delete_stream(locations)
+    }
+
+    fn list(&self, prefix: Option<&Path>) -> BoxStream<'static, ObjectStoreResult<ObjectMeta>> {
+        self.faulty_list(prefix.cloned(), self.inner.list(prefix))
+    }
+
+    fn list_with_offset(
+        &self,
+        prefix: Option<&Path>,
+        offset: &Path,
+    ) -> BoxStream<'static, ObjectStoreResult<ObjectMeta>> {
+        self.faulty_list(prefix.cloned(), self.inner.list_with_offset(prefix, offset))
+    }
+
+    async fn list_with_delimiter(&self, prefix: Option<&Path>) -> ObjectStoreResult<ListResult> {
+        self.before_request(prefix).await?;
+        self.inner.list_with_delimiter(prefix).await
+    }
+
+    async fn copy_opts(
+        &self,
+        from: &Path,
+        to: &Path,
+        options: CopyOptions,
+    ) -> ObjectStoreResult<()> {
+        self.before_request(Some(to)).await?;
+        if matches!(options.mode, CopyMode::Create) {
+            self.maybe_race(to).await?;
+        }
+        self.inner.copy_opts(from, to, options).await
+    }
+
+    async fn rename_opts(
+        &self,
+        from: &Path,
+        to: &Path,
+        options: RenameOptions,
+    ) -> ObjectStoreResult<()> {
+        self.before_request(Some(to)).await?;
+        if matches!(options.target_mode, RenameTargetMode::Create) {
+            self.maybe_race(to).await?;
+        }
+        self.inner.rename_opts(from, to, options).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use deltalake_core::logstore::object_store::memory::InMemory;
+
+    use super::*;
+
+    fn store() -> FaultInjectingStore {
+        FaultInjectingStore::new(Arc::new(InMemory::new()))
+    }
+
+    #[tokio::test]
+    async fn test_fail_next_requests() {
+        let store = store();
+        let path = Path::from("data.parquet");
+        store.fail_next(2);
+        assert!(store.put(&path, "data".into()).await.is_err());
+        assert!(store.put(&path, "data".into()).await.is_err());
+        store.put(&path, "data".into()).await.unwrap();
+        assert_eq!(store.request_count(), 3);
+        assert_eq!(store.injected_failure_count(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_filtered_failures() {
+        let store = store();
+        store
+            .fail_every(Some(1))
+            .set_filter(|path| path.as_ref().starts_with("_delta_log"));
+        store
+            .put(&Path::from("data.parquet"), "data".into())
+            .await
+            .unwrap();
+        assert!(store
+            .put(
+                &Path::from("_delta_log/00000000000000000000.json"),
+                "{}".into()
+            )
+            .await
+            .is_err());
+    }
+
+    #[tokio::test]
+    async fn test_lost_race() {
+        let store = store();
+        let path = Path::from("_delta_log/00000000000000000001.json");
+        store.race_next_create("winner");
+        let err = store
+            .put_opts(&path, "loser".into(), PutMode::Create.into())
+            .await
+            .unwrap_err();
+        assert!(matches!(err, ObjectStoreError::AlreadyExists { .. }));
+        let data = store.get(&path).await.unwrap().bytes().await.unwrap();
+        assert_eq!(data.as_ref(), b"winner");
+    }
+
+    #[tokio::test]
+    async fn test_partial_write() {
+        let store = store();
+        let path = Path::from("data.parquet");
+        store.truncate_next_put(3);
+        assert!(store.put(&path, "abcdef".into()).await.is_err());
+        let data = store.get(&path).await.unwrap().bytes().await.unwrap();
+        assert_eq!(data.as_ref(), b"abc");
+    }
+}
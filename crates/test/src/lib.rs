@@ -18,10 +18,12 @@ use tempfile::TempDir;
 pub mod acceptance;
 pub mod clock;
 pub mod concurrent;
+pub mod faults;
 pub mod read;
 pub mod utils;
 
 pub use concurrent::test_concurrent_writes;
+pub use faults::FaultInjectingStore;
 pub use read::*;
 pub use utils::{IntegrationContext, TestResult};
 
@@ -34,7 +34,6 @@ use pyo3_arrow::PyDataType;
 use deltalake::arrow::array::{
     ArrayRef, BooleanBuilder, LargeStringBuilder, ListBuilder, RecordBatchIterator,
 };
-use deltalake::delta_datafusion::create_session_state_with_spill_config;
 use deltalake::errors::DeltaTableError;
 use deltalake::kernel::scalars::ScalarExt;
 use deltalake::kernel::transaction::{CommitBuilder, CommitProperties, TableReference};
@@ -788,10 +787,11 @@ impl RawDeltaTable {
                 .optimize()
                 .with_max_concurrent_tasks(max_concurrent_tasks.unwrap_or_else(num_cpus::get));
 
-            if max_spill_size.is_some() || max_temp_directory_size.is_some() {
-                let session =
-                    create_session_state_with_spill_config(max_spill_size, max_temp_directory_size);
-                cmd = cmd.with_session_state(Arc::new(session));
+            if let Some(max_spill_size) = max_spill_size {
+                cmd = cmd.with_max_spill_size(max_spill_size);
+            }
+            if let Some(max_temp_directory_size) = max_temp_directory_size {
+                cmd = cmd.with_max_temp_directory_size(max_temp_directory_size);
             }
 
             if let Some(target_size) = target_size {
@@ -866,10 +866,11 @@ impl RawDeltaTable {
                 .with_max_concurrent_tasks(max_concurrent_tasks.unwrap_or_else(num_cpus::get))
                 .with_type(OptimizeType::ZOrder(z_order_columns));
 
-            if max_spill_size.is_some() || max_temp_directory_size.is_some() {
-                let session =
-                    create_session_state_with_spill_config(max_spill_size, max_temp_directory_size);
-                cmd = cmd.with_session_state(Arc::new(session));
+            if let Some(max_spill_size) = max_spill_size {
+                cmd = cmd.with_max_spill_size(max_spill_size);
+            }
+            if let Some(max_temp_directory_size) = max_temp_directory_size {
+                cmd = cmd.with_max_temp_directory_size(max_temp_directory_size);
             }
 
             if let Some(target_size) = target_size {
@@ -3230,6 +3231,7 @@ fn _internal(m: &Bound<'_, PyModule>) -> PyResult<()> {
     deltalake::azure::register_handlers(None);
     deltalake::gcp::register_handlers(None);
     deltalake::hdfs::register_handlers(None);
+    deltalake::http::register_handlers(None);
     deltalake_mount::register_handlers(None);
     deltalake::lakefs::register_handlers(None);
     deltalake::unity_catalog::register_handlers(None);
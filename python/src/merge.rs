@@ -3,7 +3,6 @@ use deltalake::datafusion::catalog::TableProvider;
 use deltalake::datafusion::datasource::MemTable;
 use deltalake::datafusion::physical_plan::memory::LazyBatchGenerator;
 use deltalake::delta_datafusion::create_session;
-use deltalake::delta_datafusion::create_session_state_with_spill_config;
 use deltalake::kernel::EagerSnapshot;
 use deltalake::logstore::LogStoreRef;
 use deltalake::operations::CustomExecuteHandler;
@@ -86,10 +85,11 @@ impl PyMergeBuilder {
             .with_safe_cast(safe_cast)
             .with_streaming(streamed_exec);
 
-        if max_spill_size.is_some() || max_temp_directory_size.is_some() {
-            let session =
-                create_session_state_with_spill_config(max_spill_size, max_temp_directory_size);
-            cmd = cmd.with_session_state(Arc::new(session));
+        if let Some(max_spill_size) = max_spill_size {
+            cmd = cmd.with_max_spill_size(max_spill_size);
+        }
+        if let Some(max_temp_directory_size) = max_temp_directory_size {
+            cmd = cmd.with_max_temp_directory_size(max_temp_directory_size);
         }
 
         if let Some(src_alias) = &source_alias {